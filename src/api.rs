@@ -2,13 +2,14 @@ use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use image::{ImageBuffer, Rgba};
 use base64::{Engine as _, engine::general_purpose};
-use std::io::{Cursor, BufRead, BufReader};
-use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}, mpsc};
+use std::io::Cursor;
+use std::sync::{Arc, Mutex, atomic::{AtomicBool, Ordering}};
 use image::GenericImageView;
 use windows::Win32::Foundation::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
-use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use crate::config::Preset;
+use crate::config::{ImageUploadFormat, Preset};
+use crate::audio_file::AudioClip;
+use crate::api_error::{with_rate_limit_retry, ApiError};
 
 use crate::APP;
 
@@ -43,13 +44,105 @@ struct ChatMessage {
 }
 
 lazy_static::lazy_static! {
-    static ref UREQ_AGENT: ureq::Agent = ureq::AgentBuilder::new()
+    pub(crate) static ref UREQ_AGENT: ureq::Agent = ureq::AgentBuilder::new()
         .timeout_read(std::time::Duration::from_secs(30))
         .timeout_write(std::time::Duration::from_secs(30))
         .build();
 
     pub static ref VISION_STOP_SIGNAL: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
     pub static ref VISION_ACTIVE: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    /// Live vision keeps its windows but stops capturing while set (control bar)
+    pub static ref VISION_PAUSED: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
+    /// New capture region picked during a live vision session, taken by the capture loop
+    static ref VISION_NEW_REGION: Mutex<Option<RECT>> = Mutex::new(None);
+}
+
+/// Swap the region of the running live vision session (picked from its control bar)
+pub fn set_live_vision_region(rect: RECT) {
+    *VISION_NEW_REGION.lock().unwrap() = Some(rect);
+}
+
+/// Sampling the vision request used before presets could set it (Groq only)
+pub const DEFAULT_TEMPERATURE: f32 = 0.1;
+pub const DEFAULT_MAX_TOKENS: u32 = 1024;
+
+/// Per-preset sampling of a vision request, clamped to what every provider accepts
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sampling {
+    pub temperature: f32,
+    pub max_tokens: u32,
+}
+
+impl Default for Sampling {
+    fn default() -> Self {
+        Self { temperature: DEFAULT_TEMPERATURE, max_tokens: DEFAULT_MAX_TOKENS }
+    }
+}
+
+impl Sampling {
+    pub fn from_preset(preset: &crate::config::Preset) -> Self {
+        Self {
+            temperature: preset.temperature.clamp(0.0, 2.0),
+            max_tokens: preset.max_tokens.clamp(64, 32768),
+        }
+    }
+
+    /// Gemini and OpenRouter never got the Groq defaults, so only values the user
+    /// changed are sent there (unchanged presets keep the provider's defaults)
+    fn explicit_temperature(&self) -> Option<f64> {
+        (self.temperature != DEFAULT_TEMPERATURE).then(|| (self.temperature as f64 * 100.0).round() / 100.0)
+    }
+
+    fn explicit_max_tokens(&self) -> Option<u32> {
+        (self.max_tokens != DEFAULT_MAX_TOKENS).then_some(self.max_tokens)
+    }
+
+    /// Gemini `generationConfig` fields
+    fn gemini_defaults(&self) -> serde_json::Value {
+        let mut config = serde_json::Map::new();
+        if let Some(t) = self.explicit_temperature() {
+            config.insert("temperature".to_string(), serde_json::json!(t));
+        }
+        if let Some(m) = self.explicit_max_tokens() {
+            config.insert("maxOutputTokens".to_string(), serde_json::json!(m));
+        }
+        serde_json::Value::Object(config)
+    }
+
+    /// Add `temperature` / `max_tokens` to an OpenRouter payload
+    fn apply_openrouter(&self, payload: &mut serde_json::Value) {
+        if let Some(t) = self.explicit_temperature() {
+            payload["temperature"] = serde_json::json!(t);
+        }
+        if let Some(m) = self.explicit_max_tokens() {
+            payload["max_tokens"] = serde_json::json!(m);
+        }
+    }
+}
+
+/// `generationConfig` for a Gemini request: `defaults` plus the user's Advanced
+/// overrides for `model` (`Config.model_params`). None when there's nothing to send.
+fn gemini_generation_config(model: &str, defaults: serde_json::Value) -> Option<serde_json::Value> {
+    let mut config = match defaults {
+        serde_json::Value::Object(map) => map,
+        _ => serde_json::Map::new(),
+    };
+    if let Some(params) = APP.lock().ok().and_then(|app| app.config.model_params.get(model).cloned()) {
+        params.apply_to(model, &mut config);
+    }
+    (!config.is_empty()).then_some(serde_json::Value::Object(config))
+}
+
+/// Virtual-screen rect -> crop (x, y, w, h) in the full-screen capture
+fn crop_for_rect(rect: RECT) -> (u32, u32, u32, u32) {
+    let x_virt = unsafe { GetSystemMetrics(SM_XVIRTUALSCREEN) };
+    let y_virt = unsafe { GetSystemMetrics(SM_YVIRTUALSCREEN) };
+    (
+        (rect.left - x_virt).max(0) as u32,
+        (rect.top - y_virt).max(0) as u32,
+        (rect.right - rect.left).abs() as u32,
+        (rect.bottom - rect.top).abs() as u32,
+    )
 }
 
 pub fn translate_image_streaming<F>(
@@ -59,37 +152,201 @@ pub fn translate_image_streaming<F>(
     prompt: String,
     model: String,
     provider: String,
-    image: ImageBuffer<Rgba<u8>, Vec<u8>>,
+    image: &ImageBuffer<Rgba<u8>, Vec<u8>>,
     streaming_enabled: bool,
     use_json_format: bool,
-    mut on_chunk: F,
+    sampling: Sampling,
+    on_chunk: F,
 ) -> Result<String>
 where
     F: FnMut(&str),
 {
-    log::info!("Starting image translation. Provider: {}, Model: {}, Stream: {}", provider, model, streaming_enabled);
+    translate_images_streaming(
+        groq_api_key, gemini_api_key, openrouter_api_key,
+        prompt, model, provider, std::slice::from_ref(image),
+        streaming_enabled, use_json_format, sampling, on_chunk,
+    )
+}
 
-    // FIX 6: Resize image if too large to save bandwidth
-    let processed_image = if image.width() > 1920 {
-        let ratio = 1920.0 / image.width() as f32;
-        let new_h = (image.height() as f32 * ratio) as u32;
-        image::imageops::resize(&image, 1920, new_h, image::imageops::FilterType::Triangle)
+/// Whether the provider's vision endpoint accepts several images in one message
+pub fn supports_multi_image(provider: &str) -> bool {
+    provider == "google" || provider == "openrouter"
+}
+
+/// Providers that take WebP images (Gemini, and OpenRouter's OpenAI-style API); Groq gets PNG
+pub fn supports_webp(provider: &str) -> bool {
+    provider == "google" || provider == "openrouter"
+}
+
+/// `configured` if `provider` accepts it, else PNG
+fn upload_format(configured: ImageUploadFormat, provider: &str) -> ImageUploadFormat {
+    match configured {
+        ImageUploadFormat::WebP if !supports_webp(provider) => ImageUploadFormat::Png,
+        format => format,
+    }
+}
+
+/// Wider captures are scaled down to this before upload
+const MAX_UPLOAD_WIDTH: u32 = 1920;
+/// Crops at most this tall (a line or two of subtitles) or this large count as small:
+/// their text may be too tiny for the model to read at 1:1
+const SMALL_CROP_MAX_HEIGHT: u32 = 120;
+const SMALL_CROP_MAX_AREA: u64 = 240 * 240;
+
+fn is_small_crop(width: u32, height: u32) -> bool {
+    height <= SMALL_CROP_MAX_HEIGHT || width as u64 * height as u64 <= SMALL_CROP_MAX_AREA
+}
+
+/// Size a `width` x `height` crop is uploaded at: small crops doubled (with
+/// `Config.upscale_small_crops`), anything wider than `MAX_UPLOAD_WIDTH` scaled down to it
+fn upload_size(width: u32, height: u32, upscale_small: bool) -> (u32, u32) {
+    if width == 0 || height == 0 {
+        return (width, height);
+    }
+    let max_width = width.min(MAX_UPLOAD_WIDTH);
+    let new_w = if upscale_small && is_small_crop(width, height) {
+        (width * 2).min(MAX_UPLOAD_WIDTH).max(max_width)
     } else {
-        image
+        max_width
+    };
+    if new_w == width {
+        return (width, height);
+    }
+    let ratio = new_w as f32 / width as f32;
+    (new_w, ((height as f32 * ratio) as u32).max(1))
+}
+
+fn encode_upload(image: &ImageBuffer<Rgba<u8>, Vec<u8>>, format: ImageUploadFormat) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    match format {
+        ImageUploadFormat::Png => image.write_to(&mut Cursor::new(&mut data), image::ImageFormat::Png)?,
+        ImageUploadFormat::WebP => {
+            image::codecs::webp::WebPEncoder::new_lossless(&mut data)
+                .encode(image.as_raw(), image.width(), image.height(), image::ColorType::Rgba8)?
+        }
+    }
+    Ok(data)
+}
+
+/// A JSON mode answer: its "translation" field, and the source language if the model
+/// reported one. Study layout answers carry the original and reading too, so they are
+/// kept whole (as is anything that isn't JSON). Code fences around the object are allowed.
+fn json_answer(content: &str) -> (String, Option<String>) {
+    let object = match (content.find('{'), content.rfind('}')) {
+        (Some(start), Some(end)) if start < end => &content[start..=end],
+        _ => return (content.to_string(), None),
+    };
+    let Ok(json_obj) = serde_json::from_str::<serde_json::Value>(object) else {
+        return (content.to_string(), None);
+    };
+    let detected_language = json_obj.get("detected_language").and_then(|v| v.as_str())
+        .map(str::trim).filter(|l| !l.is_empty()).map(str::to_string);
+    if json_obj.get("original").is_some() {
+        return (content.to_string(), detected_language);
+    }
+    match json_obj.get("translation").and_then(|v| v.as_str()) {
+        Some(translation) => (translation.to_string(), detected_language),
+        None => (content.to_string(), None),
+    }
+}
+
+/// An image request's answer. `detected_language` is the language of the text in the
+/// image, when a JSON mode answer named it.
+#[derive(Debug, Clone)]
+pub struct VisionAnswer {
+    pub text: String,
+    pub detected_language: Option<String>,
+}
+
+/// Same as `translate_image_streaming`, but attaches every image to a single request
+/// (multi-frame capture). Only use with more than one image if `supports_multi_image`.
+pub fn translate_images_streaming<F>(
+    groq_api_key: &str,
+    gemini_api_key: &str,
+    openrouter_api_key: &str,
+    prompt: String,
+    model: String,
+    provider: String,
+    images: &[ImageBuffer<Rgba<u8>, Vec<u8>>],
+    streaming_enabled: bool,
+    use_json_format: bool,
+    sampling: Sampling,
+    on_chunk: F,
+) -> Result<String>
+where
+    F: FnMut(&str),
+{
+    translate_images_answer(
+        groq_api_key, gemini_api_key, openrouter_api_key,
+        prompt, model, provider, images,
+        streaming_enabled, use_json_format, sampling, on_chunk,
+    ).map(|answer| answer.text)
+}
+
+/// `translate_images_streaming`, also returning the detected source language
+pub fn translate_images_answer<F>(
+    groq_api_key: &str,
+    gemini_api_key: &str,
+    openrouter_api_key: &str,
+    prompt: String,
+    model: String,
+    provider: String,
+    images: &[ImageBuffer<Rgba<u8>, Vec<u8>>],
+    streaming_enabled: bool,
+    use_json_format: bool,
+    sampling: Sampling,
+    mut on_chunk: F,
+) -> Result<VisionAnswer>
+where
+    F: FnMut(&str),
+{
+    log::info!("Starting image translation. Provider: {}, Model: {}, Stream: {}, Images: {}", provider, model, streaming_enabled, images.len());
+
+    let (configured_format, upscale_small) = APP.lock()
+        .map(|app| (app.config.image_upload_format, app.config.upscale_small_crops))
+        .unwrap_or((ImageUploadFormat::default(), true));
+    let format = upload_format(configured_format, &provider);
+    let mut b64_images = Vec::with_capacity(images.len());
+    for image in images {
+        // FIX 6: Resize image if too large to save bandwidth; tiny text gets more pixels instead
+        let (new_w, new_h) = upload_size(image.width(), image.height(), upscale_small);
+        // Small crops are cheap either way and always go as PNG
+        let image_format = if is_small_crop(image.width(), image.height()) { ImageUploadFormat::Png } else { format };
+        let resized;
+        let processed_image = if new_w > image.width() {
+            resized = image::imageops::resize(image, new_w, new_h, image::imageops::FilterType::Lanczos3);
+            &resized
+        } else if new_w < image.width() {
+            resized = image::imageops::resize(image, new_w, new_h, image::imageops::FilterType::Triangle);
+            &resized
+        } else {
+            image
+        };
+
+        // Resizing from original size to 1920px width already saves ~75% payload
+        let image_data = encode_upload(processed_image, image_format)?;
+        b64_images.push((image_format, general_purpose::STANDARD.encode(&image_data)));
+    }
+
+    let mut timer = crate::metrics::StreamTimer::start();
+    let mut on_chunk = |chunk: &str| {
+        timer.on_chunk(chunk);
+        on_chunk(chunk)
     };
 
-    let mut image_data = Vec::new();
-    // Use PNG for resized image (JPEG support requires feature flag)
-    // Resizing from original size to 1920px width already saves ~75% payload
-    processed_image.write_to(&mut Cursor::new(&mut image_data), image::ImageFormat::Png)?;
-    let b64_image = general_purpose::STANDARD.encode(&image_data);
+    // Message content in OpenAI format (OpenRouter / Groq): the prompt followed by every image
+    let mut openai_content = vec![serde_json::json!({ "type": "text", "text": prompt })];
+    for (image_format, b64_image) in &b64_images {
+        openai_content.push(serde_json::json!({ "type": "image_url", "image_url": { "url": format!("data:{};base64,{}", image_format.mime_type(), b64_image) } }));
+    }
 
     let mut full_content = String::new();
+    let mut detected_language = None;
 
     if provider == "google" {
         // Gemini API
         if gemini_api_key.trim().is_empty() {
-            return Err(anyhow::anyhow!("NO_API_KEY"));
+            return Err(ApiError::NoApiKey.into());
         }
 
         let method = if streaming_enabled { "streamGenerateContent" } else { "generateContent" };
@@ -105,38 +362,36 @@ where
             )
         };
 
-        let payload = serde_json::json!({
+        let mut gemini_parts = vec![serde_json::json!({ "text": prompt })];
+        for (image_format, b64_image) in &b64_images {
+            gemini_parts.push(serde_json::json!({
+                "inline_data": {
+                    "mime_type": image_format.mime_type(),
+                    "data": b64_image
+                }
+            }));
+        }
+
+        let mut payload = serde_json::json!({
             "contents": [{
                 "role": "user",
-                "parts": [
-                    { "text": prompt },
-                    {
-                        "inline_data": {
-                            "mime_type": "image/png",
-                            "data": b64_image
-                        }
-                    }
-                ]
+                "parts": gemini_parts
             }]
         });
+        if let Some(generation_config) = gemini_generation_config(&model, sampling.gemini_defaults()) {
+            payload["generationConfig"] = generation_config;
+        }
 
         let resp = UREQ_AGENT.post(&url)
             .set("x-goog-api-key", gemini_api_key)
             .send_json(payload)
-            .map_err(|e| {
-                let err_str = e.to_string();
-                if err_str.contains("401") || err_str.contains("403") {
-                    anyhow::anyhow!("INVALID_API_KEY")
-                } else {
-                    anyhow::anyhow!("{}", err_str)
-                }
-            })?;
+            .map_err(|e| ApiError::from_ureq("Gemini", e))?;
 
         if streaming_enabled {
-            let reader = BufReader::new(resp.into_reader());
+            let lines = crate::utf8_stream::stream_lines(resp.into_reader());
 
-            for line in reader.lines() {
-                let line = line.map_err(|e| anyhow::anyhow!("Failed to read line: {}", e))?;
+            for line in lines {
+                let line = line.map_err(|e| ApiError::Network(format!("Failed to read line: {}", e)))?;
                 if line.starts_with("data: ") {
                     let json_str = &line["data: ".len()..];
                     if json_str.trim() == "[DONE]" { break; }
@@ -167,6 +422,9 @@ where
                         full_content = parts.iter()
                             .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
                             .collect::<String>();
+                        if use_json_format {
+                            (full_content, detected_language) = json_answer(&full_content);
+                        }
                         
                         on_chunk(&full_content);
                     }
@@ -176,19 +434,16 @@ where
     } else if provider == "openrouter" {
         // OpenRouter API
         if openrouter_api_key.trim().is_empty() {
-             return Err(anyhow::anyhow!("NO_API_KEY"));
+             return Err(ApiError::NoApiKey.into());
         }
 
-        let payload = if streaming_enabled {
+        let mut payload = if streaming_enabled {
             serde_json::json!({
                 "model": model,
                 "messages": [
                     {
                         "role": "user",
-                        "content": [
-                            { "type": "text", "text": prompt },
-                            { "type": "image_url", "image_url": { "url": format!("data:image/png;base64,{}", b64_image) } }
-                        ]
+                        "content": openai_content
                     }
                 ],
                 "stream": true
@@ -199,63 +454,26 @@ where
                 "messages": [
                     {
                         "role": "user",
-                        "content": [
-                            { "type": "text", "text": prompt },
-                            { "type": "image_url", "image_url": { "url": format!("data:image/png;base64,{}", b64_image) } }
-                        ]
+                        "content": openai_content
                     }
                 ],
                 "stream": false
             })
         };
+        sampling.apply_openrouter(&mut payload);
 
-        let mut resp_result = Err(anyhow::anyhow!("Request not started"));
-        for retry in 0..3 {
-            let r = UREQ_AGENT.post("https://openrouter.ai/api/v1/chat/completions")
+        let resp = with_rate_limit_retry("OpenRouter", 2, || false, || {
+            UREQ_AGENT.post("https://openrouter.ai/api/v1/chat/completions")
                 .set("Authorization", &format!("Bearer {}", openrouter_api_key.trim()))
                 .set("HTTP-Referer", "https://github.com/nhanh-vo/screen-grounded-translator")
                 .set("X-Title", "XT Screen Translator")
-                .send_json(payload.clone());
-
-            match r {
-                Ok(res) => {
-                    resp_result = Ok(res);
-                    break;
-                }
-                Err(ureq::Error::Status(code, response)) => {
-                    let error_body = response.into_string().unwrap_or_else(|_| "Unknown error".to_string());
-                    log::error!("OpenRouter API Error (Status {}): {}", code, error_body);
-
-                    if code == 429 {
-                        log::warn!("OpenRouter 429 Rate Limit. Retrying...");
-                        if retry < 2 {
-                            std::thread::sleep(std::time::Duration::from_secs(2u64.pow(retry + 1)));
-                            continue;
-                        }
-                        resp_result = Err(anyhow::anyhow!("OpenRouter: Rate limit exceeded (429). {}", error_body));
-                        break;
-                    } else if code == 401 {
-                        resp_result = Err(anyhow::anyhow!("INVALID_API_KEY"));
-                        break;
-                    } else if code == 402 {
-                        resp_result = Err(anyhow::anyhow!("OpenRouter: Insufficient credits or not free."));
-                        break;
-                    } else {
-                        resp_result = Err(anyhow::anyhow!("OpenRouter API Error {}: {}", code, error_body));
-                        break;
-                    }
-                }
-                Err(e) => {
-                    resp_result = Err(anyhow::anyhow!("OpenRouter Connection Error: {}", e));
-                    break;
-                }
-            }
-        }
-        let resp = resp_result?;
+                .send_json(payload.clone())
+                .map_err(|e| ApiError::from_ureq("OpenRouter", e).into())
+        })?;
 
         if streaming_enabled {
-            let reader = BufReader::new(resp.into_reader());
-            for line in reader.lines() {
+            let lines = crate::utf8_stream::stream_lines(resp.into_reader());
+            for line in lines {
                 let line = line?;
                 if line.starts_with("data: ") {
                      let data = &line[6..];
@@ -276,13 +494,16 @@ where
                 .map_err(|e| anyhow::anyhow!("Failed to parse non-streaming response: {}", e))?;
              if let Some(choice) = chat_resp.choices.first() {
                  full_content = choice.message.content.clone();
+                 if use_json_format {
+                     (full_content, detected_language) = json_answer(&full_content);
+                 }
                  on_chunk(&full_content);
              }
         }
     } else {
         // Groq API (default)
         if groq_api_key.trim().is_empty() {
-            return Err(anyhow::anyhow!("NO_API_KEY"));
+            return Err(ApiError::NoApiKey.into());
         }
 
         let payload = if streaming_enabled {
@@ -291,14 +512,11 @@ where
                 "messages": [
                     {
                         "role": "user",
-                        "content": [
-                            { "type": "text", "text": prompt },
-                            { "type": "image_url", "image_url": { "url": format!("data:image/png;base64,{}", b64_image) } }
-                        ]
+                        "content": openai_content
                     }
                 ],
-                "temperature": 0.1,
-                "max_completion_tokens": 1024,
+                "temperature": sampling.temperature,
+                "max_completion_tokens": sampling.max_tokens,
                 "stream": true
             })
         } else {
@@ -307,14 +525,11 @@ where
                 "messages": [
                     {
                         "role": "user",
-                        "content": [
-                            { "type": "text", "text": prompt },
-                            { "type": "image_url", "image_url": { "url": format!("data:image/png;base64,{}", b64_image) } }
-                        ]
+                        "content": openai_content
                     }
                 ],
-                "temperature": 0.1,
-                "max_completion_tokens": 1024,
+                "temperature": sampling.temperature,
+                "max_completion_tokens": sampling.max_tokens,
                 "stream": false
             });
             
@@ -324,16 +539,7 @@ where
         let resp = UREQ_AGENT.post("https://api.groq.com/openai/v1/chat/completions")
             .set("Authorization", &format!("Bearer {}", groq_api_key))
             .send_json(payload)
-            .map_err(|e| {
-                let err_str = e.to_string();
-                if err_str.contains("401") {
-                    anyhow::anyhow!("INVALID_API_KEY")
-                } else if err_str.contains("400") {
-                    anyhow::anyhow!("Groq API 400: Bad request. Check model availability or API request format.")
-                } else {
-                    anyhow::anyhow!("{}", err_str)
-                }
-            })?;
+            .map_err(|e| ApiError::from_ureq("Groq", e))?;
 
         // --- CAPTURE RATE LIMITS ---
         if let Some(remaining) = resp.header("x-ratelimit-remaining-requests") {
@@ -347,8 +553,8 @@ where
         // ---------------------------
 
         if streaming_enabled {
-            let reader = BufReader::new(resp.into_reader());
-            for line in reader.lines() {
+            let lines = crate::utf8_stream::stream_lines(resp.into_reader());
+            for line in lines {
                 let line = line?;
 
                 if line.starts_with("data: ") {
@@ -377,19 +583,7 @@ where
             if let Some(choice) = chat_resp.choices.first() {
                 let content_str = &choice.message.content;
                 
-                if use_json_format {
-                    if let Ok(json_obj) = serde_json::from_str::<serde_json::Value>(content_str) {
-                        if let Some(translation) = json_obj.get("translation").and_then(|v| v.as_str()) {
-                            full_content = translation.to_string();
-                        } else {
-                            full_content = content_str.clone();
-                        }
-                    } else {
-                        full_content = content_str.clone();
-                    }
-                } else {
-                    full_content = content_str.clone();
-                }
+                (full_content, detected_language) = if use_json_format { json_answer(content_str) } else { (content_str.clone(), None) };
                 
                 on_chunk(&full_content);
             }
@@ -397,10 +591,11 @@ where
     }
 
     if full_content.is_empty() {
-        return Err(anyhow::anyhow!("No content received from API"));
+        return Err(ApiError::EmptyResponse.into());
     }
 
-    Ok(full_content)
+    timer.finish("image", &provider, &model);
+    Ok(VisionAnswer { text: full_content, detected_language })
 }
 
 pub fn translate_text_streaming<F>(
@@ -415,10 +610,60 @@ pub fn translate_text_streaming<F>(
     use_json_format: bool,
     mut on_chunk: F,
 ) -> Result<String>
+where
+    F: FnMut(&str),
+{
+    // Before the cache, so a missing key never looks like it works
+    if text_api_key(&provider, groq_api_key, gemini_api_key, openrouter_api_key).trim().is_empty() {
+        return Err(ApiError::NoApiKey.into());
+    }
+    let cache_key = crate::translation_cache::text_key(&format!("{}/{}", provider, model), &target_lang, &text);
+    if let Some(cached) = crate::translation_cache::lookup(cache_key) {
+        log::info!("Text translation served from cache");
+        on_chunk(&cached);
+        return Ok(cached);
+    }
+    let full_content = translate_text_uncached(
+        groq_api_key, gemini_api_key, openrouter_api_key,
+        text, target_lang, model, provider,
+        streaming_enabled, use_json_format, on_chunk,
+    )?;
+    crate::translation_cache::store(cache_key, &full_content);
+    Ok(full_content)
+}
+
+/// The key `translate_text_streaming` sends for `provider`
+fn text_api_key<'a>(provider: &str, groq_api_key: &'a str, gemini_api_key: &'a str, openrouter_api_key: &'a str) -> &'a str {
+    match provider {
+        "google" => gemini_api_key,
+        "openrouter" => openrouter_api_key,
+        _ => groq_api_key,
+    }
+}
+
+/// `translate_text_streaming` without the translation cache: always asks the API
+/// (the onboarding key test must not be answered from an earlier success)
+pub fn translate_text_uncached<F>(
+    groq_api_key: &str,
+    gemini_api_key: &str,
+    openrouter_api_key: &str,
+    text: String,
+    target_lang: String,
+    model: String,
+    provider: String,
+    streaming_enabled: bool,
+    use_json_format: bool,
+    mut on_chunk: F,
+) -> Result<String>
 where
     F: FnMut(&str),
 {
     log::info!("Starting text translation. Provider: {}, Model: {}, Target: {}", provider, model, target_lang);
+    let mut timer = crate::metrics::StreamTimer::start();
+    let mut on_chunk = |chunk: &str| {
+        timer.on_chunk(chunk);
+        on_chunk(chunk)
+    };
     let mut full_content = String::new();
     let prompt = format!(
         "Translate the following text to {}. Output ONLY the translation. Text:\n\n{}",
@@ -428,7 +673,7 @@ where
     if provider == "google" {
         // --- GEMINI TEXT API ---
         if gemini_api_key.trim().is_empty() {
-            return Err(anyhow::anyhow!("NO_API_KEY"));
+            return Err(ApiError::NoApiKey.into());
         }
 
         let method = if streaming_enabled { "streamGenerateContent" } else { "generateContent" };
@@ -444,29 +689,25 @@ where
             )
         };
 
-        let payload = serde_json::json!({
+        let mut payload = serde_json::json!({
             "contents": [{
                 "role": "user",
                 "parts": [{ "text": prompt }]
             }]
         });
+        if let Some(generation_config) = gemini_generation_config(&model, serde_json::json!({})) {
+            payload["generationConfig"] = generation_config;
+        }
 
         let resp = UREQ_AGENT.post(&url)
             .set("x-goog-api-key", gemini_api_key)
             .send_json(payload)
-            .map_err(|e| {
-                let err_str = e.to_string();
-                if err_str.contains("401") || err_str.contains("403") {
-                    anyhow::anyhow!("INVALID_API_KEY")
-                } else {
-                    anyhow::anyhow!("Gemini Text API Error: {}", err_str)
-                }
-            })?;
+            .map_err(|e| ApiError::from_ureq("Gemini", e))?;
 
         if streaming_enabled {
-            let reader = BufReader::new(resp.into_reader());
-            for line in reader.lines() {
-                let line = line.map_err(|e| anyhow::anyhow!("Failed to read line: {}", e))?;
+            let lines = crate::utf8_stream::stream_lines(resp.into_reader());
+            for line in lines {
+                let line = line.map_err(|e| ApiError::Network(format!("Failed to read line: {}", e)))?;
                 if line.starts_with("data: ") {
                     let json_str = &line["data: ".len()..];
                     if json_str.trim() == "[DONE]" { break; }
@@ -506,7 +747,7 @@ where
     } else if provider == "openrouter" {
         // --- OPENROUTER TEXT API ---
         if openrouter_api_key.trim().is_empty() {
-            return Err(anyhow::anyhow!("NO_API_KEY"));
+            return Err(ApiError::NoApiKey.into());
         }
 
         let payload = if streaming_enabled {
@@ -527,53 +768,18 @@ where
             })
         };
 
-        let mut resp_result = Err(anyhow::anyhow!("Request not started"));
-        for retry in 0..3 {            
-            let r = UREQ_AGENT.post("https://openrouter.ai/api/v1/chat/completions")
+        let resp = with_rate_limit_retry("OpenRouter", 2, || false, || {
+            UREQ_AGENT.post("https://openrouter.ai/api/v1/chat/completions")
                 .set("Authorization", &format!("Bearer {}", openrouter_api_key.trim()))
                 .set("HTTP-Referer", "https://github.com/nhanh-vo/screen-grounded-translator")
                 .set("X-Title", "XT Screen Translator")
-                .send_json(payload.clone());
-
-            match r {
-                Ok(res) => {
-                     resp_result = Ok(res);
-                     break;
-                }
-                Err(ureq::Error::Status(code, response)) => {
-                     let error_body = response.into_string().unwrap_or_else(|_| "Unknown error".to_string());
-                     log::error!("OpenRouter API Error (Status {}): {}", code, error_body);
-
-                     if code == 429 {
-                         log::warn!("OpenRouter 429 Rate Limit. Retrying...");
-                         if retry < 2 {
-                             std::thread::sleep(std::time::Duration::from_secs(2u64.pow(retry + 1)));
-                             continue;
-                         }
-                         resp_result = Err(anyhow::anyhow!("OpenRouter: Rate limit exceeded (429). {}", error_body));
-                         break;
-                     } else if code == 401 {
-                         resp_result = Err(anyhow::anyhow!("INVALID_API_KEY"));
-                         break;
-                     } else if code == 402 {
-                         resp_result = Err(anyhow::anyhow!("OpenRouter: Insufficient credits."));
-                         break;
-                     } else {
-                         resp_result = Err(anyhow::anyhow!("OpenRouter API Error {}: {}", code, error_body));
-                         break;
-                     }
-                }
-                Err(e) => {
-                    resp_result = Err(anyhow::anyhow!("OpenRouter Connection Error: {}", e));
-                    break;
-                }
-            }
-        }
-        let resp = resp_result?;
+                .send_json(payload.clone())
+                .map_err(|e| ApiError::from_ureq("OpenRouter", e).into())
+        })?;
 
          if streaming_enabled {
-            let reader = BufReader::new(resp.into_reader());
-            for line in reader.lines() {
+            let lines = crate::utf8_stream::stream_lines(resp.into_reader());
+            for line in lines {
                 let line = line?;
                 if line.starts_with("data: ") {
                      let data = &line[6..];
@@ -601,7 +807,7 @@ where
     } else {
         // --- GROQ API (Default) ---
         if groq_api_key.trim().is_empty() {
-            return Err(anyhow::anyhow!("NO_API_KEY"));
+            return Err(ApiError::NoApiKey.into());
         }
 
         let payload = if streaming_enabled {
@@ -631,14 +837,7 @@ where
         let resp = UREQ_AGENT.post("https://api.groq.com/openai/v1/chat/completions")
             .set("Authorization", &format!("Bearer {}", groq_api_key))
             .send_json(payload)
-            .map_err(|e| {
-                let err_str = e.to_string();
-                if err_str.contains("401") {
-                    anyhow::anyhow!("INVALID_API_KEY")
-                } else {
-                    anyhow::anyhow!("{}", err_str)
-                }
-            })?;
+            .map_err(|e| ApiError::from_ureq("Groq", e))?;
 
         // --- CAPTURE RATE LIMITS ---
         if let Some(remaining) = resp.header("x-ratelimit-remaining-requests") {
@@ -652,9 +851,9 @@ where
         // ---------------------------
 
         if streaming_enabled {
-            let reader = BufReader::new(resp.into_reader());
+            let lines = crate::utf8_stream::stream_lines(resp.into_reader());
             
-            for line in reader.lines() {
+            for line in lines {
                 let line = line?;
                 if line.starts_with("data: ") {
                     let data = &line[6..];
@@ -679,25 +878,14 @@ where
             if let Some(choice) = chat_resp.choices.first() {
                 let content_str = &choice.message.content;
                 
-                if use_json_format {
-                    if let Ok(json_obj) = serde_json::from_str::<serde_json::Value>(content_str) {
-                        if let Some(translation) = json_obj.get("translation").and_then(|v| v.as_str()) {
-                            full_content = translation.to_string();
-                        } else {
-                            full_content = content_str.clone();
-                        }
-                    } else {
-                        full_content = content_str.clone();
-                    }
-                } else {
-                    full_content = content_str.clone();
-                }
+                full_content = if use_json_format { json_answer(content_str).0 } else { content_str.clone() };
                 
             on_chunk(&full_content);
             }
         }
     }
 
+    timer.finish("text", &provider, &model);
     Ok(full_content)
 }
 
@@ -719,7 +907,7 @@ where
                model, conversation_history.len(), image_base64.is_some());
 
     if gemini_api_key.trim().is_empty() {
-        return Err(anyhow::anyhow!("NO_API_KEY"));
+        return Err(ApiError::NoApiKey.into());
     }
 
     let mut full_content = String::new();
@@ -793,29 +981,22 @@ where
 
     let payload = serde_json::json!({
         "contents": contents,
-        "generationConfig": {
+        "generationConfig": gemini_generation_config(&model, serde_json::json!({
             "temperature": 0.7,
             "maxOutputTokens": 2048
-        }
+        }))
     });
 
     let resp = UREQ_AGENT.post(&url)
         .set("x-goog-api-key", gemini_api_key)
         .send_json(payload)
-        .map_err(|e| {
-            let err_str = e.to_string();
-            if err_str.contains("401") || err_str.contains("403") {
-                anyhow::anyhow!("INVALID_API_KEY")
-            } else {
-                anyhow::anyhow!("Gemini Chat API Error: {}", err_str)
-            }
-        })?;
+        .map_err(|e| ApiError::from_ureq("Gemini", e))?;
 
     if streaming_enabled {
-        let reader = BufReader::new(resp.into_reader());
+        let lines = crate::utf8_stream::stream_lines(resp.into_reader());
 
-        for line in reader.lines() {
-            let line = line.map_err(|e| anyhow::anyhow!("Failed to read line: {}", e))?;
+        for line in lines {
+            let line = line.map_err(|e| ApiError::Network(format!("Failed to read line: {}", e)))?;
             if line.starts_with("data: ") {
                 let json_str = &line["data: ".len()..];
                 if json_str.trim() == "[DONE]" { break; }
@@ -854,7 +1035,7 @@ where
     }
 
     if full_content.is_empty() {
-        return Err(anyhow::anyhow!("No content received from AI Chat API"));
+        return Err(ApiError::EmptyResponse.into());
     }
 
     Ok(full_content)
@@ -864,17 +1045,17 @@ pub fn transcribe_audio_gemini<F>(
     gemini_api_key: &str,
     prompt: String,
     model: String,
-    wav_data: Vec<u8>,
+    clip: &AudioClip,
     mut on_chunk: F,
 ) -> Result<String>
 where
     F: FnMut(&str),
 {
     if gemini_api_key.trim().is_empty() {
-        return Err(anyhow::anyhow!("NO_API_KEY"));
+        return Err(ApiError::NoApiKey.into());
     }
 
-    let b64_audio = general_purpose::STANDARD.encode(&wav_data);
+    let b64_audio = general_purpose::STANDARD.encode(&clip.data);
     let url = format!(
         "https://generativelanguage.googleapis.com/v1beta/models/{}:streamGenerateContent?alt=sse",
         model
@@ -887,7 +1068,7 @@ where
                 { "text": prompt },
                 {
                     "inline_data": {
-                        "mime_type": "audio/wav",
+                        "mime_type": clip.mime_type,
                         "data": b64_audio
                     }
                 }
@@ -898,20 +1079,13 @@ where
     let resp = UREQ_AGENT.post(&url)
         .set("x-goog-api-key", gemini_api_key)
         .send_json(payload)
-        .map_err(|e| {
-            let err_str = e.to_string();
-            if err_str.contains("401") || err_str.contains("403") {
-                anyhow::anyhow!("INVALID_API_KEY")
-            } else {
-                anyhow::anyhow!("Gemini Audio API Error: {}", err_str)
-            }
-        })?;
+        .map_err(|e| ApiError::from_ureq("Gemini", e))?;
 
     let mut full_content = String::new();
-    let reader = BufReader::new(resp.into_reader());
+    let lines = crate::utf8_stream::stream_lines(resp.into_reader());
 
-    for line in reader.lines() {
-        let line = line.map_err(|e| anyhow::anyhow!("Failed to read line: {}", e))?;
+    for line in lines {
+        let line = line.map_err(|e| ApiError::Network(format!("Failed to read line: {}", e)))?;
         if line.starts_with("data: ") {
             let json_str = &line["data: ".len()..];
             if json_str.trim() == "[DONE]" { break; }
@@ -934,68 +1108,22 @@ where
     }
 
     if full_content.is_empty() {
-        return Err(anyhow::anyhow!("No content received from Gemini Audio API"));
+        return Err(ApiError::EmptyResponse.into());
     }
     
     Ok(full_content)
 }
 
-pub fn record_audio_and_transcribe(
-    preset: Preset, 
-    stop_signal: Arc<AtomicBool>, 
-    pause_signal: Arc<AtomicBool>,
-    // FIX: New argument to handle user cancellation
-    abort_signal: Arc<AtomicBool>,
-    overlay_hwnd: HWND
-) {
-    log::info!("Starting audio recording. Source: {}", preset.audio_source);
-    // FIX 5: Host Selection (WASAPI for loopback, default for mic)
-    #[cfg(target_os = "windows")]
-    let host = if preset.audio_source == "device" {
-        cpal::host_from_id(cpal::HostId::Wasapi).unwrap_or(cpal::default_host())
-    } else {
-        cpal::default_host()
-    };
-    #[cfg(not(target_os = "windows"))]
-    let host = cpal::default_host();
-
-    // Improved Device Selection logic
-    let device = if preset.audio_source == "device" {
-        #[cfg(target_os = "windows")]
-        {
-            // For device audio (loopback), prefer WASAPI default output device
-            match host.default_output_device() {
-                Some(d) => d,
-                None => {
-                    eprintln!("Error: No default output device found for loopback.");
-                    host.default_input_device().expect("No input device available")
-                }
-            }
-        }
-        #[cfg(not(target_os = "windows"))]
-        {
-            host.default_input_device().expect("No input device available")
-        }
+/// Encode interleaved f32 samples as a 16-bit PCM WAV in memory.
+/// With `optimize`, the audio is first mixed down to mono and resampled to 16 kHz (the header matches).
+pub fn encode_wav(samples: &[f32], channels: u16, sample_rate: u32, optimize: bool) -> Option<Vec<u8>> {
+    let (data, channels, sample_rate) = if optimize {
+        let rate = crate::audio_capture::SPEECH_SAMPLE_RATE.min(sample_rate);
+        (crate::audio_capture::downmix_and_resample(samples, channels as usize, sample_rate, rate), 1, rate)
     } else {
-        host.default_input_device().expect("No input device available")
+        (samples.to_vec(), channels, sample_rate)
     };
 
-    // Robust Audio Configuration
-    let config = if preset.audio_source == "device" {
-        // Try output config first for loopback accuracy
-        match device.default_output_config() {
-            Ok(c) => c,
-            Err(_) => {
-                 device.default_input_config().expect("Failed to get audio config")
-            }
-        }
-    } else {
-        device.default_input_config().expect("Failed to get audio config")
-    };
-
-    let sample_rate = config.sample_rate().0;
-    let channels = config.channels();
-    
     let spec = hound::WavSpec {
         channels,
         sample_rate,
@@ -1003,58 +1131,59 @@ pub fn record_audio_and_transcribe(
         sample_format: hound::SampleFormat::Int,
     };
 
-    // FIX 2: Use channel instead of Mutex for better lock-free audio handling
-    let (tx, rx) = mpsc::channel::<Vec<f32>>();
-
-    // Stream Setup
-    let err_fn = |err| eprintln!("Audio stream error: {}", err);
-    
-    let stream_res = match config.sample_format() {
-        cpal::SampleFormat::F32 => device.build_input_stream(
-            &config.into(),
-            move |data: &[f32], _: &_| {
-                if !pause_signal.load(Ordering::Relaxed) {
-                    // Send chunk as f32 vector. If receiver disconnects, just stop sending.
-                    let _ = tx.send(data.to_vec());
-                }
-            },
-            err_fn,
-            None
-        ),
-        cpal::SampleFormat::I16 => device.build_input_stream(
-            &config.into(),
-            move |data: &[i16], _: &_| {
-                if !pause_signal.load(Ordering::Relaxed) {
-                    // Convert i16 to f32 immediately to standardize
-                    let f32_data: Vec<f32> = data.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
-                    let _ = tx.send(f32_data);
-                }
-            },
-            err_fn,
-            None
-        ),
-        _ => {
-            eprintln!("Unsupported audio sample format: {:?}", config.sample_format());
-             Err(cpal::BuildStreamError::StreamConfigNotSupported)
-        },
-    };
-
-    if let Err(e) = stream_res {
-        eprintln!("Failed to build stream: {}", e);
-        unsafe { PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); }
-        return;
+    let mut wav_cursor = Cursor::new(Vec::new());
+    {
+        let mut writer = hound::WavWriter::new(&mut wav_cursor, spec).ok()?;
+        for &s in &data {
+            writer.write_sample((s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16).ok()?;
+        }
+        writer.finalize().ok()?;
     }
-    let stream = stream_res.unwrap();
+    Some(wav_cursor.into_inner())
+}
 
-    stream.play().expect("Failed to start audio stream");
+pub fn record_audio_and_transcribe(
+    preset: Preset, 
+    stop_signal: Arc<AtomicBool>, 
+    pause_signal: Arc<AtomicBool>,
+    // FIX: New argument to handle user cancellation
+    abort_signal: Arc<AtomicBool>,
+    overlay_hwnd: HWND
+) {
+    log::info!("Starting audio recording. Source: {}", preset.audio_source);
+    let _session = crate::sessions::register(crate::sessions::SessionKind::Recording, &preset.name);
+    let mut input = match crate::audio_capture::RecordingInput::open(&preset.audio_source, preset.loopback_device.as_deref(), pause_signal) {
+        Ok(input) => input,
+        Err(e) => {
+            log::error!("Failed to open audio input: {}", e);
+            let ui_language = crate::APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+            crate::overlay::app_blocklist::show_toast(format!("⚠ {}", e.message(&ui_language)));
+            unsafe { PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); }
+            return;
+        }
+    };
+    let sample_rate = input.sample_rate;
+    let channels = input.channels;
 
     let mut collected_samples: Vec<f32> = Vec::new();
+    // Limit counts recorded audio, so paused time doesn't count
+    let samples_per_sec = (sample_rate as usize * channels as usize).max(1);
+    let max_secs = preset.max_recording_secs;
 
     // Wait loop with channel draining
     while !stop_signal.load(Ordering::SeqCst) {
-        // Drain channel
-        while let Ok(chunk) = rx.try_recv() {
-            collected_samples.extend(chunk);
+        // Drain captured audio
+        collected_samples.extend(input.drain());
+
+        // Auto-stop at the limit: same as pressing the hotkey again
+        if max_secs > 0 {
+            let recorded = (collected_samples.len() / samples_per_sec) as u32;
+            crate::overlay::recording::RECORDING_SECS_LEFT.store(max_secs.saturating_sub(recorded), Ordering::SeqCst);
+            if recorded >= max_secs {
+                log::info!("Recording reached the {}s limit, submitting", max_secs);
+                stop_signal.store(true, Ordering::SeqCst);
+                break;
+            }
         }
         std::thread::sleep(std::time::Duration::from_millis(50));
         // Also check if UI died
@@ -1065,7 +1194,8 @@ pub fn record_audio_and_transcribe(
         }
     }
 
-    drop(stream);
+    // Stop the devices; keeps whatever was still buffered
+    let remaining = input.stop();
 
     // FIX: Check if we should ABORT instead of submitting
     if abort_signal.load(Ordering::SeqCst) {
@@ -1079,16 +1209,9 @@ pub fn record_audio_and_transcribe(
     }
 
     // Final drain of any remaining samples
-    while let Ok(chunk) = rx.try_recv() {
-        collected_samples.extend(chunk);
-    }
+    collected_samples.extend(remaining);
 
-    // Convert f32 samples to i16 for WAV
-    let samples: Vec<i16> = collected_samples.iter()
-        .map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
-        .collect();
-    
-    if samples.is_empty() {
+    if collected_samples.is_empty() {
         println!("Warning: Recorded audio buffer is empty.");
         unsafe {
             PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
@@ -1097,18 +1220,20 @@ pub fn record_audio_and_transcribe(
     }
 
     // OPTIMIZATION: Write directly to in-memory buffer instead of disk
-    let mut wav_cursor = Cursor::new(Vec::new());
-    {
-        let mut writer = hound::WavWriter::new(&mut wav_cursor, spec).expect("Failed to create memory writer");
-        for sample in &samples {
-            writer.write_sample(*sample).expect("Failed to write sample");
-        }
-        writer.finalize().expect("Failed to finalize WAV");
-    }
-    let wav_data = wav_cursor.into_inner();
+    let wav_data = encode_wav(&collected_samples, channels, sample_rate, preset.optimize_audio_upload)
+        .expect("Failed to encode WAV");
     
     // Delegate processing to overlay module (handles streaming UI)
-    crate::overlay::process::process_audio_post_record(preset, wav_data, overlay_hwnd);
+    crate::overlay::process::process_audio_post_record(preset, AudioClip::wav(wav_data), overlay_hwnd);
+}
+
+/// Live audio chunk and overlap lengths in interleaved samples (frames × channels). The
+/// overlap is kept below half a chunk so every chunk still brings mostly new audio.
+fn live_chunk_layout(sample_rate: u32, channels: u16, chunk_secs: f32, overlap_ms: u32) -> (usize, usize) {
+    let frame = channels.max(1) as usize;
+    let chunk_frames = ((sample_rate as f32 * chunk_secs.clamp(0.5, 30.0)) as usize).max(1);
+    let overlap_frames = (sample_rate as usize * overlap_ms as usize / 1000).min(chunk_frames / 2);
+    (chunk_frames * frame, overlap_frames * frame)
 }
 
 pub fn record_audio_continuous(
@@ -1118,106 +1243,54 @@ pub fn record_audio_continuous(
     abort_signal: Arc<AtomicBool>,
     overlay_hwnd: HWND,
 ) {
-    let host = cpal::default_host();
-    let device = if preset.audio_source == "device" {
-        #[cfg(target_os = "windows")]
-        {
-            match host.default_output_device() {
-                Some(d) => d,
-                None => host.default_input_device().expect("No input device available")
-            }
-        }
-        #[cfg(not(target_os = "windows"))]
-        host.default_input_device().expect("No input device available")
-    } else {
-        host.default_input_device().expect("No input device available")
-    };
-
-    let config = if preset.audio_source == "device" {
-        match device.default_output_config() {
-            Ok(c) => c,
-            Err(_) => device.default_input_config().expect("Failed to get audio config")
+    let _session = crate::sessions::register(crate::sessions::SessionKind::LiveAudio, &preset.name);
+    let mut input = match crate::audio_capture::RecordingInput::open(&preset.audio_source, preset.loopback_device.as_deref(), pause_signal) {
+        Ok(input) => input,
+        Err(e) => {
+            log::error!("Failed to open audio input: {}", e);
+            let ui_language = crate::APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+            crate::overlay::app_blocklist::show_toast(format!("⚠ {}", e.message(&ui_language)));
+            unsafe { PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); }
+            return;
         }
-    } else {
-        device.default_input_config().expect("Failed to get audio config")
     };
+    let sample_rate = input.sample_rate;
+    let channels = input.channels;
+    let optimize_upload = preset.optimize_audio_upload;
 
-    let sample_rate = config.sample_rate().0;
-    let channels = config.channels();
-    let spec = hound::WavSpec {
-        channels,
-        sample_rate,
-        bits_per_sample: 16,
-        sample_format: hound::SampleFormat::Int,
-    };
-
-    let (tx, rx) = mpsc::channel::<Vec<f32>>();
-    
     // Start the persistent result session
     let session = crate::overlay::process::start_live_translation_session(preset.clone(), overlay_hwnd);
 
-    let err_fn = |err| eprintln!("Audio stream error: {}", err);
-    let stream_res = match config.sample_format() {
-        cpal::SampleFormat::F32 => device.build_input_stream(
-            &config.into(),
-            move |data: &[f32], _: &_| {
-                if !pause_signal.load(Ordering::Relaxed) {
-                    let _ = tx.send(data.to_vec());
-                }
-            },
-            err_fn,
-            None
-        ),
-        cpal::SampleFormat::I16 => device.build_input_stream(
-            &config.into(),
-            move |data: &[i16], _: &_| {
-                if !pause_signal.load(Ordering::Relaxed) {
-                    let f32_data: Vec<f32> = data.iter().map(|&s| s as f32 / i16::MAX as f32).collect();
-                    let _ = tx.send(f32_data);
-                }
-            },
-            err_fn,
-            None
-        ),
-        _ => return,
-    };
-
-    if let Err(e) = stream_res {
-        eprintln!("Failed to build stream: {}", e);
-        return;
-    }
-    let stream = stream_res.unwrap();
-    stream.play().expect("Failed to start audio stream");
-
     let mut collected_samples: Vec<f32> = Vec::new();
-    let chunk_duration_samples = (sample_rate as usize) * 2; // 2 seconds chunks (faster response)
+    let (chunk_duration_samples, overlap_samples) = live_chunk_layout(
+        sample_rate, channels, preset.live_chunk_secs, preset.live_chunk_overlap_ms,
+    );
+    let session_start = std::time::Instant::now();
+    let max_secs = preset.max_recording_secs;
 
     while !stop_signal.load(Ordering::SeqCst) {
-        // Drain incoming audio to buffer
-        while let Ok(chunk) = rx.try_recv() {
-            collected_samples.extend(chunk);
+        // Overall session cap
+        if max_secs > 0 {
+            let elapsed = session_start.elapsed().as_secs() as u32;
+            crate::overlay::recording::RECORDING_SECS_LEFT.store(max_secs.saturating_sub(elapsed), Ordering::SeqCst);
+            if elapsed >= max_secs {
+                log::info!("Live Audio: Session reached the {}s limit, stopping", max_secs);
+                *session.stop_notice.lock().unwrap() = Some(max_secs);
+                break;
+            }
         }
 
+        // Drain incoming audio to buffer
+        collected_samples.extend(input.drain());
+
         // Process full chunks
         while collected_samples.len() >= chunk_duration_samples {
-            let chunk: Vec<f32> = collected_samples.drain(0..chunk_duration_samples).collect();
+            // Keep the tail so the next chunk starts with it (words cut at the boundary)
+            let chunk: Vec<f32> = collected_samples[..chunk_duration_samples].to_vec();
+            collected_samples.drain(0..chunk_duration_samples - overlap_samples);
             
-            let samples: Vec<i16> = chunk.iter()
-                .map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
-                .collect();
-            
-            let mut wav_cursor = Cursor::new(Vec::new());
-            let success = {
-                if let Ok(mut writer) = hound::WavWriter::new(&mut wav_cursor, spec) {
-                    for sample in samples { let _ = writer.write_sample(sample); }
-                    let _ = writer.finalize();
-                    true
-                } else {
-                    false
-                }
-            };
-            if success {
-                let _ = session.tx.send(wav_cursor.into_inner());
+            if let Some(wav) = encode_wav(&chunk, channels, sample_rate, optimize_upload) {
+                session.send(wav);
             }
         }
 
@@ -1232,28 +1305,11 @@ pub fn record_audio_continuous(
         }
     }
 
-    drop(stream);
-
     // Process remaining partial chunk if it has meaningful data (> 1 second)
-    while let Ok(chunk) = rx.try_recv() {
-        collected_samples.extend(chunk);
-    }
-    if collected_samples.len() > sample_rate as usize {
-        let samples: Vec<i16> = collected_samples.iter()
-            .map(|&s| (s.clamp(-1.0, 1.0) * i16::MAX as f32) as i16)
-            .collect();
-        let mut wav_cursor = Cursor::new(Vec::new());
-        let success = {
-            if let Ok(mut writer) = hound::WavWriter::new(&mut wav_cursor, spec) {
-                for sample in samples { let _ = writer.write_sample(sample); }
-                let _ = writer.finalize();
-                true
-            } else {
-                false
-            }
-        };
-        if success {
-            let _ = session.tx.send(wav_cursor.into_inner());
+    collected_samples.extend(input.stop());
+    if collected_samples.len() > sample_rate as usize * channels.max(1) as usize {
+        if let Some(wav) = encode_wav(&collected_samples, channels, sample_rate, optimize_upload) {
+            session.send(wav);
         }
     }
 
@@ -1266,6 +1322,7 @@ pub fn record_audio_continuous(
 
 pub fn capture_screen_continuous(
     preset: crate::config::Preset,
+    preset_idx: usize, // For picking a new region with the same preset
     rect: RECT, // The selection region
     overlay_hwnd: HWND, // The result window (or we create it here? No, session creates it)
     // Actually, session creates result window.
@@ -1277,18 +1334,30 @@ pub fn capture_screen_continuous(
     // We need a dummy HWND or handle for session?
     // start_live_vision_session takes overlay_hwnd mainly to close it (if it's recording overlay).
     // Here we can pass HWND(0) if we handle closing separately.
-    let session = crate::overlay::process::start_live_vision_session(preset.clone(), HWND(0)); 
-
-    // 2. State
+    // 2. State (set before the session so its control bar sees an active session)
+    let _worker = crate::shutdown::worker();
+    let _session = crate::sessions::register(crate::sessions::SessionKind::LiveVision, &preset.name);
     VISION_ACTIVE.store(true, Ordering::SeqCst);
     VISION_STOP_SIGNAL.store(false, Ordering::SeqCst);
-
-    let x_virt = unsafe { GetSystemMetrics(SM_XVIRTUALSCREEN) };
-    let y_virt = unsafe { GetSystemMetrics(SM_YVIRTUALSCREEN) };
-    let crop_x = (rect.left - x_virt).max(0) as u32;
-    let crop_y = (rect.top - y_virt).max(0) as u32;
-    let crop_w = (rect.right - rect.left).abs() as u32;
-    let crop_h = (rect.bottom - rect.top).abs() as u32;
+    VISION_PAUSED.store(false, Ordering::SeqCst);
+    VISION_NEW_REGION.lock().unwrap().take();
+
+    let session = crate::overlay::process::start_live_vision_session(preset.clone(), preset_idx, HWND(0));
+
+    let (mut crop_x, mut crop_y, mut crop_w, mut crop_h) = crop_for_rect(rect);
+    let mut region = rect;
+    let mut blocked_by: Option<String> = None; // Blocklisted app in front of the region: paused
+
+    // Region kept relative to an app window that may move or be resized
+    let ui_language = APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+    let mut followed = preset.live_follow_window.as_deref().and_then(|pattern| {
+        let found = crate::overlay::follow_window::FollowedWindow::find(pattern, rect);
+        match &found {
+            Some(window) => log::info!("Live Vision: Following window \"{}\"", window.title),
+            None => crate::overlay::app_blocklist::show_toast(crate::overlay::follow_window::not_found_message(&ui_language, pattern)),
+        }
+        found
+    });
 
     log::info!("Starting Live Vision Loop. Region: {}x{} at {},{}", crop_w, crop_h, crop_x, crop_y);
 
@@ -1305,7 +1374,66 @@ pub fn capture_screen_continuous(
             break;
         }
 
+        // Region changed from the control bar: keep the session, crop elsewhere
+        if let Some(new_rect) = VISION_NEW_REGION.lock().unwrap().take() {
+            region = new_rect;
+            (crop_x, crop_y, crop_w, crop_h) = crop_for_rect(new_rect);
+            if let Some(window) = followed.as_mut() {
+                window.set_region(new_rect);
+            }
+            last_processed_image = None;
+            current_interval = min_interval;
+            log::info!("Live Vision: Region changed to {}x{} at {},{}", crop_w, crop_h, crop_x, crop_y);
+        }
+
+        if VISION_PAUSED.load(Ordering::SeqCst) {
+            std::thread::sleep(std::time::Duration::from_millis(100));
+            continue;
+        }
+
+        if let Some(window) = &followed {
+            use crate::overlay::follow_window::Follow;
+            match window.region() {
+                Follow::Region(rect) => {
+                    if rect != region {
+                        region = rect;
+                        (crop_x, crop_y, crop_w, crop_h) = crop_for_rect(rect);
+                    }
+                }
+                Follow::Minimized => {
+                    std::thread::sleep(std::time::Duration::from_millis(200));
+                    continue;
+                }
+                Follow::Closed => {
+                    log::info!("Live Vision: Followed window \"{}\" closed, stopping", window.title);
+                    crate::overlay::app_blocklist::show_toast(crate::overlay::follow_window::closed_message(&ui_language, &window.title));
+                    break;
+                }
+            }
+        }
+
+        // Pause while a blocklisted app has focus on the region's monitor
+        let blocked = crate::overlay::app_blocklist::blocked_app_over_region(region);
+        if blocked != blocked_by {
+            match &blocked {
+                Some(app) => {
+                    log::info!("Live Vision: Paused, {} is on the blocklist", app);
+                    crate::overlay::app_blocklist::show_blocked_toast(app);
+                }
+                None => {
+                    log::info!("Live Vision: Resumed");
+                    last_processed_image = None;
+                }
+            }
+            blocked_by = blocked;
+        }
+        if blocked_by.is_some() {
+            std::thread::sleep(std::time::Duration::from_millis(200));
+            continue;
+        }
+
         // Capture
+        let captured_at = std::time::Instant::now();
         if let Ok(img) = crate::capture::capture_full_screen() {
              let img_w = img.width();
              let img_h = img.height();
@@ -1340,7 +1468,7 @@ pub fn capture_screen_continuous(
                      };
                      
                      // Send to session
-                     let _ = session.tx.send(resized);
+                     session.send(captured_at, resized);
                  } else {
                      // NO CHANGE - gradually slow down polling to save resources
                      static_streak += 1;
@@ -1357,10 +1485,24 @@ pub fn capture_screen_continuous(
     }
 
     VISION_ACTIVE.store(false, Ordering::SeqCst);
+    VISION_PAUSED.store(false, Ordering::SeqCst);
     log::info!("Live Vision Loop Ended");
 }
 
-pub fn upload_audio_to_whisper(api_key: &str, model: &str, audio_data: Vec<u8>) -> anyhow::Result<String> {
+/// Whisper transcription plus its segment timings (from `verbose_json`)
+pub struct WhisperTranscript {
+    pub text: String,
+    pub segments: Vec<crate::history::SubtitleSegment>,
+}
+
+const WHISPER_TRANSLATE_MODEL: &str = "whisper-large-v3";
+
+/// `language` is an ISO-639-1 hint (None = auto-detect). With `translate` the clip goes to
+/// the /translations endpoint instead, which always answers in English.
+pub fn upload_audio_to_whisper(api_key: &str, model: &str, clip: &AudioClip, language: Option<&str>, translate: bool) -> anyhow::Result<WhisperTranscript> {
+    // Groq only serves translations from the full large-v3 model
+    let model = if translate { WHISPER_TRANSLATE_MODEL } else { model };
+
     // Create multipart form data
     let boundary = format!("----SGTBoundary{}", std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
@@ -1375,22 +1517,43 @@ pub fn upload_audio_to_whisper(api_key: &str, model: &str, audio_data: Vec<u8>)
     body.extend_from_slice(model.as_bytes());
     body.extend_from_slice(b"\r\n");
     
+    // Ask for segment timestamps (needed for SRT export)
+    body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+    body.extend_from_slice(b"Content-Disposition: form-data; name=\"response_format\"\r\n\r\nverbose_json\r\n");
+    if !translate {
+        body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+        body.extend_from_slice(b"Content-Disposition: form-data; name=\"timestamp_granularities[]\"\r\n\r\nsegment\r\n");
+
+        // Language hint (the translations endpoint has no such field)
+        if let Some(lang) = language.filter(|l| !l.trim().is_empty()) {
+            body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
+            body.extend_from_slice(b"Content-Disposition: form-data; name=\"language\"\r\n\r\n");
+            body.extend_from_slice(lang.trim().as_bytes());
+            body.extend_from_slice(b"\r\n");
+        }
+    }
+    
     // Add file field
     body.extend_from_slice(format!("--{}\r\n", boundary).as_bytes());
-    body.extend_from_slice(b"Content-Disposition: form-data; name=\"file\"; filename=\"audio.wav\"\r\n");
-    body.extend_from_slice(b"Content-Type: audio/wav\r\n\r\n");
-    body.extend_from_slice(&audio_data);
+    body.extend_from_slice(format!("Content-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\n", clip.file_name.replace('"', "")).as_bytes());
+    body.extend_from_slice(format!("Content-Type: {}\r\n\r\n", clip.mime_type).as_bytes());
+    body.extend_from_slice(&clip.data);
     body.extend_from_slice(b"\r\n");
     
     // End boundary
     body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
     
     // Make API request
-    let response = UREQ_AGENT.post("https://api.groq.com/openai/v1/audio/transcriptions")
+    let endpoint = if translate {
+        "https://api.groq.com/openai/v1/audio/translations"
+    } else {
+        "https://api.groq.com/openai/v1/audio/transcriptions"
+    };
+    let response = UREQ_AGENT.post(endpoint)
         .set("Authorization", &format!("Bearer {}", api_key))
         .set("Content-Type", &format!("multipart/form-data; boundary={}", boundary))
         .send_bytes(&body)
-        .map_err(|e| anyhow::anyhow!("API request failed: {}", e))?;
+        .map_err(|e| ApiError::from_ureq("Groq", e))?;
     
     // --- CAPTURE RATE LIMITS ---
     if let Some(remaining) = response.header("x-ratelimit-remaining-requests") {
@@ -1408,21 +1571,122 @@ pub fn upload_audio_to_whisper(api_key: &str, model: &str, audio_data: Vec<u8>)
     
     let text = json.get("text")
         .and_then(|t| t.as_str())
-        .ok_or_else(|| anyhow::anyhow!("No text in response"))?;
+        .ok_or(ApiError::EmptyResponse)?;
     
-    Ok(text.to_string())
+    let segments = json.get("segments")
+        .and_then(|s| s.as_array())
+        .map(|arr| arr.iter().filter_map(|seg| {
+            let start = seg.get("start")?.as_f64()?;
+            let end = seg.get("end")?.as_f64()?;
+            let text = seg.get("text")?.as_str()?.trim();
+            if text.is_empty() { return None; }
+            Some(crate::history::SubtitleSegment {
+                start_ms: (start * 1000.0) as u64,
+                end_ms: (end * 1000.0) as u64,
+                text: text.to_string(),
+                translation: None,
+            })
+        }).collect())
+        .unwrap_or_default();
+    
+    Ok(WhisperTranscript { text: text.to_string(), segments })
+}
+
+/// Query each provider with a configured key for its current model list.
+/// Returns the usable models plus one error message per provider that failed.
+pub fn discover_models(groq_api_key: &str, gemini_api_key: &str, openrouter_api_key: &str) -> (Vec<crate::model_config::ModelConfig>, Vec<String>) {
+    use crate::model_config::{infer_model_type, ModelConfig};
+
+    let mut models = Vec::new();
+    let mut errors = Vec::new();
+
+    // (provider, model name, input modalities)
+    let mut listed: Vec<(&str, String, Vec<String>)> = Vec::new();
+
+    if !groq_api_key.trim().is_empty() {
+        match fetch_model_list("https://api.groq.com/openai/v1/models", Some(groq_api_key)) {
+            Ok(v) => {
+                for m in v.get("data").and_then(|d| d.as_array()).into_iter().flatten() {
+                    let active = m.get("active").and_then(|a| a.as_bool()).unwrap_or(true);
+                    if let (true, Some(id)) = (active, m.get("id").and_then(|i| i.as_str())) {
+                        listed.push(("groq", id.to_string(), Vec::new()));
+                    }
+                }
+            }
+            Err(e) => errors.push(format!("Groq: {}", e)),
+        }
+    }
+
+    if !gemini_api_key.trim().is_empty() {
+        let url = format!("https://generativelanguage.googleapis.com/v1beta/models?pageSize=1000&key={}", gemini_api_key);
+        match fetch_model_list(&url, None) {
+            Ok(v) => {
+                for m in v.get("models").and_then(|d| d.as_array()).into_iter().flatten() {
+                    let generates = m.get("supportedGenerationMethods")
+                        .and_then(|g| g.as_array())
+                        .map_or(false, |g| g.iter().any(|x| x.as_str() == Some("generateContent")));
+                    if let (true, Some(name)) = (generates, m.get("name").and_then(|n| n.as_str())) {
+                        listed.push(("google", name.trim_start_matches("models/").to_string(), Vec::new()));
+                    }
+                }
+            }
+            Err(e) => errors.push(format!("Gemini: {}", e)),
+        }
+    }
+
+    if !openrouter_api_key.trim().is_empty() {
+        match fetch_model_list("https://openrouter.ai/api/v1/models", Some(openrouter_api_key)) {
+            Ok(v) => {
+                for m in v.get("data").and_then(|d| d.as_array()).into_iter().flatten() {
+                    // The full catalogue has hundreds of paid models; only offer the free ones
+                    let Some(id) = m.get("id").and_then(|i| i.as_str()) else { continue };
+                    if !id.ends_with(":free") {
+                        continue;
+                    }
+                    let modalities = m.get("architecture")
+                        .and_then(|a| a.get("input_modalities"))
+                        .and_then(|i| i.as_array())
+                        .map(|i| i.iter().filter_map(|x| x.as_str().map(String::from)).collect())
+                        .unwrap_or_default();
+                    listed.push(("openrouter", id.to_string(), modalities));
+                }
+            }
+            Err(e) => errors.push(format!("OpenRouter: {}", e)),
+        }
+    }
+
+    for (provider, name, modalities) in listed {
+        if let Some(model_type) = infer_model_type(provider, &name, &modalities) {
+            models.push(ModelConfig::discovered(provider, &name, model_type));
+        }
+    }
+
+    log::info!("Model discovery: {} models, {} errors", models.len(), errors.len());
+    (models, errors)
+}
+
+fn fetch_model_list(url: &str, bearer: Option<&str>) -> Result<serde_json::Value> {
+    let mut req = UREQ_AGENT.get(url);
+    if let Some(key) = bearer {
+        req = req.set("Authorization", &format!("Bearer {}", key));
+    }
+    let resp = req.call().map_err(|e| anyhow::anyhow!("{}", e))?;
+    resp.into_json().map_err(|e| anyhow::anyhow!("Invalid model list: {}", e))
 }
 
 use crate::gemini_live::GeminiLiveClient;
 use crate::audio_capture::AudioCapture;
 use crate::config::AudioSource;
 
+/// Stream the preset's audio to Gemini Live and show the text as it arrives.
+/// Returns false (with no window left open) if Live is unavailable, so the caller
+/// can fall back to the chunked REST path (unless the model is `is_live_only`).
 pub fn run_gemini_live_preset(
     preset: crate::config::Preset,
     stop_signal: Arc<AtomicBool>,
     abort_signal: Arc<AtomicBool>,
     recording_hwnd: HWND,
-) {
+) -> bool {
     // 1. Setup Result Window (UI Thread)
     // We position it at bottom center of the screen
     let screen_w = unsafe { GetSystemMetrics(SM_CXSCREEN) };
@@ -1445,16 +1709,10 @@ pub fn run_gemini_live_preset(
         app.config.gemini_api_key.clone()
     };
     
-    if api_key.is_empty() {
-        crate::overlay::result::update_window_text(result_hwnd, "Error: Missing Gemini API Key");
-        std::thread::sleep(std::time::Duration::from_secs(3));
-        unsafe { 
-            PostMessageW(result_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); 
-            if IsWindow(recording_hwnd).as_bool() {
-                PostMessageW(recording_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
-            }
-        }
-        return;
+    if api_key.trim().is_empty() {
+        log::warn!("Gemini Live: no Gemini API key, using chunked transcription");
+        destroy_live_result_window(result_hwnd);
+        return false;
     }
 
     // 2. Gemini Client Setup
@@ -1474,31 +1732,28 @@ pub fn run_gemini_live_preset(
     // We can use it as system instruction.
     let system_instruction = if !preset.prompt.is_empty() { Some(preset.prompt.clone()) } else { None };
 
-    let mut client = match GeminiLiveClient::new(api_key, system_instruction, on_text) {
+    // Blocks until the server accepts (or rejects) the session
+    let client = match GeminiLiveClient::new(api_key, system_instruction, on_text) {
         Ok(c) => c,
         Err(e) => {
-             crate::overlay::result::update_window_text(result_hwnd, &format!("Connection Error: {}", e));
-             std::thread::sleep(std::time::Duration::from_secs(3));
-             unsafe { 
-                 PostMessageW(result_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
-                 if IsWindow(recording_hwnd).as_bool() {
-                    PostMessageW(recording_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
-                 }
-             }
-             return;
+            log::warn!("Gemini Live unavailable, using chunked transcription: {}", e);
+            destroy_live_result_window(result_hwnd);
+            // Stopped while connecting: nothing left to fall back to
+            return stop_signal.load(Ordering::SeqCst) || abort_signal.load(Ordering::SeqCst);
         }
     };
     
-    // Connection happens in background thread in new()
-    
     // 3. Audio Capture Setup
     let mut audio_capture = AudioCapture::new();
+    // The live stream takes a single device; "both" falls back to the microphone here
     let source = if preset.audio_source == "device" { AudioSource::SystemLoopback } else { AudioSource::Microphone };
     
-    if let Err(e) = audio_capture.start(source, move |data| {
+    if let Err(e) = audio_capture.start(source, preset.loopback_device.as_deref(), move |data| {
         client.send_audio(data);
     }) {
-        crate::overlay::result::update_window_text(result_hwnd, &format!("Audio Error: {}", e));
+        log::error!("Audio capture failed: {}", e);
+        let ui_language = crate::APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+        crate::overlay::result::update_window_error(result_hwnd, &e.message(&ui_language));
         std::thread::sleep(std::time::Duration::from_secs(3));
         unsafe { 
              PostMessageW(result_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
@@ -1506,7 +1761,7 @@ pub fn run_gemini_live_preset(
                 PostMessageW(recording_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
              }
         }
-        return;
+        return true;
     }
     
     crate::overlay::result::update_window_text(result_hwnd, "Listening...");
@@ -1547,4 +1802,81 @@ pub fn run_gemini_live_preset(
              PostMessageW(recording_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
         }
     }
+    true
+}
+
+/// Close the "Connecting..." window before falling back. It was created on this
+/// thread, so destroy it directly instead of leaving a WM_CLOSE nobody pumps.
+fn destroy_live_result_window(result_hwnd: HWND) {
+    unsafe {
+        if IsWindow(result_hwnd).as_bool() {
+            DestroyWindow(result_hwnd);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_webp_upload() {
+        // Screenshot-like: flat areas with a few sharp edges
+        let image = ImageBuffer::from_fn(64, 48, |x, y| {
+            if (x / 8 + y / 8) % 2 == 0 { Rgba([255, 255, 255, 255]) } else { Rgba([20, 40, (x * 4) as u8, 255]) }
+        });
+        let webp = encode_upload(&image, ImageUploadFormat::WebP).unwrap();
+        assert_eq!(&webp[0..4], b"RIFF");
+        assert_eq!(&webp[8..12], b"WEBP");
+
+        // Lossless: the model sees exactly the captured pixels
+        let decoded = image::load_from_memory_with_format(&webp, image::ImageFormat::WebP).unwrap().to_rgba8();
+        assert_eq!(decoded, image);
+
+        assert_eq!(upload_format(ImageUploadFormat::WebP, "google"), ImageUploadFormat::WebP);
+        assert_eq!(upload_format(ImageUploadFormat::WebP, "groq"), ImageUploadFormat::Png);
+        assert_eq!(upload_format(ImageUploadFormat::Png, "openrouter"), ImageUploadFormat::Png);
+    }
+
+    #[test]
+    fn test_upload_size() {
+        // A subtitle line: doubled
+        assert!(is_small_crop(600, 40));
+        assert_eq!(upload_size(600, 40, true), (1200, 80));
+        assert_eq!(upload_size(600, 40, false), (600, 40));
+        // Doubling stops at the upload width
+        assert_eq!(upload_size(1500, 60, true), (1920, 76));
+        // A paragraph: as captured
+        assert!(!is_small_crop(800, 400));
+        assert_eq!(upload_size(800, 400, true), (800, 400));
+        // Wider than the upload width: scaled down, small or not
+        assert_eq!(upload_size(3840, 1000, true), (1920, 500));
+        assert_eq!(upload_size(3840, 100, true), (1920, 50));
+        assert_eq!(upload_size(0, 0, true), (0, 0));
+        // Huge dimensions don't overflow the area
+        assert!(!is_small_crop(100_000, 100_000));
+    }
+
+    #[test]
+    fn test_live_chunk_layout() {
+        assert_eq!(live_chunk_layout(16000, 1, 5.0, 500), (80_000, 8_000));
+        // Stereo: the same five seconds hold twice the samples
+        assert_eq!(live_chunk_layout(48000, 2, 5.0, 500), (480_000, 48_000));
+        // Overlap capped at half a chunk, chunk length clamped
+        assert_eq!(live_chunk_layout(48000, 2, 1.0, 2000), (96_000, 48_000));
+        assert_eq!(live_chunk_layout(16000, 1, 0.1, 0), (8_000, 0));
+        assert_eq!(live_chunk_layout(44100, 0, 1.0, 100), (44_100, 4_410));
+    }
+
+    #[test]
+    fn test_json_answer() {
+        let answer = json_answer("{\"translation\": \"Hello\", \"detected_language\": \"Japanese\"}");
+        assert_eq!(answer, ("Hello".to_string(), Some("Japanese".to_string())));
+        let fenced = json_answer("```json\n{\"translation\": \"Hello\", \"detected_language\": \" \"}\n```");
+        assert_eq!(fenced, ("Hello".to_string(), None));
+        // Plain text and study answers come back whole
+        assert_eq!(json_answer("Hello {name}"), ("Hello {name}".to_string(), None));
+        let study = "{\"original\": \"猫\", \"translation\": \"cat\"}";
+        assert_eq!(json_answer(study), (study.to_string(), None));
+    }
 }
@@ -0,0 +1,200 @@
+//! API Error Module
+//!
+//! What went wrong with a provider request, as a type rather than a message string, so
+//! the localized text (`overlay::utils::get_error_message`) and retry decisions don't
+//! depend on a provider's wording. The request functions still return `anyhow::Result`;
+//! an `ApiError` travels inside and is found again with `ApiError::of`.
+
+use std::fmt;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ApiError {
+    NoApiKey,
+    InvalidKey,
+    RateLimited { retry_after: Option<Duration> }, // Wait the provider asked for, if any
+    QuotaExceeded, // Out of credits / free quota used up; retrying won't help
+    BadRequest(String),
+    Network(String), // Connection failures and server errors (5xx)
+    EmptyResponse,
+}
+
+/// Longest provider message kept in `BadRequest`/`Network`
+const MAX_DETAIL_CHARS: usize = 300;
+
+/// Cap on a provider's Retry-After before retrying a rate-limited request
+pub const MAX_RATE_LIMIT_WAIT: Duration = Duration::from_secs(30);
+
+impl ApiError {
+    /// The `ApiError` inside an error from the request functions, if it came from a provider
+    pub fn of(error: &anyhow::Error) -> Option<&ApiError> {
+        error.downcast_ref::<ApiError>()
+    }
+
+    /// Classify a failed request to `provider` ("Gemini", "Groq", "OpenRouter")
+    pub fn from_ureq(provider: &str, error: ureq::Error) -> Self {
+        match error {
+            ureq::Error::Status(code, response) => {
+                let retry_after = response.header("retry-after").and_then(|s| s.trim().parse::<u64>().ok()).map(Duration::from_secs);
+                let body = response.into_string().unwrap_or_default();
+                log::error!("{} API Error (Status {}): {}", provider, code, body);
+                Self::from_status(provider, code, retry_after, &body)
+            }
+            ureq::Error::Transport(transport) => ApiError::Network(format!("{}: {}", provider, transport)),
+        }
+    }
+
+    fn from_status(provider: &str, code: u16, retry_after: Option<Duration>, body: &str) -> Self {
+        let lower = body.to_lowercase();
+        match code {
+            401 | 403 => ApiError::InvalidKey,
+            // Gemini answers a wrong key with a 400
+            400 if lower.contains("api_key_invalid") || lower.contains("api key not valid") => ApiError::InvalidKey,
+            402 => ApiError::QuotaExceeded,
+            429 if lower.contains("insufficient_quota") => ApiError::QuotaExceeded,
+            429 => ApiError::RateLimited { retry_after: retry_after.or_else(|| retry_delay_in_body(body)) },
+            500.. => ApiError::Network(format!("{} {}: {}", provider, code, error_detail(body))),
+            _ => ApiError::BadRequest(format!("{} {}: {}", provider, code, error_detail(body))),
+        }
+    }
+
+    /// Worth sending the same request again (models sometimes answer with nothing)
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, ApiError::RateLimited { .. } | ApiError::Network(_) | ApiError::EmptyResponse)
+    }
+
+    /// Fixed in the API key settings: no key, a wrong one, or one out of quota
+    pub fn is_key_error(&self) -> bool {
+        matches!(self, ApiError::NoApiKey | ApiError::InvalidKey | ApiError::QuotaExceeded)
+    }
+}
+
+/// `error.message` of a JSON error body (OpenAI, Gemini and OpenRouter all use it),
+/// else the body itself, shortened
+fn error_detail(body: &str) -> String {
+    let message = serde_json::from_str::<serde_json::Value>(body).ok()
+        .and_then(|json| json["error"]["message"].as_str().map(str::to_string))
+        .unwrap_or_else(|| body.trim().to_string());
+    if message.chars().count() > MAX_DETAIL_CHARS {
+        format!("{}…", message.chars().take(MAX_DETAIL_CHARS).collect::<String>())
+    } else {
+        message
+    }
+}
+
+/// Gemini puts the wait in the body: `error.details[].retryDelay` = "33s"
+fn retry_delay_in_body(body: &str) -> Option<Duration> {
+    let json: serde_json::Value = serde_json::from_str(body).ok()?;
+    json["error"]["details"].as_array()?.iter()
+        .find_map(|detail| detail["retryDelay"].as_str())
+        .and_then(|delay| delay.trim_end_matches('s').parse::<f64>().ok())
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+/// How long to wait before retry `attempt` (from 1), or None if `error` isn't a rate limit.
+/// The provider's Retry-After wins over the 2 s, 4 s, 8 s backoff.
+pub fn rate_limit_wait(error: &anyhow::Error, attempt: u32) -> Option<Duration> {
+    match ApiError::of(error)? {
+        ApiError::RateLimited { retry_after } => Some(retry_after.unwrap_or(Duration::from_secs(1 << attempt)).min(MAX_RATE_LIMIT_WAIT)),
+        _ => None,
+    }
+}
+
+/// Run `request` again, up to `retries` times, while `provider` reports a rate limit.
+/// No more retries once `cancelled` returns true.
+pub fn with_rate_limit_retry<T>(
+    provider: &str,
+    retries: u32,
+    cancelled: impl Fn() -> bool,
+    mut request: impl FnMut() -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    let mut attempt = 0;
+    loop {
+        match request() {
+            Err(e) if attempt < retries && !cancelled() => {
+                let Some(wait) = rate_limit_wait(&e, attempt + 1) else { return Err(e) };
+                attempt += 1;
+                log::warn!("{}: rate limited, retry {} of {} in {:?}", provider, attempt, retries, wait);
+                std::thread::sleep(wait);
+            }
+            result => return result,
+        }
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiError::NoApiKey => write!(f, "No API key"),
+            ApiError::InvalidKey => write!(f, "Invalid API key"),
+            ApiError::RateLimited { retry_after: Some(wait) } => write!(f, "Rate limited (429), retry after {}s", wait.as_secs()),
+            ApiError::RateLimited { retry_after: None } => write!(f, "Rate limited (429)"),
+            ApiError::QuotaExceeded => write!(f, "Quota exceeded or out of credits"),
+            ApiError::BadRequest(detail) => write!(f, "Bad request: {}", detail),
+            ApiError::Network(detail) => write!(f, "Network error: {}", detail),
+            ApiError::EmptyResponse => write!(f, "No content received"),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_status() {
+        assert_eq!(ApiError::from_status("Groq", 401, None, ""), ApiError::InvalidKey);
+        let gemini_bad_key = r#"{"error":{"code":400,"message":"API key not valid. Please pass a valid API key.","status":"INVALID_ARGUMENT"}}"#;
+        assert_eq!(ApiError::from_status("Gemini", 400, None, gemini_bad_key), ApiError::InvalidKey);
+        assert_eq!(ApiError::from_status("OpenRouter", 402, None, ""), ApiError::QuotaExceeded);
+
+        let gemini_429 = r#"{"error":{"code":429,"details":[{"@type":"type.googleapis.com/google.rpc.RetryInfo","retryDelay":"33s"}]}}"#;
+        assert_eq!(ApiError::from_status("Gemini", 429, None, gemini_429), ApiError::RateLimited { retry_after: Some(Duration::from_secs(33)) });
+        // The header wins over the body
+        assert_eq!(ApiError::from_status("Groq", 429, Some(Duration::from_secs(5)), gemini_429), ApiError::RateLimited { retry_after: Some(Duration::from_secs(5)) });
+
+        let bad_model = r#"{"error":{"message":"The model `x` does not exist"}}"#;
+        assert_eq!(ApiError::from_status("Groq", 400, None, bad_model), ApiError::BadRequest("Groq 400: The model `x` does not exist".to_string()));
+        assert!(ApiError::from_status("Groq", 503, None, "upstream down").is_retryable());
+        assert!(!ApiError::InvalidKey.is_retryable());
+        assert!(ApiError::from_status("OpenRouter", 402, None, "").is_key_error());
+        assert!(!ApiError::RateLimited { retry_after: None }.is_key_error());
+        assert!(!ApiError::Network("timeout".to_string()).is_key_error());
+    }
+
+    #[test]
+    fn test_rate_limit_retry() {
+        let limited: anyhow::Error = ApiError::RateLimited { retry_after: None }.into();
+        assert_eq!(rate_limit_wait(&limited, 1), Some(Duration::from_secs(2)));
+        assert_eq!(rate_limit_wait(&limited, 3), Some(Duration::from_secs(8)));
+        let told: anyhow::Error = ApiError::RateLimited { retry_after: Some(Duration::from_secs(20)) }.into();
+        assert_eq!(rate_limit_wait(&told, 1), Some(Duration::from_secs(20)));
+        let too_long: anyhow::Error = ApiError::RateLimited { retry_after: Some(Duration::from_secs(600)) }.into();
+        assert_eq!(rate_limit_wait(&too_long, 1), Some(MAX_RATE_LIMIT_WAIT));
+        assert_eq!(rate_limit_wait(&ApiError::InvalidKey.into(), 1), None);
+        // The message alone doesn't count
+        assert_eq!(rate_limit_wait(&anyhow::anyhow!("Rate limit exceeded (429)"), 1), None);
+
+        // Other errors are returned at once
+        let mut calls = 0;
+        let result: anyhow::Result<()> = with_rate_limit_retry("Test", 3, || false, || { calls += 1; Err(ApiError::NoApiKey.into()) });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+        // So are rate limits after a cancel
+        let mut calls = 0;
+        let result: anyhow::Result<()> = with_rate_limit_retry("Test", 3, || true, || { calls += 1; Err(ApiError::RateLimited { retry_after: None }.into()) });
+        assert!(result.is_err());
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn test_of() {
+        let error: anyhow::Error = ApiError::NoApiKey.into();
+        assert_eq!(ApiError::of(&error), Some(&ApiError::NoApiKey));
+        assert_eq!(ApiError::of(&error.context("Vision request")), Some(&ApiError::NoApiKey));
+        assert_eq!(ApiError::of(&anyhow::anyhow!("Failed to parse response")), None);
+    }
+}
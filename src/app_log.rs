@@ -0,0 +1,157 @@
+//! Application Log Module
+//!
+//! `app.log` with a size cap. The file is appended to across runs, so the log of a
+//! session that crashed is still there after a restart, and it's moved to `app.log.1`
+//! (replacing the older backup) once it passes `Config.log_max_size_mb`.
+//! `Config.log_level` (Info / Debug) applies immediately, without a restart.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use crate::config::LogLevel;
+
+pub const DEFAULT_MAX_SIZE_MB: u32 = 5;
+
+/// Floor for the cap, so a 0 in the config can't rotate on every line
+const MIN_MAX_BYTES: u64 = 64 * 1024;
+
+static MAX_BYTES: AtomicU64 = AtomicU64::new(DEFAULT_MAX_SIZE_MB as u64 * 1024 * 1024);
+
+/// `app.log` -> `app.log.1`
+pub fn backup_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".1");
+    path.with_file_name(name)
+}
+
+/// Log file that moves itself to its backup once it would grow past `max_bytes`.
+/// Rotation only happens between lines, so a record is never split across the two files.
+pub struct RotatingFile {
+    path: PathBuf,
+    file: Option<File>,
+    len: u64,
+    at_line_start: bool,
+    max_bytes: &'static AtomicU64,
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+impl RotatingFile {
+    pub fn open(path: PathBuf, max_bytes: &'static AtomicU64) -> io::Result<Self> {
+        let file = open_append(&path)?;
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        Ok(Self { path, file: Some(file), len, at_line_start: true, max_bytes })
+    }
+
+    fn rotate(&mut self) {
+        self.file = None; // Windows can't rename an open file
+        let backup = backup_path(&self.path);
+        let _ = std::fs::remove_file(&backup);
+        if std::fs::rename(&self.path, &backup).is_ok() {
+            self.len = 0;
+        }
+        // If the rename failed we keep appending and try again at the next line
+        self.file = open_append(&self.path).ok();
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let limit = self.max_bytes.load(Ordering::Relaxed).max(MIN_MAX_BYTES);
+        if self.at_line_start && self.len > 0 && self.len + buf.len() as u64 > limit {
+            self.rotate();
+        }
+        let file = self.file.as_mut().ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "log file could not be reopened"))?;
+        let written = file.write(buf)?;
+        self.len += written as u64;
+        if written > 0 {
+            self.at_line_start = buf[written - 1] == b'\n';
+        }
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self.file.as_mut() {
+            Some(file) => file.flush(),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Start logging to `app.log`; called once from `main()` before anything logs
+pub fn init(level: LogLevel, max_size_mb: u32) {
+    let file = RotatingFile::open(crate::config::get_log_path(), &MAX_BYTES)
+        .or_else(|_| RotatingFile::open(PathBuf::from("app.log"), &MAX_BYTES));
+    if let Ok(file) = file {
+        // The logger passes everything up to Debug; the global max level does the filtering,
+        // which is what lets `configure` change it later
+        let _ = simplelog::WriteLogger::init(log::LevelFilter::Debug, simplelog::Config::default(), file);
+    }
+    configure(level, max_size_mb);
+}
+
+/// Apply `Config.log_level` / `Config.log_max_size_mb`
+pub fn configure(level: LogLevel, max_size_mb: u32) {
+    log::set_max_level(level.filter());
+    MAX_BYTES.store(max_size_mb as u64 * 1024 * 1024, Ordering::Relaxed);
+}
+
+/// The current log, preceded by the previous file (oldest first)
+pub fn read_recent() -> Option<String> {
+    let path = crate::config::get_log_path();
+    let current = std::fs::read(&path).ok()?;
+    let mut bytes = std::fs::read(backup_path(&path)).unwrap_or_default();
+    bytes.extend_from_slice(&current);
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static TEST_MAX_BYTES: AtomicU64 = AtomicU64::new(MIN_MAX_BYTES);
+
+    #[test]
+    fn test_rotating_file() {
+        let dir = std::env::temp_dir().join(format!("xst_log_test_{}", std::process::id()));
+        let _ = std::fs::create_dir_all(&dir);
+        let path = dir.join("app.log");
+        let backup = backup_path(&path);
+        assert_eq!(backup.file_name().unwrap(), "app.log.1");
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&backup);
+
+        let line = format!("{}\n", "x".repeat(1023)); // 1 KB per record
+        let half = MIN_MAX_BYTES as usize / 1024 / 2;
+        {
+            let mut log = RotatingFile::open(path.clone(), &TEST_MAX_BYTES).unwrap();
+            for _ in 0..half {
+                log.write_all(line.as_bytes()).unwrap();
+            }
+        }
+        // A restart appends instead of truncating
+        let mut log = RotatingFile::open(path.clone(), &TEST_MAX_BYTES).unwrap();
+        assert_eq!(log.len, (half * 1024) as u64);
+
+        // A record written in pieces stays in one file even when it crosses the cap
+        for _ in 0..half - 1 {
+            log.write_all(line.as_bytes()).unwrap();
+        }
+        let tail = format!("{}\n", "y".repeat(1100));
+        log.write_all(b"split ").unwrap();
+        log.write_all(tail.as_bytes()).unwrap(); // Passes the cap mid-record
+        log.write_all(b"after rotation\n").unwrap();
+        log.flush().unwrap();
+
+        let old = std::fs::read_to_string(&backup).unwrap();
+        assert!(old.ends_with(&format!("split {}", tail)));
+        assert_eq!(old.len(), half * 2 * 1024 - 1024 + 6 + tail.len());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "after rotation\n");
+
+        drop(log);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,8 +1,47 @@
 use crate::config::AudioSource;
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use std::sync::{Arc, Mutex};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex, mpsc};
 use std::sync::atomic::{AtomicBool, Ordering};
 
+/// Why a recording couldn't start; `message` is what the user is shown
+#[derive(Debug)]
+pub enum CaptureError {
+    OutputNotFound(String), // The preset's `loopback_device`
+    NoLoopback(String, String), // Output device name, cpal's reason
+    NoInput,
+    Device(String),
+}
+
+impl CaptureError {
+    pub fn message(&self, lang: &str) -> String {
+        let text = crate::gui::LocaleText::get(lang);
+        match self {
+            CaptureError::OutputNotFound(name) => text.audio_output_not_found.replace("{}", name),
+            CaptureError::NoLoopback(name, _) => text.audio_no_loopback.replace("{}", name),
+            CaptureError::NoInput => text.audio_no_input.to_string(),
+            CaptureError::Device(detail) => text.audio_capture_failed.replace("{}", detail),
+        }
+    }
+}
+
+impl std::fmt::Display for CaptureError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CaptureError::OutputNotFound(name) => write!(f, "Output device \"{}\" not found", name),
+            CaptureError::NoLoopback(name, reason) => write!(f, "Output device \"{}\" doesn't support loopback capture: {}", name, reason),
+            CaptureError::NoInput => write!(f, "No input device available"),
+            CaptureError::Device(detail) => write!(f, "{}", detail),
+        }
+    }
+}
+
+impl From<String> for CaptureError {
+    fn from(detail: String) -> Self {
+        CaptureError::Device(detail)
+    }
+}
+
 pub struct AudioCapture {
     stream: Option<cpal::Stream>,
     is_running: Arc<AtomicBool>,
@@ -16,26 +55,22 @@ impl AudioCapture {
         }
     }
 
-    pub fn start<F>(&mut self, source: AudioSource, on_audio: F) -> Result<(), String>
+    /// `loopback_device` picks the output recorded by `SystemLoopback` (default output when `None`)
+    pub fn start<F>(&mut self, source: AudioSource, loopback_device: Option<&str>, on_audio: F) -> Result<(), CaptureError>
     where
         F: Fn(Vec<u8>) + Send + Sync + 'static,
     {
-        let host = cpal::default_host();
-        
-        // Loopback logic: Use default OUTPUT device
-        let device = match source {
-            AudioSource::Microphone => host.default_input_device().ok_or_else(|| "No input device available".to_string())?,
-            AudioSource::SystemLoopback => host.default_output_device().ok_or_else(|| "No output device available for loopback".to_string())?,
+        let (device, config) = match source {
+            AudioSource::Microphone => {
+                let device = cpal::default_host().default_input_device().ok_or(CaptureError::NoInput)?;
+                let config = device.default_input_config().map_err(|e| format!("Failed to get default config: {}", e))?;
+                (device, config)
+            }
+            AudioSource::SystemLoopback => open_device("device", loopback_device)?,
         };
 
         log::info!("Audio capture device: {} (Source: {:?})", device.name().unwrap_or_default(), source);
 
-        // Get config
-        let config = match source {
-            AudioSource::Microphone => device.default_input_config(),
-            AudioSource::SystemLoopback => device.default_output_config(),
-        }.map_err(|e| format!("Failed to get default config: {}", e))?;
-
         let sample_rate = config.sample_rate().0;
         let channels = config.channels() as usize;
 
@@ -93,7 +128,7 @@ impl AudioCapture {
                 err_fn,
                 None,
             ),
-            _ => return Err("Unsupported sample format".to_string()),
+            _ => return Err(CaptureError::Device("Unsupported sample format".to_string())),
         }.map_err(|e| format!("Failed to build input stream: {}", e))?;
 
         stream.play().map_err(|e| format!("Failed to play stream: {}", e))?;
@@ -109,33 +144,297 @@ impl AudioCapture {
     }
 }
 
-// Simple resampler: Downmix to Mono -> Decimate/Interpolate to 16kHz -> f32 to i16 bytes
-fn process_f32_data(data: &[f32], channels: usize, input_rate: u32, target_rate: u32) -> Vec<u8> {
-    if data.is_empty() { return Vec::new(); }
+/// Sample rate Whisper works at internally; uploading more is wasted bandwidth
+pub const SPEECH_SAMPLE_RATE: u32 = 16000;
+
+/// Downmix interleaved samples to mono, then linearly resample to `target_rate`.
+/// Used to shrink recordings before upload (e.g. 48 kHz stereo -> 16 kHz mono is 6x smaller).
+pub fn downmix_and_resample(data: &[f32], channels: usize, input_rate: u32, target_rate: u32) -> Vec<f32> {
+    if data.is_empty() || channels == 0 || input_rate == 0 || target_rate == 0 { return Vec::new(); }
 
-    // 1. Downmix to Mono
-    let mono_data: Vec<f32> = if channels == 1 {
+    let mono: Vec<f32> = if channels == 1 {
         data.to_vec()
     } else {
-        data.chunks(channels)
-            .map(|chunk| chunk.iter().sum::<f32>() / channels as f32)
+        data.chunks_exact(channels)
+            .map(|frame| frame.iter().sum::<f32>() / channels as f32)
             .collect()
     };
+    if input_rate == target_rate { return mono; }
+
+    let step = input_rate as f64 / target_rate as f64;
+    let output_len = (mono.len() as u64 * target_rate as u64 / input_rate as u64) as usize;
+    let last = mono.len() - 1;
+    (0..output_len)
+        .map(|i| {
+            let pos = i as f64 * step;
+            let idx = pos as usize;
+            let frac = (pos - idx as f64) as f32;
+            let a = mono[idx.min(last)];
+            let b = mono[(idx + 1).min(last)];
+            a + (b - a) * frac
+        })
+        .collect()
+}
+
+/// Beyond this lag the behind source counts as silent (WASAPI loopback sends nothing while nothing plays)
+const MIXER_MAX_LAG_MS: u32 = 250;
+/// Lag tolerated before samples are dropped from the source that runs ahead (clock drift)
+const MIXER_DRIFT_TOLERANCE_MS: u32 = 60;
+
+/// Mixes two mono streams captured at the same nominal rate by different devices.
+/// The devices run on their own clocks, so the mixer keeps them aligned: a stalled source
+/// is padded with silence, and one that slowly runs ahead has samples dropped.
+pub struct StreamMixer {
+    queues: [VecDeque<f32>; 2],
+    max_lag: usize,
+    drift_tolerance: usize,
+}
+
+impl StreamMixer {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            queues: [VecDeque::new(), VecDeque::new()],
+            max_lag: (sample_rate * MIXER_MAX_LAG_MS / 1000) as usize,
+            drift_tolerance: (sample_rate * MIXER_DRIFT_TOLERANCE_MS / 1000) as usize,
+        }
+    }
+
+    pub fn push(&mut self, source: usize, samples: &[f32]) {
+        self.queues[source].extend(samples.iter().copied());
+    }
+
+    /// Mix everything both sources have delivered so far
+    pub fn pull(&mut self) -> Vec<f32> {
+        let (a, b) = (self.queues[0].len(), self.queues[1].len());
+        let (ahead, behind) = if a >= b { (0, 1) } else { (1, 0) };
+        let lag = a.abs_diff(b);
+
+        if lag > self.max_lag {
+            // Stalled source: fill the gap with silence so the other one isn't held back
+            self.queues[behind].extend(std::iter::repeat(0.0).take(lag));
+        } else if lag > self.drift_tolerance && !self.queues[behind].is_empty() {
+            // Both flowing but one clock is faster: drop a few evenly spaced samples (≈0.2%)
+            let span = self.queues[behind].len();
+            let drop = (lag - self.drift_tolerance).min(span / 500 + 1);
+            for k in 0..drop {
+                let pos = (k + 1) * span / (drop + 1) - k;
+                self.queues[ahead].remove(pos);
+            }
+        }
+
+        let n = self.queues[0].len().min(self.queues[1].len());
+        self.mix(n)
+    }
+
+    /// Mix out whatever is left, padding the shorter source with silence
+    pub fn flush(&mut self) -> Vec<f32> {
+        let n = self.queues[0].len().max(self.queues[1].len());
+        for q in &mut self.queues {
+            let missing = n - q.len();
+            q.extend(std::iter::repeat(0.0).take(missing));
+        }
+        self.mix(n)
+    }
+
+    fn mix(&mut self, n: usize) -> Vec<f32> {
+        let [q0, q1] = &mut self.queues;
+        q0.drain(..n).zip(q1.drain(..n)).map(|(x, y)| (x + y).clamp(-1.0, 1.0)).collect()
+    }
+}
+
+/// Input for preset recordings: a single device, or ("both") microphone + system audio
+/// mixed to mono at the loopback device's rate
+pub struct RecordingInput {
+    streams: Vec<cpal::Stream>,
+    rx: mpsc::Receiver<(usize, Vec<f32>)>,
+    mixer: Option<StreamMixer>,
+    pub sample_rate: u32,
+    pub channels: u16,
+}
 
-    // 2. Resample
-    // Simple nearest neighbor / dropping samples if input_rate > target_rate
-    let ratio = input_rate as f32 / target_rate as f32;
-    let output_len = (mono_data.len() as f32 / ratio).ceil() as usize;
-    let mut resampled = Vec::with_capacity(output_len);
+impl RecordingInput {
+    /// `audio_source` is the preset value: "mic", "device" or "both"; `loopback_device`
+    /// the output looped back for system audio (None = the default output)
+    pub fn open(audio_source: &str, loopback_device: Option<&str>, pause_signal: Arc<AtomicBool>) -> Result<Self, CaptureError> {
+        let (tx, rx) = mpsc::channel();
 
-    for i in 0..output_len {
-        let index = (i as f32 * ratio) as usize;
-        if index < mono_data.len() {
-            resampled.push(mono_data[index]);
+        if audio_source == "both" {
+            let mic = open_device("mic", None)?;
+            let loopback = open_device("device", loopback_device)?;
+            let rate = loopback.1.sample_rate().0;
+            log::info!("Dual-source recording: mic + system audio mixed at {} Hz", rate);
+
+            let mut streams = Vec::new();
+            for (idx, (device, config)) in [mic, loopback].into_iter().enumerate() {
+                streams.push(build_recording_stream(&device, config, Some(rate), idx, tx.clone(), pause_signal.clone())?);
+            }
+            Ok(Self { streams, rx, mixer: Some(StreamMixer::new(rate)), sample_rate: rate, channels: 1 })
+        } else {
+            let (device, config) = open_device(audio_source, loopback_device)?;
+            let (sample_rate, channels) = (config.sample_rate().0, config.channels());
+            let stream = build_recording_stream(&device, config, None, 0, tx, pause_signal)?;
+            Ok(Self { streams: vec![stream], rx, mixer: None, sample_rate, channels })
         }
     }
 
-    // 3. Convert to i16 bytes (Little Endian)
+    /// Samples captured since the last call, interleaved at `channels`
+    pub fn drain(&mut self) -> Vec<f32> {
+        let mut out = Vec::new();
+        while let Ok((idx, chunk)) = self.rx.try_recv() {
+            update_input_level(idx, &chunk);
+            match &mut self.mixer {
+                Some(mixer) => mixer.push(idx, &chunk),
+                None => out.extend(chunk),
+            }
+        }
+        if let Some(mixer) = &mut self.mixer {
+            out = mixer.pull();
+        }
+        out
+    }
+
+    /// Stop the device streams and return everything still buffered
+    pub fn stop(&mut self) -> Vec<f32> {
+        self.streams.clear();
+        let mut out = self.drain();
+        if let Some(mixer) = &mut self.mixer {
+            out.extend(mixer.flush());
+        }
+        out
+    }
+}
+
+/// Host that can loop back output devices (WASAPI on Windows)
+fn loopback_host() -> cpal::Host {
+    #[cfg(target_os = "windows")]
+    let host = cpal::host_from_id(cpal::HostId::Wasapi).unwrap_or(cpal::default_host());
+    #[cfg(not(target_os = "windows"))]
+    let host = cpal::default_host();
+    host
+}
+
+/// Names of the output devices system audio can be recorded from, for the preset's picker
+pub fn list_output_devices() -> Vec<String> {
+    loopback_host().output_devices()
+        .map(|devices| devices.filter_map(|d| d.name().ok()).collect())
+        .unwrap_or_default()
+}
+
+/// Device names are compared ignoring case and surrounding spaces
+fn same_device_name(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+/// The output device named `name` and its config, checked to be capturable as loopback
+fn open_named_output(host: &cpal::Host, name: &str) -> Result<(cpal::Device, cpal::SupportedStreamConfig), CaptureError> {
+    let device = host.output_devices()
+        .map_err(|e| format!("Failed to list output devices: {}", e))?
+        .find(|d| d.name().map_or(false, |n| same_device_name(&n, name)))
+        .ok_or_else(|| CaptureError::OutputNotFound(name.to_string()))?;
+    // Loopback records in the output's own mix format; a device without one can't be captured
+    let config = device.default_output_config()
+        .map_err(|e| CaptureError::NoLoopback(name.to_string(), e.to_string()))?;
+    Ok((device, config))
+}
+
+/// Device + config for "mic" or "device" (loopback on `loopback_device`, or the default output)
+fn open_device(source: &str, loopback_device: Option<&str>) -> Result<(cpal::Device, cpal::SupportedStreamConfig), CaptureError> {
+    if source == "device" {
+        let host = loopback_host();
+        if let Some(name) = loopback_device.filter(|n| !n.trim().is_empty()) {
+            let (device, config) = open_named_output(&host, name)?;
+            log::info!("Loopback device: {}", name);
+            return Ok((device, config));
+        }
+
+        let device = match host.default_output_device() {
+            Some(d) => d,
+            None => {
+                log::error!("No default output device found for loopback");
+                host.default_input_device().ok_or(CaptureError::NoInput)?
+            }
+        };
+        // Try output config first for loopback accuracy
+        let config = device.default_output_config()
+            .or_else(|_| device.default_input_config())
+            .map_err(|e| format!("Failed to get audio config: {}", e))?;
+        Ok((device, config))
+    } else {
+        let host = cpal::default_host();
+        let device = host.default_input_device().ok_or(CaptureError::NoInput)?;
+        let config = device.default_input_config().map_err(|e| format!("Failed to get audio config: {}", e))?;
+        Ok((device, config))
+    }
+}
+
+/// Input stream sending f32 chunks tagged with `idx`. With `mono_rate` set, chunks are
+/// downmixed and resampled to that rate (so two devices can be mixed).
+fn build_recording_stream(
+    device: &cpal::Device,
+    config: cpal::SupportedStreamConfig,
+    mono_rate: Option<u32>,
+    idx: usize,
+    tx: mpsc::Sender<(usize, Vec<f32>)>,
+    pause_signal: Arc<AtomicBool>,
+) -> Result<cpal::Stream, String> {
+    let rate = config.sample_rate().0;
+    let channels = config.channels() as usize;
+    let send = move |data: Vec<f32>| {
+        if pause_signal.load(Ordering::Relaxed) { return; }
+        let data = match mono_rate {
+            Some(target) => downmix_and_resample(&data, channels, rate, target),
+            None => data,
+        };
+        // If the receiver is gone the recording is over, just stop sending
+        let _ = tx.send((idx, data));
+    };
+    let err_fn = |err| log::error!("Audio stream error: {}", err);
+
+    let stream_config: cpal::StreamConfig = config.clone().into();
+    let stream = match config.sample_format() {
+        cpal::SampleFormat::F32 => device.build_input_stream(
+            &stream_config,
+            move |data: &[f32], _: &_| send(data.to_vec()),
+            err_fn,
+            None,
+        ),
+        cpal::SampleFormat::I16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[i16], _: &_| send(data.iter().map(|&s| s as f32 / i16::MAX as f32).collect()),
+            err_fn,
+            None,
+        ),
+        cpal::SampleFormat::U16 => device.build_input_stream(
+            &stream_config,
+            move |data: &[u16], _: &_| send(data.iter().map(|&s| (s as f32 - 32768.0) / 32768.0).collect()),
+            err_fn,
+            None,
+        ),
+        other => return Err(format!("Unsupported audio sample format: {:?}", other)),
+    }.map_err(|e| format!("Failed to build input stream: {}", e))?;
+
+    stream.play().map_err(|e| format!("Failed to start audio stream: {}", e))?;
+    Ok(stream)
+}
+
+/// Feed the recording overlay's level meter: peak-hold RMS on a -50..0 dB scale (the overlay decays it)
+fn update_input_level(idx: usize, chunk: &[f32]) {
+    let Some(level) = crate::overlay::recording::INPUT_LEVELS.get(idx) else { return };
+    if chunk.is_empty() { return; }
+
+    let rms = (chunk.iter().map(|s| s * s).sum::<f32>() / chunk.len() as f32).sqrt();
+    let db = 20.0 * rms.max(1e-6).log10();
+    let new = ((db + 50.0) / 50.0).clamp(0.0, 1.0);
+    let old = f32::from_bits(level.load(Ordering::Relaxed));
+    level.store(new.max(old).to_bits(), Ordering::Relaxed);
+}
+
+// Simple resampler: Downmix to Mono -> Decimate/Interpolate to 16kHz -> f32 to i16 bytes
+fn process_f32_data(data: &[f32], channels: usize, input_rate: u32, target_rate: u32) -> Vec<u8> {
+    // 1. Downmix to mono and resample
+    let resampled = downmix_and_resample(data, channels, input_rate, target_rate);
+
+    // 2. Convert to i16 bytes (Little Endian)
     let mut bytes = Vec::with_capacity(resampled.len() * 2);
     for sample in resampled {
         let clamped = sample.max(-1.0).min(1.0);
@@ -145,3 +444,96 @@ fn process_f32_data(data: &[f32], channels: usize, input_rate: u32, target_rate:
 
     bytes
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sine(freq: f32, rate: u32, secs: f32, channels: usize) -> Vec<f32> {
+        let frames = (rate as f32 * secs) as usize;
+        (0..frames)
+            .flat_map(|i| {
+                let v = (2.0 * std::f32::consts::PI * freq * i as f32 / rate as f32).sin() * 0.5;
+                std::iter::repeat(v).take(channels)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_same_device_name() {
+        assert!(same_device_name("CABLE Input (VB-Audio Virtual Cable)", " cable input (vb-audio virtual cable)"));
+        assert!(!same_device_name("Speakers (Realtek(R) Audio)", "Speakers"));
+    }
+
+    #[test]
+    fn test_downmix_and_resample_sine() {
+        // 1 second of 440 Hz at 48 kHz stereo -> 16 kHz mono
+        let input = sine(440.0, 48000, 1.0, 2);
+        let output = downmix_and_resample(&input, 2, 48000, SPEECH_SAMPLE_RATE);
+
+        assert_eq!(output.len(), 16000);
+
+        // Must still be the same sine, sampled at the new rate
+        let expected = sine(440.0, SPEECH_SAMPLE_RATE, 1.0, 1);
+        let max_err = output.iter().zip(&expected).map(|(a, b)| (a - b).abs()).fold(0.0f32, f32::max);
+        assert!(max_err < 0.01, "max error {}", max_err);
+    }
+
+    #[test]
+    fn test_resample_non_integer_ratio() {
+        // 44.1 kHz mono: the output length follows the rate ratio
+        let input = sine(1000.0, 44100, 0.5, 1);
+        let output = downmix_and_resample(&input, 1, 44100, SPEECH_SAMPLE_RATE);
+        assert_eq!(output.len(), 8000);
+
+        let expected = sine(1000.0, SPEECH_SAMPLE_RATE, 0.5, 1);
+        let max_err = output.iter().zip(&expected).map(|(a, b)| (a - b).abs()).fold(0.0f32, f32::max);
+        assert!(max_err < 0.02, "max error {}", max_err);
+    }
+
+    #[test]
+    fn test_process_f32_data() {
+        // 10 ms of 48 kHz stereo: 160 mono samples of 16-bit PCM
+        let pcm = process_f32_data(&sine(440.0, 48000, 0.01, 2), 2, 48000, SPEECH_SAMPLE_RATE);
+        assert_eq!(pcm.len(), 320);
+        assert_eq!(&pcm[..2], &0i16.to_le_bytes());
+        assert_eq!(process_f32_data(&[2.0, 2.0], 2, 16000, 16000), 32767i16.to_le_bytes().to_vec());
+        assert!(process_f32_data(&[], 2, 48000, 16000).is_empty());
+    }
+
+    #[test]
+    fn test_mixer_sums_aligned_sources() {
+        let mut mixer = StreamMixer::new(1000);
+        mixer.push(0, &[0.25, 0.5, 0.75]);
+        mixer.push(1, &[0.25, 0.25]);
+        assert_eq!(mixer.pull(), vec![0.5, 0.75]);
+        // The extra sample waits for its partner, then clips at full scale
+        mixer.push(1, &[0.5]);
+        assert_eq!(mixer.pull(), vec![1.0]);
+    }
+
+    #[test]
+    fn test_mixer_pads_stalled_source() {
+        // 1 kHz: max lag 250 samples. Loopback (1) silent while the mic keeps delivering
+        let mut mixer = StreamMixer::new(1000);
+        mixer.push(0, &vec![0.1; 300]);
+        let out = mixer.pull();
+        assert_eq!(out.len(), 300);
+        assert!(out.iter().all(|&s| (s - 0.1).abs() < 1e-6));
+    }
+
+    #[test]
+    fn test_mixer_drops_samples_on_drift() {
+        // Mic runs 100 samples ahead (tolerance 60): a few mic samples are dropped per pull
+        let mut mixer = StreamMixer::new(1000);
+        mixer.push(0, &vec![0.1; 1100]);
+        mixer.push(1, &vec![0.1; 1000]);
+        let out = mixer.pull();
+        assert_eq!(out.len(), 1000);
+        assert_eq!(mixer.queues[0].len(), 100 - 3); // 1000 / 500 + 1 samples dropped
+        assert!(mixer.queues[1].is_empty());
+
+        // Flush pads the remainder with silence
+        assert_eq!(mixer.flush().len(), 97);
+    }
+}
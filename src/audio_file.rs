@@ -0,0 +1,348 @@
+//! Audio Files Module
+//!
+//! Loads audio files from disk (voice memos, recordings) for the audio presets and
+//! prepares them for upload: files the provider can't take as-is (unsupported container
+//! or over the size limit) are decoded, downmixed to 16 kHz mono and split on silence.
+
+use std::io::Cursor;
+use std::path::{Path, PathBuf};
+
+use crate::audio_capture::{downmix_and_resample, SPEECH_SAMPLE_RATE};
+
+/// Groq's transcription endpoint rejects uploads over 25 MB; leave room for the multipart body
+pub const WHISPER_MAX_BYTES: usize = 24 * 1024 * 1024;
+/// Gemini inline requests are capped at 20 MB, and base64 grows the payload by a third
+pub const GEMINI_MAX_BYTES: usize = 14 * 1024 * 1024;
+
+/// Extensions offered in the file picker and accepted by drag & drop
+pub const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "m4a", "mp4", "aac", "ogg", "opus", "flac", "webm", "aiff", "wma"];
+
+/// Encoded audio plus what the upload endpoints need to label it
+#[derive(Clone)]
+pub struct AudioClip {
+    pub data: Vec<u8>,
+    pub mime_type: String,
+    pub file_name: String,
+    /// Start of this clip in the original audio (non-zero for split chunks)
+    pub offset_ms: u64,
+    /// File on disk, if any (decoded again when the clip has to be converted or split)
+    pub source: Option<PathBuf>,
+}
+
+impl AudioClip {
+    /// In-memory WAV from a recording
+    pub fn wav(data: Vec<u8>) -> Self {
+        Self {
+            data,
+            mime_type: "audio/wav".to_string(),
+            file_name: "audio.wav".to_string(),
+            offset_ms: 0,
+            source: None,
+        }
+    }
+
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let mime_type = mime_for_path(path)
+            .ok_or_else(|| anyhow::anyhow!("Unsupported audio file: {}", path.display()))?;
+        let data = std::fs::read(path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        let file_name = path.file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| "audio".to_string());
+
+        Ok(Self {
+            data,
+            mime_type: mime_type.to_string(),
+            file_name,
+            offset_ms: 0,
+            source: Some(path.to_path_buf()),
+        })
+    }
+}
+
+pub fn mime_for_path(path: &Path) -> Option<&'static str> {
+    let ext = path.extension()?.to_string_lossy().to_lowercase();
+    Some(match ext.as_str() {
+        "wav" => "audio/wav",
+        "mp3" => "audio/mpeg",
+        "m4a" | "mp4" => "audio/mp4",
+        "aac" => "audio/aac",
+        "ogg" | "opus" => "audio/ogg",
+        "flac" => "audio/flac",
+        "webm" => "audio/webm",
+        "aiff" => "audio/aiff",
+        "wma" => "audio/x-ms-wma",
+        _ => return None,
+    })
+}
+
+pub fn is_audio_file(path: &Path) -> bool {
+    mime_for_path(path).is_some()
+}
+
+/// Containers Groq Whisper accepts as-is
+pub fn whisper_accepts(mime_type: &str) -> bool {
+    matches!(mime_type, "audio/wav" | "audio/mpeg" | "audio/mp4" | "audio/ogg" | "audio/flac" | "audio/webm")
+}
+
+/// Containers Gemini accepts as inline audio
+pub fn gemini_accepts(mime_type: &str) -> bool {
+    matches!(mime_type, "audio/wav" | "audio/mpeg" | "audio/aac" | "audio/ogg" | "audio/flac" | "audio/aiff")
+}
+
+/// Returns the clip unchanged if the provider takes it, otherwise WAV chunks (16 kHz mono)
+/// of at most `max_bytes` each, cut at the quietest moment near each limit.
+pub fn prepare_for_upload(clip: AudioClip, max_bytes: usize, accepts: fn(&str) -> bool) -> anyhow::Result<Vec<AudioClip>> {
+    if clip.data.len() <= max_bytes && accepts(&clip.mime_type) {
+        return Ok(vec![clip]);
+    }
+
+    let (samples, channels, sample_rate) = decode(&clip)?;
+    if channels == 0 || sample_rate == 0 {
+        return Err(anyhow::anyhow!("Invalid audio format in {}", clip.file_name));
+    }
+    let rate = SPEECH_SAMPLE_RATE.min(sample_rate);
+    let mono = downmix_and_resample(&samples, channels as usize, sample_rate, rate);
+
+    // 16-bit mono PCM, minus room for the WAV header
+    let max_len = (max_bytes.saturating_sub(1024) / 2).max(rate as usize);
+    let mut bounds = vec![0];
+    bounds.extend(find_split_points(&mono, rate, max_len));
+    bounds.push(mono.len());
+
+    let stem = Path::new(&clip.file_name).file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "audio".to_string());
+    log::info!("Converting {} for upload: {} chunk(s)", clip.file_name, bounds.len() - 1);
+
+    bounds.windows(2).enumerate().map(|(i, w)| {
+        let data = crate::api::encode_wav(&mono[w[0]..w[1]], 1, rate, false)
+            .ok_or_else(|| anyhow::anyhow!("Failed to encode audio chunk"))?;
+        Ok(AudioClip {
+            data,
+            mime_type: "audio/wav".to_string(),
+            file_name: format!("{}_{}.wav", stem, i + 1),
+            offset_ms: clip.offset_ms + w[0] as u64 * 1000 / rate as u64,
+            source: None,
+        })
+    }).collect()
+}
+
+/// Sample indices to cut mono audio at so that no chunk is longer than `max_len`.
+/// Each cut is placed in the quietest 100 ms window of the last third of the chunk.
+pub fn find_split_points(samples: &[f32], sample_rate: u32, max_len: usize) -> Vec<usize> {
+    let max_len = max_len.max(1);
+    let window = ((sample_rate / 10) as usize).clamp(1, max_len);
+    let step = (window / 2).max(1);
+    let mut cuts = Vec::new();
+    let mut start = 0;
+
+    while samples.len() - start > max_len {
+        let search_from = start + max_len * 2 / 3;
+        let search_to = start + max_len - window;
+
+        let mut best = start + max_len;
+        let mut best_energy = f32::MAX;
+        let mut pos = search_from;
+        while pos <= search_to {
+            let energy = samples[pos..pos + window].iter().map(|s| s * s).sum::<f32>();
+            if energy < best_energy {
+                best_energy = energy;
+                best = pos + window / 2;
+            }
+            pos += step;
+        }
+
+        // Always move on: with tiny limits the window can sit right at `start`
+        let best = best.clamp(start + 1, start + max_len);
+        cuts.push(best);
+        start = best;
+    }
+
+    cuts
+}
+
+/// Interleaved f32 samples, channel count and sample rate
+fn decode(clip: &AudioClip) -> anyhow::Result<(Vec<f32>, u16, u32)> {
+    if clip.mime_type == "audio/wav" {
+        if let Ok(decoded) = decode_wav(&clip.data) {
+            return Ok(decoded);
+        }
+    }
+    match &clip.source {
+        Some(path) => decode_with_media_foundation(path),
+        None => Err(anyhow::anyhow!("Cannot decode {}", clip.file_name)),
+    }
+}
+
+fn decode_wav(data: &[u8]) -> anyhow::Result<(Vec<f32>, u16, u32)> {
+    let mut reader = hound::WavReader::new(Cursor::new(data))?;
+    let spec = reader.spec();
+    let samples = match spec.sample_format {
+        hound::SampleFormat::Float => reader.samples::<f32>().filter_map(Result::ok).collect(),
+        hound::SampleFormat::Int => {
+            let scale = (1i64 << (spec.bits_per_sample.clamp(1, 32) - 1)) as f32;
+            reader.samples::<i32>().filter_map(Result::ok).map(|s| s as f32 / scale).collect()
+        }
+    };
+    Ok((samples, spec.channels, spec.sample_rate))
+}
+
+/// Decodes any container Windows has a codec for (mp3, m4a/aac, wma, flac...) to f32 PCM
+fn decode_with_media_foundation(path: &Path) -> anyhow::Result<(Vec<f32>, u16, u32)> {
+    use windows::Win32::Media::MediaFoundation::*;
+    use windows::Win32::System::Com::{CoInitializeEx, COINIT_MULTITHREADED};
+
+    unsafe {
+        let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+        MFStartup(MF_VERSION, MFSTARTUP_FULL)?;
+
+        let result = (|| -> anyhow::Result<(Vec<f32>, u16, u32)> {
+            let url = windows::core::HSTRING::from(path);
+            let reader = MFCreateSourceReaderFromURL(&url, None)?;
+            let stream = MF_SOURCE_READER_FIRST_AUDIO_STREAM.0 as u32;
+
+            // Ask the reader to decode to float PCM
+            let wanted = MFCreateMediaType()?;
+            wanted.SetGUID(&MF_MT_MAJOR_TYPE, &MFMediaType_Audio)?;
+            wanted.SetGUID(&MF_MT_SUBTYPE, &MFAudioFormat_Float)?;
+            reader.SetCurrentMediaType(stream, None, &wanted)?;
+
+            let actual = reader.GetCurrentMediaType(stream)?;
+            let channels = actual.GetUINT32(&MF_MT_AUDIO_NUM_CHANNELS)? as u16;
+            let sample_rate = actual.GetUINT32(&MF_MT_AUDIO_SAMPLES_PER_SECOND)?;
+
+            let mut samples = Vec::new();
+            loop {
+                let mut flags = 0u32;
+                let mut sample: Option<IMFSample> = None;
+                reader.ReadSample(stream, 0, None, Some(&mut flags), None, Some(&mut sample))?;
+                if let Some(sample) = sample {
+                    let buffer = sample.ConvertToContiguousBuffer()?;
+                    let mut ptr = std::ptr::null_mut();
+                    let mut len = 0u32;
+                    buffer.Lock(&mut ptr, None, Some(&mut len))?;
+                    let data = std::slice::from_raw_parts(ptr as *const f32, len as usize / 4);
+                    samples.extend_from_slice(data);
+                    buffer.Unlock()?;
+                }
+                if flags & MF_SOURCE_READERF_ENDOFSTREAM.0 as u32 != 0 { break; }
+            }
+
+            Ok((samples, channels, sample_rate))
+        })();
+
+        let _ = MFShutdown();
+        result.map_err(|e| anyhow::anyhow!("Failed to decode {}: {}", path.display(), e))
+    }
+}
+
+/// Native "Open" dialog filtered to audio files. Blocks, so call it off the UI thread.
+pub fn pick_audio_file() -> Option<PathBuf> {
+    use windows::Win32::UI::Controls::Dialogs::*;
+
+    let patterns = AUDIO_EXTENSIONS.iter().map(|e| format!("*.{}", e)).collect::<Vec<_>>().join(";");
+    let filter: Vec<u16> = format!("Audio ({})\0{}\0All files (*.*)\0*.*\0\0", patterns, patterns).encode_utf16().collect();
+    let mut file_buf = vec![0u16; 1024];
+
+    let mut ofn = OPENFILENAMEW {
+        lStructSize: std::mem::size_of::<OPENFILENAMEW>() as u32,
+        lpstrFilter: windows::core::PCWSTR(filter.as_ptr()),
+        lpstrFile: windows::core::PWSTR(file_buf.as_mut_ptr()),
+        nMaxFile: file_buf.len() as u32,
+        Flags: OFN_FILEMUSTEXIST | OFN_EXPLORER | OFN_NOCHANGEDIR,
+        ..Default::default()
+    };
+
+    if unsafe { GetOpenFileNameW(&mut ofn) }.as_bool() {
+        let len = file_buf.iter().position(|&c| c == 0).unwrap_or(file_buf.len());
+        Some(PathBuf::from(String::from_utf16_lossy(&file_buf[..len])))
+    } else {
+        None
+    }
+}
+
+fn open_failed_message(ui_language: &str, file_name: &str, supported: bool) -> String {
+    match (ui_language, supported) {
+        ("vi", true) => format!("Không đọc được file {}", file_name),
+        ("vi", false) => format!("{} không phải định dạng âm thanh được hỗ trợ", file_name),
+        ("ko", true) => format!("{} 파일을 읽을 수 없습니다", file_name),
+        ("ko", false) => format!("{}은(는) 지원되는 오디오 형식이 아닙니다", file_name),
+        (_, true) => format!("Can't read {}", file_name),
+        (_, false) => format!("{} isn't a supported audio format", file_name),
+    }
+}
+
+/// Loads `path` and runs it through the audio preset like a finished recording.
+/// A file that can't be loaded is reported with a toast.
+pub fn transcribe_file(preset: crate::config::Preset, path: PathBuf) {
+    std::thread::spawn(move || {
+        match AudioClip::from_file(&path) {
+            Ok(clip) => {
+                log::info!("Transcribing audio file {} with preset {}", path.display(), preset.name);
+                crate::overlay::process::process_audio_post_record(preset, clip, windows::Win32::Foundation::HWND(0));
+            }
+            Err(e) => {
+                log::error!("{}", e);
+                let ui_language = crate::APP.lock().map(|a| a.config.ui_language.clone()).unwrap_or_default();
+                let file_name = path.file_name().map_or_else(|| path.display().to_string(), |n| n.to_string_lossy().to_string());
+                let message = open_failed_message(&ui_language, &file_name, is_audio_file(&path));
+                crate::overlay::app_blocklist::show_toast(format!("⚠ {}", message));
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_split_points_prefers_silence() {
+        let rate = 1000;
+        // 3 s of tone with a 200 ms gap at 1.5 s
+        let samples: Vec<f32> = (0..3000).map(|i| {
+            if (1500..1700).contains(&i) { 0.0 } else { (i as f32 * 0.3).sin() * 0.5 }
+        }).collect();
+
+        let cuts = find_split_points(&samples, rate, 2000);
+        assert_eq!(cuts.len(), 1);
+        assert!((1500..1700).contains(&cuts[0]), "cut at {}", cuts[0]);
+
+        // Short enough: no cut
+        assert!(find_split_points(&samples, rate, 3000).is_empty());
+    }
+
+    #[test]
+    fn test_find_split_points_respects_limit() {
+        let rate = 1000;
+        let samples: Vec<f32> = (0..10_000).map(|i| (i as f32 * 0.1).sin()).collect();
+
+        let cuts = find_split_points(&samples, rate, 1500);
+        let mut bounds = vec![0];
+        bounds.extend(&cuts);
+        bounds.push(samples.len());
+        for w in bounds.windows(2) {
+            assert!(w[1] > w[0]);
+            assert!(w[1] - w[0] <= 1500);
+        }
+    }
+
+    #[test]
+    fn test_find_split_points_tiny_limit() {
+        // Used to loop forever: the cut never moved past the chunk start
+        let samples = vec![0.5; 5];
+        assert_eq!(find_split_points(&samples, 16_000, 1), vec![1, 2, 3, 4]);
+        assert_eq!(find_split_points(&samples, 16_000, 0), vec![1, 2, 3, 4]);
+        assert_eq!(find_split_points(&samples, 1, 2), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_mime_for_path() {
+        assert_eq!(mime_for_path(Path::new("memo.M4A")), Some("audio/mp4"));
+        assert_eq!(mime_for_path(Path::new("a/b/voice.mp3")), Some("audio/mpeg"));
+        assert_eq!(mime_for_path(Path::new("notes.txt")), None);
+        assert!(whisper_accepts("audio/mp4"));
+        assert!(!gemini_accepts("audio/mp4"));
+    }
+}
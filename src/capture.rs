@@ -2,13 +2,422 @@ use image::ImageBuffer;
 use windows::Win32::Foundation::*;
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::Win32::Graphics::Direct3D::*;
+use windows::Win32::Graphics::Direct3D11::*;
+use windows::Win32::Graphics::Dxgi::Common::*;
+use windows::Win32::Graphics::Dxgi::*;
+use windows::Win32::Devices::Display::*;
+use windows::core::ComInterface;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// scRGB encodes 1.0 as 80 nits
+const SCRGB_WHITE_NITS: f32 = 80.0;
+/// Used when Windows won't report the "SDR content brightness" setting
+const DEFAULT_SDR_WHITE_NITS: f32 = 200.0;
+/// Above this (SDR white = 1.0) highlights are rolled off instead of clipped
+const TONE_MAP_KNEE: f32 = 0.9;
+
+/// Cleared once Windows refuses WDA_EXCLUDEFROMCAPTURE (Windows 10 before 2004)
+static EXCLUSION_SUPPORTED: AtomicBool = AtomicBool::new(true);
+
+/// Keep one of our overlay windows out of screen captures
+pub fn exclude_from_capture(hwnd: HWND) {
+    let ok = unsafe { SetWindowDisplayAffinity(hwnd, WDA_EXCLUDEFROMCAPTURE) }.as_bool();
+    if !ok && EXCLUSION_SUPPORTED.swap(false, Ordering::SeqCst) {
+        log::warn!("WDA_EXCLUDEFROMCAPTURE not supported; result windows will be hidden during captures");
+    }
+}
+
+/// False on Windows versions where our overlays show up in captures
+pub fn exclusion_supported() -> bool {
+    EXCLUSION_SUPPORTED.load(Ordering::SeqCst)
+}
+
+/// Bounds of all monitors together
+pub fn virtual_screen_rect() -> RECT {
+    unsafe {
+        RECT {
+            left: GetSystemMetrics(SM_XVIRTUALSCREEN),
+            top: GetSystemMetrics(SM_YVIRTUALSCREEN),
+            right: GetSystemMetrics(SM_XVIRTUALSCREEN) + GetSystemMetrics(SM_CXVIRTUALSCREEN),
+            bottom: GetSystemMetrics(SM_YVIRTUALSCREEN) + GetSystemMetrics(SM_CYVIRTUALSCREEN),
+        }
+    }
+}
 
 pub fn capture_full_screen() -> anyhow::Result<ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+    capture_rect(virtual_screen_rect())
+}
+
+/// Capture `area` (virtual-screen coordinates), keeping our result windows out of it
+pub fn capture_rect(area: RECT) -> anyhow::Result<ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+    // Without capture exclusion our result windows would end up in the screenshot
+    let hidden = if EXCLUSION_SUPPORTED.load(Ordering::SeqCst) {
+        Vec::new()
+    } else {
+        crate::overlay::result::hide_for_capture()
+    };
+    let result = capture_area(area);
+    crate::overlay::result::restore_after_capture(&hidden);
+    result
+}
+
+/// Capture one whole monitor, by GDI device name (e.g. "\\.\DISPLAY2"). Falls back
+/// to the primary monitor when that one is disconnected. Returns the image and the
+/// monitor's rect in virtual-screen coordinates.
+pub fn capture_monitor(device_name: &str) -> anyhow::Result<(ImageBuffer<image::Rgba<u8>, Vec<u8>>, RECT)> {
+    let monitors = list_monitors();
+    let rect = match monitors.iter().find(|m| m.0 == device_name) {
+        Some(m) => m.1,
+        None => {
+            let primary = monitors.iter().find(|m| m.2).or(monitors.first())
+                .ok_or_else(|| anyhow::anyhow!("No monitors found"))?;
+            log::warn!("Monitor {} not found, capturing the primary monitor ({}) instead", device_name, primary.0);
+            primary.1
+        }
+    };
+    capture_rect(rect).map(|img| (img, rect))
+}
+
+/// Bounds of the monitor with this GDI device name, if it's still attached
+pub fn find_monitor(device_name: &str) -> Option<RECT> {
+    list_monitors().into_iter().find(|m| m.0 == device_name).map(|m| m.1)
+}
+
+/// Bounds of the monitor under the mouse cursor
+pub fn cursor_monitor_rect() -> RECT {
+    unsafe {
+        let mut pt = POINT::default();
+        GetCursorPos(&mut pt);
+        let mut mi = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+        GetMonitorInfoW(MonitorFromPoint(pt, MONITOR_DEFAULTTONEAREST), &mut mi);
+        mi.rcMonitor
+    }
+}
+
+/// (GDI device name, rect, is primary) of every attached monitor
+fn list_monitors() -> Vec<(String, RECT, bool)> {
+    unsafe extern "system" fn enum_proc(hmonitor: HMONITOR, _hdc: HDC, _rect: *mut RECT, data: LPARAM) -> BOOL {
+        let monitors = &mut *(data.0 as *mut Vec<(String, RECT, bool)>);
+        let mut mi = MONITORINFOEXW::default();
+        mi.monitorInfo.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
+        if GetMonitorInfoW(hmonitor, &mut mi as *mut _ as *mut _).as_bool() {
+            let primary = mi.monitorInfo.dwFlags & MONITORINFOF_PRIMARY != 0;
+            monitors.push((wide_to_string(&mi.szDevice), mi.monitorInfo.rcMonitor, primary));
+        }
+        BOOL(1)
+    }
+
+    let mut monitors: Vec<(String, RECT, bool)> = Vec::new();
+    unsafe {
+        EnumDisplayMonitors(HDC(0), None, Some(enum_proc), LPARAM(&mut monitors as *mut _ as isize));
+    }
+    monitors
+}
+
+/// GDI capture of `area` (virtual-screen coordinates) with HDR monitors tone-mapped
+fn capture_area(area: RECT) -> anyhow::Result<ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+    let mut img = capture_gdi(area)?;
+    replace_hdr_monitors(&mut img, area.left, area.top);
+    Ok(img)
+}
+
+/// HDR outputs from the last enumeration, each with its desktop duplication once it has
+/// been captured. Rebuilt when the factory goes stale, which DXGI signals for monitors
+/// being plugged in or out and for HDR being switched on or off.
+struct HdrCache {
+    factory: IDXGIFactory1,
+    outputs: Vec<HdrOutput>,
+}
+
+// D3D11 devices are free-threaded; the contexts and duplications are only used under HDR_CACHE's lock
+unsafe impl Send for HdrCache {}
+
+static HDR_CACHE: Mutex<Option<HdrCache>> = Mutex::new(None);
+
+struct HdrOutput {
+    adapter: IDXGIAdapter1,
+    output: IDXGIOutput5,
+    desc: DXGI_OUTPUT_DESC1,
+    duplication: Option<HdrDuplication>,
+}
+
+/// A running duplication and a CPU-readable copy of the last frame it delivered
+struct HdrDuplication {
+    device: ID3D11Device,
+    context: ID3D11DeviceContext,
+    duplication: IDXGIOutputDuplication,
+    staging: Option<ID3D11Texture2D>,
+}
+
+/// GDI reads an HDR desktop as the compositor's washed-out SDR fallback. Re-grab every
+/// monitor that is in HDR mode through DXGI Desktop Duplication (FP16 scRGB) and
+/// paste the tone-mapped result over its area; SDR monitors keep the GDI pixels.
+fn replace_hdr_monitors(img: &mut ImageBuffer<image::Rgba<u8>, Vec<u8>>, origin_x: i32, origin_y: i32) {
+    let mut cache = HDR_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    if cache.as_ref().map_or(true, |c| unsafe { !c.factory.IsCurrent().as_bool() }) {
+        *cache = match hdr_outputs() {
+            Ok(found) => Some(found),
+            Err(e) => {
+                log::warn!("HDR detection failed, using GDI capture: {}", e);
+                None
+            }
+        };
+    }
+    let Some(cache) = cache.as_mut() else { return };
+    for output in &mut cache.outputs {
+        // Cheap, and catches resolution changes the factory doesn't report
+        if !output.refresh_desc() {
+            continue;
+        }
+        let rect = output.desc.DesktopCoordinates;
+        if rect.right <= origin_x || rect.bottom <= origin_y
+            || rect.left >= origin_x + img.width() as i32 || rect.top >= origin_y + img.height() as i32 {
+            continue; // Not part of this capture
+        }
+        match output.capture() {
+            Ok(hdr) => image::imageops::replace(img, &hdr, (rect.left - origin_x) as i64, (rect.top - origin_y) as i64),
+            Err(e) => log::warn!("HDR capture failed for {}, keeping GDI pixels: {}", wide_to_string(&output.desc.DeviceName), e),
+        }
+    }
+}
+
+/// Desktop outputs currently using the HDR (BT.2020 / PQ) color space
+fn hdr_outputs() -> windows::core::Result<HdrCache> {
+    let mut outputs = Vec::new();
     unsafe {
-        let x = GetSystemMetrics(SM_XVIRTUALSCREEN);
-        let y = GetSystemMetrics(SM_YVIRTUALSCREEN);
-        let width = GetSystemMetrics(SM_CXVIRTUALSCREEN);
-        let height = GetSystemMetrics(SM_CYVIRTUALSCREEN);
+        let factory: IDXGIFactory1 = CreateDXGIFactory1()?;
+        let mut a = 0;
+        while let Ok(adapter) = factory.EnumAdapters1(a) {
+            a += 1;
+            let mut o = 0;
+            while let Ok(output) = adapter.EnumOutputs(o) {
+                o += 1;
+                // IDXGIOutput6 needs Windows 10 1703+; older systems have no HDR desktop anyway
+                let Ok(output6) = output.cast::<IDXGIOutput6>() else { continue };
+                let mut desc = DXGI_OUTPUT_DESC1::default();
+                if output6.GetDesc1(&mut desc).is_err() || !desc.AttachedToDesktop.as_bool() {
+                    continue;
+                }
+                if desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020 {
+                    outputs.push(HdrOutput { adapter: adapter.clone(), output: output6.cast::<IDXGIOutput5>()?, desc, duplication: None });
+                }
+            }
+        }
+        Ok(HdrCache { factory, outputs })
+    }
+}
+
+impl HdrOutput {
+    /// Re-read the output's position and color space; false once it's no longer an HDR desktop
+    fn refresh_desc(&mut self) -> bool {
+        unsafe {
+            let Ok(output6) = self.output.cast::<IDXGIOutput6>() else { return false };
+            let mut desc = DXGI_OUTPUT_DESC1::default();
+            if output6.GetDesc1(&mut desc).is_err() {
+                return false;
+            }
+            self.desc = desc;
+        }
+        self.desc.AttachedToDesktop.as_bool() && self.desc.ColorSpace == DXGI_COLOR_SPACE_RGB_FULL_G2084_NONE_P2020
+    }
+
+    fn capture(&mut self) -> anyhow::Result<ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+        if self.desc.Rotation != DXGI_MODE_ROTATION_IDENTITY && self.desc.Rotation != DXGI_MODE_ROTATION_UNSPECIFIED {
+            return Err(anyhow::anyhow!("rotated HDR monitors are not supported"));
+        }
+        let white_scale = SCRGB_WHITE_NITS / sdr_white_nits(&self.desc.DeviceName).unwrap_or(DEFAULT_SDR_WHITE_NITS);
+
+        let result = unsafe { self.duplicated()?.read(white_scale) };
+        match result {
+            // Mode switches, full-screen apps and the secure desktop end a duplication; start a new one
+            Err(e) if e.downcast_ref::<windows::core::Error>().map_or(false, |e| e.code() == DXGI_ERROR_ACCESS_LOST) => {
+                self.duplication = None;
+                unsafe { self.duplicated()?.read(white_scale) }
+            }
+            result => result,
+        }
+    }
+
+    fn duplicated(&mut self) -> anyhow::Result<&mut HdrDuplication> {
+        let duplication = match self.duplication.take() {
+            Some(d) => d,
+            None => unsafe { HdrDuplication::new(&self.adapter, &self.output)? },
+        };
+        Ok(self.duplication.insert(duplication))
+    }
+}
+
+impl HdrDuplication {
+    unsafe fn new(adapter: &IDXGIAdapter1, output: &IDXGIOutput5) -> anyhow::Result<Self> {
+        let mut device = None;
+        let mut context = None;
+        D3D11CreateDevice(adapter, D3D_DRIVER_TYPE_UNKNOWN, None, D3D11_CREATE_DEVICE_BGRA_SUPPORT, None, D3D11_SDK_VERSION, Some(&mut device), None, Some(&mut context))?;
+        let device = device.ok_or_else(|| anyhow::anyhow!("D3D11 device creation failed"))?;
+        let context = context.ok_or_else(|| anyhow::anyhow!("D3D11 context creation failed"))?;
+
+        // Asking for FP16 makes DWM hand over linear scRGB instead of an SDR-converted frame
+        let duplication = output.DuplicateOutput1(&device, 0, &[DXGI_FORMAT_R16G16B16A16_FLOAT])?;
+        Ok(Self { device, context, duplication, staging: None })
+    }
+
+    unsafe fn read(&mut self, white_scale: f32) -> anyhow::Result<ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+        // The first frame of a new duplication carries the whole desktop. After that frames
+        // only come when something changed; without one the copy from last time is current.
+        let timeout = if self.staging.is_some() { 0 } else { 500 };
+        let mut frame_info = DXGI_OUTDUPL_FRAME_INFO::default();
+        let mut resource = None;
+        match self.duplication.AcquireNextFrame(timeout, &mut frame_info, &mut resource) {
+            Ok(()) => {
+                let copied = resource.ok_or_else(|| anyhow::anyhow!("Desktop duplication returned no frame"))
+                    .and_then(|frame| self.copy_frame(&frame.cast::<ID3D11Texture2D>()?));
+                let _ = self.duplication.ReleaseFrame();
+                copied?;
+            }
+            Err(e) if e.code() == DXGI_ERROR_WAIT_TIMEOUT && self.staging.is_some() => {}
+            Err(e) => return Err(e.into()),
+        }
+        let staging = self.staging.as_ref().ok_or_else(|| anyhow::anyhow!("No desktop frame yet"))?;
+        read_hdr_frame(&self.context, staging, white_scale)
+    }
+
+    /// Copy `frame` into the staging texture, (re)creating it for a new size
+    unsafe fn copy_frame(&mut self, frame: &ID3D11Texture2D) -> anyhow::Result<()> {
+        let mut desc = D3D11_TEXTURE2D_DESC::default();
+        frame.GetDesc(&mut desc);
+        if desc.Format != DXGI_FORMAT_R16G16B16A16_FLOAT {
+            return Err(anyhow::anyhow!("unexpected duplication format {:?}", desc.Format));
+        }
+        let reusable = self.staging.as_ref().map_or(false, |staging| {
+            let mut current = D3D11_TEXTURE2D_DESC::default();
+            staging.GetDesc(&mut current);
+            (current.Width, current.Height) == (desc.Width, desc.Height)
+        });
+        if !reusable {
+            let staging_desc = D3D11_TEXTURE2D_DESC {
+                MipLevels: 1,
+                ArraySize: 1,
+                SampleDesc: DXGI_SAMPLE_DESC { Count: 1, Quality: 0 },
+                Usage: D3D11_USAGE_STAGING,
+                BindFlags: D3D11_BIND_FLAG(0),
+                CPUAccessFlags: D3D11_CPU_ACCESS_READ,
+                MiscFlags: D3D11_RESOURCE_MISC_FLAG(0),
+                ..desc
+            };
+            let mut staging = None;
+            self.device.CreateTexture2D(&staging_desc, None, Some(&mut staging))?;
+            self.staging = Some(staging.ok_or_else(|| anyhow::anyhow!("Staging texture creation failed"))?);
+        }
+        if let Some(staging) = &self.staging {
+            self.context.CopyResource(staging, frame);
+        }
+        Ok(())
+    }
+}
+
+/// Tone-map the FP16 scRGB pixels of a staging texture to 8-bit sRGB
+unsafe fn read_hdr_frame(context: &ID3D11DeviceContext, staging: &ID3D11Texture2D, white_scale: f32) -> anyhow::Result<ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+    let mut desc = D3D11_TEXTURE2D_DESC::default();
+    staging.GetDesc(&mut desc);
+
+    let mut mapped = D3D11_MAPPED_SUBRESOURCE::default();
+    context.Map(staging, 0, D3D11_MAP_READ, 0, Some(&mut mapped))?;
+    let (width, height) = (desc.Width as usize, desc.Height as usize);
+    let mut buffer = vec![255u8; width * height * 4];
+    for y in 0..height {
+        let row = std::slice::from_raw_parts((mapped.pData as *const u8).add(y * mapped.RowPitch as usize) as *const u16, width * 4);
+        for (x, px) in row.chunks_exact(4).enumerate() {
+            let out = &mut buffer[(y * width + x) * 4..][..3];
+            for (dst, &half) in out.iter_mut().zip(px) {
+                *dst = tone_map_scrgb(f16_to_f32(half), white_scale);
+            }
+        }
+    }
+    context.Unmap(staging, 0);
+
+    ImageBuffer::from_raw(desc.Width, desc.Height, buffer).ok_or_else(|| anyhow::anyhow!("Buffer creation failed"))
+}
+
+/// The "SDR content brightness" of the display behind GDI device `device_name`
+fn sdr_white_nits(device_name: &[u16; 32]) -> Option<f32> {
+    unsafe {
+        let (mut path_count, mut mode_count) = (0u32, 0u32);
+        GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count).ok().ok()?;
+        let mut paths = vec![DISPLAYCONFIG_PATH_INFO::default(); path_count as usize];
+        let mut modes = vec![DISPLAYCONFIG_MODE_INFO::default(); mode_count as usize];
+        QueryDisplayConfig(QDC_ONLY_ACTIVE_PATHS, &mut path_count, paths.as_mut_ptr(), &mut mode_count, modes.as_mut_ptr(), None).ok().ok()?;
+
+        for path in &paths[..path_count as usize] {
+            let mut source = DISPLAYCONFIG_SOURCE_DEVICE_NAME::default();
+            source.header = DISPLAYCONFIG_DEVICE_INFO_HEADER {
+                r#type: DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+                size: std::mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32,
+                adapterId: path.sourceInfo.adapterId,
+                id: path.sourceInfo.id,
+            };
+            if DisplayConfigGetDeviceInfo(&mut source.header) != 0 || wide_to_string(&source.viewGdiDeviceName) != wide_to_string(device_name) {
+                continue;
+            }
+            let mut white = DISPLAYCONFIG_SDR_WHITE_LEVEL::default();
+            white.header = DISPLAYCONFIG_DEVICE_INFO_HEADER {
+                r#type: DISPLAYCONFIG_DEVICE_INFO_GET_SDR_WHITE_LEVEL,
+                size: std::mem::size_of::<DISPLAYCONFIG_SDR_WHITE_LEVEL>() as u32,
+                adapterId: path.targetInfo.adapterId,
+                id: path.targetInfo.id,
+            };
+            if DisplayConfigGetDeviceInfo(&mut white.header) != 0 {
+                return None;
+            }
+            // Reported in thousandths of 80 nits
+            return Some(white.SDRWhiteLevel as f32 / 1000.0 * SCRGB_WHITE_NITS);
+        }
+        None
+    }
+}
+
+fn wide_to_string(wide: &[u16]) -> String {
+    let len = wide.iter().position(|&c| c == 0).unwrap_or(wide.len());
+    String::from_utf16_lossy(&wide[..len])
+}
+
+/// IEEE 754 half -> f32
+fn f16_to_f32(bits: u16) -> f32 {
+    let sign = if bits & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((bits >> 10) & 0x1F) as i32;
+    let mantissa = (bits & 0x3FF) as f32;
+    match exponent {
+        0 => sign * mantissa * 2f32.powi(-24),
+        0x1F if mantissa == 0.0 => sign * f32::INFINITY,
+        0x1F => f32::NAN,
+        _ => sign * (1.0 + mantissa / 1024.0) * 2f32.powi(exponent - 15),
+    }
+}
+
+/// One linear scRGB channel -> 8-bit sRGB. `white_scale` maps the SDR white level to
+/// 1.0; brighter HDR highlights are compressed into the top of the range.
+fn tone_map_scrgb(value: f32, white_scale: f32) -> u8 {
+    let x = if value.is_finite() { (value * white_scale).max(0.0) } else { 0.0 };
+    let linear = if x <= TONE_MAP_KNEE {
+        x
+    } else {
+        let headroom = 1.0 - TONE_MAP_KNEE;
+        TONE_MAP_KNEE + headroom * (1.0 - (-(x - TONE_MAP_KNEE) / headroom).exp())
+    };
+    let srgb = if linear <= 0.0031308 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    };
+    (srgb.clamp(0.0, 1.0) * 255.0).round() as u8
+}
+
+fn capture_gdi(area: RECT) -> anyhow::Result<ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+    unsafe {
+        let x = area.left;
+        let y = area.top;
+        let width = area.right - area.left;
+        let height = area.bottom - area.top;
         
         // Validate dimensions
         if width <= 0 || height <= 0 {
@@ -69,3 +478,27 @@ pub fn capture_full_screen() -> anyhow::Result<ImageBuffer<image::Rgba<u8>, Vec<
         Ok(img)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hdr_tone_mapping() {
+        assert_eq!(f16_to_f32(0x3C00), 1.0);
+        assert_eq!(f16_to_f32(0xC000), -2.0);
+        assert_eq!(f16_to_f32(0x3800), 0.5);
+        assert!(f16_to_f32(0x0001) > 0.0);
+
+        // SDR white (200 nits = 2.5 in scRGB) lands on full white, black stays black
+        let scale = SCRGB_WHITE_NITS / 200.0;
+        assert_eq!(tone_map_scrgb(0.0, scale), 0);
+        assert_eq!(tone_map_scrgb(-0.2, scale), 0);
+        assert_eq!(tone_map_scrgb(f32::NAN, scale), 0);
+        assert!(tone_map_scrgb(2.5, scale) >= 250);
+        // Mid grey keeps its sRGB value; highlights roll off but stay ordered
+        assert_eq!(tone_map_scrgb(0.2158 * 2.5, scale), 128);
+        let (sdr, bright, peak) = (tone_map_scrgb(2.5, scale), tone_map_scrgb(6.0, scale), tone_map_scrgb(12.5, scale));
+        assert!(sdr < bright && bright <= peak && peak == 255);
+    }
+}
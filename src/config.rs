@@ -20,32 +20,116 @@ pub struct Preset {
     pub model: String,
     pub streaming_enabled: bool,
     pub auto_copy: bool,
+    #[serde(default = "default_auto_copy_delay_ms")]
+    pub auto_copy_delay_ms: u32, // Wait before auto-copying (some clipboard managers race with an immediate copy)
+    #[serde(default)]
+    pub copy_on_close: bool, // Auto copy when the result window is dismissed instead of when the result is done
+    #[serde(default)]
+    pub output_mode: OutputMode, // How an auto-copied result is delivered
+    #[serde(default = "default_type_out_delay_ms")]
+    pub type_out_delay_ms: u32, // Wait between characters in OutputMode::TypeOut
+    #[serde(default)]
+    pub copy_format: CopyFormat, // How the result windows' copy and the clipboard auto copy wrap the text
+    #[serde(default)]
+    pub output_file: Option<PathBuf>, // Each result is also appended to this notes file
+    #[serde(default)]
+    pub webhook: WebhookConfig, // Each result is also POSTed here
     pub hotkeys: Vec<Hotkey>,
     pub retranslate: bool,
     pub retranslate_to: String,
+    #[serde(default)]
+    pub retranslate_to_ui_language: bool, // Translate into the app's UI language instead of `retranslate_to`
     pub retranslate_model: String,
     pub retranslate_streaming_enabled: bool,
     #[serde(default)]
     pub retranslate_auto_copy: bool,
+    #[serde(default)]
+    pub retranslate_secondary_to: Option<String>, // Optional third language, chained off the vision text
+    #[serde(default = "default_retranslate_secondary_model")]
+    pub retranslate_secondary_model: String,
+    #[serde(default = "default_true")]
+    pub ocr_quality_check: bool, // Show a low-confidence badge when the output looks garbled
+    #[serde(default)]
+    pub multi_frame_capture: Option<u32>, // Grab N frames over ~1s and merge the text (tickers, fading subtitles)
+    #[serde(default)]
+    pub expand_on_empty: Option<u32>, // No text found: retry once with the selection grown by this many pixels per side
+    #[serde(default)]
+    pub preserve_layout: bool, // Keep line breaks/indentation; monospace, unwrapped result window
+    #[serde(default)]
+    pub reading_order: ReadingOrder, // Panel/bubble order the text is output in (comics, manga)
+    #[serde(default)]
+    pub compare_model: Option<String>, // Second vision model run on the same capture, shown side by side
+    #[serde(default)]
+    pub retranslate_srt: bool, // Audio: also translate Whisper segments one by one for a translated SRT
+    #[serde(default)]
+    pub early_retranslate: bool, // Start retranslating a draft while the vision text is still streaming
+    #[serde(default)]
+    pub strip_preamble: bool, // Drop a leading "Here is the translation:" style line from results
+    #[serde(default)]
+    pub reflow_paragraphs: bool, // Image results: join the lines of each paragraph (the model copies the image's line breaks)
+    #[serde(default = "default_true")]
+    pub reflow_dehyphenate: bool, // With reflow: rejoin words hyphenated at a line end
+    #[serde(default)]
+    pub study_layout: bool, // Image results: original / reading / translation sections for language study (asks for JSON)
+    #[serde(default)]
+    pub capture_on_release: bool, // Region presets: capture the selection at mouse-up, not a frozen screenshot at the hotkey (video frames)
+    #[serde(default)]
+    pub font_family: Option<String>, // Result window font (None = picked for the text's script)
     pub hide_overlay: bool,
+    #[serde(default)]
+    pub completion_sound: bool, // Hidden overlay: system sound when the result is ready (another on failure)
+    #[serde(default)]
+    pub completion_toast: bool, // Hidden overlay: "Copied N characters" toast when the result is ready
     #[serde(default = "default_preset_type")]
     pub preset_type: String, // "image", "audio", "video", "chat"
     
     // --- Audio Fields ---
     #[serde(default = "default_audio_source")]
-    pub audio_source: String, // "mic" or "device"
+    pub audio_source: String, // "mic", "device" or "both" (mixed)
+    #[serde(default)]
+    pub loopback_device: Option<String>, // Output device recorded as "device" audio (None = the default output)
     #[serde(default)]
     pub hide_recording_ui: bool,
+    #[serde(default = "default_optimize_audio_upload")]
+    pub optimize_audio_upload: bool, // Downmix/resample to 16 kHz mono before upload
+    #[serde(default = "default_max_recording_secs")]
+    pub max_recording_secs: u32, // Auto-stop recordings / live sessions after this long (0 = unlimited)
+    #[serde(default)]
+    pub whisper_language: Option<String>, // ISO-639-1 hint for Whisper (None = auto-detect)
+    #[serde(default)]
+    pub whisper_translate: bool, // Use Whisper's /translations endpoint (always outputs English)
     #[serde(default)]
     pub live_mode: bool, // "Chế độ hội thoại"
+    #[serde(default)]
+    pub use_gemini_live: bool, // Stream live audio over the Gemini Live websocket instead of chunked uploads
     #[serde(default = "default_skip_frames")]
     pub skip_frames: bool, // "Nhảy cóc" - skip old frames in queue
     #[serde(default = "default_capture_interval")]
     pub capture_interval_ms: u64, // Capture interval in milliseconds for Live Mode
+    #[serde(default)]
+    pub live_follow_window: Option<String>, // Live Vision region moves with this window (title part or class; "" = the one under the region)
+    #[serde(default = "default_live_chunk_secs")]
+    pub live_chunk_secs: f32, // Length of each live audio chunk sent for transcription
+    #[serde(default)]
+    pub live_chunk_overlap_ms: u32, // Tail of the previous chunk repeated at the start of the next one
+    #[serde(default)]
+    pub show_live_stats: bool, // Latency HUD at the bottom of the live result window
+    #[serde(default = "default_dedup_history")]
+    pub dedup_history: usize, // Live Vision: recent lines a new frame's text is checked against before it's shown/retranslated
+    #[serde(default)]
+    pub dismiss_on_outside_click: bool, // Close the result windows on a click anywhere else (one-shot results, not live sessions)
+    #[serde(default)]
+    pub rerun_hotkey: Option<Hotkey>, // Runs this preset on the last captured region, without selecting it again
+    #[serde(default)]
+    pub language_cycle: Vec<String>, // Target languages `cycle_language_hotkey` steps `{language1}` through, in order
+    #[serde(default)]
+    pub cycle_language_hotkey: Option<Hotkey>, // Switches `{language1}` to the next `language_cycle` entry
+    #[serde(default)]
+    pub target_monitor: Option<String>, // GDI device name the region selection is limited to (None = all monitors)
 
     // --- Video Fields ---
     #[serde(default)]
-    pub video_capture_method: String, // "region" or "monitor:DeviceName"
+    pub video_capture_method: String, // "region" or "monitor:DeviceName" (video and image presets)
 
     #[serde(default)]
     pub is_upcoming: bool,
@@ -53,14 +137,44 @@ pub struct Preset {
     // --- AI Chat Fields ---
     #[serde(default)]
     pub enable_chat_mode: bool, // Allow asking follow-up questions
+    #[serde(default = "default_true")]
+    pub keep_chat_image: bool, // Re-send the (downscaled) capture with follow-up questions
     #[serde(default)]
     pub show_quick_actions: bool, // Show action menu after selection
+
+    #[serde(default = "default_temperature")]
+    pub temperature: f32, // Vision request sampling (Advanced)
+    #[serde(default = "default_max_tokens")]
+    pub max_tokens: u32, // Answer length cap; full-page OCR may need more
+    #[serde(default)]
+    pub capture_mode: String, // "" = screen region, "replace_selection" = translate the selected text in place
+    #[serde(default)]
+    pub per_app_language_memory: bool, // Default `retranslate_to` to the language last used in the foreground app
+    #[serde(default)]
+    pub include_context: bool, // Tell the model the title of the window the text was captured in
 }
 
 fn default_preset_type() -> String { "image".to_string() }
 fn default_audio_source() -> String { "mic".to_string() }
 fn default_skip_frames() -> bool { true } // Enabled by default for faster response
 fn default_capture_interval() -> u64 { 200 } // 200ms default capture interval
+fn default_dedup_history() -> usize { 2 } // Same as the 2-line subtitle buffer
+fn default_optimize_audio_upload() -> bool { true }
+fn default_max_recording_secs() -> u32 { 600 }
+fn default_true() -> bool { true }
+fn default_live_chunk_secs() -> f32 { 2.0 }
+fn default_temperature() -> f32 { crate::api::DEFAULT_TEMPERATURE }
+fn default_max_tokens() -> u32 { crate::api::DEFAULT_MAX_TOKENS }
+fn default_max_history_entries() -> usize { crate::history::DEFAULT_MAX_HISTORY_ENTRIES }
+fn default_translation_cache_size() -> usize { crate::translation_cache::DEFAULT_CACHE_SIZE }
+fn default_log_max_size_mb() -> u32 { crate::app_log::DEFAULT_MAX_SIZE_MB }
+fn default_last_capture_keep_secs() -> u32 { 300 }
+fn default_min_region_size() -> u32 { 12 }
+fn default_hotkey_debounce_ms() -> u32 { 250 }
+fn default_retranslate_secondary_model() -> String { "fast_text".to_string() }
+fn default_auto_copy_delay_ms() -> u32 { 100 }
+fn default_type_out_delay_ms() -> u32 { 10 }
+fn default_quick_translate_to() -> String { "English".to_string() }
 
 impl Default for Preset {
     fn default() -> Self {
@@ -90,6 +204,154 @@ impl Default for Preset {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
+        }
+    }
+}
+
+impl Preset {
+    /// GDI device name of the monitor an image preset captures whole, skipping the
+    /// region selection
+    pub fn capture_monitor(&self) -> Option<&str> {
+        if self.preset_type != "image" || !self.capture_mode.is_empty() {
+            return None;
+        }
+        self.video_capture_method.strip_prefix("monitor:").filter(|name| !name.is_empty())
+    }
+
+    /// `prompt` with its `{languageN}` tags (and the old `{language}` tag) filled in.
+    /// Tags without a `language_vars` entry are left as-is.
+    pub fn render_prompt(&self) -> String {
+        let mut prompt = self.prompt.clone();
+        for (key, value) in &self.language_vars {
+            let pattern = format!("{{{}}}", key); // e.g., "{language1}"
+            prompt = prompt.replace(&pattern, value);
+        }
+        prompt.replace("{language}", &self.selected_language)
+    }
+
+    /// Language the retranslation goes into: the UI language's when
+    /// `retranslate_to_ui_language` is set, `retranslate_to` otherwise. `SAME_AS_DETECTED`
+    /// only resolves once a capture is answered, so callers without a detected language get the UI language.
+    pub fn retranslate_target(&self, ui_language: &str) -> String {
+        if self.retranslate_to_ui_language || self.retranslates_to_detected() {
+            ui_language_to_target(ui_language)
+        } else {
+            self.retranslate_to.clone()
+        }
+    }
+
+    /// Whether `retranslate_to` is `SAME_AS_DETECTED`
+    pub fn retranslates_to_detected(&self) -> bool {
+        !self.retranslate_to_ui_language && self.retranslate_to == SAME_AS_DETECTED
+    }
+}
+
+/// `Preset.retranslate_to` value that retranslates into the language the model detected in the
+/// image (a back-translation), skipped when that already is the language of the answer
+pub const SAME_AS_DETECTED: &str = "same_as_detected";
+
+/// Language name (as in `get_all_languages`) for a `Config.ui_language` code
+pub fn ui_language_to_target(code: &str) -> String {
+    match code {
+        "vi" => "Vietnamese",
+        "ko" => "Korean",
+        _ => "English",
+    }.to_string()
+}
+
+/// `{languageN}` tags in `prompt` that `language_vars` has no language for, in order of appearance
+pub fn unmapped_language_tags(prompt: &str, language_vars: &HashMap<String, String>) -> Vec<String> {
+    let mut missing: Vec<String> = Vec::new();
+    let mut rest = prompt;
+    while let Some(start) = rest.find("{language") {
+        let after = &rest[start + "{language".len()..];
+        let digits = after.chars().take_while(|c| c.is_ascii_digit()).count();
+        if digits > 0 && after[digits..].starts_with('}') {
+            let key = format!("language{}", &after[..digits]);
+            if !language_vars.contains_key(&key) && !missing.contains(&key) {
+                missing.push(key);
+            }
+        }
+        rest = after;
+    }
+    missing
+}
+
+/// Optional Gemini `generationConfig` overrides for one model (Advanced section of
+/// the preset editor). Unset fields keep the API's defaults.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct GenerationParams {
+    #[serde(default)]
+    pub temperature: Option<f32>,
+    #[serde(default)]
+    pub max_output_tokens: Option<u32>,
+    #[serde(default)]
+    pub thinking_budget: Option<i32>, // -1 = dynamic, 0 = off (where the model allows it)
+}
+
+impl GenerationParams {
+    /// Write the overrides into a `generationConfig` object. The thinking budget is
+    /// left out for models without thinking and clamped to the model's range otherwise.
+    pub fn apply_to(&self, model: &str, config: &mut serde_json::Map<String, serde_json::Value>) {
+        if let Some(temperature) = self.temperature {
+            // f32 -> JSON would send 0.699999988 for 0.7
+            let rounded = (temperature as f64 * 100.0).round() / 100.0;
+            config.insert("temperature".to_string(), serde_json::json!(rounded));
+        }
+        if let Some(max_tokens) = self.max_output_tokens {
+            config.insert("maxOutputTokens".to_string(), serde_json::json!(max_tokens));
+        }
+        if let (Some(budget), Some((min, max))) = (self.thinking_budget, crate::model_config::thinking_budget_range(model)) {
+            let budget = if budget < 0 { -1 } else { budget.clamp(min, max) };
+            config.insert("thinkingConfig".to_string(), serde_json::json!({ "thinkingBudget": budget }));
         }
     }
 }
@@ -115,6 +377,8 @@ pub struct LiveCaptionsConfig {
     pub auto_hide_live_captions: bool,
     #[serde(default = "default_lc_audio_source")]
     pub audio_source: AudioSource,
+    #[serde(default)]
+    pub hotkey: Option<Hotkey>, // Global toggle for the overlay
 }
 
 impl Default for LiveCaptionsConfig {
@@ -127,10 +391,182 @@ impl Default for LiveCaptionsConfig {
             show_original: true,
             auto_hide_live_captions: true,
             audio_source: AudioSource::Microphone,
+            hotkey: None,
+        }
+    }
+}
+
+// --- Selection Overlay Appearance ---
+
+/// How the region selection overlay looks; defaults match the original fixed look
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct OverlayAppearance {
+    pub dim_alpha: u8,          // Opacity of the darkening layer over the desktop
+    pub border_color: [u8; 3],  // RGB of the selection border while dragging
+    pub border_width: u8,       // Px
+    pub show_grid: bool,        // Rule-of-thirds lines inside the selection
+    pub crosshair: bool,        // Full-screen guide lines through the cursor
+}
+
+impl Default for OverlayAppearance {
+    fn default() -> Self {
+        Self {
+            dim_alpha: 120,
+            border_color: [0xAA, 0xAA, 0xAA],
+            border_width: 2,
+            show_grid: false,
+            crosshair: false,
         }
     }
 }
 
+impl OverlayAppearance {
+    /// Darkest the dim layer can be set, in percent
+    pub const MAX_DIM_PERCENT: u8 = 80;
+    pub const MAX_BORDER_WIDTH: u8 = 8;
+
+    /// Dim level as the settings show it: 0-80%
+    pub fn dim_percent(&self) -> u8 {
+        ((self.dim_alpha as u32 * 100 + 127) / 255).min(Self::MAX_DIM_PERCENT as u32) as u8
+    }
+
+    pub fn set_dim_percent(&mut self, percent: u8) {
+        self.dim_alpha = ((percent.min(Self::MAX_DIM_PERCENT) as u32 * 255 + 50) / 100) as u8;
+    }
+
+    /// Alpha of the overlay's dark backdrop: the dim level, capped at 80%. Never 0,
+    /// which would let clicks fall through to the desktop. The border stays opaque.
+    pub fn overlay_alpha(&self) -> u8 {
+        let max = (Self::MAX_DIM_PERCENT as u32 * 255 / 100) as u8;
+        self.dim_alpha.clamp(1, max)
+    }
+
+    /// Border color as 0x00RRGGBB, the pixel format of the overlay's paint buffers
+    pub fn border_rgb(&self) -> u32 {
+        let [r, g, b] = self.border_color;
+        ((r as u32) << 16) | ((g as u32) << 8) | b as u32
+    }
+}
+
+/// Corner of the primary monitor's work area a small overlay sits in
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreenCorner {
+    TopLeft,
+    #[default]
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl ScreenCorner {
+    pub const ALL: [ScreenCorner; 4] = [ScreenCorner::TopLeft, ScreenCorner::TopRight, ScreenCorner::BottomLeft, ScreenCorner::BottomRight];
+}
+
+/// Order a vision preset reads comic panels and speech bubbles in
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadingOrder {
+    #[default]
+    Auto, // Whatever the model does; no instruction added
+    LeftToRight,
+    RightToLeft, // Manga
+}
+
+impl ReadingOrder {
+    pub const ALL: [ReadingOrder; 3] = [ReadingOrder::Auto, ReadingOrder::LeftToRight, ReadingOrder::RightToLeft];
+}
+
+/// How a preset's auto copy delivers the finished result
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum OutputMode {
+    #[default]
+    Clipboard,
+    TypeOut, // Simulated keystrokes into the window focused at the hotkey (apps/games that block paste)
+}
+
+impl OutputMode {
+    pub const ALL: [OutputMode; 2] = [OutputMode::Clipboard, OutputMode::TypeOut];
+}
+
+/// How copied text is wrapped, for pasting into chat apps (Discord, Slack)
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CopyFormat {
+    #[default]
+    Plain,
+    CodeBlock, // ``` fences
+    Quote, // "> " before every line
+    SourceHeader, // "[Preset] date time" line above the text
+}
+
+impl CopyFormat {
+    pub const ALL: [CopyFormat; 4] = [CopyFormat::Plain, CopyFormat::CodeBlock, CopyFormat::Quote, CopyFormat::SourceHeader];
+}
+
+/// How captures are encoded for vision requests
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageUploadFormat {
+    #[default]
+    Png,
+    WebP, // Lossless; usually smaller than PNG for screenshots. Providers without it get PNG
+}
+
+impl ImageUploadFormat {
+    pub const ALL: [ImageUploadFormat; 2] = [ImageUploadFormat::Png, ImageUploadFormat::WebP];
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ImageUploadFormat::Png => "image/png",
+            ImageUploadFormat::WebP => "image/webp",
+        }
+    }
+}
+
+/// Most detailed messages written to `app.log`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    #[default]
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    pub fn filter(self) -> log::LevelFilter {
+        match self {
+            LogLevel::Info => log::LevelFilter::Info,
+            LogLevel::Debug => log::LevelFilter::Debug,
+        }
+    }
+}
+
+/// `retranslate_to` remembered per foreground app, for one preset (`per_app_language_memory`)
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct AppLanguageMemory {
+    #[serde(default)]
+    pub apps: HashMap<String, String>, // Process name ("notepad.exe") -> language used there
+    #[serde(default)]
+    pub last_setting: String, // The preset's `retranslate_to` at its last run
+}
+
+impl AppLanguageMemory {
+    /// Language for a run in `app` while the preset is set to `setting`. A setting that
+    /// changed since the last run is the user's pick for this app; otherwise an app
+    /// seen before gets its remembered language and a new app starts from the setting.
+    pub fn resolve(&mut self, app: &str, setting: &str) -> String {
+        let changed = self.last_setting != setting;
+        self.last_setting = setting.to_string();
+        if changed || !self.apps.contains_key(app) {
+            self.apps.insert(app.to_string(), setting.to_string());
+        }
+        self.apps[app].clone()
+    }
+}
+
 // --- Quick Actions Configuration ---
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -144,6 +580,51 @@ pub struct QuickAction {
     pub model: String,     // Model to use (empty = use preset's model)
 }
 
+/// Translating image files from disk (`image_batch`)
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BatchConfig {
+    pub concurrency: usize, // Images sent at once
+    pub output_dir: String, // "" = a "translated" folder next to the images
+    pub per_image_files: bool, // <image>.txt beside each result
+    pub combined_file: bool, // batch_<time>.txt with every result in input order
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            concurrency: 2,
+            output_dir: String::new(),
+            per_image_files: true,
+            combined_file: true,
+        }
+    }
+}
+
+/// HTTP POST of every successful run of a preset (`webhook`); off while `url` is empty
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct WebhookConfig {
+    pub url: String,
+    pub template: String, // JSON body with {result}, {retranslation}, {preset}, {timestamp}
+    pub headers: Vec<WebhookHeader>, // e.g. Authorization: Bearer …
+}
+
+impl Default for WebhookConfig {
+    fn default() -> Self {
+        Self {
+            url: String::new(),
+            template: crate::webhook::DEFAULT_TEMPLATE.to_string(),
+            headers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct WebhookHeader {
+    pub name: String,
+    pub value: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct QuickActionsConfig {
     pub enabled: bool,
@@ -206,6 +687,70 @@ pub struct Config {
     pub live_captions: LiveCaptionsConfig,
     #[serde(default)]
     pub quick_actions: QuickActionsConfig,
+    #[serde(default)]
+    pub onboarding_done: bool, // First-run setup finished or skipped
+    #[serde(default = "default_max_history_entries")]
+    pub max_history_entries: usize, // Oldest non-favorites are pruned beyond this (0 = unlimited)
+    #[serde(default)]
+    pub debug_metrics: bool, // Log time-to-first-chunk / throughput of API calls
+    #[serde(default = "default_translation_cache_size")]
+    pub translation_cache_size: usize, // Cached API outputs kept (0 = no cache)
+    #[serde(default)]
+    pub translation_cache_persist: bool, // Keep the cache on disk between runs
+    #[serde(default)]
+    pub toggle_results_hotkey: Option<Hotkey>, // Hides/restores every open result window
+    #[serde(default)]
+    pub preset_picker_hotkey: Option<Hotkey>, // Asks which region preset to use, then opens the selection
+    #[serde(default)]
+    pub stop_all_hotkey: Option<Hotkey>, // Stops every recording and live session, whichever preset started it
+    #[serde(default)]
+    pub recent_copies_hotkey: Option<Hotkey>, // Lists the last copied results to copy one again
+    #[serde(default)]
+    pub replace_selection_confirmed: bool, // User accepted the simulated copy/paste of "replace selection" presets
+    #[serde(default)]
+    pub overlay_appearance: OverlayAppearance,
+    #[serde(default)]
+    pub blocked_apps: Vec<String>, // Process names never captured (e.g. "KeePassXC.exe")
+    #[serde(default)]
+    pub model_params: HashMap<String, GenerationParams>, // Keyed by the model's full API name
+    #[serde(default = "default_true")]
+    pub copy_button_markdown: bool, // Chat results: copy button copies Markdown, right-click plain text (false = swapped)
+    #[serde(default)]
+    pub preset_indicator: bool, // Always-on-top label with the active preset's name
+    #[serde(default)]
+    pub preset_indicator_corner: ScreenCorner,
+    #[serde(default)]
+    pub app_language_memory: HashMap<String, AppLanguageMemory>, // Keyed by preset id
+    #[serde(default = "default_true")]
+    pub overlay_animations: bool, // Broom cursor and smash/fade on result windows (also off when Windows animations are)
+    #[serde(default = "default_true")]
+    pub show_model_badge: bool, // "model · elapsed" corner badge on finished image/chat results
+    #[serde(default = "default_quick_translate_to")]
+    pub quick_translate_to: String, // Target of the globe button on result windows
+    #[serde(default)]
+    pub batch: BatchConfig,
+    #[serde(default = "default_true")]
+    pub show_crash_dialog: bool, // Message box on a crash (release builds; always logged)
+    #[serde(default)]
+    pub image_upload_format: ImageUploadFormat, // Encoding of captures sent to vision models
+    #[serde(default = "default_true")]
+    pub upscale_small_crops: bool, // Send small crops (tiny text) at 2x, as PNG
+    #[serde(default)]
+    pub log_level: LogLevel,
+    #[serde(default = "default_log_max_size_mb")]
+    pub log_max_size_mb: u32, // app.log is moved to app.log.1 beyond this
+    #[serde(default = "default_true")]
+    pub check_updates: bool, // Look for a newer release on startup (at most daily)
+    #[serde(default)]
+    pub last_update_check: u64, // Unix seconds
+    #[serde(default)]
+    pub latest_release: Option<crate::update_check::UpdateInfo>, // Found by the last check; the banner shows it on every startup until this build catches up
+    #[serde(default = "default_last_capture_keep_secs")]
+    pub last_capture_keep_secs: u32, // How long the last capture is kept for re-run hotkeys (0 = not kept)
+    #[serde(default = "default_min_region_size")]
+    pub min_region_size: u32, // Selections narrower or shorter than this (px) are treated as accidental
+    #[serde(default = "default_hotkey_debounce_ms")]
+    pub hotkey_debounce_ms: u32, // A hotkey firing again this soon is a key repeat / macro double-fire (0 = off)
 }
 
     impl Default for Config {
@@ -242,6 +787,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 1.5. Translate+Retranslate Preset
@@ -274,6 +867,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 2. OCR Preset
@@ -303,6 +944,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 2.5. Extract text+Retranslate Preset
@@ -332,6 +1021,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 3. Summarize Preset
@@ -364,6 +1101,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 4. Description Preset
@@ -396,6 +1181,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 5. Transcribe (Audio)
@@ -425,6 +1258,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 6. Study language Preset
@@ -454,6 +1335,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 7. Quick foreigner reply
@@ -483,6 +1412,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 8. Quicker foreigner reply Preset (new 4th audio preset with gemini-audio)
@@ -515,6 +1492,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 9. Ask AI (Chat) Preset - NEW
@@ -547,6 +1572,54 @@ pub struct Config {
             is_upcoming: false,
             enable_chat_mode: true, // Enable chat mode for follow-up questions
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 10. Video Summarize Placeholder
@@ -576,6 +1649,54 @@ pub struct Config {
             capture_interval_ms: 200,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         // 11. Screenshot Preset
@@ -605,6 +1726,54 @@ pub struct Config {
             capture_interval_ms: 200,
             enable_chat_mode: false,
             show_quick_actions: false,
+            retranslate_secondary_to: None,
+            retranslate_secondary_model: "fast_text".to_string(),
+            early_retranslate: false,
+            retranslate_srt: false,
+            preserve_layout: false,
+            optimize_audio_upload: true,
+            ocr_quality_check: true,
+            multi_frame_capture: None,
+            max_recording_secs: 600,
+            whisper_language: None,
+            whisper_translate: false,
+            use_gemini_live: false,
+            show_live_stats: false,
+            capture_mode: String::new(),
+            live_chunk_secs: 2.0,
+            live_chunk_overlap_ms: 0,
+            keep_chat_image: true,
+            temperature: 0.1,
+            max_tokens: 1024,
+            per_app_language_memory: false,
+            include_context: false,
+            dedup_history: 2,
+            dismiss_on_outside_click: false,
+            rerun_hotkey: None,
+            target_monitor: None,
+            reading_order: ReadingOrder::Auto,
+            compare_model: None,
+            auto_copy_delay_ms: 100,
+            copy_on_close: false,
+            output_mode: OutputMode::Clipboard,
+            type_out_delay_ms: 10,
+            output_file: None,
+            webhook: WebhookConfig::default(),
+            strip_preamble: false,
+            copy_format: CopyFormat::Plain,
+            live_follow_window: None,
+            completion_sound: false,
+            completion_toast: false,
+            capture_on_release: false,
+            loopback_device: None,
+            font_family: None,
+            retranslate_to_ui_language: false,
+            reflow_paragraphs: false,
+            reflow_dehyphenate: true,
+            study_layout: false,
+            expand_on_empty: None,
+            language_cycle: Vec::new(),
+            cycle_language_hotkey: None,
         };
 
         Self {
@@ -621,16 +1790,58 @@ pub struct Config {
             ui_language: "vi".to_string(),
             live_captions: LiveCaptionsConfig::default(),
             quick_actions: QuickActionsConfig::default(),
+            onboarding_done: false,
+            max_history_entries: default_max_history_entries(),
+            debug_metrics: false,
+            translation_cache_size: default_translation_cache_size(),
+            translation_cache_persist: false,
+            toggle_results_hotkey: None,
+            preset_picker_hotkey: None,
+            stop_all_hotkey: None,
+            recent_copies_hotkey: None,
+            replace_selection_confirmed: false,
+            overlay_appearance: OverlayAppearance::default(),
+            blocked_apps: Vec::new(),
+            model_params: HashMap::new(),
+            copy_button_markdown: true,
+            preset_indicator: false,
+            preset_indicator_corner: ScreenCorner::default(),
+            app_language_memory: HashMap::new(),
+            overlay_animations: true,
+            show_model_badge: true,
+            quick_translate_to: default_quick_translate_to(),
+            batch: BatchConfig::default(),
+            show_crash_dialog: true,
+            image_upload_format: ImageUploadFormat::default(),
+            upscale_small_crops: true,
+            log_level: LogLevel::default(),
+            log_max_size_mb: default_log_max_size_mb(),
+            check_updates: true,
+            last_update_check: 0,
+            latest_release: None,
+            last_capture_keep_secs: default_last_capture_keep_secs(),
+            min_region_size: default_min_region_size(),
+            hotkey_debounce_ms: default_hotkey_debounce_ms(),
         }
     }
 }
 
-pub fn get_config_path() -> PathBuf {
+/// %APPDATA%/xt-screen-translator: config, log, history and cache
+pub fn get_app_dir() -> PathBuf {
     let config_dir = dirs::config_dir()
         .unwrap_or_default()
         .join("xt-screen-translator");
     let _ = std::fs::create_dir_all(&config_dir);
-    config_dir.join("config_v2.json") // Changed filename to avoid conflict/migration issues for now
+    config_dir
+}
+
+pub fn get_config_path() -> PathBuf {
+    get_app_dir().join("config_v2.json") // Changed filename to avoid conflict/migration issues for now
+}
+
+/// Appended to across runs; the previous file is `app.log.1` (see `app_log`)
+pub fn get_log_path() -> PathBuf {
+    get_app_dir().join("app.log")
 }
 
 pub fn load_config() -> Config {
@@ -667,3 +1878,171 @@ lazy_static::lazy_static! {
 pub fn get_all_languages() -> &'static Vec<String> {
     &ALL_LANGUAGES
 }
+
+/// Languages Whisper accepts as a `language` hint, keyed by the same English names
+/// `get_all_languages` produces (isolang reference names)
+pub const WHISPER_LANGUAGES: &[(&str, &str)] = &[
+    ("Afrikaans", "af"), ("Albanian", "sq"), ("Amharic", "am"), ("Arabic", "ar"), ("Armenian", "hy"),
+    ("Assamese", "as"), ("Azerbaijani", "az"), ("Bashkir", "ba"), ("Basque", "eu"), ("Belarusian", "be"),
+    ("Bengali", "bn"), ("Bosnian", "bs"), ("Breton", "br"), ("Bulgarian", "bg"), ("Burmese", "my"),
+    ("Catalan", "ca"), ("Chinese", "zh"), ("Croatian", "hr"), ("Czech", "cs"), ("Danish", "da"),
+    ("Dutch", "nl"), ("English", "en"), ("Estonian", "et"), ("Faroese", "fo"), ("Finnish", "fi"),
+    ("French", "fr"), ("Galician", "gl"), ("Georgian", "ka"), ("German", "de"), ("Gujarati", "gu"),
+    ("Haitian", "ht"), ("Hausa", "ha"), ("Hebrew", "he"), ("Hindi", "hi"), ("Hungarian", "hu"),
+    ("Icelandic", "is"), ("Indonesian", "id"), ("Italian", "it"), ("Japanese", "ja"), ("Javanese", "jv"),
+    ("Kannada", "kn"), ("Kazakh", "kk"), ("Khmer", "km"), ("Korean", "ko"), ("Lao", "lo"),
+    ("Latin", "la"), ("Latvian", "lv"), ("Lingala", "ln"), ("Lithuanian", "lt"), ("Luxembourgish", "lb"),
+    ("Macedonian", "mk"), ("Malagasy", "mg"), ("Malay", "ms"), ("Malayalam", "ml"), ("Maltese", "mt"),
+    ("Maori", "mi"), ("Marathi", "mr"), ("Modern Greek", "el"), ("Mongolian", "mn"), ("Nepali", "ne"),
+    ("Norwegian", "no"), ("Norwegian Nynorsk", "nn"), ("Occitan", "oc"), ("Panjabi", "pa"), ("Persian", "fa"),
+    ("Polish", "pl"), ("Portuguese", "pt"), ("Pushto", "ps"), ("Romanian", "ro"), ("Russian", "ru"),
+    ("Sanskrit", "sa"), ("Serbian", "sr"), ("Shona", "sn"), ("Sindhi", "sd"), ("Sinhala", "si"),
+    ("Slovak", "sk"), ("Slovenian", "sl"), ("Somali", "so"), ("Spanish", "es"), ("Sundanese", "su"),
+    ("Swahili", "sw"), ("Swedish", "sv"), ("Tagalog", "tl"), ("Tajik", "tg"), ("Tamil", "ta"),
+    ("Tatar", "tt"), ("Telugu", "te"), ("Thai", "th"), ("Tibetan", "bo"), ("Turkish", "tr"),
+    ("Turkmen", "tk"), ("Ukrainian", "uk"), ("Urdu", "ur"), ("Uzbek", "uz"), ("Vietnamese", "vi"),
+    ("Welsh", "cy"), ("Yiddish", "yi"), ("Yoruba", "yo"),
+];
+
+/// ISO-639-1 code for a language name (case-insensitive). Codes pass through unchanged.
+pub fn whisper_language_code(name: &str) -> Option<&'static str> {
+    let name = name.trim();
+    WHISPER_LANGUAGES.iter()
+        .find(|(n, code)| n.eq_ignore_ascii_case(name) || code.eq_ignore_ascii_case(name))
+        .map(|(_, code)| *code)
+}
+
+/// Display name for an ISO-639-1 code from the Whisper table
+pub fn whisper_language_name(code: &str) -> Option<&'static str> {
+    WHISPER_LANGUAGES.iter().find(|(_, c)| c.eq_ignore_ascii_case(code)).map(|(n, _)| *n)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_whisper_language_code() {
+        assert_eq!(whisper_language_code("Vietnamese"), Some("vi"));
+        assert_eq!(whisper_language_code("english"), Some("en"));
+        assert_eq!(whisper_language_code("Modern Greek"), Some("el"));
+        assert_eq!(whisper_language_code("ko"), Some("ko"));
+        assert_eq!(whisper_language_code("Klingon"), None);
+        assert_eq!(whisper_language_name("vi"), Some("Vietnamese"));
+    }
+
+    #[test]
+    fn test_whisper_languages_match_app_names() {
+        let all = get_all_languages();
+        for (name, code) in WHISPER_LANGUAGES {
+            assert!(all.iter().any(|l| l == name), "{} is not an app language name", name);
+            assert_eq!(code.len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_render_prompt() {
+        let mut preset = Preset::default();
+        preset.prompt = "Translate {language1} to {language2}, not {language3}. Old: {language}".to_string();
+        preset.selected_language = "Korean".to_string();
+        preset.language_vars = HashMap::from([
+            ("language1".to_string(), "English".to_string()),
+            ("language2".to_string(), "Vietnamese".to_string()),
+        ]);
+        assert_eq!(preset.render_prompt(), "Translate English to Vietnamese, not {language3}. Old: Korean");
+        assert_eq!(unmapped_language_tags(&preset.prompt, &preset.language_vars), vec!["language3".to_string()]);
+        assert!(unmapped_language_tags("{language} {languageX} {language12", &HashMap::new()).is_empty());
+        assert_eq!(unmapped_language_tags("{language11} {language11}", &HashMap::new()), vec!["language11".to_string()]);
+    }
+
+    #[test]
+    fn test_capture_monitor() {
+        let mut preset = Preset::default();
+        assert_eq!(preset.capture_monitor(), None);
+        preset.video_capture_method = "monitor:\\\\.\\DISPLAY2".to_string();
+        assert_eq!(preset.capture_monitor(), Some("\\\\.\\DISPLAY2"));
+        // Replace-selection presets never capture the screen
+        preset.capture_mode = "replace_selection".to_string();
+        assert_eq!(preset.capture_monitor(), None);
+        preset.capture_mode.clear();
+        preset.preset_type = "audio".to_string();
+        assert_eq!(preset.capture_monitor(), None);
+    }
+
+    #[test]
+    fn test_app_language_memory() {
+        let mut memory = AppLanguageMemory::default();
+        // Japanese game, preset set to English
+        assert_eq!(memory.resolve("game.exe", "English"), "English");
+        // Switched the preset to Vietnamese for the browser
+        assert_eq!(memory.resolve("chrome.exe", "Vietnamese"), "Vietnamese");
+        // Back in the game without touching the setting: its own language
+        assert_eq!(memory.resolve("game.exe", "Vietnamese"), "English");
+        assert_eq!(memory.resolve("chrome.exe", "Vietnamese"), "Vietnamese");
+        // A new app starts from the setting
+        assert_eq!(memory.resolve("notepad.exe", "Vietnamese"), "Vietnamese");
+        // Changing the setting re-records the app it's used in next
+        assert_eq!(memory.resolve("game.exe", "Korean"), "Korean");
+        assert_eq!(memory.resolve("game.exe", "Korean"), "Korean");
+        assert_eq!(memory.apps.len(), 3);
+    }
+
+    #[test]
+    fn test_generation_params() {
+        let params = GenerationParams { temperature: Some(0.7), max_output_tokens: Some(512), thinking_budget: Some(0) };
+        let mut config = serde_json::Map::new();
+        params.apply_to("gemini-2.5-pro", &mut config);
+        assert_eq!(serde_json::Value::Object(config), serde_json::json!({
+            "temperature": 0.7,
+            "maxOutputTokens": 512,
+            "thinkingConfig": { "thinkingBudget": 128 }
+        }));
+
+        // No thinking on 2.0; unset fields keep what's there
+        let mut config = serde_json::Map::new();
+        config.insert("temperature".to_string(), serde_json::json!(0.1));
+        GenerationParams { thinking_budget: Some(-1), ..Default::default() }.apply_to("gemini-2.0-flash-exp", &mut config);
+        assert_eq!(serde_json::Value::Object(config), serde_json::json!({ "temperature": 0.1 }));
+    }
+
+    #[test]
+    fn test_retranslate_target() {
+        let mut preset = Preset { retranslate_to: "Japanese".to_string(), ..Default::default() };
+        assert_eq!(preset.retranslate_target("ko"), "Japanese");
+        preset.retranslate_to_ui_language = true;
+        assert_eq!(preset.retranslate_target("ko"), "Korean");
+        assert_eq!(preset.retranslate_target("vi"), "Vietnamese");
+        assert_eq!(preset.retranslate_target("en"), "English");
+        for code in ["en", "vi", "ko"] {
+            assert!(get_all_languages().contains(&ui_language_to_target(code)));
+        }
+    }
+
+    #[test]
+    fn test_overlay_appearance_defaults() {
+        let partial: OverlayAppearance = serde_json::from_str(r#"{"show_grid": true}"#).unwrap();
+        assert_eq!(partial.dim_alpha, 120);
+        assert!(partial.show_grid);
+        assert_eq!(OverlayAppearance::default().border_rgb(), 0x00AAAAAA);
+        assert_eq!(partial.border_width, 2);
+        assert!(!partial.crosshair);
+    }
+
+    #[test]
+    fn test_overlay_dim_percent() {
+        let mut appearance = OverlayAppearance::default();
+        assert_eq!(appearance.dim_percent(), 47);
+        appearance.set_dim_percent(0);
+        assert_eq!(appearance.overlay_alpha(), 1);
+        appearance.set_dim_percent(100);
+        assert_eq!(appearance.dim_percent(), 80);
+        assert_eq!(appearance.overlay_alpha(), 204);
+        // Configs saved before the 80% cap
+        appearance.dim_alpha = 240;
+        assert_eq!((appearance.dim_percent(), appearance.overlay_alpha()), (80, 204));
+        for percent in 0..=80 {
+            appearance.set_dim_percent(percent);
+            assert_eq!(appearance.dim_percent(), percent);
+        }
+    }
+}
@@ -136,6 +136,43 @@ pub fn update_image_context(image_base64: String) {
     }
 }
 
+// --- Image context ---
+
+/// Retained capture is capped at ~1 megapixel; bigger screenshots are downscaled
+/// before being kept (and re-sent) with every follow-up turn
+const MAX_CONTEXT_IMAGE_PIXELS: u64 = 1024 * 1024;
+
+/// Size the capture is stored at, keeping the aspect ratio
+fn context_image_size(width: u32, height: u32) -> (u32, u32) {
+    let pixels = width as u64 * height as u64;
+    if pixels <= MAX_CONTEXT_IMAGE_PIXELS {
+        return (width, height);
+    }
+    let scale = (MAX_CONTEXT_IMAGE_PIXELS as f64 / pixels as f64).sqrt();
+    (((width as f64 * scale) as u32).max(1), ((height as f64 * scale) as u32).max(1))
+}
+
+/// Base64 PNG of the capture for `Conversation::image_base64`, downscaled to the memory cap
+pub fn encode_context_image(image: &image::ImageBuffer<image::Rgba<u8>, Vec<u8>>) -> Option<String> {
+    use base64::{Engine as _, engine::general_purpose};
+
+    let (w, h) = context_image_size(image.width(), image.height());
+    let resized;
+    let image = if (w, h) != image.dimensions() {
+        resized = image::imageops::resize(image, w, h, image::imageops::FilterType::Triangle);
+        &resized
+    } else {
+        image
+    };
+
+    let mut png = Vec::new();
+    if let Err(e) = image.write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png) {
+        log::error!("Failed to encode chat image context: {}", e);
+        return None;
+    }
+    Some(general_purpose::STANDARD.encode(png))
+}
+
 // --- Helper functions ---
 
 fn get_timestamp() -> u64 {
@@ -188,4 +225,16 @@ mod tests {
         clear_conversation();
         assert!(!has_active_conversation());
     }
+
+    #[test]
+    fn test_context_image_size() {
+        assert_eq!(context_image_size(800, 600), (800, 600));
+        assert_eq!(context_image_size(1024, 1024), (1024, 1024));
+        // 4K capture: scaled to ~1MP, same aspect ratio
+        let (w, h) = context_image_size(3840, 2160);
+        assert!(w as u64 * h as u64 <= MAX_CONTEXT_IMAGE_PIXELS);
+        assert_eq!((w, h), (1365, 768));
+        // Very thin strips never collapse to zero
+        assert_eq!(context_image_size(100_000, 20).1, 14);
+    }
 }
@@ -0,0 +1,83 @@
+//! Crash Report Module
+//!
+//! The panic hook: every panic is logged, and the first one also gets a message box
+//! unless `Config.show_crash_dialog` is off (debug builds always show it). Panics on
+//! other threads after that are only logged, so a cascade can't stack up dialogs.
+//! The box offers to copy the details and the log location for a bug report.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::core::PCWSTR;
+
+static SHOW_DIALOG: AtomicBool = AtomicBool::new(true);
+static DIALOG_SHOWN: AtomicBool = AtomicBool::new(false);
+
+/// Apply `Config.show_crash_dialog`
+pub fn configure(show_dialog: bool) {
+    SHOW_DIALOG.store(show_dialog, Ordering::Relaxed);
+}
+
+/// Whether a panic gets a message box: the first one only, and release builds ask the config
+fn wants_dialog(enabled: bool, debug_build: bool, already_shown: &AtomicBool) -> bool {
+    (enabled || debug_build) && !already_shown.swap(true, Ordering::SeqCst)
+}
+
+/// Install the panic hook; called once from `main()` after logging starts
+pub fn install() {
+    std::panic::set_hook(Box::new(|panic_info| {
+        let location = if let Some(location) = panic_info.location() {
+            format!("File: {}\nLine: {}", location.file(), location.line())
+        } else {
+            "Unknown location".to_string()
+        };
+
+        let payload = if let Some(s) = panic_info.payload().downcast_ref::<&str>() {
+            s.to_string()
+        } else if let Some(s) = panic_info.payload().downcast_ref::<String>() {
+            s.clone()
+        } else {
+            "Unknown panic payload".to_string()
+        };
+
+        let error_msg = format!("CRASH DETECTED!\n\nError: {}\n\nLocation:\n{}", payload, location);
+        log::error!("{}", error_msg);
+
+        if !wants_dialog(SHOW_DIALOG.load(Ordering::Relaxed), cfg!(debug_assertions), &DIALOG_SHOWN) {
+            return;
+        }
+
+        let details = format!("{}\n\nLog: {}", error_msg, crate::config::get_log_path().display());
+        let prompt = format!("{}\n\nCopy these details to the clipboard?", details);
+        let wide_msg: Vec<u16> = prompt.encode_utf16().chain(std::iter::once(0)).collect();
+        let wide_title: Vec<u16> = "XT Screen Translator Crash Report".encode_utf16().chain(std::iter::once(0)).collect();
+
+        let choice = unsafe {
+            MessageBoxW(
+                None,
+                PCWSTR(wide_msg.as_ptr()),
+                PCWSTR(wide_title.as_ptr()),
+                MB_ICONERROR | MB_YESNO
+            )
+        };
+        if choice == IDYES {
+            crate::overlay::copy_to_clipboard(&details, windows::Win32::Foundation::HWND(0));
+        }
+    }));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wants_dialog() {
+        let shown = AtomicBool::new(false);
+        assert!(!wants_dialog(false, false, &shown));
+        assert!(wants_dialog(true, false, &shown));
+        // Later panics (other threads) are only logged
+        assert!(!wants_dialog(true, false, &shown));
+
+        // Debug builds show it with the option off
+        assert!(wants_dialog(false, true, &AtomicBool::new(false)));
+    }
+}
@@ -0,0 +1,75 @@
+//! Diagnostics Module
+//!
+//! "Copy diagnostics" in Global settings: app version, Windows version and the end of
+//! `app.log`, ready to paste into a bug report. API keys live in the config file, which
+//! is never included.
+
+use winreg::enums::HKEY_LOCAL_MACHINE;
+use winreg::RegKey;
+
+/// Lines of `app.log` included in the report
+const LOG_TAIL_LINES: usize = 200;
+
+/// Last `max_lines` lines of `log`
+fn log_tail(log: &str, max_lines: usize) -> &str {
+    match log.trim_end_matches('\n').rmatch_indices('\n').nth(max_lines.saturating_sub(1)) {
+        Some((i, _)) => &log[i + 1..],
+        None => log,
+    }
+}
+
+/// e.g. "Windows 10 Pro 23H2 (build 22631.3737)"; the registry still says "Windows 10" on 11
+fn os_version() -> String {
+    let key = match RegKey::predef(HKEY_LOCAL_MACHINE).open_subkey(r"SOFTWARE\Microsoft\Windows NT\CurrentVersion") {
+        Ok(key) => key,
+        Err(_) => return "Windows (unknown version)".to_string(),
+    };
+    let product: String = key.get_value("ProductName").unwrap_or_else(|_| "Windows".to_string());
+    let display: String = key.get_value("DisplayVersion").unwrap_or_default();
+    let build: String = key.get_value("CurrentBuild").unwrap_or_default();
+    let ubr: u32 = key.get_value("UBR").unwrap_or(0);
+    format!("{} {} (build {}.{})", product, display, build, ubr).replace("  ", " ")
+}
+
+/// "Result windows: 2 · GDI objects: 431", also shown next to the diagnostics buttons
+pub fn resource_summary() -> String {
+    format!("Result windows: {} · GDI objects: {}", crate::overlay::result::live_window_count(), crate::overlay::result::gdi_object_count())
+}
+
+fn format_report(version: &str, os: &str, resources: &str, log_path: &str, log: Option<&str>) -> String {
+    let log_section = match log {
+        Some(log) if !log.trim().is_empty() => log_tail(log, LOG_TAIL_LINES).trim_end().to_string(),
+        Some(_) => "(empty)".to_string(),
+        None => "(could not be read)".to_string(),
+    };
+    format!(
+        "XT Screen Translator {}\nOS: {}\n{}\nLog: {}\n\n--- last {} log lines ---\n{}\n",
+        version, os, resources, log_path, LOG_TAIL_LINES, log_section
+    )
+}
+
+/// The report copied by "Copy diagnostics"
+pub fn report() -> String {
+    let log_path = crate::config::get_log_path();
+    let log = crate::app_log::read_recent();
+    format_report(env!("CARGO_PKG_VERSION"), &os_version(), &resource_summary(), &log_path.to_string_lossy(), log.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report() {
+        let log = "one\ntwo\nthree\nfour\n";
+        assert_eq!(log_tail(log, 2), "three\nfour\n");
+        assert_eq!(log_tail(log, 4), log);
+        assert_eq!(log_tail(log, 10), log);
+        assert_eq!(log_tail("single", 1), "single");
+
+        let report = format_report("0.5.0", "Windows 11", "Result windows: 1", "C:\\app.log", Some(log));
+        assert!(report.starts_with("XT Screen Translator 0.5.0\nOS: Windows 11\nResult windows: 1\n"));
+        assert!(report.ends_with("one\ntwo\nthree\nfour\n"));
+        assert!(format_report("0.5.0", "Windows 11", "", "C:\\app.log", None).contains("(could not be read)"));
+    }
+}
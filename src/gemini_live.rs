@@ -1,8 +1,10 @@
-use tungstenite::{connect, Message};
+use tungstenite::{connect, Message, WebSocket};
 use tungstenite::stream::MaybeTlsStream;
-use url::Url;
-use std::sync::{Arc, Mutex};
+use std::net::TcpStream;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::thread;
+use std::time::Duration;
 use std::sync::mpsc::{Sender, Receiver, channel};
 use serde::Serialize;
 use serde_json::Value;
@@ -56,151 +58,76 @@ struct MediaChunk {
     data: String,
 }
 
+const LIVE_MODEL: &str = "models/gemini-2.0-flash-exp";
+const SETUP_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+
+type LiveSocket = WebSocket<MaybeTlsStream<TcpStream>>;
+type TextCallback = Box<dyn Fn(String) + Send + Sync>;
+
+/// Why a session loop returned
+enum SessionEnd {
+    Stopped,
+    Dropped(String),
+}
+
 pub struct GeminiLiveClient {
     audio_sender: Sender<Vec<u8>>,
-    stop_signal: Arc<Mutex<bool>>,
+    stop_signal: Arc<AtomicBool>,
     handle: Option<thread::JoinHandle<()>>,
 }
 
 impl GeminiLiveClient {
-    pub fn new(api_key: String, system_instruction_text: Option<String>, on_text_received: Box<dyn Fn(String) + Send + Sync>) -> Result<Self, String> {
+    /// Connects and waits for the server to accept the setup, so an invalid key or an
+    /// unsupported model is reported here (callers fall back to the chunked REST path).
+    /// After that, dropped connections are retried in the background with backoff.
+    pub fn new(api_key: String, system_instruction_text: Option<String>, on_text_received: TextCallback) -> Result<Self, String> {
         let (audio_sender, audio_receiver): (Sender<Vec<u8>>, Receiver<Vec<u8>>) = channel();
-        let stop_signal = Arc::new(Mutex::new(false));
+        let stop_signal = Arc::new(AtomicBool::new(false));
         let stop_clone = stop_signal.clone();
 
-        let handle = thread::spawn(move || {
-            let url_str = format!(
-                "wss://generativelanguage.googleapis.com/ws/google.ai.generativelanguage.v1alpha.GenerativeService.BidiGenerateContent?key={}",
-                api_key
-            );
-            
-            // Tungstenite connect takes a string or Uri, NOT a Url struct directly if not implemented.
-            // Converting to string is safest.
-            
-            log::info!("Connecting to Gemini Live API...");
-            
-            let (mut socket, _) = match connect(&url_str) {
-                Ok(s) => s,
-                Err(e) => {
-                    let err = format!("Failed to connect to Gemini Live: {}", e);
-                    log::error!("{}", err);
-                    on_text_received(format!("[ERROR] {}", err));
-                    return;
-                }
-            };
-            
-            log::info!("Connected to Gemini Live API");
-            on_text_received("[INFO] Connected to Gemini Live.".to_string());
-
-            // 1. Send Setup Message
-            // ... (setup msg creation) ...
-            let setup_msg = SetupMessage {
-                setup: SetupData {
-                    model: "models/gemini-2.0-flash-exp".to_string(),
-                    generation_config: GenerationConfig {
-                        response_modalities: vec!["TEXT".to_string()],
-                    },
-                    system_instruction: system_instruction_text.map(|text| SystemInstruction {
-                        parts: vec![Part { text }],
-                    }),
+        let url = format!(
+            "wss://generativelanguage.googleapis.com/ws/google.ai.generativelanguage.v1alpha.GenerativeService.BidiGenerateContent?key={}",
+            api_key
+        );
+        let setup_msg = SetupMessage {
+            setup: SetupData {
+                model: LIVE_MODEL.to_string(),
+                generation_config: GenerationConfig {
+                    response_modalities: vec!["TEXT".to_string()],
                 },
-            };
-            
-            let setup_json = serde_json::to_string(&setup_msg).unwrap();
-            // Message::Text takes Utf8Bytes in newer tungstenite, so use into()
-            if let Err(e) = socket.write_message(Message::Text(setup_json.into())) {
-                let err = format!("Failed to send setup message: {}", e);
-                log::error!("{}", err);
-                on_text_received(format!("[ERROR] {}", err));
-                return;
-            }
+                system_instruction: system_instruction_text.map(|text| SystemInstruction {
+                    parts: vec![Part { text }],
+                }),
+            },
+        };
+        let setup_json = serde_json::to_string(&setup_msg).map_err(|e| e.to_string())?;
 
-            // Set non-blocking based on stream type
-            // socket.get_mut() returns &mut Stream
-            match socket.get_mut() {
-                MaybeTlsStream::Plain(s) => {
-                    let _ = s.set_nonblocking(true);
-                },
-                MaybeTlsStream::Rustls(s) => {
-                     // Attempt to set non-blocking on underlying socket if possible
-                     // Simplest way is let implicit deref or method handle it if available
-                     // But RustlsStream wraps TcpStream. 
-                     // We can try:
-                     if let Err(e) = s.get_mut().set_nonblocking(true) {
-                         log::warn!("Failed to set non-blocking: {}", e);
-                     }
-                },
-                _ => {
-                    // Ignore other cases (e.g. NativeTls if enabled, but we used rustls)
-                    log::warn!("Unknown stream type, non-blocking might fail");
-                }
-            }
+        log::info!("Connecting to Gemini Live API...");
+        let socket = open_session(&url, &setup_json)?;
+        log::info!("Connected to Gemini Live API");
 
+        let handle = thread::spawn(move || {
+            let mut socket = socket;
             loop {
-                if *stop_clone.lock().unwrap() {
-                    break;
-                }
+                let reason = match run_session(&mut socket, &audio_receiver, &stop_clone, &on_text_received) {
+                    SessionEnd::Stopped => break,
+                    SessionEnd::Dropped(reason) => reason,
+                };
+                log::warn!("Gemini Live connection dropped: {}", reason);
+                let _ = socket.close(None);
 
-                // 2. Send Audio
-                while let Ok(data) = audio_receiver.try_recv() {
-                    // Use engine instead of deprecated encode
-                    use base64::{Engine as _, engine::general_purpose};
-                    let b64_data = general_purpose::STANDARD.encode(&data);
-                    
-                    let msg = RealtimeInputMessage {
-                        realtime_input: RealtimeInputData {
-                            media_chunks: vec![MediaChunk {
-                                mime_type: "audio/pcm; rate=16000".to_string(),
-                                data: b64_data,
-                            }],
-                        },
-                    };
-                    if let Ok(json) = serde_json::to_string(&msg) {
-                        if let Err(e) = socket.write_message(Message::Text(json.into())) {
-                             log::error!("Send error: {}", e);
-                             on_text_received(format!("[ERROR] Send error: {}", e));
-                             break;
-                        }
+                match reconnect(&url, &setup_json, &stop_clone) {
+                    Some(new_socket) => {
+                        socket = new_socket;
+                        // Audio captured while offline is stale; skip it to stay real-time
+                        while audio_receiver.try_recv().is_ok() {}
                     }
-                }
-
-                // 3. Read Messages (Non-blocking attempt)
-                match socket.read_message() {
-                    Ok(msg) => {
-                        if let Message::Text(text) = msg {
-                            // Text is Utf8Bytes, implements Display/Deref
-                            let text_str = text.to_string(); 
-                            // Parse JSON
-                            if let Ok(v) = serde_json::from_str::<Value>(&text_str) {
-                                // Extract text
-                                if let Some(parts) = v.get("serverContent")
-                                    .and_then(|sc| sc.get("modelTurn"))
-                                    .and_then(|mt| mt.get("parts"))
-                                    .and_then(|p| p.as_array()) 
-                                {
-                                    for part in parts {
-                                        if let Some(t) = part.get("text").and_then(|t| t.as_str()) {
-                                            on_text_received(t.to_string());
-                                        }
-                                    }
-                                }
-                            }
-                        } else if let Message::Close(_) = msg {
-                            on_text_received("[INFO] Connection closed server-side.".to_string());
-                            break;
+                    None => {
+                        if !stop_clone.load(Ordering::SeqCst) {
+                            on_text_received(format!("[ERROR] Gemini Live connection lost: {}", reason));
                         }
-                    },
-                    Err(tungstenite::Error::Io(ref e)) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                        // No message, sleep briefly
-                        thread::sleep(std::time::Duration::from_millis(10));
-                    },
-                    Err(e) => {
-                         // Only break on serious errors. ConnectionClosed is one.
-                         // But we might get other errors?
-                         // Log and break for now.
-                         log::error!("WebSocket error: {}", e);
-                         on_text_received(format!("[ERROR] WebSocket error: {}", e));
-                         break;
+                        return;
                     }
                 }
             }
@@ -221,9 +148,189 @@ impl GeminiLiveClient {
 
 impl Drop for GeminiLiveClient {
     fn drop(&mut self) {
-        *self.stop_signal.lock().unwrap() = true;
+        self.stop_signal.store(true, Ordering::SeqCst);
         if let Some(handle) = self.handle.take() {
             let _ = handle.join();
         }
     }
 }
+
+/// Delay before reconnect attempt `attempt` (0-based): 0.5s doubling, capped at 8s
+pub fn reconnect_delay(attempt: u32) -> Duration {
+    Duration::from_millis((500u64 << attempt.min(4)).min(8000))
+}
+
+/// Retry `open_session` with backoff. Returns None when stopped or out of attempts.
+fn reconnect(url: &str, setup_json: &str, stop: &AtomicBool) -> Option<LiveSocket> {
+    for attempt in 0..MAX_RECONNECT_ATTEMPTS {
+        // Sleep in small steps so stopping doesn't wait for the whole backoff
+        let deadline = std::time::Instant::now() + reconnect_delay(attempt);
+        while std::time::Instant::now() < deadline {
+            if stop.load(Ordering::SeqCst) {
+                return None;
+            }
+            thread::sleep(Duration::from_millis(50));
+        }
+
+        log::info!("Reconnecting to Gemini Live (attempt {}/{})", attempt + 1, MAX_RECONNECT_ATTEMPTS);
+        match open_session(url, setup_json) {
+            Ok(socket) => return Some(socket),
+            Err(e) => log::warn!("{}", e),
+        }
+    }
+    None
+}
+
+/// Connect, send the setup message and wait for `setupComplete`
+fn open_session(url: &str, setup_json: &str) -> Result<LiveSocket, String> {
+    let (mut socket, _) = connect(url).map_err(|e| format!("Failed to connect to Gemini Live: {}", e))?;
+
+    socket
+        .send(Message::Text(setup_json.into()))
+        .map_err(|e| format!("Failed to send setup message: {}", e))?;
+
+    if let Some(stream) = tcp_stream(&mut socket) {
+        let _ = stream.set_read_timeout(Some(SETUP_TIMEOUT));
+    }
+    loop {
+        match socket.read() {
+            Ok(Message::Close(frame)) => {
+                let reason = frame.map(|f| f.reason.to_string()).unwrap_or_default();
+                return Err(format!("Gemini Live rejected the session: {}", reason));
+            }
+            Ok(msg) => {
+                if let Some(v) = message_json(&msg) {
+                    if v.get("setupComplete").is_some() {
+                        break;
+                    }
+                    if let Some(err) = v.get("error") {
+                        return Err(format!("Gemini Live rejected the session: {}", err));
+                    }
+                }
+            }
+            Err(e) => return Err(format!("Gemini Live setup failed: {}", e)),
+        }
+    }
+
+    // From here on, poll the socket so audio can be sent between reads
+    if let Some(stream) = tcp_stream(&mut socket) {
+        let _ = stream.set_read_timeout(None);
+        if let Err(e) = stream.set_nonblocking(true) {
+            log::warn!("Failed to set non-blocking: {}", e);
+        }
+    }
+    Ok(socket)
+}
+
+fn tcp_stream(socket: &mut LiveSocket) -> Option<&mut TcpStream> {
+    match socket.get_mut() {
+        MaybeTlsStream::Plain(s) => Some(s),
+        MaybeTlsStream::Rustls(s) => Some(s.get_mut()),
+        _ => {
+            log::warn!("Unknown stream type, non-blocking might fail");
+            None
+        }
+    }
+}
+
+/// Pump audio out and text in until stopped or the connection drops
+fn run_session(socket: &mut LiveSocket, audio_receiver: &Receiver<Vec<u8>>, stop: &AtomicBool, on_text: &TextCallback) -> SessionEnd {
+    use base64::{Engine as _, engine::general_purpose};
+
+    loop {
+        if stop.load(Ordering::SeqCst) {
+            return SessionEnd::Stopped;
+        }
+
+        // 1. Send Audio
+        while let Ok(data) = audio_receiver.try_recv() {
+            let msg = RealtimeInputMessage {
+                realtime_input: RealtimeInputData {
+                    media_chunks: vec![MediaChunk {
+                        mime_type: "audio/pcm; rate=16000".to_string(),
+                        data: general_purpose::STANDARD.encode(&data),
+                    }],
+                },
+            };
+            if let Ok(json) = serde_json::to_string(&msg) {
+                match socket.send(Message::Text(json.into())) {
+                    Ok(()) => {}
+                    // Frame stays queued and is flushed on the next read
+                    Err(tungstenite::Error::Io(ref e)) if e.kind() == std::io::ErrorKind::WouldBlock => {}
+                    Err(e) => return SessionEnd::Dropped(format!("Send error: {}", e)),
+                }
+            }
+        }
+
+        // 2. Read Messages (non-blocking)
+        match socket.read() {
+            Ok(Message::Close(frame)) => {
+                let reason = frame.map(|f| f.reason.to_string()).unwrap_or_else(|| "closed by server".to_string());
+                return SessionEnd::Dropped(reason);
+            }
+            Ok(msg) => {
+                if let Some(v) = message_json(&msg) {
+                    for text in extract_texts(&v) {
+                        on_text(text);
+                    }
+                }
+            }
+            Err(tungstenite::Error::Io(ref e)) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                thread::sleep(Duration::from_millis(10));
+            }
+            Err(e) => return SessionEnd::Dropped(e.to_string()),
+        }
+    }
+}
+
+/// Server messages arrive as JSON in either text or binary frames
+fn message_json(msg: &Message) -> Option<Value> {
+    match msg {
+        Message::Text(text) => serde_json::from_str(text.as_str()).ok(),
+        Message::Binary(data) => serde_json::from_slice(&data[..]).ok(),
+        _ => None,
+    }
+}
+
+/// Text parts of a `serverContent` message. A finished turn adds a separating space.
+fn extract_texts(v: &Value) -> Vec<String> {
+    let mut texts = Vec::new();
+    if let Some(content) = v.get("serverContent") {
+        if let Some(parts) = content.get("modelTurn")
+            .and_then(|mt| mt.get("parts"))
+            .and_then(|p| p.as_array())
+        {
+            for part in parts {
+                if let Some(t) = part.get("text").and_then(|t| t.as_str()) {
+                    texts.push(t.to_string());
+                }
+            }
+        }
+        if content.get("turnComplete").and_then(|t| t.as_bool()) == Some(true) {
+            texts.push(" ".to_string());
+        }
+    }
+    texts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reconnect_delay_backoff() {
+        assert_eq!(reconnect_delay(0), Duration::from_millis(500));
+        assert_eq!(reconnect_delay(1), Duration::from_millis(1000));
+        assert_eq!(reconnect_delay(4), Duration::from_millis(8000));
+        assert_eq!(reconnect_delay(10), Duration::from_millis(8000));
+    }
+
+    #[test]
+    fn test_extract_texts() {
+        let v: Value = serde_json::from_str(
+            r#"{"serverContent":{"modelTurn":{"parts":[{"text":"Xin "},{"text":"chào"}]},"turnComplete":true}}"#
+        ).unwrap();
+        assert_eq!(extract_texts(&v), vec!["Xin ", "chào", " "]);
+        assert!(extract_texts(&serde_json::json!({"setupComplete": {}})).is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 use eframe::egui;
-use crate::config::{Config, save_config, get_all_languages, Preset, Hotkey};
+use crate::config::{Config, save_config, get_all_languages, unmapped_language_tags, Preset, Hotkey, WHISPER_LANGUAGES, whisper_language_name};
 use std::sync::{Arc, Mutex};
-use tray_icon::{TrayIcon, TrayIconEvent, MouseButton, menu::{Menu, MenuEvent}};
+use tray_icon::{TrayIcon, TrayIconEvent, MouseButton, menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem}};
 use auto_launch::AutoLaunch;
 use std::sync::mpsc::{Receiver, channel};
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -16,6 +16,7 @@ use crate::gui::locale::LocaleText;
 use crate::gui::key_mapping::egui_key_to_vk;
 use crate::gui::icons::{Icon, icon_button, draw_icon_static};
 use crate::model_config::{get_all_models, ModelType, get_model_by_id};
+use crate::overlay::replace_selection::CAPTURE_MODE as REPLACE_SELECTION_MODE;
 
 // Simple timestamp formatter (no chrono dependency)
 fn chrono_lite_format(timestamp: u64) -> String {
@@ -64,6 +65,120 @@ fn chrono_lite_format(timestamp: u64) -> String {
     format!("{:02}/{:02} {:02}:{:02}", day, month, hour, minute)
 }
 
+// --- Find-in-text Helpers (history detail) ---
+
+/// Byte ranges of every non-overlapping occurrence of `query` in `text`
+fn find_match_ranges(text: &str, query: &str, case_sensitive: bool) -> Vec<std::ops::Range<usize>> {
+    let mut ranges = Vec::new();
+    if query.is_empty() { return ranges; }
+
+    let chars_eq = |a: char, b: char| {
+        if case_sensitive { a == b } else { a == b || a.to_lowercase().eq(b.to_lowercase()) }
+    };
+
+    let mut start = 0;
+    while start < text.len() {
+        let mut hay = text[start..].char_indices();
+        let mut end = None;
+        let mut matched = true;
+        for q in query.chars() {
+            match hay.next() {
+                Some((i, c)) if chars_eq(c, q) => end = Some(start + i + c.len_utf8()),
+                _ => { matched = false; break; }
+            }
+        }
+        match end {
+            Some(end) if matched => {
+                ranges.push(start..end);
+                start = end;
+            }
+            _ => start += text[start..].chars().next().map_or(1, |c| c.len_utf8()),
+        }
+    }
+    ranges
+}
+
+/// Read-only text with `matches` highlighted (`current` in a stronger colour).
+/// Returns the on-screen rect of the current match so the caller can scroll to it.
+fn show_highlighted_text(ui: &mut egui::Ui, text: &str, matches: &[std::ops::Range<usize>], current: Option<usize>) -> Option<egui::Rect> {
+    use egui::text::{LayoutJob, TextFormat};
+
+    let font_id = egui::TextStyle::Body.resolve(ui.style());
+    let normal = TextFormat { font_id: font_id.clone(), color: ui.visuals().text_color(), ..Default::default() };
+    let hit = TextFormat {
+        font_id: font_id.clone(),
+        color: egui::Color32::BLACK,
+        background: egui::Color32::from_rgb(255, 220, 90),
+        ..Default::default()
+    };
+    let current_hit = TextFormat { background: egui::Color32::from_rgb(255, 140, 40), ..hit.clone() };
+
+    let mut job = LayoutJob::default();
+    job.wrap.max_width = ui.available_width();
+    let mut pos = 0;
+    for (i, range) in matches.iter().enumerate() {
+        job.append(&text[pos..range.start], 0.0, normal.clone());
+        let format = if Some(i) == current { current_hit.clone() } else { hit.clone() };
+        job.append(&text[range.clone()], 0.0, format);
+        pos = range.end;
+    }
+    job.append(&text[pos..], 0.0, normal);
+
+    let galley = ui.fonts(|f| f.layout_job(job));
+    let (rect, _) = ui.allocate_exact_size(galley.size(), egui::Sense::hover());
+
+    let current_rect = current.and_then(|i| matches.get(i)).map(|range| {
+        let ccursor = egui::text::CCursor::new(text[..range.start].chars().count());
+        galley.pos_from_ccursor(ccursor).translate(rect.min.to_vec2())
+    });
+    ui.painter().galley(rect.min, galley, ui.visuals().text_color());
+    current_rect
+}
+
+/// Mock desktop with the selection overlay drawn over it the way `appearance` would,
+/// so the settings can be tuned without opening the overlay. The crosshair follows
+/// the pointer while it is over the preview.
+fn show_overlay_preview(ui: &mut egui::Ui, appearance: &crate::config::OverlayAppearance) {
+    use egui::{pos2, vec2, Color32, Rect, Stroke};
+
+    let (rect, response) = ui.allocate_exact_size(vec2(240.0, 120.0), egui::Sense::hover());
+    let painter = ui.painter().with_clip_rect(rect);
+
+    // A page of "text"
+    painter.rect_filled(rect, 2.0, Color32::from_gray(245));
+    for i in 0..8 {
+        let y = rect.top() + 12.0 + i as f32 * 13.0;
+        let len = [0.9, 0.75, 0.85, 0.6, 0.9, 0.8, 0.7, 0.5][i];
+        let line = Rect::from_min_size(pos2(rect.left() + 12.0, y), vec2((rect.width() - 24.0) * len, 5.0));
+        painter.rect_filled(line, 1.0, Color32::from_gray(90));
+    }
+
+    // The backdrop is dimmed to the dim level; the border, grid and crosshair stay opaque
+    let alpha = appearance.overlay_alpha() as f32 / 255.0;
+    let dim = |color: Color32, a: f32| Color32::from_rgba_unmultiplied(color.r(), color.g(), color.b(), (a * alpha * 255.0) as u8);
+    let [r, g, b] = appearance.border_color;
+    let border = Color32::from_rgb(r, g, b);
+
+    let selection = Rect::from_min_size(rect.min + vec2(60.0, 35.0), vec2(110.0, 50.0));
+    painter.rect_filled(rect, 2.0, dim(Color32::BLACK, 1.0));
+    painter.rect_filled(selection, 6.0, dim(Color32::from_gray(0x11), 0.85));
+    let width = appearance.border_width.clamp(1, crate::config::OverlayAppearance::MAX_BORDER_WIDTH) as f32;
+    painter.rect_stroke(selection.expand(width / 2.0), 6.0, Stroke::new(width, border));
+    if appearance.show_grid {
+        for i in 1..3 {
+            let x = selection.left() + selection.width() * i as f32 / 3.0;
+            let y = selection.top() + selection.height() * i as f32 / 3.0;
+            painter.line_segment([pos2(x, selection.top()), pos2(x, selection.bottom())], Stroke::new(1.0, border));
+            painter.line_segment([pos2(selection.left(), y), pos2(selection.right(), y)], Stroke::new(1.0, border));
+        }
+    }
+    if appearance.crosshair {
+        let at = response.hover_pos().unwrap_or(selection.max);
+        painter.line_segment([pos2(at.x, rect.top()), pos2(at.x, rect.bottom())], Stroke::new(1.0, border));
+        painter.line_segment([pos2(rect.left(), at.y), pos2(rect.right(), at.y)], Stroke::new(1.0, border));
+    }
+}
+
 // --- Monitor Enumeration Helper ---
 struct MonitorEnumContext {
     monitors: Vec<String>,
@@ -82,6 +197,68 @@ unsafe extern "system" fn monitor_enum_proc(_hmonitor: HMONITOR, _hdc: HDC, _lpr
     BOOL(1)
 }
 
+fn corner_name(text: &LocaleText, corner: crate::config::ScreenCorner) -> &'static str {
+    use crate::config::ScreenCorner;
+    match corner {
+        ScreenCorner::TopLeft => text.corner_top_left,
+        ScreenCorner::TopRight => text.corner_top_right,
+        ScreenCorner::BottomLeft => text.corner_bottom_left,
+        ScreenCorner::BottomRight => text.corner_bottom_right,
+    }
+}
+
+fn output_mode_name(text: &LocaleText, mode: crate::config::OutputMode) -> &'static str {
+    use crate::config::OutputMode;
+    match mode {
+        OutputMode::Clipboard => text.output_mode_clipboard,
+        OutputMode::TypeOut => text.output_mode_type_out,
+    }
+}
+
+/// Also used by the result window's Shift+right-click copy menu
+pub fn copy_format_name(text: &LocaleText, format: crate::config::CopyFormat) -> &'static str {
+    use crate::config::CopyFormat;
+    match format {
+        CopyFormat::Plain => text.copy_format_plain,
+        CopyFormat::CodeBlock => text.copy_format_code_block,
+        CopyFormat::Quote => text.copy_format_quote,
+        CopyFormat::SourceHeader => text.copy_format_source_header,
+    }
+}
+
+fn reading_order_name(text: &LocaleText, order: crate::config::ReadingOrder) -> &'static str {
+    use crate::config::ReadingOrder;
+    match order {
+        ReadingOrder::Auto => text.reading_order_auto,
+        ReadingOrder::LeftToRight => text.reading_order_ltr,
+        ReadingOrder::RightToLeft => text.reading_order_rtl,
+    }
+}
+
+/// "Use the app's language" and "Remember per app" checkboxes for a preset's target
+/// language, plus a button to forget what was remembered; returns true when the preset changed
+fn app_language_memory_ui(ui: &mut egui::Ui, text: &LocaleText, preset: &mut Preset) -> bool {
+    let mut changed = ui.checkbox(&mut preset.retranslate_to_ui_language, text.retranslate_to_ui_language_label)
+        .on_hover_text(text.retranslate_to_ui_language_tooltip)
+        .changed();
+    // The remembered language would never be used
+    if preset.retranslate_to_ui_language {
+        return changed;
+    }
+    ui.horizontal(|ui| {
+        if ui.checkbox(&mut preset.per_app_language_memory, text.per_app_language_label)
+            .on_hover_text(text.per_app_language_tooltip)
+            .changed() {
+            changed = true;
+        }
+        let remembered = crate::overlay::app_language::remembered_count(&preset.id);
+        if remembered > 0 && ui.small_button(text.per_app_language_forget.replace("{}", &remembered.to_string())).clicked() {
+            crate::overlay::app_language::forget(&preset.id);
+        }
+    });
+    changed
+}
+
 fn get_monitor_names() -> Vec<String> {
     let mut ctx = MonitorEnumContext { monitors: Vec::new() };
     unsafe {
@@ -101,15 +278,74 @@ enum UserEvent {
     Menu(MenuEvent),
 }
 
+/// Image presets that can translate image files (not live or replace-selection ones)
+fn runs_batches(preset: &Preset) -> bool {
+    preset.preset_type == "image" && !preset.live_mode && preset.capture_mode.is_empty()
+}
+
 lazy_static::lazy_static! {
     static ref RESTORE_SIGNAL: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
 }
 
+/// Set with the restore event to open the settings on the API keys
+static SHOW_API_KEYS: AtomicBool = AtomicBool::new(false);
+
+/// Bring the settings window up on Global settings, where the API keys are
+/// (clicking an error result window)
+pub fn show_api_keys() {
+    SHOW_API_KEYS.store(true, Ordering::SeqCst);
+    if let Some(event) = crate::RESTORE_EVENT.as_ref() {
+        unsafe { let _ = SetEvent(*event); }
+    }
+}
+
 #[derive(PartialEq, Clone, Copy)]
 enum ViewMode {
     Global,
     Preset(usize),
     History,
+    Onboarding,
+}
+
+/// Result of the onboarding "Test" request
+#[derive(Clone, PartialEq)]
+enum KeyTestState {
+    Idle,
+    Running,
+    Ok,
+    InvalidKey,
+    Failed(String),
+}
+
+/// What a hotkey being recorded will be assigned to
+#[derive(Clone, Copy, PartialEq)]
+enum HotkeyTarget {
+    Preset(usize),
+    RerunLastCapture(usize), // A preset's "re-run last capture" key
+    CycleLanguage(usize), // A preset's "cycle target language" key
+    LiveCaptions,
+    ToggleResultWindows,
+    PresetPicker,
+    StopAll, // Stops every recording and live session
+    RecentCopies,
+}
+
+/// Tray ids of the "Recent Copies" submenu: the picker, then one per entry
+const RECENT_COPIES_LIST_ID: &str = "1006";
+const RECENT_COPY_ID_BASE: usize = 1100;
+
+/// Ring index of a "Recent Copies" tray entry
+fn recent_copy_index(menu_id: &str) -> Option<usize> {
+    let index = menu_id.parse::<usize>().ok()?.checked_sub(RECENT_COPY_ID_BASE)?;
+    (index < crate::overlay::recent_copies::MAX_ENTRIES).then_some(index)
+}
+
+/// Progress of the Global settings "Refresh models" action
+#[derive(Clone, PartialEq)]
+enum ModelRefreshState {
+    Idle,
+    Running,
+    Done(usize, Vec<String>), // Discovered model count, per-provider errors
 }
 
 pub struct SettingsApp {
@@ -117,7 +353,9 @@ pub struct SettingsApp {
     app_state_ref: Arc<Mutex<crate::AppState>>,
     search_query: String, // Shared search for languages
     tray_icon: Option<TrayIcon>,
-    _tray_menu: Menu,
+    tray_menu: Menu,
+    recent_copies_menu_gen: Option<u64>, // Ring generation the tray submenu shows
+    language_cycle_gen: u64, // Last language_cycle::generation() copied from APP
     event_rx: Receiver<UserEvent>,
     is_quitting: bool,
     run_at_startup: bool,
@@ -128,7 +366,7 @@ pub struct SettingsApp {
     
     // New State
     view_mode: ViewMode,
-    recording_hotkey_for_preset: Option<usize>,
+    recording_hotkey: Option<HotkeyTarget>,
     hotkey_conflict_msg: Option<String>,
     splash: Option<crate::gui::splash::SplashScreen>,
     fade_in_start: Option<f64>,
@@ -138,14 +376,39 @@ pub struct SettingsApp {
     
     // Cache monitors
     cached_monitors: Vec<String>,
+    cached_output_devices: Arc<Mutex<Vec<String>>>, // Loopback picker of audio presets, listed in the background
     
     // History state
     history_entries: Vec<crate::history::HistoryEntry>,
     history_search_query: String,
     show_favorites_only: bool,
     selected_history_id: Option<String>,
+    history_undo: Option<(Vec<String>, f64)>, // Deleted ids + time of deletion, for the undo toast
+    confirm_clear_history: bool,
+    confirm_replace_selection: Option<usize>, // Preset waiting for the one-time "replace selection" consent
+    prompt_preview: Option<String>, // Resolved prompt shown by "Preview prompt"
+    blocked_app_input: String,
+    diagnostics_copied_at: Option<f64>, // Shows "Copied!" next to "Copy diagnostics" for a moment
+    history_find_query: String, // Find-in-text within the history detail view
+    history_find_case_sensitive: bool,
+    history_find_index: usize,
+    history_find_scroll: bool, // Scroll to the current match on the next frame
+    history_tag_input: String,
+    history_tag_filter: Option<String>,
+
+    // First-run setup
+    onboarding_provider: String, // "groq" or "google"
+    onboarding_test: Arc<Mutex<KeyTestState>>,
+
+    /// Notes file chosen in the (blocking) dialog: (preset index, path)
+    picked_output_file: Arc<Mutex<Option<(usize, std::path::PathBuf)>>>,
+
+    model_refresh: Arc<Mutex<ModelRefreshState>>,
 }
 
+// How long the "Undo" toast stays up after a history deletion (seconds)
+const HISTORY_UNDO_WINDOW: f64 = 5.0;
+
 impl SettingsApp {
     pub fn new(config: Config, app_state: Arc<Mutex<crate::AppState>>, tray_icon: TrayIcon, tray_menu: Menu, ctx: egui::Context) -> Self {
         let app_name = "ScreenGroundedTranslator";
@@ -199,13 +462,31 @@ impl SettingsApp {
             }
         });
 
+        // Enumerating audio endpoints can take a while; don't hold up the first frame
+        let cached_output_devices = Arc::new(Mutex::new(Vec::new()));
+        Self::list_output_devices_async(cached_output_devices.clone(), ctx.clone());
+
         // Menu thread
         let tx_menu = tx.clone();
         let ctx_menu = ctx.clone();
         std::thread::spawn(move || {
             while let Ok(event) = MenuEvent::receiver().recv() {
                 match event.id.0.as_str() {
-                    "1001" => std::process::exit(0),
+                    "1001" => crate::shutdown::shutdown(),
+                    "1003" => {
+                        crate::overlay::toggle_live_captions_overlay();
+                        ctx_menu.request_repaint();
+                    }
+                    "1004" => {
+                        // The GUI copies the flag back from APP when it handles the event
+                        if let Ok(mut app) = crate::APP.lock() {
+                            app.config.preset_indicator = !app.config.preset_indicator;
+                            save_config(&app.config);
+                        }
+                        crate::overlay::preset_indicator::refresh();
+                        let _ = tx_menu.send(UserEvent::Menu(event));
+                        ctx_menu.request_repaint();
+                    }
                     "1002" => {
                         // Try to find and restore window directly
                         unsafe {
@@ -228,26 +509,43 @@ impl SettingsApp {
                         let _ = tx_menu.send(UserEvent::Menu(event.clone()));
                         ctx_menu.request_repaint();
                     }
+                    RECENT_COPIES_LIST_ID => { std::thread::spawn(crate::overlay::recent_copies::pick_and_copy); }
+                    id if recent_copy_index(id).is_some() => {
+                        crate::overlay::recent_copies::recopy(recent_copy_index(id).unwrap());
+                    }
                     _ => { let _ = tx_menu.send(UserEvent::Menu(event)); ctx_menu.request_repaint(); }
                 }
             }
         });
 
         // Determine initial view mode
-        let view_mode = if config.presets.is_empty() {
+        let needs_onboarding = !config.onboarding_done
+            && config.api_key.trim().is_empty()
+            && config.gemini_api_key.trim().is_empty();
+        let view_mode = if needs_onboarding {
+             ViewMode::Onboarding
+        } else if config.presets.is_empty() {
              ViewMode::Global 
         } else {
              ViewMode::Preset(if config.active_preset_idx < config.presets.len() { config.active_preset_idx } else { 0 })
         };
         
         let cached_monitors = get_monitor_names();
+        crate::history::purge_trash(crate::history::TRASH_RETENTION_DAYS);
+        crate::history::set_max_entries(config.max_history_entries);
+        crate::metrics::set_enabled(config.debug_metrics);
+        crate::translation_cache::configure(config.translation_cache_size, config.translation_cache_persist);
+        crate::app_log::configure(config.log_level, config.log_max_size_mb);
+        crate::crash_report::configure(config.show_crash_dialog);
 
         Self {
             config,
             app_state_ref: app_state,
             search_query: String::new(),
             tray_icon: Some(tray_icon),
-            _tray_menu: tray_menu,
+            tray_menu,
+            recent_copies_menu_gen: None,
+            language_cycle_gen: crate::overlay::language_cycle::generation(),
             event_rx: rx,
             is_quitting: false,
             run_at_startup,
@@ -256,16 +554,33 @@ impl SettingsApp {
             show_gemini_api_key: false,
             show_openrouter_api_key: false,
             view_mode,
-            recording_hotkey_for_preset: None,
+            recording_hotkey: None,
             hotkey_conflict_msg: None,
             splash: Some(crate::gui::splash::SplashScreen::new(&ctx)),
             fade_in_start: None,
             startup_stage: 0,
             cached_monitors,
+            cached_output_devices,
             history_entries: crate::history::load_history(),
             history_search_query: String::new(),
             show_favorites_only: false,
             selected_history_id: None,
+            history_undo: None,
+            confirm_clear_history: false,
+            confirm_replace_selection: None,
+            prompt_preview: None,
+            blocked_app_input: String::new(),
+            diagnostics_copied_at: None,
+            history_find_query: String::new(),
+            history_find_case_sensitive: false,
+            history_find_index: 0,
+            history_find_scroll: false,
+            history_tag_input: String::new(),
+            history_tag_filter: None,
+            onboarding_provider: "groq".to_string(),
+            onboarding_test: Arc::new(Mutex::new(KeyTestState::Idle)),
+            picked_output_file: Arc::new(Mutex::new(None)),
+            model_refresh: Arc::new(Mutex::new(ModelRefreshState::Idle)),
         }
     }
 
@@ -276,14 +591,33 @@ impl SettingsApp {
         }
 
         let mut state = self.app_state_ref.lock().unwrap();
+        let selection_changed = state.config.active_preset_idx != self.config.active_preset_idx;
         
         // Check if hotkeys changed
         // Simplification: Always signal update on save. Overhead is low.
         state.hotkeys_updated = true;
+        // Recorded by hotkey runs (app_language), not edited here; drop deleted presets' entries
+        let preset_ids: Vec<&String> = self.config.presets.iter().map(|p| &p.id).collect();
+        self.config.app_language_memory = state.config.app_language_memory.iter()
+            .filter(|(id, _)| preset_ids.contains(id))
+            .map(|(id, m)| (id.clone(), m.clone()))
+            .collect();
+        // Saved by the background update check
+        self.config.latest_release = state.config.latest_release.clone();
         state.config = self.config.clone();
         
         drop(state);
         save_config(&self.config);
+        crate::history::set_max_entries(self.config.max_history_entries);
+        crate::metrics::set_enabled(self.config.debug_metrics);
+        crate::translation_cache::configure(self.config.translation_cache_size, self.config.translation_cache_persist);
+        crate::app_log::configure(self.config.log_level, self.config.log_max_size_mb);
+        crate::crash_report::configure(self.config.show_crash_dialog);
+        if selection_changed {
+            crate::overlay::preset_indicator::set_active_preset(self.config.active_preset_idx);
+        } else {
+            crate::overlay::preset_indicator::refresh();
+        }
         
         // FIX 7: Post message to hotkey listener to reload hotkeys instead of waiting for timer
         unsafe {
@@ -296,6 +630,59 @@ impl SettingsApp {
         }
     }
     
+    /// Tray check items mirror the config after it was changed from the GUI
+    fn sync_tray_checks(&self) {
+        for item in self.tray_menu.items() {
+            if let Some(check) = item.as_check_menuitem() {
+                if check.id().0 == "1004" {
+                    check.set_checked(self.config.preset_indicator);
+                }
+            }
+        }
+    }
+
+    /// Rebuild the tray's "Recent Copies" submenu when the ring changed. Done here because
+    /// menu items live on this thread; the tray thread repaints on hover, before the menu opens.
+    fn sync_recent_copies_menu(&mut self) {
+        let (generation, labels) = match self.app_state_ref.lock() {
+            Ok(app) => (app.recent_copies.generation(), app.recent_copies.labels()),
+            Err(_) => return,
+        };
+        if self.recent_copies_menu_gen == Some(generation) {
+            return;
+        }
+        let Some(submenu) = self.tray_menu.items().into_iter().find_map(|item| item.as_submenu().cloned()) else { return };
+        while submenu.remove_at(0).is_some() {}
+        let _ = submenu.append(&MenuItem::with_id(RECENT_COPIES_LIST_ID, "Show list…", !labels.is_empty(), None));
+        let _ = submenu.append(&PredefinedMenuItem::separator());
+        if labels.is_empty() {
+            let _ = submenu.append(&MenuItem::new("(nothing copied yet)", false, None));
+        }
+        for (i, label) in labels.iter().enumerate() {
+            // '&' marks a mnemonic in menu text
+            let _ = submenu.append(&MenuItem::with_id((RECENT_COPY_ID_BASE + i).to_string(), label.replace('&', "&&"), true, None));
+        }
+        self.recent_copies_menu_gen = Some(generation);
+    }
+
+    /// Take `{language1}` values switched by a cycle hotkey from APP, so the next save
+    /// doesn't put the old ones back
+    fn sync_cycled_languages(&mut self) {
+        use crate::overlay::language_cycle::{generation, LANGUAGE_KEY};
+        let current = generation();
+        if current == self.language_cycle_gen {
+            return;
+        }
+        self.language_cycle_gen = current;
+        let Ok(state) = self.app_state_ref.lock() else { return };
+        for preset in &mut self.config.presets {
+            let cycled = state.config.presets.iter().find(|p| p.id == preset.id).and_then(|p| p.language_vars.get(LANGUAGE_KEY));
+            if let Some(language) = cycled {
+                preset.language_vars.insert(LANGUAGE_KEY.to_string(), language.clone());
+            }
+        }
+    }
+
     fn restore_window(&self, ctx: &egui::Context) {
          ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
          ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
@@ -305,9 +692,155 @@ impl SettingsApp {
          ctx.request_repaint();
      }
 
-    fn check_hotkey_conflict(&self, vk: u32, mods: u32, current_preset_idx: usize) -> Option<String> {
+    /// Sends a tiny translation request with the onboarding provider's key
+    fn start_key_test(&self, ctx: &egui::Context) {
+        let state = self.onboarding_test.clone();
+        *state.lock().unwrap() = KeyTestState::Running;
+
+        let model_id = if self.onboarding_provider == "google" { "text_gemini_flash_lite" } else { "fast_text" };
+        let model = get_model_by_id(model_id).expect("Onboarding test model missing");
+        let groq_api_key = self.config.api_key.clone();
+        let gemini_api_key = self.config.gemini_api_key.clone();
+        let ctx = ctx.clone();
+
+        std::thread::spawn(move || {
+            let res = crate::api::translate_text_uncached(
+                &groq_api_key,
+                &gemini_api_key,
+                "",
+                "Hello".to_string(),
+                "Vietnamese".to_string(),
+                model.full_name,
+                model.provider,
+                false,
+                false,
+                |_| {},
+            );
+            *state.lock().unwrap() = match res {
+                Ok(_) => KeyTestState::Ok,
+                Err(e) => match crate::api_error::ApiError::of(&e) {
+                    Some(crate::api_error::ApiError::NoApiKey | crate::api_error::ApiError::InvalidKey) => KeyTestState::InvalidKey,
+                    _ => KeyTestState::Failed(e.to_string()),
+                },
+            };
+            ctx.request_repaint();
+        });
+    }
+
+    /// Fills the loopback picker from a background thread
+    fn list_output_devices_async(devices: Arc<Mutex<Vec<String>>>, ctx: egui::Context) {
+        std::thread::spawn(move || {
+            let names = crate::audio_capture::list_output_devices();
+            *devices.lock().unwrap() = names;
+            ctx.request_repaint();
+        });
+    }
+
+    /// Fetches every configured provider's model list and merges it into the dropdowns
+    fn start_model_refresh(&self, ctx: &egui::Context) {
+        let state = self.model_refresh.clone();
+        *state.lock().unwrap() = ModelRefreshState::Running;
+
+        let groq_api_key = self.config.api_key.clone();
+        let gemini_api_key = self.config.gemini_api_key.clone();
+        let openrouter_api_key = self.config.openrouter_api_key.clone();
+        let ctx = ctx.clone();
+
+        std::thread::spawn(move || {
+            let (models, errors) = crate::api::discover_models(&groq_api_key, &gemini_api_key, &openrouter_api_key);
+            // Keep the previous cache if every provider failed
+            let count = if models.is_empty() && !errors.is_empty() {
+                0
+            } else {
+                crate::model_config::set_discovered_models(models)
+            };
+            *state.lock().unwrap() = ModelRefreshState::Done(count, errors);
+            ctx.request_repaint();
+        });
+    }
+
+    /// Leaves the first-run setup for good (finished or skipped)
+    fn finish_onboarding(&mut self) {
+        self.config.onboarding_done = true;
+        self.view_mode = if self.config.presets.is_empty() {
+            ViewMode::Global
+        } else {
+            ViewMode::Preset(self.config.active_preset_idx.min(self.config.presets.len() - 1))
+        };
+        self.save_and_sync();
+    }
+
+    /// The config slot of a single global hotkey (None for preset targets)
+    fn global_hotkey_slot(&mut self, target: HotkeyTarget) -> Option<&mut Option<Hotkey>> {
+        match target {
+            HotkeyTarget::Preset(_) | HotkeyTarget::RerunLastCapture(_) | HotkeyTarget::CycleLanguage(_) => None,
+            HotkeyTarget::LiveCaptions => Some(&mut self.config.live_captions.hotkey),
+            HotkeyTarget::ToggleResultWindows => Some(&mut self.config.toggle_results_hotkey),
+            HotkeyTarget::PresetPicker => Some(&mut self.config.preset_picker_hotkey),
+            HotkeyTarget::StopAll => Some(&mut self.config.stop_all_hotkey),
+            HotkeyTarget::RecentCopies => Some(&mut self.config.recent_copies_hotkey),
+        }
+    }
+
+    /// "Label: <hotkey> x" / "+ Add Key" row for a single global hotkey
+    fn global_hotkey_row(&mut self, ui: &mut egui::Ui, text: &LocaleText, label: &str, target: HotkeyTarget) {
+        let current = self.global_hotkey_slot(target).and_then(|h| h.clone());
+        ui.horizontal(|ui| {
+            ui.label(label);
+            if self.recording_hotkey == Some(target) {
+                ui.colored_label(egui::Color32::YELLOW, text.press_keys);
+                if ui.button(text.cancel_label).clicked() {
+                    self.recording_hotkey = None;
+                    self.hotkey_conflict_msg = None;
+                }
+            } else if let Some(hotkey) = current {
+                ui.label(&hotkey.name);
+                if ui.small_button("x").clicked() {
+                    if let Some(slot) = self.global_hotkey_slot(target) {
+                        *slot = None;
+                    }
+                    self.save_and_sync();
+                }
+            } else if ui.button(text.add_hotkey_button).clicked() {
+                self.recording_hotkey = Some(target);
+                self.hotkey_conflict_msg = None;
+            }
+        });
+        if self.recording_hotkey == Some(target) {
+            if let Some(msg) = &self.hotkey_conflict_msg {
+                ui.colored_label(egui::Color32::RED, msg);
+            }
+        }
+    }
+
+    fn check_hotkey_conflict(&self, vk: u32, mods: u32, target: HotkeyTarget) -> Option<String> {
+        let globals = [
+            (HotkeyTarget::LiveCaptions, &self.config.live_captions.hotkey, "Live Captions"),
+            (HotkeyTarget::ToggleResultWindows, &self.config.toggle_results_hotkey, "Show/hide result windows"),
+            (HotkeyTarget::PresetPicker, &self.config.preset_picker_hotkey, "Preset picker"),
+            (HotkeyTarget::StopAll, &self.config.stop_all_hotkey, "Stop everything"),
+            (HotkeyTarget::RecentCopies, &self.config.recent_copies_hotkey, "Recent copies"),
+        ];
+        for (global, hotkey, label) in globals {
+            if global == target { continue; }
+            if let Some(hk) = hotkey {
+                if hk.code == vk && hk.modifiers == mods {
+                    return Some(format!("Conflict with '{}' in {}", hk.name, label));
+                }
+            }
+        }
         for (idx, preset) in self.config.presets.iter().enumerate() {
-            if idx == current_preset_idx { continue; }
+            if let Some(hk) = &preset.rerun_hotkey {
+                if target != HotkeyTarget::RerunLastCapture(idx) && hk.code == vk && hk.modifiers == mods {
+                    return Some(format!("Conflict with '{}' (re-run last capture) in preset '{}'", hk.name, preset.name));
+                }
+            }
+            if let Some(hk) = &preset.cycle_language_hotkey {
+                if target != HotkeyTarget::CycleLanguage(idx) && hk.code == vk && hk.modifiers == mods {
+                    return Some(format!("Conflict with '{}' (cycle target language) in preset '{}'", hk.name, preset.name));
+                }
+            }
+            if target == HotkeyTarget::Preset(idx) { continue; }
             for hk in &preset.hotkeys {
                 if hk.code == vk && hk.modifiers == mods {
                     return Some(format!("Conflict with '{}' in preset '{}'", hk.name, preset.name));
@@ -418,9 +951,55 @@ impl eframe::App for SettingsApp {
         if RESTORE_SIGNAL.swap(false, Ordering::SeqCst) {
             self.restore_window(ctx);
         }
+        if SHOW_API_KEYS.swap(false, Ordering::SeqCst) {
+            self.view_mode = ViewMode::Global;
+        }
+        let picked_output_file = self.picked_output_file.lock().unwrap().take();
+        if let Some((idx, path)) = picked_output_file {
+            if let Some(preset) = self.config.presets.get_mut(idx) {
+                preset.output_file = Some(path);
+                self.save_and_sync();
+            }
+        }
+
+        // --- Audio File Drops: transcribe with the open audio preset (or the first one) ---
+        // Anything that isn't an image goes here, so unsupported files are reported too
+        let dropped: Vec<std::path::PathBuf> = ctx.input(|i| i.raw.dropped_files.iter().filter_map(|f| f.path.clone()).collect());
+        let (images, dropped): (Vec<_>, Vec<_>) = dropped.into_iter().partition(|p| crate::image_batch::is_image_file(p));
+        if !dropped.is_empty() {
+            let open_preset = match self.view_mode {
+                ViewMode::Preset(idx) => self.config.presets.get(idx).filter(|p| p.preset_type == "audio"),
+                _ => None,
+            };
+            match open_preset.or_else(|| self.config.presets.iter().find(|p| p.preset_type == "audio")) {
+                Some(preset) => {
+                    for path in dropped {
+                        crate::audio_file::transcribe_file(preset.clone(), path);
+                    }
+                }
+                None => {
+                    let text = LocaleText::get(&self.config.ui_language);
+                    crate::overlay::app_blocklist::show_toast(format!("⚠ {}", text.audio_drop_no_preset));
+                }
+            }
+        }
+
+        // --- Image File Drops: batch translate with the open image preset (or the first one) ---
+        if !images.is_empty() {
+            let open_preset = match self.view_mode {
+                ViewMode::Preset(idx) => self.config.presets.get(idx).filter(|p| runs_batches(p)),
+                _ => None,
+            };
+            if let Some(preset) = open_preset.or_else(|| self.config.presets.iter().find(|p| runs_batches(p))) {
+                crate::image_batch::translate_files(preset.clone(), images);
+            }
+        }
+        if crate::image_batch::progress().running {
+            ctx.request_repaint_after(std::time::Duration::from_millis(500));
+        }
 
         // --- Hotkey Recording Logic ---
-        if let Some(preset_idx) = self.recording_hotkey_for_preset {
+        if let Some(target) = self.recording_hotkey {
             let mut key_recorded: Option<(u32, u32, String)> = None;
             let mut cancel = false;
 
@@ -448,11 +1027,11 @@ impl eframe::App for SettingsApp {
             });
 
             if cancel {
-                self.recording_hotkey_for_preset = None;
+                self.recording_hotkey = None;
                 self.hotkey_conflict_msg = None;
             } else if let Some((vk, mods, key_name)) = key_recorded {
                 // Conflict Check
-                if let Some(msg) = self.check_hotkey_conflict(vk, mods, preset_idx) {
+                if let Some(msg) = self.check_hotkey_conflict(vk, mods, target) {
                     self.hotkey_conflict_msg = Some(msg);
                 } else {
                     // No conflict
@@ -469,13 +1048,49 @@ impl eframe::App for SettingsApp {
                         name: name_parts.join(" + "),
                     };
 
-                    if let Some(preset) = self.config.presets.get_mut(preset_idx) {
-                        if !preset.hotkeys.iter().any(|h| h.code == vk && h.modifiers == mods) {
-                            preset.hotkeys.push(new_hotkey);
+                    match target {
+                        HotkeyTarget::Preset(preset_idx) => {
+                            if let Some(preset) = self.config.presets.get_mut(preset_idx) {
+                                if !preset.hotkeys.iter().any(|h| h.code == vk && h.modifiers == mods) {
+                                    preset.hotkeys.push(new_hotkey);
+                                    self.save_and_sync();
+                                }
+                            }
+                        }
+                        HotkeyTarget::RerunLastCapture(preset_idx) => {
+                            if let Some(preset) = self.config.presets.get_mut(preset_idx) {
+                                preset.rerun_hotkey = Some(new_hotkey);
+                                self.save_and_sync();
+                            }
+                        }
+                        HotkeyTarget::CycleLanguage(preset_idx) => {
+                            if let Some(preset) = self.config.presets.get_mut(preset_idx) {
+                                preset.cycle_language_hotkey = Some(new_hotkey);
+                                self.save_and_sync();
+                            }
+                        }
+                        HotkeyTarget::LiveCaptions => {
+                            self.config.live_captions.hotkey = Some(new_hotkey);
+                            self.save_and_sync();
+                        }
+                        HotkeyTarget::ToggleResultWindows => {
+                            self.config.toggle_results_hotkey = Some(new_hotkey);
+                            self.save_and_sync();
+                        }
+                        HotkeyTarget::PresetPicker => {
+                            self.config.preset_picker_hotkey = Some(new_hotkey);
+                            self.save_and_sync();
+                        }
+                        HotkeyTarget::StopAll => {
+                            self.config.stop_all_hotkey = Some(new_hotkey);
+                            self.save_and_sync();
+                        }
+                        HotkeyTarget::RecentCopies => {
+                            self.config.recent_copies_hotkey = Some(new_hotkey);
                             self.save_and_sync();
                         }
                     }
-                    self.recording_hotkey_for_preset = None;
+                    self.recording_hotkey = None;
                     self.hotkey_conflict_msg = None;
                 }
             }
@@ -493,10 +1108,14 @@ impl eframe::App for SettingsApp {
                 UserEvent::Menu(menu_event) => {
                     if menu_event.id.0 == "1002" {
                         self.restore_window(ctx);
+                    } else if menu_event.id.0 == "1004" {
+                        self.config.preset_indicator = self.app_state_ref.lock().unwrap().config.preset_indicator;
                     }
                 }
             }
         }
+        self.sync_recent_copies_menu();
+        self.sync_cycled_languages();
 
         if ctx.input(|i| i.viewport().close_requested()) {
             if !self.is_quitting {
@@ -547,6 +1166,12 @@ impl eframe::App for SettingsApp {
                     ui.label(egui::RichText::new(text.footer_admin_text).size(11.0).color(ui.visuals().weak_text_color()));
                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                         ui.label(egui::RichText::new(text.footer_version).size(11.0).color(ui.visuals().weak_text_color()));
+                        if let Some(update) = crate::update_check::available() {
+                            let banner = egui::RichText::new(text.update_available.replace("{}", &update.version)).size(11.0).strong();
+                            if ui.link(banner).on_hover_text(&update.url).clicked() {
+                                let _ = open::that(&update.url);
+                            }
+                        }
                     });
                 });
             });
@@ -602,6 +1227,7 @@ impl eframe::App for SettingsApp {
                     ui.label(egui::RichText::new(text.presets_section).strong());
                     
                     let mut preset_idx_to_delete = None;
+                    let mut preset_clicked = false;
 
                     // Removed ScrollArea wrapper as requested
                      for (idx, preset) in self.config.presets.iter().enumerate() {
@@ -625,6 +1251,7 @@ impl eframe::App for SettingsApp {
                                      draw_icon_static(ui, icon_type, None);
                                      if ui.selectable_label(is_selected, &preset.name).clicked() {
                                          self.view_mode = ViewMode::Preset(idx);
+                                         preset_clicked = true;
                                      }
                                  });
                                  // Delete button (X icon)
@@ -646,6 +1273,10 @@ impl eframe::App for SettingsApp {
                         self.save_and_sync();
                     }
 
+                    if preset_clicked {
+                        self.save_and_sync(); // Persists the selection and moves the preset indicator
+                    }
+
                     if let Some(idx) = preset_idx_to_delete {
                         self.config.presets.remove(idx);
                         if let ViewMode::Preset(curr) = self.view_mode {
@@ -724,6 +1355,28 @@ impl eframe::App for SettingsApp {
                                     let eye_icon = if self.show_openrouter_api_key { Icon::EyeOpen } else { Icon::EyeClosed };
                                     if icon_button(ui, eye_icon).clicked() { self.show_openrouter_api_key = !self.show_openrouter_api_key; }
                                 });
+
+                                ui.add_space(5.0);
+                                let refresh_state = self.model_refresh.lock().unwrap().clone();
+                                ui.horizontal(|ui| {
+                                    let running = refresh_state == ModelRefreshState::Running;
+                                    if ui.add_enabled(!running, egui::Button::new(text.refresh_models_button))
+                                        .on_hover_text(text.refresh_models_tooltip).clicked() {
+                                        self.start_model_refresh(ctx);
+                                    }
+                                    match &refresh_state {
+                                        ModelRefreshState::Idle => {}
+                                        ModelRefreshState::Running => { ui.spinner(); }
+                                        ModelRefreshState::Done(count, _) => {
+                                            ui.label(text.refresh_models_done.replace("{}", &count.to_string()));
+                                        }
+                                    }
+                                });
+                                if let ModelRefreshState::Done(_, errors) = &refresh_state {
+                                    for err in errors {
+                                        ui.colored_label(egui::Color32::from_rgb(255, 100, 100), err);
+                                    }
+                                }
                             });
 
                             ui.add_space(10.0);
@@ -750,7 +1403,7 @@ impl eframe::App for SettingsApp {
                                     let mut shown_models = std::collections::HashSet::new();
                                     
                                     for model in get_all_models() {
-                                        if !model.enabled { continue; }
+                                        if !model.enabled || model.discovered { continue; }
                                         
                                         // Skip duplicates (same full_name)
                                         if shown_models.contains(&model.full_name) {
@@ -775,6 +1428,48 @@ impl eframe::App for SettingsApp {
                                         ui.end_row();
                                     }
                                 });
+
+                                // Translation memory: repeated requests answered without an API call
+                                ui.add_space(5.0);
+                                ui.horizontal(|ui| {
+                                    ui.label(text.translation_cache_hits
+                                        .replacen("{}", &crate::translation_cache::hit_count().to_string(), 1)
+                                        .replacen("{}", &crate::translation_cache::entry_count().to_string(), 1));
+                                    if ui.small_button(text.translation_cache_clear).clicked() {
+                                        crate::translation_cache::clear();
+                                    }
+                                });
+                                ui.horizontal(|ui| {
+                                    ui.label(text.translation_cache_size_label);
+                                    if ui.add(egui::DragValue::new(&mut self.config.translation_cache_size).clamp_range(0..=10000).speed(10.0))
+                                        .on_hover_text(text.translation_cache_tooltip).changed() {
+                                        self.save_and_sync();
+                                    }
+                                    if ui.checkbox(&mut self.config.translation_cache_persist, text.translation_cache_persist_label).changed() {
+                                        self.save_and_sync();
+                                    }
+                                });
+                                // Encoding of captures sent to vision models
+                                ui.horizontal(|ui| {
+                                    ui.label(text.image_upload_format_label).on_hover_text(text.image_upload_format_tooltip);
+                                    let name = |format: crate::config::ImageUploadFormat| match format {
+                                        crate::config::ImageUploadFormat::Png => "PNG",
+                                        crate::config::ImageUploadFormat::WebP => "WebP",
+                                    };
+                                    egui::ComboBox::from_id_source("image_upload_format_combo")
+                                        .selected_text(name(self.config.image_upload_format))
+                                        .show_ui(ui, |ui| {
+                                            for format in crate::config::ImageUploadFormat::ALL {
+                                                if ui.selectable_value(&mut self.config.image_upload_format, format, name(format)).clicked() {
+                                                    self.save_and_sync();
+                                                }
+                                            }
+                                        });
+                                });
+                                if ui.checkbox(&mut self.config.upscale_small_crops, text.upscale_small_crops_label)
+                                    .on_hover_text(text.upscale_small_crops_tooltip).changed() {
+                                    self.save_and_sync();
+                                }
                             });
                             // -----------------------------
 
@@ -824,6 +1519,9 @@ impl eframe::App for SettingsApp {
                                     ui.label(status_text);
                                 });
                                 
+                                // Toggle hotkey (also available from the tray menu)
+                                self.global_hotkey_row(ui, &text, text.live_captions_hotkey, HotkeyTarget::LiveCaptions);
+                                
                                 // Instructions
                                 if !is_active {
                                     ui.add_space(3.0);
@@ -884,9 +1582,10 @@ impl eframe::App for SettingsApp {
                                             });
                                     });
                                     
-                                    // Audio Source
-                                    ui.horizontal(|ui| {
-                                        ui.label("Nguồn âm thanh:");
+                                    // Audio Source (only the Gemini Live pipeline captures audio itself)
+                                    let uses_audio = self.config.live_captions.translation_model == "gemini-2.0-flash-live";
+                                    ui.add_enabled_ui(uses_audio, |ui| ui.horizontal(|ui| {
+                                        ui.label(text.live_captions_audio_source).on_hover_text(text.live_captions_audio_source_tooltip);
                                         let current_source = self.config.live_captions.audio_source.clone();
                                         egui::ComboBox::from_id_source("lc_audio_source")
                                             .width(180.0)
@@ -902,7 +1601,7 @@ impl eframe::App for SettingsApp {
                                                     self.save_and_sync();
                                                 }
                                             });
-                                    });
+                                    }));
 
                                     // Overlay sentences
                                     ui.horizontal(|ui| {
@@ -944,8 +1643,9 @@ impl eframe::App for SettingsApp {
                                     ui.add_space(8.0);
                                     
                                     // Get vision models for dropdown
+                                    let all_models = crate::model_config::get_all_models();
                                     let vision_models: Vec<&crate::model_config::ModelConfig> = 
-                                        crate::model_config::get_all_models()
+                                        all_models
                                             .iter()
                                             .filter(|m| m.model_type == crate::model_config::ModelType::Vision)
                                             .collect();
@@ -996,52 +1696,300 @@ impl eframe::App for SettingsApp {
 
                             ui.add_space(10.0);
 
-                            ui.horizontal(|ui| {
-                                if let Some(launcher) = &self.auto_launcher {
-                                    if ui.checkbox(&mut self.run_at_startup, text.startup_label).clicked() {
-                                        if self.run_at_startup { let _ = launcher.enable(); } else { let _ = launcher.disable(); }
-                                    }
-                                }
-                                if ui.button(text.reset_defaults_btn).clicked() {
-                                    // Save API keys before resetting
-                                    let saved_groq_key = self.config.api_key.clone();
-                                    let saved_gemini_key = self.config.gemini_api_key.clone();
-                                    
-                                    // Reset to defaults
-                                    self.config = Config::default();
-                                    
-                                    // Restore API keys
-                                    self.config.api_key = saved_groq_key;
-                                    self.config.gemini_api_key = saved_gemini_key;
-                                    
+                            // --- SELECTION OVERLAY APPEARANCE ---
+                            ui.group(|ui| {
+                                ui.label(egui::RichText::new(text.overlay_appearance_title).strong());
+                                let mut changed = false;
+                                let appearance = &mut self.config.overlay_appearance;
+                                ui.horizontal(|ui| {
+                                    ui.vertical(|ui| {
+                                        ui.horizontal(|ui| {
+                                            ui.label(text.overlay_dim_label);
+                                            let mut percent = appearance.dim_percent();
+                                            if ui.add(egui::Slider::new(&mut percent, 0..=crate::config::OverlayAppearance::MAX_DIM_PERCENT).suffix("%")).changed() {
+                                                appearance.set_dim_percent(percent);
+                                                changed = true;
+                                            }
+                                        });
+                                        ui.horizontal(|ui| {
+                                            ui.label(text.overlay_border_color_label);
+                                            changed |= ui.color_edit_button_srgb(&mut appearance.border_color).changed();
+                                            ui.label(text.overlay_border_width_label);
+                                            changed |= ui.add(egui::DragValue::new(&mut appearance.border_width).clamp_range(1..=crate::config::OverlayAppearance::MAX_BORDER_WIDTH).suffix(" px")).changed();
+                                        });
+                                        changed |= ui.checkbox(&mut appearance.show_grid, text.overlay_grid_label).changed();
+                                        changed |= ui.checkbox(&mut appearance.crosshair, text.overlay_crosshair_label).changed();
+                                        if *appearance != crate::config::OverlayAppearance::default() && ui.button(text.overlay_appearance_reset).clicked() {
+                                            *appearance = crate::config::OverlayAppearance::default();
+                                            changed = true;
+                                        }
+                                    });
+                                    show_overlay_preview(ui, appearance);
+                                });
+                                if changed {
                                     self.save_and_sync();
                                 }
                             });
-                        }
-                        
-                        ViewMode::Preset(idx) => {
-                            // Ensure index is valid (could be invalid if just deleted)
-                            if idx >= self.config.presets.len() {
-                                self.view_mode = ViewMode::Global; 
-                                return;
-                            }
-
-                            let mut preset = self.config.presets[idx].clone();
-                            let mut preset_changed = false;
-                            let _is_vietnamese = self.config.ui_language == "vi";
 
-                            // Removed Heading
-                            ui.add_space(5.0);
+                            ui.add_space(10.0);
 
-                            // 1. Name (Bigger)
-                            ui.horizontal(|ui| {
-                                ui.label(egui::RichText::new(text.preset_name_label).heading());
-                                if ui.add(egui::TextEdit::singleline(&mut preset.name).font(egui::TextStyle::Heading)).changed() {
-                                    preset_changed = true;
-                                }
+                            // --- ACTIVE PRESET INDICATOR ---
+                            ui.group(|ui| {
+                                ui.horizontal(|ui| {
+                                    if ui.checkbox(&mut self.config.preset_indicator, text.preset_indicator_label).changed() {
+                                        self.sync_tray_checks();
+                                        self.save_and_sync();
+                                    }
+                                    icon_button(ui, Icon::Info).on_hover_text(text.preset_indicator_tooltip);
+                                });
+                                ui.add_enabled_ui(self.config.preset_indicator, |ui| {
+                                    ui.horizontal(|ui| {
+                                        ui.label(text.preset_indicator_corner_label);
+                                        let mut corner = self.config.preset_indicator_corner;
+                                        egui::ComboBox::from_id_source("preset_indicator_corner")
+                                            .selected_text(corner_name(&text, corner))
+                                            .show_ui(ui, |ui| {
+                                                for option in crate::config::ScreenCorner::ALL {
+                                                    ui.selectable_value(&mut corner, option, corner_name(&text, option));
+                                                }
+                                            });
+                                        if corner != self.config.preset_indicator_corner {
+                                            self.config.preset_indicator_corner = corner;
+                                            self.save_and_sync();
+                                        }
+                                    });
+                                });
                             });
-                            
-                            // Type Dropdown
+
+                            // --- CAPTURE BLOCKLIST ---
+                            ui.group(|ui| {
+                                ui.horizontal(|ui| {
+                                    ui.label(egui::RichText::new(text.blocked_apps_title).strong());
+                                    icon_button(ui, Icon::Info).on_hover_text(text.blocked_apps_tooltip);
+                                });
+                                let mut remove = None;
+                                for (i, app) in self.config.blocked_apps.iter().enumerate() {
+                                    ui.horizontal(|ui| {
+                                        ui.label(app);
+                                        if icon_button(ui, Icon::Delete).clicked() {
+                                            remove = Some(i);
+                                        }
+                                    });
+                                }
+                                if let Some(i) = remove {
+                                    self.config.blocked_apps.remove(i);
+                                    self.save_and_sync();
+                                }
+                                ui.horizontal(|ui| {
+                                    let input = ui.add(egui::TextEdit::singleline(&mut self.blocked_app_input).hint_text(text.blocked_apps_placeholder).desired_width(200.0));
+                                    let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                    if (ui.button(text.blocked_apps_add).clicked() || submitted) && !self.blocked_app_input.trim().is_empty() {
+                                        let name = self.blocked_app_input.trim().to_string();
+                                        if !crate::overlay::app_blocklist::is_blocked_process(&name, &self.config.blocked_apps) {
+                                            self.config.blocked_apps.push(name);
+                                            self.save_and_sync();
+                                        }
+                                        self.blocked_app_input.clear();
+                                    }
+                                });
+                            });
+
+                            ui.add_space(10.0);
+
+                            ui.horizontal(|ui| {
+                                ui.label(text.max_history_label);
+                                if ui.add(egui::DragValue::new(&mut self.config.max_history_entries).clamp_range(0..=100000).speed(10.0))
+                                    .on_hover_text(text.max_history_tooltip).changed() {
+                                    self.save_and_sync();
+                                }
+                            });
+
+                            self.global_hotkey_row(ui, &text, text.toggle_results_hotkey_label, HotkeyTarget::ToggleResultWindows);
+                            self.global_hotkey_row(ui, &text, text.preset_picker_hotkey_label, HotkeyTarget::PresetPicker);
+                            self.global_hotkey_row(ui, &text, text.stop_all_hotkey_label, HotkeyTarget::StopAll);
+                            self.global_hotkey_row(ui, &text, text.recent_copies_hotkey_label, HotkeyTarget::RecentCopies);
+
+                            if ui.checkbox(&mut self.config.copy_button_markdown, text.copy_markdown_label)
+                                .on_hover_text(text.copy_markdown_tooltip).changed() {
+                                self.save_and_sync();
+                            }
+
+                            if ui.checkbox(&mut self.config.overlay_animations, text.overlay_animations_label)
+                                .on_hover_text(text.overlay_animations_tooltip).changed() {
+                                self.save_and_sync();
+                            }
+
+                            if ui.checkbox(&mut self.config.show_model_badge, text.show_model_badge_label)
+                                .on_hover_text(text.show_model_badge_tooltip).changed() {
+                                self.save_and_sync();
+                            }
+
+                            ui.horizontal(|ui| {
+                                ui.label(text.quick_translate_label).on_hover_text(text.quick_translate_tooltip);
+                                egui::ComboBox::from_id_source("quick_translate_to")
+                                    .width(150.0)
+                                    .selected_text(&self.config.quick_translate_to)
+                                    .show_ui(ui, |ui| {
+                                        for lang in get_all_languages().iter() {
+                                            if ui.selectable_label(self.config.quick_translate_to == *lang, lang).clicked() {
+                                                self.config.quick_translate_to = lang.clone();
+                                                self.save_and_sync();
+                                            }
+                                        }
+                                    });
+                            });
+
+                            // Batch translation of image files (dropped here or from an image preset's Batch button)
+                            ui.horizontal(|ui| {
+                                ui.label(text.batch_concurrency_label).on_hover_text(text.batch_concurrency_tooltip);
+                                if ui.add(egui::DragValue::new(&mut self.config.batch.concurrency).clamp_range(1..=crate::image_batch::MAX_CONCURRENCY)).changed() {
+                                    self.save_and_sync();
+                                }
+                                if ui.checkbox(&mut self.config.batch.per_image_files, text.batch_per_image_label).changed() {
+                                    self.save_and_sync();
+                                }
+                                if ui.checkbox(&mut self.config.batch.combined_file, text.batch_combined_label).changed() {
+                                    self.save_and_sync();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(text.batch_output_label);
+                                if ui.add(egui::TextEdit::singleline(&mut self.config.batch.output_dir).hint_text(text.batch_output_hint).desired_width(260.0)).changed() {
+                                    self.save_and_sync();
+                                }
+                            });
+
+                            ui.horizontal(|ui| {
+                                if ui.checkbox(&mut self.config.debug_metrics, text.debug_metrics_label)
+                                    .on_hover_text(text.debug_metrics_tooltip).changed() {
+                                    self.save_and_sync();
+                                }
+                                if self.config.debug_metrics {
+                                    if let Some(summary) = crate::metrics::last_summary() {
+                                        ui.label(egui::RichText::new(summary.describe()).small().weak());
+                                    }
+                                }
+                            });
+
+                            // --- SUPPORT: config/log location and a report for bug reports ---
+                            ui.horizontal(|ui| {
+                                if ui.button(text.open_config_folder_btn).clicked() {
+                                    let _ = open::that(crate::config::get_app_dir());
+                                }
+                                if ui.button(text.open_log_file_btn).clicked() {
+                                    let _ = open::that(crate::config::get_log_path());
+                                }
+                                if ui.button(text.copy_diagnostics_btn).on_hover_text(text.copy_diagnostics_tooltip).clicked() {
+                                    let report = crate::diagnostics::report();
+                                    ui.output_mut(|o| o.copied_text = report);
+                                    self.diagnostics_copied_at = Some(ui.input(|i| i.time));
+                                }
+                                if let Some(copied_at) = self.diagnostics_copied_at {
+                                    if ui.input(|i| i.time) - copied_at < 2.0 {
+                                        ui.label(egui::RichText::new(text.diagnostics_copied).small().weak());
+                                        ui.ctx().request_repaint_after(std::time::Duration::from_millis(250));
+                                    } else {
+                                        self.diagnostics_copied_at = None;
+                                    }
+                                }
+                            });
+                            // Should drop back to 0 once every result window is closed
+                            ui.label(egui::RichText::new(crate::diagnostics::resource_summary()).small().weak());
+                            ui.horizontal(|ui| {
+                                ui.label(text.log_level_label);
+                                let mut level = self.config.log_level;
+                                ui.selectable_value(&mut level, crate::config::LogLevel::Info, "Info");
+                                ui.selectable_value(&mut level, crate::config::LogLevel::Debug, "Debug")
+                                    .on_hover_text(text.log_level_debug_tooltip);
+                                if level != self.config.log_level {
+                                    self.config.log_level = level;
+                                    self.save_and_sync();
+                                }
+                                ui.add_space(10.0);
+                                ui.label(text.log_max_size_label);
+                                if ui.add(egui::DragValue::new(&mut self.config.log_max_size_mb).clamp_range(1..=100).suffix(" MB"))
+                                    .on_hover_text(text.log_max_size_tooltip).changed() {
+                                    self.save_and_sync();
+                                }
+                            });
+                            if ui.checkbox(&mut self.config.show_crash_dialog, text.show_crash_dialog_label)
+                                .on_hover_text(text.show_crash_dialog_tooltip).changed() {
+                                self.save_and_sync();
+                            }
+                            ui.horizontal(|ui| {
+                                ui.label(text.last_capture_keep_label);
+                                if ui.add(egui::DragValue::new(&mut self.config.last_capture_keep_secs).clamp_range(0..=3600).suffix(" s"))
+                                    .on_hover_text(text.last_capture_keep_tooltip).changed() {
+                                    if self.config.last_capture_keep_secs == 0 {
+                                        self.app_state_ref.lock().unwrap().last_capture = None;
+                                    }
+                                    self.save_and_sync();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(text.min_region_size_label);
+                                if ui.add(egui::DragValue::new(&mut self.config.min_region_size).clamp_range(1..=200).suffix(" px"))
+                                    .on_hover_text(text.min_region_size_tooltip).changed() {
+                                    self.save_and_sync();
+                                }
+                            });
+                            ui.horizontal(|ui| {
+                                ui.label(text.hotkey_debounce_label);
+                                if ui.add(egui::DragValue::new(&mut self.config.hotkey_debounce_ms).clamp_range(0..=1000).speed(5.0).suffix(" ms"))
+                                    .on_hover_text(text.hotkey_debounce_tooltip).changed() {
+                                    self.save_and_sync();
+                                }
+                            });
+
+                            ui.horizontal(|ui| {
+                                if let Some(launcher) = &self.auto_launcher {
+                                    if ui.checkbox(&mut self.run_at_startup, text.startup_label).clicked() {
+                                        if self.run_at_startup { let _ = launcher.enable(); } else { let _ = launcher.disable(); }
+                                    }
+                                }
+                                if ui.checkbox(&mut self.config.check_updates, text.check_updates_label).on_hover_text(text.check_updates_tooltip).clicked() {
+                                    self.save_and_sync();
+                                }
+                                if ui.button(text.reset_defaults_btn).clicked() {
+                                    // Save API keys before resetting
+                                    let saved_groq_key = self.config.api_key.clone();
+                                    let saved_gemini_key = self.config.gemini_api_key.clone();
+                                    
+                                    // Reset to defaults
+                                    self.config = Config::default();
+                                    
+                                    // Restore API keys
+                                    self.config.api_key = saved_groq_key;
+                                    self.config.gemini_api_key = saved_gemini_key;
+                                    
+                                    self.save_and_sync();
+                                }
+                            });
+                        }
+                        
+                        ViewMode::Preset(idx) => {
+                            // Ensure index is valid (could be invalid if just deleted)
+                            if idx >= self.config.presets.len() {
+                                self.view_mode = ViewMode::Global; 
+                                return;
+                            }
+
+                            let mut preset = self.config.presets[idx].clone();
+                            let mut preset_changed = false;
+                            let _is_vietnamese = self.config.ui_language == "vi";
+
+                            // Removed Heading
+                            ui.add_space(5.0);
+
+                            // 1. Name (Bigger)
+                            ui.horizontal(|ui| {
+                                ui.label(egui::RichText::new(text.preset_name_label).heading());
+                                if ui.add(egui::TextEdit::singleline(&mut preset.name).font(egui::TextStyle::Heading)).changed() {
+                                    preset_changed = true;
+                                }
+                            });
+                            
+                            // Type Dropdown
                              ui.horizontal(|ui| {
                                  ui.label(text.preset_type_label);
                                  let image_label = text.preset_type_image;
@@ -1072,9 +2020,115 @@ impl eframe::App for SettingsApp {
                                      });
                              });
 
+                             // Capture mode (image presets): screen region or replace the selected text
+                             if preset.preset_type == "image" {
+                                 let monitor = preset.capture_monitor().map(str::to_string);
+                                 ui.horizontal(|ui| {
+                                     ui.label(text.capture_mode_label);
+                                     let is_replace = preset.capture_mode == REPLACE_SELECTION_MODE;
+                                     let is_region = !is_replace && monitor.is_none();
+                                     let selected = if is_replace {
+                                         text.capture_mode_replace_selection.to_string()
+                                     } else if let Some(name) = &monitor {
+                                         format!("{} ({})", text.capture_mode_full_monitor, name)
+                                     } else {
+                                         text.capture_mode_region.to_string()
+                                     };
+                                     egui::ComboBox::from_id_source("capture_mode_combo")
+                                         .selected_text(selected)
+                                         .show_ui(ui, |ui| {
+                                             if ui.selectable_label(is_region, text.capture_mode_region).clicked() && !is_region {
+                                                 preset.capture_mode.clear();
+                                                 preset.video_capture_method = "region".to_string();
+                                                 preset_changed = true;
+                                             }
+                                             for name in &self.cached_monitors {
+                                                 let is_this = monitor.as_deref() == Some(name.as_str());
+                                                 if ui.selectable_label(is_this, format!("{} ({})", text.capture_mode_full_monitor, name)).clicked() && !is_this {
+                                                     preset.capture_mode.clear();
+                                                     preset.video_capture_method = format!("monitor:{}", name);
+                                                     preset_changed = true;
+                                                 }
+                                             }
+                                             if ui.selectable_label(is_replace, text.capture_mode_replace_selection).clicked() && !is_replace {
+                                                 if self.config.replace_selection_confirmed {
+                                                     preset.capture_mode = REPLACE_SELECTION_MODE.to_string();
+                                                     preset.video_capture_method = "region".to_string();
+                                                     preset_changed = true;
+                                                 } else {
+                                                     self.confirm_replace_selection = Some(idx);
+                                                 }
+                                             }
+                                         });
+                                     if icon_button(ui, Icon::Refresh).on_hover_text("Refresh Monitors").clicked() {
+                                         self.cached_monitors = get_monitor_names();
+                                     }
+                                 });
+                                 if monitor.as_ref().map_or(false, |name| !self.cached_monitors.contains(name)) {
+                                     ui.colored_label(egui::Color32::from_rgb(230, 150, 40), text.capture_monitor_missing);
+                                 }
+                             }
+
+                             // Image files from disk instead of a capture
+                             if runs_batches(&preset) {
+                                 let progress = crate::image_batch::progress();
+                                 ui.horizontal(|ui| {
+                                     if ui.add_enabled(!progress.running, egui::Button::new(text.batch_btn)).on_hover_text(text.batch_tooltip).clicked() {
+                                         let preset_for_batch = preset.clone();
+                                         // The file dialog blocks, keep it off the UI thread
+                                         std::thread::spawn(move || {
+                                             let files = crate::image_batch::pick_image_files();
+                                             crate::image_batch::translate_files(preset_for_batch, files);
+                                         });
+                                     }
+                                     if progress.total > 0 {
+                                         let status = text.batch_progress
+                                             .replace("{done}", &progress.done.to_string())
+                                             .replace("{total}", &progress.total.to_string())
+                                             .replace("{failed}", &progress.failed.to_string());
+                                         ui.label(egui::RichText::new(status).small());
+                                         if progress.running {
+                                             if ui.small_button(text.batch_cancel_btn).clicked() {
+                                                 crate::image_batch::cancel();
+                                             }
+                                         } else if let Some(dir) = progress.output_dir {
+                                             if ui.small_button(text.batch_open_folder_btn).clicked() {
+                                                 let _ = open::that(dir);
+                                             }
+                                         }
+                                     }
+                                 });
+                             }
+
+                             // Region presets: keep the selection overlay on one monitor
+                             if matches!(preset.preset_type.as_str(), "image" | "chat" | "screenshot") && preset.capture_mode.is_empty() && preset.capture_monitor().is_none() {
+                                 ui.horizontal(|ui| {
+                                     ui.label(text.target_monitor_label).on_hover_text(text.target_monitor_tooltip);
+                                     egui::ComboBox::from_id_source("target_monitor_combo")
+                                         .selected_text(preset.target_monitor.clone().unwrap_or_else(|| text.target_monitor_all.to_string()))
+                                         .show_ui(ui, |ui| {
+                                             if ui.selectable_value(&mut preset.target_monitor, None, text.target_monitor_all).clicked() {
+                                                 preset_changed = true;
+                                             }
+                                             for name in &self.cached_monitors {
+                                                 if ui.selectable_value(&mut preset.target_monitor, Some(name.clone()), name).clicked() {
+                                                     preset_changed = true;
+                                                 }
+                                             }
+                                         });
+                                 });
+                                 if preset.target_monitor.as_ref().map_or(false, |name| !self.cached_monitors.contains(name)) {
+                                     ui.colored_label(egui::Color32::from_rgb(230, 150, 40), text.target_monitor_missing);
+                                 }
+                                 if ui.checkbox(&mut preset.capture_on_release, text.capture_on_release_label).on_hover_text(text.capture_on_release_tooltip).changed() {
+                                     preset_changed = true;
+                                 }
+                             }
+
                              let is_audio = preset.preset_type == "audio";
                              let is_video = preset.preset_type == "video";
                              let is_screenshot = preset.preset_type == "screenshot";
+                             let is_replace_selection = preset.preset_type == "image" && preset.capture_mode == REPLACE_SELECTION_MODE;
 
                              // --- VIDEO PLACEHOLDER UI ---
                              if is_video {
@@ -1134,6 +2188,68 @@ impl eframe::App for SettingsApp {
                                      );
                                  });
                                  // Hide AI options for screenshot
+                             } else if is_replace_selection {
+                                 // --- REPLACE SELECTION UI (text model only, no capture) ---
+                                 ui.group(|ui| {
+                                     ui.label(egui::RichText::new(text.capture_mode_replace_selection).strong());
+                                     ui.label(text.replace_selection_hint);
+                                     ui.add_space(5.0);
+
+                                     ui.horizontal(|ui| {
+                                         ui.label(text.retranslate_to_label);
+                                         ui.add_enabled_ui(!preset.retranslate_to_ui_language, |ui| {
+                                             ui.menu_button(preset.retranslate_target(&self.config.ui_language), |ui| {
+                                                 ui.style_mut().wrap = Some(false);
+                                                 ui.set_min_width(150.0);
+                                                 ui.add(egui::TextEdit::singleline(&mut self.search_query).hint_text(text.search_placeholder));
+                                                 let q = self.search_query.to_lowercase();
+                                                 egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                                                     for lang in get_all_languages().iter() {
+                                                         if q.is_empty() || lang.to_lowercase().contains(&q) {
+                                                             if ui.button(lang).clicked() {
+                                                                 preset.retranslate_to = lang.clone();
+                                                                 preset_changed = true;
+                                                                 ui.close_menu();
+                                                             }
+                                                         }
+                                                     }
+                                                 });
+                                             });
+                                         });
+                                     });
+                                     if app_language_memory_ui(ui, &text, &mut preset) { preset_changed = true; }
+
+                                     ui.horizontal(|ui| {
+                                         ui.label(text.retranslate_model_label);
+                                         let text_display_label = get_model_by_id(&preset.retranslate_model)
+                                             .map(|m| match self.config.ui_language.as_str() {
+                                                 "vi" => m.name_vi,
+                                                 "ko" => m.name_ko,
+                                                 _ => m.name_en,
+                                             })
+                                             .unwrap_or_else(|| preset.retranslate_model.clone());
+                                         egui::ComboBox::from_id_source("replace_text_model_selector")
+                                             .selected_text(text_display_label)
+                                             .show_ui(ui, |ui| {
+                                                 for model in get_all_models() {
+                                                     if model.enabled && model.model_type == ModelType::Text {
+                                                         let dropdown_label = format!("{} ({}) - {}",
+                                                             match self.config.ui_language.as_str() {
+                                                                 "vi" => &model.name_vi,
+                                                                 "ko" => &model.name_ko,
+                                                                 _ => &model.name_en,
+                                                             },
+                                                             model.full_name,
+                                                             model.quota_limit
+                                                         );
+                                                         if ui.selectable_value(&mut preset.retranslate_model, model.id.clone(), dropdown_label).clicked() {
+                                                             preset_changed = true;
+                                                         }
+                                                     }
+                                                 }
+                                             });
+                                     });
+                                 });
                              } else {
                                  // STANDARD UI (Image/Audio)
                                  
@@ -1162,6 +2278,9 @@ impl eframe::App for SettingsApp {
                                             }
                                             preset_changed = true;
                                         }
+                                        if ui.button(text.preview_prompt_btn).clicked() {
+                                            self.prompt_preview = Some(crate::overlay::process::preview_prompt(&preset, text.preview_prompt_sample_question));
+                                        }
                                     });
                                     
                                     if ui.add(egui::TextEdit::multiline(&mut preset.prompt).desired_rows(3).desired_width(f32::INFINITY)).changed() {
@@ -1172,6 +2291,16 @@ impl eframe::App for SettingsApp {
                                     if preset.prompt.trim().is_empty() {
                                         ui.colored_label(egui::Color32::RED, text.empty_prompt_warning);
                                     }
+                                    let unmapped = unmapped_language_tags(&preset.prompt, &preset.language_vars);
+                                    if !unmapped.is_empty() {
+                                        let tags: Vec<String> = unmapped.iter().map(|k| format!("{{{}}}", k)).collect();
+                                        ui.colored_label(egui::Color32::from_rgb(230, 160, 40), format!("{} {}", text.unmapped_language_warning, tags.join(", ")));
+                                    }
+                                    if preset.preset_type == "image" {
+                                        if ui.checkbox(&mut preset.include_context, text.include_context_label).on_hover_text(text.include_context_tooltip).clicked() {
+                                            preset_changed = true;
+                                        }
+                                    }
                                     
                                     // ... (existing language tag selectors logic) ...
                                     let mut detected_langs = Vec::new();
@@ -1214,6 +2343,45 @@ impl eframe::App for SettingsApp {
                                             });
                                         });
                                     }
+
+                                    // Languages the cycle hotkey steps {language1} through
+                                    if preset.prompt.contains("{language1}") {
+                                        ui.horizontal_wrapped(|ui| {
+                                            ui.label(text.language_cycle_label).on_hover_text(text.language_cycle_tooltip);
+                                            let mut cycle_to_remove = None;
+                                            for (c_idx, lang) in preset.language_cycle.iter().enumerate() {
+                                                ui.label(format!("{}.", c_idx + 1));
+                                                ui.label(lang);
+                                                if ui.small_button("x").clicked() {
+                                                    cycle_to_remove = Some(c_idx);
+                                                }
+                                            }
+                                            if let Some(c_idx) = cycle_to_remove {
+                                                preset.language_cycle.remove(c_idx);
+                                                preset_changed = true;
+                                            }
+                                            ui.menu_button(text.language_cycle_add, |ui| {
+                                                ui.style_mut().wrap = Some(false);
+                                                ui.set_min_width(150.0);
+                                                ui.add(egui::TextEdit::singleline(&mut self.search_query).hint_text(text.search_placeholder));
+                                                let q = self.search_query.to_lowercase();
+                                                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                                                    for lang in get_all_languages().iter() {
+                                                        if preset.language_cycle.contains(lang) {
+                                                            continue;
+                                                        }
+                                                        if q.is_empty() || lang.to_lowercase().contains(&q) {
+                                                            if ui.button(lang).clicked() {
+                                                                preset.language_cycle.push(lang.clone());
+                                                                preset_changed = true;
+                                                                ui.close_menu();
+                                                            }
+                                                        }
+                                                    }
+                                                });
+                                            });
+                                        });
+                                    }
                                 });
                             }
 
@@ -1229,22 +2397,125 @@ impl eframe::App for SettingsApp {
                                         if ui.radio_value(&mut preset.audio_source, "device".to_string(), text.audio_src_device).clicked() {
                                             preset_changed = true;
                                         }
+                                        if ui.radio_value(&mut preset.audio_source, "both".to_string(), text.audio_src_both).clicked() {
+                                            preset_changed = true;
+                                        }
                                         if ui.checkbox(&mut preset.hide_recording_ui, text.hide_recording_ui_label).clicked() {
                                             preset_changed = true;
                                         }
+                                        if ui.checkbox(&mut preset.optimize_audio_upload, text.optimize_audio_upload_label).on_hover_text(text.optimize_audio_upload_tooltip).clicked() {
+                                            preset_changed = true;
+                                        }
                                         // NEW: Live Mode Checkbox
                                         if ui.checkbox(&mut preset.live_mode, "Chế độ hội thoại (Live)").on_hover_text("Ghi âm và dịch liên tục (Beta)").clicked() {
                                             preset_changed = true;
                                         }
                                         // Skip Frames toggle (only show when Live Mode is enabled)
                                         if preset.live_mode {
+                                            if ui.checkbox(&mut preset.use_gemini_live, text.use_gemini_live_label)
+                                                .on_hover_text(text.use_gemini_live_tooltip)
+                                                .clicked() {
+                                                preset_changed = true;
+                                            }
                                             if ui.checkbox(&mut preset.skip_frames, "Nhảy cóc (Skip Frames)")
                                                 .on_hover_text("Bỏ qua các đoạn audio cũ để bám sát thời gian thực. Tắt nếu muốn dịch đầy đủ.")
                                                 .clicked() {
                                                 preset_changed = true;
                                             }
+                                            if ui.checkbox(&mut preset.show_live_stats, text.show_live_stats_label)
+                                                .on_hover_text(text.show_live_stats_tooltip)
+                                                .clicked() {
+                                                preset_changed = true;
+                                            }
+                                            if !preset.use_gemini_live {
+                                                ui.horizontal(|ui| {
+                                                    ui.label(text.live_chunk_secs_label);
+                                                    if ui.add(egui::Slider::new(&mut preset.live_chunk_secs, 1.0..=8.0).step_by(0.5).suffix(" s"))
+                                                        .on_hover_text(text.live_chunk_secs_tooltip).changed() {
+                                                        preset_changed = true;
+                                                    }
+                                                });
+                                                ui.horizontal(|ui| {
+                                                    ui.label(text.live_chunk_overlap_label);
+                                                    if ui.add(egui::Slider::new(&mut preset.live_chunk_overlap_ms, 0..=1000).step_by(50.0).suffix(" ms"))
+                                                        .on_hover_text(text.live_chunk_overlap_tooltip).changed() {
+                                                        preset_changed = true;
+                                                    }
+                                                });
+                                            }
                                         }
                                     });
+                                    if preset.audio_source != "mic" {
+                                        ui.horizontal(|ui| {
+                                            ui.label(text.loopback_device_label).on_hover_text(text.loopback_device_tooltip);
+                                            egui::ComboBox::from_id_source("loopback_device_combo")
+                                                .selected_text(preset.loopback_device.clone().unwrap_or_else(|| text.loopback_device_default.to_string()))
+                                                .show_ui(ui, |ui| {
+                                                    if ui.selectable_value(&mut preset.loopback_device, None, text.loopback_device_default).clicked() {
+                                                        preset_changed = true;
+                                                    }
+                                                    for name in self.cached_output_devices.lock().unwrap().iter() {
+                                                        if ui.selectable_value(&mut preset.loopback_device, Some(name.clone()), name).clicked() {
+                                                            preset_changed = true;
+                                                        }
+                                                    }
+                                                });
+                                            if icon_button(ui, Icon::Refresh).on_hover_text(text.loopback_device_refresh).clicked() {
+                                                Self::list_output_devices_async(self.cached_output_devices.clone(), ui.ctx().clone());
+                                            }
+                                        });
+                                        // Empty until the background listing is done
+                                        let devices = self.cached_output_devices.lock().unwrap();
+                                        if !devices.is_empty() && preset.loopback_device.as_ref().map_or(false, |name| !devices.contains(name)) {
+                                            ui.colored_label(egui::Color32::from_rgb(230, 150, 40), text.loopback_device_missing);
+                                        }
+                                    }
+
+                                    ui.horizontal(|ui| {
+                                        ui.label(text.max_recording_label);
+                                        if ui.add(egui::DragValue::new(&mut preset.max_recording_secs).clamp_range(0..=7200).speed(10.0).suffix(" s"))
+                                            .on_hover_text(text.max_recording_tooltip).changed() {
+                                            preset_changed = true;
+                                        }
+                                    });
+
+                                    // Whisper-only options (Groq)
+                                    if get_model_by_id(&preset.model).map_or(false, |m| m.provider == "groq") {
+                                        ui.horizontal(|ui| {
+                                            ui.label(text.whisper_language_label).on_hover_text(text.whisper_language_tooltip);
+                                            let current = preset.whisper_language.as_deref()
+                                                .and_then(whisper_language_name)
+                                                .unwrap_or(text.whisper_language_auto);
+                                            ui.add_enabled_ui(!preset.whisper_translate, |ui| {
+                                                egui::ComboBox::from_id_source("whisper_language")
+                                                    .selected_text(current)
+                                                    .height(300.0)
+                                                    .show_ui(ui, |ui| {
+                                                        if ui.selectable_value(&mut preset.whisper_language, None, text.whisper_language_auto).clicked() {
+                                                            preset_changed = true;
+                                                        }
+                                                        for (name, code) in WHISPER_LANGUAGES {
+                                                            if ui.selectable_value(&mut preset.whisper_language, Some(code.to_string()), *name).clicked() {
+                                                                preset_changed = true;
+                                                            }
+                                                        }
+                                                    });
+                                            });
+                                            if ui.checkbox(&mut preset.whisper_translate, text.whisper_translate_label).on_hover_text(text.whisper_translate_tooltip).clicked() {
+                                                preset_changed = true;
+                                            }
+                                        });
+                                    }
+
+                                    if ui.button(text.open_audio_file_btn).on_hover_text(text.open_audio_file_tooltip).clicked() {
+                                        let preset_for_file = preset.clone();
+                                        // The file dialog blocks, keep it off the UI thread
+                                        std::thread::spawn(move || {
+                                            if let Some(path) = crate::audio_file::pick_audio_file() {
+                                                crate::audio_file::transcribe_file(preset_for_file, path);
+                                            }
+                                        });
+                                    }
                                 });
                             }
 
@@ -1320,6 +2591,11 @@ impl eframe::App for SettingsApp {
                                                                  .clicked() {
                                                                  preset_changed = true;
                                                              }
+                                                             if ui.checkbox(&mut preset.show_live_stats, text.show_live_stats_label)
+                                                                 .on_hover_text(text.show_live_stats_tooltip)
+                                                                 .clicked() {
+                                                                 preset_changed = true;
+                                                             }
                                                              // Capture interval slider
                                                              ui.horizontal(|ui| {
                                                                  ui.label("Độ trễ chụp:");
@@ -1333,22 +2609,356 @@ impl eframe::App for SettingsApp {
                                                                      preset_changed = true;
                                                                  }
                                                              });
+                                                             ui.horizontal(|ui| {
+                                                                 ui.label(text.dedup_history_label);
+                                                                 if ui.add(egui::Slider::new(&mut preset.dedup_history, 1..=20))
+                                                                     .on_hover_text(text.dedup_history_tooltip).changed() {
+                                                                     preset_changed = true;
+                                                                 }
+                                                             });
+                                                             let mut follow_window = preset.live_follow_window.is_some();
+                                                             if ui.checkbox(&mut follow_window, text.live_follow_window_label).on_hover_text(text.live_follow_window_tooltip).clicked() {
+                                                                 preset.live_follow_window = if follow_window { Some(String::new()) } else { None };
+                                                                 preset_changed = true;
+                                                             }
+                                                             if let Some(pattern) = preset.live_follow_window.as_mut() {
+                                                                 ui.horizontal(|ui| {
+                                                                     ui.label(text.live_follow_window_match_label);
+                                                                     if ui.add(egui::TextEdit::singleline(pattern).hint_text(text.live_follow_window_hint).desired_width(200.0)).changed() {
+                                                                         preset_changed = true;
+                                                                     }
+                                                                 });
+                                                             }
+                                                         } else {
+                                                             let mut multi_frame = preset.multi_frame_capture.is_some();
+                                                             if ui.checkbox(&mut multi_frame, text.multi_frame_label).on_hover_text(text.multi_frame_tooltip).clicked() {
+                                                                 preset.multi_frame_capture = if multi_frame { Some(5) } else { None };
+                                                                 preset_changed = true;
+                                                             }
+                                                             if let Some(frames) = preset.multi_frame_capture.as_mut() {
+                                                                 ui.horizontal(|ui| {
+                                                                     ui.label(text.multi_frame_count_label);
+                                                                     if ui.add(egui::Slider::new(frames, 2..=8)).changed() {
+                                                                         preset_changed = true;
+                                                                     }
+                                                                 });
+                                                             }
+                                                             // Chat answers don't come back empty the same way
+                                                             if !preset.enable_chat_mode {
+                                                                 let mut expand = preset.expand_on_empty.is_some();
+                                                                 if ui.checkbox(&mut expand, text.expand_on_empty_label).on_hover_text(text.expand_on_empty_tooltip).clicked() {
+                                                                     preset.expand_on_empty = if expand { Some(24) } else { None };
+                                                                     preset_changed = true;
+                                                                 }
+                                                                 if let Some(margin) = preset.expand_on_empty.as_mut() {
+                                                                     ui.horizontal(|ui| {
+                                                                         ui.label(text.expand_on_empty_margin_label);
+                                                                         if ui.add(egui::Slider::new(margin, 8..=200).suffix(" px")).changed() {
+                                                                             preset_changed = true;
+                                                                         }
+                                                                     });
+                                                                 }
+                                                             }
+                                                         }
+                                                         if ui.checkbox(&mut preset.preserve_layout, text.preserve_layout_label).on_hover_text(text.preserve_layout_tooltip).clicked() {
+                                                             preset_changed = true;
+                                                         }
+                                                         ui.horizontal(|ui| {
+                                                             ui.label(text.reading_order_label).on_hover_text(text.reading_order_tooltip);
+                                                             egui::ComboBox::from_id_source("reading_order_combo")
+                                                                 .selected_text(reading_order_name(&text, preset.reading_order))
+                                                                 .show_ui(ui, |ui| {
+                                                                     for order in crate::config::ReadingOrder::ALL {
+                                                                         if ui.selectable_value(&mut preset.reading_order, order, reading_order_name(&text, order)).clicked() {
+                                                                             preset_changed = true;
+                                                                         }
+                                                                     }
+                                                                 });
+                                                         });
+                                                         if !preset.live_mode && !preset.hide_overlay {
+                                                             ui.horizontal(|ui| {
+                                                                 ui.label(text.compare_model_label).on_hover_text(text.compare_model_tooltip);
+                                                                 let compare_label = preset.compare_model.as_deref().and_then(get_model_by_id)
+                                                                     .map(|m| m.full_name)
+                                                                     .unwrap_or_else(|| text.compare_model_off.to_string());
+                                                                 egui::ComboBox::from_id_source("compare_model_selector")
+                                                                     .selected_text(compare_label)
+                                                                     .show_ui(ui, |ui| {
+                                                                         if ui.selectable_value(&mut preset.compare_model, None, text.compare_model_off).clicked() {
+                                                                             preset_changed = true;
+                                                                         }
+                                                                         for model in get_all_models() {
+                                                                             if model.enabled && model.model_type == ModelType::Vision && model.id != preset.model {
+                                                                                 if ui.selectable_value(&mut preset.compare_model, Some(model.id.clone()), &model.full_name).clicked() {
+                                                                                     preset_changed = true;
+                                                                                 }
+                                                                             }
+                                                                         }
+                                                                     });
+                                                             });
+                                                         }
+                                                         if ui.checkbox(&mut preset.ocr_quality_check, text.ocr_quality_check_label).on_hover_text(text.ocr_quality_check_tooltip).clicked() {
+                                                             preset_changed = true;
+                                                         }
+                                                         if preset.preset_type == "chat" || preset.enable_chat_mode {
+                                                             if ui.checkbox(&mut preset.keep_chat_image, text.keep_chat_image_label).on_hover_text(text.keep_chat_image_tooltip).clicked() {
+                                                                 preset_changed = true;
+                                                             }
                                                          }
                                                      }
                                                     });
 
+                                // Advanced: sampling of this preset's vision request, plus Gemini settings shared per model
+                                if !is_audio {
+                                    egui::CollapsingHeader::new(text.advanced_model_params)
+                                        .id_source("advanced_model_params")
+                                        .show(ui, |ui| {
+                                            ui.horizontal(|ui| {
+                                                ui.label(text.temperature_label);
+                                                if ui.add(egui::Slider::new(&mut preset.temperature, 0.0..=2.0).step_by(0.05)).changed() {
+                                                    preset_changed = true;
+                                                }
+                                            });
+                                            ui.horizontal(|ui| {
+                                                ui.label(text.max_output_tokens_label);
+                                                if ui.add(egui::DragValue::new(&mut preset.max_tokens).clamp_range(64..=32768).speed(16.0))
+                                                    .on_hover_text(text.max_tokens_tooltip).changed() {
+                                                    preset_changed = true;
+                                                }
+                                            });
+                                            let Some(model) = get_model_by_id(&preset.model).filter(|m| m.provider == "google") else { return; };
+                                            ui.separator();
+                                            ui.label(egui::RichText::new(format!("{} ({})", text.model_params_title, model.full_name)).strong());
+                                            ui.label(egui::RichText::new(text.advanced_model_params_hint).small().weak());
+                                            let mut params = self.config.model_params.get(&model.full_name).cloned().unwrap_or_default();
+                                            let before = params.clone();
+                                            ui.horizontal(|ui| {
+                                                let mut on = params.temperature.is_some();
+                                                if ui.checkbox(&mut on, text.temperature_label).changed() {
+                                                    params.temperature = on.then_some(1.0);
+                                                }
+                                                if let Some(t) = params.temperature.as_mut() {
+                                                    ui.add(egui::Slider::new(t, 0.0..=2.0).step_by(0.05));
+                                                }
+                                            });
+                                            ui.horizontal(|ui| {
+                                                let mut on = params.max_output_tokens.is_some();
+                                                if ui.checkbox(&mut on, text.max_output_tokens_label).changed() {
+                                                    params.max_output_tokens = on.then_some(2048);
+                                                }
+                                                if let Some(m) = params.max_output_tokens.as_mut() {
+                                                    ui.add(egui::DragValue::new(m).clamp_range(1..=65536).speed(16.0));
+                                                }
+                                            });
+                                            if let Some((min, max)) = crate::model_config::thinking_budget_range(&model.full_name) {
+                                                ui.horizontal(|ui| {
+                                                    let mut on = params.thinking_budget.is_some();
+                                                    if ui.checkbox(&mut on, text.thinking_budget_label).on_hover_text(text.thinking_budget_tooltip).changed() {
+                                                        params.thinking_budget = on.then_some(-1);
+                                                    }
+                                                    if let Some(b) = params.thinking_budget.as_mut() {
+                                                        ui.add(egui::DragValue::new(b).clamp_range(-1..=max).speed(64.0));
+                                                        if *b >= 0 && *b < min { *b = min; }
+                                                    }
+                                                });
+                                            }
+                                            if params != before {
+                                                if params == crate::config::GenerationParams::default() {
+                                                    self.config.model_params.remove(&model.full_name);
+                                                } else {
+                                                    self.config.model_params.insert(model.full_name.clone(), params);
+                                                }
+                                                preset_changed = true;
+                                            }
+                                        });
+                                }
+
                                 // Auto copy + Hide overlay on same line
                                 ui.horizontal(|ui| {
                                     if ui.checkbox(&mut preset.auto_copy, text.auto_copy_label).clicked() {
                                         preset_changed = true;
-                                        if preset.auto_copy { preset.retranslate_auto_copy = false; }
+                                        if preset.auto_copy { preset.retranslate_auto_copy = false; }
+                                    }
+                                    if preset.auto_copy {
+                                        if ui.checkbox(&mut preset.hide_overlay, text.hide_overlay_label).clicked() {
+                                            preset_changed = true;
+                                        }
+                                    }
+                                });
+                                // Nothing else shows that a hidden preset is done
+                                if preset.hide_overlay {
+                                    ui.horizontal(|ui| {
+                                        if ui.checkbox(&mut preset.completion_sound, text.completion_sound_label).on_hover_text(text.completion_tooltip).clicked() {
+                                            preset_changed = true;
+                                        }
+                                        if ui.checkbox(&mut preset.completion_toast, text.completion_toast_label).on_hover_text(text.completion_tooltip).clicked() {
+                                            preset_changed = true;
+                                        }
+                                    });
+                                }
+                                // When the copy happens (also used by the retranslation's auto copy)
+                                if preset.auto_copy || preset.retranslate_auto_copy {
+                                    ui.horizontal(|ui| {
+                                        ui.label(text.auto_copy_delay_label).on_hover_text(text.auto_copy_delay_tooltip);
+                                        if ui.add_enabled(!preset.copy_on_close || preset.hide_overlay, egui::DragValue::new(&mut preset.auto_copy_delay_ms).clamp_range(0..=5000).speed(10.0).suffix(" ms")).changed() {
+                                            preset_changed = true;
+                                        }
+                                        if !preset.hide_overlay && preset.output_mode == crate::config::OutputMode::Clipboard {
+                                            if ui.checkbox(&mut preset.copy_on_close, text.copy_on_close_label).on_hover_text(text.copy_on_close_tooltip).clicked() {
+                                                preset_changed = true;
+                                            }
+                                        }
+                                    });
+                                    ui.horizontal(|ui| {
+                                        ui.label(text.output_mode_label).on_hover_text(text.output_mode_tooltip);
+                                        egui::ComboBox::from_id_source("output_mode_selector")
+                                            .selected_text(output_mode_name(&text, preset.output_mode))
+                                            .show_ui(ui, |ui| {
+                                                for mode in crate::config::OutputMode::ALL {
+                                                    if ui.selectable_value(&mut preset.output_mode, mode, output_mode_name(&text, mode)).clicked() {
+                                                        preset_changed = true;
+                                                    }
+                                                }
+                                            });
+                                        if preset.output_mode == crate::config::OutputMode::TypeOut {
+                                            ui.label(text.type_out_delay_label).on_hover_text(text.type_out_delay_tooltip);
+                                            if ui.add(egui::DragValue::new(&mut preset.type_out_delay_ms).clamp_range(0..=1000).speed(1.0).suffix(" ms")).changed() {
+                                                preset_changed = true;
+                                            }
+                                        }
+                                    });
+                                }
+                                // Wrapping of copied text (copy button, right click, auto copy)
+                                if !preset.live_mode {
+                                    ui.horizontal(|ui| {
+                                        ui.label(text.copy_format_label).on_hover_text(text.copy_format_tooltip);
+                                        egui::ComboBox::from_id_source("copy_format_selector")
+                                            .selected_text(copy_format_name(&text, preset.copy_format))
+                                            .show_ui(ui, |ui| {
+                                                for format in crate::config::CopyFormat::ALL {
+                                                    if ui.selectable_value(&mut preset.copy_format, format, copy_format_name(&text, format)).clicked() {
+                                                        preset_changed = true;
+                                                    }
+                                                }
+                                            });
+                                    });
+                                }
+                                if !preset.hide_overlay && !preset.live_mode {
+                                    if ui.checkbox(&mut preset.dismiss_on_outside_click, text.dismiss_on_outside_click_label).on_hover_text(text.dismiss_on_outside_click_tooltip).clicked() {
+                                        preset_changed = true;
                                     }
-                                    if preset.auto_copy {
+                                }
+                                // Result window font; empty picks one covering the text's script
+                                if !preset.hide_overlay {
+                                    ui.horizontal(|ui| {
+                                        ui.label(text.font_family_label).on_hover_text(text.font_family_tooltip);
+                                        let mut family = preset.font_family.clone().unwrap_or_default();
+                                        if ui.add(egui::TextEdit::singleline(&mut family).hint_text(text.font_family_auto).desired_width(160.0)).changed() {
+                                            preset.font_family = Some(family).filter(|f| !f.trim().is_empty());
+                                            preset_changed = true;
+                                        }
+                                    });
+                                }
+                                // Chat answers are kept as the model wrote them
+                                if !preset.live_mode && preset.preset_type != "chat" && !preset.enable_chat_mode {
+                                    if ui.checkbox(&mut preset.strip_preamble, text.strip_preamble_label).on_hover_text(text.strip_preamble_tooltip).clicked() {
+                                        preset_changed = true;
+                                    }
+                                }
+                                if !preset.live_mode && preset.preset_type == "image" && !preset.enable_chat_mode {
+                                    if ui.checkbox(&mut preset.study_layout, text.study_layout_label).on_hover_text(text.study_layout_tooltip).clicked() {
+                                        preset_changed = true;
+                                    }
+                                }
+                                // Image results only; a preserved layout keeps its line breaks by definition, a study card has its own sections
+                                if !preset.live_mode && preset.preset_type == "image" && !preset.enable_chat_mode && !preset.preserve_layout && !preset.study_layout {
+                                    ui.horizontal(|ui| {
+                                        if ui.checkbox(&mut preset.reflow_paragraphs, text.reflow_paragraphs_label).on_hover_text(text.reflow_paragraphs_tooltip).clicked() {
+                                            preset_changed = true;
+                                        }
+                                        if preset.reflow_paragraphs && ui.checkbox(&mut preset.reflow_dehyphenate, text.reflow_dehyphenate_label).on_hover_text(text.reflow_dehyphenate_tooltip).clicked() {
+                                            preset_changed = true;
+                                        }
+                                    });
+                                }
+
+                                // Notes file every result is appended to
+                                if !preset.live_mode {
+                                    ui.horizontal(|ui| {
+                                        ui.label(text.output_file_label).on_hover_text(text.output_file_tooltip);
+                                        match preset.output_file.clone() {
+                                            Some(path) => {
+                                                let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+                                                ui.label(egui::RichText::new(name).small()).on_hover_text(path.display().to_string());
+                                                if ui.small_button("✕").clicked() {
+                                                    preset.output_file = None;
+                                                    preset_changed = true;
+                                                }
+                                            }
+                                            None => { ui.label(egui::RichText::new(text.output_file_none).small().weak()); }
+                                        }
+                                        if ui.small_button(text.output_file_browse).clicked() {
+                                            let slot = self.picked_output_file.clone();
+                                            let current = preset.output_file.clone();
+                                            let ctx = ctx.clone();
+                                            // The file dialog blocks, keep it off the UI thread
+                                            std::thread::spawn(move || {
+                                                if let Some(path) = crate::notes_file::pick_notes_file(current.as_deref()) {
+                                                    *slot.lock().unwrap() = Some((idx, path));
+                                                    ctx.request_repaint();
+                                                }
+                                            });
+                                        }
+                                    });
+                                    // Auto copy shows this one already
+                                    if preset.output_file.is_some() && !preset.auto_copy {
                                         if ui.checkbox(&mut preset.hide_overlay, text.hide_overlay_label).clicked() {
                                             preset_changed = true;
                                         }
                                     }
-                                });
+
+                                    // Webhook: every result POSTed as JSON
+                                    egui::CollapsingHeader::new(text.webhook_header).id_source("webhook_section").show(ui, |ui| {
+                                        ui.horizontal(|ui| {
+                                            ui.label(text.webhook_url_label);
+                                            if ui.add(egui::TextEdit::singleline(&mut preset.webhook.url).hint_text("https://…").desired_width(260.0)).changed() {
+                                                preset_changed = true;
+                                            }
+                                        });
+                                        ui.label(text.webhook_template_label).on_hover_text(text.webhook_template_tooltip);
+                                        if ui.add(egui::TextEdit::multiline(&mut preset.webhook.template).code_editor().desired_rows(3).desired_width(f32::INFINITY)).changed() {
+                                            preset_changed = true;
+                                        }
+                                        ui.label(text.webhook_headers_label);
+                                        let mut remove_header = None;
+                                        for (i, header) in preset.webhook.headers.iter_mut().enumerate() {
+                                            ui.horizontal(|ui| {
+                                                if ui.add(egui::TextEdit::singleline(&mut header.name).hint_text("Authorization").desired_width(110.0)).changed() {
+                                                    preset_changed = true;
+                                                }
+                                                if ui.add(egui::TextEdit::singleline(&mut header.value).hint_text("Bearer …").password(true).desired_width(180.0)).changed() {
+                                                    preset_changed = true;
+                                                }
+                                                if ui.small_button("✕").clicked() {
+                                                    remove_header = Some(i);
+                                                }
+                                            });
+                                        }
+                                        if let Some(i) = remove_header {
+                                            preset.webhook.headers.remove(i);
+                                            preset_changed = true;
+                                        }
+                                        ui.horizontal(|ui| {
+                                            if ui.small_button(text.webhook_add_header).clicked() {
+                                                preset.webhook.headers.push(crate::config::WebhookHeader::default());
+                                                preset_changed = true;
+                                            }
+                                            if ui.add_enabled(!preset.webhook.url.trim().is_empty(), egui::Button::new(text.webhook_test_btn)).clicked() {
+                                                crate::webhook::send_test(preset.webhook.clone(), &preset.name);
+                                            }
+                                        });
+                                    });
+                                }
                             });
 
                             // 4. Retranslate (Shared)
@@ -1365,28 +2975,43 @@ impl eframe::App for SettingsApp {
                                         
                                         if preset.retranslate {
                                             ui.label(text.retranslate_to_label);
-                                            let retrans_label = preset.retranslate_to.clone();
-                                            ui.menu_button(retrans_label, |ui| {
-                                                ui.style_mut().wrap = Some(false);
-                                                ui.set_min_width(150.0);
-                                                ui.add(egui::TextEdit::singleline(&mut self.search_query).hint_text(text.search_placeholder));
-                                                let q = self.search_query.to_lowercase();
-                                                egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
-                                                    for lang in get_all_languages().iter() {
-                                                        if q.is_empty() || lang.to_lowercase().contains(&q) {
-                                                            if ui.button(lang).clicked() {
-                                                                preset.retranslate_to = lang.clone();
-                                                                preset_changed = true;
-                                                                ui.close_menu();
+                                            let retrans_label = if preset.retranslates_to_detected() {
+                                                text.retranslate_same_as_detected_label.to_string()
+                                            } else {
+                                                preset.retranslate_target(&self.config.ui_language)
+                                            };
+                                            // Only the Translate preset's answers name the source language, and only when not streamed
+                                            let offers_detected = preset.id == "preset_translate" && !preset.streaming_enabled && !preset.live_mode;
+                                            ui.add_enabled_ui(!preset.retranslate_to_ui_language, |ui| {
+                                                ui.menu_button(retrans_label, |ui| {
+                                                    ui.style_mut().wrap = Some(false);
+                                                    ui.set_min_width(150.0);
+                                                    ui.add(egui::TextEdit::singleline(&mut self.search_query).hint_text(text.search_placeholder));
+                                                    let q = self.search_query.to_lowercase();
+                                                    if offers_detected && ui.button(text.retranslate_same_as_detected_label).on_hover_text(text.retranslate_same_as_detected_tooltip).clicked() {
+                                                        preset.retranslate_to = crate::config::SAME_AS_DETECTED.to_string();
+                                                        preset_changed = true;
+                                                        ui.close_menu();
+                                                    }
+                                                    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                                                        for lang in get_all_languages().iter() {
+                                                            if q.is_empty() || lang.to_lowercase().contains(&q) {
+                                                                if ui.button(lang).clicked() {
+                                                                    preset.retranslate_to = lang.clone();
+                                                                    preset_changed = true;
+                                                                    ui.close_menu();
+                                                                }
                                                             }
                                                         }
-                                                    }
+                                                    });
                                                 });
                                             });
                                         }
                                     });
 
                                     if preset.retranslate {
+                                        if app_language_memory_ui(ui, &text, &mut preset) { preset_changed = true; }
+
                                         // Text Model Selector + Auto Copy on same line
                                         ui.horizontal(|ui| {
                                             ui.label(text.retranslate_model_label);
@@ -1440,6 +3065,78 @@ impl eframe::App for SettingsApp {
                                                          if ui.selectable_value(&mut preset.retranslate_streaming_enabled, true, text.streaming_option_stream).clicked() { preset_changed = true; }
                                                      });
                                              });
+                                             // Early retranslation needs the vision text to stream in
+                                             if !is_audio && preset.streaming_enabled {
+                                                 if ui.checkbox(&mut preset.early_retranslate, text.early_retranslate_label).clicked() { preset_changed = true; }
+                                             }
+                                            }
+
+                                            // Per-segment translation for the translated SRT (Whisper models return timings)
+                                            if is_audio && !preset.live_mode {
+                                                if ui.checkbox(&mut preset.retranslate_srt, text.retranslate_srt_label).clicked() { preset_changed = true; }
+                                            }
+
+                                            // Chained translation into a third language (image presets only)
+                                            if !is_audio {
+                                             ui.horizontal(|ui| {
+                                                 let mut chain_enabled = preset.retranslate_secondary_to.is_some();
+                                                 if ui.checkbox(&mut chain_enabled, text.retranslate_secondary_checkbox).clicked() {
+                                                     preset.retranslate_secondary_to = if chain_enabled { Some("English".to_string()) } else { None };
+                                                     preset_changed = true;
+                                                 }
+                                                 if let Some(chain_label) = preset.retranslate_secondary_to.clone() {
+                                                     ui.menu_button(chain_label, |ui| {
+                                                         ui.style_mut().wrap = Some(false);
+                                                         ui.set_min_width(150.0);
+                                                         ui.add(egui::TextEdit::singleline(&mut self.search_query).hint_text(text.search_placeholder));
+                                                         let q = self.search_query.to_lowercase();
+                                                         egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+                                                             for lang in get_all_languages().iter() {
+                                                                 if q.is_empty() || lang.to_lowercase().contains(&q) {
+                                                                     if ui.button(lang).clicked() {
+                                                                         preset.retranslate_secondary_to = Some(lang.clone());
+                                                                         preset_changed = true;
+                                                                         ui.close_menu();
+                                                                     }
+                                                                 }
+                                                             }
+                                                         });
+                                                     });
+                                                 }
+                                             });
+
+                                             if preset.retranslate_secondary_to.is_some() {
+                                                 ui.horizontal(|ui| {
+                                                     ui.label(text.retranslate_secondary_model_label);
+                                                     let chain_model_label = get_model_by_id(&preset.retranslate_secondary_model)
+                                                         .map(|m| match self.config.ui_language.as_str() {
+                                                             "vi" => m.name_vi,
+                                                             "ko" => m.name_ko,
+                                                             _ => m.name_en,
+                                                         })
+                                                         .unwrap_or_else(|| preset.retranslate_secondary_model.clone());
+                                                     egui::ComboBox::from_id_source("text_model_selector_secondary")
+                                                         .selected_text(chain_model_label)
+                                                         .show_ui(ui, |ui| {
+                                                             for model in get_all_models() {
+                                                                 if model.enabled && model.model_type == ModelType::Text {
+                                                                     let dropdown_label = format!("{} ({}) - {}", 
+                                                                         match self.config.ui_language.as_str() {
+                                                                             "vi" => &model.name_vi,
+                                                                             "ko" => &model.name_ko,
+                                                                             _ => &model.name_en,
+                                                                         },
+                                                                         model.full_name,
+                                                                         model.quota_limit
+                                                                     );
+                                                                     if ui.selectable_value(&mut preset.retranslate_secondary_model, model.id.clone(), dropdown_label).clicked() {
+                                                                         preset_changed = true;
+                                                                     }
+                                                                 }
+                                                             }
+                                                         });
+                                                 });
+                                             }
                                             }
                                             }
                                             });
@@ -1465,11 +3162,11 @@ impl eframe::App for SettingsApp {
                                        preset_changed = true;
                                    }
 
-                                   if self.recording_hotkey_for_preset == Some(idx) {
+                                   if self.recording_hotkey == Some(HotkeyTarget::Preset(idx)) {
                                        ui.horizontal(|ui| {
                                            ui.colored_label(egui::Color32::YELLOW, text.press_keys);
                                            if ui.button(text.cancel_label).clicked() {
-                                               self.recording_hotkey_for_preset = None;
+                                               self.recording_hotkey = None;
                                                self.hotkey_conflict_msg = None;
                                            }
                                        });
@@ -1478,7 +3175,64 @@ impl eframe::App for SettingsApp {
                                        }
                                    } else {
                                        if ui.button(text.add_hotkey_button).clicked() {
-                                           self.recording_hotkey_for_preset = Some(idx);
+                                           self.recording_hotkey = Some(HotkeyTarget::Preset(idx));
+                                           self.hotkey_conflict_msg = None;
+                                       }
+                                   }
+
+                                   if crate::overlay::last_capture::supports_rerun(&preset) {
+                                       ui.add_space(4.0);
+                                       ui.horizontal(|ui| {
+                                           ui.label(text.rerun_hotkey_label).on_hover_text(text.rerun_hotkey_tooltip);
+                                           if self.recording_hotkey == Some(HotkeyTarget::RerunLastCapture(idx)) {
+                                               ui.colored_label(egui::Color32::YELLOW, text.press_keys);
+                                               if ui.button(text.cancel_label).clicked() {
+                                                   self.recording_hotkey = None;
+                                                   self.hotkey_conflict_msg = None;
+                                               }
+                                           } else if let Some(hotkey) = &preset.rerun_hotkey {
+                                               ui.label(&hotkey.name);
+                                               if ui.small_button("x").clicked() {
+                                                   preset.rerun_hotkey = None;
+                                                   preset_changed = true;
+                                               }
+                                           } else if ui.button(text.add_hotkey_button).clicked() {
+                                               self.recording_hotkey = Some(HotkeyTarget::RerunLastCapture(idx));
+                                               self.hotkey_conflict_msg = None;
+                                           }
+                                       });
+                                       if self.recording_hotkey == Some(HotkeyTarget::RerunLastCapture(idx)) {
+                                           if let Some(msg) = &self.hotkey_conflict_msg {
+                                               ui.colored_label(egui::Color32::RED, msg);
+                                           }
+                                       }
+                                   }
+
+                                   if crate::overlay::language_cycle::supports_cycle(&preset) {
+                                       ui.add_space(4.0);
+                                       ui.horizontal(|ui| {
+                                           ui.label(text.cycle_language_hotkey_label).on_hover_text(text.cycle_language_hotkey_tooltip);
+                                           if self.recording_hotkey == Some(HotkeyTarget::CycleLanguage(idx)) {
+                                               ui.colored_label(egui::Color32::YELLOW, text.press_keys);
+                                               if ui.button(text.cancel_label).clicked() {
+                                                   self.recording_hotkey = None;
+                                                   self.hotkey_conflict_msg = None;
+                                               }
+                                           } else if let Some(hotkey) = &preset.cycle_language_hotkey {
+                                               ui.label(&hotkey.name);
+                                               if ui.small_button("x").clicked() {
+                                                   preset.cycle_language_hotkey = None;
+                                                   preset_changed = true;
+                                               }
+                                           } else if ui.button(text.add_hotkey_button).clicked() {
+                                               self.recording_hotkey = Some(HotkeyTarget::CycleLanguage(idx));
+                                               self.hotkey_conflict_msg = None;
+                                           }
+                                       });
+                                       if self.recording_hotkey == Some(HotkeyTarget::CycleLanguage(idx)) {
+                                           if let Some(msg) = &self.hotkey_conflict_msg {
+                                               ui.colored_label(egui::Color32::RED, msg);
+                                           }
                                        }
                                    }
                                });
@@ -1494,6 +3248,78 @@ impl eframe::App for SettingsApp {
                             }
                         }
                         
+                        ViewMode::Onboarding => {
+                            ui.add_space(10.0);
+                            ui.heading(text.onboarding_title);
+                            ui.add_space(5.0);
+                            ui.label(text.onboarding_intro);
+                            ui.add_space(8.0);
+
+                            // 1. Provider
+                            ui.group(|ui| {
+                                ui.horizontal(|ui| {
+                                    if ui.radio_value(&mut self.onboarding_provider, "groq".to_string(), "Groq").clicked() {
+                                        *self.onboarding_test.lock().unwrap() = KeyTestState::Idle;
+                                    }
+                                    if ui.link(text.get_key_link).clicked() { let _ = open::that("https://console.groq.com/keys"); }
+                                });
+                                ui.label(egui::RichText::new(text.onboarding_groq_desc).weak());
+                                ui.add_space(5.0);
+                                ui.horizontal(|ui| {
+                                    if ui.radio_value(&mut self.onboarding_provider, "google".to_string(), "Google Gemini").clicked() {
+                                        *self.onboarding_test.lock().unwrap() = KeyTestState::Idle;
+                                    }
+                                    if ui.link(text.gemini_get_key_link).clicked() { let _ = open::that("https://aistudio.google.com/app/apikey"); }
+                                });
+                                ui.label(egui::RichText::new(text.onboarding_gemini_desc).weak());
+                            });
+                            ui.add_space(8.0);
+
+                            // 2. Key + Test
+                            ui.group(|ui| {
+                                ui.label(egui::RichText::new(text.onboarding_paste_key).strong());
+                                let is_google = self.onboarding_provider == "google";
+                                let test_state = self.onboarding_test.lock().unwrap().clone();
+                                let mut key_changed = false;
+                                ui.horizontal(|ui| {
+                                    let key = if is_google { &mut self.config.gemini_api_key } else { &mut self.config.api_key };
+                                    if ui.add(egui::TextEdit::singleline(key).password(true).desired_width(320.0)).changed() {
+                                        key_changed = true;
+                                    }
+                                    let can_test = !key.trim().is_empty() && test_state != KeyTestState::Running;
+                                    if ui.add_enabled(can_test, egui::Button::new(text.onboarding_test_btn)).clicked() {
+                                        self.start_key_test(ctx);
+                                    }
+                                });
+                                if key_changed {
+                                    *self.onboarding_test.lock().unwrap() = KeyTestState::Idle;
+                                    self.save_and_sync();
+                                }
+                                match test_state {
+                                    KeyTestState::Idle => {}
+                                    KeyTestState::Running => { ui.horizontal(|ui| { ui.spinner(); ui.label(text.onboarding_testing); }); }
+                                    KeyTestState::Ok => { ui.colored_label(egui::Color32::from_rgb(100, 200, 100), text.onboarding_test_ok); }
+                                    KeyTestState::InvalidKey => { ui.colored_label(egui::Color32::from_rgb(255, 100, 100), text.onboarding_test_invalid); }
+                                    KeyTestState::Failed(err) => { ui.colored_label(egui::Color32::from_rgb(255, 100, 100), format!("❌ {}", err)); }
+                                }
+                            });
+                            ui.add_space(8.0);
+
+                            // 3. Default hotkey
+                            if let Some(hotkey) = self.config.presets.iter().find_map(|p| p.hotkeys.first()) {
+                                ui.label(text.onboarding_hotkey_label.replace("{}", &hotkey.name));
+                                ui.add_space(8.0);
+                            }
+
+                            ui.horizontal(|ui| {
+                                if ui.button(egui::RichText::new(text.onboarding_finish).strong()).clicked() {
+                                    self.finish_onboarding();
+                                }
+                                if ui.button(text.onboarding_skip).clicked() {
+                                    self.finish_onboarding();
+                                }
+                            });
+                        }
                         ViewMode::History => {
                             ui.add_space(5.0);
                             
@@ -1515,6 +3341,9 @@ impl eframe::App for SettingsApp {
                                 ui.horizontal(|ui| {
                                     let type_icon = if entry.preset_type == "audio" { "🎤" } else { "🖼" };
                                     ui.label(format!("{} {} • {}", type_icon, entry.preset_type, chrono_lite_format(entry.timestamp)));
+                                    if let Some(lang) = &entry.detected_language {
+                                        ui.label(format!("• 🌐 {}", lang)).on_hover_text(text.history_detected_language_tooltip);
+                                    }
                                     
                                     let star_icon = if entry.is_favorite { "★" } else { "☆" };
                                     let star_color = if entry.is_favorite { egui::Color32::GOLD } else { ui.visuals().text_color() };
@@ -1523,14 +3352,124 @@ impl eframe::App for SettingsApp {
                                         self.history_entries = crate::history::load_history();
                                     }
                                 });
+                                ui.add_space(5.0);
+
+                                // Tags
+                                let mut tag_to_add: Option<String> = None;
+                                let mut tag_to_remove: Option<String> = None;
+                                ui.horizontal_wrapped(|ui| {
+                                    ui.label(text.history_tags_label);
+                                    for tag in &entry.tags {
+                                        if ui.small_button(format!("#{} ✕", tag)).clicked() {
+                                            tag_to_remove = Some(tag.clone());
+                                        }
+                                    }
+                                    let tag_resp = ui.add(egui::TextEdit::singleline(&mut self.history_tag_input)
+                                        .hint_text(text.history_tags_hint)
+                                        .desired_width(100.0));
+                                    if tag_resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                        tag_to_add = Some(self.history_tag_input.clone());
+                                    }
+                                    if ui.small_button("+").clicked() {
+                                        tag_to_add = Some(self.history_tag_input.clone());
+                                    }
+                                });
+
+                                // Autocomplete from tags used on other entries
+                                let typed = self.history_tag_input.trim().to_lowercase();
+                                if !typed.is_empty() {
+                                    let suggestions: Vec<String> = crate::history::all_tags(&self.history_entries).into_iter()
+                                        .filter(|t| t.to_lowercase().starts_with(&typed) && !entry.tags.iter().any(|e| e.eq_ignore_ascii_case(t)))
+                                        .take(6)
+                                        .collect();
+                                    if !suggestions.is_empty() {
+                                        ui.horizontal_wrapped(|ui| {
+                                            for tag in suggestions {
+                                                if ui.selectable_label(false, egui::RichText::new(format!("#{}", tag)).weak()).clicked() {
+                                                    tag_to_add = Some(tag);
+                                                }
+                                            }
+                                        });
+                                    }
+                                }
+
+                                if let Some(tag) = tag_to_add {
+                                    crate::history::add_tag(&entry.id, &tag);
+                                    self.history_tag_input.clear();
+                                    self.history_entries = crate::history::load_history();
+                                }
+                                if let Some(tag) = tag_to_remove {
+                                    crate::history::remove_tag(&entry.id, &tag);
+                                    self.history_entries = crate::history::load_history();
+                                }
                                 ui.add_space(10.0);
                                 
-                                // Full result text
+                                // Find in text
+                                let result_matches = find_match_ranges(&entry.result_text, &self.history_find_query, self.history_find_case_sensitive);
+                                let retrans_matches = entry.retrans_text.as_deref()
+                                    .map(|t| find_match_ranges(t, &self.history_find_query, self.history_find_case_sensitive))
+                                    .unwrap_or_default();
+                                let total_matches = result_matches.len() + retrans_matches.len();
+                                if self.history_find_index >= total_matches { self.history_find_index = 0; }
+
+                                ui.horizontal(|ui| {
+                                    let find_resp = ui.add(egui::TextEdit::singleline(&mut self.history_find_query)
+                                        .hint_text(text.history_find_hint)
+                                        .desired_width(160.0));
+                                    if find_resp.changed() {
+                                        self.history_find_index = 0;
+                                        self.history_find_scroll = true;
+                                    }
+                                    if ui.checkbox(&mut self.history_find_case_sensitive, text.history_find_case).changed() {
+                                        self.history_find_index = 0;
+                                        self.history_find_scroll = true;
+                                    }
+                                    if !self.history_find_query.is_empty() {
+                                        let counter = if total_matches == 0 { "0/0".to_string() } else { format!("{}/{}", self.history_find_index + 1, total_matches) };
+                                        ui.label(counter);
+                                        let enter_pressed = find_resp.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                                        if ui.add_enabled(total_matches > 0, egui::Button::new("▲")).clicked() {
+                                            self.history_find_index = (self.history_find_index + total_matches - 1) % total_matches;
+                                            self.history_find_scroll = true;
+                                        }
+                                        if ui.add_enabled(total_matches > 0, egui::Button::new("▼")).clicked() || (enter_pressed && total_matches > 0) {
+                                            self.history_find_index = (self.history_find_index + 1) % total_matches;
+                                            self.history_find_scroll = true;
+                                        }
+                                    }
+                                });
+                                ui.add_space(5.0);
+
+                                // Full result text (highlighted while searching)
+                                let current_match = if total_matches > 0 { Some(self.history_find_index) } else { None };
+                                let scroll_pending = std::mem::take(&mut self.history_find_scroll);
                                 ui.label(egui::RichText::new("Kết quả:").strong());
                                 egui::ScrollArea::vertical().max_height(250.0).show(ui, |ui| {
-                                    ui.add(egui::TextEdit::multiline(&mut entry.result_text.as_str())
-                                        .desired_width(f32::INFINITY)
-                                        .font(egui::TextStyle::Body));
+                                    let mut current_rect = None;
+                                    if self.history_find_query.is_empty() {
+                                        ui.add(egui::TextEdit::multiline(&mut entry.result_text.as_str())
+                                            .desired_width(f32::INFINITY)
+                                            .font(egui::TextStyle::Body));
+                                    } else {
+                                        current_rect = show_highlighted_text(ui, &entry.result_text, &result_matches, current_match);
+                                    }
+
+                                    if let Some(retrans) = &entry.retrans_text {
+                                        ui.add_space(5.0);
+                                        ui.separator();
+                                        if self.history_find_query.is_empty() {
+                                            ui.add(egui::TextEdit::multiline(&mut retrans.as_str())
+                                                .desired_width(f32::INFINITY)
+                                                .font(egui::TextStyle::Body));
+                                        } else {
+                                            let retrans_current = current_match.and_then(|i| i.checked_sub(result_matches.len()));
+                                            current_rect = current_rect.or(show_highlighted_text(ui, retrans, &retrans_matches, retrans_current));
+                                        }
+                                    }
+
+                                    if scroll_pending {
+                                        if let Some(r) = current_rect { ui.scroll_to_rect(r, Some(egui::Align::Center)); }
+                                    }
                                 });
                                 
                                 ui.add_space(10.0);
@@ -1545,10 +3484,15 @@ impl eframe::App for SettingsApp {
                                     if ui.button("📋 Copy").clicked() {
                                         ui.output_mut(|o| o.copied_text = entry.result_text.clone());
                                     }
-                                    if ui.button("📋 Copy (có format)").clicked() {
-                                        let formatted = crate::history::format_for_clipboard(&entry);
-                                        ui.output_mut(|o| o.copied_text = formatted);
-                                    }
+                                    ui.menu_button("📋 Copy (có format)", |ui| {
+                                        for format in crate::config::CopyFormat::ALL {
+                                            if ui.button(copy_format_name(&text, format)).clicked() {
+                                                let formatted = crate::history::format_for_clipboard(&entry, format);
+                                                ui.output_mut(|o| o.copied_text = formatted);
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    });
                                 });
                                 
                                 ui.add_space(5.0);
@@ -1569,6 +3513,14 @@ impl eframe::App for SettingsApp {
                                             Err(_) => {}
                                         }
                                     }
+                                    if !entry.segments.is_empty() && ui.button(text.history_export_srt).clicked() {
+                                        match crate::history::export_to_srt(&entry) {
+                                            Ok(path) => {
+                                                let _ = open::that(path.parent().unwrap_or(&path));
+                                            }
+                                            Err(_) => {}
+                                        }
+                                    }
                                 });
                                 
                                 ui.add_space(10.0);
@@ -1576,6 +3528,7 @@ impl eframe::App for SettingsApp {
                                     crate::history::delete_entry(&entry.id);
                                     self.history_entries = crate::history::load_history();
                                     self.selected_history_id = None;
+                                    self.history_undo = Some((vec![entry.id.clone()], ui.input(|i| i.time)));
                                 }
                             } else {
                                 // LIST VIEW
@@ -1595,6 +3548,25 @@ impl eframe::App for SettingsApp {
                                         self.show_favorites_only = true;
                                     }
                                 });
+
+                                // Tag Filter Row
+                                let known_tags = crate::history::all_tags(&self.history_entries);
+                                if self.history_tag_filter.as_ref().map_or(false, |f| !known_tags.contains(f)) {
+                                    self.history_tag_filter = None;
+                                }
+                                if !known_tags.is_empty() {
+                                    ui.horizontal_wrapped(|ui| {
+                                        if ui.selectable_label(self.history_tag_filter.is_none(), text.history_tag_filter_all).clicked() {
+                                            self.history_tag_filter = None;
+                                        }
+                                        for tag in &known_tags {
+                                            let selected = self.history_tag_filter.as_ref() == Some(tag);
+                                            if ui.selectable_label(selected, format!("#{}", tag)).clicked() {
+                                                self.history_tag_filter = if selected { None } else { Some(tag.clone()) };
+                                            }
+                                        }
+                                    });
+                                }
                                 ui.add_space(10.0);
                                 
                                 // Collect actions
@@ -1605,18 +3577,33 @@ impl eframe::App for SettingsApp {
                                 let entries_snapshot = self.history_entries.clone();
                                 let search_q = self.history_search_query.to_lowercase();
                                 let show_favs = self.show_favorites_only;
+                                let tag_filter = self.history_tag_filter.clone();
                                 
                                 let filtered: Vec<_> = entries_snapshot.iter()
                                     .filter(|e| {
                                         if show_favs && !e.is_favorite { return false; }
+                                        if let Some(tag) = &tag_filter {
+                                            if !e.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) { return false; }
+                                        }
                                         if !search_q.is_empty() {
                                             return e.result_text.to_lowercase().contains(&search_q) 
-                                                || e.preset_name.to_lowercase().contains(&search_q);
+                                                || e.preset_name.to_lowercase().contains(&search_q)
+                                                || e.tags.iter().any(|t| t.to_lowercase().contains(&search_q));
                                         }
                                         true
                                     })
                                     .collect();
                                 
+                                // Anki deck of the study layout results in view (e.g. one tag's)
+                                if filtered.iter().any(|e| e.study.is_some()) {
+                                    if ui.button(text.history_export_anki).on_hover_text(text.history_export_anki_tooltip).clicked() {
+                                        if let Ok(path) = crate::history::export_study_deck(&filtered) {
+                                            let _ = open::that(path.parent().unwrap_or(&path));
+                                        }
+                                    }
+                                    ui.add_space(5.0);
+                                }
+
                                 if filtered.is_empty() {
                                     ui.add_space(20.0);
                                     ui.label(egui::RichText::new(text.history_empty).italics().weak());
@@ -1633,6 +3620,12 @@ impl eframe::App for SettingsApp {
                                                     
                                                     let type_icon = if entry.preset_type == "audio" { "🎤" } else { "🖼" };
                                                     ui.label(format!("{} {}", type_icon, entry.preset_name));
+                                                    if let Some(lang) = &entry.detected_language {
+                                                        ui.label(egui::RichText::new(format!("🌐 {}", lang)).weak().small());
+                                                    }
+                                                    for tag in &entry.tags {
+                                                        ui.label(egui::RichText::new(format!("#{}", tag)).weak().small());
+                                                    }
                                                     
                                                     ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                                                         if icon_button(ui, Icon::Delete).clicked() {
@@ -1668,6 +3661,7 @@ impl eframe::App for SettingsApp {
                                 // Handle actions
                                 if let Some(id) = entry_to_select {
                                     self.selected_history_id = Some(id);
+                                    self.history_find_index = 0;
                                 }
                                 if let Some(id) = entry_to_toggle {
                                     crate::history::toggle_favorite(&id);
@@ -1676,13 +3670,13 @@ impl eframe::App for SettingsApp {
                                 if let Some(id) = entry_to_delete {
                                     crate::history::delete_entry(&id);
                                     self.history_entries = crate::history::load_history();
+                                    self.history_undo = Some((vec![id], ui.input(|i| i.time)));
                                 }
                                 
                                 ui.add_space(10.0);
                                 if !self.history_entries.is_empty() {
                                     if ui.button(text.history_clear_all).clicked() {
-                                        crate::history::clear_all_history();
-                                        self.history_entries = Vec::new();
+                                        self.confirm_clear_history = true;
                                     }
                                 }
                             }
@@ -1691,9 +3685,113 @@ impl eframe::App for SettingsApp {
                 });
             }); // End of Main Split
         }); // End of CentralPanel
+
+        // --- CLEAR HISTORY CONFIRMATION ---
+        if self.confirm_clear_history {
+            egui::Window::new(text.history_clear_all)
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+                .show(ctx, |ui| {
+                    ui.label(text.history_clear_confirm);
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.button(egui::RichText::new(text.history_clear_confirm_yes).color(egui::Color32::from_rgb(255, 100, 100))).clicked() {
+                            let ids = crate::history::clear_all_history();
+                            self.history_entries = Vec::new();
+                            self.selected_history_id = None;
+                            self.history_undo = Some((ids, ctx.input(|i| i.time)));
+                            self.confirm_clear_history = false;
+                        }
+                        if ui.button(text.cancel_label).clicked() {
+                            self.confirm_clear_history = false;
+                        }
+                    });
+                });
+        }
+
+        // --- REPLACE SELECTION CONSENT (first time only) ---
+        if let Some(preset_idx) = self.confirm_replace_selection {
+            egui::Window::new(text.capture_mode_replace_selection)
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+                .show(ctx, |ui| {
+                    ui.label(text.replace_selection_confirm);
+                    ui.add_space(8.0);
+                    ui.horizontal(|ui| {
+                        if ui.button(text.replace_selection_confirm_yes).clicked() {
+                            self.config.replace_selection_confirmed = true;
+                            if let Some(preset) = self.config.presets.get_mut(preset_idx) {
+                                preset.capture_mode = REPLACE_SELECTION_MODE.to_string();
+                                preset.video_capture_method = "region".to_string();
+                            }
+                            self.confirm_replace_selection = None;
+                            self.save_and_sync();
+                        }
+                        if ui.button(text.cancel_label).clicked() {
+                            self.confirm_replace_selection = None;
+                        }
+                    });
+                });
+        }
+
+        // --- PROMPT PREVIEW ---
+        if let Some(preview) = self.prompt_preview.clone() {
+            egui::Window::new(text.preview_prompt_btn)
+                .collapsible(false)
+                .resizable(true)
+                .default_width(420.0)
+                .anchor(egui::Align2::CENTER_CENTER, egui::vec2(0.0, 0.0))
+                .show(ctx, |ui| {
+                    ui.label(text.preview_prompt_hint);
+                    ui.add_space(4.0);
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        let mut shown = preview.as_str();
+                        ui.add(egui::TextEdit::multiline(&mut shown).font(egui::TextStyle::Monospace).desired_width(f32::INFINITY));
+                    });
+                    ui.add_space(8.0);
+                    if ui.button(text.close_label).clicked() {
+                        self.prompt_preview = None;
+                    }
+                });
+        }
+
+        // --- UNDO TOAST (history deletion) ---
+        if let Some((ids, deleted_at)) = self.history_undo.clone() {
+            let now = ctx.input(|i| i.time);
+            if now - deleted_at > HISTORY_UNDO_WINDOW {
+                self.history_undo = None;
+            } else {
+                egui::Area::new(egui::Id::new("history_undo_toast"))
+                    .anchor(egui::Align2::CENTER_BOTTOM, egui::vec2(0.0, -30.0))
+                    .order(egui::Order::Foreground)
+                    .show(ctx, |ui| {
+                        egui::Frame::popup(ui.style()).show(ui, |ui| {
+                            ui.horizontal(|ui| {
+                                let label = if ids.len() > 1 {
+                                    format!("{} ({})", text.history_deleted_toast, ids.len())
+                                } else {
+                                    text.history_deleted_toast.to_string()
+                                };
+                                ui.label(label);
+                                if ui.button(text.history_undo).clicked() {
+                                    for id in &ids {
+                                        crate::history::restore_entry(id);
+                                    }
+                                    self.history_entries = crate::history::load_history();
+                                    self.history_undo = None;
+                                }
+                            });
+                        });
+                    });
+                ctx.request_repaint_after(std::time::Duration::from_millis(250));
+            }
+        }
     }
     
     fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        crate::shutdown::stop_and_flush();
         self.tray_icon = None;
     }
 }
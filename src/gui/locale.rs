@@ -7,6 +7,9 @@ pub struct LocaleText {
      pub gemini_get_key_link: &'static str,
      pub openrouter_api_key_label: &'static str,
      pub openrouter_get_key_link: &'static str,
+     pub refresh_models_button: &'static str,
+     pub refresh_models_tooltip: &'static str,
+     pub refresh_models_done: &'static str,
      pub presets_section: &'static str,
      pub global_settings: &'static str,
      pub preset_name_label: &'static str,
@@ -16,6 +19,22 @@ pub struct LocaleText {
      pub retranslate_checkbox: &'static str,
      pub retranslate_to_label: &'static str,
      pub retranslate_model_label: &'static str,
+     pub retranslate_secondary_checkbox: &'static str,
+     pub retranslate_secondary_model_label: &'static str,
+     pub early_retranslate_label: &'static str,
+     pub retranslate_same_as_detected_label: &'static str,
+     pub retranslate_same_as_detected_tooltip: &'static str,
+     pub retranslate_srt_label: &'static str,
+     pub preserve_layout_label: &'static str,
+     pub preserve_layout_tooltip: &'static str,
+     pub ocr_quality_check_label: &'static str,
+     pub ocr_quality_check_tooltip: &'static str,
+     pub multi_frame_label: &'static str,
+     pub multi_frame_tooltip: &'static str,
+     pub multi_frame_count_label: &'static str,
+     pub expand_on_empty_label: &'static str,
+     pub expand_on_empty_tooltip: &'static str,
+     pub expand_on_empty_margin_label: &'static str,
      #[allow(dead_code)]
      pub hotkey_bag_label: &'static str,
      pub add_preset_btn: &'static str,
@@ -26,6 +45,187 @@ pub struct LocaleText {
      pub streaming_option_wait: &'static str,
      pub auto_copy_label: &'static str,
      pub startup_label: &'static str,
+     pub max_history_label: &'static str,
+     pub max_history_tooltip: &'static str,
+     pub toggle_results_hotkey_label: &'static str,
+     pub debug_metrics_label: &'static str,
+     pub debug_metrics_tooltip: &'static str,
+     pub use_gemini_live_label: &'static str,
+     pub use_gemini_live_tooltip: &'static str,
+     pub gemini_live_only_failed: &'static str,
+     pub show_live_stats_label: &'static str,
+     pub show_live_stats_tooltip: &'static str,
+     pub capture_mode_label: &'static str,
+     pub overlay_appearance_title: &'static str,
+     pub overlay_dim_label: &'static str,
+     pub overlay_border_color_label: &'static str,
+     pub overlay_border_width_label: &'static str,
+     pub overlay_grid_label: &'static str,
+     pub overlay_crosshair_label: &'static str,
+     pub overlay_appearance_reset: &'static str,
+     pub live_chunk_secs_label: &'static str,
+     pub live_chunk_secs_tooltip: &'static str,
+     pub live_chunk_overlap_label: &'static str,
+     pub live_chunk_overlap_tooltip: &'static str,
+     pub keep_chat_image_label: &'static str,
+     pub keep_chat_image_tooltip: &'static str,
+     pub preview_prompt_btn: &'static str,
+     pub preview_prompt_hint: &'static str,
+     pub preview_prompt_sample_question: &'static str,
+     pub unmapped_language_warning: &'static str,
+     pub include_context_label: &'static str,
+     pub include_context_tooltip: &'static str,
+     pub language_cycle_label: &'static str,
+     pub language_cycle_add: &'static str,
+     pub language_cycle_tooltip: &'static str,
+     pub close_label: &'static str,
+     pub blocked_apps_title: &'static str,
+     pub blocked_apps_tooltip: &'static str,
+     pub blocked_apps_placeholder: &'static str,
+     pub blocked_apps_add: &'static str,
+     pub advanced_model_params: &'static str,
+     pub advanced_model_params_hint: &'static str,
+     pub temperature_label: &'static str,
+     pub max_output_tokens_label: &'static str,
+     pub thinking_budget_label: &'static str,
+     pub thinking_budget_tooltip: &'static str,
+     pub max_tokens_tooltip: &'static str,
+     pub model_params_title: &'static str,
+     pub preset_indicator_label: &'static str,
+     pub capture_mode_full_monitor: &'static str,
+     pub capture_monitor_missing: &'static str,
+     pub copy_markdown_label: &'static str,
+     pub copy_markdown_tooltip: &'static str,
+     pub per_app_language_label: &'static str,
+     pub per_app_language_tooltip: &'static str,
+     pub retranslate_to_ui_language_label: &'static str,
+     pub retranslate_to_ui_language_tooltip: &'static str,
+     pub per_app_language_forget: &'static str,
+     pub overlay_animations_label: &'static str,
+     pub overlay_animations_tooltip: &'static str,
+     pub dedup_history_label: &'static str,
+     pub dedup_history_tooltip: &'static str,
+     pub open_config_folder_btn: &'static str,
+     pub open_log_file_btn: &'static str,
+     pub copy_diagnostics_btn: &'static str,
+     pub copy_diagnostics_tooltip: &'static str,
+     pub diagnostics_copied: &'static str,
+     pub log_level_label: &'static str,
+     pub log_level_debug_tooltip: &'static str,
+     pub log_max_size_label: &'static str,
+     pub log_max_size_tooltip: &'static str,
+     pub dismiss_on_outside_click_label: &'static str,
+     pub dismiss_on_outside_click_tooltip: &'static str,
+     pub check_updates_label: &'static str,
+     pub check_updates_tooltip: &'static str,
+     pub update_available: &'static str,
+     pub rerun_hotkey_label: &'static str,
+     pub rerun_hotkey_tooltip: &'static str,
+     pub cycle_language_hotkey_label: &'static str,
+     pub cycle_language_hotkey_tooltip: &'static str,
+     pub last_capture_keep_label: &'static str,
+     pub last_capture_keep_tooltip: &'static str,
+     pub target_monitor_label: &'static str,
+     pub target_monitor_tooltip: &'static str,
+     pub capture_on_release_label: &'static str,
+     pub capture_on_release_tooltip: &'static str,
+     pub font_family_label: &'static str,
+     pub font_family_tooltip: &'static str,
+     pub font_family_auto: &'static str,
+     pub target_monitor_all: &'static str,
+     pub target_monitor_missing: &'static str,
+     pub preset_picker_hotkey_label: &'static str,
+     pub stop_all_hotkey_label: &'static str,
+     pub recent_copies_hotkey_label: &'static str,
+     pub reading_order_label: &'static str,
+     pub reading_order_tooltip: &'static str,
+     pub reading_order_auto: &'static str,
+     pub reading_order_ltr: &'static str,
+     pub reading_order_rtl: &'static str,
+     pub show_model_badge_label: &'static str,
+     pub show_model_badge_tooltip: &'static str,
+     pub compare_model_label: &'static str,
+     pub compare_model_tooltip: &'static str,
+     pub compare_model_off: &'static str,
+     pub quick_translate_label: &'static str,
+     pub quick_translate_tooltip: &'static str,
+     pub auto_copy_delay_label: &'static str,
+     pub auto_copy_delay_tooltip: &'static str,
+     pub copy_on_close_label: &'static str,
+     pub copy_on_close_tooltip: &'static str,
+     pub batch_btn: &'static str,
+     pub batch_tooltip: &'static str,
+     pub batch_progress: &'static str,
+     pub batch_cancel_btn: &'static str,
+     pub batch_open_folder_btn: &'static str,
+     pub batch_concurrency_label: &'static str,
+     pub batch_concurrency_tooltip: &'static str,
+     pub batch_per_image_label: &'static str,
+     pub batch_combined_label: &'static str,
+     pub batch_output_label: &'static str,
+     pub batch_output_hint: &'static str,
+     pub show_crash_dialog_label: &'static str,
+     pub show_crash_dialog_tooltip: &'static str,
+     pub output_mode_label: &'static str,
+     pub output_mode_tooltip: &'static str,
+     pub output_mode_clipboard: &'static str,
+     pub output_mode_type_out: &'static str,
+     pub type_out_delay_label: &'static str,
+     pub type_out_delay_tooltip: &'static str,
+     pub output_file_label: &'static str,
+     pub output_file_tooltip: &'static str,
+     pub output_file_none: &'static str,
+     pub output_file_browse: &'static str,
+     pub image_upload_format_label: &'static str,
+     pub image_upload_format_tooltip: &'static str,
+     pub upscale_small_crops_label: &'static str,
+     pub upscale_small_crops_tooltip: &'static str,
+     pub webhook_header: &'static str,
+     pub webhook_url_label: &'static str,
+     pub webhook_template_label: &'static str,
+     pub webhook_template_tooltip: &'static str,
+     pub webhook_headers_label: &'static str,
+     pub webhook_add_header: &'static str,
+     pub webhook_test_btn: &'static str,
+     pub strip_preamble_label: &'static str,
+     pub strip_preamble_tooltip: &'static str,
+     pub reflow_paragraphs_label: &'static str,
+     pub reflow_paragraphs_tooltip: &'static str,
+     pub reflow_dehyphenate_label: &'static str,
+     pub reflow_dehyphenate_tooltip: &'static str,
+     pub study_layout_label: &'static str,
+     pub study_layout_tooltip: &'static str,
+     pub copy_format_label: &'static str,
+     pub copy_format_tooltip: &'static str,
+     pub copy_format_plain: &'static str,
+     pub copy_format_code_block: &'static str,
+     pub copy_format_quote: &'static str,
+     pub copy_format_source_header: &'static str,
+     pub result_copy_hint: &'static str,
+     pub result_retry_label: &'static str,
+     pub copy_unreflowed_label: &'static str,
+     pub live_follow_window_label: &'static str,
+     pub live_follow_window_tooltip: &'static str,
+     pub live_follow_window_match_label: &'static str,
+     pub live_follow_window_hint: &'static str,
+     pub completion_sound_label: &'static str,
+     pub completion_toast_label: &'static str,
+     pub completion_tooltip: &'static str,
+     pub min_region_size_label: &'static str,
+     pub min_region_size_tooltip: &'static str,
+     pub hotkey_debounce_label: &'static str,
+     pub hotkey_debounce_tooltip: &'static str,
+     pub preset_indicator_tooltip: &'static str,
+     pub preset_indicator_corner_label: &'static str,
+     pub corner_top_left: &'static str,
+     pub corner_top_right: &'static str,
+     pub corner_bottom_left: &'static str,
+     pub corner_bottom_right: &'static str,
+     pub capture_mode_region: &'static str,
+     pub capture_mode_replace_selection: &'static str,
+     pub replace_selection_hint: &'static str,
+     pub replace_selection_confirm: &'static str,
+     pub replace_selection_confirm_yes: &'static str,
      pub add_hotkey_button: &'static str,
      pub press_keys: &'static str,
      pub cancel_label: &'static str,
@@ -40,13 +240,59 @@ pub struct LocaleText {
      pub audio_source_label: &'static str,
      pub audio_src_mic: &'static str,
      pub audio_src_device: &'static str,
+     pub audio_src_both: &'static str,
+     pub loopback_device_label: &'static str,
+     pub loopback_device_tooltip: &'static str,
+     pub loopback_device_default: &'static str,
+     pub loopback_device_refresh: &'static str,
+     pub loopback_device_missing: &'static str,
      pub hide_recording_ui_label: &'static str,
+     pub optimize_audio_upload_label: &'static str,
+     pub optimize_audio_upload_tooltip: &'static str,
+     pub max_recording_label: &'static str,
+     pub max_recording_tooltip: &'static str,
+     pub recording_processing: &'static str,
+     pub recording_paused: &'static str,
+     pub recording_device: &'static str,
+     pub recording_both: &'static str,
+     pub recording_mic: &'static str,
+     pub recording_hint: &'static str,
+     pub recording_countdown: &'static str,
+     pub whisper_language_label: &'static str,
+     pub whisper_language_auto: &'static str,
+     pub whisper_language_tooltip: &'static str,
+     pub whisper_translate_label: &'static str,
+     pub whisper_translate_tooltip: &'static str,
+     pub open_audio_file_btn: &'static str,
+     pub open_audio_file_tooltip: &'static str,
+     pub audio_drop_no_preset: &'static str,
+     pub audio_output_not_found: &'static str,
+     pub audio_no_loopback: &'static str,
+     pub audio_no_input: &'static str,
+     pub audio_capture_failed: &'static str,
+     pub onboarding_title: &'static str,
+     pub onboarding_intro: &'static str,
+     pub onboarding_groq_desc: &'static str,
+     pub onboarding_gemini_desc: &'static str,
+     pub onboarding_paste_key: &'static str,
+     pub onboarding_test_btn: &'static str,
+     pub onboarding_testing: &'static str,
+     pub onboarding_test_ok: &'static str,
+     pub onboarding_test_invalid: &'static str,
+     pub onboarding_hotkey_label: &'static str,
+     pub onboarding_finish: &'static str,
+     pub onboarding_skip: &'static str,
      pub hotkeys_section: &'static str,
      pub usage_statistics_title: &'static str,
      pub usage_statistics_tooltip: &'static str,
      pub usage_model_column: &'static str,
      pub usage_remaining_column: &'static str,
      pub usage_check_link: &'static str,
+     pub translation_cache_hits: &'static str,
+     pub translation_cache_clear: &'static str,
+     pub translation_cache_size_label: &'static str,
+     pub translation_cache_tooltip: &'static str,
+     pub translation_cache_persist_label: &'static str,
      pub empty_prompt_warning: &'static str,
      pub footer_admin_text: &'static str,
      pub footer_version: &'static str,
@@ -57,6 +303,19 @@ pub struct LocaleText {
      pub history_favorites: &'static str,
      pub history_clear_all: &'static str,
      pub history_empty: &'static str,
+     pub history_deleted_toast: &'static str,
+     pub history_undo: &'static str,
+     pub history_find_hint: &'static str,
+     pub history_find_case: &'static str,
+     pub history_tags_label: &'static str,
+     pub history_tags_hint: &'static str,
+     pub history_tag_filter_all: &'static str,
+     pub history_export_srt: &'static str,
+     pub history_export_anki: &'static str,
+     pub history_export_anki_tooltip: &'static str,
+     pub history_detected_language_tooltip: &'static str,
+     pub history_clear_confirm: &'static str,
+     pub history_clear_confirm_yes: &'static str,
      // Live Captions
      pub live_captions_title: &'static str,
      pub live_captions_tooltip: &'static str,
@@ -67,6 +326,9 @@ pub struct LocaleText {
      pub live_captions_sentences: &'static str,
      pub live_captions_show_original: &'static str,
      pub live_captions_auto_hide: &'static str,
+     pub live_captions_hotkey: &'static str,
+     pub live_captions_audio_source: &'static str,
+     pub live_captions_audio_source_tooltip: &'static str,
      // Quick Actions & AI Chat
      pub quick_actions_title: &'static str,
      pub quick_actions_enabled: &'static str,
@@ -93,6 +355,9 @@ impl LocaleText {
                 gemini_get_key_link: "Lấy mã tại aistudio.google.com",
                 openrouter_api_key_label: "Mã API OpenRouter:",
                 openrouter_get_key_link: "Lấy mã tại openrouter.ai",
+                refresh_models_button: "🔄 Cập nhật danh sách model",
+                refresh_models_tooltip: "Lấy danh sách model mới nhất từ Groq, Gemini và OpenRouter (chỉ model miễn phí) bằng API key đã nhập, rồi thêm vào các danh sách chọn model. Danh sách mặc định vẫn được giữ nguyên.",
+                refresh_models_done: "Đã tìm thấy {} model mới",
                 presets_section: "Danh Sách Cấu Hình",
                 global_settings: "Cài Đặt Chung",
                 preset_name_label: "Tên Cấu Hình:",
@@ -102,6 +367,22 @@ impl LocaleText {
                 retranslate_checkbox: "Bật dịch lại",
                 retranslate_to_label: "Dịch sang:",
                 retranslate_model_label: "Mô hình dịch lại:",
+                retranslate_secondary_checkbox: "Dịch thêm sang:",
+                retranslate_secondary_model_label: "Mô hình dịch thêm:",
+                early_retranslate_label: "Dịch sớm từ bản nháp (khi đang stream)",
+                retranslate_same_as_detected_label: "Giống ngôn ngữ gốc",
+                retranslate_same_as_detected_tooltip: "Dịch ngược bản dịch về ngôn ngữ mà mô hình nhận ra trong ảnh. Nếu chữ trong ảnh đã là ngôn ngữ của bản dịch thì không mở cửa sổ dịch lại (không tốn thêm lượt gọi API).",
+                retranslate_srt_label: "Dịch từng đoạn để xuất SRT bản dịch",
+                preserve_layout_label: "Giữ nguyên bố cục (code, bảng)",
+                preserve_layout_tooltip: "Giữ xuống dòng và thụt lề, hiển thị bằng font monospace, không tự ngắt dòng",
+                ocr_quality_check_label: "Cảnh báo khi kết quả có vẻ bị lỗi",
+                ocr_quality_check_tooltip: "Hiện nhãn \"độ tin cậy thấp\" nhỏ trên cửa sổ kết quả (ký tự lạ, lặp từ, độ dài bất thường)",
+                multi_frame_label: "Chụp nhiều khung hình",
+                multi_frame_tooltip: "Chụp vài khung hình trong ~1 giây rồi gộp chữ lại (chữ chạy, phụ đề mờ dần)",
+                multi_frame_count_label: "Số khung hình:",
+                expand_on_empty_label: "Không thấy chữ: thử lại với vùng lớn hơn",
+                expand_on_empty_tooltip: "Khi mô hình không tìm thấy chữ trong vùng chọn (thường do chọn lệch một chút), mở rộng vùng chọn mỗi bên thêm một khoảng và gửi lại một lần. Lần gửi thứ hai tốn thêm lượt gọi API.",
+                expand_on_empty_margin_label: "Mở rộng mỗi bên:",
                 hotkey_bag_label: "Phím tắt kích hoạt:",
                 add_preset_btn: "+ Thêm Cấu Hình",
                 search_placeholder: "Tìm ngôn ngữ...",
@@ -111,6 +392,187 @@ impl LocaleText {
                 streaming_option_wait: "Nhận hết mới hiện",
                 auto_copy_label: "Tự động copy",
                 startup_label: "Khởi động cùng Windows",
+                max_history_label: "Số mục lịch sử tối đa:",
+                max_history_tooltip: "Mục cũ nhất (trừ mục yêu thích) sẽ bị xoá khi vượt quá giới hạn. 0 = không giới hạn.",
+                toggle_results_hotkey_label: "Phím ẩn/hiện cửa sổ kết quả:",
+                debug_metrics_label: "Ghi thời gian phản hồi API",
+                debug_metrics_tooltip: "Ghi vào log thời gian tới đoạn đầu tiên, tổng thời gian và tốc độ (ký tự/giây) của mỗi yêu cầu. Giúp phân biệt mạng chậm với model chậm.",
+                use_gemini_live_label: "Dùng Gemini Live",
+                use_gemini_live_tooltip: "Truyền âm thanh trực tiếp tới Gemini Live qua websocket (độ trễ dưới 1 giây). Cần Gemini API key; nếu không kết nối được sẽ tự quay về chế độ gửi từng đoạn.",
+                gemini_live_only_failed: "Không kết nối được Gemini Live, mà model này chỉ chạy qua Live. Kiểm tra API key Gemini hoặc chọn model khác.",
+                show_live_stats_label: "Hiện thống kê độ trễ",
+                show_live_stats_tooltip: "Hiện một dòng nhỏ dưới cửa sổ kết quả: thời gian chờ sau khi chụp, thời gian gọi API và số đoạn đang chờ (cap / api / q)",
+                capture_mode_label: "Chế độ:",
+                overlay_appearance_title: "Lớp phủ chọn vùng",
+                overlay_dim_label: "Độ tối nền:",
+                overlay_border_color_label: "Màu viền:",
+                overlay_border_width_label: "Độ dày:",
+                overlay_grid_label: "Lưới 1/3",
+                overlay_crosshair_label: "Đường gióng theo con trỏ",
+                overlay_appearance_reset: "Mặc định",
+                live_chunk_secs_label: "Độ dài mỗi đoạn:",
+                live_chunk_secs_tooltip: "Đoạn dài hơn (4–5 giây) cho kết quả tốt hơn với người nói chậm, nhưng chữ hiện ra chậm hơn.",
+                live_chunk_overlap_label: "Chồng lấn:",
+                live_chunk_overlap_tooltip: "Lặp lại phần cuối của đoạn trước ở đầu đoạn sau để không cắt mất chữ ở ranh giới; chữ bị lặp sẽ được lọc bỏ.",
+                keep_chat_image_label: "Giữ ảnh chụp cho câu hỏi tiếp",
+                keep_chat_image_tooltip: "Gửi lại ảnh đã chụp (thu nhỏ tối đa ~1MP) cùng mỗi câu hỏi tiếp theo. Nhấn Enter trên cửa sổ kết quả để hỏi tiếp.",
+                preview_prompt_btn: "Xem trước câu lệnh",
+                preview_prompt_hint: "Đây là câu lệnh sẽ được gửi đi (câu hỏi mẫu cho chế độ chat):",
+                preview_prompt_sample_question: "Trong ảnh này có gì?",
+                unmapped_language_warning: "Chưa chọn ngôn ngữ cho thẻ (sẽ được gửi nguyên văn):",
+                include_context_label: "Gửi kèm tên cửa sổ (ngữ cảnh)",
+                include_context_tooltip: "Thêm tiêu đề cửa sổ đang mở khi nhấn phím tắt vào prompt (vd. tên game hoặc truyện), giúp dịch đúng tên riêng. Tốn thêm token mỗi lần chụp.",
+                language_cycle_label: "Xoay vòng ngôn ngữ {language1}:",
+                language_cycle_add: "+ Thêm",
+                language_cycle_tooltip: "Danh sách ngôn ngữ theo thứ tự. Phím xoay vòng ngôn ngữ (trong phần phím tắt, cần ít nhất 2 ngôn ngữ) chuyển {language1} sang ngôn ngữ tiếp theo và lưu lại.",
+                close_label: "Đóng",
+                blocked_apps_title: "Ứng dụng bị chặn chụp",
+                blocked_apps_tooltip: "Không bao giờ chụp hoặc dịch khi các ứng dụng này đang ở phía trước (ví dụ trình quản lý mật khẩu). Chế độ Live tự tạm dừng khi chúng được chọn.",
+                blocked_apps_placeholder: "Tên tiến trình, vd. KeePassXC.exe",
+                blocked_apps_add: "Thêm",
+                advanced_model_params: "Nâng cao",
+                advanced_model_params_hint: "Áp dụng cho mọi preset dùng mô hình này. Bỏ chọn = mặc định của API.",
+                temperature_label: "Nhiệt độ",
+                max_output_tokens_label: "Số token tối đa",
+                thinking_budget_label: "Ngân sách suy nghĩ",
+                thinking_budget_tooltip: "Số token mô hình được dùng để suy luận trước khi trả lời. -1 = tự động, 0 = tắt. Cao hơn = kỹ hơn nhưng chậm và tốn hơn.",
+                max_tokens_tooltip: "Giới hạn độ dài câu trả lời. Tăng lên giúp dịch cả trang dài không bị cắt, nhưng tốn nhiều token hơn.",
+                model_params_title: "Cài đặt mô hình Gemini",
+                preset_indicator_label: "Hiện preset đang dùng",
+                capture_mode_full_monitor: "Toàn màn hình",
+                capture_monitor_missing: "Màn hình này hiện không được kết nối; sẽ chụp màn hình chính.",
+                copy_markdown_label: "Nút copy chép Markdown (kết quả chat)",
+                copy_markdown_tooltip: "Câu trả lời chat được hiển thị không có định dạng Markdown. Khi bật, nút copy chép bản gốc (giữ code block, danh sách) còn chuột phải chép văn bản thuần; khi tắt thì ngược lại.",
+                per_app_language_label: "Nhớ ngôn ngữ theo từng ứng dụng",
+                per_app_language_tooltip: "Ghi lại ngôn ngữ đích đã dùng trong ứng dụng đang mở (ví dụ game.exe) và tự chọn lại ngôn ngữ đó lần sau khi chạy preset trong ứng dụng này. Đổi ngôn ngữ ở đây sẽ áp dụng cho ứng dụng mà preset chạy tiếp theo.",
+                retranslate_to_ui_language_label: "Dịch sang ngôn ngữ giao diện",
+                retranslate_to_ui_language_tooltip: "Luôn dịch sang ngôn ngữ giao diện của ứng dụng (Tiếng Việt, 한국어 hoặc English) thay vì ngôn ngữ chọn ở trên. Đổi ngôn ngữ giao diện thì bản dịch cũng đổi theo.",
+                per_app_language_forget: "Quên ({} ứng dụng)",
+                overlay_animations_label: "Hiệu ứng chổi trên cửa sổ kết quả",
+                overlay_animations_tooltip: "Con trỏ chổi và hiệu ứng quét khi đóng cửa sổ kết quả. Khi tắt (hoặc khi Windows tắt hiệu ứng hoạt ảnh), con trỏ là mũi tên bình thường và bấm vào cửa sổ sẽ đóng ngay. Áp dụng cho các cửa sổ mở sau đó.",
+                dedup_history_label: "Nhớ số dòng gần nhất:",
+                dedup_history_tooltip: "Bỏ qua kết quả trùng với một trong N dòng gần nhất, để phụ đề lặp lại không bị dịch lại nhiều lần.",
+                open_config_folder_btn: "Mở thư mục cấu hình",
+                open_log_file_btn: "Mở file log",
+                copy_diagnostics_btn: "Sao chép thông tin chẩn đoán",
+                copy_diagnostics_tooltip: "Sao chép phiên bản ứng dụng, phiên bản Windows và phần cuối của app.log để dán vào báo lỗi. Không bao gồm API key.",
+                diagnostics_copied: "Đã sao chép!",
+                log_level_label: "Mức log:",
+                log_level_debug_tooltip: "Ghi thêm chi tiết để tìm lỗi (file log lớn nhanh hơn).",
+                log_max_size_label: "Giới hạn file log:",
+                log_max_size_tooltip: "Khi app.log vượt quá mức này, nó được đổi tên thành app.log.1 (thay bản cũ) và bắt đầu file mới.",
+                dismiss_on_outside_click_label: "Đóng khi bấm ra ngoài",
+                dismiss_on_outside_click_tooltip: "Bấm chuột ở bất kỳ đâu ngoài cửa sổ kết quả sẽ đóng nó (cú bấm vẫn đến ứng dụng bên dưới). Bấm vào cửa sổ dịch lại không đóng gì. Không áp dụng cho phiên trực tiếp.",
+                check_updates_label: "Kiểm tra cập nhật",
+                check_updates_tooltip: "Khi khởi động (tối đa mỗi ngày một lần), xem GitHub có bản mới hơn không và báo ở chân cửa sổ. Không tự tải về.",
+                update_available: "Đã có bản {} — bấm để xem",
+                rerun_hotkey_label: "Chạy lại vùng chụp gần nhất:",
+                rerun_hotkey_tooltip: "Chạy preset này trên vùng vừa chụp (bằng bất kỳ preset nào) mà không cần chọn lại",
+                cycle_language_hotkey_label: "Xoay vòng ngôn ngữ đích:",
+                cycle_language_hotkey_tooltip: "Chuyển {language1} sang ngôn ngữ tiếp theo trong danh sách xoay vòng và hiện thông báo; lần chụp sau sẽ dùng ngôn ngữ đó",
+                last_capture_keep_label: "Giữ vùng chụp gần nhất:",
+                last_capture_keep_tooltip: "Thời gian giữ ảnh vùng chụp gần nhất cho phím chạy lại, sau đó ảnh bị xóa khỏi bộ nhớ (0 = không giữ)",
+                target_monitor_label: "Màn hình chọn vùng:",
+                target_monitor_tooltip: "Chỉ hiện lớp chọn vùng trên màn hình này, bất kể con trỏ đang ở đâu",
+                capture_on_release_label: "Chụp khi thả chuột",
+                capture_on_release_tooltip: "Không chụp màn hình khi nhấn phím tắt; màn hình vẫn chạy dưới lớp chọn vùng và chỉ vùng đã chọn được chụp khi thả chuột (dùng cho khung hình video)",
+                font_family_label: "Phông chữ kết quả:",
+                font_family_tooltip: "Tên phông chữ Windows cho cửa sổ kết quả (ví dụ: Malgun Gothic). Để trống để tự chọn phông hỗ trợ chữ viết của văn bản (Hàn, Nhật, Trung, Thái...).",
+                font_family_auto: "Tự động",
+                target_monitor_all: "Tất cả màn hình",
+                target_monitor_missing: "Màn hình này hiện không được kết nối; sẽ dùng màn hình có con trỏ.",
+                preset_picker_hotkey_label: "Phím chọn preset rồi chọn vùng:",
+                stop_all_hotkey_label: "Phím dừng mọi ghi âm/Live:",
+                recent_copies_hotkey_label: "Phím mở danh sách kết quả đã sao chép gần đây:",
+                reading_order_label: "Thứ tự đọc:",
+                reading_order_tooltip: "Thứ tự khung truyện và bong bóng thoại trong kết quả. \"Phải sang trái\" dành cho manga",
+                reading_order_auto: "Tự động",
+                reading_order_ltr: "Trái sang phải (truyện tranh)",
+                reading_order_rtl: "Phải sang trái (manga)",
+                show_model_badge_label: "Hiện model và thời gian trên kết quả",
+                show_model_badge_tooltip: "Góc trên bên phải cửa sổ kết quả ghi model đã dùng và thời gian xử lý (vd. \"gemini-2.0-flash · 1.8s\"). Không được sao chép cùng văn bản.",
+                compare_model_label: "So sánh với:",
+                compare_model_tooltip: "Gửi cùng ảnh chụp tới model thứ hai và hiện kết quả trong cửa sổ bên cạnh. Cả hai được lưu vào lịch sử; tự động sao chép chỉ dùng kết quả chính.",
+                compare_model_off: "Tắt",
+                quick_translate_label: "Nút dịch nhanh (🌐) dịch sang:",
+                quick_translate_tooltip: "Nút quả địa cầu trên cửa sổ kết quả dịch nội dung đang hiện sang ngôn ngữ này; bấm lần nữa để xem lại bản gốc",
+                auto_copy_delay_label: "Trễ khi copy",
+                auto_copy_delay_tooltip: "Chờ một chút trước khi copy kết quả. Tăng lên nếu trình quản lý clipboard bỏ sót hoặc copy sai.",
+                copy_on_close_label: "Copy khi đóng",
+                copy_on_close_tooltip: "Chỉ copy khi bạn đóng cửa sổ kết quả (đã xong và đã sửa, nếu có), thay vì ngay khi có kết quả.",
+                batch_btn: "Dịch hàng loạt ảnh…",
+                batch_tooltip: "Chọn nhiều ảnh PNG hoặc WebP để chạy preset này, kết quả được ghi ra thư mục xuất. Cũng có thể kéo thả ảnh vào cửa sổ này.",
+                batch_progress: "{done}/{total} ảnh ({failed} lỗi)",
+                batch_cancel_btn: "Dừng",
+                batch_open_folder_btn: "Mở thư mục",
+                batch_concurrency_label: "Dịch hàng loạt: số ảnh cùng lúc",
+                batch_concurrency_tooltip: "Nhiều hơn = nhanh hơn nhưng dễ chạm giới hạn tốc độ của API (tự thử lại khi bị 429).",
+                batch_per_image_label: "File .txt cho mỗi ảnh",
+                batch_combined_label: "File gộp",
+                batch_output_label: "Thư mục xuất:",
+                batch_output_hint: "Để trống = thư mục \"translated\" cạnh ảnh",
+                show_crash_dialog_label: "Hiện thông báo khi ứng dụng bị lỗi (crash)",
+                show_crash_dialog_tooltip: "Tắt để chỉ ghi lỗi vào app.log.",
+                output_mode_label: "Xuất kết quả:",
+                output_mode_tooltip: "Gõ ra: gõ kết quả vào cửa sổ đang dùng lúc nhấn phím tắt, cho ứng dụng/game chặn dán. Nhấn Esc hoặc di chuột để dừng.",
+                output_mode_clipboard: "Bộ nhớ tạm",
+                output_mode_type_out: "Gõ ra",
+                type_out_delay_label: "Mỗi ký tự:",
+                type_out_delay_tooltip: "Thời gian chờ giữa các ký tự. Tăng lên nếu ứng dụng bị mất ký tự.",
+                output_file_label: "Ghi vào file:",
+                output_file_tooltip: "Mỗi kết quả (kèm bản dịch lại) được thêm vào cuối file này, có ghi thời gian, ví dụ file ghi chú notes.md.",
+                output_file_none: "Không",
+                output_file_browse: "Chọn file…",
+                image_upload_format_label: "Định dạng ảnh gửi đi:",
+                image_upload_format_tooltip: "WebP (không mất dữ liệu) thường nhỏ hơn PNG với ảnh chụp màn hình, gửi nhanh hơn. Groq không nhận WebP nên vẫn dùng PNG.",
+                upscale_small_crops_label: "Phóng to vùng chụp nhỏ (chữ nhỏ)",
+                upscale_small_crops_tooltip: "Vùng chụp thấp (khoảng 1-2 dòng, như phụ đề) hoặc nhỏ được gửi với kích thước gấp đôi (bộ lọc Lanczos) để mô hình đọc chữ nhỏ chính xác hơn. Vùng nhỏ luôn gửi dạng PNG.",
+                webhook_header: "Webhook (HTTP POST)",
+                webhook_url_label: "URL:",
+                webhook_template_label: "Nội dung JSON:",
+                webhook_template_tooltip: "Được gửi sau mỗi lần chạy thành công. {result}, {retranslation}, {preset} và {timestamp} được thay bằng giá trị (đã escape cho chuỗi JSON).",
+                webhook_headers_label: "Header (vd. token xác thực):",
+                webhook_add_header: "+ Header",
+                webhook_test_btn: "Gửi dữ liệu thử",
+                strip_preamble_label: "Bỏ câu mở đầu (\"Đây là bản dịch:\")",
+                strip_preamble_tooltip: "Xóa dòng đầu kiểu \"Đây là bản dịch:\" mà một số mô hình thêm vào trước kết quả. Chỉ xóa các mẫu quen thuộc, phần còn lại giữ nguyên.",
+                reflow_paragraphs_label: "Nối dòng trong đoạn văn",
+                reflow_paragraphs_tooltip: "Nối các dòng bị ngắt theo hình ảnh thành đoạn văn liền mạch (giữ dòng trống giữa các đoạn và các mục danh sách), bỏ khoảng trắng thừa. Tiếng Trung, Nhật, Thái được nối không thêm dấu cách. Shift+chuột phải trên cửa sổ kết quả để sao chép bản có ngắt dòng gốc.",
+                reflow_dehyphenate_label: "Bỏ gạch nối cuối dòng",
+                reflow_dehyphenate_tooltip: "Nối lại từ bị tách bằng gạch nối ở cuối dòng (\"transla-\" + \"tion\" → \"translation\").",
+                study_layout_label: "Chế độ học (cách đọc + bản dịch)",
+                study_layout_tooltip: "Hiển thị văn bản gốc, cách đọc (furigana, pinyin, phiên âm) và bản dịch thành ba phần riêng. Yêu cầu mô hình trả về JSON, nên kết quả hiện khi đã xong (không stream); nếu không đọc được thì hiển thị như bình thường. Lịch sử giữ riêng từng phần để xuất sang Anki.",
+                copy_format_label: "Định dạng khi copy:",
+                copy_format_tooltip: "Cách bọc văn bản khi copy (nút copy, chuột phải, tự động copy), ví dụ khối code để dán vào Discord. Shift+chuột phải trên cửa sổ kết quả để chọn định dạng khác cho một lần copy.",
+                copy_format_plain: "Văn bản thường",
+                copy_format_code_block: "Khối code Markdown",
+                copy_format_quote: "Trích dẫn (>)",
+                copy_format_source_header: "Kèm tiêu đề nguồn",
+                result_copy_hint: "Ctrl+Enter: sao chép rồi đóng",
+                result_retry_label: "Thử lại",
+                copy_unreflowed_label: "Sao chép với ngắt dòng gốc",
+                live_follow_window_label: "Bám theo cửa sổ",
+                live_follow_window_tooltip: "Vùng chụp gắn với cửa sổ bên dưới (ví dụ trình phát video) và di chuyển, co giãn theo cửa sổ đó. Live sẽ dừng khi cửa sổ bị đóng.",
+                live_follow_window_match_label: "Tiêu đề / lớp cửa sổ:",
+                live_follow_window_hint: "Để trống = cửa sổ tại vùng chọn",
+                completion_sound_label: "Âm báo khi xong",
+                completion_toast_label: "Thông báo khi xong",
+                completion_tooltip: "Cửa sổ kết quả đang ẩn: báo khi preset xong (\"Đã copy 213 ký tự\") và báo khác khi lỗi. Cách nhau ít nhất 3 giây để chế độ Live không báo liên tục.",
+                min_region_size_label: "Vùng chọn tối thiểu:",
+                min_region_size_tooltip: "Vùng chọn hẹp hơn hoặc thấp hơn số này bị bỏ qua kèm thông báo, tránh gửi yêu cầu vô ích khi lỡ tay. Giảm xuống nếu cần chọn chữ rất nhỏ.",
+                hotkey_debounce_label: "Chống lặp phím tắt:",
+                hotkey_debounce_tooltip: "Bỏ qua lần nhấn thứ hai của cùng một phím tắt trong khoảng thời gian này (phím tự lặp, bàn phím hoặc phần mềm macro gửi phím hai lần), tránh mở hai vùng chọn hoặc gửi hai yêu cầu. 0 = tắt.",
+                preset_indicator_tooltip: "Một nhãn nhỏ luôn nằm trên cùng ở góc màn hình cho biết preset đang dùng (preset vừa kích hoạt bằng phím tắt hoặc đang chọn trong cài đặt). Không chặn chuột. Cũng bật/tắt được từ khay hệ thống.",
+                preset_indicator_corner_label: "Vị trí:",
+                corner_top_left: "Trên trái",
+                corner_top_right: "Trên phải",
+                corner_bottom_left: "Dưới trái",
+                corner_bottom_right: "Dưới phải",
+                capture_mode_region: "Chọn vùng màn hình",
+                capture_mode_replace_selection: "Dịch và thay thế văn bản đang chọn",
+                replace_selection_hint: "Bôi đen văn bản trong ô nhập bất kỳ rồi nhấn phím tắt: văn bản được dịch và dán đè lên vùng chọn.",
+                replace_selection_confirm: "Chế độ này giả lập phím Ctrl+C và Ctrl+V trong ứng dụng đang mở, và tạm thời dùng clipboard (nội dung cũ sẽ được khôi phục sau đó). Văn bản đã chọn sẽ được gửi tới mô hình dịch. Bật chế độ này?",
+                replace_selection_confirm_yes: "Bật",
                 add_hotkey_button: "+ Thêm Phím",
                 press_keys: "Ấn tổ hợp phím...",
                 cancel_label: "Hủy",
@@ -125,13 +587,59 @@ impl LocaleText {
                 audio_source_label: "Nguồn âm thanh:",
                 audio_src_mic: "Microphone",
                 audio_src_device: "Âm thanh máy tính",
+                audio_src_both: "Cả hai (mic + máy)",
+                loopback_device_label: "Thiết bị phát:",
+                loopback_device_tooltip: "Thiết bị đầu ra được ghi lại khi ghi âm máy (ví dụ một cáp âm thanh ảo mà một ứng dụng được chuyển tới)",
+                loopback_device_default: "Mặc định",
+                loopback_device_refresh: "Làm mới danh sách thiết bị",
+                loopback_device_missing: "⚠ Thiết bị này hiện không kết nối, ghi âm sẽ báo lỗi",
                 hide_recording_ui_label: "Ẩn giao diện ghi âm",
+                optimize_audio_upload_label: "Tối ưu dung lượng tải lên",
+                optimize_audio_upload_tooltip: "Chuyển âm thanh sang 16 kHz mono trước khi gửi (nhỏ hơn tới ~6 lần, chất lượng nhận dạng không đổi)",
+                max_recording_label: "Giới hạn ghi âm:",
+                max_recording_tooltip: "Tự dừng và xử lý khi đạt giới hạn (0 = không giới hạn). Ở chế độ Live, đây là giới hạn của cả phiên.",
+                recording_processing: "Đang xử lý...",
+                recording_paused: "Tạm dừng",
+                recording_device: "Ghi âm máy...",
+                recording_both: "Ghi âm mic + máy...",
+                recording_mic: "Ghi âm mic...",
+                recording_hint: "Bấm hotkey lần nữa để xử lý âm thanh",
+                recording_countdown: "Tự dừng sau {}",
+                whisper_language_label: "Ngôn ngữ nói:",
+                whisper_language_auto: "Tự nhận diện",
+                whisper_language_tooltip: "Gợi ý ngôn ngữ cho Whisper. Chọn cố định nếu bạn nói xen kẽ nhiều ngôn ngữ và Whisper nhận sai.",
+                whisper_translate_label: "Dịch sang tiếng Anh",
+                whisper_translate_tooltip: "Dùng endpoint dịch của Whisper: kết quả luôn là tiếng Anh (dùng model large-v3).",
+                open_audio_file_btn: "Mở file âm thanh…",
+                open_audio_file_tooltip: "Chép lời một file có sẵn (mp3, m4a, wav…) bằng preset này. Cũng có thể kéo thả file vào cửa sổ cài đặt.",
+                audio_drop_no_preset: "Chưa có preset âm thanh nào để chép lời file này",
+                audio_output_not_found: "Không tìm thấy thiết bị phát \"{}\". Thiết bị còn kết nối không?",
+                audio_no_loopback: "Không ghi âm được từ thiết bị phát \"{}\" (không hỗ trợ loopback)",
+                audio_no_input: "Không tìm thấy micro",
+                audio_capture_failed: "Không bắt đầu ghi âm được: {}",
+                onboarding_title: "Chào mừng! Thiết lập nhanh",
+                onboarding_intro: "Ứng dụng cần một API key (miễn phí) để gửi ảnh chụp và âm thanh tới mô hình AI. Chọn một nhà cung cấp:",
+                onboarding_groq_desc: "Groq — rất nhanh, hạn mức miễn phí rộng rãi. Dùng cho đa số preset và Whisper (ghi âm).",
+                onboarding_gemini_desc: "Google Gemini — chính xác hơn, hiểu âm thanh trực tiếp, hạn mức miễn phí thấp hơn.",
+                onboarding_paste_key: "Dán API key vào đây:",
+                onboarding_test_btn: "Kiểm tra",
+                onboarding_testing: "Đang kiểm tra...",
+                onboarding_test_ok: "✅ Key hoạt động!",
+                onboarding_test_invalid: "❌ Key không hợp lệ, hãy kiểm tra lại.",
+                onboarding_hotkey_label: "Phím tắt mặc định: nhấn {} để chọn vùng màn hình và dịch. Có thể đổi trong từng preset.",
+                onboarding_finish: "Bắt đầu sử dụng",
+                onboarding_skip: "Bỏ qua",
                 hotkeys_section: "Phím tắt",
                 usage_statistics_title: "Thống kê sử dụng",
                 usage_statistics_tooltip: "Dùng mô hình ít nhất một lần để hiện chính xác",
                 usage_model_column: "Mô hình",
                 usage_remaining_column: "Còn lại / Tổng",
                 usage_check_link: "Xem lượng dùng ↗",
+                translation_cache_hits: "Bộ nhớ dịch: {} lần dùng lại ({} mục)",
+                translation_cache_clear: "Xóa bộ nhớ",
+                translation_cache_size_label: "Số mục tối đa:",
+                translation_cache_tooltip: "Kết quả giống hệt (cùng model, yêu cầu và nội dung) được lấy lại từ bộ nhớ thay vì gọi API lần nữa. 0 = tắt.",
+                translation_cache_persist_label: "Lưu vào ổ đĩa",
                 empty_prompt_warning: "Cảnh báo: Câu lệnh trống có thể cho kết quả không như mong đợi!",
                 footer_admin_text: "chạy bằng admin để dịch game",
                 footer_version: "phiên bản v2.1",
@@ -141,6 +649,19 @@ impl LocaleText {
                 history_favorites: "Yêu thích",
                 history_clear_all: "Xóa tất cả",
                 history_empty: "Chưa có lịch sử",
+                history_deleted_toast: "Đã xóa khỏi lịch sử",
+                history_undo: "Hoàn tác",
+                history_find_hint: "Tìm trong nội dung...",
+                history_find_case: "Aa (phân biệt hoa/thường)",
+                history_tags_label: "Nhãn:",
+                history_tags_hint: "Thêm nhãn...",
+                history_tag_filter_all: "Mọi nhãn",
+                history_export_srt: "🎬 Xuất SRT",
+                history_export_anki: "🃏 Xuất bộ thẻ Anki",
+                history_export_anki_tooltip: "Các kết quả chế độ học đang hiển thị, mỗi kết quả một thẻ (Gốc, Cách đọc, Bản dịch, nhãn). Nhập trong Anki bằng File > Import.",
+                history_detected_language_tooltip: "Ngôn ngữ của chữ trong ảnh, theo mô hình",
+                history_clear_confirm: "Xóa toàn bộ lịch sử? Bạn vẫn có thể hoàn tác ngay sau đó.",
+                history_clear_confirm_yes: "Xóa hết",
                 // Live Captions
                 live_captions_title: "Live Captions (Dịch giọng nói)",
                 live_captions_tooltip: "Dịch thời gian thực từ Windows Live Captions (Win11 22H2+)",
@@ -151,6 +672,9 @@ impl LocaleText {
                 live_captions_sentences: "Số câu hiển thị:",
                 live_captions_show_original: "Hiển thị gốc",
                 live_captions_auto_hide: "Tự ẩn Live Captions",
+                live_captions_hotkey: "Phím tắt bật/tắt:",
+                live_captions_audio_source: "Nguồn âm thanh:",
+                live_captions_audio_source_tooltip: "Chỉ dùng cho Gemini Live. Các model khác đọc chữ từ Live Captions của Windows.",
                 // Quick Actions & AI Chat
                 quick_actions_title: "Hành động nhanh",
                 quick_actions_enabled: "Bật menu hành động nhanh",
@@ -173,6 +697,9 @@ impl LocaleText {
                 gemini_get_key_link: "aistudio.google.com에서 API 키 받기",
                 openrouter_api_key_label: "OpenRouter API 키:",
                 openrouter_get_key_link: "openrouter.ai에서 API 키 받기",
+                refresh_models_button: "🔄 모델 목록 새로고침",
+                refresh_models_tooltip: "입력한 API 키로 Groq, Gemini, OpenRouter(무료 모델만)의 최신 모델 목록을 가져와 모델 선택 목록에 추가합니다. 기본 목록은 그대로 유지됩니다.",
+                refresh_models_done: "새 모델 {}개를 찾았습니다",
                 presets_section: "프리셋 목록",
                 global_settings: "전역 설정",
                 preset_name_label: "프리셋 이름:",
@@ -182,6 +709,22 @@ impl LocaleText {
                 retranslate_checkbox: "재번역 활성화",
                 retranslate_to_label: "번역 대상:",
                 retranslate_model_label: "재번역 모델:",
+                retranslate_secondary_checkbox: "추가 번역 대상:",
+                retranslate_secondary_model_label: "추가 번역 모델:",
+                early_retranslate_label: "초안으로 미리 번역 (스트리밍 중)",
+                retranslate_same_as_detected_label: "감지된 원문 언어",
+                retranslate_same_as_detected_tooltip: "번역문을 모델이 이미지에서 감지한 언어로 다시 번역합니다. 이미지 속 텍스트가 이미 번역문의 언어이면 재번역 창을 열지 않습니다 (API 호출 절약).",
+                retranslate_srt_label: "번역된 SRT용 구간별 번역",
+                preserve_layout_label: "레이아웃 유지 (코드, 표)",
+                preserve_layout_tooltip: "줄바꿈과 들여쓰기를 유지하고 고정폭 글꼴로 줄바꿈 없이 표시",
+                ocr_quality_check_label: "결과가 깨져 보이면 경고",
+                ocr_quality_check_tooltip: "결과 창에 작은 '신뢰도 낮음' 배지를 표시 (이상한 문자, 반복, 비정상적 길이)",
+                multi_frame_label: "다중 프레임 캡처",
+                multi_frame_tooltip: "약 1초 동안 여러 프레임을 캡처해 텍스트를 병합 (흐르는 자막, 사라지는 자막)",
+                multi_frame_count_label: "프레임 수:",
+                expand_on_empty_label: "텍스트가 없으면 더 큰 영역으로 재시도",
+                expand_on_empty_tooltip: "모델이 선택 영역에서 텍스트를 찾지 못하면(대개 선택이 약간 어긋난 경우) 영역을 사방으로 넓혀 한 번 더 보냅니다. 두 번째 요청은 API 사용량이 추가됩니다.",
+                expand_on_empty_margin_label: "각 방향 확장:",
                 hotkey_bag_label: "활성화 단축키:",
                 add_preset_btn: "+ 프리셋 추가",
                 search_placeholder: "언어 검색...",
@@ -191,6 +734,187 @@ impl LocaleText {
                 streaming_option_wait: "완료 대기",
                 auto_copy_label: "자동 복사",
                 startup_label: "Windows 시작 시 실행",
+                max_history_label: "최대 기록 항목 수:",
+                max_history_tooltip: "한도를 넘으면 가장 오래된 항목(즐겨찾기 제외)이 삭제됩니다. 0 = 무제한.",
+                toggle_results_hotkey_label: "결과 창 숨기기/표시 단축키:",
+                debug_metrics_label: "API 응답 시간 기록",
+                debug_metrics_tooltip: "각 요청의 첫 청크까지의 시간, 총 시간, 속도(문자/초)를 로그에 기록합니다. 네트워크 지연과 모델 지연을 구분하는 데 도움이 됩니다.",
+                use_gemini_live_label: "Gemini Live 사용",
+                use_gemini_live_tooltip: "웹소켓으로 오디오를 Gemini Live에 실시간 전송합니다(1초 미만 지연). Gemini API 키가 필요하며, 연결할 수 없으면 구간 업로드 방식으로 자동 전환됩니다.",
+                gemini_live_only_failed: "Gemini Live에 연결할 수 없으며 이 모델은 Live에서만 작동합니다. Gemini API 키를 확인하거나 다른 모델을 선택하세요.",
+                show_live_stats_label: "지연 통계 표시",
+                show_live_stats_tooltip: "결과 창 하단에 작은 줄 표시: 캡처 후 대기 시간, API 응답 시간, 대기 중인 청크 수 (cap / api / q)",
+                capture_mode_label: "모드:",
+                overlay_appearance_title: "영역 선택 오버레이",
+                overlay_dim_label: "배경 어둡기:",
+                overlay_border_color_label: "테두리 색:",
+                overlay_border_width_label: "두께:",
+                overlay_grid_label: "3분할 격자",
+                overlay_crosshair_label: "커서 십자선",
+                overlay_appearance_reset: "기본값",
+                live_chunk_secs_label: "청크 길이:",
+                live_chunk_secs_tooltip: "청크가 길수록(4–5초) 느리게 말하는 사람의 인식이 좋아지지만 텍스트가 늦게 표시됩니다.",
+                live_chunk_overlap_label: "겹침:",
+                live_chunk_overlap_tooltip: "이전 청크의 끝부분을 다음 청크 앞에 반복해 경계에서 단어가 잘리지 않게 합니다. 중복된 단어는 제거됩니다.",
+                keep_chat_image_label: "후속 질문에 스크린샷 유지",
+                keep_chat_image_tooltip: "후속 질문마다 캡처한 이미지(최대 약 1MP로 축소)를 함께 보냅니다. 결과 창에서 Enter를 눌러 이어서 질문하세요.",
+                preview_prompt_btn: "프롬프트 미리보기",
+                preview_prompt_hint: "실제로 전송될 프롬프트입니다 (채팅 모드는 예시 질문 사용):",
+                preview_prompt_sample_question: "이 이미지에 무엇이 있나요?",
+                unmapped_language_warning: "언어가 지정되지 않은 태그 (그대로 전송됨):",
+                include_context_label: "창 제목 함께 보내기 (맥락)",
+                include_context_tooltip: "단축키를 누를 때 앞에 있던 창의 제목(예: 게임이나 만화 이름)을 프롬프트에 추가해 고유명사를 더 정확하게 번역합니다. 캡처마다 토큰이 조금 더 듭니다.",
+                language_cycle_label: "{language1} 언어 순환:",
+                language_cycle_add: "+ 추가",
+                language_cycle_tooltip: "순서대로 된 언어 목록입니다. 언어 순환 단축키(단축키 섹션, 언어 2개 이상 필요)가 {language1}을 다음 언어로 바꾸고 저장합니다.",
+                close_label: "닫기",
+                blocked_apps_title: "캡처 차단 앱",
+                blocked_apps_tooltip: "이 앱이 앞에 있을 때는 캡처나 번역을 하지 않습니다 (예: 비밀번호 관리자). Live 모드는 해당 앱이 활성화되면 자동으로 일시 중지됩니다.",
+                blocked_apps_placeholder: "프로세스 이름, 예: KeePassXC.exe",
+                blocked_apps_add: "추가",
+                advanced_model_params: "고급",
+                advanced_model_params_hint: "이 모델을 쓰는 모든 프리셋에 적용됩니다. 선택 해제 = API 기본값.",
+                temperature_label: "온도",
+                max_output_tokens_label: "최대 출력 토큰",
+                thinking_budget_label: "사고 예산",
+                thinking_budget_tooltip: "답변 전에 추론에 쓸 수 있는 토큰 수. -1 = 자동, 0 = 끔. 높을수록 정확하지만 느리고 비용이 큽니다.",
+                max_tokens_tooltip: "답변 길이 제한. 높이면 긴 전체 페이지 번역이 잘리지 않지만 토큰 비용이 늘어납니다.",
+                model_params_title: "Gemini 모델 설정",
+                preset_indicator_label: "활성 프리셋 표시",
+                capture_mode_full_monitor: "전체 화면",
+                capture_monitor_missing: "이 모니터가 현재 연결되어 있지 않습니다. 기본 모니터를 캡처합니다.",
+                copy_markdown_label: "복사 버튼으로 Markdown 복사 (채팅 결과)",
+                copy_markdown_tooltip: "채팅 답변은 Markdown 서식 없이 표시됩니다. 켜면 복사 버튼은 원본 Markdown(코드 블록, 목록 유지)을, 오른쪽 클릭은 일반 텍스트를 복사합니다. 끄면 반대로 동작합니다.",
+                per_app_language_label: "앱별로 언어 기억",
+                per_app_language_tooltip: "열려 있는 앱(예: game.exe)에서 사용한 대상 언어를 기록하고, 다음에 그 앱에서 프리셋을 실행하면 같은 언어를 사용합니다. 여기서 언어를 바꾸면 프리셋이 다음에 실행되는 앱에 적용됩니다.",
+                retranslate_to_ui_language_label: "UI 언어로 번역",
+                retranslate_to_ui_language_tooltip: "위에서 선택한 언어 대신 항상 앱의 UI 언어(Tiếng Việt, 한국어 또는 English)로 번역합니다. UI 언어를 바꾸면 번역 언어도 함께 바뀝니다.",
+                per_app_language_forget: "잊기 (앱 {}개)",
+                overlay_animations_label: "결과 창 빗자루 애니메이션",
+                overlay_animations_tooltip: "빗자루 커서와 결과 창을 닫을 때의 쓸기 효과입니다. 끄면(또는 Windows 애니메이션 효과가 꺼져 있으면) 일반 화살표 커서가 표시되고 클릭하면 창이 바로 닫힙니다. 이후에 열리는 창에 적용됩니다.",
+                dedup_history_label: "최근 줄 기억:",
+                dedup_history_tooltip: "최근 N줄 중 하나와 같은 결과는 건너뛰어 반복되는 자막을 다시 번역하지 않습니다.",
+                open_config_folder_btn: "설정 폴더 열기",
+                open_log_file_btn: "로그 파일 열기",
+                copy_diagnostics_btn: "진단 정보 복사",
+                copy_diagnostics_tooltip: "앱 버전, Windows 버전과 app.log의 끝부분을 복사해 버그 보고에 붙여넣을 수 있습니다. API 키는 포함되지 않습니다.",
+                diagnostics_copied: "복사됨!",
+                log_level_label: "로그 수준:",
+                log_level_debug_tooltip: "문제 해결용 상세 정보를 기록합니다 (로그 파일이 더 빨리 커집니다).",
+                log_max_size_label: "로그 파일 최대 크기:",
+                log_max_size_tooltip: "app.log가 이 크기를 넘으면 app.log.1로 이름이 바뀌고(이전 백업 대체) 새 파일이 시작됩니다.",
+                dismiss_on_outside_click_label: "바깥을 클릭하면 닫기",
+                dismiss_on_outside_click_tooltip: "결과 창 밖 아무 곳이나 클릭하면 창이 닫힙니다 (클릭은 아래 앱에 그대로 전달됨). 재번역 창을 클릭하면 닫히지 않습니다. 라이브 세션에는 적용되지 않습니다.",
+                check_updates_label: "업데이트 확인",
+                check_updates_tooltip: "시작할 때 (하루 최대 한 번) GitHub에 새 버전이 있는지 확인하고 창 하단에 알려줍니다. 자동으로 다운로드하지 않습니다.",
+                update_available: "새 버전 {} — 클릭하여 보기",
+                rerun_hotkey_label: "마지막 캡처 다시 실행:",
+                rerun_hotkey_tooltip: "마지막으로 캡처한 영역(어떤 프리셋이든)에 이 프리셋을 다시 선택 없이 실행합니다",
+                cycle_language_hotkey_label: "대상 언어 순환:",
+                cycle_language_hotkey_tooltip: "{language1}을 순환 목록의 다음 언어로 바꾸고 알림을 표시합니다. 다음 캡처부터 적용됩니다",
+                last_capture_keep_label: "마지막 캡처 보관:",
+                last_capture_keep_tooltip: "다시 실행 단축키용으로 마지막 캡처 이미지를 보관하는 시간이며, 이후 메모리에서 삭제됩니다 (0 = 보관 안 함)",
+                target_monitor_label: "선택 모니터:",
+                target_monitor_tooltip: "커서 위치와 관계없이 이 모니터에서만 영역을 선택합니다",
+                capture_on_release_label: "마우스를 놓을 때 캡처",
+                capture_on_release_tooltip: "단축키를 누를 때 화면을 고정하지 않습니다. 선택 중에도 화면이 계속 재생되고, 마우스를 놓는 순간 선택 영역만 캡처합니다 (동영상 프레임용)",
+                font_family_label: "결과 글꼴:",
+                font_family_tooltip: "결과 창에 사용할 Windows 글꼴 이름 (예: Malgun Gothic). 비워 두면 텍스트의 문자(한글, 일본어, 중국어, 태국어 등)를 지원하는 글꼴을 자동으로 선택합니다.",
+                font_family_auto: "자동",
+                target_monitor_all: "모든 모니터",
+                target_monitor_missing: "이 모니터가 현재 연결되어 있지 않습니다. 커서가 있는 모니터를 사용합니다.",
+                preset_picker_hotkey_label: "프리셋 선택 후 영역 선택 단축키:",
+                stop_all_hotkey_label: "모든 녹음/Live 중지 단축키:",
+                recent_copies_hotkey_label: "최근 복사한 결과 목록 단축키:",
+                reading_order_label: "읽는 순서:",
+                reading_order_tooltip: "결과에 담길 컷과 말풍선의 순서. 만화(망가)는 \"오른쪽에서 왼쪽\"을 선택하세요",
+                reading_order_auto: "자동",
+                reading_order_ltr: "왼쪽에서 오른쪽 (코믹스)",
+                reading_order_rtl: "오른쪽에서 왼쪽 (망가)",
+                show_model_badge_label: "결과에 모델과 소요 시간 표시",
+                show_model_badge_tooltip: "결과 창 오른쪽 위에 사용한 모델과 처리 시간을 표시합니다 (예: \"gemini-2.0-flash · 1.8s\"). 텍스트와 함께 복사되지 않습니다.",
+                compare_model_label: "비교 모델:",
+                compare_model_tooltip: "같은 캡처를 두 번째 모델에도 보내 옆 창에 결과를 표시합니다. 두 결과 모두 기록에 저장되며, 자동 복사는 기본 결과만 사용합니다.",
+                compare_model_off: "끔",
+                quick_translate_label: "빠른 번역 버튼(🌐) 대상 언어:",
+                quick_translate_tooltip: "결과 창의 지구본 버튼은 표시된 내용을 이 언어로 번역합니다. 다시 누르면 원문으로 돌아갑니다",
+                auto_copy_delay_label: "복사 지연",
+                auto_copy_delay_tooltip: "결과를 복사하기 전에 잠시 기다립니다. 클립보드 관리자가 놓치거나 잘못 복사하면 늘리세요.",
+                copy_on_close_label: "닫을 때 복사",
+                copy_on_close_tooltip: "결과가 나오자마자가 아니라 결과 창을 닫을 때 복사합니다 (완료 및 수정 내용 포함).",
+                batch_btn: "이미지 일괄 번역…",
+                batch_tooltip: "여러 PNG 또는 WebP 이미지를 골라 이 프리셋으로 처리하고 결과를 출력 폴더에 저장합니다. 이미지를 이 창에 끌어다 놓아도 됩니다.",
+                batch_progress: "{done}/{total} 이미지 ({failed} 실패)",
+                batch_cancel_btn: "중지",
+                batch_open_folder_btn: "폴더 열기",
+                batch_concurrency_label: "일괄 번역: 동시 처리 수",
+                batch_concurrency_tooltip: "많을수록 빠르지만 API 속도 제한에 걸리기 쉽습니다 (429 시 자동 재시도).",
+                batch_per_image_label: "이미지별 .txt",
+                batch_combined_label: "합친 파일",
+                batch_output_label: "출력 폴더:",
+                batch_output_hint: "비우면 이미지 옆 \"translated\" 폴더",
+                show_crash_dialog_label: "충돌 시 알림 표시",
+                show_crash_dialog_tooltip: "끄면 app.log에만 기록합니다.",
+                output_mode_label: "결과 출력:",
+                output_mode_tooltip: "타이핑: 단축키를 누를 때 활성 창에 결과를 입력합니다 (붙여넣기를 막는 앱/게임용). Esc 또는 마우스 이동으로 중지.",
+                output_mode_clipboard: "클립보드",
+                output_mode_type_out: "타이핑",
+                type_out_delay_label: "글자당:",
+                type_out_delay_tooltip: "글자 사이 대기 시간. 글자가 누락되면 늘리세요.",
+                output_file_label: "파일에 추가:",
+                output_file_tooltip: "각 결과(재번역 포함)를 시간과 함께 이 파일 끝에 추가합니다. 예: notes.md 메모 파일.",
+                output_file_none: "없음",
+                output_file_browse: "파일 선택…",
+                image_upload_format_label: "업로드 이미지 형식:",
+                image_upload_format_tooltip: "WebP(무손실)는 화면 캡처에서 보통 PNG보다 작아 업로드가 빠릅니다. Groq는 WebP를 지원하지 않아 PNG를 사용합니다.",
+                upscale_small_crops_label: "작은 캡처 영역 확대 (작은 글자)",
+                upscale_small_crops_tooltip: "낮은(자막처럼 1~2줄) 또는 작은 캡처 영역을 2배 크기(Lanczos 필터)로 보내 작은 글자를 더 정확하게 읽게 합니다. 작은 영역은 항상 PNG로 보냅니다.",
+                webhook_header: "웹훅 (HTTP POST)",
+                webhook_url_label: "URL:",
+                webhook_template_label: "JSON 본문:",
+                webhook_template_tooltip: "성공한 실행마다 전송됩니다. {result}, {retranslation}, {preset}, {timestamp}는 값으로 바뀝니다 (JSON 문자열용으로 이스케이프).",
+                webhook_headers_label: "헤더 (예: 인증 토큰):",
+                webhook_add_header: "+ 헤더",
+                webhook_test_btn: "테스트 전송",
+                strip_preamble_label: "머리말 제거 (\"다음은 번역입니다:\")",
+                strip_preamble_tooltip: "일부 모델이 결과 앞에 붙이는 \"다음은 번역입니다:\" 같은 첫 줄을 제거합니다. 알려진 형태만 제거하고 나머지는 그대로 둡니다.",
+                reflow_paragraphs_label: "문단 줄 합치기",
+                reflow_paragraphs_tooltip: "이미지대로 끊긴 줄을 이어 하나의 문단으로 만들고 (문단 사이 빈 줄과 목록 항목은 유지) 중복 공백을 정리합니다. 중국어, 일본어, 태국어는 공백 없이 잇습니다. 결과 창에서 Shift+오른쪽 클릭하면 원래 줄바꿈 그대로 복사할 수 있습니다.",
+                reflow_dehyphenate_label: "줄 끝 하이픈 제거",
+                reflow_dehyphenate_tooltip: "줄 끝에서 하이픈으로 나뉜 단어를 다시 붙입니다 (\"transla-\" + \"tion\" → \"translation\").",
+                study_layout_label: "학습 레이아웃 (읽기 + 번역)",
+                study_layout_tooltip: "원문, 읽기(후리가나, 병음, 로마자 표기), 번역을 세 부분으로 나누어 표시합니다. 모델에 JSON 응답을 요청하므로 결과는 완료된 뒤 표시되며(스트리밍 없음), 해석할 수 없으면 그대로 표시합니다. 기록에 각 부분이 따로 저장되어 Anki로 내보낼 수 있습니다.",
+                copy_format_label: "복사 형식:",
+                copy_format_tooltip: "복사할 때 텍스트를 감싸는 방식 (복사 버튼, 우클릭, 자동 복사). 예: Discord에 붙여넣을 코드 블록. 결과 창에서 Shift+우클릭하면 이번 복사에만 다른 형식을 고를 수 있습니다.",
+                copy_format_plain: "일반 텍스트",
+                copy_format_code_block: "마크다운 코드 블록",
+                copy_format_quote: "인용 (>)",
+                copy_format_source_header: "출처 머리글 포함",
+                result_copy_hint: "Ctrl+Enter: 복사 후 닫기",
+                result_retry_label: "다시 시도",
+                copy_unreflowed_label: "원래 줄바꿈으로 복사",
+                live_follow_window_label: "창 따라가기",
+                live_follow_window_tooltip: "캡처 영역이 아래 창(예: 동영상 플레이어)에 붙어 창을 옮기거나 크기를 바꾸면 함께 따라갑니다. 창이 닫히면 Live가 중지됩니다.",
+                live_follow_window_match_label: "창 제목 / 클래스:",
+                live_follow_window_hint: "비우면 = 선택 영역의 창",
+                completion_sound_label: "완료 시 소리",
+                completion_toast_label: "완료 시 알림",
+                completion_tooltip: "결과 창이 숨겨져 있을 때: 프리셋이 끝나면 알리고(\"213자 복사됨\") 실패하면 다르게 알립니다. Live 모드에서 반복되지 않도록 최소 3초 간격을 둡니다.",
+                min_region_size_label: "최소 선택 크기:",
+                min_region_size_tooltip: "이보다 좁거나 낮은 선택은 알림과 함께 건너뛰어 실수로 인한 요청을 막습니다. 아주 작은 글자를 선택하려면 낮추세요.",
+                hotkey_debounce_label: "단축키 중복 방지:",
+                hotkey_debounce_tooltip: "이 시간 안에 같은 단축키가 다시 눌리면 무시합니다 (키 반복, 키를 두 번 보내는 키보드나 매크로 도구). 선택 화면이 두 번 열리거나 요청이 두 번 전송되는 것을 막습니다. 0 = 끔.",
+                preset_indicator_tooltip: "화면 모서리에 현재 프리셋(마지막으로 단축키로 실행했거나 설정에서 선택한 프리셋)을 보여주는 작은 라벨이 항상 위에 표시됩니다. 클릭은 통과됩니다. 트레이 메뉴에서도 켜고 끌 수 있습니다.",
+                preset_indicator_corner_label: "위치:",
+                corner_top_left: "왼쪽 위",
+                corner_top_right: "오른쪽 위",
+                corner_bottom_left: "왼쪽 아래",
+                corner_bottom_right: "오른쪽 아래",
+                capture_mode_region: "화면 영역 선택",
+                capture_mode_replace_selection: "선택한 텍스트 번역 후 바꾸기",
+                replace_selection_hint: "아무 입력란에서 텍스트를 선택하고 단축키를 누르면 번역된 텍스트가 선택 영역에 붙여넣어집니다.",
+                replace_selection_confirm: "이 모드는 현재 앱에 Ctrl+C와 Ctrl+V 키 입력을 보내고 클립보드를 잠시 사용합니다(기존 내용은 이후 복원됨). 선택한 텍스트는 번역 모델로 전송됩니다. 이 모드를 사용하시겠습니까?",
+                replace_selection_confirm_yes: "사용",
                 add_hotkey_button: "+ 키 추가",
                 press_keys: "조합 키 누르기...",
                 cancel_label: "취소",
@@ -205,13 +929,59 @@ impl LocaleText {
                 audio_source_label: "오디오 소스:",
                 audio_src_mic: "마이크",
                 audio_src_device: "컴퓨터 오디오",
+                audio_src_both: "둘 다 (마이크 + 시스템)",
+                loopback_device_label: "출력 장치:",
+                loopback_device_tooltip: "시스템 오디오로 녹음할 출력 장치 (예: 특정 앱을 보낸 가상 오디오 케이블)",
+                loopback_device_default: "기본값",
+                loopback_device_refresh: "장치 목록 새로 고침",
+                loopback_device_missing: "⚠ 이 장치가 연결되어 있지 않아 녹음이 실패합니다",
                 hide_recording_ui_label: "녹음 UI 숨기기",
+                optimize_audio_upload_label: "업로드 용량 최적화",
+                optimize_audio_upload_tooltip: "전송 전 16 kHz 모노로 변환 (최대 약 6배 작아지며 인식 품질은 동일)",
+                max_recording_label: "최대 녹음 시간:",
+                max_recording_tooltip: "제한에 도달하면 자동으로 멈추고 처리합니다 (0 = 무제한). 라이브 모드에서는 세션 전체 제한입니다.",
+                recording_processing: "처리 중...",
+                recording_paused: "일시 정지",
+                recording_device: "시스템 소리 녹음 중...",
+                recording_both: "마이크 + 시스템 소리 녹음 중...",
+                recording_mic: "마이크 녹음 중...",
+                recording_hint: "단축키를 다시 누르면 오디오를 처리합니다",
+                recording_countdown: "{} 후 자동 정지",
+                whisper_language_label: "음성 언어:",
+                whisper_language_auto: "자동 감지",
+                whisper_language_tooltip: "Whisper에 언어 힌트를 줍니다. 여러 언어를 섞어 말해 자동 감지가 틀릴 때 고정하세요.",
+                whisper_translate_label: "영어로 번역",
+                whisper_translate_tooltip: "Whisper 번역 엔드포인트 사용: 결과는 항상 영어입니다 (large-v3 모델 사용).",
+                open_audio_file_btn: "오디오 파일 열기…",
+                open_audio_file_tooltip: "이 프리셋으로 기존 파일(mp3, m4a, wav…)을 받아쓰기. 설정 창에 파일을 끌어다 놓아도 됩니다.",
+                audio_drop_no_preset: "이 파일을 받아쓸 오디오 프리셋이 없습니다",
+                audio_output_not_found: "출력 장치 \"{}\"을(를) 찾을 수 없습니다. 연결되어 있나요?",
+                audio_no_loopback: "출력 장치 \"{}\"은(는) 녹음할 수 없습니다 (루프백 미지원)",
+                audio_no_input: "마이크를 찾을 수 없습니다",
+                audio_capture_failed: "오디오 녹음을 시작할 수 없습니다: {}",
+                onboarding_title: "환영합니다! 빠른 설정",
+                onboarding_intro: "앱이 캡처한 이미지와 오디오를 AI 모델로 보내려면 (무료) API 키가 필요합니다. 제공자를 선택하세요:",
+                onboarding_groq_desc: "Groq — 매우 빠르고 무료 한도가 넉넉합니다. 대부분의 프리셋과 Whisper(녹음)에 사용됩니다.",
+                onboarding_gemini_desc: "Google Gemini — 더 정확하고 오디오를 직접 이해하며 무료 한도는 더 적습니다.",
+                onboarding_paste_key: "여기에 API 키를 붙여넣으세요:",
+                onboarding_test_btn: "테스트",
+                onboarding_testing: "테스트 중...",
+                onboarding_test_ok: "✅ 키가 작동합니다!",
+                onboarding_test_invalid: "❌ 잘못된 키입니다. 다시 확인하세요.",
+                onboarding_hotkey_label: "기본 단축키: {} 를 눌러 화면 영역을 선택하고 번역합니다. 프리셋마다 변경할 수 있습니다.",
+                onboarding_finish: "시작하기",
+                onboarding_skip: "건너뛰기",
                 hotkeys_section: "단축키",
                 usage_statistics_title: "사용 통계",
                 usage_statistics_tooltip: "정확한 데이터를 보려면 모델을 최소 한 번 사용하세요",
                 usage_model_column: "모델",
                 usage_remaining_column: "남은 / 전체",
                 usage_check_link: "사용량 확인 ↗",
+                translation_cache_hits: "번역 캐시: {}회 재사용 ({}개 항목)",
+                translation_cache_clear: "캐시 지우기",
+                translation_cache_size_label: "최대 항목 수:",
+                translation_cache_tooltip: "동일한 요청(같은 모델, 프롬프트, 내용)은 API를 다시 호출하지 않고 캐시에서 가져옵니다. 0 = 사용 안 함.",
+                translation_cache_persist_label: "디스크에 저장",
                 empty_prompt_warning: "경고: 빈 프롬프트는 예측할 수 없는 결과를 낼 수 있습니다!",
                 footer_admin_text: "게임을 번역하려면 관리자로 실행하세요",
                 footer_version: "버전 v2.1",
@@ -221,6 +991,19 @@ impl LocaleText {
                 history_favorites: "즐겨찾기",
                 history_clear_all: "전체 삭제",
                 history_empty: "기록이 없습니다",
+                history_deleted_toast: "기록에서 삭제됨",
+                history_undo: "실행 취소",
+                history_find_hint: "내용에서 찾기...",
+                history_find_case: "Aa (대소문자 구분)",
+                history_tags_label: "태그:",
+                history_tags_hint: "태그 추가...",
+                history_tag_filter_all: "모든 태그",
+                history_export_srt: "🎬 SRT 내보내기",
+                history_export_anki: "🃏 Anki 덱 내보내기",
+                history_export_anki_tooltip: "표시된 학습 레이아웃 결과를 하나씩 카드로 (원문, 읽기, 번역, 태그). Anki에서 파일 > 가져오기로 불러오세요.",
+                history_detected_language_tooltip: "모델이 판단한 이미지 속 텍스트의 언어",
+                history_clear_confirm: "모든 기록을 삭제할까요? 삭제 직후 실행 취소할 수 있습니다.",
+                history_clear_confirm_yes: "모두 삭제",
                 // Live Captions
                 live_captions_title: "Live Captions (음성 번역)",
                 live_captions_tooltip: "Windows Live Captions를 통한 실시간 번역 (Win11 22H2+)",
@@ -231,6 +1014,9 @@ impl LocaleText {
                 live_captions_sentences: "표시할 문장 수:",
                 live_captions_show_original: "원문 표시",
                 live_captions_auto_hide: "Live Captions 자동 숨기기",
+                live_captions_hotkey: "켜기/끄기 단축키:",
+                live_captions_audio_source: "오디오 소스:",
+                live_captions_audio_source_tooltip: "Gemini Live 전용입니다. 다른 모델은 Windows 라이브 캡션의 텍스트를 읽습니다.",
                 // Quick Actions & AI Chat
                 quick_actions_title: "빠른 작업",
                 quick_actions_enabled: "빠른 작업 메뉴 활성화",
@@ -253,6 +1039,9 @@ impl LocaleText {
                 gemini_get_key_link: "Get API Key at aistudio.google.com",
                 openrouter_api_key_label: "OpenRouter API Key:",
                 openrouter_get_key_link: "Get API Key at openrouter.ai",
+                refresh_models_button: "🔄 Refresh models",
+                refresh_models_tooltip: "Fetches the current model lists from Groq, Gemini and OpenRouter (free models only) using your keys and adds them to the model dropdowns. The built-in list is kept.",
+                refresh_models_done: "Found {} additional models",
                 presets_section: "Presets",
                 global_settings: "Global Settings",
                 preset_name_label: "Preset Name:",
@@ -262,6 +1051,22 @@ impl LocaleText {
                 retranslate_checkbox: "Enable Retranslate",
                 retranslate_to_label: "Translate to:",
                 retranslate_model_label: "Retranslation Model:",
+                retranslate_secondary_checkbox: "Also translate to:",
+                retranslate_secondary_model_label: "Extra Translation Model:",
+                early_retranslate_label: "Start early from a draft (while streaming)",
+                retranslate_same_as_detected_label: "Same as detected",
+                retranslate_same_as_detected_tooltip: "Translates the answer back into the language the model detected in the image. When the text in the image already is in the answer's language, no retranslation window opens (and no API call is made).",
+                retranslate_srt_label: "Translate each segment for a translated SRT",
+                preserve_layout_label: "Preserve layout (code, tables)",
+                preserve_layout_tooltip: "Keeps line breaks and indentation; shows the result in a monospace font without word-wrap",
+                ocr_quality_check_label: "Warn when the result looks garbled",
+                ocr_quality_check_tooltip: "Shows a small \"low confidence\" badge on the result window (odd characters, repeated words, implausible length)",
+                multi_frame_label: "Multi-frame capture",
+                multi_frame_tooltip: "Grabs several frames over ~1s and merges the text (scrolling tickers, fading subtitles)",
+                multi_frame_count_label: "Frames:",
+                expand_on_empty_label: "No text found: retry with a larger region",
+                expand_on_empty_tooltip: "When the model finds no text in the selection (usually drawn a little off), grow the selection on every side and send it once more. The second request costs another API call.",
+                expand_on_empty_margin_label: "Grow each side by:",
                 hotkey_bag_label: "Activation Hotkeys:",
                 add_preset_btn: "+ Add Preset",
                 search_placeholder: "Search language...",
@@ -271,6 +1076,187 @@ impl LocaleText {
                 streaming_option_wait: "Wait for completion",
                 auto_copy_label: "Auto copy result",
                 startup_label: "Run at Windows Startup",
+                max_history_label: "Max history entries:",
+                max_history_tooltip: "Oldest entries (except favorites) are removed beyond this. 0 = unlimited.",
+                toggle_results_hotkey_label: "Show/hide result windows:",
+                debug_metrics_label: "Log API response timing",
+                debug_metrics_tooltip: "Logs time to first chunk, total time and throughput (chars/s) for each request. Helps tell a slow network from a slow model.",
+                use_gemini_live_label: "Use Gemini Live",
+                use_gemini_live_tooltip: "Streams audio to Gemini Live over a websocket (sub-second latency). Needs a Gemini API key; falls back to chunked uploads if Live can't connect.",
+                gemini_live_only_failed: "Couldn't connect to Gemini Live, and this model only works over Live. Check the Gemini API key or pick another model.",
+                show_live_stats_label: "Show latency stats",
+                show_live_stats_tooltip: "Shows a small line at the bottom of the result window: wait after capture, API round trip and chunks waiting in the queue (cap / api / q)",
+                capture_mode_label: "Mode:",
+                overlay_appearance_title: "Selection overlay",
+                overlay_dim_label: "Dim level:",
+                overlay_border_color_label: "Border color:",
+                overlay_border_width_label: "Width:",
+                overlay_grid_label: "Rule-of-thirds grid",
+                overlay_crosshair_label: "Crosshair guides",
+                overlay_appearance_reset: "Default",
+                live_chunk_secs_label: "Chunk length:",
+                live_chunk_secs_tooltip: "Longer chunks (4–5 s) transcribe slow speakers better, but text appears later.",
+                live_chunk_overlap_label: "Overlap:",
+                live_chunk_overlap_tooltip: "Repeats the end of the previous chunk at the start of the next so words aren't cut at the boundary; the repeated words are removed from the transcript.",
+                keep_chat_image_label: "Keep screenshot for follow-ups",
+                keep_chat_image_tooltip: "Re-send the capture (downscaled to ~1MP) with every follow-up question. Press Enter on the result window to ask one.",
+                preview_prompt_btn: "Preview prompt",
+                preview_prompt_hint: "This is the prompt that will be sent (chat mode uses a sample question):",
+                preview_prompt_sample_question: "What is in this image?",
+                unmapped_language_warning: "No language set for these tags (they will be sent literally):",
+                include_context_label: "Include the window title as context",
+                include_context_tooltip: "Adds the title of the window in front when the hotkey is pressed (e.g. a game or manga name) to the prompt, which helps with proper nouns. Costs a few more tokens per capture.",
+                language_cycle_label: "Cycle {language1} through:",
+                language_cycle_add: "+ Add",
+                language_cycle_tooltip: "Ordered list of languages. The cycle target language hotkey (in the hotkeys section, needs at least 2 languages) switches {language1} to the next one and saves it.",
+                close_label: "Close",
+                blocked_apps_title: "Blocked apps",
+                blocked_apps_tooltip: "Never capture or translate while one of these apps is in front (e.g. a password manager). Live mode pauses automatically when they get focus.",
+                blocked_apps_placeholder: "Process name, e.g. KeePassXC.exe",
+                blocked_apps_add: "Add",
+                advanced_model_params: "Advanced",
+                advanced_model_params_hint: "Applies to every preset using this model. Unchecked = API default.",
+                temperature_label: "Temperature",
+                max_output_tokens_label: "Max output tokens",
+                thinking_budget_label: "Thinking budget",
+                thinking_budget_tooltip: "Tokens the model may spend reasoning before it answers. -1 = dynamic, 0 = off. Higher = more careful but slower and more expensive.",
+                max_tokens_tooltip: "Caps the answer length. Raising it keeps full-page translations from being cut off, but costs more tokens.",
+                model_params_title: "Gemini model settings",
+                preset_indicator_label: "Show active preset",
+                capture_mode_full_monitor: "Full screen",
+                capture_monitor_missing: "This monitor isn't connected right now; the primary monitor will be captured instead.",
+                copy_markdown_label: "Copy button copies Markdown (chat results)",
+                copy_markdown_tooltip: "Chat answers are shown without Markdown formatting. When on, the copy button copies the original Markdown (code fences, lists) and right-click copies the plain text; when off, the other way round.",
+                per_app_language_label: "Remember language per app",
+                per_app_language_tooltip: "Records the target language used in the foreground app (e.g. game.exe) and uses it again the next time this preset runs in that app. Changing the language here applies to the app the preset runs in next.",
+                retranslate_to_ui_language_label: "Translate into the UI language",
+                retranslate_to_ui_language_tooltip: "Always translate into the app's UI language (Tiếng Việt, 한국어 or English) instead of the language chosen above. Switching the UI language switches the translation with it.",
+                per_app_language_forget: "Forget ({} apps)",
+                overlay_animations_label: "Overlay animations (broom cursor)",
+                overlay_animations_tooltip: "Broom cursor and the sweep effect when closing result windows. When off (or when Windows animation effects are off), the cursor stays a normal arrow and a click closes the window at once. Applies to windows opened afterwards.",
+                dedup_history_label: "Dedup history:",
+                dedup_history_tooltip: "Skip results matching any of the last N lines, so subtitles that cycle back aren't translated again.",
+                open_config_folder_btn: "Open config folder",
+                open_log_file_btn: "Open log file",
+                copy_diagnostics_btn: "Copy diagnostics",
+                copy_diagnostics_tooltip: "Copies the app version, Windows version and the end of app.log for pasting into a bug report. API keys are not included.",
+                diagnostics_copied: "Copied!",
+                log_level_label: "Log level:",
+                log_level_debug_tooltip: "Records extra detail for troubleshooting (the log grows faster).",
+                log_max_size_label: "Log size cap:",
+                log_max_size_tooltip: "When app.log passes this size it's renamed to app.log.1 (replacing the older backup) and a new file is started.",
+                dismiss_on_outside_click_label: "Close when clicking elsewhere",
+                dismiss_on_outside_click_tooltip: "A click anywhere outside the result windows closes them (the click still reaches the app underneath). Clicking the retranslation window keeps them open. Not used by live sessions.",
+                check_updates_label: "Check for updates",
+                check_updates_tooltip: "On startup (at most once a day), asks GitHub whether a newer version is out and mentions it in the window footer. Nothing is downloaded.",
+                update_available: "Version {} is available — click to view",
+                rerun_hotkey_label: "Re-run last capture:",
+                rerun_hotkey_tooltip: "Runs this preset on the region captured last (by any preset) without selecting it again",
+                cycle_language_hotkey_label: "Cycle target language:",
+                cycle_language_hotkey_tooltip: "Switches {language1} to the next language of the cycle list and shows a notice; the next capture uses it",
+                last_capture_keep_label: "Keep last capture for:",
+                last_capture_keep_tooltip: "How long the last captured image stays in memory for re-run hotkeys before it's discarded (0 = not kept)",
+                target_monitor_label: "Selection monitor:",
+                target_monitor_tooltip: "Shows the region selection on this monitor only, wherever the cursor is",
+                capture_on_release_label: "Capture at selection end",
+                capture_on_release_tooltip: "Don't freeze the screen when the hotkey is pressed. The screen keeps playing under the selection overlay and only the selected area is captured when you release the mouse (for video frames)",
+                font_family_label: "Result font:",
+                font_family_tooltip: "Windows font name for the result windows (e.g. Malgun Gothic). Leave empty to pick a font that covers the text's script (Korean, Japanese, Chinese, Thai...) automatically.",
+                font_family_auto: "Automatic",
+                target_monitor_all: "All monitors",
+                target_monitor_missing: "This monitor isn't connected right now; the monitor under the cursor will be used instead.",
+                preset_picker_hotkey_label: "Pick a preset, then a region:",
+                stop_all_hotkey_label: "Stop all recordings and live sessions:",
+                recent_copies_hotkey_label: "Recently copied results (pick one to copy again):",
+                reading_order_label: "Reading order:",
+                reading_order_tooltip: "Order of comic panels and speech bubbles in the output. Use \"Right to left\" for manga",
+                reading_order_auto: "Automatic",
+                reading_order_ltr: "Left to right (comics)",
+                reading_order_rtl: "Right to left (manga)",
+                show_model_badge_label: "Show model and time on results",
+                show_model_badge_tooltip: "Top-right corner of result windows shows the model used and how long it took (e.g. \"gemini-2.0-flash · 1.8s\"). Never copied with the text.",
+                compare_model_label: "Compare with:",
+                compare_model_tooltip: "Also send the capture to a second model and show its answer in a window beside the main one. Both go to history; auto-copy only uses the main result.",
+                compare_model_off: "Off",
+                quick_translate_label: "Quick translate button (🌐) into:",
+                quick_translate_tooltip: "The globe button on result windows translates what they show into this language; click it again for the original",
+                auto_copy_delay_label: "Copy delay",
+                auto_copy_delay_tooltip: "Wait this long before copying the result. Raise it if a clipboard manager misses the copy or grabs the wrong text.",
+                copy_on_close_label: "Copy on close",
+                copy_on_close_tooltip: "Copy when you dismiss the result window (finished, with any edits) instead of as soon as the result is done.",
+                batch_btn: "Batch translate images…",
+                batch_tooltip: "Pick PNG or WebP images to run through this preset; the results are written to the output folder. You can also drop images on this window.",
+                batch_progress: "{done}/{total} images ({failed} failed)",
+                batch_cancel_btn: "Stop",
+                batch_open_folder_btn: "Open folder",
+                batch_concurrency_label: "Batch translate: images at once",
+                batch_concurrency_tooltip: "More is faster but hits the API's rate limits sooner (rate-limited requests are retried).",
+                batch_per_image_label: ".txt per image",
+                batch_combined_label: "Combined file",
+                batch_output_label: "Output folder:",
+                batch_output_hint: "Empty = a \"translated\" folder next to the images",
+                show_crash_dialog_label: "Show a message when the app crashes",
+                show_crash_dialog_tooltip: "Off: crashes are only written to app.log.",
+                output_mode_label: "Output:",
+                output_mode_tooltip: "Type out: types the result into the window focused at the hotkey, for apps and games that block paste. Press Escape or move the mouse to stop.",
+                output_mode_clipboard: "Clipboard",
+                output_mode_type_out: "Type out",
+                type_out_delay_label: "Per character:",
+                type_out_delay_tooltip: "Wait between characters. Raise it if the app drops characters.",
+                output_file_label: "Append to file:",
+                output_file_tooltip: "Each result (with its retranslation) is appended to this file with a timestamp, e.g. a running notes.md.",
+                output_file_none: "Off",
+                output_file_browse: "Choose file…",
+                image_upload_format_label: "Upload image format:",
+                image_upload_format_tooltip: "Lossless WebP is usually smaller than PNG for screenshots, so uploads are faster. Groq doesn't take WebP and still gets PNG.",
+                upscale_small_crops_label: "Upscale small crops (tiny text)",
+                upscale_small_crops_tooltip: "Short crops (a line or two, like subtitles) and small ones are sent at twice the size (Lanczos filter), so the model reads tiny text more reliably. Small crops are always sent as PNG.",
+                webhook_header: "Webhook (HTTP POST)",
+                webhook_url_label: "URL:",
+                webhook_template_label: "JSON body:",
+                webhook_template_tooltip: "Sent after each successful run. {result}, {retranslation}, {preset} and {timestamp} are replaced by their values (escaped for a JSON string).",
+                webhook_headers_label: "Headers (e.g. auth tokens):",
+                webhook_add_header: "+ Header",
+                webhook_test_btn: "Send test payload",
+                strip_preamble_label: "Strip preamble (\"Here is the translation:\")",
+                strip_preamble_tooltip: "Remove a first line like \"Here is the translation:\" that some models put before the result. Only familiar patterns are removed; everything else is kept as is.",
+                reflow_paragraphs_label: "Reflow paragraphs",
+                reflow_paragraphs_tooltip: "Join the lines the model broke where the image does into flowing paragraphs (blank lines between paragraphs and list items are kept) and collapse repeated spaces. Chinese, Japanese and Thai lines are joined without a space. Shift+right-click the result window to copy it with the original line breaks.",
+                reflow_dehyphenate_label: "Remove line-end hyphens",
+                reflow_dehyphenate_tooltip: "Rejoin words split with a hyphen at the end of a line (\"transla-\" + \"tion\" → \"translation\").",
+                study_layout_label: "Study layout (reading + translation)",
+                study_layout_tooltip: "Show the original text, its reading (furigana, pinyin, romanization) and the translation as three sections. Asks the model for JSON, so the answer appears once complete instead of streaming; one that can't be parsed is shown as it came. History keeps the sections apart for the Anki export.",
+                copy_format_label: "Copy format:",
+                copy_format_tooltip: "How copied text is wrapped (copy button, right click, auto copy), e.g. a code block for pasting into Discord. Shift+right-click a result window to pick another format for one copy.",
+                copy_format_plain: "Plain text",
+                copy_format_code_block: "Markdown code block",
+                copy_format_quote: "Quoted (>)",
+                copy_format_source_header: "With source header",
+                result_copy_hint: "Ctrl+Enter: copy and close",
+                result_retry_label: "Retry",
+                copy_unreflowed_label: "Copy with original line breaks",
+                live_follow_window_label: "Follow a window",
+                live_follow_window_tooltip: "The capture region sticks to the window under it (e.g. a video player) and moves and resizes with it. Live stops when that window is closed.",
+                live_follow_window_match_label: "Window title / class:",
+                live_follow_window_hint: "Empty = the window under the region",
+                completion_sound_label: "Sound when done",
+                completion_toast_label: "Notice when done",
+                completion_tooltip: "With the result windows hidden: signal when the preset is done (\"Copied 213 characters\"), and differently when it failed. At most one every 3 seconds, so live modes don't repeat it.",
+                min_region_size_label: "Minimum selection:",
+                min_region_size_tooltip: "Selections narrower or shorter than this are skipped with a notice, so accidental clicks don't send a request. Lower it to select very small text.",
+                hotkey_debounce_label: "Hotkey debounce:",
+                hotkey_debounce_tooltip: "A second press of the same hotkey within this time is ignored (key repeat, keyboards or macro tools that send the key twice), so it can't open two selections or send two requests. 0 = off.",
+                preset_indicator_tooltip: "A small always-on-top label in a screen corner showing the active preset (the one last triggered by a hotkey, or selected here). Clicks pass through it. Can also be toggled from the tray menu.",
+                preset_indicator_corner_label: "Position:",
+                corner_top_left: "Top left",
+                corner_top_right: "Top right",
+                corner_bottom_left: "Bottom left",
+                corner_bottom_right: "Bottom right",
+                capture_mode_region: "Screen region",
+                capture_mode_replace_selection: "Translate and replace selection",
+                replace_selection_hint: "Select text in any text box and press the hotkey: it is translated and pasted over the selection.",
+                replace_selection_confirm: "This mode sends Ctrl+C and Ctrl+V key presses to the focused app and briefly uses the clipboard (its previous contents are restored afterwards). The selected text is sent to the translation model. Enable it?",
+                replace_selection_confirm_yes: "Enable",
                 add_hotkey_button: "+ Add Key",
                 press_keys: "Press combination...",
                 cancel_label: "Cancel",
@@ -285,13 +1271,59 @@ impl LocaleText {
                 audio_source_label: "Audio Source:",
                 audio_src_mic: "Microphone",
                 audio_src_device: "Device Audio",
+                audio_src_both: "Both (mic + system)",
+                loopback_device_label: "Output device:",
+                loopback_device_tooltip: "The output recorded as system audio (e.g. a virtual audio cable one app is routed to)",
+                loopback_device_default: "Default output",
+                loopback_device_refresh: "Refresh devices",
+                loopback_device_missing: "⚠ This device isn't connected; recording will fail",
                 hide_recording_ui_label: "Hide Recording UI",
+                optimize_audio_upload_label: "Optimize upload size",
+                optimize_audio_upload_tooltip: "Convert audio to 16 kHz mono before sending (up to ~6x smaller, same recognition quality)",
+                max_recording_label: "Max recording:",
+                max_recording_tooltip: "Stops and processes automatically at the limit (0 = unlimited). In Live mode this caps the whole session.",
+                recording_processing: "Processing...",
+                recording_paused: "Paused",
+                recording_device: "Recording system audio...",
+                recording_both: "Recording mic + system audio...",
+                recording_mic: "Recording mic...",
+                recording_hint: "Press the hotkey again to process the audio",
+                recording_countdown: "Stops in {}",
+                whisper_language_label: "Spoken language:",
+                whisper_language_auto: "Auto-detect",
+                whisper_language_tooltip: "Language hint for Whisper. Pin it if you code-switch and auto-detect picks the wrong one.",
+                whisper_translate_label: "Translate to English",
+                whisper_translate_tooltip: "Use Whisper's translation endpoint: output is always English (uses the large-v3 model).",
+                open_audio_file_btn: "Open audio file…",
+                open_audio_file_tooltip: "Transcribe an existing file (mp3, m4a, wav…) with this preset. You can also drop files on the settings window.",
+                audio_drop_no_preset: "No audio preset to transcribe this file with",
+                audio_output_not_found: "Output device \"{}\" not found. Is it connected?",
+                audio_no_loopback: "Output device \"{}\" can't be recorded (no loopback capture)",
+                audio_no_input: "No microphone found",
+                audio_capture_failed: "Can't start audio recording: {}",
+                onboarding_title: "Welcome! Quick setup",
+                onboarding_intro: "The app needs a (free) API key to send screenshots and audio to an AI model. Pick a provider:",
+                onboarding_groq_desc: "Groq — very fast with a generous free tier. Used by most presets and Whisper (recording).",
+                onboarding_gemini_desc: "Google Gemini — more accurate, understands audio natively, smaller free quota.",
+                onboarding_paste_key: "Paste your API key here:",
+                onboarding_test_btn: "Test",
+                onboarding_testing: "Testing...",
+                onboarding_test_ok: "✅ The key works!",
+                onboarding_test_invalid: "❌ Invalid key, please check it.",
+                onboarding_hotkey_label: "Default hotkey: press {} to select a screen region and translate it. You can change it in each preset.",
+                onboarding_finish: "Get started",
+                onboarding_skip: "Skip",
                 hotkeys_section: "Hotkeys",
                 usage_statistics_title: "Usage Statistics",
                 usage_statistics_tooltip: "Use a model at least once for accurate data",
                 usage_model_column: "Model",
                 usage_remaining_column: "Remaining / Total",
                 usage_check_link: "Check Usage ↗",
+                translation_cache_hits: "Translation cache: {} hits ({} entries)",
+                translation_cache_clear: "Clear cache",
+                translation_cache_size_label: "Max entries:",
+                translation_cache_tooltip: "Identical requests (same model, prompt and input) are answered from the cache instead of calling the API again. 0 = off.",
+                translation_cache_persist_label: "Keep on disk",
                 empty_prompt_warning: "Warning: Empty prompt may yield unpredictable results!",
                 footer_admin_text: "Run with admin to translate games",
                 footer_version: "Version v2.1",
@@ -301,6 +1333,19 @@ impl LocaleText {
                 history_favorites: "Favorites",
                 history_clear_all: "Clear All",
                 history_empty: "No history yet",
+                history_deleted_toast: "Removed from history",
+                history_undo: "Undo",
+                history_find_hint: "Find in text...",
+                history_find_case: "Aa (match case)",
+                history_tags_label: "Tags:",
+                history_tags_hint: "Add tag...",
+                history_tag_filter_all: "All tags",
+                history_export_srt: "🎬 Export SRT",
+                history_export_anki: "🃏 Export Anki deck",
+                history_export_anki_tooltip: "The study layout results in view, one note each (Original, Reading, Translation, tags). Import it in Anki with File > Import.",
+                history_detected_language_tooltip: "Language of the text in the image, as detected by the model",
+                history_clear_confirm: "Clear all history? You can still undo right after.",
+                history_clear_confirm_yes: "Clear all",
                 // Live Captions
                 live_captions_title: "Live Captions (Speech Translation)",
                 live_captions_tooltip: "Real-time translation via Windows Live Captions (Win11 22H2+)",
@@ -311,6 +1356,9 @@ impl LocaleText {
                 live_captions_sentences: "Lines to display:",
                 live_captions_show_original: "Show Original",
                 live_captions_auto_hide: "Auto-hide Live Captions",
+                live_captions_hotkey: "Toggle hotkey:",
+                live_captions_audio_source: "Audio source:",
+                live_captions_audio_source_tooltip: "Only used by Gemini Live. Other models read the text from Windows Live Captions.",
                 // Quick Actions & AI Chat
                 quick_actions_title: "Quick Actions",
                 quick_actions_enabled: "Enable Quick Actions menu",
@@ -6,3 +6,6 @@ pub mod icons;
 
 pub use app::SettingsApp;
 pub use app::configure_fonts;
+pub use app::show_api_keys;
+pub use app::copy_format_name;
+pub use locale::LocaleText;
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct HistoryEntry {
@@ -12,14 +13,57 @@ pub struct HistoryEntry {
     pub retrans_text: Option<String>,
     pub timestamp: u64,
     pub is_favorite: bool,
+    #[serde(default)]
+    pub tags: Vec<String>, // User labels, e.g. "work", "manga"
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub segments: Vec<SubtitleSegment>, // Timed transcript (audio only), used for SRT export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>, // Shared by the entries of one capture run through two models (compare mode)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub study: Option<StudyFields>, // Study layout presets: the result split into its sections, for the Anki export
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub detected_language: Option<String>, // Language of the text in the image, when the model reported it
+}
+
+/// A study layout result: the text as written, its reading and its translation
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct StudyFields {
+    #[serde(default)]
+    pub original: String,
+    #[serde(default)]
+    pub reading: String, // Furigana, pinyin or romanization
+    #[serde(default)]
+    pub translation: String,
+}
+
+/// A timed piece of an audio transcript, optionally with its own translation
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SubtitleSegment {
+    pub start_ms: u64,
+    pub end_ms: u64,
+    pub text: String,
+    #[serde(default)]
+    pub translation: Option<String>,
+}
+
+/// A deleted entry kept around so the deletion can be undone
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TrashedEntry {
+    pub entry: HistoryEntry,
+    pub deleted_at: u64,
 }
 
 lazy_static::lazy_static! {
     static ref HISTORY_CACHE: Mutex<Vec<HistoryEntry>> = Mutex::new(Vec::new());
     static ref HISTORY_LOADED: Mutex<bool> = Mutex::new(false);
+    static ref TRASH_CACHE: Mutex<Option<Vec<TrashedEntry>>> = Mutex::new(None);
 }
 
-const MAX_HISTORY_ENTRIES: usize = 100;
+pub const DEFAULT_MAX_HISTORY_ENTRIES: usize = 500;
+/// Current cap from `Config.max_history_entries` (0 = unlimited)
+static MAX_HISTORY_ENTRIES: AtomicUsize = AtomicUsize::new(DEFAULT_MAX_HISTORY_ENTRIES);
+/// Trashed entries older than this are purged for good
+pub const TRASH_RETENTION_DAYS: u64 = 7;
 
 pub fn get_history_path() -> PathBuf {
     let config_dir = dirs::config_dir()
@@ -63,13 +107,41 @@ pub fn add_history_entry(entry: HistoryEntry) {
     entries.insert(0, entry);
     
     // Limit history size
-    if entries.len() > MAX_HISTORY_ENTRIES {
-        entries.truncate(MAX_HISTORY_ENTRIES);
+    let pruned = prune_entries(&mut entries, MAX_HISTORY_ENTRIES.load(Ordering::Relaxed));
+    if pruned > 0 {
+        log::info!("History: pruned {} old entries", pruned);
     }
     
     save_history(&entries);
 }
 
+/// Set the history cap (0 = unlimited). Takes effect on the next added entry.
+pub fn set_max_entries(max: usize) {
+    MAX_HISTORY_ENTRIES.store(max, Ordering::Relaxed);
+}
+
+/// Drop the oldest non-favorite entries until at most `max` remain (entries are newest-first).
+/// Favorites are never pruned, so the list can stay above the cap if they alone exceed it.
+/// Returns how many entries were removed.
+pub fn prune_entries(entries: &mut Vec<HistoryEntry>, max: usize) -> usize {
+    if max == 0 || entries.len() <= max { return 0; }
+
+    let mut excess = entries.len() - max;
+    let before = entries.len();
+    // Walk from the oldest end, keeping favorites
+    let mut keep: Vec<bool> = vec![true; entries.len()];
+    for (i, entry) in entries.iter().enumerate().rev() {
+        if excess == 0 { break; }
+        if !entry.is_favorite {
+            keep[i] = false;
+            excess -= 1;
+        }
+    }
+    let mut flags = keep.into_iter();
+    entries.retain(|_| flags.next().unwrap_or(true));
+    before - entries.len()
+}
+
 pub fn toggle_favorite(id: &str) {
     let mut entries = load_history();
     if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
@@ -78,14 +150,120 @@ pub fn toggle_favorite(id: &str) {
     save_history(&entries);
 }
 
-pub fn delete_entry(id: &str) {
+/// Add a tag to an entry (trimmed; ignored if empty or already present, case-insensitively)
+pub fn add_tag(id: &str, tag: &str) {
+    let tag = tag.trim();
+    if tag.is_empty() { return; }
     let mut entries = load_history();
-    entries.retain(|e| e.id != id);
+    if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+        if !entry.tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            entry.tags.push(tag.to_string());
+        }
+    }
+    save_history(&entries);
+}
+
+pub fn remove_tag(id: &str, tag: &str) {
+    let mut entries = load_history();
+    if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+        entry.tags.retain(|t| t != tag);
+    }
     save_history(&entries);
 }
 
-pub fn clear_all_history() {
+/// Every tag in use, sorted and de-duplicated (for autocomplete and the filter row)
+pub fn all_tags(entries: &[HistoryEntry]) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    for tag in entries.iter().flat_map(|e| e.tags.iter()) {
+        if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+            tags.push(tag.clone());
+        }
+    }
+    tags.sort_by_key(|t| t.to_lowercase());
+    tags
+}
+
+/// Soft-delete: the entry is moved to the trash and can be brought back with `restore_entry`
+pub fn delete_entry(id: &str) {
+    let mut entries = load_history();
+    let (removed, kept): (Vec<_>, Vec<_>) = entries.drain(..).partition(|e| e.id == id);
+    move_to_trash(removed);
+    save_history(&kept);
+}
+
+/// Moves every entry to the trash. Returns the ids so the caller can offer an undo.
+pub fn clear_all_history() -> Vec<String> {
+    let entries = load_history();
+    let ids = entries.iter().map(|e| e.id.clone()).collect();
+    move_to_trash(entries);
     save_history(&[]);
+    ids
+}
+
+/// Bring a trashed entry back into the history list (keeps newest-first order)
+pub fn restore_entry(id: &str) -> bool {
+    let mut trash = load_trash();
+    let Some(pos) = trash.iter().position(|t| t.entry.id == id) else { return false; };
+    let restored = trash.remove(pos).entry;
+    save_trash(&trash);
+
+    let mut entries = load_history();
+    if !entries.iter().any(|e| e.id == restored.id) {
+        entries.push(restored);
+        entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    }
+    save_history(&entries);
+    true
+}
+
+/// Permanently drop trashed entries deleted more than `max_age_days` ago. Runs at startup;
+/// a long-running session also purges whenever something is trashed (`move_to_trash`).
+pub fn purge_trash(max_age_days: u64) {
+    let mut trash = load_trash();
+    if expire_trash(&mut trash, get_current_timestamp(), max_age_days) > 0 {
+        save_trash(&trash);
+    }
+}
+
+/// Drops entries deleted more than `max_age_days` before `now`; returns how many
+fn expire_trash(trash: &mut Vec<TrashedEntry>, now: u64, max_age_days: u64) -> usize {
+    let max_age = max_age_days * 86400;
+    let before = trash.len();
+    trash.retain(|t| now.saturating_sub(t.deleted_at) <= max_age);
+    before - trash.len()
+}
+
+// --- TRASH STORAGE ---
+
+fn get_trash_path() -> PathBuf {
+    get_history_path().with_file_name("history_trash.json")
+}
+
+fn load_trash() -> Vec<TrashedEntry> {
+    let mut cache = TRASH_CACHE.lock().unwrap();
+    if let Some(trash) = cache.as_ref() {
+        return trash.clone();
+    }
+    let data = std::fs::read_to_string(get_trash_path()).unwrap_or_default();
+    let trash: Vec<TrashedEntry> = serde_json::from_str(&data).unwrap_or_default();
+    *cache = Some(trash.clone());
+    trash
+}
+
+fn save_trash(trash: &[TrashedEntry]) {
+    if let Ok(data) = serde_json::to_string_pretty(trash) {
+        let _ = std::fs::write(get_trash_path(), data);
+    }
+    *TRASH_CACHE.lock().unwrap() = Some(trash.to_vec());
+}
+
+fn move_to_trash(entries: Vec<HistoryEntry>) {
+    if entries.is_empty() { return; }
+    let now = get_current_timestamp();
+    let mut trash = load_trash();
+    expire_trash(&mut trash, now, TRASH_RETENTION_DAYS);
+    trash.extend(entries.into_iter().map(|entry| TrashedEntry { entry, deleted_at: now }));
+    save_trash(&trash);
 }
 
 pub fn generate_entry_id() -> String {
@@ -121,11 +299,13 @@ pub fn export_to_txt(entry: &HistoryEntry) -> Result<PathBuf, String> {
     let filename = format!("{}_{}.txt", entry.preset_name.replace(" ", "_"), entry.id);
     let path = exports_dir.join(&filename);
     
+    let tags_line = if entry.tags.is_empty() { String::new() } else { format!("Tags: {}\n", entry.tags.join(", ")) };
     let content = format!(
-        "Preset: {}\nType: {}\nTime: {}\n\n---\n\n{}",
+        "Preset: {}\nType: {}\nTime: {}\n{}\n---\n\n{}",
         entry.preset_name,
         entry.preset_type,
         format_timestamp(entry.timestamp),
+        tags_line,
         entry.result_text
     );
     
@@ -139,12 +319,18 @@ pub fn export_to_markdown(entry: &HistoryEntry) -> Result<PathBuf, String> {
     let path = exports_dir.join(&filename);
     
     let star = if entry.is_favorite { " ⭐" } else { "" };
+    let tags_line = if entry.tags.is_empty() {
+        String::new()
+    } else {
+        format!("  \n**Tags:** {}", entry.tags.iter().map(|t| format!("`{}`", t)).collect::<Vec<_>>().join(" "))
+    };
     let content = format!(
-        "# {}{}\n\n**Type:** {}  \n**Time:** {}\n\n---\n\n{}\n",
+        "# {}{}\n\n**Type:** {}  \n**Time:** {}{}\n\n---\n\n{}\n",
         entry.preset_name,
         star,
         entry.preset_type,
         format_timestamp(entry.timestamp),
+        tags_line,
         entry.result_text
     );
     
@@ -152,13 +338,158 @@ pub fn export_to_markdown(entry: &HistoryEntry) -> Result<PathBuf, String> {
     Ok(path)
 }
 
-pub fn format_for_clipboard(entry: &HistoryEntry) -> String {
-    format!(
-        "[{}] {}\n\n{}",
-        entry.preset_name,
-        format_timestamp(entry.timestamp),
-        entry.result_text
-    )
+/// A field of an Anki text import: quoted when it holds a tab, line break or quote
+fn anki_field(text: &str) -> String {
+    if text.contains(['\t', '\n', '\r', '"']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Anki "Notes in Plain Text" import of the study entries: one note per entry with
+/// Original, Reading and Translation fields, the entry's tags as the note's tags
+pub fn study_deck(entries: &[&HistoryEntry]) -> String {
+    let mut out = String::from("#separator:tab\n#html:false\n#columns:Original\tReading\tTranslation\tTags\n#tags column:4\n");
+    for entry in entries {
+        let Some(study) = &entry.study else { continue };
+        // Anki tags are space separated
+        let tags: Vec<String> = entry.tags.iter().map(|t| t.replace(' ', "_")).collect();
+        out.push_str(&format!("{}\t{}\t{}\t{}\n", anki_field(&study.original), anki_field(&study.reading), anki_field(&study.translation), tags.join(" ")));
+    }
+    out
+}
+
+/// Write the study entries among `entries` as an Anki import file
+pub fn export_study_deck(entries: &[&HistoryEntry]) -> Result<PathBuf, String> {
+    if !entries.iter().any(|e| e.study.is_some()) { return Err("No study entries".to_string()); }
+    let path = get_exports_dir().join(format!("study_cards_{}.txt", get_current_timestamp()));
+    std::fs::write(&path, study_deck(entries)).map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+// --- Live Session Transcripts ---
+
+/// One transcribed chunk of a live audio session. Offsets are relative to the session start.
+#[derive(Clone, Debug)]
+pub struct TranscriptSegment {
+    pub at_ms: u64, // When the chunk finished transcribing
+    pub text: String,
+    pub translation: Option<String>,
+}
+
+/// Full transcript (and translation, if any) of a live session, unlike the trimmed on-screen buffers
+pub fn session_full_text(segments: &[TranscriptSegment]) -> (String, Option<String>) {
+    let transcript = segments.iter().map(|s| s.text.trim()).collect::<Vec<_>>().join(" ");
+    let translations: Vec<&str> = segments.iter().filter_map(|s| s.translation.as_deref()).map(str::trim).collect();
+    let translation = if translations.is_empty() { None } else { Some(translations.join(" ")) };
+    (transcript, translation)
+}
+
+/// Timed subtitles for a live session. Each cue runs until the next chunk arrives (last one for a few seconds).
+pub fn session_to_subtitles(segments: &[TranscriptSegment]) -> Vec<SubtitleSegment> {
+    const LAST_CUE_MS: u64 = 4000;
+    segments.iter().enumerate().map(|(i, seg)| SubtitleSegment {
+        start_ms: seg.at_ms,
+        end_ms: segments.get(i + 1).map_or(seg.at_ms + LAST_CUE_MS, |next| next.at_ms.max(seg.at_ms + 1)),
+        text: seg.text.trim().to_string(),
+        translation: seg.translation.as_ref().map(|t| t.trim().to_string()),
+    }).collect()
+}
+
+/// Render a live session as SRT, original and translation in the same cue
+pub fn session_to_srt(segments: &[TranscriptSegment]) -> String {
+    render_srt(session_to_subtitles(segments).into_iter().map(|seg| {
+        let text = match seg.translation {
+            Some(tr) => format!("{}\n{}", seg.text, tr),
+            None => seg.text,
+        };
+        (seg.start_ms, seg.end_ms, text)
+    }))
+}
+
+/// Write the session so far as .txt and .srt into the exports folder. Returns the .txt path.
+pub fn export_session(preset_name: &str, segments: &[TranscriptSegment]) -> Result<PathBuf, String> {
+    let exports_dir = get_exports_dir();
+    let base = format!("{}_session_{}", preset_name.replace(" ", "_"), generate_entry_id());
+
+    let (transcript, translation) = session_full_text(segments);
+    let mut content = format!(
+        "Preset: {}\nType: audio (live)\nTime: {}\n\n---\n\n{}",
+        preset_name,
+        format_timestamp(get_current_timestamp()),
+        transcript
+    );
+    if let Some(tr) = translation {
+        content.push_str(&format!("\n\n---\n\n{}", tr));
+    }
+
+    let txt_path = exports_dir.join(format!("{}.txt", base));
+    std::fs::write(&txt_path, content).map_err(|e| e.to_string())?;
+    std::fs::write(exports_dir.join(format!("{}.srt", base)), session_to_srt(segments)).map_err(|e| e.to_string())?;
+    Ok(txt_path)
+}
+
+// --- SRT ---
+
+/// Numbered, timecoded SRT from `(start_ms, end_ms, text)` cues
+pub fn render_srt(cues: impl IntoIterator<Item = (u64, u64, String)>) -> String {
+    let mut out = String::new();
+    for (i, (start, end, text)) in cues.into_iter().enumerate() {
+        out.push_str(&format!("{}\n{} --> {}\n{}\n\n", i + 1, format_srt_time(start), format_srt_time(end), text.trim()));
+    }
+    out
+}
+
+/// SRT of the transcript, or of the per-segment translations (untranslated segments keep the original)
+pub fn segments_to_srt(segments: &[SubtitleSegment], translated: bool) -> String {
+    render_srt(segments.iter().map(|seg| {
+        let text = if translated { seg.translation.as_ref().unwrap_or(&seg.text) } else { &seg.text };
+        (seg.start_ms, seg.end_ms, text.clone())
+    }))
+}
+
+/// Write `<base>.srt` and, if any segment was translated, `<base>.translated.srt`. Returns the first path.
+pub fn export_segments_srt(base_name: &str, segments: &[SubtitleSegment]) -> Result<PathBuf, String> {
+    if segments.is_empty() { return Err("No timed segments".to_string()); }
+    let exports_dir = get_exports_dir();
+    let base = base_name.replace(" ", "_");
+
+    let path = exports_dir.join(format!("{}.srt", base));
+    std::fs::write(&path, segments_to_srt(segments, false)).map_err(|e| e.to_string())?;
+    if segments.iter().any(|s| s.translation.is_some()) {
+        std::fs::write(exports_dir.join(format!("{}.translated.srt", base)), segments_to_srt(segments, true)).map_err(|e| e.to_string())?;
+    }
+    Ok(path)
+}
+
+pub fn export_to_srt(entry: &HistoryEntry) -> Result<PathBuf, String> {
+    export_segments_srt(&format!("{}_{}", entry.preset_name, entry.id), &entry.segments)
+}
+
+/// Replace the timed segments of an entry (e.g. once their translations arrive)
+pub fn set_segments(id: &str, segments: Vec<SubtitleSegment>) {
+    let mut entries = load_history();
+    if let Some(entry) = entries.iter_mut().find(|e| e.id == id) {
+        entry.segments = segments;
+    }
+    save_history(&entries);
+}
+
+fn format_srt_time(ms: u64) -> String {
+    format!("{:02}:{:02}:{:02},{:03}", ms / 3_600_000, (ms / 60_000) % 60, (ms / 1000) % 60, ms % 1000)
+}
+
+/// The entry's result in one of the result windows' copy formats; the source header
+/// also carries the tags
+pub fn format_for_clipboard(entry: &HistoryEntry, format: crate::config::CopyFormat) -> String {
+    let tags = if entry.tags.is_empty() {
+        String::new()
+    } else {
+        format!(" {}", entry.tags.iter().map(|t| format!("#{}", t)).collect::<Vec<_>>().join(" "))
+    };
+    let source = format!("[{}] {}{}", entry.preset_name, format_timestamp(entry.timestamp), tags);
+    crate::overlay::format_copy(&entry.result_text, format, &source)
 }
 
 fn format_timestamp(timestamp: u64) -> String {
@@ -198,3 +529,89 @@ fn format_timestamp(timestamp: u64) -> String {
     
     format!("{:02}/{:02}/{} {:02}:{:02}", day, month, year, hour, minute)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(n: u64, is_favorite: bool) -> HistoryEntry {
+        HistoryEntry {
+            id: format!("e{}", n),
+            preset_name: "Translate".to_string(),
+            preset_type: "image".to_string(),
+            input_summary: String::new(),
+            result_text: format!("result {}", n),
+            retrans_text: None,
+            timestamp: n,
+            is_favorite,
+            tags: Vec::new(),
+            segments: Vec::new(),
+            group_id: None,
+            study: None,
+            detected_language: None,
+        }
+    }
+
+    #[test]
+    fn test_prune_keeps_favorites() {
+        // Newest first: e10 .. e1, with the two oldest marked favorite
+        let mut entries: Vec<HistoryEntry> = (1..=10).rev().map(|n| entry(n, n <= 2)).collect();
+
+        let removed = prune_entries(&mut entries, 5);
+        assert_eq!(removed, 5);
+        let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        assert_eq!(ids, ["e10", "e9", "e8", "e2", "e1"]);
+
+        // Favorites alone exceeding the cap are kept
+        let removed = prune_entries(&mut entries, 1);
+        assert_eq!(removed, 3);
+        assert!(entries.iter().all(|e| e.is_favorite));
+
+        // 0 = unlimited
+        let mut many: Vec<HistoryEntry> = (1..=20).map(|n| entry(n, false)).collect();
+        assert_eq!(prune_entries(&mut many, 0), 0);
+        assert_eq!(many.len(), 20);
+    }
+
+    #[test]
+    fn test_expire_trash() {
+        let day = 86400;
+        let now = 100 * day;
+        let mut trash: Vec<TrashedEntry> = [now, now - 7 * day, now - 7 * day - 1, 0].iter().enumerate()
+            .map(|(n, &deleted_at)| TrashedEntry { entry: entry(n as u64, false), deleted_at })
+            .collect();
+        assert_eq!(expire_trash(&mut trash, now, 7), 2);
+        let ids: Vec<&str> = trash.iter().map(|t| t.entry.id.as_str()).collect();
+        assert_eq!(ids, ["e0", "e1"]);
+        assert_eq!(expire_trash(&mut trash, now, 7), 0);
+    }
+
+    #[test]
+    fn test_group_id_serde() {
+        // Entries without a group don't write the field, and older files without it still load
+        let json = serde_json::to_string(&entry(1, false)).unwrap();
+        assert!(!json.contains("group_id"));
+        assert_eq!(serde_json::from_str::<HistoryEntry>(&json).unwrap().group_id, None);
+
+        let grouped = HistoryEntry { group_id: Some("g1".to_string()), ..entry(2, false) };
+        let json = serde_json::to_string(&grouped).unwrap();
+        assert_eq!(serde_json::from_str::<HistoryEntry>(&json).unwrap().group_id.as_deref(), Some("g1"));
+    }
+
+    #[test]
+    fn test_study_deck() {
+        let card = |original: &str, reading: &str, translation: &str| Some(StudyFields {
+            original: original.to_string(),
+            reading: reading.to_string(),
+            translation: translation.to_string(),
+        });
+        let tagged = HistoryEntry { study: card("猫", "ねこ", "cat"), tags: vec!["manga".to_string(), "vol 2".to_string()], ..entry(1, false) };
+        let multiline = HistoryEntry { study: card("一行目\n二行目", "", "say \"hi\""), ..entry(2, false) };
+        let plain = entry(3, false);
+        let deck = study_deck(&[&tagged, &plain, &multiline]);
+        let notes: Vec<&str> = deck.lines().filter(|l| !l.starts_with('#')).collect();
+        // Entries without study fields are skipped; line breaks and quotes are quoted
+        assert_eq!(notes, ["猫\tねこ\tcat\tmanga vol_2", "\"一行目", "二行目\"\t\t\"say \"\"hi\"\"\"\t"]);
+        assert!(deck.starts_with("#separator:tab\n"));
+    }
+}
@@ -0,0 +1,305 @@
+//! Image Batch Module
+//!
+//! Runs an image preset over image files from disk (folders of screenshots, manga pages)
+//! instead of a screen capture: files dropped on the settings window or picked with the
+//! Batch button are sent a few at a time and each result is written to the output folder
+//! as `<image>.txt` (numbered when two images share a name), plus one combined file in
+//! input order. Progress is kept here for the settings window to show.
+
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+use crate::api::{translate_image_streaming, Sampling};
+use crate::api_error::with_rate_limit_retry;
+use crate::config::{BatchConfig, Preset};
+
+/// Formats the image crate is built with
+pub const IMAGE_EXTENSIONS: &[&str] = &["png", "webp"];
+
+/// Upper bound of `BatchConfig.concurrency`
+pub const MAX_CONCURRENCY: usize = 8;
+
+/// Attempts after a rate-limit error
+const RATE_LIMIT_RETRIES: u32 = 3;
+
+/// Where the running (or last) batch is
+#[derive(Clone, Default)]
+pub struct BatchProgress {
+    pub total: usize,
+    pub done: usize, // Including failures
+    pub failed: usize,
+    pub running: bool,
+    pub output_dir: Option<PathBuf>,
+}
+
+lazy_static::lazy_static! {
+    static ref PROGRESS: Mutex<BatchProgress> = Mutex::new(BatchProgress::default());
+}
+
+static CANCEL: AtomicBool = AtomicBool::new(false);
+
+pub fn progress() -> BatchProgress {
+    PROGRESS.lock().unwrap().clone()
+}
+
+/// Stop after the images already being sent
+pub fn cancel() {
+    CANCEL.store(true, Ordering::SeqCst);
+}
+
+pub fn is_image_file(path: &Path) -> bool {
+    path.extension()
+        .map(|e| e.to_string_lossy().to_lowercase())
+        .map_or(false, |e| IMAGE_EXTENSIONS.contains(&e.as_str()))
+}
+
+/// What every worker of a batch sends
+struct BatchJob {
+    keys: (String, String, String),
+    prompt: String,
+    model: String,
+    provider: String,
+    use_json_format: bool,
+    sampling: Sampling,
+    study: bool, // Write a study layout answer as its sections
+}
+
+/// Translate `files` with `preset` in the background. Ignored while another batch runs.
+pub fn translate_files(preset: Preset, files: Vec<PathBuf>) {
+    let files: Vec<PathBuf> = files.into_iter().filter(|p| is_image_file(p)).collect();
+    if files.is_empty() {
+        return;
+    }
+    {
+        let mut progress = PROGRESS.lock().unwrap();
+        if progress.running {
+            log::warn!("Batch: already running, {} file(s) ignored", files.len());
+            return;
+        }
+        *progress = BatchProgress { total: files.len(), running: true, ..Default::default() };
+    }
+    CANCEL.store(false, Ordering::SeqCst);
+
+    let (keys, batch) = {
+        let app = crate::APP.lock().unwrap();
+        let c = &app.config;
+        ((c.api_key.clone(), c.gemini_api_key.clone(), c.openrouter_api_key.clone()), c.batch.clone())
+    };
+
+    std::thread::spawn(move || {
+        let output_dir = output_dir(&batch.output_dir, &files[0]);
+        PROGRESS.lock().unwrap().output_dir = Some(output_dir.clone());
+        if let Err(e) = run(&preset, files, keys, &batch, &output_dir) {
+            log::error!("Batch failed: {}", e);
+        }
+        PROGRESS.lock().unwrap().running = false;
+    });
+}
+
+/// `configured` if set, else a "translated" folder next to the images
+fn output_dir(configured: &str, first_file: &Path) -> PathBuf {
+    if !configured.trim().is_empty() {
+        return PathBuf::from(configured.trim());
+    }
+    first_file.parent().unwrap_or(Path::new(".")).join("translated")
+}
+
+fn run(preset: &Preset, files: Vec<PathBuf>, keys: (String, String, String), batch: &BatchConfig, output_dir: &Path) -> anyhow::Result<()> {
+    let model = crate::model_config::get_model_by_id(&preset.model)
+        .ok_or_else(|| anyhow::anyhow!("Unknown model {}", preset.model))?;
+    std::fs::create_dir_all(output_dir)
+        .map_err(|e| anyhow::anyhow!("Failed to create {}: {}", output_dir.display(), e))?;
+
+    let job = BatchJob {
+        keys,
+        prompt: crate::overlay::process::vision_prompt(preset),
+        model: model.full_name,
+        provider: model.provider,
+        use_json_format: preset.id == "preset_translate" || crate::overlay::study::enabled_for(preset),
+        sampling: Sampling::from_preset(preset),
+        study: crate::overlay::study::enabled_for(preset),
+    };
+    let concurrency = batch.concurrency.clamp(1, MAX_CONCURRENCY).min(files.len());
+    log::info!("Batch: {} image(s) with {} ({} at a time) -> {}", files.len(), job.model, concurrency, output_dir.display());
+
+    let txt_names = output_names(&files);
+    let queue: Mutex<VecDeque<(usize, PathBuf)>> = Mutex::new(files.iter().cloned().enumerate().collect());
+    let results: Mutex<Vec<Option<Result<String, String>>>> = Mutex::new(vec![None; files.len()]);
+
+    std::thread::scope(|s| {
+        for _ in 0..concurrency {
+            s.spawn(|| loop {
+                if CANCEL.load(Ordering::SeqCst) { break; }
+                let Some((idx, path)) = queue.lock().unwrap().pop_front() else { break };
+
+                let result = translate_file(&path, &job).map_err(|e| e.to_string());
+                match &result {
+                    Ok(text) if batch.per_image_files => {
+                        let txt = output_dir.join(&txt_names[idx]);
+                        if let Err(e) = std::fs::write(&txt, text) {
+                            log::error!("Batch: failed to write {} - {}", txt.display(), e);
+                        }
+                    }
+                    Ok(_) => {}
+                    Err(e) => log::warn!("Batch: {} failed - {}", path.display(), e),
+                }
+
+                {
+                    let mut progress = PROGRESS.lock().unwrap();
+                    progress.done += 1;
+                    if result.is_err() { progress.failed += 1; }
+                }
+                results.lock().unwrap()[idx] = Some(result);
+            });
+        }
+    });
+
+    if batch.combined_file {
+        let name = format!("batch_{}.txt", chrono::Local::now().format("%Y%m%d_%H%M%S"));
+        let combined = combined_text(&files, &results.into_inner().unwrap());
+        std::fs::write(output_dir.join(&name), combined)
+            .map_err(|e| anyhow::anyhow!("Failed to write {}: {}", name, e))?;
+    }
+    if CANCEL.load(Ordering::SeqCst) {
+        log::info!("Batch cancelled");
+    }
+    Ok(())
+}
+
+fn translate_file(path: &Path, job: &BatchJob) -> anyhow::Result<String> {
+    let image = image::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?
+        .to_rgba8();
+    let (groq_api_key, gemini_api_key, openrouter_api_key) = &job.keys;
+    let text = with_rate_limit_retry("Batch", RATE_LIMIT_RETRIES, || CANCEL.load(Ordering::SeqCst), || translate_image_streaming(
+        groq_api_key,
+        gemini_api_key,
+        openrouter_api_key,
+        job.prompt.clone(),
+        job.model.clone(),
+        job.provider.clone(),
+        &image,
+        false,
+        job.use_json_format,
+        job.sampling,
+        |_| {},
+    ))?;
+    Ok(if job.study { crate::overlay::study::apply(text).0 } else { text })
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_else(|| "image".to_string())
+}
+
+/// `<stem>.txt` per file; a stem seen before (other folder or format) gets " (2)", " (3)"…
+fn output_names(files: &[PathBuf]) -> Vec<String> {
+    let mut taken = HashSet::new();
+    files.iter().map(|path| {
+        let stem = file_stem(path);
+        let mut name = format!("{}.txt", stem);
+        let mut n = 2;
+        // Windows file names ignore case
+        while !taken.insert(name.to_lowercase()) {
+            name = format!("{} ({}).txt", stem, n);
+            n += 1;
+        }
+        name
+    }).collect()
+}
+
+/// Every result under its file name, in input order (skipped files after a cancel are left out)
+fn combined_text(files: &[PathBuf], results: &[Option<Result<String, String>>]) -> String {
+    files.iter().zip(results)
+        .filter_map(|(path, result)| {
+            let name = path.file_name()?.to_string_lossy();
+            let body = match result.as_ref()? {
+                Ok(text) => text.trim().to_string(),
+                Err(e) => format!("[Failed: {}]", e),
+            };
+            Some(format!("=== {} ===\n{}\n", name, body))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Native "Open" dialog for several images. Blocks, so call it off the UI thread.
+pub fn pick_image_files() -> Vec<PathBuf> {
+    use windows::Win32::UI::Controls::Dialogs::*;
+
+    let patterns = IMAGE_EXTENSIONS.iter().map(|e| format!("*.{}", e)).collect::<Vec<_>>().join(";");
+    let filter: Vec<u16> = format!("Images ({})\0{}\0\0", patterns, patterns).encode_utf16().collect();
+    let mut file_buf = vec![0u16; 64 * 1024];
+
+    let mut ofn = OPENFILENAMEW {
+        lStructSize: std::mem::size_of::<OPENFILENAMEW>() as u32,
+        lpstrFilter: windows::core::PCWSTR(filter.as_ptr()),
+        lpstrFile: windows::core::PWSTR(file_buf.as_mut_ptr()),
+        nMaxFile: file_buf.len() as u32,
+        Flags: OFN_FILEMUSTEXIST | OFN_EXPLORER | OFN_NOCHANGEDIR | OFN_ALLOWMULTISELECT,
+        ..Default::default()
+    };
+
+    if unsafe { GetOpenFileNameW(&mut ofn) }.as_bool() {
+        parse_multi_select(&file_buf)
+    } else {
+        Vec::new()
+    }
+}
+
+/// The dialog returns one full path, or the folder followed by file names,
+/// each NUL-terminated and the list ended by an empty string
+fn parse_multi_select(buf: &[u16]) -> Vec<PathBuf> {
+    let parts: Vec<String> = buf.split(|&c| c == 0)
+        .take_while(|part| !part.is_empty())
+        .map(String::from_utf16_lossy)
+        .collect();
+    match parts.as_slice() {
+        [] => Vec::new(),
+        [single] => vec![PathBuf::from(single)],
+        [dir, names @ ..] => names.iter().map(|name| Path::new(dir).join(name)).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_multi_select() {
+        let wide = |s: &str| -> Vec<u16> { s.encode_utf16().collect() };
+        assert_eq!(parse_multi_select(&wide("C:\\pages\\01.png\0\0")), vec![PathBuf::from("C:\\pages\\01.png")]);
+        assert_eq!(
+            parse_multi_select(&wide("C:\\pages\0001.png\0002.png\0\0")),
+            vec![Path::new("C:\\pages").join("01.png"), Path::new("C:\\pages").join("02.png")]
+        );
+        assert!(parse_multi_select(&wide("\0\0")).is_empty());
+    }
+
+    #[test]
+    fn test_combined_text() {
+        let files = vec![PathBuf::from("a/01.png"), PathBuf::from("a/02.png"), PathBuf::from("a/03.png")];
+        let results = vec![Some(Ok("Xin chào\n".to_string())), Some(Err("timeout".to_string())), None];
+        assert_eq!(combined_text(&files, &results), "=== 01.png ===\nXin chào\n\n=== 02.png ===\n[Failed: timeout]\n");
+
+        assert_eq!(output_dir("", Path::new("C:/manga/01.png")), Path::new("C:/manga").join("translated"));
+        assert_eq!(output_dir(" D:/out ", Path::new("C:/manga/01.png")), PathBuf::from("D:/out"));
+        assert!(is_image_file(Path::new("page.PNG")));
+        assert!(is_image_file(Path::new("page.webp")));
+        assert!(!is_image_file(Path::new("page.jpg")));
+    }
+
+    #[test]
+    fn test_output_names() {
+        let files = vec![
+            PathBuf::from("a/01.png"),
+            PathBuf::from("b/01.png"),
+            PathBuf::from("a/01.webp"),
+            PathBuf::from("a/02.png"),
+            PathBuf::from("a/01 (2).png"),
+            PathBuf::from("c/02.PNG"),
+        ];
+        assert_eq!(output_names(&files), vec!["01.txt", "01 (2).txt", "01 (3).txt", "02.txt", "01 (2) (2).txt", "02 (2).txt"]);
+    }
+}
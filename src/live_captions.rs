@@ -294,6 +294,11 @@ where
         std::thread::sleep(std::time::Duration::from_millis(300));
     }
     
+    // Bring the native window back if we hid it
+    if auto_hide && unsafe { IsWindow(hwnd).as_bool() } {
+        let _ = show_live_captions(hwnd);
+    }
+    
     LIVE_CAPTIONS_ACTIVE.store(false, Ordering::SeqCst);
     log::info!("Live Captions capture loop ended");
     
@@ -324,11 +329,124 @@ pub fn is_live_captions_available() -> bool {
     }
 }
 
-/// Helper: Extract the latest complete sentence from Live Captions text
-pub fn extract_latest_sentence(text: &str) -> Option<String> {
-    let trimmed = text.trim();
-    if trimmed.is_empty() {
-        return None;
+/// How many emitted sentences are remembered to avoid re-translating them
+const SENTENCE_MEMORY: usize = 16;
+
+/// Tracks the rolling Live Captions text and yields each completed sentence once.
+///
+/// Live Captions keeps a scrolling window of recent text: old sentences fall off
+/// the front, the last one keeps growing, and earlier words are sometimes revised.
+/// The tracker only emits sentences that come after the last ones it already saw.
+#[derive(Default)]
+pub struct SentenceTracker {
+    emitted: std::collections::VecDeque<String>,
+}
+
+impl SentenceTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the full caption text, get back the newly completed sentences
+    pub fn update(&mut self, text: &str) -> Vec<String> {
+        let (complete, _pending) = split_sentences(text);
+        let keys: Vec<String> = complete.iter().map(|s| sentence_key(s)).collect();
+        let start = self.resume_point(&keys);
+
+        let fresh: Vec<String> = complete[start..].to_vec();
+        for sentence in &fresh {
+            self.emitted.push_back(sentence_key(sentence));
+            while self.emitted.len() > SENTENCE_MEMORY {
+                self.emitted.pop_front();
+            }
+        }
+        fresh
+    }
+
+    /// Index in `keys` just past the longest run that matches the end of what was emitted.
+    /// Matching a run rather than single sentences places a sentence the caption repeats
+    /// ("Yes. No. Yes.") by its neighbours. If the last emitted sentence was revised out of
+    /// the text, runs ending at earlier ones are tried.
+    fn resume_point(&self, keys: &[String]) -> usize {
+        let emitted: Vec<&String> = self.emitted.iter().collect();
+        for end in (1..=emitted.len()).rev() {
+            for len in (1..=end.min(keys.len())).rev() {
+                let tail = &emitted[end - len..end];
+                let found = (0..=keys.len() - len).rev()
+                    .find(|&p| keys[p..p + len].iter().zip(tail).all(|(k, e)| k == *e));
+                if let Some(p) = found {
+                    return p + len;
+                }
+            }
+        }
+        0
+    }
+}
+
+/// Split text into complete sentences and the trailing unfinished part
+pub fn split_sentences(text: &str) -> (Vec<String>, String) {
+    let mut complete = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        // Live Captions separates lines with newlines; treat them as spaces
+        current.push(if c == '\n' || c == '\r' { ' ' } else { c });
+        let is_end = matches!(c, '.' | '!' | '?' | '…' | '。' | '！' | '？');
+        // "3.5" or "e.g." mid-word is not a sentence end
+        let next_is_boundary = chars.peek().map_or(true, |n| n.is_whitespace());
+        if is_end && next_is_boundary {
+            let sentence = current.split_whitespace().collect::<Vec<_>>().join(" ");
+            if sentence.chars().any(|c| c.is_alphanumeric()) {
+                complete.push(sentence);
+            }
+            current.clear();
+        }
+    }
+
+    let pending = current.split_whitespace().collect::<Vec<_>>().join(" ");
+    (complete, pending)
+}
+
+/// Comparison key that ignores case and punctuation revisions
+fn sentence_key(sentence: &str) -> String {
+    sentence
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sentence_tracker_rolling_text() {
+        let mut tracker = SentenceTracker::new();
+        assert!(tracker.update("Hello there").is_empty());
+        assert_eq!(tracker.update("Hello there. How are"), vec!["Hello there."]);
+        assert!(tracker.update("Hello there. How are you").is_empty());
+        assert_eq!(
+            tracker.update("hello there! How are you? Version 3.5 is out. Next"),
+            vec!["How are you?", "Version 3.5 is out."]
+        );
+        // Older sentences scrolled off the front
+        assert_eq!(tracker.update("Version 3.5 is out. Next one.\nThen"), vec!["Next one."]);
+    }
+
+    #[test]
+    fn test_sentence_tracker_repeated_sentences() {
+        let mut tracker = SentenceTracker::new();
+        assert_eq!(tracker.update("Yes. No."), vec!["Yes.", "No."]);
+        // The second "Yes." is new, even though "Yes." was emitted before
+        assert_eq!(tracker.update("Yes. No. Yes."), vec!["Yes."]);
+        assert_eq!(tracker.update("Yes. No. Yes. No."), vec!["No."]);
+        assert!(tracker.update("Yes. No. Yes. No.").is_empty());
+        // The first "Yes." scrolled off: only what follows the emitted run is new
+        assert_eq!(tracker.update("No. Yes. No. Yes. Maybe."), vec!["Yes.", "Maybe."]);
     }
-    Some(trimmed.to_string())
 }
@@ -2,6 +2,7 @@
 
 mod config;
 mod api;
+mod api_error;
 mod gui;
 mod overlay;
 mod capture;
@@ -12,9 +13,21 @@ mod live_captions;
 mod conversation;
 mod gemini_live;
 mod audio_capture;
+mod audio_file;
+mod image_batch;
+mod metrics;
+mod notes_file;
+mod translation_cache;
+mod utf8_stream;
+mod diagnostics;
+mod app_log;
+mod crash_report;
+mod shutdown;
+mod update_check;
+mod webhook;
+mod sessions;
 
 use std::sync::{Arc, Mutex};
-use std::panic;
 use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::Win32::UI::Input::KeyboardAndMouse::*;
 use windows::Win32::System::LibraryLoader::*;
@@ -25,7 +38,7 @@ use windows::core::*;
 use lazy_static::lazy_static;
 use image::ImageBuffer;
 use config::{Config, load_config};
-use tray_icon::{TrayIconBuilder, menu::{Menu, MenuItem}};
+use tray_icon::{TrayIconBuilder, menu::{Menu, MenuItem, CheckMenuItem, Submenu}};
 use std::collections::HashMap;
 
 // Global event for inter-process restore signaling (manual-reset event)
@@ -38,10 +51,15 @@ lazy_static! {
 pub struct AppState {
     pub config: Config,
     pub original_screenshot: Option<ImageBuffer<image::Rgba<u8>, Vec<u8>>>,
+    pub screenshot_origin: (i32, i32), // Virtual-screen position of original_screenshot's top-left pixel
     pub hotkeys_updated: bool,
     pub registered_hotkey_ids: Vec<i32>, // Track IDs of currently registered hotkeys
     // New: Track API usage limits (Key: Model Full Name, Value: "Remaining / Total")
     pub model_usage_stats: HashMap<String, String>, 
+    pub last_capture: Option<overlay::last_capture::LastCapture>, // Crop of the last image capture, for re-runs
+    pub active_sessions: Vec<sessions::ActiveSession>, // Running recordings / live sessions, for the stop-all hotkey
+    pub last_hotkey_trigger: HashMap<i32, std::time::Instant>, // Last WM_HOTKEY per hotkey id, for the debounce
+    pub recent_copies: overlay::recent_copies::RecentCopies, // Last texts put on the clipboard, newest first
 }
 
 lazy_static! {
@@ -50,71 +68,30 @@ lazy_static! {
         AppState {
             config,
             original_screenshot: None,
+            screenshot_origin: (0, 0),
             hotkeys_updated: false,
             registered_hotkey_ids: Vec::new(),
             model_usage_stats: HashMap::new(),
+            last_capture: None,
+            active_sessions: Vec::new(),
+            last_hotkey_trigger: HashMap::new(),
+            recent_copies: Default::default(),
         }
     }));
 }
 
 fn main() -> eframe::Result<()> {
     // --- LOGGING INIT ---
-    if let Some(config_dir) = dirs::config_dir() {
-        let app_dir = config_dir.join("xt-screen-translator");
-        let _ = std::fs::create_dir_all(&app_dir);
-        let log_file = app_dir.join("app.log");
-        
-        let _ = simplelog::WriteLogger::init(
-            simplelog::LevelFilter::Info,
-            simplelog::Config::default(),
-            std::fs::File::create(log_file).unwrap_or_else(|_| std::fs::File::create("app.log").unwrap())
-        );
-    }
+    let (log_level, log_max_size_mb, show_crash_dialog) = {
+        let app = APP.lock().unwrap();
+        (app.config.log_level, app.config.log_max_size_mb, app.config.show_crash_dialog)
+    };
+    app_log::init(log_level, log_max_size_mb);
+    crash_report::configure(show_crash_dialog);
     log::info!("Application starting...");
 
-    // --- CRASH HANDLER START ---
-    panic::set_hook(Box::new(|panic_info| {
-        let error_msg = if let Some(s) = panic_info.payload().downcast_ref::<&str>() {
-            format!("{}", s)
-        } else if let Some(s) = panic_info.payload().downcast_ref::<String>() {
-            format!("{}", s)
-        } else {
-            "Unknown panic".to_string()
-        };
-
-        // 1. Format the error message
-        let location = if let Some(location) = panic_info.location() {
-            format!("File: {}\nLine: {}", location.file(), location.line())
-        } else {
-            "Unknown location".to_string()
-        };
-
-        let payload = if let Some(s) = panic_info.payload().downcast_ref::<&str>() {
-            s.to_string()
-        } else if let Some(s) = panic_info.payload().downcast_ref::<String>() {
-            s.clone()
-        } else {
-            "Unknown panic payload".to_string()
-        };
-
-        let error_msg = format!("CRASH DETECTED!\n\nError: {}\n\nLocation:\n{}", payload, location);
-        
-        log::error!("{}", error_msg);
-
-        // Show a Windows Message Box so the user knows it crashed
-        let wide_msg: Vec<u16> = error_msg.encode_utf16().chain(std::iter::once(0)).collect();
-        let wide_title: Vec<u16> = "XT Screen Translator Crash Report".encode_utf16().chain(std::iter::once(0)).collect();
-
-        unsafe {
-            MessageBoxW(
-                None,
-                PCWSTR(wide_msg.as_ptr()),
-                PCWSTR(wide_title.as_ptr()),
-                MB_ICONERROR | MB_OK
-            );
-        }
-    }));
-    // --- CRASH HANDLER END ---
+    // --- CRASH HANDLER ---
+    crash_report::install();
     
     // Ensure the named event exists (for first instance, for second instance to signal)
     let _ = RESTORE_EVENT.as_ref();
@@ -139,11 +116,24 @@ fn main() -> eframe::Result<()> {
     std::thread::spawn(|| {
         run_hotkey_listener();
     });
+    overlay::result::start_state_janitor();
+    update_check::start();
+    #[cfg(debug_assertions)]
+    overlay::result::start_gdi_monitor();
+    overlay::preset_indicator::refresh();
 
     let tray_menu = Menu::new();
     let settings_i = MenuItem::with_id("1002", "Settings", true, None);
+    let live_captions_i = MenuItem::with_id("1003", "Live Captions", true, None);
+    let preset_indicator_on = APP.lock().map(|a| a.config.preset_indicator).unwrap_or(false);
+    let preset_indicator_i = CheckMenuItem::with_id("1004", "Show Active Preset", true, preset_indicator_on, None);
+    // Filled by the settings app (menu items can't leave this thread), see sync_recent_copies_menu
+    let recent_copies_i = Submenu::with_id("1005", "Recent Copies", true);
     let quit_i = MenuItem::with_id("1001", "Quit", true, None);
     let _ = tray_menu.append(&settings_i);
+    let _ = tray_menu.append(&live_captions_i);
+    let _ = tray_menu.append(&preset_indicator_i);
+    let _ = tray_menu.append(&recent_copies_i);
     let _ = tray_menu.append(&quit_i);
 
     let icon = icon_gen::generate_icon();
@@ -190,6 +180,22 @@ fn main() -> eframe::Result<()> {
     )
 }
 
+// Outside the preset ID range (and below the 0xC000 limit for app hotkeys)
+const LIVE_CAPTIONS_HOTKEY_ID: i32 = 0xBFFF;
+const TOGGLE_RESULTS_HOTKEY_ID: i32 = 0xBFFE;
+const PRESET_PICKER_HOTKEY_ID: i32 = 0xBFFD;
+const STOP_ALL_HOTKEY_ID: i32 = 0xBFFC;
+const RECENT_COPIES_HOTKEY_ID: i32 = 0xBFFB;
+// Hotkey index reserved in each preset's ID range for its "re-run last capture" key
+const RERUN_HOTKEY_SLOT: i32 = 998;
+// ... and for its "cycle target language" key
+const CYCLE_LANGUAGE_HOTKEY_SLOT: i32 = 997;
+
+/// MOD_NOREPEAT: a held key sends one WM_HOTKEY, not one per key repeat
+unsafe fn register_hotkey(hwnd: HWND, id: i32, hotkey: &config::Hotkey) {
+    RegisterHotKey(hwnd, id, HOT_KEY_MODIFIERS(hotkey.modifiers) | MOD_NOREPEAT, hotkey.code);
+}
+
 fn register_all_hotkeys(hwnd: HWND) {
     let mut app = APP.lock().unwrap();
     let presets = &app.config.presets;
@@ -200,10 +206,54 @@ fn register_all_hotkeys(hwnd: HWND) {
             // ID encoding: 1000 * preset_idx + hotkey_idx + 1
             let id = (p_idx as i32 * 1000) + (h_idx as i32) + 1;
             unsafe {
-                RegisterHotKey(hwnd, id, HOT_KEY_MODIFIERS(hotkey.modifiers), hotkey.code);
+                register_hotkey(hwnd, id, hotkey);
             }
             registered_ids.push(id);
         }
+        if let Some(hotkey) = preset.rerun_hotkey.as_ref().filter(|_| overlay::last_capture::supports_rerun(preset)) {
+            let id = (p_idx as i32 * 1000) + RERUN_HOTKEY_SLOT + 1;
+            unsafe {
+                register_hotkey(hwnd, id, hotkey);
+            }
+            registered_ids.push(id);
+        }
+        if let Some(hotkey) = preset.cycle_language_hotkey.as_ref().filter(|_| overlay::language_cycle::supports_cycle(preset)) {
+            let id = (p_idx as i32 * 1000) + CYCLE_LANGUAGE_HOTKEY_SLOT + 1;
+            unsafe {
+                register_hotkey(hwnd, id, hotkey);
+            }
+            registered_ids.push(id);
+        }
+    }
+    if let Some(hotkey) = &app.config.live_captions.hotkey {
+        unsafe {
+            register_hotkey(hwnd, LIVE_CAPTIONS_HOTKEY_ID, hotkey);
+        }
+        registered_ids.push(LIVE_CAPTIONS_HOTKEY_ID);
+    }
+    if let Some(hotkey) = &app.config.toggle_results_hotkey {
+        unsafe {
+            register_hotkey(hwnd, TOGGLE_RESULTS_HOTKEY_ID, hotkey);
+        }
+        registered_ids.push(TOGGLE_RESULTS_HOTKEY_ID);
+    }
+    if let Some(hotkey) = &app.config.preset_picker_hotkey {
+        unsafe {
+            register_hotkey(hwnd, PRESET_PICKER_HOTKEY_ID, hotkey);
+        }
+        registered_ids.push(PRESET_PICKER_HOTKEY_ID);
+    }
+    if let Some(hotkey) = &app.config.stop_all_hotkey {
+        unsafe {
+            register_hotkey(hwnd, STOP_ALL_HOTKEY_ID, hotkey);
+        }
+        registered_ids.push(STOP_ALL_HOTKEY_ID);
+    }
+    if let Some(hotkey) = &app.config.recent_copies_hotkey {
+        unsafe {
+            register_hotkey(hwnd, RECENT_COPIES_HOTKEY_ID, hotkey);
+        }
+        registered_ids.push(RECENT_COPIES_HOTKEY_ID);
     }
     app.registered_hotkey_ids = registered_ids;
 }
@@ -280,55 +330,131 @@ unsafe extern "system" fn hotkey_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lpar
     match msg {
         WM_HOTKEY => {
             let id = wparam.0 as i32;
+            if hotkey_debounced(id) {
+                return LRESULT(0);
+            }
+            if id == LIVE_CAPTIONS_HOTKEY_ID {
+                std::thread::spawn(overlay::toggle_live_captions_overlay);
+                return LRESULT(0);
+            }
+            if id == TOGGLE_RESULTS_HOTKEY_ID {
+                overlay::result::toggle_all_result_windows();
+                return LRESULT(0);
+            }
+            if id == STOP_ALL_HOTKEY_ID {
+                sessions::stop_all();
+                return LRESULT(0);
+            }
+            if id == RECENT_COPIES_HOTKEY_ID {
+                std::thread::spawn(overlay::recent_copies::pick_and_copy);
+                return LRESULT(0);
+            }
+            if id == PRESET_PICKER_HOTKEY_ID {
+                if !region_capture_allowed() {
+                    return LRESULT(0);
+                }
+                let foreground_app = overlay::app_blocklist::foreground_app();
+                overlay::type_out::remember_foreground();
+                overlay::window_context::remember();
+                // The picker doesn't know the preset's monitor yet, so every monitor is captured
+                std::thread::spawn(move || capture_and_select(capture::virtual_screen_rect(), move || {
+                    let p_idx = overlay::preset_picker::pick_preset()?;
+                    overlay::preset_indicator::set_active_preset(p_idx);
+                    overlay::app_language::on_preset_picked(p_idx, foreground_app);
+                    let target_monitor = APP.lock().ok()?.config.presets.get(p_idx)?.target_monitor.clone();
+                    Some((p_idx, overlay::selection::selection_bounds(target_monitor.as_deref())))
+                }));
+                return LRESULT(0);
+            }
+            if id > 0 && (id - 1) % 1000 == CYCLE_LANGUAGE_HOTKEY_SLOT {
+                // Changes a setting only: not a run of the preset
+                std::thread::spawn(move || overlay::language_cycle::cycle(((id - 1) / 1000) as usize));
+                return LRESULT(0);
+            }
             if id > 0 {
                 let preset_idx = ((id - 1) / 1000) as usize;
+                overlay::preset_indicator::set_active_preset(preset_idx);
+                // Before any overlay takes focus; a type-out result goes back to this window
+                overlay::type_out::remember_foreground();
+                overlay::window_context::remember();
+
+                if (id - 1) % 1000 == RERUN_HOTKEY_SLOT {
+                    std::thread::spawn(move || overlay::last_capture::rerun(preset_idx));
+                    return LRESULT(0);
+                }
                 
-                let preset_type = {
+                let (preset_type, capture_mode, capture_monitor, target_monitor, capture_on_release) = {
                     if let Ok(app) = APP.lock() {
                         if preset_idx < app.config.presets.len() {
-                            app.config.presets[preset_idx].preset_type.clone()
-                        } else { "image".to_string() }
+                            let preset = &app.config.presets[preset_idx];
+                            (preset.preset_type.clone(), preset.capture_mode.clone(), preset.capture_monitor().map(str::to_string), preset.target_monitor.clone(), preset.capture_on_release)
+                        } else { ("image".to_string(), String::new(), None, None, false) }
                     } else {
                         eprintln!("Error: APP mutex poisoned on hotkey trigger.");
                         return LRESULT(0);
                     }
                 };
 
-                if preset_type == "audio" {
+                if preset_type == "image" && capture_mode == overlay::replace_selection::CAPTURE_MODE {
+                    if let Some(app) = overlay::app_blocklist::blocked_foreground_app() {
+                        log::info!("Capture blocked: {} is on the blocklist", app);
+                        overlay::app_blocklist::show_blocked_toast(&app);
+                        return LRESULT(0);
+                    }
+                    overlay::app_language::on_preset_hotkey(preset_idx);
+                    std::thread::spawn(move || overlay::replace_selection::run_replace_selection(preset_idx));
+                } else if preset_type == "audio" {
                     if overlay::is_recording_overlay_active() {
                         overlay::stop_recording_and_submit();
                     } else {
+                        overlay::app_language::on_preset_hotkey(preset_idx);
                         std::thread::spawn(move || {
                             overlay::show_recording_overlay(preset_idx);
                         });
                     }
                 } else {
-                    // Check if Live Vision is Active -> STOP IT
-                    if crate::api::VISION_ACTIVE.load(std::sync::atomic::Ordering::SeqCst) {
-                        crate::api::VISION_STOP_SIGNAL.store(true, std::sync::atomic::Ordering::SeqCst);
-                        return LRESULT(0);
-                    }
-
-                    if overlay::is_selection_overlay_active_and_dismiss() {
+                    if !region_capture_allowed() {
                         return LRESULT(0);
                     }
+                    overlay::app_language::on_preset_hotkey(preset_idx);
                     
                     let app_clone = APP.clone();
                     let p_idx = preset_idx;
 
-                    std::thread::spawn(move || {
-                        match capture::capture_full_screen() {
-                            Ok(img) => {
-                                if let Ok(mut app) = app_clone.lock() {
-                                    app.original_screenshot = Some(img);
-                                } else {
-                                    return;
+                    // Full-monitor presets skip the selection overlay
+                    if let Some(device_name) = capture_monitor {
+                        std::thread::spawn(move || {
+                            match capture::capture_monitor(&device_name) {
+                                Ok((img, rect)) => {
+                                    if let Ok(mut app) = app_clone.lock() {
+                                        app.original_screenshot = Some(img);
+                                        app.screenshot_origin = (rect.left, rect.top);
+                                    } else {
+                                        return;
+                                    }
+                                    overlay::process::process_and_close(app_clone, rect, HWND(0), p_idx);
+                                },
+                                Err(e) => {
+                                    eprintln!("Capture Error: {}", e);
                                 }
-                                overlay::show_selection_overlay(p_idx);
-                            },
-                            Err(e) => {
-                                eprintln!("Capture Error: {}", e);
                             }
+                        });
+                        return LRESULT(0);
+                    }
+
+                    std::thread::spawn(move || {
+                        // Only the overlay's area is captured
+                        let bounds = overlay::selection::selection_bounds(target_monitor.as_deref());
+                        if capture_on_release {
+                            // The screen keeps moving under the overlay; the selection is captured at mouse-up
+                            if let Ok(mut app) = APP.lock() {
+                                app.original_screenshot = None;
+                            } else {
+                                return;
+                            }
+                            overlay::show_selection_overlay(p_idx, bounds);
+                        } else {
+                            capture_and_select(bounds, move || Some((p_idx, bounds)));
                         }
                     });
                 }
@@ -337,4 +463,86 @@ unsafe extern "system" fn hotkey_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lpar
         }
         _ => DefWindowProcW(hwnd, msg, wparam, lparam),
     }
-}
\ No newline at end of file
+}
+
+/// Whether this `WM_HOTKEY` repeats hotkey `id` within `Config.hotkey_debounce_ms`
+/// (keyboards and macro tools that send the key twice; MOD_NOREPEAT already drops key
+/// repeat). Every trigger, ignored or not, restarts the window; audio presets still stop
+/// on the next real press.
+fn hotkey_debounced(id: i32) -> bool {
+    let now = std::time::Instant::now();
+    let Ok(mut app) = APP.lock() else { return false; };
+    let window = std::time::Duration::from_millis(app.config.hotkey_debounce_ms as u64);
+    let last = app.last_hotkey_trigger.insert(id, now);
+    let repeat = is_repeat(last, now, window);
+    if repeat {
+        log::debug!("Hotkey {} fired again within {:?}, ignored", id, window);
+    }
+    repeat
+}
+
+fn is_repeat(last: Option<std::time::Instant>, now: std::time::Instant, window: std::time::Duration) -> bool {
+    last.map_or(false, |last| now.saturating_duration_since(last) < window)
+}
+
+/// Checks before a hotkey captures the screen. A press while Live Vision runs stops it,
+/// a press while the selection overlay is open dismisses it, and blocklisted apps are
+/// never captured. False if the hotkey shouldn't capture.
+fn region_capture_allowed() -> bool {
+    if crate::api::VISION_ACTIVE.load(std::sync::atomic::Ordering::SeqCst) {
+        crate::api::VISION_STOP_SIGNAL.store(true, std::sync::atomic::Ordering::SeqCst);
+        return false;
+    }
+
+    if overlay::is_selection_overlay_active_and_dismiss() {
+        return false;
+    }
+
+    if let Some(app) = overlay::app_blocklist::blocked_foreground_app() {
+        log::info!("Capture blocked: {} is on the blocklist", app);
+        overlay::app_blocklist::show_blocked_toast(&app);
+        return false;
+    }
+    true
+}
+
+/// Capture `area` (virtual-screen coordinates), then open the region selection for the
+/// preset and overlay bounds `choose` returns. The capture comes first so that nothing
+/// shown while choosing ends up in it; crops are offset by `screenshot_origin`, so
+/// `area` only has to cover the bounds.
+fn capture_and_select(area: RECT, choose: impl FnOnce() -> Option<(usize, RECT)>) {
+    match capture::capture_rect(area) {
+        Ok(img) => {
+            if let Ok(mut app) = APP.lock() {
+                app.original_screenshot = Some(img);
+                app.screenshot_origin = (area.left, area.top);
+            } else {
+                return;
+            }
+        }
+        Err(e) => {
+            eprintln!("Capture Error: {}", e);
+            return;
+        }
+    }
+    if let Some((p_idx, bounds)) = choose() {
+        overlay::show_selection_overlay(p_idx, bounds);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_is_repeat() {
+        let window = Duration::from_millis(250);
+        let now = Instant::now();
+        assert!(!is_repeat(None, now, window));
+        assert!(is_repeat(Some(now), now + Duration::from_millis(30), window));
+        assert!(!is_repeat(Some(now), now + Duration::from_millis(400), window));
+        // 0 turns the debounce off
+        assert!(!is_repeat(Some(now), now, Duration::ZERO));
+    }
+}
@@ -0,0 +1,231 @@
+//! Streaming Metrics Module
+//!
+//! Optional timing for streaming API calls (time to first chunk, total time,
+//! throughput), so a slow network can be told apart from a slow model.
+//! Enabled by `Config.debug_metrics`.
+//!
+//! `LiveStats` collects per-stage latencies of a live audio/vision session
+//! for the `show_live_stats` HUD and the end-of-session log line.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static::lazy_static! {
+    /// Summary of the most recent finished request (shown in settings)
+    static ref LAST_SUMMARY: Mutex<Option<StreamSummary>> = Mutex::new(None);
+}
+
+/// Apply the `debug_metrics` setting
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::SeqCst);
+}
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::SeqCst)
+}
+
+/// Get the summary of the last measured request, if any
+pub fn last_summary() -> Option<StreamSummary> {
+    LAST_SUMMARY.lock().ok().and_then(|s| s.clone())
+}
+
+#[derive(Clone, Debug)]
+pub struct StreamSummary {
+    pub first_chunk: Option<Duration>,
+    pub total: Duration,
+    pub chars: usize,
+}
+
+impl StreamSummary {
+    pub fn chars_per_sec(&self) -> f64 {
+        let secs = self.total.as_secs_f64();
+        if secs > 0.0 { self.chars as f64 / secs } else { 0.0 }
+    }
+
+    /// e.g. "first chunk 420 ms, total 2.10 s, 85 chars/s"
+    pub fn describe(&self) -> String {
+        let first = match self.first_chunk {
+            Some(d) => format!("{} ms", d.as_millis()),
+            None => "-".to_string(),
+        };
+        format!(
+            "first chunk {}, total {:.2} s, {:.0} chars/s",
+            first,
+            self.total.as_secs_f64(),
+            self.chars_per_sec()
+        )
+    }
+}
+
+/// Measures one request. Does nothing when metrics are disabled.
+pub struct StreamTimer {
+    start: Option<Instant>,
+    first_chunk: Option<Duration>,
+    chars: usize,
+}
+
+impl StreamTimer {
+    pub fn start() -> Self {
+        Self {
+            start: if is_enabled() { Some(Instant::now()) } else { None },
+            first_chunk: None,
+            chars: 0,
+        }
+    }
+
+    /// Call for every chunk handed to the UI
+    pub fn on_chunk(&mut self, chunk: &str) {
+        if let Some(start) = self.start {
+            if self.first_chunk.is_none() {
+                self.first_chunk = Some(start.elapsed());
+            }
+            self.chars += chunk.chars().count();
+        }
+    }
+
+    /// Log the timings and remember them as the last summary
+    pub fn finish(self, kind: &str, provider: &str, model: &str) {
+        if let Some(start) = self.start {
+            let summary = StreamSummary {
+                first_chunk: self.first_chunk,
+                total: start.elapsed(),
+                chars: self.chars,
+            };
+            log::info!("[metrics] {} {}/{}: {}", kind, provider, model, summary.describe());
+            if let Ok(mut last) = LAST_SUMMARY.lock() {
+                *last = Some(summary);
+            }
+        }
+    }
+}
+
+/// Per-stage timings of one live session
+#[derive(Default)]
+pub struct LiveStats {
+    capture: Vec<Duration>,     // Captured -> request sent (time spent queued)
+    api: Vec<Duration>,         // Main request round trip
+    retranslate: Vec<Duration>, // Retranslation round trip
+    processed: usize,
+    skipped: usize,
+    dropped: usize,             // Evicted from the full queue before they were taken
+    queued: usize,              // Chunks still waiting after the last one was taken
+}
+
+impl LiveStats {
+    pub fn record_capture(&mut self, d: Duration, queued: usize) {
+        self.capture.push(d);
+        self.queued = queued;
+    }
+
+    pub fn record_api(&mut self, d: Duration) {
+        self.api.push(d);
+        self.processed += 1;
+    }
+
+    pub fn record_retranslate(&mut self, d: Duration) {
+        self.retranslate.push(d);
+    }
+
+    pub fn add_skipped(&mut self, n: usize) {
+        self.skipped += n;
+    }
+
+    pub fn set_dropped(&mut self, total: usize) {
+        self.dropped = total;
+    }
+
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Latest values, e.g. "cap 40ms | api 820ms | q 2"
+    pub fn hud_line(&self) -> String {
+        let ms = |v: &[Duration]| v.last().map_or("-".to_string(), |d| format!("{}ms", d.as_millis()));
+        let mut line = format!("cap {} | api {}", ms(&self.capture), ms(&self.api));
+        if !self.retranslate.is_empty() {
+            line.push_str(&format!(" | rt {}", ms(&self.retranslate)));
+        }
+        line.push_str(&format!(" | q {}", self.queued));
+        if self.dropped > 0 {
+            line.push_str(&format!(" | drop {}", self.dropped));
+        }
+        line
+    }
+
+    /// Whole-session summary for the log
+    pub fn summary(&self) -> String {
+        let stage = |name: &str, v: &[Duration]| match (average(v), percentile_95(v)) {
+            (Some(avg), Some(p95)) => format!("{} avg {}ms / p95 {}ms", name, avg.as_millis(), p95.as_millis()),
+            _ => format!("{} -", name),
+        };
+        let mut parts = vec![stage("cap", &self.capture), stage("api", &self.api)];
+        if !self.retranslate.is_empty() {
+            parts.push(stage("rt", &self.retranslate));
+        }
+        let dropped = if self.dropped > 0 { format!(", {} dropped", self.dropped) } else { String::new() };
+        format!("{} chunks processed, {} skipped{}; {}", self.processed, self.skipped, dropped, parts.join(", "))
+    }
+}
+
+fn average(values: &[Duration]) -> Option<Duration> {
+    if values.is_empty() {
+        return None;
+    }
+    Some(values.iter().sum::<Duration>() / values.len() as u32)
+}
+
+/// Nearest-rank 95th percentile
+fn percentile_95(values: &[Duration]) -> Option<Duration> {
+    if values.is_empty() {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort();
+    let rank = (sorted.len() * 95).div_ceil(100);
+    Some(sorted[rank.max(1) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_describe() {
+        let summary = StreamSummary {
+            first_chunk: Some(Duration::from_millis(420)),
+            total: Duration::from_millis(2000),
+            chars: 170,
+        };
+        assert_eq!(summary.describe(), "first chunk 420 ms, total 2.00 s, 85 chars/s");
+
+        let empty = StreamSummary { first_chunk: None, total: Duration::ZERO, chars: 0 };
+        assert_eq!(empty.chars_per_sec(), 0.0);
+    }
+
+    #[test]
+    fn test_live_stats() {
+        let mut stats = LiveStats::default();
+        assert_eq!(stats.hud_line(), "cap - | api - | q 0");
+
+        for ms in 1..=20u64 {
+            stats.record_capture(Duration::from_millis(ms * 10), 2);
+            stats.record_api(Duration::from_millis(ms * 100));
+        }
+        stats.add_skipped(3);
+        assert_eq!(stats.hud_line(), "cap 200ms | api 2000ms | q 2");
+        assert_eq!(
+            stats.summary(),
+            "20 chunks processed, 3 skipped; cap avg 105ms / p95 190ms, api avg 1050ms / p95 1900ms"
+        );
+
+        stats.record_retranslate(Duration::from_millis(300));
+        assert_eq!(stats.hud_line(), "cap 200ms | api 2000ms | rt 300ms | q 2");
+
+        stats.set_dropped(4);
+        assert_eq!(stats.hud_line(), "cap 200ms | api 2000ms | rt 300ms | q 2 | drop 4");
+        assert!(stats.summary().starts_with("20 chunks processed, 3 skipped, 4 dropped; "));
+    }
+}
@@ -1,13 +1,17 @@
 /// Centralized Model Configuration
 
-#[derive(Clone, Debug, PartialEq)]
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum ModelType {
     Vision,
     Text,
     Audio,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ModelConfig {
     pub id: String,
     pub provider: String,
@@ -18,6 +22,8 @@ pub struct ModelConfig {
     pub model_type: ModelType,
     pub enabled: bool,
     pub quota_limit: String, 
+    #[serde(default)]
+    pub discovered: bool, // Fetched from the provider's /models endpoint, not curated
 }
 
 impl ModelConfig {
@@ -42,6 +48,24 @@ impl ModelConfig {
              model_type,
              enabled,
              quota_limit: quota_limit.to_string(),
+             discovered: false,
+         }
+     }
+
+     /// A model found via "Refresh models". The id is namespaced by provider so it
+     /// can't collide with the curated ids.
+     pub fn discovered(provider: &str, full_name: &str, model_type: ModelType) -> Self {
+         Self {
+             id: format!("{}:{}", provider, full_name),
+             provider: provider.to_string(),
+             name_vi: full_name.to_string(),
+             name_ko: full_name.to_string(),
+             name_en: full_name.to_string(),
+             full_name: full_name.to_string(),
+             model_type,
+             enabled: true,
+             quota_limit: "Discovered".to_string(),
+             discovered: true,
          }
      }
 
@@ -334,10 +358,146 @@ lazy_static::lazy_static! {
     ];
 }
 
-pub fn get_all_models() -> &'static [ModelConfig] {
-    &ALL_MODELS
+lazy_static::lazy_static! {
+    /// Models from the last "Refresh models", loaded from the disk cache on first use
+    static ref DISCOVERED_MODELS: Mutex<Vec<ModelConfig>> = Mutex::new(load_discovered_models());
+}
+
+/// Curated models first, then discovered ones that aren't already curated
+pub fn get_all_models() -> Vec<ModelConfig> {
+    let mut models = ALL_MODELS.clone();
+    if let Ok(discovered) = DISCOVERED_MODELS.lock() {
+        models.extend(discovered.iter().cloned());
+    }
+    models
+}
+
+/// Models only served over the Gemini Live websocket: the REST endpoints reject them,
+/// so a failed Live session has no chunked fallback
+pub fn is_live_only(id: &str) -> bool {
+    id == "gemini-2.0-flash-live"
 }
 
 pub fn get_model_by_id(id: &str) -> Option<ModelConfig> {
-    get_all_models().iter().find(|m| m.id == id).cloned()
+    if let Some(m) = ALL_MODELS.iter().find(|m| m.id == id) {
+        return Some(m.clone());
+    }
+    DISCOVERED_MODELS.lock().ok()?.iter().find(|m| m.id == id).cloned()
+}
+
+fn get_models_cache_path() -> PathBuf {
+    let config_dir = dirs::config_dir()
+        .unwrap_or_default()
+        .join("xt-screen-translator");
+    let _ = std::fs::create_dir_all(&config_dir);
+    config_dir.join("models_cache.json")
+}
+
+fn load_discovered_models() -> Vec<ModelConfig> {
+    std::fs::read_to_string(get_models_cache_path())
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+/// Replace the discovered models (dropping curated duplicates) and cache them to disk.
+/// Returns how many discovered models were kept.
+pub fn set_discovered_models(models: Vec<ModelConfig>) -> usize {
+    let mut models = merge_discovered(&ALL_MODELS, models);
+    models.sort_by(|a, b| (&a.provider, &a.full_name).cmp(&(&b.provider, &b.full_name)));
+
+    if let Ok(data) = serde_json::to_string_pretty(&models) {
+        if let Err(e) = std::fs::write(get_models_cache_path(), data) {
+            log::error!("Failed to write models cache: {}", e);
+        }
+    }
+    let count = models.len();
+    if let Ok(mut discovered) = DISCOVERED_MODELS.lock() {
+        *discovered = models;
+    }
+    count
+}
+
+/// Keep discovered models whose (provider, full_name) isn't curated or repeated
+fn merge_discovered(curated: &[ModelConfig], discovered: Vec<ModelConfig>) -> Vec<ModelConfig> {
+    let mut seen: std::collections::HashSet<(String, String)> = curated
+        .iter()
+        .map(|m| (m.provider.clone(), m.full_name.clone()))
+        .collect();
+    discovered
+        .into_iter()
+        .filter(|m| seen.insert((m.provider.clone(), m.full_name.clone())))
+        .collect()
+}
+
+/// Guess how a listed model can be used from its name and (if the provider reports
+/// them) input modalities. None = not usable here (TTS, embeddings, guard models...).
+pub fn infer_model_type(provider: &str, name: &str, input_modalities: &[String]) -> Option<ModelType> {
+    let lower = name.to_lowercase();
+    const UNUSABLE: &[&str] = &["tts", "embed", "guard", "imagen", "veo", "aqa", "image-generation", "native-audio"];
+    if UNUSABLE.iter().any(|k| lower.contains(k)) {
+        return None;
+    }
+    if lower.contains("whisper") {
+        return Some(ModelType::Audio);
+    }
+    if input_modalities.iter().any(|m| m == "image") {
+        return Some(ModelType::Vision);
+    }
+    let vision = match provider {
+        // Gemini models are multimodal
+        "google" => lower.starts_with("gemini") || lower.starts_with("gemma-3"),
+        _ => lower.contains("vision") || lower.contains("llama-4") || lower.contains("-vl"),
+    };
+    Some(if vision { ModelType::Vision } else { ModelType::Text })
+}
+
+/// Valid `thinkingBudget` range of a Gemini model; None = it takes no thinking config.
+/// -1 (dynamic budget) is accepted on top of the range.
+pub fn thinking_budget_range(full_name: &str) -> Option<(i32, i32)> {
+    let lower = full_name.to_lowercase();
+    // The "-latest" aliases point at the 2.5 family
+    if !lower.starts_with("gemini") || !(lower.contains("2.5") || lower.ends_with("-latest")) {
+        return None;
+    }
+    // Pro can't turn thinking off
+    Some(if lower.contains("pro") { (128, 32768) } else { (0, 24576) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_infer_model_type() {
+        assert_eq!(infer_model_type("groq", "whisper-large-v3-turbo", &[]), Some(ModelType::Audio));
+        assert_eq!(infer_model_type("groq", "meta-llama/llama-4-maverick-17b-128e-instruct", &[]), Some(ModelType::Vision));
+        assert_eq!(infer_model_type("groq", "llama-3.3-70b-versatile", &[]), Some(ModelType::Text));
+        assert_eq!(infer_model_type("groq", "playai-tts", &[]), None);
+        assert_eq!(infer_model_type("google", "gemini-2.5-flash", &[]), Some(ModelType::Vision));
+        assert_eq!(infer_model_type("google", "text-embedding-004", &[]), None);
+        let modalities = vec!["text".to_string(), "image".to_string()];
+        assert_eq!(infer_model_type("openrouter", "qwen/qwen2.5-72b:free", &modalities), Some(ModelType::Vision));
+    }
+
+    #[test]
+    fn test_thinking_budget_range() {
+        assert_eq!(thinking_budget_range("gemini-2.5-pro"), Some((128, 32768)));
+        assert_eq!(thinking_budget_range("gemini-flash-lite-latest"), Some((0, 24576)));
+        assert_eq!(thinking_budget_range("gemini-2.0-flash-exp"), None);
+        assert_eq!(thinking_budget_range("openai/gpt-oss-20b"), None);
+    }
+
+    #[test]
+    fn test_merge_discovered_skips_curated() {
+        let curated = vec![ModelConfig::new("fast", "groq", "", "", "", "llama-3.3-70b-versatile", ModelType::Text, true, "")];
+        let merged = merge_discovered(&curated, vec![
+            ModelConfig::discovered("groq", "llama-3.3-70b-versatile", ModelType::Text),
+            ModelConfig::discovered("groq", "qwen-qwq-32b", ModelType::Text),
+            ModelConfig::discovered("groq", "qwen-qwq-32b", ModelType::Text),
+        ]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].id, "groq:qwen-qwq-32b");
+        assert!(merged[0].discovered);
+    }
 }
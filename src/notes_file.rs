@@ -0,0 +1,173 @@
+//! Notes File Module
+//!
+//! `Preset.output_file`: each successful run appends a timestamped Markdown block (preset
+//! name, result, retranslations) to a file the user keeps, like a running notes.md. A
+//! run's block is written when the last holder of its `RunNote` drops it, so it waits
+//! for the retranslation windows too. Appends of runs finishing together take turns.
+//! The finished run is also what `Preset.webhook` sends.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::config::{Preset, WebhookConfig};
+
+/// Serializes appends from rapid captures (one process writes the file)
+static APPEND_LOCK: Mutex<()> = Mutex::new(());
+
+/// What one run adds to the notes file and sends to the webhook
+pub struct RunNote {
+    path: Option<PathBuf>,
+    webhook: Option<WebhookConfig>,
+    preset_name: String,
+    result: Mutex<Option<String>>, // Unset if the run failed: nothing is written
+    retranslations: Mutex<Vec<String>>,
+}
+
+impl RunNote {
+    /// None unless the preset writes to a notes file or has a webhook
+    pub fn for_preset(preset: &Preset) -> Option<Arc<RunNote>> {
+        let path = preset.output_file.clone().filter(|p| !p.as_os_str().is_empty());
+        let webhook = Some(preset.webhook.clone()).filter(|w| !w.url.trim().is_empty());
+        if path.is_none() && webhook.is_none() {
+            return None;
+        }
+        Some(Arc::new(RunNote {
+            path,
+            webhook,
+            preset_name: preset.name.clone(),
+            result: Mutex::new(None),
+            retranslations: Mutex::new(Vec::new()),
+        }))
+    }
+
+    /// An empty note for another run of the same preset (a retried request)
+    pub fn again(&self) -> Arc<RunNote> {
+        Arc::new(RunNote {
+            path: self.path.clone(),
+            webhook: self.webhook.clone(),
+            preset_name: self.preset_name.clone(),
+            result: Mutex::new(None),
+            retranslations: Mutex::new(Vec::new()),
+        })
+    }
+
+    pub fn set_result(&self, text: &str) {
+        if !text.trim().is_empty() {
+            *self.result.lock().unwrap() = Some(text.to_string());
+        }
+    }
+
+    pub fn add_retranslation(&self, text: &str) {
+        if !text.trim().is_empty() {
+            self.retranslations.lock().unwrap().push(text.to_string());
+        }
+    }
+}
+
+impl Drop for RunNote {
+    fn drop(&mut self) {
+        let Some(result) = self.result.get_mut().ok().and_then(|r| r.take()) else { return };
+        let retranslations = self.retranslations.get_mut().map(std::mem::take).unwrap_or_default();
+        if let Some(path) = &self.path {
+            let time = chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
+            let block = format_block(&time, &self.preset_name, &result, &retranslations);
+            if let Err(e) = append(path, &block) {
+                log::error!("Notes file: failed to append to {} - {}", path.display(), e);
+            }
+        }
+        if let Some(webhook) = self.webhook.take() {
+            let retranslation = retranslations.join("\n\n");
+            crate::webhook::post(webhook, &crate::webhook::Payload::now(&self.preset_name, &result, &retranslation), false);
+        }
+    }
+}
+
+/// A heading with the time and preset, the result, then each retranslation as a quote
+fn format_block(time: &str, preset_name: &str, result: &str, retranslations: &[String]) -> String {
+    let mut block = format!("## {} · {}\n\n{}\n", time, preset_name, result.trim());
+    for text in retranslations {
+        let quoted: Vec<String> = text.trim().lines().map(|line| format!("> {}", line).trim_end().to_string()).collect();
+        block.push_str(&format!("\n{}\n", quoted.join("\n")));
+    }
+    block
+}
+
+fn append(path: &Path, block: &str) -> std::io::Result<()> {
+    let _guard = APPEND_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut file = std::fs::OpenOptions::new().create(true).append(true).open(path)?;
+    // A blank line between blocks (none before the first)
+    let separator = if file.metadata()?.len() > 0 { "\n" } else { "" };
+    file.write_all(format!("{}{}", separator, block).as_bytes())
+}
+
+/// Native "Save as" dialog for the notes file (an existing file is appended to, not
+/// replaced). Blocks, so call it off the UI thread.
+pub fn pick_notes_file(current: Option<&Path>) -> Option<PathBuf> {
+    use windows::Win32::UI::Controls::Dialogs::*;
+
+    let filter: Vec<u16> = "Markdown (*.md)\0*.md\0Text (*.txt)\0*.txt\0All files (*.*)\0*.*\0\0".encode_utf16().collect();
+    let default_ext: Vec<u16> = "md\0".encode_utf16().collect();
+    let mut file_buf = vec![0u16; 1024];
+    let initial = current.map_or_else(|| "notes.md".to_string(), |p| p.display().to_string());
+    for (slot, unit) in file_buf.iter_mut().zip(initial.encode_utf16().take(1023)) {
+        *slot = unit;
+    }
+
+    let mut ofn = OPENFILENAMEW {
+        lStructSize: std::mem::size_of::<OPENFILENAMEW>() as u32,
+        lpstrFilter: windows::core::PCWSTR(filter.as_ptr()),
+        lpstrFile: windows::core::PWSTR(file_buf.as_mut_ptr()),
+        nMaxFile: file_buf.len() as u32,
+        lpstrDefExt: windows::core::PCWSTR(default_ext.as_ptr()),
+        Flags: OFN_EXPLORER | OFN_NOCHANGEDIR | OFN_PATHMUSTEXIST,
+        ..Default::default()
+    };
+
+    if unsafe { GetSaveFileNameW(&mut ofn) }.as_bool() {
+        let len = file_buf.iter().position(|&c| c == 0).unwrap_or(file_buf.len());
+        Some(PathBuf::from(String::from_utf16_lossy(&file_buf[..len])))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_block() {
+        assert_eq!(
+            format_block("2026-10-15 09:30:00", "Translate", " Hello\n", &["Xin chào\n\nbạn".to_string()]),
+            "## 2026-10-15 09:30:00 · Translate\n\nHello\n\n> Xin chào\n>\n> bạn\n"
+        );
+        assert_eq!(format_block("t", "OCR", "text", &[]), "## t · OCR\n\ntext\n");
+    }
+
+    #[test]
+    fn test_append() {
+        let dir = std::env::temp_dir().join(format!("xst_notes_test_{}", std::process::id()));
+        let path = dir.join("sub").join("notes.md");
+        let _ = std::fs::remove_dir_all(&dir);
+
+        append(&path, "## a\n").unwrap();
+        append(&path, "## b\n").unwrap();
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "## a\n\n## b\n");
+
+        // A failed run writes nothing
+        let preset = Preset { output_file: Some(path.clone()), ..Default::default() };
+        let failed = RunNote::for_preset(&preset).unwrap();
+        // Its retry succeeds and is written on its own
+        let retried = failed.again();
+        drop(failed);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "## a\n\n## b\n");
+        retried.set_result("Hello");
+        drop(retried);
+        assert!(std::fs::read_to_string(&path).unwrap().ends_with("\n\nHello\n"));
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
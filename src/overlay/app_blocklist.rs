@@ -0,0 +1,213 @@
+//! App Blocklist Module
+//!
+//! `Config.blocked_apps` lists process names (e.g. "KeePassXC.exe") that must
+//! never be captured. Checked before a capture starts and on every live vision
+//! frame; a blocked capture shows a short toast instead (`show_toast`, also used for
+//! other short notices). Also resolves the foreground
+//! app for other per-app behaviour (`app_language`).
+
+use windows::Win32::Foundation::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::Win32::Graphics::Gdi::*;
+use windows::Win32::System::LibraryLoader::*;
+use windows::Win32::System::Threading::*;
+use windows::core::*;
+use std::sync::{Mutex, Once};
+
+use crate::APP;
+
+const TOAST_WIDTH: i32 = 380;
+const TOAST_HEIGHT: i32 = 44;
+const TOAST_MS: u32 = 1800;
+const TOAST_TIMER_ID: usize = 1;
+
+static REGISTER_TOAST_CLASS: Once = Once::new();
+
+lazy_static::lazy_static! {
+    static ref TOAST_TEXT: Mutex<String> = Mutex::new(String::new());
+}
+
+/// "C:\\Apps\\KeePassXC.exe" / " keepassxc " -> "keepassxc.exe"
+pub fn normalize_process_name(name: &str) -> String {
+    let file = name.trim().rsplit(['\\', '/']).next().unwrap_or("").to_lowercase();
+    if file.is_empty() || file.ends_with(".exe") {
+        file
+    } else {
+        format!("{}.exe", file)
+    }
+}
+
+pub fn is_blocked_process(process: &str, blocked: &[String]) -> bool {
+    let process = normalize_process_name(process);
+    !process.is_empty() && blocked.iter().any(|b| normalize_process_name(b) == process)
+}
+
+/// Executable name of the process owning `hwnd`
+fn window_process_name(hwnd: HWND) -> Option<String> {
+    unsafe {
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if pid == 0 {
+            return None;
+        }
+        let process = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+        let mut buf = [0u16; 260];
+        let mut len = buf.len() as u32;
+        let ok = QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, PWSTR(buf.as_mut_ptr()), &mut len).as_bool();
+        CloseHandle(process);
+        if !ok {
+            return None;
+        }
+        Some(normalize_process_name(&String::from_utf16_lossy(&buf[..len as usize])))
+    }
+}
+
+/// Executable name of the foreground app. When one of our own windows is in front
+/// (settings, a result window, the selection overlay), the first visible window of
+/// another process beneath it in the Z-order counts instead.
+pub fn foreground_app() -> Option<String> {
+    foreground_window().and_then(window_process_name)
+}
+
+/// The window `foreground_app` names
+pub fn foreground_window() -> Option<HWND> {
+    unsafe {
+        let own_pid = GetCurrentProcessId();
+        let mut hwnd = GetForegroundWindow();
+        while hwnd.0 != 0 {
+            let mut pid = 0u32;
+            GetWindowThreadProcessId(hwnd, Some(&mut pid));
+            if pid != own_pid && IsWindowVisible(hwnd).as_bool() && !IsIconic(hwnd).as_bool() {
+                return Some(hwnd);
+            }
+            hwnd = GetWindow(hwnd, GW_HWNDNEXT);
+        }
+        None
+    }
+}
+
+/// Name of the foreground app if it's on the blocklist
+pub fn blocked_foreground_app() -> Option<String> {
+    let blocked = APP.lock().ok()?.config.blocked_apps.clone();
+    if blocked.is_empty() {
+        return None;
+    }
+    let process = window_process_name(unsafe { GetForegroundWindow() })?;
+    is_blocked_process(&process, &blocked).then_some(process)
+}
+
+/// Like `blocked_foreground_app`, but only when the foreground window is on the
+/// monitor showing `region` (live vision keeps running while the app is elsewhere)
+pub fn blocked_app_over_region(region: RECT) -> Option<String> {
+    let app = blocked_foreground_app()?;
+    unsafe {
+        let fg_monitor = MonitorFromWindow(GetForegroundWindow(), MONITOR_DEFAULTTONEAREST);
+        let region_monitor = MonitorFromRect(&region, MONITOR_DEFAULTTONEAREST);
+        (fg_monitor == region_monitor).then_some(app)
+    }
+}
+
+fn blocked_message(ui_language: &str, app: &str) -> String {
+    match ui_language {
+        "vi" => format!("Đã chặn chụp màn hình cho {}", app),
+        "ko" => format!("{}에서는 캡처가 차단되었습니다", app),
+        _ => format!("Capture blocked for {}", app),
+    }
+}
+
+/// Short "capture blocked" notice at the top of the foreground monitor; returns at once
+pub fn show_blocked_toast(app: &str) {
+    let ui_language = APP.lock().map(|a| a.config.ui_language.clone()).unwrap_or_default();
+    show_toast(format!("⛔ {}", blocked_message(&ui_language, app)));
+}
+
+/// Short notice at the top of the foreground monitor; returns at once
+pub fn show_toast(text: String) {
+    *TOAST_TEXT.lock().unwrap() = text;
+    std::thread::spawn(|| unsafe {
+        let instance = GetModuleHandleW(None).unwrap();
+        let class_name = w!("SGTBlockedToast");
+        REGISTER_TOAST_CLASS.call_once(|| {
+            let mut wc = WNDCLASSW::default();
+            wc.lpfnWndProc = Some(toast_wnd_proc);
+            wc.hInstance = instance;
+            wc.lpszClassName = class_name;
+            let _ = RegisterClassW(&wc);
+        });
+
+        let monitor = MonitorFromWindow(GetForegroundWindow(), MONITOR_DEFAULTTOPRIMARY);
+        let mut info = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+        GetMonitorInfoW(monitor, &mut info);
+        let work = info.rcWork;
+        let x = work.left + (work.right - work.left - TOAST_WIDTH) / 2;
+        let y = work.top + 40;
+
+        let hwnd = CreateWindowExW(
+            WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE | WS_EX_TRANSPARENT,
+            class_name,
+            w!("SGT Toast"),
+            WS_POPUP,
+            x, y, TOAST_WIDTH, TOAST_HEIGHT,
+            None, None, instance, None
+        );
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), 235, LWA_ALPHA);
+        let region = CreateRoundRectRgn(0, 0, TOAST_WIDTH, TOAST_HEIGHT, 12, 12);
+        SetWindowRgn(hwnd, region, true);
+        SetTimer(hwnd, TOAST_TIMER_ID, TOAST_MS, None);
+        ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).into() {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+            if !IsWindow(hwnd).as_bool() { break; }
+        }
+    });
+}
+
+unsafe extern "system" fn toast_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_TIMER => {
+            KillTimer(hwnd, TOAST_TIMER_ID);
+            DestroyWindow(hwnd);
+            LRESULT(0)
+        }
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let mut rect = RECT { left: 0, top: 0, right: TOAST_WIDTH, bottom: TOAST_HEIGHT };
+
+            let bg = CreateSolidBrush(COLORREF(0x00202020));
+            FillRect(hdc, &rect, bg);
+            DeleteObject(bg);
+
+            let hfont = CreateFontW(17, 0, 0, 0, FW_SEMIBOLD.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+            let old_font = SelectObject(hdc, hfont);
+            SetBkMode(hdc, TRANSPARENT);
+            SetTextColor(hdc, COLORREF(0x006060F0)); // Red-ish (BGR)
+            let mut text: Vec<u16> = TOAST_TEXT.lock().unwrap().encode_utf16().collect();
+            DrawTextW(hdc, &mut text, &mut rect, DT_CENTER | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS);
+            SelectObject(hdc, old_font);
+            DeleteObject(hfont);
+
+            EndPaint(hwnd, &mut ps);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_blocked_process() {
+        let blocked = vec!["KeePassXC.exe".to_string(), " 1Password ".to_string()];
+        assert!(is_blocked_process("C:\\Program Files\\KeePassXC\\keepassxc.exe", &blocked));
+        assert!(is_blocked_process("1password.exe", &blocked));
+        assert!(!is_blocked_process("notepad.exe", &blocked));
+        assert!(!is_blocked_process("", &["".to_string()]));
+        assert_eq!(normalize_process_name("D:/tools/Bitwarden"), "bitwarden.exe");
+    }
+}
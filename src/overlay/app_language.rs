@@ -0,0 +1,78 @@
+//! Per-App Language Memory
+//!
+//! Presets with `per_app_language_memory` remember which `retranslate_to` was used in
+//! each foreground app (`Config.app_language_memory`, keyed by preset id) and default
+//! to it the next time they run there. The app is resolved when the hotkey fires,
+//! before any overlay of ours takes the foreground; the run paths then apply the
+//! language to their preset snapshot with `apply`.
+
+use std::sync::Mutex;
+
+use crate::config::{save_config, Preset};
+use crate::APP;
+
+lazy_static::lazy_static! {
+    // (preset index, language) resolved for the run in progress
+    static ref RUN_LANGUAGE: Mutex<Option<(usize, String)>> = Mutex::new(None);
+}
+
+/// A hotkey fired preset `idx`: resolve (and record) its language for the foreground app
+pub fn on_preset_hotkey(idx: usize) {
+    on_preset_picked(idx, super::app_blocklist::foreground_app());
+}
+
+/// Same as `on_preset_hotkey`, for a preset chosen after the app was looked up (the
+/// preset picker takes the foreground while it is open)
+pub fn on_preset_picked(idx: usize, foreground_app: Option<String>) {
+    let mut run = None;
+    let enabled = APP.lock().map_or(false, |app| app.config.presets.get(idx).map_or(false, |p| p.per_app_language_memory));
+    if enabled {
+        if let Some(process) = foreground_app {
+            if let Ok(mut app) = APP.lock() {
+                if let Some(preset) = app.config.presets.get(idx) {
+                    let (id, setting) = (preset.id.clone(), preset.retranslate_to.clone());
+                    let memory = app.config.app_language_memory.entry(id).or_default();
+                    let before = memory.clone();
+                    let language = memory.resolve(&process, &setting);
+                    let changed = *memory != before;
+                    if language != setting {
+                        log::info!("Using {} for {} (remembered)", language, process);
+                    }
+                    // Most runs change nothing; the others shouldn't hold up the capture with disk I/O
+                    if changed {
+                        std::thread::spawn(|| {
+                            if let Ok(app) = APP.lock() {
+                                save_config(&app.config);
+                            }
+                        });
+                    }
+                    run = Some((idx, language));
+                }
+            }
+        }
+    }
+    *RUN_LANGUAGE.lock().unwrap() = run;
+}
+
+/// Use the language resolved for this run on the snapshot of preset `idx`. Taken, so a
+/// later run that skipped the hotkey lookup doesn't pick it up.
+pub fn apply(preset: &mut Preset, idx: usize) {
+    let mut run = RUN_LANGUAGE.lock().unwrap();
+    if let Some((_, language)) = run.take_if(|(run_idx, _)| *run_idx == idx) {
+        preset.retranslate_to = language;
+    }
+}
+
+/// Drop everything remembered for the preset with `preset_id`
+pub fn forget(preset_id: &str) {
+    if let Ok(mut app) = APP.lock() {
+        if app.config.app_language_memory.remove(preset_id).is_some() {
+            save_config(&app.config);
+        }
+    }
+}
+
+/// Number of apps with a remembered language for the preset with `preset_id`
+pub fn remembered_count(preset_id: &str) -> usize {
+    APP.lock().map_or(0, |app| app.config.app_language_memory.get(preset_id).map_or(0, |m| m.apps.len()))
+}
@@ -0,0 +1,163 @@
+//! Busy Indicator
+//!
+//! Tiny spinner beside the mouse cursor while a request is waiting for its first chunk,
+//! so a preset with hidden result windows still shows that something is happening.
+//! Click-through and never activated. Each request holds a `BusyGuard`; the spinner
+//! goes away when the last guard is dropped, which also covers every error and
+//! cancel path.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::Win32::Graphics::Gdi::*;
+use windows::Win32::System::LibraryLoader::*;
+use windows::core::*;
+use std::sync::Once;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+/// Requests still waiting
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+static WINDOW_RUNNING: AtomicBool = AtomicBool::new(false);
+
+// --- UI CONSTANTS ---
+const SIZE: i32 = 20;
+const CURSOR_OFFSET: i32 = 18; // Below and right of the hotspot, clear of the arrow
+const DOTS: usize = 8;
+const FRAME_MS: u32 = 60;
+const TIMER_ID: usize = 1;
+
+static REGISTER_BUSY_CLASS: Once = Once::new();
+static mut FRAME: usize = 0;
+
+/// Keeps the spinner up while alive
+pub struct BusyGuard(());
+
+impl Drop for BusyGuard {
+    fn drop(&mut self) {
+        ACTIVE.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Show the spinner (if it isn't already) until the returned guard is dropped
+pub fn start() -> BusyGuard {
+    ACTIVE.fetch_add(1, Ordering::SeqCst);
+    if !WINDOW_RUNNING.swap(true, Ordering::SeqCst) {
+        std::thread::spawn(run_window);
+    }
+    BusyGuard(())
+}
+
+fn run_window() {
+    loop {
+        unsafe { run_window_once(); }
+        WINDOW_RUNNING.store(false, Ordering::SeqCst);
+        // A request that started while the window was closing found it still running
+        if ACTIVE.load(Ordering::SeqCst) == 0 || WINDOW_RUNNING.swap(true, Ordering::SeqCst) {
+            break;
+        }
+    }
+}
+
+unsafe fn run_window_once() {
+    let instance = GetModuleHandleW(None).unwrap();
+    let class_name = w!("SGTBusyIndicator");
+    REGISTER_BUSY_CLASS.call_once(|| {
+        let mut wc = WNDCLASSW::default();
+        wc.lpfnWndProc = Some(busy_wnd_proc);
+        wc.hInstance = instance;
+        wc.lpszClassName = class_name;
+        let _ = RegisterClassW(&wc);
+    });
+
+    let (x, y) = spinner_position();
+    let hwnd = CreateWindowExW(
+        WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE | WS_EX_TRANSPARENT,
+        class_name,
+        w!("SGT Busy"),
+        WS_POPUP,
+        x, y, SIZE, SIZE,
+        None, None, instance, None
+    );
+    SetLayeredWindowAttributes(hwnd, COLORREF(0), 220, LWA_ALPHA);
+    crate::capture::exclude_from_capture(hwnd);
+    SetWindowRgn(hwnd, CreateEllipticRgn(0, 0, SIZE + 1, SIZE + 1), true);
+    SetTimer(hwnd, TIMER_ID, FRAME_MS, None);
+    ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+
+    let mut msg = MSG::default();
+    while GetMessageW(&mut msg, None, 0, 0).into() {
+        TranslateMessage(&msg);
+        DispatchMessageW(&msg);
+    }
+}
+
+fn spinner_position() -> (i32, i32) {
+    let mut pt = POINT::default();
+    unsafe { GetCursorPos(&mut pt); }
+    (pt.x + CURSOR_OFFSET, pt.y + CURSOR_OFFSET)
+}
+
+/// Centre of dot `i` of the ring (clockwise from the top), in window coordinates
+fn dot_center(i: usize) -> (i32, i32) {
+    let angle = i as f64 / DOTS as f64 * std::f64::consts::TAU;
+    let radius = (SIZE / 2 - 4) as f64;
+    let c = (SIZE / 2) as f64;
+    ((c + radius * angle.sin()).round() as i32, (c - radius * angle.cos()).round() as i32)
+}
+
+unsafe extern "system" fn busy_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_TIMER => {
+            if ACTIVE.load(Ordering::SeqCst) == 0 {
+                DestroyWindow(hwnd);
+                return LRESULT(0);
+            }
+            FRAME = (FRAME + 1) % DOTS;
+            let (x, y) = spinner_position();
+            SetWindowPos(hwnd, HWND_TOPMOST, x, y, 0, 0, SWP_NOSIZE | SWP_NOACTIVATE);
+            InvalidateRect(hwnd, None, false);
+            LRESULT(0)
+        }
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let client = RECT { left: 0, top: 0, right: SIZE, bottom: SIZE };
+            let bg = CreateSolidBrush(COLORREF(0x00202020));
+            FillRect(hdc, &client, bg);
+            DeleteObject(bg);
+
+            // The head dot is brightest, the ones behind it fade out
+            let old_pen = SelectObject(hdc, GetStockObject(NULL_PEN));
+            for i in 0..DOTS {
+                let age = (FRAME + DOTS - i) % DOTS;
+                let level = 0xF0u32.saturating_sub(age as u32 * 0x1C).max(0x40);
+                let brush = CreateSolidBrush(COLORREF(level | (level << 8) | (level << 16)));
+                let old_brush = SelectObject(hdc, brush);
+                let (cx, cy) = dot_center(i);
+                Ellipse(hdc, cx - 2, cy - 2, cx + 2, cy + 2);
+                SelectObject(hdc, old_brush);
+                DeleteObject(brush);
+            }
+            SelectObject(hdc, old_pen);
+            EndPaint(hwnd, &mut ps);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            KillTimer(hwnd, TIMER_ID);
+            PostQuitMessage(0);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dot_center() {
+        assert_eq!(dot_center(0), (10, 4)); // Top
+        assert_eq!(dot_center(DOTS / 4), (16, 10)); // Right
+        assert_eq!(dot_center(DOTS / 2), (10, 16)); // Bottom
+    }
+}
@@ -8,6 +8,7 @@ use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::System::LibraryLoader::*;
 use windows::Win32::UI::Input::KeyboardAndMouse::*;
+use windows::Win32::System::Threading::{AttachThreadInput, GetCurrentThreadId};
 use windows::core::*;
 use std::sync::{Mutex, atomic::{AtomicBool, Ordering}};
 
@@ -15,12 +16,13 @@ lazy_static::lazy_static! {
     static ref USER_INPUT: Mutex<Option<String>> = Mutex::new(None);
     static ref INPUT_DISMISSED: AtomicBool = AtomicBool::new(false);
     static ref EDIT_HWND: Mutex<HWND> = Mutex::new(HWND(0));
+    static ref COUNTER_HWND: Mutex<HWND> = Mutex::new(HWND(0));
 }
 
 // Layout constants
 const POPUP_WIDTH: i32 = 400;
-const POPUP_HEIGHT: i32 = 120;
-const EDIT_HEIGHT: i32 = 36;
+const POPUP_HEIGHT: i32 = 140;
+const EDIT_HEIGHT: i32 = 56;
 const BTN_WIDTH: i32 = 80;
 const BTN_HEIGHT: i32 = 32;
 const PADDING: i32 = 16;
@@ -28,6 +30,10 @@ const PADDING: i32 = 16;
 const ID_EDIT: u16 = 100;
 const ID_SEND_BTN: u16 = 101;
 const ID_CANCEL_BTN: u16 = 102;
+const ID_COUNTER: u16 = 103;
+
+/// Max question length (enforced by the edit control, shown in the counter)
+const MAX_QUESTION_CHARS: usize = 1000;
 
 /// Show chat input popup and return user's question, or None if cancelled
 pub fn show_chat_input_popup(selection_rect: RECT) -> Option<String> {
@@ -35,6 +41,7 @@ pub fn show_chat_input_popup(selection_rect: RECT) -> Option<String> {
     *USER_INPUT.lock().unwrap() = None;
     INPUT_DISMISSED.store(false, Ordering::SeqCst);
     *EDIT_HWND.lock().unwrap() = HWND(0);
+    *COUNTER_HWND.lock().unwrap() = HWND(0);
 
     // Calculate popup position (centered below selection)
     let selection_center_x = (selection_rect.left + selection_rect.right) / 2;
@@ -75,12 +82,13 @@ pub fn show_chat_input_popup(selection_rect: RECT) -> Option<String> {
             return None;
         }
 
-        // Create Edit control (text input)
+        // Create Edit control (text input). Multi-line so Shift+Enter can insert a newline;
+        // plain Enter is intercepted in the message loop below.
         let edit_hwnd = CreateWindowExW(
             WS_EX_CLIENTEDGE,
             w!("EDIT"),
             w!(""),
-            WS_CHILD | WS_VISIBLE | WINDOW_STYLE(0x0080), // ES_AUTOHSCROLL
+            WS_CHILD | WS_VISIBLE | WS_VSCROLL | WINDOW_STYLE(0x0004 | 0x0040), // ES_MULTILINE | ES_AUTOVSCROLL
             PADDING,
             PADDING,
             POPUP_WIDTH - PADDING * 2,
@@ -91,6 +99,25 @@ pub fn show_chat_input_popup(selection_rect: RECT) -> Option<String> {
             None,
         );
         *EDIT_HWND.lock().unwrap() = edit_hwnd;
+        SendMessageW(edit_hwnd, 0x00C5 /* EM_SETLIMITTEXT */, WPARAM(MAX_QUESTION_CHARS), LPARAM(0));
+
+        // Character counter (bottom-left, next to the buttons)
+        let counter_hwnd = CreateWindowExW(
+            WINDOW_EX_STYLE(0),
+            w!("STATIC"),
+            w!(""),
+            WS_CHILD | WS_VISIBLE,
+            PADDING,
+            PADDING + EDIT_HEIGHT + 12 + 8,
+            120,
+            20,
+            hwnd,
+            HMENU(ID_COUNTER as isize),
+            instance,
+            None,
+        );
+        *COUNTER_HWND.lock().unwrap() = counter_hwnd;
+        update_counter();
 
         // Create Send button
         let btn_y = PADDING + EDIT_HEIGHT + 12;
@@ -129,8 +156,7 @@ pub fn show_chat_input_popup(selection_rect: RECT) -> Option<String> {
 
         // Show window and set focus to edit
         ShowWindow(hwnd, SW_SHOW);
-        let _ = SetForegroundWindow(hwnd);
-        let _ = SetFocus(edit_hwnd);
+        focus_popup(hwnd, edit_hwnd);
         let _ = UpdateWindow(hwnd);
 
         // Message loop
@@ -140,9 +166,20 @@ pub fn show_chat_input_popup(selection_rect: RECT) -> Option<String> {
                 if msg.message == WM_QUIT {
                     break;
                 }
-                // Handle Enter key in edit control
-                if msg.message == WM_KEYDOWN && msg.wParam.0 == VK_RETURN.0 as usize {
-                    submit_input(hwnd);
+                // Keys go to the focused child, so handle them here before dispatch
+                if msg.message == WM_KEYDOWN {
+                    if msg.wParam.0 == VK_ESCAPE.0 as usize {
+                        cancel_input();
+                        continue;
+                    }
+                    // Enter submits, Shift+Enter falls through to the edit control (newline)
+                    if msg.wParam.0 == VK_RETURN.0 as usize && GetKeyState(VK_SHIFT.0 as i32) >= 0 {
+                        submit_input(hwnd);
+                        continue;
+                    }
+                }
+                // Swallow the WM_CHAR of a submitting Enter / Escape (the edit control would beep)
+                if msg.message == WM_CHAR && (msg.wParam.0 == 0x1B || (msg.wParam.0 == 0x0D && GetKeyState(VK_SHIFT.0 as i32) >= 0)) {
                     continue;
                 }
                 TranslateMessage(&msg);
@@ -160,25 +197,56 @@ pub fn show_chat_input_popup(selection_rect: RECT) -> Option<String> {
     USER_INPUT.lock().unwrap().take()
 }
 
-unsafe fn submit_input(hwnd: HWND) {
-    let edit_hwnd = *EDIT_HWND.lock().unwrap();
-    log::info!("submit_input called, edit_hwnd: {:?}", edit_hwnd.0);
-    
-    if edit_hwnd.0 != 0 {
-        let len = GetWindowTextLengthW(edit_hwnd) + 1;
-        let mut buf = vec![0u16; len as usize];
-        GetWindowTextW(edit_hwnd, &mut buf);
-        let text = String::from_utf16_lossy(&buf[..len as usize - 1]);
-        
-        log::info!("Chat input text: '{}' (len={})", text, text.len());
-        
-        if !text.trim().is_empty() {
-            *USER_INPUT.lock().unwrap() = Some(text.trim().to_string());
-            log::info!("Chat input accepted");
-        } else {
-            log::info!("Chat input was empty");
-        }
+/// The popup is opened from a worker thread right after the selection overlay closes,
+/// so borrow the foreground thread's input state to be allowed to take focus.
+unsafe fn focus_popup(hwnd: HWND, edit_hwnd: HWND) {
+    let fg_thread = GetWindowThreadProcessId(GetForegroundWindow(), None);
+    let this_thread = GetCurrentThreadId();
+    let attached = fg_thread != 0 && fg_thread != this_thread && AttachThreadInput(fg_thread, this_thread, true).as_bool();
+
+    let _ = BringWindowToTop(hwnd);
+    let _ = SetForegroundWindow(hwnd);
+    let _ = SetFocus(edit_hwnd);
+
+    if attached {
+        let _ = AttachThreadInput(fg_thread, this_thread, false);
     }
+}
+
+unsafe fn edit_text() -> String {
+    let edit_hwnd = *EDIT_HWND.lock().unwrap();
+    if edit_hwnd.0 == 0 { return String::new(); }
+
+    let len = GetWindowTextLengthW(edit_hwnd) + 1;
+    let mut buf = vec![0u16; len as usize];
+    let copied = GetWindowTextW(edit_hwnd, &mut buf).max(0) as usize;
+    String::from_utf16_lossy(&buf[..copied])
+}
+
+unsafe fn update_counter() {
+    let counter_hwnd = *COUNTER_HWND.lock().unwrap();
+    if counter_hwnd.0 == 0 { return; }
+
+    let label = format!("{}/{}", edit_text().chars().count(), MAX_QUESTION_CHARS);
+    let wide: Vec<u16> = label.encode_utf16().chain(std::iter::once(0)).collect();
+    let _ = SetWindowTextW(counter_hwnd, PCWSTR(wide.as_ptr()));
+}
+
+/// Trimmed question with Windows line endings normalized, or None if blank
+fn normalize_question(raw: &str) -> Option<String> {
+    let text = raw.replace("\r\n", "\n");
+    let text = text.trim();
+    if text.is_empty() { None } else { Some(text.to_string()) }
+}
+
+unsafe fn submit_input(_hwnd: HWND) {
+    let question = normalize_question(&edit_text());
+    log::info!("Chat input submitted ({} chars)", question.as_ref().map_or(0, |q| q.chars().count()));
+
+    // Nothing to ask yet: keep the popup open
+    if question.is_none() { return; }
+
+    *USER_INPUT.lock().unwrap() = question;
     INPUT_DISMISSED.store(true, Ordering::SeqCst);
 }
 
@@ -198,6 +266,12 @@ unsafe extern "system" fn chat_input_wnd_proc(
             let id = (wparam.0 & 0xFFFF) as u16;
             let notification = ((wparam.0 >> 16) & 0xFFFF) as u16;
             
+            // EN_CHANGE: refresh the character counter
+            if id == ID_EDIT && notification == 0x0300 {
+                update_counter();
+                return LRESULT(0);
+            }
+
             // Button click (BN_CLICKED = 0)
             if notification == 0 {
                 match id {
@@ -227,6 +301,16 @@ unsafe extern "system" fn chat_input_wnd_proc(
             LRESULT(0)
         }
 
+        WM_CTLCOLORSTATIC => {
+            // Counter: gray on the popup background, orange near the limit
+            let hdc = HDC(wparam.0 as isize);
+            let near_limit = edit_text().chars().count() * 10 >= MAX_QUESTION_CHARS * 9;
+            let _ = SetTextColor(hdc, if near_limit { COLORREF(0x0060C0FF) } else { COLORREF(0x00999999) });
+            let _ = SetBkColor(hdc, COLORREF(0x00282828));
+            let _ = SetDCBrushColor(hdc, COLORREF(0x00282828));
+            LRESULT(GetStockObject(DC_BRUSH).0)
+        }
+
         WM_ACTIVATEAPP => {
             // Don't auto-dismiss - user must click Cancel or press Escape
             // This prevents the popup from closing when it first appears
@@ -255,3 +339,15 @@ unsafe extern "system" fn chat_input_wnd_proc(
         _ => DefWindowProcW(hwnd, msg, wparam, lparam),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_question() {
+        assert_eq!(normalize_question("  what is this?\r\n"), Some("what is this?".to_string()));
+        assert_eq!(normalize_question("line one\r\nline two"), Some("line one\nline two".to_string()));
+        assert_eq!(normalize_question(" \r\n\t "), None);
+    }
+}
@@ -0,0 +1,109 @@
+//! Completion Module
+//!
+//! `Preset.completion_sound` / `completion_toast`: a preset with hidden result windows
+//! gives no sign that it finished, so the old clipboard gets pasted by mistake. These
+//! play a system sound and/or show a toast ("Copied 213 characters") when it's done,
+//! and a different sound and message when it failed. Notices closer together than
+//! `MIN_INTERVAL` are dropped, so a live session can't spam them.
+
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use windows::Win32::Media::Audio::*;
+use windows::core::{w, PCWSTR};
+
+/// Shortest gap between two notices (of any preset)
+const MIN_INTERVAL: Duration = Duration::from_secs(3);
+
+static LAST_NOTICE: Mutex<Option<Instant>> = Mutex::new(None);
+
+/// How a hidden-overlay preset reports that it finished
+#[derive(Clone)]
+pub struct CompletionNotice {
+    sound: bool,
+    toast: bool,
+    copies: bool, // The result goes to the clipboard ("Copied ..." rather than "Done ...")
+    preset_name: String,
+    ui_language: String,
+}
+
+impl CompletionNotice {
+    /// None unless the preset hides its windows and has a notice turned on
+    pub fn for_preset(preset: &crate::config::Preset, ui_language: &str) -> Option<Self> {
+        if !preset.hide_overlay || !(preset.completion_sound || preset.completion_toast) {
+            return None;
+        }
+        Some(CompletionNotice {
+            sound: preset.completion_sound,
+            toast: preset.completion_toast,
+            copies: preset.auto_copy && !preset.live_mode && preset.output_mode == crate::config::OutputMode::Clipboard,
+            preset_name: preset.name.clone(),
+            ui_language: ui_language.to_string(),
+        })
+    }
+
+    pub fn success(&self, text: &str) {
+        if !take_turn(&mut LAST_NOTICE.lock().unwrap(), Instant::now()) {
+            return;
+        }
+        if self.sound {
+            play(w!("SystemAsterisk"));
+        }
+        if self.toast {
+            let chars = text.trim().chars().count();
+            super::app_blocklist::show_toast(success_message(&self.ui_language, &self.preset_name, chars, self.copies));
+        }
+    }
+
+    pub fn failure(&self, error: &anyhow::Error) {
+        if !take_turn(&mut LAST_NOTICE.lock().unwrap(), Instant::now()) {
+            return;
+        }
+        if self.sound {
+            play(w!("SystemHand"));
+        }
+        if self.toast {
+            let reason = super::utils::get_error_message(error, &self.ui_language);
+            super::app_blocklist::show_toast(format!("⚠ {}: {}", self.preset_name, reason));
+        }
+    }
+}
+
+/// Whether a notice may be shown at `now`; if so the next one waits `MIN_INTERVAL`
+fn take_turn(last: &mut Option<Instant>, now: Instant) -> bool {
+    if last.map_or(false, |at| now.duration_since(at) < MIN_INTERVAL) {
+        return false;
+    }
+    *last = Some(now);
+    true
+}
+
+/// A Windows sound event ("SystemAsterisk"), played without waiting for it
+fn play(alias: PCWSTR) {
+    unsafe { PlaySoundW(alias, None, SND_ALIAS | SND_ASYNC | SND_NODEFAULT | SND_SYSTEM); }
+}
+
+fn success_message(lang: &str, preset_name: &str, chars: usize, copied: bool) -> String {
+    match (lang, copied) {
+        ("vi", true) => format!("✓ {}: Đã copy {} ký tự", preset_name, chars),
+        ("vi", false) => format!("✓ {}: Xong ({} ký tự)", preset_name, chars),
+        ("ko", true) => format!("✓ {}: {}자 복사됨", preset_name, chars),
+        ("ko", false) => format!("✓ {}: 완료 ({}자)", preset_name, chars),
+        (_, true) => format!("✓ {}: Copied {} characters", preset_name, chars),
+        (_, false) => format!("✓ {}: Done ({} characters)", preset_name, chars),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_turn() {
+        let start = Instant::now();
+        let mut last = None;
+        assert!(take_turn(&mut last, start));
+        assert!(!take_turn(&mut last, start + Duration::from_secs(1)));
+        assert!(take_turn(&mut last, start + MIN_INTERVAL));
+        assert_eq!(success_message("en", "OCR", 213, true), "✓ OCR: Copied 213 characters");
+    }
+}
@@ -0,0 +1,163 @@
+//! Follow Window Module
+//!
+//! `Preset.live_follow_window`: Live Vision's region is kept relative to an app window
+//! (a video player) instead of fixed on the screen, so moving or resizing the player
+//! doesn't leave the crop on stale pixels. The window is picked when the session
+//! starts, by part of its title or its class name, and its rect is read again before
+//! every capture.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::System::Threading::GetCurrentProcessId;
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+/// The region as fractions of the window's rect, so it scales when the window is resized
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct RelativeRegion {
+    left: f64,
+    top: f64,
+    right: f64,
+    bottom: f64,
+}
+
+impl RelativeRegion {
+    fn of(region: RECT, window: RECT) -> Self {
+        let w = (window.right - window.left).max(1) as f64;
+        let h = (window.bottom - window.top).max(1) as f64;
+        RelativeRegion {
+            left: (region.left - window.left) as f64 / w,
+            top: (region.top - window.top) as f64 / h,
+            right: (region.right - window.left) as f64 / w,
+            bottom: (region.bottom - window.top) as f64 / h,
+        }
+    }
+
+    fn within(&self, window: RECT) -> RECT {
+        let w = (window.right - window.left) as f64;
+        let h = (window.bottom - window.top) as f64;
+        RECT {
+            left: window.left + (self.left * w).round() as i32,
+            top: window.top + (self.top * h).round() as i32,
+            right: window.left + (self.right * w).round() as i32,
+            bottom: window.top + (self.bottom * h).round() as i32,
+        }
+    }
+}
+
+/// Where the followed window puts the region now
+pub enum Follow {
+    Region(RECT),
+    Minimized, // Nothing to capture until it's restored
+    Closed,
+}
+
+pub struct FollowedWindow {
+    hwnd: HWND,
+    relative: RelativeRegion,
+    pub title: String,
+}
+
+impl FollowedWindow {
+    /// The topmost visible window of another app that covers the middle of `region`
+    /// and matches `pattern`: part of its title or its exact class name, either case.
+    /// An empty pattern takes whatever window is there.
+    pub fn find(pattern: &str, region: RECT) -> Option<Self> {
+        let center = POINT { x: (region.left + region.right) / 2, y: (region.top + region.bottom) / 2 };
+        let pattern = pattern.trim().to_lowercase();
+        let hwnd = top_level_windows().into_iter().find(|&hwnd| {
+            let rect = window_rect(hwnd);
+            let covers = center.x >= rect.left && center.x < rect.right && center.y >= rect.top && center.y < rect.bottom;
+            covers && (pattern.is_empty()
+                || window_title(hwnd).to_lowercase().contains(&pattern)
+                || window_class(hwnd).to_lowercase() == pattern)
+        })?;
+        Some(FollowedWindow { hwnd, relative: RelativeRegion::of(region, window_rect(hwnd)), title: window_title(hwnd) })
+    }
+
+    pub fn region(&self) -> Follow {
+        unsafe {
+            if !IsWindow(self.hwnd).as_bool() {
+                Follow::Closed
+            } else if IsIconic(self.hwnd).as_bool() {
+                Follow::Minimized
+            } else {
+                Follow::Region(self.relative.within(window_rect(self.hwnd)))
+            }
+        }
+    }
+
+    /// A region picked again from the control bar, kept relative to the window from now on
+    pub fn set_region(&mut self, region: RECT) {
+        self.relative = RelativeRegion::of(region, window_rect(self.hwnd));
+    }
+}
+
+/// Visible, titled top-level windows of other processes, front to back
+fn top_level_windows() -> Vec<HWND> {
+    unsafe extern "system" fn collect(hwnd: HWND, lparam: LPARAM) -> BOOL {
+        let windows = &mut *(lparam.0 as *mut Vec<HWND>);
+        let mut pid = 0u32;
+        GetWindowThreadProcessId(hwnd, Some(&mut pid));
+        if IsWindowVisible(hwnd).as_bool() && pid != GetCurrentProcessId() && GetWindowTextLengthW(hwnd) > 0 {
+            windows.push(hwnd);
+        }
+        TRUE
+    }
+    let mut windows: Vec<HWND> = Vec::new();
+    unsafe { EnumWindows(Some(collect), LPARAM(&mut windows as *mut Vec<HWND> as isize)); }
+    windows
+}
+
+fn window_rect(hwnd: HWND) -> RECT {
+    let mut rect = RECT::default();
+    unsafe { GetWindowRect(hwnd, &mut rect); }
+    rect
+}
+
+pub(super) fn window_title(hwnd: HWND) -> String {
+    let mut buf = [0u16; 256];
+    let len = unsafe { GetWindowTextW(hwnd, &mut buf) }.max(0) as usize;
+    String::from_utf16_lossy(&buf[..len])
+}
+
+fn window_class(hwnd: HWND) -> String {
+    let mut buf = [0u16; 256];
+    let len = unsafe { GetClassNameW(hwnd, &mut buf) }.max(0) as usize;
+    String::from_utf16_lossy(&buf[..len])
+}
+
+pub fn not_found_message(lang: &str, pattern: &str) -> String {
+    match lang {
+        "vi" => format!("Không tìm thấy cửa sổ \"{}\" tại vùng đã chọn, vùng sẽ đứng yên", pattern),
+        "ko" => format!("선택 영역에서 \"{}\" 창을 찾지 못해 영역이 고정됩니다", pattern),
+        _ => format!("No \"{}\" window under the region; it stays fixed", pattern),
+    }
+}
+
+pub fn closed_message(lang: &str, title: &str) -> String {
+    match lang {
+        "vi" => format!("Đã dừng Live: cửa sổ \"{}\" đã đóng", title),
+        "ko" => format!("Live 중지: \"{}\" 창이 닫혔습니다", title),
+        _ => format!("Live stopped: \"{}\" was closed", title),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_region() {
+        let window = RECT { left: 100, top: 50, right: 900, bottom: 650 };
+        let subtitles = RECT { left: 200, top: 500, right: 800, bottom: 600 };
+        let relative = RelativeRegion::of(subtitles, window);
+        assert_eq!(relative.within(window), subtitles);
+
+        // Moved: the region moves with it
+        let moved = RECT { left: -300, top: 250, right: 500, bottom: 850 };
+        assert_eq!(relative.within(moved), RECT { left: -200, top: 700, right: 400, bottom: 800 });
+
+        // Resized to twice the size: the region scales
+        let larger = RECT { left: 100, top: 50, right: 1700, bottom: 1250 };
+        assert_eq!(relative.within(larger), RECT { left: 300, top: 950, right: 1500, bottom: 1150 });
+    }
+}
@@ -0,0 +1,67 @@
+//! Target Language Cycling
+//!
+//! A preset with a `language_cycle` (two or more languages) and a `cycle_language_hotkey`
+//! switches its `{language1}` to the next language of the list on that hotkey, saves it
+//! and says so in a toast; the next capture uses it. The settings window copies the new
+//! language into its own config when `generation` moves.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use crate::config::{save_config, Preset};
+use crate::APP;
+
+pub const LANGUAGE_KEY: &str = "language1";
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Whether the cycle hotkey does anything for `preset`
+pub fn supports_cycle(preset: &Preset) -> bool {
+    preset.language_cycle.len() >= 2
+}
+
+/// The entry after `current`; the first one when `current` isn't in the list
+pub fn next_language(cycle: &[String], current: Option<&str>) -> Option<String> {
+    let next = match cycle.iter().position(|l| Some(l.as_str()) == current) {
+        Some(i) => (i + 1) % cycle.len(),
+        None => 0,
+    };
+    cycle.get(next).cloned()
+}
+
+/// Bumped on every switch, so the settings window knows to pick it up
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+/// Advance preset `idx` to its next language (hotkey)
+pub fn cycle(idx: usize) {
+    let (name, language) = {
+        let Ok(mut app) = APP.lock() else { return };
+        let Some(preset) = app.config.presets.get_mut(idx).filter(|p| supports_cycle(p)) else { return };
+        let current = preset.language_vars.get(LANGUAGE_KEY).map(String::as_str);
+        let Some(language) = next_language(&preset.language_cycle, current) else { return };
+        preset.language_vars.insert(LANGUAGE_KEY.to_string(), language.clone());
+        let name = preset.name.clone();
+        save_config(&app.config);
+        (name, language)
+    };
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+    log::info!("Preset '{}' now translates to {}", name, language);
+    super::app_blocklist::show_toast(format!("🌐 {}: {}", name, language));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_language() {
+        let cycle = vec!["Vietnamese".to_string(), "English".to_string(), "Korean".to_string()];
+        assert_eq!(next_language(&cycle, Some("Vietnamese")).as_deref(), Some("English"));
+        assert_eq!(next_language(&cycle, Some("Korean")).as_deref(), Some("Vietnamese"));
+        // Set to something else in the meantime: start over
+        assert_eq!(next_language(&cycle, Some("French")).as_deref(), Some("Vietnamese"));
+        assert_eq!(next_language(&cycle, None).as_deref(), Some("Vietnamese"));
+        assert_eq!(next_language(&[], Some("English")), None);
+    }
+}
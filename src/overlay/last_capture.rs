@@ -0,0 +1,91 @@
+//! Re-run Last Capture Module
+//!
+//! The crop of the last image capture is kept in `AppState::last_capture` so a preset's
+//! "re-run last capture" hotkey can send the same region through that preset without
+//! selecting it again. The image is dropped `Config.last_capture_keep_secs` after it was
+//! taken (0 = never kept), since it may show something private.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use image::{ImageBuffer, Rgba};
+use windows::Win32::Foundation::*;
+
+use crate::APP;
+
+pub struct LastCapture {
+    pub image: ImageBuffer<Rgba<u8>, Vec<u8>>,
+    pub rect: RECT, // Where it was on screen; the result windows open there again
+    seq: u64,
+}
+
+/// Identifies a stored capture, so an expiry timer doesn't clear a newer one
+static NEXT_SEQ: AtomicU64 = AtomicU64::new(1);
+
+/// Presets whose hotkey opens the region selection (the only ones with a re-run key)
+pub fn supports_rerun(preset: &crate::config::Preset) -> bool {
+    matches!(preset.preset_type.as_str(), "image" | "chat" | "screenshot")
+        && !preset.live_mode
+        && preset.capture_mode != super::replace_selection::CAPTURE_MODE
+}
+
+/// Keep `image` (the crop just processed) for re-runs, and schedule its removal
+pub fn remember(image: &ImageBuffer<Rgba<u8>, Vec<u8>>, rect: RECT) {
+    let seq = NEXT_SEQ.fetch_add(1, Ordering::Relaxed);
+    let keep_secs = {
+        let mut app = APP.lock().unwrap();
+        let keep_secs = app.config.last_capture_keep_secs;
+        app.last_capture = (keep_secs > 0).then(|| LastCapture { image: image.clone(), rect, seq });
+        keep_secs
+    };
+    if keep_secs > 0 {
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_secs(keep_secs as u64));
+            let mut app = APP.lock().unwrap();
+            if app.last_capture.as_ref().map_or(false, |c| c.seq == seq) {
+                app.last_capture = None;
+                log::debug!("Last capture expired");
+            }
+        });
+    }
+}
+
+fn nothing_stored_message(ui_language: &str) -> &'static str {
+    match ui_language {
+        "vi" => "Chưa có vùng chụp nào để chạy lại",
+        "ko" => "다시 실행할 캡처가 없습니다",
+        _ => "No recent capture to re-run",
+    }
+}
+
+/// Process the stored capture with `preset_idx`, or show a toast if there is none.
+/// Blocks until the result windows are set up, so call it off the hotkey thread.
+pub fn rerun(preset_idx: usize) {
+    let (capture, ui_language) = {
+        let app = APP.lock().unwrap();
+        let capture = app.last_capture.as_ref().map(|c| (c.image.clone(), c.rect));
+        (capture, app.config.ui_language.clone())
+    };
+    match capture {
+        Some((image, rect)) => {
+            super::app_language::on_preset_hotkey(preset_idx);
+            super::process::process_capture(APP.clone(), rect, HWND(0), preset_idx, Some(image));
+        }
+        None => super::app_blocklist::show_toast(nothing_stored_message(&ui_language).to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Preset;
+
+    #[test]
+    fn test_supports_rerun() {
+        let preset = |preset_type: &str| Preset { preset_type: preset_type.to_string(), ..Default::default() };
+        assert!(supports_rerun(&preset("image")));
+        assert!(supports_rerun(&preset("chat")));
+        assert!(!supports_rerun(&preset("audio")));
+        assert!(!supports_rerun(&Preset { live_mode: true, ..preset("image") }));
+        assert!(!supports_rerun(&Preset { capture_mode: crate::overlay::replace_selection::CAPTURE_MODE.to_string(), ..preset("image") }));
+    }
+}
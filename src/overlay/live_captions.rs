@@ -5,8 +5,7 @@ use crate::config::LiveCaptionsConfig;
 use crate::api::translate_text_streaming;
 use crate::live_captions::{
     launch_live_captions, run_live_captions_loop, stop_live_captions,
-    hide_live_captions, show_live_captions, LIVE_CAPTIONS_ACTIVE, 
-    extract_latest_sentence,
+    hide_live_captions, show_live_captions, LIVE_CAPTIONS_ACTIVE, SentenceTracker,
 };
 use crate::gemini_live::GeminiLiveClient;
 use crate::audio_capture::AudioCapture;
@@ -85,6 +84,18 @@ pub fn is_live_captions_active() -> bool {
     LIVE_CAPTIONS_ACTIVE.load(Ordering::SeqCst)
 }
 
+/// Start or stop the overlay with the saved settings (hotkey / tray menu)
+pub fn toggle_live_captions_overlay() {
+    let overlay_open = OVERLAY_HWND.lock().map(|h| h.is_some()).unwrap_or(false);
+    if is_live_captions_active() || overlay_open {
+        stop_live_captions_overlay();
+    } else if let Ok(app) = APP.lock() {
+        let config = app.config.live_captions.clone();
+        drop(app);
+        start_live_captions_overlay(config);
+    }
+}
+
 /// Main thread for overlay window with proper message loop
 fn run_overlay_window_thread(config: LiveCaptionsConfig) -> anyhow::Result<()> {
     // Check mode
@@ -126,6 +137,7 @@ fn run_overlay_window_thread(config: LiveCaptionsConfig) -> anyhow::Result<()> {
     if use_gemini_live {
         // --- GEMINI LIVE MODE ---
         std::thread::spawn(move || {
+            let _worker = crate::shutdown::worker(); // Owns the audio stream until the session stops
             crate::live_captions::LIVE_CAPTIONS_ACTIVE.store(true, Ordering::SeqCst);
             crate::live_captions::LIVE_CAPTIONS_STOP_SIGNAL.store(false, Ordering::SeqCst);
             
@@ -167,7 +179,7 @@ fn run_overlay_window_thread(config: LiveCaptionsConfig) -> anyhow::Result<()> {
             
             match GeminiLiveClient::new(gemini_key, Some(system_instruction), Box::new(on_text)) {
                 Ok(client) => {
-                     if let Err(e) = audio_capture.start(audio_source, move |data| client.send_audio(data)) {
+                     if let Err(e) = audio_capture.start(audio_source, None, move |data| client.send_audio(data)) {
                          log::error!("Audio capture failed: {}", e);
                      } else {
                          log::info!("Gemini Live audio streaming started");
@@ -185,55 +197,72 @@ fn run_overlay_window_thread(config: LiveCaptionsConfig) -> anyhow::Result<()> {
         
     } else {
         // --- ORIGINAL LIVE CAPTIONS MODE ---
+        let (tm_name, tm_provider) = match crate::model_config::get_model_by_id(&model) {
+            Some(m) => (m.full_name, m.provider),
+            None => ("openai/gpt-oss-20b".to_string(), "groq".to_string()),
+        };
+        
         std::thread::spawn(move || {
+            let mut tracker = SentenceTracker::new();
+            
             if let Err(e) = run_live_captions_loop(lc_hwnd, auto_hide, move |text| {
-                // Extract latest sentence
-                if let Some(sentence) = extract_latest_sentence(&text) {
-                    if sentence.trim().is_empty() {
-                        return;
-                    }
-                    
-                    log::info!("Live caption captured: {}", sentence);
+                // Only translate sentences that Live Captions has finished
+                let sentences = tracker.update(&text);
+                if sentences.is_empty() {
+                    return;
+                }
+                let sentence = sentences.join(" ");
+                log::info!("Live caption captured: {}", sentence);
+                
+                // Add the line first, then stream the translation into it
+                if let Ok(mut lines) = CAPTION_LINES.lock() {
+                    let max_lines = MAX_LINES.lock().map(|m| *m).unwrap_or(2);
                     
-                    // Translate in a blocking way
-                    let translated = match translate_text_streaming(
-                        &groq_key,
-                        &gemini_key,
-                        &openrouter_key,
-                        sentence.clone(),
-                        target_lang.clone(),
-                        model.clone(),
-                        "groq".to_string(), // Default provider for now, logic inside handles it
-                        false,
-                        false,
-                        |_| {},
-                    ) {
-                        Ok(t) => t,
-                        Err(e) => {
-                            log::error!("Translation error: {}", e);
-                            format!("[Error: {}]", e)
-                        }
-                    };
+                    lines.push_back(CaptionLine {
+                        original: if show_original { sentence.clone() } else { String::new() },
+                        translated: String::new(),
+                    });
                     
-                    // Add to caption lines
+                    // Keep only max_lines
+                    while lines.len() > max_lines {
+                        lines.pop_front();
+                    }
+                }
+                
+                let set_last_translation = |translated: &str| {
                     if let Ok(mut lines) = CAPTION_LINES.lock() {
-                        let max_lines = MAX_LINES.lock().map(|m| *m).unwrap_or(2);
-                        
-                        lines.push_back(CaptionLine {
-                            original: if show_original { sentence } else { String::new() },
-                            translated,
-                        });
-                        
-                        // Keep only max_lines
-                        while lines.len() > max_lines {
-                            lines.pop_front();
+                        if let Some(last) = lines.back_mut() {
+                            last.translated = translated.to_string();
                         }
                     }
-                    
-                    // Trigger redraw
                     unsafe {
                         let _ = PostMessageW(overlay_hwnd_for_capture, WM_USER + 1, WPARAM(0), LPARAM(0));
                     }
+                };
+                
+                let mut acc_text = String::new();
+                let result = translate_text_streaming(
+                    &groq_key,
+                    &gemini_key,
+                    &openrouter_key,
+                    sentence,
+                    target_lang.clone(),
+                    tm_name.clone(),
+                    tm_provider.clone(),
+                    true,
+                    false,
+                    |chunk| {
+                        acc_text.push_str(chunk);
+                        set_last_translation(&acc_text);
+                    },
+                );
+                
+                match result {
+                    Ok(t) => set_last_translation(&t),
+                    Err(e) => {
+                        log::error!("Translation error: {}", e);
+                        set_last_translation(&format!("[Error: {}]", e));
+                    }
                 }
             }) {
                 log::error!("Live Captions capture loop error: {}", e);
@@ -303,6 +332,7 @@ fn create_overlay_window() -> anyhow::Result<HWND> {
         
         // Set transparency
         let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), 230, LWA_ALPHA);
+        crate::capture::exclude_from_capture(hwnd);
         
         // Set rounded corners if available
         let _ = set_rounded_corners(hwnd);
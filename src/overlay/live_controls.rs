@@ -0,0 +1,175 @@
+//! Live Vision Control Bar
+//!
+//! Small draggable bar shown above the result window of a live vision session:
+//! pause/resume the capture loop and pick a new capture region without tearing
+//! the session down. Closes itself once the session (or its result window) ends.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::Win32::Graphics::Gdi::*;
+use windows::Win32::System::LibraryLoader::*;
+use windows::core::*;
+use std::sync::{atomic::Ordering, Once};
+
+use crate::api::{VISION_ACTIVE, VISION_PAUSED};
+
+static mut RESULT_HWND: HWND = HWND(0); // Session's primary result window
+static mut PRESET_IDX: usize = 0; // Session's preset, for the new region's selection overlay
+
+// --- UI CONSTANTS ---
+const BAR_WIDTH: i32 = 96;
+const BAR_HEIGHT: i32 = 34;
+const GRIP_WIDTH: i32 = 16; // Drag handle on the left; the rest of the background drags too
+const BTN_SIZE: i32 = 34;
+const CORNER_RADIUS: i32 = 10;
+const WATCH_TIMER_ID: usize = 1;
+
+static REGISTER_CONTROLS_CLASS: Once = Once::new();
+
+#[derive(Clone, Copy, PartialEq)]
+enum Button {
+    PauseResume,
+    ChangeRegion,
+}
+
+/// Create the bar above `anchor` (the result window rect) on the calling thread,
+/// which must pump messages. `preset_idx` is the session's preset.
+pub fn create_live_controls(anchor: RECT, result_hwnd: HWND, preset_idx: usize) -> HWND {
+    unsafe {
+        let instance = GetModuleHandleW(None).unwrap();
+        let class_name = w!("LiveVisionControls");
+
+        REGISTER_CONTROLS_CLASS.call_once(|| {
+            let mut wc = WNDCLASSW::default();
+            wc.lpfnWndProc = Some(controls_wnd_proc);
+            wc.hInstance = instance;
+            wc.hCursor = LoadCursorW(None, IDC_ARROW).unwrap();
+            wc.lpszClassName = class_name;
+            let _ = RegisterClassW(&wc);
+        });
+
+        let x = (anchor.right - BAR_WIDTH).max(0);
+        let y = (anchor.top - BAR_HEIGHT - 6).max(0);
+        let hwnd = CreateWindowExW(
+            WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+            class_name,
+            w!("SGT Live Controls"),
+            WS_POPUP,
+            x, y, BAR_WIDTH, BAR_HEIGHT,
+            None, None, instance, None
+        );
+
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), 230, LWA_ALPHA);
+        crate::capture::exclude_from_capture(hwnd);
+        let region = CreateRoundRectRgn(0, 0, BAR_WIDTH, BAR_HEIGHT, CORNER_RADIUS, CORNER_RADIUS);
+        SetWindowRgn(hwnd, region, true);
+
+        RESULT_HWND = result_hwnd;
+        PRESET_IDX = preset_idx;
+        SetTimer(hwnd, WATCH_TIMER_ID, 250, None);
+        ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+        hwnd
+    }
+}
+
+fn button_rect(button: Button) -> RECT {
+    let left = match button {
+        Button::PauseResume => GRIP_WIDTH,
+        Button::ChangeRegion => GRIP_WIDTH + BTN_SIZE + 4,
+    };
+    RECT { left, top: 0, right: left + BTN_SIZE, bottom: BAR_HEIGHT }
+}
+
+fn button_at(x: i32, y: i32) -> Option<Button> {
+    [Button::PauseResume, Button::ChangeRegion].into_iter().find(|b| {
+        let r = button_rect(*b);
+        x >= r.left && x < r.right && y >= r.top && y < r.bottom
+    })
+}
+
+unsafe extern "system" fn controls_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_NCHITTEST => {
+            // Buttons are clickable, everything else drags the bar
+            let mut pt = POINT { x: (lparam.0 & 0xFFFF) as i16 as i32, y: ((lparam.0 >> 16) & 0xFFFF) as i16 as i32 };
+            ScreenToClient(hwnd, &mut pt);
+            if button_at(pt.x, pt.y).is_some() {
+                LRESULT(HTCLIENT as isize)
+            } else {
+                LRESULT(HTCAPTION as isize)
+            }
+        }
+        WM_MOUSEACTIVATE => LRESULT(MA_NOACTIVATE as isize),
+        WM_LBUTTONUP => {
+            let x = (lparam.0 & 0xFFFF) as i16 as i32;
+            let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
+            match button_at(x, y) {
+                Some(Button::PauseResume) => {
+                    let paused = !VISION_PAUSED.load(Ordering::SeqCst);
+                    VISION_PAUSED.store(paused, Ordering::SeqCst);
+                    log::info!("Live Vision: {}", if paused { "Paused" } else { "Resumed" });
+                    InvalidateRect(hwnd, None, false);
+                }
+                Some(Button::ChangeRegion) => {
+                    let preset_idx = PRESET_IDX;
+                    std::thread::spawn(move || super::selection::pick_live_vision_region(preset_idx));
+                }
+                None => {}
+            }
+            LRESULT(0)
+        }
+        WM_TIMER => {
+            if !VISION_ACTIVE.load(Ordering::SeqCst) || !IsWindow(RESULT_HWND).as_bool() {
+                DestroyWindow(hwnd);
+            }
+            LRESULT(0)
+        }
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+
+            let bg = CreateSolidBrush(COLORREF(0x00202020));
+            FillRect(hdc, &RECT { left: 0, top: 0, right: BAR_WIDTH, bottom: BAR_HEIGHT }, bg);
+            DeleteObject(bg);
+
+            // Grip dots
+            let dot = CreateSolidBrush(COLORREF(0x00808080));
+            for row in 0..3 {
+                for col in 0..2 {
+                    let (x, y) = (5 + col * 5, 10 + row * 6);
+                    FillRect(hdc, &RECT { left: x, top: y, right: x + 2, bottom: y + 2 }, dot);
+                }
+            }
+            DeleteObject(dot);
+
+            let hfont = CreateFontW(18, 0, 0, 0, FW_NORMAL.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI Symbol"));
+            let old_font = SelectObject(hdc, hfont);
+            SetBkMode(hdc, TRANSPARENT);
+
+            let paused = VISION_PAUSED.load(Ordering::SeqCst);
+            let buttons = [
+                (Button::PauseResume, if paused { "▶" } else { "⏸" }, if paused { 0x0060D060 } else { 0x00F0F0F0 }),
+                (Button::ChangeRegion, "⛶", 0x00F0F0F0),
+            ];
+            for (button, glyph, color) in buttons {
+                let mut r = button_rect(button);
+                let btn_bg = CreateSolidBrush(COLORREF(0x00383838));
+                FillRect(hdc, &RECT { left: r.left + 2, top: r.top + 4, right: r.right - 2, bottom: r.bottom - 4 }, btn_bg);
+                DeleteObject(btn_bg);
+                SetTextColor(hdc, COLORREF(color));
+                let mut text: Vec<u16> = glyph.encode_utf16().collect();
+                DrawTextW(hdc, &mut text, &mut r, DT_CENTER | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX);
+            }
+
+            SelectObject(hdc, old_font);
+            DeleteObject(hfont);
+            EndPaint(hwnd, &mut ps);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            KillTimer(hwnd, WATCH_TIMER_ID);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
@@ -0,0 +1,139 @@
+//! Live Session Queue
+//!
+//! Bounded hand-off between a live capture loop and its processor thread. When the
+//! processor falls behind (slow API), pushing onto a full queue drops the OLDEST
+//! chunk/frame instead of growing, so memory stays flat however long the stall.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+
+/// Chunks/frames held at most; the capture loop never waits on the processor
+pub const LIVE_QUEUE_BOUND: usize = 3;
+
+struct Shared<T> {
+    items: Mutex<(VecDeque<T>, bool)>, // (queued items, sender gone)
+    ready: Condvar,
+    capacity: usize,
+    dropped: AtomicUsize,
+}
+
+/// Capture side. Dropping it closes the queue once the remaining items are taken.
+pub struct LiveSender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+/// Processor side
+pub struct LiveReceiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+pub fn live_queue<T>(capacity: usize) -> (LiveSender<T>, LiveReceiver<T>) {
+    let shared = Arc::new(Shared {
+        items: Mutex::new((VecDeque::with_capacity(capacity), false)),
+        ready: Condvar::new(),
+        capacity: capacity.max(1),
+        dropped: AtomicUsize::new(0),
+    });
+    (LiveSender { shared: shared.clone() }, LiveReceiver { shared })
+}
+
+impl<T> LiveSender<T> {
+    /// Queue `item`, evicting the oldest one when full. Returns true if something was dropped.
+    pub fn push(&self, item: T) -> bool {
+        let mut guard = self.shared.items.lock().unwrap();
+        let evicted = if guard.0.len() >= self.shared.capacity {
+            guard.0.pop_front();
+            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+            true
+        } else {
+            false
+        };
+        guard.0.push_back(item);
+        drop(guard);
+        self.shared.ready.notify_one();
+        evicted
+    }
+}
+
+impl<T> Drop for LiveSender<T> {
+    fn drop(&mut self) {
+        self.shared.items.lock().unwrap().1 = true;
+        self.shared.ready.notify_all();
+    }
+}
+
+impl<T> LiveReceiver<T> {
+    /// Block for the next item; None once the sender is gone and the queue is empty
+    pub fn recv(&self) -> Option<T> {
+        let mut guard = self.shared.items.lock().unwrap();
+        loop {
+            if let Some(item) = guard.0.pop_front() {
+                return Some(item);
+            }
+            if guard.1 {
+                return None;
+            }
+            guard = self.shared.ready.wait(guard).unwrap();
+        }
+    }
+
+    pub fn try_recv(&self) -> Option<T> {
+        self.shared.items.lock().unwrap().0.pop_front()
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.items.lock().unwrap().0.len()
+    }
+
+    /// Items evicted by `push` since the queue was created
+    pub fn dropped(&self) -> usize {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_slow_consumer_keeps_queue_bounded() {
+        let (tx, rx) = live_queue::<Vec<u8>>(LIVE_QUEUE_BOUND);
+        let consumer = std::thread::spawn(move || {
+            let mut received = Vec::new();
+            while let Some(chunk) = rx.recv() {
+                assert!(rx.len() < LIVE_QUEUE_BOUND);
+                received.push(chunk[0]);
+                std::thread::sleep(Duration::from_millis(5)); // Stalled API
+            }
+            (received, rx.dropped())
+        });
+
+        for i in 0..200u8 {
+            tx.push(vec![i; 64 * 1024]);
+        }
+        drop(tx);
+
+        let (received, dropped) = consumer.join().unwrap();
+        assert_eq!(received.len() + dropped, 200);
+        assert!(dropped > 0);
+        // Oldest are evicted: what arrives stays in order and ends with the newest chunk
+        assert!(received.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(received.last(), Some(&199));
+    }
+
+    #[test]
+    fn test_push_evicts_oldest() {
+        let (tx, rx) = live_queue(2);
+        assert!(!tx.push(1));
+        assert!(!tx.push(2));
+        assert!(tx.push(3));
+        assert_eq!(rx.try_recv(), Some(2));
+        assert_eq!(rx.try_recv(), Some(3));
+        assert_eq!(rx.try_recv(), None);
+        assert_eq!(rx.dropped(), 1);
+        drop(tx);
+        assert_eq!(rx.recv(), None);
+    }
+}
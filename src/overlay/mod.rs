@@ -8,7 +8,25 @@ pub mod paint_utils;
 pub mod live_captions;
 pub mod quick_actions;
 pub mod chat_input;
+pub mod replace_selection;
+pub mod live_controls;
+pub mod app_blocklist;
+pub mod app_language;
+pub mod preset_indicator;
+pub mod last_capture;
+pub mod preset_picker;
+pub mod recent_copies;
+pub mod language_cycle;
+pub mod type_out;
+pub mod follow_window;
+pub mod window_context;
+pub mod completion;
+pub mod busy_indicator;
+mod live_queue;
+mod reflow;
+pub mod study;
 
 pub use selection::{show_selection_overlay, is_selection_overlay_active_and_dismiss};
 pub use recording::{show_recording_overlay, is_recording_overlay_active, stop_recording_and_submit};
-pub use live_captions::{start_live_captions_overlay, stop_live_captions_overlay, is_live_captions_active};
+pub use live_captions::{start_live_captions_overlay, stop_live_captions_overlay, is_live_captions_active, toggle_live_captions_overlay};
+pub use utils::{copy_to_clipboard, format_copy};
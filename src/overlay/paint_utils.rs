@@ -34,7 +34,29 @@ pub fn sd_rounded_box(px: f32, py: f32, bx: f32, by: f32, r: f32) -> f32 {
     len_max_q + min_max_q - r
 }
 
-pub unsafe fn render_box_sdf(hdc_dest: HDC, bounds: RECT, w: i32, h: i32, is_glowing: bool, time_offset: f32) {
+/// Dark selection interior while dragging: slightly lighter than the backdrop around it
+#[inline(always)]
+fn interior_fill(backdrop: u8) -> u32 {
+    let a = ((backdrop as f32 * 0.85) as u32).max(1); // Alpha 0 would let clicks through
+    let c = 0x11 * a / 255;
+    (a << 24) | (c << 16) | (c << 8) | c
+}
+
+/// Premultiplied ARGB of `color` (0x00RRGGBB) at `alpha` (0..1) over black at `backdrop`
+#[inline(always)]
+fn over_backdrop(color: u32, alpha: f32, backdrop: u8) -> u32 {
+    let a = ((alpha * 255.0) as u32).min(255);
+    let out_a = a + backdrop as u32 * (255 - a) / 255;
+    let r = ((color >> 16) & 0xFF) * a / 255;
+    let g = ((color >> 8) & 0xFF) * a / 255;
+    let b = (color & 0xFF) * a / 255;
+    (out_a << 24) | (r << 16) | (g << 8) | b
+}
+
+/// `border_color` (0x00RRGGBB) and `border_width` (px) are the border while dragging;
+/// processing uses the rainbow glow. Writes premultiplied ARGB for a per-pixel alpha
+/// window: the border and glow are drawn over black at `backdrop` alpha (the dim level).
+pub unsafe fn render_box_sdf(hdc_dest: HDC, bounds: RECT, w: i32, h: i32, is_glowing: bool, time_offset: f32, border_color: u32, border_width: f32, backdrop: u8) {
     let pad = 60; 
     let buf_w = w + (pad * 2);
     let buf_h = h + (pad * 2);
@@ -60,6 +82,7 @@ pub unsafe fn render_box_sdf(hdc_dest: HDC, bounds: RECT, w: i32, h: i32, is_glo
     
     if !p_bits.is_null() {
         let pixels = std::slice::from_raw_parts_mut(p_bits as *mut u32, (buf_w * buf_h) as usize);
+        pixels.fill((backdrop as u32) << 24);
         
         let bx = (w as f32) / 2.0;
         let by = (h as f32) / 2.0;
@@ -76,10 +99,7 @@ pub unsafe fn render_box_sdf(hdc_dest: HDC, bounds: RECT, w: i32, h: i32, is_glo
             let glow_width = 12.0 + time_sin * 4.0;
             let hue = (time_offset * 3.0) % 360.0;
             let global_color = hsv_to_rgb(hue, 0.75, 1.0);
-            
-            let gc_r = ((global_color >> 16) & 0xFF) as u32;
-            let gc_g = ((global_color >> 8) & 0xFF) as u32;
-            let gc_b = (global_color & 0xFF) as u32;
+            let fill = interior_fill(backdrop);
 
             for y in 0..buf_h {
                 let py = (y as f32) - center_y;
@@ -92,17 +112,11 @@ pub unsafe fn render_box_sdf(hdc_dest: HDC, bounds: RECT, w: i32, h: i32, is_glo
                     let d = if qx > 0.0 && qy > 0.0 { ((qx * qx + qy * qy).sqrt()) - eff_radius } else { qx.max(qy) - eff_radius };
                     
                     if d <= 0.0 {
-                        pixels[(y * buf_w + x) as usize] = 0xD80E0E0E;
+                        pixels[(y * buf_w + x) as usize] = fill;
                     } else if d < glow_width {
                         let t = d / glow_width;
                         let alpha_f = (1.0 - t).powi(2);
-                        let a = (alpha_f * 255.0) as u32;
-                        if a > 0 {
-                            let r = (gc_r * a) >> 8;
-                            let g = (gc_g * a) >> 8;
-                            let b = (gc_b * a) >> 8;
-                            pixels[(y * buf_w + x) as usize] = (a << 24) | (r << 16) | (g << 8) | b;
-                        }
+                        pixels[(y * buf_w + x) as usize] = over_backdrop(global_color, alpha_f, backdrop);
                     }
                 }
             }
@@ -120,6 +134,7 @@ pub unsafe fn render_box_sdf(hdc_dest: HDC, bounds: RECT, w: i32, h: i32, is_glo
             let complexity_scale = 1.0 + (perimeter / 1800.0);
             let freq1 = (2.0 * complexity_scale).round();
             let freq2 = (5.0 * complexity_scale).round();
+            let fill = interior_fill(backdrop);
             
             for y in 0..buf_h {
                 for x in 0..buf_w {
@@ -147,8 +162,8 @@ pub unsafe fn render_box_sdf(hdc_dest: HDC, bounds: RECT, w: i32, h: i32, is_glo
                             }
                         } else {
                             // DRAGGING: Grey Border (Anti-Aliased)
-                            // Soft fade out from d=1.5 to d=2.5
-                            let t_out = (d - 1.5).max(0.0);
+                            // Soft fade out over the last px (d=1.5 to d=2.5 at the default width of 2)
+                            let t_out = (d - (border_width - 0.5)).max(0.0);
                             let fade = (1.0 - t_out).clamp(0.0, 1.0);
                             
                             if fade > 0.0 {
@@ -158,9 +173,9 @@ pub unsafe fn render_box_sdf(hdc_dest: HDC, bounds: RECT, w: i32, h: i32, is_glo
                                 final_alpha = (0.85 * (1.0 - t_in)) + (1.0 * t_in);
                                 final_alpha *= fade;
                                 
-                                // Color blend: Dark(0x111111) -> Grey(0xAAAAAA)
-                                // Simplification: Just set to Grey for d>0 to keep it crisp
-                                final_col = 0x00AAAAAA; 
+                                // Color blend: Dark(0x111111) -> border color
+                                // Simplification: Just set to the border color for d>0 to keep it crisp
+                                final_col = border_color;
                             }
                         }
                     } else {
@@ -189,25 +204,12 @@ pub unsafe fn render_box_sdf(hdc_dest: HDC, bounds: RECT, w: i32, h: i32, is_glo
                             }
                         } else {
                             // DRAGGING: Dark Interior
-                            final_alpha = 0.85;
-                            final_col = 0x00111111;
+                            pixels[idx] = fill;
+                            continue;
                         }
                     }
 
-                    if final_alpha > 0.0 {
-                        let a = (final_alpha * 255.0) as u32;
-                        let r = ((final_col >> 16) & 0xFF) as f32;
-                        let g = ((final_col >> 8) & 0xFF) as f32;
-                        let b = (final_col & 0xFF) as f32;
-                        
-                        let r_pre = ((r * final_alpha) as u32).min(255);
-                        let g_pre = ((g * final_alpha) as u32).min(255);
-                        let b_pre = ((b * final_alpha) as u32).min(255);
-                        
-                        pixels[idx] = (a << 24) | (r_pre << 16) | (g_pre << 8) | b_pre;
-                    } else {
-                        pixels[idx] = 0;
-                    }
+                    pixels[idx] = over_backdrop(final_col, final_alpha, backdrop);
                 }
             }
         }
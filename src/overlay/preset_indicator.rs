@@ -0,0 +1,242 @@
+//! Preset Indicator
+//!
+//! Small click-through label in a corner of the primary monitor showing which preset
+//! is active: the one last fired by a hotkey, or the one selected in the settings.
+//! Enabled with `Config.preset_indicator` (settings or tray); refreshed on every
+//! config save.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::Win32::Graphics::Gdi::*;
+use windows::Win32::System::LibraryLoader::*;
+use windows::core::*;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Mutex, Once};
+
+use crate::config::{Preset, ScreenCorner};
+use crate::APP;
+
+static mut INDICATOR_HWND: HWND = HWND(0);
+static WINDOW_RUNNING: AtomicBool = AtomicBool::new(false);
+/// Preset last fired by a hotkey (usize::MAX = none yet, use the settings selection)
+static ACTIVE_PRESET: AtomicUsize = AtomicUsize::new(usize::MAX);
+
+// --- UI CONSTANTS ---
+const HEIGHT: i32 = 28;
+const PADDING: i32 = 10;
+const ICON_WIDTH: i32 = 22;
+const MARGIN: i32 = 12;
+const MAX_WIDTH: i32 = 320;
+const WM_REFRESH_INDICATOR: u32 = WM_APP + 21;
+
+static REGISTER_INDICATOR_CLASS: Once = Once::new();
+
+lazy_static::lazy_static! {
+    // (icon glyph, preset name, corner) of what the window should show
+    static ref CONTENT: Mutex<(&'static str, String, ScreenCorner)> = Mutex::new(("", String::new(), ScreenCorner::default()));
+}
+
+/// Monochrome glyph (Segoe UI Symbol) matching the preset's type
+fn preset_glyph(preset: &Preset) -> &'static str {
+    match preset.preset_type.as_str() {
+        "audio" => "🎤",
+        "video" => "🎬",
+        _ => "▣",
+    }
+}
+
+/// Top-left of a `width` x `height` box in `corner` of `work`
+fn corner_position(work: RECT, corner: ScreenCorner, width: i32, height: i32) -> (i32, i32) {
+    let left = work.left + MARGIN;
+    let right = work.right - MARGIN - width;
+    let top = work.top + MARGIN;
+    let bottom = work.bottom - MARGIN - height;
+    match corner {
+        ScreenCorner::TopLeft => (left, top),
+        ScreenCorner::TopRight => (right, top),
+        ScreenCorner::BottomLeft => (left, bottom),
+        ScreenCorner::BottomRight => (right, bottom),
+    }
+}
+
+/// A hotkey fired preset `idx`
+pub fn set_active_preset(idx: usize) {
+    ACTIVE_PRESET.store(idx, Ordering::SeqCst);
+    refresh();
+}
+
+/// Show, update or close the indicator to match the current config
+pub fn refresh() {
+    let content = match APP.lock() {
+        Ok(app) => {
+            let config = &app.config;
+            let idx = match ACTIVE_PRESET.load(Ordering::SeqCst) {
+                usize::MAX => config.active_preset_idx,
+                idx => idx,
+            };
+            let preset = config.presets.get(idx).or(config.presets.first());
+            match preset {
+                Some(p) if config.preset_indicator => Some((preset_glyph(p), p.name.clone(), config.preset_indicator_corner)),
+                _ => None,
+            }
+        }
+        Err(_) => return,
+    };
+
+    unsafe {
+        match content {
+            Some(content) => {
+                *CONTENT.lock().unwrap() = content;
+                if !WINDOW_RUNNING.swap(true, Ordering::SeqCst) {
+                    std::thread::spawn(run_indicator_window);
+                } else if INDICATOR_HWND.0 != 0 {
+                    PostMessageW(INDICATOR_HWND, WM_REFRESH_INDICATOR, WPARAM(0), LPARAM(0));
+                }
+            }
+            None => {
+                if INDICATOR_HWND.0 != 0 {
+                    PostMessageW(INDICATOR_HWND, WM_CLOSE, WPARAM(0), LPARAM(0));
+                }
+            }
+        }
+    }
+}
+
+fn create_font(face: PCWSTR) -> HFONT {
+    unsafe {
+        CreateFontW(15, 0, 0, 0, FW_SEMIBOLD.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, face)
+    }
+}
+
+fn run_indicator_window() {
+    unsafe {
+        let instance = GetModuleHandleW(None).unwrap();
+        let class_name = w!("SGTPresetIndicator");
+        REGISTER_INDICATOR_CLASS.call_once(|| {
+            let mut wc = WNDCLASSW::default();
+            wc.lpfnWndProc = Some(indicator_wnd_proc);
+            wc.hInstance = instance;
+            wc.lpszClassName = class_name;
+            let _ = RegisterClassW(&wc);
+        });
+
+        let hwnd = CreateWindowExW(
+            WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE | WS_EX_TRANSPARENT,
+            class_name,
+            w!("SGT Preset Indicator"),
+            WS_POPUP,
+            0, 0, 1, HEIGHT,
+            None, None, instance, None
+        );
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), 215, LWA_ALPHA);
+        crate::capture::exclude_from_capture(hwnd);
+        INDICATOR_HWND = hwnd;
+        layout(hwnd);
+        ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).into() {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+        INDICATOR_HWND = HWND(0);
+        WINDOW_RUNNING.store(false, Ordering::SeqCst);
+    }
+}
+
+/// Size the window to the current label and move it into its corner
+unsafe fn layout(hwnd: HWND) {
+    let (_, name, corner) = CONTENT.lock().unwrap().clone();
+
+    let hdc = GetDC(hwnd);
+    let hfont = create_font(w!("Segoe UI"));
+    let old_font = SelectObject(hdc, hfont);
+    let wide: Vec<u16> = name.encode_utf16().collect();
+    let mut size = SIZE::default();
+    GetTextExtentPoint32W(hdc, &wide, &mut size);
+    SelectObject(hdc, old_font);
+    DeleteObject(hfont);
+    ReleaseDC(hwnd, hdc);
+
+    let width = (PADDING * 2 + ICON_WIDTH + size.cx).min(MAX_WIDTH);
+    let monitor = MonitorFromWindow(HWND(0), MONITOR_DEFAULTTOPRIMARY);
+    let mut info = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+    GetMonitorInfoW(monitor, &mut info);
+    let (x, y) = corner_position(info.rcWork, corner, width, HEIGHT);
+
+    SetWindowPos(hwnd, HWND_TOPMOST, x, y, width, HEIGHT, SWP_NOACTIVATE);
+    let region = CreateRoundRectRgn(0, 0, width + 1, HEIGHT + 1, 10, 10);
+    SetWindowRgn(hwnd, region, true);
+    InvalidateRect(hwnd, None, true);
+}
+
+unsafe extern "system" fn indicator_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_REFRESH_INDICATOR => {
+            layout(hwnd);
+            LRESULT(0)
+        }
+        WM_DISPLAYCHANGE | WM_SETTINGCHANGE => {
+            // Work area moved (taskbar, resolution)
+            layout(hwnd);
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let mut client = RECT::default();
+            GetClientRect(hwnd, &mut client);
+            let (glyph, name, _) = CONTENT.lock().unwrap().clone();
+
+            let bg = CreateSolidBrush(COLORREF(0x00202020));
+            FillRect(hdc, &client, bg);
+            DeleteObject(bg);
+            SetBkMode(hdc, TRANSPARENT);
+            SetTextColor(hdc, COLORREF(0x00F0F0F0));
+
+            let symbol_font = create_font(w!("Segoe UI Symbol"));
+            let old_font = SelectObject(hdc, symbol_font);
+            let mut icon_rect = RECT { left: PADDING, top: 0, right: PADDING + ICON_WIDTH, bottom: client.bottom };
+            let mut icon: Vec<u16> = glyph.encode_utf16().collect();
+            DrawTextW(hdc, &mut icon, &mut icon_rect, DT_LEFT | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX);
+
+            let text_font = create_font(w!("Segoe UI"));
+            SelectObject(hdc, text_font);
+            let mut text_rect = RECT { left: PADDING + ICON_WIDTH, top: 0, right: client.right - PADDING, bottom: client.bottom };
+            let mut text: Vec<u16> = name.encode_utf16().collect();
+            DrawTextW(hdc, &mut text, &mut text_rect, DT_LEFT | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS);
+
+            SelectObject(hdc, old_font);
+            DeleteObject(symbol_font);
+            DeleteObject(text_font);
+            EndPaint(hwnd, &mut ps);
+            LRESULT(0)
+        }
+        WM_CLOSE => {
+            DestroyWindow(hwnd);
+            LRESULT(0)
+        }
+        WM_DESTROY => {
+            PostQuitMessage(0);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_corner_position() {
+        // Work area of a 1920x1080 monitor with a bottom taskbar
+        let work = RECT { left: 0, top: 0, right: 1920, bottom: 1040 };
+        assert_eq!(corner_position(work, ScreenCorner::TopLeft, 200, HEIGHT), (MARGIN, MARGIN));
+        assert_eq!(corner_position(work, ScreenCorner::TopRight, 200, HEIGHT), (1920 - MARGIN - 200, MARGIN));
+        assert_eq!(corner_position(work, ScreenCorner::BottomRight, 200, HEIGHT), (1920 - MARGIN - 200, 1040 - MARGIN - HEIGHT));
+        // Secondary monitor left of the primary
+        let work = RECT { left: -1280, top: 0, right: 0, bottom: 1024 };
+        assert_eq!(corner_position(work, ScreenCorner::BottomLeft, 100, HEIGHT), (-1280 + MARGIN, 1024 - MARGIN - HEIGHT));
+    }
+}
@@ -0,0 +1,277 @@
+//! Preset Picker Overlay Module
+//!
+//! `Config.preset_picker_hotkey` captures the screen like a preset hotkey, then shows a
+//! small numbered list of the region presets next to the cursor. A number key or a click
+//! picks the preset the following region selection uses; Escape or clicking elsewhere
+//! cancels. `pick_row` is the bare list, also used by the recent copies quick-pick.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::Win32::Graphics::Gdi::*;
+use windows::Win32::System::LibraryLoader::*;
+use windows::Win32::UI::Input::KeyboardAndMouse::{SetFocus, VK_ESCAPE, VK_NUMPAD1, VK_NUMPAD9};
+use windows::core::*;
+use std::sync::Mutex;
+
+use crate::APP;
+use crate::config::Preset;
+
+/// Number keys 1-9, so at most 9 rows are listed
+pub const MAX_ITEMS: usize = 9;
+
+const MENU_WIDTH: i32 = 260;
+const ITEM_HEIGHT: i32 = 30;
+const MENU_PADDING: i32 = 6;
+const CORNER_RADIUS: i32 = 12;
+
+struct PickerState {
+    labels: Vec<String>,
+    hovered: Option<usize>,
+    picked: Option<usize>, // Row
+    done: bool,
+}
+
+lazy_static::lazy_static! {
+    static ref PICKER_STATE: Mutex<Option<PickerState>> = Mutex::new(None);
+}
+
+/// Presets whose hotkey opens the region selection
+fn uses_region_selection(preset: &Preset) -> bool {
+    matches!(preset.preset_type.as_str(), "image" | "chat" | "screenshot")
+        && preset.capture_mode.is_empty()
+        && preset.capture_monitor().is_none()
+}
+
+/// First `MAX_ITEMS` region presets as (preset index, name)
+fn picker_items(presets: &[Preset]) -> Vec<(usize, String)> {
+    presets.iter().enumerate()
+        .filter(|(_, p)| uses_region_selection(p) && !p.is_upcoming)
+        .take(MAX_ITEMS)
+        .map(|(idx, p)| (idx, p.name.clone()))
+        .collect()
+}
+
+/// List row for a number key ('1'-'9' on either keyboard row or the numpad)
+fn item_for_key(vk: u32, count: usize) -> Option<usize> {
+    let n = match vk {
+        0x31..=0x39 => vk - 0x31,
+        vk if (VK_NUMPAD1.0 as u32..=VK_NUMPAD9.0 as u32).contains(&vk) => vk - VK_NUMPAD1.0 as u32,
+        _ => return None,
+    } as usize;
+    (n < count).then_some(n)
+}
+
+fn item_at_y(y: i32, count: usize) -> Option<usize> {
+    let offset = y - MENU_PADDING;
+    if offset < 0 {
+        return None;
+    }
+    let row = (offset / ITEM_HEIGHT) as usize;
+    (row < count).then_some(row)
+}
+
+/// Show the list near the cursor and wait. Returns the picked preset index, None if
+/// cancelled (or no preset uses a region selection).
+pub fn pick_preset() -> Option<usize> {
+    let items = picker_items(&APP.lock().ok()?.config.presets);
+    if items.is_empty() {
+        log::info!("Preset picker: no region presets to pick from");
+        return None;
+    }
+    let labels = items.iter().map(|(_, name)| name.clone()).collect();
+    pick_row(labels).and_then(|row| items.get(row)).map(|(idx, _)| *idx)
+}
+
+/// Show `labels` (at most `MAX_ITEMS`) as a numbered list near the cursor and wait.
+/// Returns the picked row, None if cancelled or another list is already open (the
+/// preset picker and the recent copies share the window state).
+pub fn pick_row(mut labels: Vec<String>) -> Option<usize> {
+    labels.truncate(MAX_ITEMS);
+    if labels.is_empty() {
+        return None;
+    }
+    let height = MENU_PADDING * 2 + labels.len() as i32 * ITEM_HEIGHT;
+    {
+        let mut state = PICKER_STATE.lock().unwrap();
+        if state.is_some() {
+            log::info!("Quick-pick list already open");
+            return None;
+        }
+        *state = Some(PickerState { labels, hovered: None, picked: None, done: false });
+    }
+
+    unsafe {
+        let instance = GetModuleHandleW(None).unwrap_or_default();
+        let class_name = w!("PresetPickerClass");
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(picker_wnd_proc),
+            hInstance: instance,
+            lpszClassName: class_name,
+            hCursor: LoadCursorW(None, IDC_ARROW).unwrap_or_default(),
+            ..Default::default()
+        };
+        let _ = RegisterClassW(&wc);
+
+        // Next to the cursor, kept on its monitor
+        let mut pt = POINT::default();
+        GetCursorPos(&mut pt);
+        let mut mi = MONITORINFO { cbSize: std::mem::size_of::<MONITORINFO>() as u32, ..Default::default() };
+        GetMonitorInfoW(MonitorFromPoint(pt, MONITOR_DEFAULTTONEAREST), &mut mi);
+        let work = mi.rcWork;
+        let x = (pt.x + 12).min(work.right - MENU_WIDTH).max(work.left);
+        let y = (pt.y + 12).min(work.bottom - height).max(work.top);
+
+        let hwnd = CreateWindowExW(
+            WS_EX_TOOLWINDOW | WS_EX_TOPMOST | WS_EX_LAYERED,
+            class_name,
+            w!("Preset Picker"),
+            WS_POPUP,
+            x, y, MENU_WIDTH, height,
+            None, None, instance, None,
+        );
+        if hwnd.0 == 0 {
+            *PICKER_STATE.lock().unwrap() = None;
+            return None;
+        }
+        crate::capture::exclude_from_capture(hwnd);
+        SetLayeredWindowAttributes(hwnd, COLORREF(0), 245, LWA_ALPHA);
+        let region = CreateRoundRectRgn(0, 0, MENU_WIDTH, height, CORNER_RADIUS, CORNER_RADIUS);
+        SetWindowRgn(hwnd, region, true);
+
+        ShowWindow(hwnd, SW_SHOW);
+        SetForegroundWindow(hwnd);
+        SetFocus(hwnd);
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).into() {
+            TranslateMessage(&msg);
+            DispatchMessageW(&msg);
+        }
+        if IsWindow(hwnd).as_bool() {
+            DestroyWindow(hwnd);
+        }
+    }
+
+    PICKER_STATE.lock().unwrap().take().and_then(|s| s.picked)
+}
+
+/// Record the pick (None = cancel) and end `pick_preset`'s loop. Only the first call
+/// counts: destroying the window deactivates it, which would cancel again.
+unsafe fn finish(hwnd: HWND, row: Option<usize>) {
+    {
+        let mut guard = PICKER_STATE.lock().unwrap();
+        let Some(state) = guard.as_mut().filter(|s| !s.done) else { return };
+        state.done = true;
+        state.picked = row.filter(|&r| r < state.labels.len());
+    }
+    DestroyWindow(hwnd);
+    PostQuitMessage(0);
+}
+
+fn item_count() -> usize {
+    PICKER_STATE.lock().unwrap().as_ref().map_or(0, |s| s.labels.len())
+}
+
+unsafe extern "system" fn picker_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    match msg {
+        WM_KEYDOWN => {
+            let vk = wparam.0 as u32;
+            if vk == VK_ESCAPE.0 as u32 {
+                finish(hwnd, None);
+            } else if let Some(row) = item_for_key(vk, item_count()) {
+                finish(hwnd, Some(row));
+            }
+            LRESULT(0)
+        }
+        WM_MOUSEMOVE => {
+            let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
+            let row = item_at_y(y, item_count());
+            let changed = match PICKER_STATE.lock().unwrap().as_mut() {
+                Some(state) if state.hovered != row => { state.hovered = row; true }
+                _ => false,
+            };
+            if changed {
+                InvalidateRect(hwnd, None, false);
+            }
+            LRESULT(0)
+        }
+        WM_LBUTTONUP => {
+            let y = ((lparam.0 >> 16) & 0xFFFF) as i16 as i32;
+            if let Some(row) = item_at_y(y, item_count()) {
+                finish(hwnd, Some(row));
+            }
+            LRESULT(0)
+        }
+        WM_ACTIVATE => {
+            // Clicked somewhere else
+            if (wparam.0 & 0xFFFF) as u32 == WA_INACTIVE {
+                finish(hwnd, None);
+            }
+            LRESULT(0)
+        }
+        WM_PAINT => {
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            if let Some(state) = PICKER_STATE.lock().unwrap().as_ref() {
+                paint_items(hdc, hwnd, state);
+            }
+            EndPaint(hwnd, &ps);
+            LRESULT(0)
+        }
+        _ => DefWindowProcW(hwnd, msg, wparam, lparam),
+    }
+}
+
+unsafe fn paint_items(hdc: HDC, hwnd: HWND, state: &PickerState) {
+    let mut rect = RECT::default();
+    GetClientRect(hwnd, &mut rect);
+    let bg = CreateSolidBrush(COLORREF(0x002D2323)); // (35, 35, 45) in BGR
+    FillRect(hdc, &rect, bg);
+    DeleteObject(bg);
+
+    let hfont = CreateFontW(16, 0, 0, 0, FW_NORMAL.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+    let old_font = SelectObject(hdc, hfont);
+    SetBkMode(hdc, TRANSPARENT);
+    SetTextColor(hdc, COLORREF(0x00F0E6E6));
+
+    for (row, label) in state.labels.iter().enumerate() {
+        let top = MENU_PADDING + row as i32 * ITEM_HEIGHT;
+        let item_rect = RECT { left: MENU_PADDING, top, right: rect.right - MENU_PADDING, bottom: top + ITEM_HEIGHT };
+        if state.hovered == Some(row) {
+            let hover = CreateSolidBrush(COLORREF(0x00503C3C)); // (60, 60, 80)
+            FillRect(hdc, &item_rect, hover);
+            DeleteObject(hover);
+        }
+        let mut text_rect = RECT { left: item_rect.left + 10, ..item_rect };
+        let mut text: Vec<u16> = format!("{}   {}", row + 1, label).encode_utf16().collect();
+        DrawTextW(hdc, &mut text, &mut text_rect, DT_LEFT | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS);
+    }
+
+    SelectObject(hdc, old_font);
+    DeleteObject(hfont);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_picker_items() {
+        let preset = |name: &str, preset_type: &str| Preset { name: name.to_string(), preset_type: preset_type.to_string(), ..Default::default() };
+        let mut presets = vec![preset("OCR", "image"), preset("Dictate", "audio"), preset("Ask", "chat")];
+        presets.push(Preset { capture_mode: crate::overlay::replace_selection::CAPTURE_MODE.to_string(), ..preset("Replace", "image") });
+        assert_eq!(picker_items(&presets), vec![(0, "OCR".to_string()), (2, "Ask".to_string())]);
+
+        let many: Vec<Preset> = (0..12).map(|i| preset(&i.to_string(), "image")).collect();
+        assert_eq!(picker_items(&many).len(), MAX_ITEMS);
+
+        assert_eq!(item_for_key(0x31, 3), Some(0));
+        assert_eq!(item_for_key(VK_NUMPAD1.0 as u32 + 2, 3), Some(2));
+        assert_eq!(item_for_key(0x34, 3), None); // "4" with only 3 rows
+        assert_eq!(item_for_key(0x30, 3), None);
+
+        assert_eq!(item_at_y(MENU_PADDING + ITEM_HEIGHT + 1, 3), Some(1));
+        assert_eq!(item_at_y(MENU_PADDING - 1, 3), None);
+        assert_eq!(item_at_y(MENU_PADDING + 3 * ITEM_HEIGHT, 3), None);
+    }
+}
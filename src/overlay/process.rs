@@ -1,41 +1,186 @@
 use windows::Win32::Foundation::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
 use std::sync::{Arc, Mutex};
-use std::sync::mpsc::{channel, Sender}; // ADDED
+use std::sync::mpsc::{channel, Receiver, Sender}; // ADDED
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
 use image::GenericImageView;
 
-use crate::{AppState, api::{translate_image_streaming, translate_text_streaming, transcribe_audio_gemini, upload_audio_to_whisper}};
-use super::utils::{copy_to_clipboard, get_error_message};
-use super::result::{create_result_window, update_window_text, WindowType, link_windows};
+use crate::{AppState, api::{translate_image_streaming, translate_images_answer, VisionAnswer, supports_multi_image, Sampling, translate_text_streaming, transcribe_audio_gemini, upload_audio_to_whisper}};
+use crate::audio_file::{AudioClip, prepare_for_upload};
+use crate::api_error::ApiError;
+use crate::config::{CopyFormat, OutputMode, ReadingOrder};
+use crate::notes_file::RunNote;
+use super::completion::CompletionNotice;
+use super::utils::{copy_to_clipboard, copy_source};
+use super::live_queue::{live_queue, LiveSender, LIVE_QUEUE_BOUND};
+use super::result::{create_result_window, update_window_text, update_window_markdown, update_window_error, update_window_request_error, WindowType, link_windows, set_export_action, set_follow_up_action, set_preserve_layout, set_dismiss_on_outside_click, set_warning_badge, set_stats_line, set_model_badge, set_retry_action, set_copy_on_close, set_copy_format, set_font_family, set_text_model, set_unreflowed_text, set_study_card, format_for_copy, set_source_image};
+
+/// Appended to vision prompts of presets with `preserve_layout` (code, tables, columns)
+const PRESERVE_LAYOUT_INSTRUCTION: &str = "\n\nPreserve the original layout exactly: keep every line break, indentation, column alignment and blank line as they appear in the image. Do not reflow, merge or wrap lines, and do not wrap the output in code fences.";
+
+/// Appended to vision prompts of presets with a `reading_order`
+const LEFT_TO_RIGHT_INSTRUCTION: &str = "\n\nIf the image is a comic page or has several panels, read the panels left to right, then top to bottom, and the speech bubbles inside each panel in the same order. Output the text in that reading order.";
+const RIGHT_TO_LEFT_INSTRUCTION: &str = "\n\nThe image is laid out right to left like a manga page: read the panels right to left, then top to bottom, and the speech bubbles inside each panel right to left as well. Output the text in that reading order, not in left-to-right screen order.";
+
+/// Appended to live vision prompts so empty frames can be filtered out, and to those of
+/// `Preset.expand_on_empty` presets so an empty selection can be retried
+const NO_TEXT_INSTRUCTION: &str = "\n\nIf the image does not contain any text, output EXACTLY '[NO_TEXT]' and nothing else.";
+
+/// Appended to vision prompts of `asks_detected_language` presets; parsed by `api::json_answer`
+const DETECT_LANGUAGE_INSTRUCTION: &str = "\n\nAnswer only with a JSON object with two string fields: \"translation\" (your answer as asked above) and \"detected_language\" (the English name of the language the text in the image is written in, e.g. \"Japanese\"). Do not wrap it in code fences.";
+
+/// Whether requests of `preset` ask the model to name the source language. That is the
+/// Translate preset's JSON mode, when it doesn't stream (the JSON would show while streaming).
+fn asks_detected_language(preset: &crate::config::Preset) -> bool {
+    let chat = preset.preset_type == "chat" || preset.enable_chat_mode;
+    preset.id == "preset_translate" && !preset.streaming_enabled && !preset.live_mode && !chat && !super::study::enabled_for(preset)
+}
+
+/// Whether a detected source language is the given language ("Chinese" matches
+/// "Chinese (Simplified)")
+fn same_language(detected: &str, target: &str) -> bool {
+    let base = |name: &str| name.split('(').next().unwrap_or("").trim().to_lowercase();
+    !detected.trim().is_empty() && base(detected) == base(target)
+}
+
+/// Stats-line note naming the source language the model detected
+fn detected_language_badge(lang: &str) -> String {
+    format!("🌐 {}", lang)
+}
+
+/// Language a retranslation target goes into once the answer is in. `SAME_AS_DETECTED` becomes
+/// the `detected` source language; `None` (no retranslation) when nothing was detected or the
+/// answer already is in that language.
+fn resolve_target(target: &str, detected: Option<&str>, answer_language: Option<&str>) -> Option<String> {
+    if target != crate::config::SAME_AS_DETECTED {
+        return Some(target.to_string());
+    }
+    let detected = detected.map(str::trim).filter(|lang| !lang.is_empty())?;
+    if answer_language.map_or(false, |answer| same_language(detected, answer)) {
+        return None;
+    }
+    Some(detected.to_string())
+}
+
+/// Layout and reading-order instructions every vision prompt of `preset` ends with
+fn push_vision_instructions(prompt: &mut String, preset: &crate::config::Preset) {
+    if preset.preserve_layout { prompt.push_str(PRESERVE_LAYOUT_INSTRUCTION); }
+    if super::study::enabled_for(preset) { super::study::push_instruction(prompt); }
+    if asks_detected_language(preset) { prompt.push_str(DETECT_LANGUAGE_INSTRUCTION); }
+    match preset.reading_order {
+        ReadingOrder::Auto => {}
+        ReadingOrder::LeftToRight => prompt.push_str(LEFT_TO_RIGHT_INSTRUCTION),
+        ReadingOrder::RightToLeft => prompt.push_str(RIGHT_TO_LEFT_INSTRUCTION),
+    }
+}
+
+/// Prompt of an image request: the preset's prompt with its layout / reading order instructions
+pub fn vision_prompt(preset: &crate::config::Preset) -> String {
+    let mut prompt = preset.render_prompt();
+    push_vision_instructions(&mut prompt, preset);
+    prompt
+}
+
+/// Corner badge of a finished result, e.g. "gemini-2.0-flash · 1.8s"
+fn model_badge(model: &str, elapsed: std::time::Duration) -> String {
+    format!("{} · {:.1}s", model, elapsed.as_secs_f32())
+}
+
+/// Chat presets send the user's question after the preset prompt
+fn chat_prompt(prompt: &str, question: &str) -> String {
+    format!("{}\n\nUser question: {}", prompt, question)
+}
+
+/// The prompt a capture with `preset` would send, built like the capture paths do;
+/// chat presets get `sample_question` as the user's question
+pub fn preview_prompt(preset: &crate::config::Preset, sample_question: &str) -> String {
+    if preset.preset_type == "audio" {
+        return preset.render_prompt();
+    }
+    let mut prompt = vision_prompt(preset);
+    if preset.include_context {
+        prompt.push_str(&super::window_context::context_instruction("Window title").unwrap_or_default());
+    }
+    // Live mode always asks for the sentinel; expand_on_empty captures when they can retry
+    if preset.live_mode || ExpandOnEmpty::applies_to(preset) {
+        prompt.push_str(NO_TEXT_INSTRUCTION);
+    } else if preset.preset_type == "chat" || preset.enable_chat_mode {
+        prompt = chat_prompt(&prompt, sample_question);
+    }
+    prompt
+}
+
+/// `Config.min_region_size`: a selection this thin is an accidental click or drag
+pub fn region_too_small(rect: RECT, min_size: u32) -> bool {
+    (rect.right - rect.left).unsigned_abs() < min_size || (rect.bottom - rect.top).unsigned_abs() < min_size
+}
+
+pub fn too_small_message(lang: &str, min_size: u32) -> String {
+    match lang {
+        "vi" => format!("Vùng chọn quá nhỏ (tối thiểu {0}×{0} px)", min_size),
+        "ko" => format!("선택 영역이 너무 작습니다 (최소 {0}×{0} px)", min_size),
+        _ => format!("Selection too small (at least {0}×{0} px)", min_size),
+    }
+}
 
 pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HWND, preset_idx: usize) {
+    process_capture(app, rect, overlay_hwnd, preset_idx, None);
+}
+
+/// `rerun`: the stored crop of the last capture (`last_capture`) instead of the screenshot
+pub(super) fn process_capture(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HWND, preset_idx: usize, rerun: Option<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>) {
+    let is_rerun = rerun.is_some();
     // 1. Snapshot and Configuration Retrieval
-    let (img, config, preset) = {
+    let (img, origin, config, mut preset) = {
         let guard = app.lock().unwrap();
         if preset_idx >= guard.config.presets.len() {
             // Should not happen, but safety check
             unsafe { PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); }
             return;
         }
+        let (img, origin) = match (rerun, guard.original_screenshot.clone()) {
+            (Some(img), _) => (img, (rect.left, rect.top)),
+            (None, Some(img)) => (img, guard.screenshot_origin),
+            // Preset.capture_on_release: nothing was captured at the hotkey, take the region now
+            (None, None) => match crate::capture::capture_rect(rect) {
+                Ok(img) => (img, (rect.left, rect.top)),
+                Err(e) => {
+                    log::error!("Capture Error: {}", e);
+                    unsafe { PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); }
+                    return;
+                }
+            },
+        };
         (
-            guard.original_screenshot.clone().unwrap(), 
+            img,
+            origin,
             guard.config.clone(),
             guard.config.presets[preset_idx].clone()
         )
     };
+    super::app_language::apply(&mut preset, preset_idx);
+
+    // Junk OCR and a wasted request otherwise
+    if region_too_small(rect, config.min_region_size) {
+        log::info!("Region {}x{} is below the {} px minimum, skipped", rect.right - rect.left, rect.bottom - rect.top, config.min_region_size);
+        super::app_blocklist::show_toast(too_small_message(&config.ui_language, config.min_region_size));
+        unsafe { PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); }
+        return;
+    }
 
-    // Live Mode / Subtitle Mode Check
+    // Live Mode / Subtitle Mode Check (a stored crop can't be watched)
+    if preset.live_mode && is_rerun {
+        return;
+    }
     if preset.live_mode {
         unsafe { PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); }
-        crate::api::capture_screen_continuous(preset, rect, overlay_hwnd);
+        crate::api::capture_screen_continuous(preset, preset_idx, rect, overlay_hwnd);
         return;
     }
 
-    let x_virt = unsafe { GetSystemMetrics(SM_XVIRTUALSCREEN) };
-    let y_virt = unsafe { GetSystemMetrics(SM_YVIRTUALSCREEN) };
-    
-    let crop_x = (rect.left - x_virt).max(0) as u32;
-    let crop_y = (rect.top - y_virt).max(0) as u32;
+    let crop_x = (rect.left - origin.0).max(0) as u32;
+    let crop_y = (rect.top - origin.1).max(0) as u32;
     let crop_w = (rect.right - rect.left).abs() as u32;
     let crop_h = (rect.bottom - rect.top).abs() as u32;
     
@@ -47,6 +192,7 @@ pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HW
     // --- SCREENSHOT PRESET HANDLING (Before model lookup!) ---
     if preset.preset_type == "screenshot" && crop_w > 0 && crop_h > 0 {
         let cropped = img.view(crop_x, crop_y, crop_w, crop_h).to_image();
+        if !is_rerun { super::last_capture::remember(&cropped, rect); }
         let ui_language = config.ui_language.clone();
         
         std::thread::spawn(move || {
@@ -76,18 +222,23 @@ pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HW
             
             let full_path = filepath.display().to_string();
             let folder_display = folder_path.display().to_string();
-            let msg = if save_result.is_ok() {
-                match ui_language.as_str() {
-                    "vi" => format!("✅ Đã lưu:\n{}\n\n📋 Đã copy vào clipboard!\n\n📂 Thư mục:\n{}", full_path, folder_display),
-                    _ => format!("✅ Saved:\n{}\n\n📋 Copied to clipboard!\n\n📂 Folder:\n{}", full_path, folder_display),
+            match save_result {
+                Ok(()) => {
+                    let msg = match ui_language.as_str() {
+                        "vi" => format!("✅ Đã lưu:\n{}\n\n📋 Đã copy vào clipboard!\n\n📂 Thư mục:\n{}", full_path, folder_display),
+                        _ => format!("✅ Saved:\n{}\n\n📋 Copied to clipboard!\n\n📂 Folder:\n{}", full_path, folder_display),
+                    };
+                    super::result::update_window_text(primary_hwnd, &msg);
                 }
-            } else {
-                match ui_language.as_str() {
-                    "vi" => "❌ Lỗi lưu ảnh!".to_string(),
-                    _ => "❌ Failed to save image!".to_string(),
+                Err(e) => {
+                    log::error!("Failed to save screenshot {}: {}", full_path, e);
+                    let msg = match ui_language.as_str() {
+                        "vi" => "❌ Lỗi lưu ảnh!",
+                        _ => "❌ Failed to save image!",
+                    };
+                    update_window_error(primary_hwnd, msg);
                 }
-            };
-            super::result::update_window_text(primary_hwnd, &msg);
+            }
             
             // Message loop
             unsafe {
@@ -97,6 +248,7 @@ pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HW
                     DispatchMessageW(&msg_struct);
                     if !IsWindow(primary_hwnd).as_bool() { break; }
                 }
+                super::result::destroy_thread_windows();
             }
         });
         return;
@@ -114,42 +266,76 @@ pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HW
         let provider = model_config.provider.clone();
         
         let cropped = img.view(crop_x, crop_y, crop_w, crop_h).to_image();
+        if !is_rerun { super::last_capture::remember(&cropped, rect); }
+        let expand_on_empty = ExpandOnEmpty::for_capture(&preset, &img, (crop_x, crop_y, crop_w, crop_h));
         
         let groq_api_key = config.api_key.clone();
         let gemini_api_key = config.gemini_api_key.clone();
         let openrouter_api_key = config.openrouter_api_key.clone();
         let ui_language = config.ui_language.clone();
+        let show_model_badge = config.show_model_badge;
         
         // Prepare Prompt - replace all {languageN} with actual languages
-        let mut final_prompt = preset.prompt.clone();
-        
-        // Replace numbered language tags
-        for (key, value) in &preset.language_vars {
-            let pattern = format!("{{{}}}", key); // e.g., "{language1}"
-            final_prompt = final_prompt.replace(&pattern, value);
-        }
-        
-        // Backward compatibility: also replace old {language} tag
-        final_prompt = final_prompt.replace("{language}", &preset.selected_language);
+        let mut final_prompt = vision_prompt(&preset);
+        super::window_context::push_context(&mut final_prompt, &preset);
         
         // Settings for thread
-        let streaming_enabled = preset.streaming_enabled;
+        let study = super::study::enabled_for(&preset);
+        // A study answer is JSON: streamed, the window would show its raw fields until the end
+        let streaming_enabled = preset.streaming_enabled && !study;
         let retranslate_streaming_enabled = preset.retranslate_streaming_enabled;
         let auto_copy = preset.auto_copy;
         let retranslate_auto_copy = preset.retranslate_auto_copy;
+        let copy_timing = AutoCopy::from_preset(&preset);
+        let note = RunNote::for_preset(&preset);
+        let completion = CompletionNotice::for_preset(&preset, &ui_language);
         let do_retranslate = preset.retranslate;
-        let retranslate_to = preset.retranslate_to.clone();
+        // SAME_AS_DETECTED stays as-is here: it resolves once the answer names the source language
+        let retranslate_to = if preset.retranslates_to_detected() {
+            crate::config::SAME_AS_DETECTED.to_string()
+        } else {
+            preset.retranslate_target(&ui_language)
+        };
+        // The Translate preset answers in `{language1}`
+        let answer_language = preset.language_vars.get("language1").cloned();
         let retranslate_model_id = preset.retranslate_model.clone();
-        let use_json_format = preset.id == "preset_translate";
+        let retranslate_secondary_to = preset.retranslate_secondary_to.clone();
+        let retranslate_secondary_model_id = preset.retranslate_secondary_model.clone();
+        let use_json_format = preset.id == "preset_translate" || study;
         let hide_overlay = preset.hide_overlay;
+        let preserve_layout = preset.preserve_layout;
+        let dismiss_on_outside_click = preset.dismiss_on_outside_click;
+        let multi_frame_capture = preset.multi_frame_capture.filter(|&n| n > 1 && !is_rerun); // Later frames come from the screen
+        // Chat answers are free-form, so the OCR heuristics don't apply
+        let sampling = Sampling::from_preset(&preset);
+        let ocr_quality_check = preset.ocr_quality_check && preset.preset_type != "chat" && !preset.enable_chat_mode;
+        // Drafts are only useful when the vision text streams in and the windows are visible
+        // (and a target to translate into, which SAME_AS_DETECTED only has once the answer is complete)
+        let early_retranslate = do_retranslate && preset.early_retranslate && streaming_enabled && !hide_overlay
+            && !preset.retranslates_to_detected();
+        // Chat answers are conversation, where an opening line can be the answer
+        let strip_preamble = preset.strip_preamble && preset.preset_type != "chat" && !preset.enable_chat_mode;
+        let reflow = super::reflow::Reflow::for_preset(&preset);
+        let copy_format = preset.copy_format;
+        let font_family = preset.font_family.clone();
+        
+        // Compare mode: a second model runs on the same capture in its own window
+        let compare_model = preset.compare_model.as_deref()
+            .filter(|id| *id != preset.model && !hide_overlay)
+            .and_then(crate::model_config::get_model_by_id);
+        // Both windows need their model shown to be told apart
+        let show_model_badge = show_model_badge || compare_model.is_some();
         
         // For History
         let preset_name_for_history = preset.name.clone();
         let input_summary = format!("Screenshot {}x{}", crop_w, crop_h);
+        let group_id = compare_model.as_ref().map(|_| crate::history::generate_entry_id());
         
         // Check if this is a chat preset - show input popup first
         let is_chat_mode = preset.preset_type == "chat" || preset.enable_chat_mode;
         
+        let keep_chat_image = preset.keep_chat_image;
+        
         let user_question = if is_chat_mode {
             // Close selection overlay
             unsafe { PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); }
@@ -171,58 +357,273 @@ pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HW
         std::thread::spawn(move || {
             // Create Primary Window (Hidden initially)
             let primary_hwnd = create_result_window(rect, WindowType::Primary);
+            set_copy_format(primary_hwnd, copy_format, copy_source(&preset_name_for_history));
+            set_font_family(primary_hwnd, font_family.clone());
+            set_text_model(primary_hwnd, retranslate_model_id.clone(), retranslate_streaming_enabled);
+            if preserve_layout { set_preserve_layout(primary_hwnd, true); }
+            if dismiss_on_outside_click { set_dismiss_on_outside_click(primary_hwnd, true); }
+            set_source_image(primary_hwnd, &cropped);
             
             // Worker thread for API calls
             std::thread::spawn(move || {
+                let started = Instant::now();
+                let badge_model = model_name.clone();
                 let accumulated_vision = Arc::new(Mutex::new(String::new()));
                 let acc_vis_clone = accumulated_vision.clone();
                 let mut first_chunk_received = false;
+                // Spinner by the cursor until the first chunk (shown even with hide_overlay)
+                let mut busy = Some(super::busy_indicator::start());
+
+                // Retranslation target(s): the secondary window plus the optional chained third language
+                let mut retranslate_targets = vec![RetranslateTarget {
+                    target_lang: retranslate_to,
+                    model_id: retranslate_model_id.clone(),
+                    auto_copy: retranslate_auto_copy.then_some(copy_timing),
+                    // The final copy is the retranslation's, so it reports completion
+                    notice: completion.clone().filter(|_| retranslate_auto_copy),
+                    note: note.clone(),
+                    strip_preamble,
+                }];
+                if let Some(lang) = retranslate_secondary_to {
+                    retranslate_targets.push(RetranslateTarget {
+                        target_lang: lang,
+                        model_id: retranslate_secondary_model_id,
+                        auto_copy: None,
+                        notice: None,
+                        note: note.clone(),
+                        strip_preamble,
+                    });
+                }
+                let retranslate_setup = RetranslateSetup {
+                    rect,
+                    keys: (groq_api_key.clone(), gemini_api_key.clone(), openrouter_api_key.clone()),
+                    targets: retranslate_targets,
+                    streaming_enabled: retranslate_streaming_enabled,
+                    hide_overlay,
+                    copy_format,
+                    copy_source: copy_source(&preset_name_for_history),
+                    font_family: font_family.clone(),
+                };
+
+                // --- EARLY RETRANSLATE ---
+                // Start a draft retranslation once the vision stream goes idle or completes a sentence
+                let early_state = Arc::new(Mutex::new(EarlyRetranslate::default()));
+                if early_retranslate {
+                    let early_watch = early_state.clone();
+                    let vision_watch = accumulated_vision.clone();
+                    let setup_watch = retranslate_setup.clone();
+                    std::thread::spawn(move || loop {
+                        std::thread::sleep(std::time::Duration::from_millis(50));
+                        // Snapshot the text first: the chunk callback locks vision -> early state
+                        let text = vision_watch.lock().unwrap().clone();
+                        let mut st = early_watch.lock().unwrap();
+                        if st.vision_done || st.draft_source.is_some() { break; }
+                        let idle = st.last_chunk_at.map_or(false, |t| t.elapsed() >= EARLY_RETRANSLATE_IDLE);
+                        if idle && !text.trim().is_empty() {
+                            st.start_draft(&setup_watch, primary_hwnd, text);
+                            break;
+                        }
+                    });
+                }
+                let early_chunk = early_state.clone();
+                let setup_chunk = retranslate_setup.clone();
 
                 // --- STEP 1: VISION API ---
                 // For chat mode, combine system prompt with user question
                 let effective_prompt = if is_chat_mode && !user_question.is_empty() {
-                    format!("{}\n\nUser question: {}", final_prompt, user_question)
+                    chat_prompt(&final_prompt, &user_question)
                 } else {
                     final_prompt
                 };
+                // The sentinel tells "no text in the region" apart from an answer. Only the
+                // requests that can retry with a larger region ask for it (not compare / error retry).
+                let expand_prompt = expand_on_empty.as_ref().map(|_| format!("{}{}", effective_prompt, NO_TEXT_INSTRUCTION));
+
+                // Chat mode keeps the capture (downscaled) so follow-up turns stay grounded in it
+                let chat_session = if is_chat_mode {
+                    let image = if keep_chat_image { crate::conversation::encode_context_image(&cropped) } else { None };
+                    Some(ChatFollowUp {
+                        conversation: Arc::new(Mutex::new(crate::conversation::Conversation::new(image))),
+                        first_turn: effective_prompt.clone(),
+                        gemini_api_key: gemini_api_key.clone(),
+                        model: if provider == "google" { model_name.clone() } else { FOLLOW_UP_FALLBACK_MODEL.to_string() },
+                        streaming_enabled,
+                        ui_language: ui_language.clone(),
+                    })
+                } else {
+                    None
+                };
                 
-                let vision_res = translate_image_streaming(
-                    &groq_api_key, 
-                    &gemini_api_key, 
-                    &openrouter_api_key,
-                    effective_prompt, 
-                    model_name, 
-                    provider, 
-                    cropped, 
-                    streaming_enabled, 
-                    use_json_format,
-                    |chunk| {
-                        let mut text = acc_vis_clone.lock().unwrap();
-                        text.push_str(chunk);
-                        
-                        if !first_chunk_received {
-                            first_chunk_received = true;
-                            unsafe {
-                                PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
-                                if !hide_overlay {
-                                    ShowWindow(primary_hwnd, SW_SHOW);
-                                }
+                // Multi-frame: grab the extra frames now that the selection is done
+                let frames = match multi_frame_capture {
+                    Some(count) => {
+                        // The selection overlay would otherwise show up in the extra frames
+                        unsafe { PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0)); }
+                        std::thread::sleep(std::time::Duration::from_millis(100));
+                        let area = RECT {
+                            left: origin.0 + crop_x as i32,
+                            top: origin.1 + crop_y as i32,
+                            right: origin.0 + (crop_x + crop_w) as i32,
+                            bottom: origin.1 + (crop_y + crop_h) as i32,
+                        };
+                        capture_frames(cropped, area, count)
+                    }
+                    None => vec![cropped],
+                };
+                // Shared with the compare window and the Retry button instead of copied
+                let frames = Arc::new(frames);
+
+                if let Some(model) = compare_model {
+                    CompareRun {
+                        rect: super::result::rect_beside_selection(rect, do_retranslate),
+                        keys: (groq_api_key.clone(), gemini_api_key.clone(), openrouter_api_key.clone()),
+                        model,
+                        prompt: effective_prompt.clone(),
+                        frames: Arc::clone(&frames),
+                        streaming_enabled,
+                        use_json_format,
+                        sampling,
+                        markdown: is_chat_mode,
+                        strip_preamble,
+                        reflow,
+                        study,
+                        copy_format,
+                        font_family: font_family.clone(),
+                        text_model: (retranslate_model_id.clone(), retranslate_streaming_enabled),
+                        ui_language: ui_language.clone(),
+                        preset_name: preset_name_for_history.clone(),
+                        input_summary: input_summary.clone(),
+                        group_id: group_id.clone(),
+                    }.spawn(primary_hwnd);
+                }
+
+                let mut on_chunk = |chunk: &str| {
+                    let mut text = acc_vis_clone.lock().unwrap();
+                    text.push_str(chunk);
+                    
+                    if early_retranslate {
+                        let mut st = early_chunk.lock().unwrap();
+                        st.last_chunk_at = Some(std::time::Instant::now());
+                        if st.draft_source.is_none() && ends_with_sentence(&text) {
+                            st.start_draft(&setup_chunk, primary_hwnd, text.clone());
+                        }
+                    }
+                    
+                    if !first_chunk_received {
+                        first_chunk_received = true;
+                        busy.take();
+                        unsafe {
+                            PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+                            if !hide_overlay {
+                                ShowWindow(primary_hwnd, SW_SHOW);
                             }
                         }
-                        if !hide_overlay {
-                            // Apply markdown cleaning for chat mode
-                            let display_text = if is_chat_mode {
-                                super::utils::clean_markdown_for_display(&text)
-                            } else {
-                                text.to_string()
-                            };
-                            update_window_text(primary_hwnd, &display_text);
+                    }
+                    if !hide_overlay {
+                        // Chat answers are shown without markdown (the source is kept for copying)
+                        if is_chat_mode {
+                            update_window_markdown(primary_hwnd, &text);
+                        } else {
+                            update_window_text(primary_hwnd, &text);
                         }
                     }
+                };
+
+                let retry = VisionRetry {
+                    keys: (groq_api_key.clone(), gemini_api_key.clone(), openrouter_api_key.clone()),
+                    prompt: effective_prompt.clone(),
+                    model: model_name.clone(),
+                    provider: provider.clone(),
+                    frames: Arc::clone(&frames),
+                    streaming_enabled,
+                    use_json_format,
+                    sampling,
+                    markdown: is_chat_mode,
+                    strip_preamble,
+                    reflow,
+                    study,
+                    auto_copy: auto_copy.then_some(copy_timing),
+                    note: note.as_ref().map(|n| n.again()),
+                    ui_language: ui_language.clone(),
+                    preset_name: preset_name_for_history.clone(),
+                    input_summary: input_summary.clone(),
+                };
+
+                // A single frame can use the expanded-region prompt (STEP 1.1 may retry with it)
+                let prompt = if frames.len() > 1 { effective_prompt } else { expand_prompt.clone().unwrap_or(effective_prompt) };
+                let vision_res = request_frames(
+                    (&groq_api_key, &gemini_api_key, &openrouter_api_key),
+                    prompt,
+                    model_name,
+                    provider,
+                    &frames,
+                    streaming_enabled,
+                    use_json_format,
+                    sampling,
+                    &mut on_chunk,
                 );
 
+                // --- STEP 1.1: NO TEXT FOUND, TRY A LARGER REGION (Preset.expand_on_empty) ---
+                let mut expanded_by = None;
+                let vision_res = match (expand_on_empty, &expand_prompt) {
+                    (Some(expand), Some(prompt)) if found_no_text(&vision_res) => {
+                        log::info!("No text found, retrying with the region expanded by {} px", expand.margin);
+                        accumulated_vision.lock().unwrap().clear();
+                        // Clear a streamed "[NO_TEXT]"
+                        if !hide_overlay {
+                            update_window_text(primary_hwnd, "");
+                        }
+                        expanded_by = Some(expand.margin);
+                        translate_images_answer(
+                            &groq_api_key,
+                            &gemini_api_key,
+                            &openrouter_api_key,
+                            prompt.clone(),
+                            retry.model.clone(),
+                            retry.provider.clone(),
+                            std::slice::from_ref(&expand.image),
+                            streaming_enabled,
+                            use_json_format,
+                            sampling,
+                            &mut on_chunk
+                        )
+                    }
+                    _ => vision_res,
+                };
+                // Nothing in the larger region either: say so instead of showing the sentinel
+                let no_text_found = expand_prompt.is_some() && matches!(&vision_res, Ok(answer) if is_no_text(&answer.text));
+                // The source language, when the JSON answer named it (DETECT_LANGUAGE_INSTRUCTION)
+                let mut detected_language = None;
+                let vision_res = vision_res.map(|answer| {
+                    detected_language = answer.detected_language;
+                    answer.text
+                });
+
+                early_state.lock().unwrap().vision_done = true;
+                drop(busy);
+
                 match vision_res {
+                    // Not copied, saved or retranslated
+                    Ok(_) if no_text_found => {
+                        unsafe {
+                            PostMessageW(overlay_hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+                            if !hide_overlay {
+                                ShowWindow(primary_hwnd, SW_SHOW);
+                            }
+                        }
+                        if !hide_overlay {
+                            update_window_text(primary_hwnd, no_text_message(&ui_language));
+                            if let Some(margin) = expanded_by {
+                                set_stats_line(primary_hwnd, Some(expanded_region_message(&ui_language, margin)));
+                            }
+                        }
+                    }
                     Ok(vision_text) => {
+                        let streamed_text = vision_text.clone();
+                        let vision_text = if strip_preamble { without_preamble(&vision_text) } else { vision_text };
+                        let (vision_text, study_card) = if study { super::study::apply(vision_text) } else { (vision_text, None) };
+                        let unreflowed = reflow.map(|_| vision_text.clone());
+                        let vision_text = match reflow { Some(r) => r.apply(&vision_text), None => vision_text };
                         // Ensure window is shown if it wasn't already (non-streaming or fast response)
                         if !first_chunk_received {
                              unsafe {
@@ -232,130 +633,110 @@ pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HW
                                 }
                             }
                             if !hide_overlay {
-                                // Apply markdown cleaning for chat mode
-                                let display_text = if is_chat_mode {
-                                    super::utils::clean_markdown_for_display(&vision_text)
+                                if is_chat_mode {
+                                    update_window_markdown(primary_hwnd, &vision_text);
                                 } else {
-                                    vision_text.clone()
-                                };
-                                update_window_text(primary_hwnd, &display_text);
+                                    update_window_text(primary_hwnd, &vision_text);
+                                }
+                            }
+                        } else if vision_text != streamed_text && !hide_overlay {
+                            // The streamed preamble comes off (and lines are joined) once the answer is complete
+                            update_window_text(primary_hwnd, &vision_text);
+                        }
+                        if let Some(original) = unreflowed.filter(|t| *t != vision_text) {
+                            set_unreflowed_text(primary_hwnd, Some(original));
+                        }
+                        if study_card.is_some() && !hide_overlay {
+                            set_study_card(primary_hwnd, study_card.clone());
+                        }
+
+                        if show_model_badge && !hide_overlay {
+                            set_model_badge(primary_hwnd, Some(model_badge(&badge_model, started.elapsed())));
+                        }
+                        let notes: Vec<String> = detected_language.iter().map(|lang| detected_language_badge(lang))
+                            .chain(expanded_by.map(|margin| expanded_region_message(&ui_language, margin)))
+                            .collect();
+                        if !notes.is_empty() && !hide_overlay {
+                            set_stats_line(primary_hwnd, Some(notes.join(" · ")));
+                        }
+
+                        // --- STEP 1.4: LOW-CONFIDENCE HINT (non-blocking) ---
+                        if ocr_quality_check && !hide_overlay {
+                            let hint = assess_ocr_quality(&vision_text, crop_w, crop_h);
+                            if hint.is_low() {
+                                log::info!("OCR quality hint: {:?}", hint);
+                                set_warning_badge(primary_hwnd, Some(low_confidence_message(&ui_language).to_string()));
                             }
                         }
 
                         // --- STEP 1.5: MAIN AUTO COPY ---
+                        // SAME_AS_DETECTED targets become the detected language, or are left out of STEP 2
+                        let resolved: Vec<Option<String>> = retranslate_setup.targets.iter()
+                            .map(|t| resolve_target(&t.target_lang, detected_language.as_deref(), answer_language.as_deref()))
+                            .collect();
+                        let in_target: Vec<bool> = resolved.iter().map(Option::is_none).collect();
+                        let retranslates = do_retranslate && !vision_text.trim().is_empty() && in_target.contains(&false);
+                        // The notice reports the last copy: the retranslation's if it auto-copies
+                        let notice = completion.clone().filter(|_| !(retranslates && retranslate_auto_copy && !in_target[0]));
                         if auto_copy && !vision_text.trim().is_empty() {
-                            let vt = vision_text.clone();
-                            std::thread::spawn(move || {
-                                std::thread::sleep(std::time::Duration::from_millis(100));
-                                copy_to_clipboard(&vt, HWND(0));
-                            });
+                            copy_timing.copy(primary_hwnd, vision_text.clone(), notice);
+                        } else if let Some(notice) = notice {
+                            notice.success(&vision_text);
                         }
                         
-                        // --- STEP 1.6: SAVE TO HISTORY ---
+                        // --- STEP 1.6: SAVE TO HISTORY (and the notes file) ---
+                        if let Some(note) = &note {
+                            note.set_result(&vision_text);
+                        }
                         if !vision_text.trim().is_empty() {
                             let entry = crate::history::HistoryEntry {
                                 id: crate::history::generate_entry_id(),
                                 preset_name: preset_name_for_history.clone(),
                                 preset_type: "image".to_string(),
-                                input_summary: input_summary.clone(),
+                                input_summary: match &group_id {
+                                    Some(_) => format!("{} · {}", input_summary, badge_model),
+                                    None => input_summary.clone(),
+                                },
                                 result_text: vision_text.clone(),
                                 retrans_text: None, // Will be updated if retranslation happens
                                 timestamp: crate::history::get_current_timestamp(),
                                 is_favorite: false,
+                                tags: Vec::new(),
+                                segments: Vec::new(),
+                                group_id: group_id.clone(),
+                                study: study_card.clone(),
+                                detected_language: detected_language.clone(),
                             };
                             crate::history::add_history_entry(entry);
                         }
 
+                        // --- STEP 1.7: CHAT FOLLOW-UPS ---
+                        if let Some(chat) = chat_session {
+                            chat.start(primary_hwnd, &vision_text);
+                        }
+
                         // --- STEP 2: RETRANSLATE (Optional) ---
-                         if do_retranslate && !vision_text.trim().is_empty() {
-                             // Create Secondary Window
-                             // We need to do this on the UI thread? No, create_result_window handles it?
-                             // Actually create_result_window creates a window on the CURRENT thread.
-                             // The current thread is this worker thread? 
-                             // NO. `create_result_window` creates a window. Windows must be pumped on the thread they are created.
-                             // This worker thread DOES NOT pump messages. The PARENT thread (spawned above) pumps messages for `primary_hwnd`.
-                             // So `primary_hwnd` was created on the parent thread.
-                             // If we want a secondary window, it ALSO needs to be created on the parent thread to share the message loop.
-                             // Solution: We cannot easily create the secondary window from THIS worker thread if we want the parent loop to handle it.
-                             // However, we can use `PostMessage` to signal the parent thread to create it? 
-                             // Or, simplified: Just spawn a NEW thread/loop for the secondary window?
-                             // Yes, spawning a new thread for the secondary window is easiest and isolates it.
-                             
-                             let vision_text_for_retrans = vision_text.clone();
-                             let groq_key_for_retrans = groq_api_key.clone();
-                             let gemini_key_for_retrans = gemini_api_key.clone();
-                             let openrouter_key_for_retrans = openrouter_api_key.clone();
-                             
-                             // Spawn Secondary UI Thread
-                             std::thread::spawn(move || {
-                                 let secondary_hwnd = create_result_window(rect, WindowType::Secondary);
-                                 super::result::link_windows(primary_hwnd, secondary_hwnd);
-                                 if !hide_overlay {
-                                     unsafe { ShowWindow(secondary_hwnd, SW_SHOW); }
-                                     update_window_text(secondary_hwnd, "");
-                                 }
-
-                                 // API Call for Retranslation (Blocking in this UI thread? No, need another worker or just block since it's simple text?)
-                                 // Better to block here? If we block, the window won't repaint.
-                                 // So spawn a worker for text API too.
-                                 
-                                 std::thread::spawn(move || {
-                                     let acc_text = Arc::new(Mutex::new(String::new()));
-                                     let acc_text_clone = acc_text.clone();
-                                     
-                                     // Resolve text model
-                                     let tm_config = crate::model_config::get_model_by_id(&retranslate_model_id);
-                                     let (tm_name, tm_provider) = match tm_config {
-                                         Some(m) => (m.full_name, m.provider),
-                                         None => ("openai/gpt-oss-20b".to_string(), "groq".to_string())
-                                     };
-
-                                     let text_res = translate_text_streaming(
-                                         &groq_key_for_retrans,
-                                         &gemini_key_for_retrans, 
-                                         &openrouter_key_for_retrans,
-                                         vision_text_for_retrans,
-                                         retranslate_to,
-                                         tm_name,
-                                         tm_provider, // Pass Provider
-                                         retranslate_streaming_enabled,
-                                         false,
-                                         |chunk| {
-                                             let mut t = acc_text_clone.lock().unwrap();
-                                             t.push_str(chunk);
-                                             if !hide_overlay {
-                                                 update_window_text(secondary_hwnd, &t);
-                                             }
-                                         }
-                                     );
-                                    
-                                    if let Ok(final_text) = text_res {
-                                        if !hide_overlay {
-                                            update_window_text(secondary_hwnd, &final_text);
-                                        }
-                                        if retranslate_auto_copy {
-                                            std::thread::spawn(move || {
-                                                std::thread::sleep(std::time::Duration::from_millis(100));
-                                                copy_to_clipboard(&final_text, HWND(0));
-                                            });
-                                        }
-                                    } else if let Err(e) = text_res {
-                                         if !hide_overlay {
-                                            update_window_text(secondary_hwnd, &format!("Error: {}", e));
-                                         }
-                                    }
-                                });
-
-                                // Message Loop for Secondary
-                                unsafe {
-                                    let mut msg = MSG::default();
-                                    while GetMessageW(&mut msg, None, 0, 0).into() {
-                                        TranslateMessage(&msg);
-                                        DispatchMessageW(&msg);
-                                        if !IsWindow(secondary_hwnd).as_bool() { break; }
-                                    }
-                                }
-                            });
+                        // Retranslation windows live on their own UI thread (see RetranslateSetup::open);
+                        // this worker thread does not pump messages.
+                        for (target, _) in retranslate_setup.targets.iter().zip(&in_target).filter(|(_, skip)| **skip) {
+                            log::info!("No language to retranslate into for {} (detected: {:?}), skipped", target.target_lang, detected_language);
+                        }
+                        if retranslates {
+                            let mut st = early_state.lock().unwrap();
+                            let jobs: Vec<_> = match st.jobs.take() {
+                                // The draft opened a window for every target
+                                Some(jobs) => jobs.into_iter().zip(&in_target).filter(|(_, skip)| !**skip).map(|(tx, _)| tx).collect(),
+                                None => retranslate_setup.resolved(&resolved).open(primary_hwnd),
+                            };
+                            // Don't bill twice when the draft already covered the final vision text
+                            let job = match &st.draft_source {
+                                // The draft was taken from the streamed lines, before any reflow
+                                Some(draft) if !grew_meaningfully(&reflow.map_or_else(|| draft.clone(), |r| r.apply(draft)), &vision_text) => RetranslateJob::PromoteDraft(vision_text.clone()),
+                                _ => RetranslateJob::Final(vision_text.clone()),
+                            };
+                            for tx in jobs {
+                                let _ = tx.send(job.clone());
+                            }
                         }
                     }
                     Err(e) => {
@@ -365,8 +746,10 @@ pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HW
                                 ShowWindow(primary_hwnd, SW_SHOW);
                             }
                         }
-                        let error_msg = get_error_message(&e.to_string(), &ui_language);
-                        update_window_text(primary_hwnd, &error_msg);
+                        if let Some(completion) = &completion {
+                            completion.failure(&e);
+                        }
+                        retry.offer(primary_hwnd, &e);
                     }
                 }
             });
@@ -379,6 +762,7 @@ pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HW
                     DispatchMessageW(&msg);
                     if !IsWindow(primary_hwnd).as_bool() { break; }
                 }
+                super::result::destroy_thread_windows();
             }
         });
 
@@ -387,30 +771,960 @@ pub fn process_and_close(app: Arc<Mutex<AppState>>, rect: RECT, overlay_hwnd: HW
     }
 }
 
+// --- CHAT FOLLOW-UPS ---
+
+/// `chat_with_image_context` only talks to Gemini; non-Gemini chat presets use this for follow-ups
+const FOLLOW_UP_FALLBACK_MODEL: &str = "gemini-flash-latest";
+
+static FOLLOW_UP_BUSY: AtomicBool = AtomicBool::new(false);
+
+/// Conversation behind a chat-mode result window: Enter on the window asks another
+/// question, answered with the retained capture plus the turns so far
+#[derive(Clone)]
+struct ChatFollowUp {
+    conversation: Arc<Mutex<crate::conversation::Conversation>>,
+    first_turn: String, // Prompt + first question, sent alongside the image
+    gemini_api_key: String,
+    model: String,
+    streaming_enabled: bool,
+    ui_language: String,
+}
+
+impl ChatFollowUp {
+    /// Record the first answer and hook the window's Enter key
+    fn start(self, hwnd: HWND, answer: &str) {
+        {
+            let mut conv = self.conversation.lock().unwrap();
+            let has_image = conv.image_base64.is_some();
+            conv.add_message("user", &self.first_turn, has_image);
+            conv.add_message("assistant", answer, false);
+        }
+        let chat = self.clone();
+        set_follow_up_action(hwnd, Arc::new(move || {
+            // The chat popup uses global state: one follow-up at a time
+            if FOLLOW_UP_BUSY.swap(true, Ordering::SeqCst) { return; }
+            let chat = chat.clone();
+            std::thread::spawn(move || {
+                chat.ask(hwnd);
+                FOLLOW_UP_BUSY.store(false, Ordering::SeqCst);
+            });
+        }));
+    }
+
+    fn ask(&self, hwnd: HWND) {
+        let mut rect = RECT::default();
+        unsafe { GetWindowRect(hwnd, &mut rect); }
+        let question = match super::chat_input::show_chat_input_popup(rect) {
+            Some(q) => q,
+            None => return,
+        };
+        let (image, history) = {
+            let conv = self.conversation.lock().unwrap();
+            (conv.image_base64.clone(), conv.get_api_messages())
+        };
+
+        let mut answer = String::new();
+        let res = crate::api::chat_with_image_context(
+            &self.gemini_api_key,
+            image.as_deref(),
+            history,
+            question.clone(),
+            self.model.clone(),
+            self.streaming_enabled,
+            |chunk| {
+                answer.push_str(chunk);
+                update_window_markdown(hwnd, &answer);
+            },
+        );
+        match res {
+            Ok(text) => {
+                update_window_markdown(hwnd, &text);
+                let mut conv = self.conversation.lock().unwrap();
+                conv.add_message("user", &question, false);
+                conv.add_message("assistant", &text, false);
+            }
+            Err(e) => {
+                update_window_request_error(hwnd, &e, &self.ui_language);
+            }
+        }
+    }
+}
+
+// --- OCR QUALITY HEURISTIC ---
+
+/// Rough guess at whether a vision result is usable
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum QualityHint {
+    Ok,
+    /// Lots of replacement / control / private-use characters
+    Garbled,
+    /// The same token over and over (model got stuck in a loop)
+    Repetitive,
+    /// Far more text than could physically fit in the captured region
+    Disproportionate,
+}
+
+impl QualityHint {
+    pub fn is_low(self) -> bool { self != QualityHint::Ok }
+}
+
+/// Cheap heuristics on the OCR/translation output. Pure, so it can't block or fail the pipeline.
+pub fn assess_ocr_quality(text: &str, crop_w: u32, crop_h: u32) -> QualityHint {
+    let visible: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if visible.is_empty() { return QualityHint::Ok; }
+
+    // 1. Unusual code points
+    let suspicious = visible.iter().filter(|&&c| {
+        c == '\u{FFFD}'
+            || c.is_control()
+            || ('\u{E000}'..='\u{F8FF}').contains(&c) // Private use
+            || ('\u{FFF0}'..='\u{FFFF}').contains(&c) // Specials
+    }).count();
+    if visible.len() >= 4 && suspicious as f32 / visible.len() as f32 > 0.15 {
+        return QualityHint::Garbled;
+    }
+
+    // 2. Repeated tokens: one token dominating, or a long run of the same token
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    if tokens.len() >= 12 {
+        let mut counts: std::collections::HashMap<&str, usize> = std::collections::HashMap::new();
+        for t in &tokens { *counts.entry(t).or_insert(0) += 1; }
+        let most_common = counts.values().copied().max().unwrap_or(0);
+
+        let mut longest_run = 1;
+        let mut run = 1;
+        for pair in tokens.windows(2) {
+            run = if pair[0] == pair[1] { run + 1 } else { 1 };
+            longest_run = longest_run.max(run);
+        }
+
+        if most_common as f32 / tokens.len() as f32 > 0.5 || longest_run >= 8 {
+            return QualityHint::Repetitive;
+        }
+    }
+
+    // 3. Length vs. area: even tiny legible glyphs need ~40 px² each
+    let max_plausible_chars = (crop_w as u64 * crop_h as u64) / 40;
+    if visible.len() > 200 && visible.len() as u64 > max_plausible_chars * 2 {
+        return QualityHint::Disproportionate;
+    }
+
+    QualityHint::Ok
+}
+
+fn low_confidence_message(lang: &str) -> &'static str {
+    match lang {
+        "vi" => "⚠ Độ tin cậy thấp — hãy thử chọn vùng lớn hơn",
+        "ko" => "⚠ 신뢰도 낮음 — 더 큰 영역을 선택해 보세요",
+        _ => "⚠ Low confidence — try a larger region",
+    }
+}
+
+// --- EXPANDED REGION RETRY ---
+
+/// `Preset.expand_on_empty`: when the model finds no text in the selection (usually
+/// drawn a little off), the selection grown by `margin` is sent once more before giving up
+struct ExpandOnEmpty {
+    margin: u32,
+    image: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>, // The grown crop of the screenshot
+}
+
+impl ExpandOnEmpty {
+    /// Chat answers and live captions have their own ways of finding nothing
+    fn applies_to(preset: &crate::config::Preset) -> bool {
+        let chat = preset.preset_type == "chat" || preset.enable_chat_mode;
+        preset.expand_on_empty.map_or(false, |m| m > 0) && preset.preset_type == "image" && !chat && !preset.live_mode
+    }
+
+    /// None when the preset doesn't retry, takes several frames, or the crop can't grow
+    /// (it already fills the screenshot, or only the region itself was captured)
+    fn for_capture(preset: &crate::config::Preset, img: &image::ImageBuffer<image::Rgba<u8>, Vec<u8>>, crop: (u32, u32, u32, u32)) -> Option<Self> {
+        if !Self::applies_to(preset) || preset.multi_frame_capture.map_or(false, |n| n > 1) {
+            return None;
+        }
+        let margin = preset.expand_on_empty?;
+        let (x, y, w, h) = expand_crop(crop, margin, img.dimensions());
+        ((x, y, w, h) != crop).then(|| ExpandOnEmpty { margin, image: img.view(x, y, w, h).to_image() })
+    }
+}
+
+/// `crop` (x, y, w, h) grown by `margin` on every side, clamped to an image of `bounds`
+fn expand_crop((x, y, w, h): (u32, u32, u32, u32), margin: u32, bounds: (u32, u32)) -> (u32, u32, u32, u32) {
+    let left = x.saturating_sub(margin);
+    let top = y.saturating_sub(margin);
+    let right = (x + w).saturating_add(margin).min(bounds.0);
+    let bottom = (y + h).saturating_add(margin).min(bounds.1);
+    (left, top, right.saturating_sub(left), bottom.saturating_sub(top))
+}
+
+/// Whether a vision result found no text: nothing came back, or the `NO_TEXT_INSTRUCTION` sentinel
+fn found_no_text(result: &anyhow::Result<VisionAnswer>) -> bool {
+    match result {
+        Ok(answer) => is_no_text(&answer.text),
+        Err(e) => matches!(ApiError::of(e), Some(ApiError::EmptyResponse)),
+    }
+}
+
+fn is_no_text(text: &str) -> bool {
+    let text = text.trim();
+    text.is_empty() || text.eq_ignore_ascii_case("[NO_TEXT]")
+}
+
+fn no_text_message(lang: &str) -> &'static str {
+    match lang {
+        "vi" => "Không tìm thấy chữ trong vùng đã chọn",
+        "ko" => "선택한 영역에서 텍스트를 찾지 못했습니다",
+        _ => "No text found in the selected region",
+    }
+}
+
+fn expanded_region_message(lang: &str, margin: u32) -> String {
+    match lang {
+        "vi" => format!("⤢ Đã mở rộng vùng chọn thêm {} px", margin),
+        "ko" => format!("⤢ 영역을 {} px 넓혀서 다시 시도함", margin),
+        _ => format!("⤢ Region expanded by {} px", margin),
+    }
+}
+
+// --- MULTI-FRAME CAPTURE ---
+
+/// Appended to the prompt when all frames go out in a single request
+const MULTI_FRAME_INSTRUCTION: &str = "\n\nThe images are consecutive frames of the same screen region, captured over about one second. The text may scroll, fade or change between frames. Combine the text of all frames into one result in reading order, include text that appears in only some frames, and do not repeat text that appears in several frames.";
+const MULTI_FRAME_WINDOW_MS: u64 = 1000;
+const MULTI_FRAME_MAX: u32 = 8;
+
+/// Grabs `count` frames of the crop over ~1s, starting with the selection snapshot `first`.
+/// Frames identical to the previous one are dropped.
+fn capture_frames(
+    first: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>,
+    area: RECT, // The crop in virtual-screen coordinates
+    count: u32,
+) -> Vec<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>> {
+    let count = count.min(MULTI_FRAME_MAX);
+    let interval = std::time::Duration::from_millis(MULTI_FRAME_WINDOW_MS / (count.max(2) - 1) as u64);
+    let mut frames = vec![first];
+
+    for _ in 1..count {
+        std::thread::sleep(interval);
+        let frame = match crate::capture::capture_rect(area) {
+            Ok(img) => img,
+            Err(e) => {
+                log::warn!("Multi-frame capture failed: {}", e);
+                continue;
+            }
+        };
+        if frames.last() != Some(&frame) {
+            frames.push(frame);
+        }
+    }
+
+    log::info!("Multi-frame capture: {} distinct frame(s) of {}", frames.len(), count);
+    frames
+}
+
+/// Lowercase alphanumerics only, so punctuation/spacing jitter doesn't count as new text
+pub(crate) fn normalize_for_dedup(s: &str) -> String {
+    s.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase()
+}
+
+/// Upper bound on `dedup_history`, whatever the config says
+const MAX_DEDUP_HISTORY: usize = 50;
+
+/// The last N normalized Live Vision lines, so subtitles that cycle back after a few
+/// lines aren't shown (and retranslated) again
+struct DedupHistory {
+    lines: std::collections::VecDeque<String>,
+    capacity: usize,
+}
+
+impl DedupHistory {
+    fn new(capacity: usize) -> Self {
+        let capacity = capacity.clamp(1, MAX_DEDUP_HISTORY);
+        Self { lines: std::collections::VecDeque::with_capacity(capacity), capacity }
+    }
+
+    /// True if `text` matches a remembered line; otherwise remembers it (dropping the oldest)
+    fn check_and_remember(&mut self, text: &str) -> bool {
+        let norm = normalize_for_dedup(text);
+        if self.lines.contains(&norm) {
+            return true;
+        }
+        if self.lines.len() == self.capacity {
+            self.lines.pop_front();
+        }
+        self.lines.push_back(norm);
+        false
+    }
+}
+
+/// Merges per-frame results: every distinct line once, in first-seen order
+pub fn merge_frame_texts(texts: &[String]) -> String {
+    let mut seen = std::collections::HashSet::new();
+    let mut lines = Vec::new();
+    for text in texts {
+        for line in text.lines() {
+            let norm = normalize_for_dedup(line);
+            if norm.is_empty() || norm == "notext" { continue; }
+            if seen.insert(norm) {
+                lines.push(line.trim_end());
+            }
+        }
+    }
+    lines.join("\n")
+}
+
+/// Longest run of words checked when trimming a live audio chunk's overlap
+const MAX_OVERLAP_WORDS: usize = 12;
+
+/// Drops the words at the start of `next` that repeat the end of `prev` (consecutive
+/// live audio chunks overlap by a few hundred ms, so boundary words come back twice)
+fn strip_chunk_overlap<'a>(prev: &str, next: &'a str) -> &'a str {
+    let prev_words: Vec<String> = prev.split_whitespace().map(normalize_for_dedup).collect();
+    let next_words: Vec<(usize, &str)> = next.split_whitespace()
+        .map(|w| (w.as_ptr() as usize - next.as_ptr() as usize, w))
+        .collect();
+    let max = MAX_OVERLAP_WORDS.min(prev_words.len()).min(next_words.len());
+    for k in (1..=max).rev() {
+        let tail = &prev_words[prev_words.len() - k..];
+        let matches = next_words[..k].iter().zip(tail)
+            .all(|((_, w), p)| !p.is_empty() && normalize_for_dedup(w) == *p);
+        if matches {
+            return match next_words.get(k) {
+                Some((offset, _)) => &next[*offset..],
+                None => "",
+            };
+        }
+    }
+    next
+}
+
+// --- RETRANSLATION WINDOWS ---
+
+const EARLY_RETRANSLATE_IDLE: std::time::Duration = std::time::Duration::from_millis(400);
+const DRAFT_LABEL: &str = "✎ draft…\n";
+
+/// One retranslation window: target language, text model and whether to auto-copy its result
+#[derive(Clone)]
+struct RetranslateTarget {
+    target_lang: String,
+    model_id: String,
+    auto_copy: Option<AutoCopy>,
+    notice: Option<CompletionNotice>, // Given after this window's auto copy, or on its failure
+    note: Option<Arc<RunNote>>, // The capture's notes file block, which waits for this window
+    strip_preamble: bool,
+}
+
+/// Everything needed to open the retranslation window(s) for a capture
+#[derive(Clone)]
+struct RetranslateSetup {
+    rect: RECT,
+    keys: (String, String, String),
+    targets: Vec<RetranslateTarget>, // [0] = Secondary, [1] = Tertiary
+    streaming_enabled: bool,
+    hide_overlay: bool,
+    copy_format: CopyFormat,
+    copy_source: String,
+    font_family: Option<String>,
+}
+
+#[derive(Clone)]
+enum RetranslateJob {
+    /// Intermediate translation of a partial vision text, shown with a "draft…" label
+    Draft(String),
+    /// Translation of the complete vision text
+    Final(String),
+    /// The vision text didn't change meaningfully since the draft: reuse it (falls back to Final if the draft failed)
+    PromoteDraft(String),
+}
+
+/// Early-retranslate bookkeeping shared between the vision stream, the idle watcher and completion
+#[derive(Default)]
+struct EarlyRetranslate {
+    jobs: Option<Vec<Sender<RetranslateJob>>>,
+    draft_source: Option<String>,
+    last_chunk_at: Option<std::time::Instant>,
+    vision_done: bool,
+}
+
+impl EarlyRetranslate {
+    fn start_draft(&mut self, setup: &RetranslateSetup, primary_hwnd: HWND, text: String) {
+        let jobs = setup.open(primary_hwnd);
+        for tx in &jobs {
+            let _ = tx.send(RetranslateJob::Draft(text.clone()));
+        }
+        self.jobs = Some(jobs);
+        self.draft_source = Some(text);
+    }
+}
+
+impl RetranslateSetup {
+    /// The same setup with each target's language as `resolve_target` gave it; `None` ones are left out
+    fn resolved(&self, languages: &[Option<String>]) -> Self {
+        let targets = self.targets.iter().zip(languages)
+            .filter_map(|(t, lang)| lang.clone().map(|target_lang| RetranslateTarget { target_lang, ..t.clone() }))
+            .collect();
+        RetranslateSetup { targets, ..self.clone() }
+    }
+
+    /// Spawn a UI thread hosting one linked result window per target. Returns a job queue per window;
+    /// dropping the senders ends the workers once their queued jobs are done.
+    fn open(&self, primary_hwnd: HWND) -> Vec<Sender<RetranslateJob>> {
+        let setup = self.clone();
+        let (senders, receivers): (Vec<_>, Vec<_>) = setup.targets.iter().map(|_| channel::<RetranslateJob>()).unzip();
+
+        std::thread::spawn(move || {
+            let mut hwnds: Vec<HWND> = Vec::new();
+            for (i, (target, rx)) in setup.targets.into_iter().zip(receivers).enumerate() {
+                let win_type = if i == 0 { WindowType::Secondary } else { WindowType::Tertiary };
+                let hwnd = create_result_window(setup.rect, win_type);
+                set_copy_format(hwnd, setup.copy_format, setup.copy_source.clone());
+                set_font_family(hwnd, setup.font_family.clone());
+                set_text_model(hwnd, target.model_id.clone(), setup.streaming_enabled);
+                // Link with the primary and with each other so dismissing any of them animates all
+                link_windows(primary_hwnd, hwnd);
+                for other in &hwnds { link_windows(*other, hwnd); }
+                if !setup.hide_overlay {
+                    unsafe { ShowWindow(hwnd, SW_SHOW); }
+                    update_window_text(hwnd, "");
+                }
+                hwnds.push(hwnd);
+
+                // API calls run on their own workers so these windows keep repainting
+                spawn_retranslate_worker(hwnd, setup.keys.clone(), target, setup.streaming_enabled, setup.hide_overlay, rx);
+            }
+
+            // Message Loop for the retranslation windows
+            unsafe {
+                let mut msg = MSG::default();
+                while GetMessageW(&mut msg, None, 0, 0).into() {
+                    TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                    if !hwnds.iter().any(|h| IsWindow(*h).as_bool()) { break; }
+                }
+                super::result::destroy_thread_windows();
+            }
+        });
+
+        senders
+    }
+}
+
+/// Send a capture's frames. A provider that takes several images gets them in one
+/// request with the multi-frame instruction; others get one request per frame, and the
+/// lines are merged like live vision does (shown once, when all frames are done).
+fn request_frames(
+    (groq_api_key, gemini_api_key, openrouter_api_key): (&str, &str, &str),
+    prompt: String,
+    model: String,
+    provider: String,
+    frames: &[image::ImageBuffer<image::Rgba<u8>, Vec<u8>>],
+    streaming_enabled: bool,
+    use_json_format: bool,
+    sampling: Sampling,
+    mut on_chunk: impl FnMut(&str),
+) -> anyhow::Result<VisionAnswer> {
+    if frames.len() > 1 && !supports_multi_image(&provider) {
+        let mut texts = Vec::new();
+        let mut first_err = None;
+        for frame in frames {
+            match translate_image_streaming(
+                groq_api_key,
+                gemini_api_key,
+                openrouter_api_key,
+                prompt.clone(),
+                model.clone(),
+                provider.clone(),
+                frame,
+                false,
+                use_json_format,
+                sampling,
+                |_| {}
+            ) {
+                Ok(text) => texts.push(text),
+                Err(e) => { first_err.get_or_insert(e); }
+            }
+        }
+        let merged = merge_frame_texts(&texts);
+        if merged.is_empty() {
+            Err(first_err.unwrap_or_else(|| anyhow::anyhow!("No content received from API")))
+        } else {
+            on_chunk(&merged);
+            Ok(VisionAnswer { text: merged, detected_language: None })
+        }
+    } else {
+        let prompt = if frames.len() > 1 { format!("{}{}", prompt, MULTI_FRAME_INSTRUCTION) } else { prompt };
+        translate_images_answer(
+            groq_api_key,
+            gemini_api_key,
+            openrouter_api_key,
+            prompt,
+            model,
+            provider,
+            frames,
+            streaming_enabled,
+            use_json_format,
+            sampling,
+            on_chunk,
+        )
+    }
+}
+
+// --- AUTO COPY ---
+
+/// When and how an auto-copied result is delivered
+#[derive(Clone, Copy)]
+struct AutoCopy {
+    delay: std::time::Duration,
+    on_close: bool, // When the window is dismissed, so the copy can't catch it mid-stream
+    type_out: Option<std::time::Duration>, // Typed into the focused app with this delay per character
+}
+
+impl AutoCopy {
+    fn from_preset(preset: &crate::config::Preset) -> Self {
+        Self {
+            delay: std::time::Duration::from_millis(preset.auto_copy_delay_ms.min(5000) as u64),
+            // A hidden window is never dismissed by the user
+            on_close: preset.copy_on_close && !preset.hide_overlay && preset.output_mode == OutputMode::Clipboard,
+            type_out: (preset.output_mode == OutputMode::TypeOut)
+                .then(|| std::time::Duration::from_millis(preset.type_out_delay_ms.min(1000) as u64)),
+        }
+    }
+
+    /// Copy (or type out) the finished result shown in `hwnd`. `notice` is given once
+    /// the text is actually on the clipboard (or typed), after the delay.
+    fn copy(self, hwnd: HWND, text: String, notice: Option<CompletionNotice>) {
+        if self.on_close {
+            set_copy_on_close(hwnd, true);
+            return;
+        }
+        // Typed text stays plain; the wrapping is for pasting into chat apps
+        let text = if self.type_out.is_some() { text } else { format_for_copy(hwnd, &text, None) };
+        std::thread::spawn(move || {
+            std::thread::sleep(self.delay);
+            let delivered = match self.type_out {
+                Some(char_delay) => { crate::overlay::type_out::type_text(&text, char_delay); true }
+                None => copy_to_clipboard(&text, HWND(0)),
+            };
+            if let Some(notice) = notice.filter(|_| delivered) {
+                notice.success(&text);
+            }
+        });
+    }
+}
+
+// --- RETRY ---
+
+/// A vision request kept with its result window, so a failure can be retried from the
+/// window's Retry button without selecting the region again
+#[derive(Clone)]
+struct VisionRetry {
+    keys: (String, String, String),
+    prompt: String,
+    model: String,
+    provider: String,
+    frames: Arc<Vec<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>>,
+    streaming_enabled: bool,
+    use_json_format: bool,
+    sampling: Sampling,
+    markdown: bool, // Chat answers
+    strip_preamble: bool,
+    reflow: Option<super::reflow::Reflow>,
+    study: bool, // Show the answer's study fields (Preset.study_layout)
+    auto_copy: Option<AutoCopy>,
+    note: Option<Arc<RunNote>>, // Template: each retry writes its own block (`RunNote::again`)
+    ui_language: String,
+    preset_name: String,
+    input_summary: String,
+}
+
+impl VisionRetry {
+    /// Show the error in the window, with a Retry button that sends the request again
+    fn offer(self, hwnd: HWND, error: &anyhow::Error) {
+        update_window_request_error(hwnd, error, &self.ui_language);
+        // The same key would fail the same way
+        if !ApiError::of(error).map_or(true, ApiError::is_retryable) {
+            return;
+        }
+        set_retry_action(hwnd, Arc::new(move || {
+            let retry = self.clone();
+            std::thread::spawn(move || retry.run(hwnd));
+        }));
+    }
+
+    fn show(&self, hwnd: HWND, text: &str) {
+        if self.markdown {
+            update_window_markdown(hwnd, text);
+        } else {
+            update_window_text(hwnd, text);
+        }
+    }
+
+    fn run(self, hwnd: HWND) {
+        log::info!("Retrying {} with {}", self.preset_name, self.model);
+        update_window_text(hwnd, "");
+        let (groq_api_key, gemini_api_key, openrouter_api_key) = &self.keys;
+        let mut text = String::new();
+        let result = request_frames(
+            (groq_api_key, gemini_api_key, openrouter_api_key),
+            self.prompt.clone(),
+            self.model.clone(),
+            self.provider.clone(),
+            &self.frames,
+            self.streaming_enabled,
+            self.use_json_format,
+            self.sampling,
+            |chunk| {
+                text.push_str(chunk);
+                self.show(hwnd, &text);
+            },
+        );
+
+        match result {
+            Ok(VisionAnswer { text: result_text, detected_language }) => {
+                let result_text = if self.strip_preamble { without_preamble(&result_text) } else { result_text };
+                let (result_text, study_card) = if self.study { super::study::apply(result_text) } else { (result_text, None) };
+                let unreflowed = self.reflow.map(|_| result_text.clone());
+                let result_text = match self.reflow { Some(r) => r.apply(&result_text), None => result_text };
+                self.show(hwnd, &result_text);
+                if let Some(original) = unreflowed.filter(|t| *t != result_text) {
+                    set_unreflowed_text(hwnd, Some(original));
+                }
+                if study_card.is_some() {
+                    set_study_card(hwnd, study_card.clone());
+                }
+                if let Some(lang) = &detected_language {
+                    set_stats_line(hwnd, Some(detected_language_badge(lang)));
+                }
+                if result_text.trim().is_empty() { return; }
+                if let Some(copy) = self.auto_copy {
+                    copy.copy(hwnd, result_text.clone(), None);
+                }
+                if let Some(note) = self.note.as_ref().map(|n| n.again()) {
+                    note.set_result(&result_text);
+                }
+                crate::history::add_history_entry(crate::history::HistoryEntry {
+                    id: crate::history::generate_entry_id(),
+                    preset_name: self.preset_name,
+                    preset_type: "image".to_string(),
+                    input_summary: self.input_summary,
+                    result_text,
+                    retrans_text: None,
+                    timestamp: crate::history::get_current_timestamp(),
+                    is_favorite: false,
+                    tags: Vec::new(),
+                    segments: Vec::new(),
+                    group_id: None,
+                    study: study_card,
+                    detected_language,
+                });
+            }
+            Err(e) => self.offer(hwnd, &e),
+        }
+    }
+}
+
+// --- COMPARE MODE ---
+
+/// The capture sent to `Preset.compare_model` as well, shown in a window beside the
+/// primary one. Saved to history on its own, with the primary entry's `group_id`.
+struct CompareRun {
+    rect: RECT,
+    keys: (String, String, String),
+    model: crate::model_config::ModelConfig,
+    prompt: String,
+    frames: Arc<Vec<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>>,
+    streaming_enabled: bool,
+    use_json_format: bool,
+    sampling: Sampling,
+    markdown: bool, // Chat answers, shown like the primary's
+    strip_preamble: bool,
+    reflow: Option<super::reflow::Reflow>,
+    study: bool, // Show the answer's study fields (Preset.study_layout)
+    copy_format: CopyFormat,
+    font_family: Option<String>,
+    text_model: (String, bool), // The preset's retranslation model and streaming, for the globe button
+    ui_language: String,
+    preset_name: String,
+    input_summary: String,
+    group_id: Option<String>,
+}
+
+impl CompareRun {
+    /// Open the window on its own UI thread and stream the second model's answer into it
+    fn spawn(self, primary_hwnd: HWND) {
+        std::thread::spawn(move || {
+            let hwnd = create_result_window(self.rect, WindowType::SecondaryExplicit);
+            set_copy_format(hwnd, self.copy_format, copy_source(&self.preset_name));
+            set_font_family(hwnd, self.font_family.clone());
+            set_text_model(hwnd, self.text_model.0.clone(), self.text_model.1);
+            link_windows(primary_hwnd, hwnd);
+            unsafe { ShowWindow(hwnd, SW_SHOW); }
+            update_window_text(hwnd, "");
+
+            // The API call runs on a worker so the window keeps repainting
+            std::thread::spawn(move || self.run(hwnd));
+
+            unsafe {
+                let mut msg = MSG::default();
+                while GetMessageW(&mut msg, None, 0, 0).into() {
+                    TranslateMessage(&msg);
+                    DispatchMessageW(&msg);
+                    if !IsWindow(hwnd).as_bool() { break; }
+                }
+                super::result::destroy_thread_windows();
+            }
+        });
+    }
+
+    fn show(&self, hwnd: HWND, text: &str) {
+        if self.markdown {
+            update_window_markdown(hwnd, text);
+        } else {
+            update_window_text(hwnd, text);
+        }
+    }
+
+    fn run(self, hwnd: HWND) {
+        let started = Instant::now();
+        let (groq_api_key, gemini_api_key, openrouter_api_key) = &self.keys;
+        let mut text = String::new();
+        let result = request_frames(
+            (groq_api_key, gemini_api_key, openrouter_api_key),
+            self.prompt.clone(),
+            self.model.full_name.clone(),
+            self.model.provider.clone(),
+            &self.frames,
+            self.streaming_enabled,
+            self.use_json_format,
+            self.sampling,
+            |chunk| {
+                text.push_str(chunk);
+                self.show(hwnd, &text);
+            },
+        );
+
+        match result {
+            Ok(VisionAnswer { text: result_text, detected_language }) => {
+                let result_text = if self.strip_preamble { without_preamble(&result_text) } else { result_text };
+                let (result_text, study_card) = if self.study { super::study::apply(result_text) } else { (result_text, None) };
+                let unreflowed = self.reflow.map(|_| result_text.clone());
+                let result_text = match self.reflow { Some(r) => r.apply(&result_text), None => result_text };
+                self.show(hwnd, &result_text);
+                if let Some(original) = unreflowed.filter(|t| *t != result_text) {
+                    set_unreflowed_text(hwnd, Some(original));
+                }
+                if study_card.is_some() {
+                    set_study_card(hwnd, study_card.clone());
+                }
+                if let Some(lang) = &detected_language {
+                    set_stats_line(hwnd, Some(detected_language_badge(lang)));
+                }
+                set_model_badge(hwnd, Some(model_badge(&self.model.full_name, started.elapsed())));
+                if !result_text.trim().is_empty() {
+                    crate::history::add_history_entry(crate::history::HistoryEntry {
+                        id: crate::history::generate_entry_id(),
+                        preset_name: self.preset_name,
+                        preset_type: "image".to_string(),
+                        input_summary: format!("{} · {}", self.input_summary, self.model.full_name),
+                        result_text,
+                        retrans_text: None,
+                        timestamp: crate::history::get_current_timestamp(),
+                        is_favorite: false,
+                        tags: Vec::new(),
+                        segments: Vec::new(),
+                        group_id: self.group_id,
+                        study: study_card,
+                        detected_language,
+                    });
+                }
+            }
+            Err(e) => {
+                log::warn!("Compare model {} failed: {}", self.model.full_name, e);
+                update_window_request_error(hwnd, &e, &self.ui_language);
+            }
+        }
+    }
+}
+
+/// Process retranslation jobs for `hwnd` in order on a worker thread, streaming chunks into the window.
+fn spawn_retranslate_worker(
+    hwnd: HWND,
+    keys: (String, String, String),
+    target: RetranslateTarget,
+    streaming_enabled: bool,
+    hide_overlay: bool,
+    jobs: Receiver<RetranslateJob>,
+) {
+    std::thread::spawn(move || {
+        let (groq_key, gemini_key, openrouter_key) = keys;
+        
+        // Resolve text model
+        let tm_config = crate::model_config::get_model_by_id(&target.model_id);
+        let (tm_name, tm_provider) = match tm_config {
+            Some(m) => (m.full_name, m.provider),
+            None => ("openai/gpt-oss-20b".to_string(), "groq".to_string())
+        };
+
+        let translate = |source_text: String, label: &str| {
+            let mut acc_text = String::new();
+            translate_text_streaming(
+                &groq_key,
+                &gemini_key, 
+                &openrouter_key,
+                source_text,
+                target.target_lang.clone(),
+                tm_name.clone(),
+                tm_provider.clone(), // Pass Provider
+                streaming_enabled,
+                false,
+                |chunk| {
+                    acc_text.push_str(chunk);
+                    if !hide_overlay {
+                        update_window_text(hwnd, &format!("{}{}", label, acc_text));
+                    }
+                }
+            )
+        };
+
+        let finish = |final_text: String| {
+            let final_text = if target.strip_preamble { without_preamble(&final_text) } else { final_text };
+            if !hide_overlay {
+                update_window_text(hwnd, &final_text);
+            }
+            if let Some(note) = &target.note {
+                note.add_retranslation(&final_text);
+            }
+            if let Some(copy) = target.auto_copy {
+                copy.copy(hwnd, final_text, target.notice.clone());
+            }
+        };
+
+        let mut last_draft: Option<String> = None;
+        for job in jobs {
+            let (source_text, is_draft) = match job {
+                RetranslateJob::Draft(text) => (text, true),
+                RetranslateJob::Final(text) => (text, false),
+                RetranslateJob::PromoteDraft(text) => match last_draft.take() {
+                    Some(draft) => { finish(draft); continue; }
+                    None => (text, false),
+                },
+            };
+
+            match translate(source_text, if is_draft { DRAFT_LABEL } else { "" }) {
+                Ok(text) if is_draft => {
+                    if !hide_overlay {
+                        update_window_text(hwnd, &format!("{}{}", DRAFT_LABEL, text));
+                    }
+                    last_draft = Some(text);
+                }
+                Ok(text) => finish(text),
+                Err(e) => {
+                    if !hide_overlay {
+                        update_window_error(hwnd, &format!("Error: {}", e));
+                    }
+                    if let (Some(notice), false) = (&target.notice, is_draft) {
+                        notice.failure(&e);
+                    }
+                }
+            }
+        }
+    });
+}
+
+// --- PREAMBLE ---
+
+/// How a boilerplate first line starts (lowercase): "Here is the translation:", "Sure! ..."
+const PREAMBLE_OPENERS: &[&str] = &[
+    "here is", "here's", "here are", "below is", "the following is",
+    "sure", "certainly", "of course", "okay",
+    "đây là", "dưới đây là", "다음은",
+];
+
+/// A line that is only one of these and a colon ("Translation:") labels the text after it.
+/// Other lines starting with them ("Translation services:") may be the text itself.
+const PREAMBLE_LABELS: &[&str] = &[
+    "translation", "translated text", "extracted text", "transcription", "bản dịch", "văn bản", "번역", "번역문",
+];
+
+/// What a preamble line announces; an opener without one of these may be the text itself
+const PREAMBLE_SUBJECTS: &[&str] = &[
+    "translat", "text", "transcri", "result", "bản dịch", "văn bản", "nội dung", "번역", "텍스트",
+];
+
+const MAX_PREAMBLE_CHARS: usize = 100;
+
+/// `Preset.strip_preamble`: the text without a first line like "Here is the translation:".
+/// Only a short line with a known opener and subject is removed, and only when it ends
+/// with ':' or ends a sentence before a blank line, or a bare label such as "Translation:";
+/// anything else is left as it is.
+fn without_preamble(text: &str) -> String {
+    let body = text.trim_start();
+    let (first, rest) = body.split_once('\n').unwrap_or((body, ""));
+    let line = first.trim();
+    let lower = line.to_lowercase();
+    let rest_lines = rest.trim_start_matches(['\r', '\n']);
+
+    let ends_with_colon = line.ends_with([':', '：']);
+    let announces = line.chars().count() <= MAX_PREAMBLE_CHARS
+        && PREAMBLE_OPENERS.iter().any(|o| lower.starts_with(o))
+        && PREAMBLE_SUBJECTS.iter().any(|s| lower.contains(s))
+        && (ends_with_colon
+            || (line.ends_with(['.', '!']) && rest.trim_start_matches([' ', '\t', '\r']).starts_with('\n')));
+    let is_label = ends_with_colon && PREAMBLE_LABELS.contains(&lower.trim_end_matches([':', '：']).trim_end());
+    let is_preamble = announces || is_label;
+    if is_preamble && !rest_lines.trim().is_empty() {
+        rest_lines.to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+/// True when the streamed text so far ends a sentence (terminal punctuation, incl. CJK)
+fn ends_with_sentence(text: &str) -> bool {
+    matches!(text.trim_end().chars().last(), Some('.' | '!' | '?' | '…' | '。' | '！' | '？'))
+}
+
+/// Whether the final vision text differs enough from the draft's source to justify a second retranslation
+fn grew_meaningfully(draft_source: &str, final_text: &str) -> bool {
+    let draft = draft_source.trim();
+    let full = final_text.trim();
+    if draft == full { return false; }
+    match full.strip_prefix(draft) {
+        // Only trailing whitespace/punctuation was added
+        Some(extra) => extra.chars().filter(|c| c.is_alphanumeric()).count() >= 3,
+        None => true,
+    }
+}
+
 pub fn show_audio_result(preset: crate::config::Preset, text: String, rect: RECT, retrans_rect: Option<RECT>) {
     let hide_overlay = preset.hide_overlay;
     let auto_copy = preset.auto_copy;
+    let copy_timing = AutoCopy::from_preset(&preset);
     let retranslate = preset.retranslate && retrans_rect.is_some();
-    let retranslate_to = preset.retranslate_to.clone();
+    let dismiss_on_outside_click = preset.dismiss_on_outside_click;
     let retranslate_model_id = preset.retranslate_model.clone();
     let retranslate_streaming_enabled = preset.retranslate_streaming_enabled;
     let retranslate_auto_copy = preset.retranslate_auto_copy;
     let preset_name_for_history = preset.name.clone();
+    let font_family = preset.font_family.clone();
     
-    let (groq_key, gemini_key, openrouter_key) = {
+    let (groq_key, gemini_key, openrouter_key, ui_language) = {
         let app = crate::APP.lock().unwrap();
-        (app.config.api_key.clone(), app.config.gemini_api_key.clone(), app.config.openrouter_api_key.clone())
+        (app.config.api_key.clone(), app.config.gemini_api_key.clone(), app.config.openrouter_api_key.clone(), app.config.ui_language.clone())
     };
+    let retranslate_to = preset.retranslate_target(&ui_language);
     
     std::thread::spawn(move || {
         let primary_hwnd = create_result_window(rect, WindowType::Primary);
+        set_font_family(primary_hwnd, font_family.clone());
+        if dismiss_on_outside_click { set_dismiss_on_outside_click(primary_hwnd, true); }
         if !hide_overlay {
             unsafe { ShowWindow(primary_hwnd, SW_SHOW); }
             update_window_text(primary_hwnd, &text);
         }
         
         if auto_copy {
-            copy_to_clipboard(&text, HWND(0));
+            copy_timing.copy(primary_hwnd, text.clone(), None);
         }
         
         // Save to history
@@ -424,6 +1738,11 @@ pub fn show_audio_result(preset: crate::config::Preset, text: String, rect: RECT
                 retrans_text: None,
                 timestamp: crate::history::get_current_timestamp(),
                 is_favorite: false,
+                tags: Vec::new(),
+                segments: Vec::new(),
+                group_id: None,
+                study: None,
+                detected_language: None,
             };
             crate::history::add_history_entry(entry);
 
@@ -436,6 +1755,7 @@ pub fn show_audio_result(preset: crate::config::Preset, text: String, rect: RECT
                 
                 std::thread::spawn(move || {
                     let secondary_hwnd = create_result_window(rect_r, WindowType::SecondaryExplicit);
+                    set_font_family(secondary_hwnd, font_family);
                     link_windows(primary_hwnd, secondary_hwnd);
                     if !hide_overlay {
                         unsafe { ShowWindow(secondary_hwnd, SW_SHOW); }
@@ -477,10 +1797,7 @@ pub fn show_audio_result(preset: crate::config::Preset, text: String, rect: RECT
                             update_window_text(secondary_hwnd, &final_text);
                         }
                         if retranslate_auto_copy {
-                             std::thread::spawn(move || {
-                                std::thread::sleep(std::time::Duration::from_millis(100));
-                                copy_to_clipboard(&final_text, HWND(0));
-                            });
+                            copy_timing.copy(secondary_hwnd, final_text, None);
                         }
                     });
 
@@ -492,6 +1809,7 @@ pub fn show_audio_result(preset: crate::config::Preset, text: String, rect: RECT
                             DispatchMessageW(&msg);
                             if !IsWindow(secondary_hwnd).as_bool() { break; }
                         }
+                        super::result::destroy_thread_windows();
                     }
                 });
             }
@@ -504,13 +1822,14 @@ pub fn show_audio_result(preset: crate::config::Preset, text: String, rect: RECT
                 DispatchMessageW(&msg);
                 if !IsWindow(primary_hwnd).as_bool() { break; }
             }
+            super::result::destroy_thread_windows();
         }
     });
 }
 
 pub fn process_audio_post_record(
     preset: crate::config::Preset,
-    wav_data: Vec<u8>,
+    clip: AudioClip,
     overlay_hwnd: HWND,
 ) {
     let screen_w = unsafe { GetSystemMetrics(SM_CXSCREEN) };
@@ -546,34 +1865,50 @@ pub fn process_audio_post_record(
         (app.config.api_key.clone(), app.config.gemini_api_key.clone(), app.config.openrouter_api_key.clone(), app.config.ui_language.clone())
     };
 
-    let mut final_prompt = preset.prompt.clone();
-    for (key, value) in &preset.language_vars {
-        let pattern = format!("{{{}}}", key);
-        final_prompt = final_prompt.replace(&pattern, value);
-    }
-    final_prompt = final_prompt.replace("{language}", &preset.selected_language);
+    let final_prompt = preset.render_prompt();
 
     let streaming_enabled = preset.streaming_enabled;
     let hide_overlay = preset.hide_overlay;
     let auto_copy = preset.auto_copy;
+    let copy_timing = AutoCopy::from_preset(&preset);
+    let note = RunNote::for_preset(&preset);
+    let dismiss_on_outside_click = preset.dismiss_on_outside_click;
+    let whisper_language = preset.whisper_language.as_deref().and_then(crate::config::whisper_language_code).map(str::to_string);
+    let whisper_translate = preset.whisper_translate;
+    let strip_preamble = preset.strip_preamble;
+    let copy_format = preset.copy_format;
+    let font_family = preset.font_family.clone();
+    let completion = CompletionNotice::for_preset(&preset, &ui_language);
     
     // Retranslate settings
     let retranslate = preset.retranslate && retranslate_rect.is_some();
     let retranslate_streaming_enabled = preset.retranslate_streaming_enabled;
     let retranslate_auto_copy = preset.retranslate_auto_copy;
-    let retranslate_to = preset.retranslate_to.clone();
+    let retranslate_to = preset.retranslate_target(&ui_language);
     let retranslate_model_id = preset.retranslate_model.clone();
+    let retranslate_srt = preset.retranslate_srt;
 
     // History
     let preset_name = preset.name.clone();
+    let input_summary = match &clip.source {
+        Some(_) => format!("Audio file: {}", clip.file_name),
+        None => "Audio recording".to_string(),
+    };
 
     // --- Spawn UI Thread ---
     std::thread::spawn(move || {
         let primary_hwnd = create_result_window(rect, WindowType::Primary);
+        set_copy_format(primary_hwnd, copy_format, copy_source(&preset_name));
+        set_font_family(primary_hwnd, font_family.clone());
+        set_text_model(primary_hwnd, retranslate_model_id.clone(), retranslate_streaming_enabled);
+        if dismiss_on_outside_click { set_dismiss_on_outside_click(primary_hwnd, true); }
         
         let secondary_hwnd = if retranslate {
             if let Some(r) = retranslate_rect {
                 let hwnd = create_result_window(r, WindowType::SecondaryExplicit);
+                set_copy_format(hwnd, copy_format, copy_source(&preset_name));
+                set_font_family(hwnd, font_family.clone());
+                set_text_model(hwnd, retranslate_model_id.clone(), retranslate_streaming_enabled);
                 link_windows(primary_hwnd, hwnd);
                 Some(hwnd)
             } else { None }
@@ -607,69 +1942,125 @@ pub fn process_audio_post_record(
         std::thread::spawn(move || {
             let accumulated_text = Arc::new(Mutex::new(String::new()));
             let acc_text_clone = accumulated_text.clone();
+            // Timed segments (Whisper only), for SRT export
+            let segments: Arc<Mutex<Vec<crate::history::SubtitleSegment>>> = Arc::new(Mutex::new(Vec::new()));
+            // Spinner by the cursor until the first chunk (shown even with hide_overlay)
+            let mut busy = Some(super::busy_indicator::start());
             
             // Logic Split: Gemini (Streaming) vs Whisper (Batch)
+            // Files over the upload limit (or in other containers) are converted and split into chunks
             let res: anyhow::Result<String> = if provider == "google" {
                  if gemini_api_key.trim().is_empty() {
-                    Err(anyhow::anyhow!("NO_API_KEY"))
+                    Err(ApiError::NoApiKey.into())
                 } else {
-                    transcribe_audio_gemini(
-                        &gemini_api_key,
-                        final_prompt,
-                        model_name,
-                        wav_data,
-                        |chunk| {
-                            let mut t = acc_text_clone.lock().unwrap();
-                            if t.is_empty() {
-                                // Clear "Processing..." on first chunk
-                                if !hide_overlay { update_window_text(primary_hwnd, ""); }
-                            }
-                            t.push_str(chunk);
-                            if streaming_enabled && !hide_overlay {
-                                update_window_text(primary_hwnd, &t);
+                    prepare_for_upload(clip, crate::audio_file::GEMINI_MAX_BYTES, crate::audio_file::gemini_accepts).and_then(|chunks| {
+                        let mut parts = Vec::new();
+                        for chunk in &chunks {
+                            if !parts.is_empty() {
+                                acc_text_clone.lock().unwrap().push('\n');
                             }
+                            parts.push(transcribe_audio_gemini(
+                                &gemini_api_key,
+                                final_prompt.clone(),
+                                model_name.clone(),
+                                chunk,
+                                |chunk| {
+                                    busy.take();
+                                    let mut t = acc_text_clone.lock().unwrap();
+                                    if t.is_empty() {
+                                        // Clear "Processing..." on first chunk
+                                        if !hide_overlay { update_window_text(primary_hwnd, ""); }
+                                    }
+                                    t.push_str(chunk);
+                                    if streaming_enabled && !hide_overlay {
+                                        update_window_text(primary_hwnd, &t);
+                                    }
+                                }
+                            )?);
                         }
-                    )
+                        Ok(parts.join("\n"))
+                    })
                 }
             } else {
                  // GROQ / WHISPER
                  if groq_api_key.trim().is_empty() {
-                    Err(anyhow::anyhow!("NO_API_KEY"))
+                    Err(ApiError::NoApiKey.into())
                 } else {
-                    let r = upload_audio_to_whisper(&groq_api_key, &model_name, wav_data);
-                    r
+                    prepare_for_upload(clip, crate::audio_file::WHISPER_MAX_BYTES, crate::audio_file::whisper_accepts).and_then(|chunks| {
+                        let mut texts = Vec::new();
+                        for chunk in &chunks {
+                            let t = upload_audio_to_whisper(&groq_api_key, &model_name, chunk, whisper_language.as_deref(), whisper_translate)?;
+                            // Chunk timestamps are relative to the chunk
+                            segments.lock().unwrap().extend(t.segments.into_iter().map(|mut seg| {
+                                seg.start_ms += chunk.offset_ms;
+                                seg.end_ms += chunk.offset_ms;
+                                seg
+                            }));
+                            texts.push(t.text.trim().to_string());
+                        }
+                        Ok(texts.join(" "))
+                    })
                 }
             };
+            drop(busy);
 
             match res {
                 Ok(full_text) => {
+                    let full_text = if strip_preamble { without_preamble(&full_text) } else { full_text };
                     let mut t = acc_text_clone.lock().unwrap();
                     *t = full_text.clone(); 
                     if !hide_overlay {
                         update_window_text(primary_hwnd, &full_text);
                     }
                     
+                    // The notice reports the last copy: the retranslation's if it auto-copies
+                    let notice = completion.clone().filter(|_| !(retranslate_auto_copy && secondary_hwnd.is_some()));
                     if auto_copy {
-                         copy_to_clipboard(&full_text, HWND(0));
+                        copy_timing.copy(primary_hwnd, full_text.clone(), notice);
+                    } else if let Some(notice) = notice {
+                        notice.success(&full_text);
+                    }
+                    if let Some(note) = &note {
+                        note.set_result(&full_text);
                     }
                     
                     // History
+                    let entry_id = crate::history::generate_entry_id();
                     if !full_text.trim().is_empty() {
                         let entry = crate::history::HistoryEntry {
-                            id: crate::history::generate_entry_id(),
+                            id: entry_id.clone(),
                             preset_name: preset_name.clone(),
                             preset_type: "audio".to_string(),
-                            input_summary: "Audio recording".to_string(),
+                            input_summary: input_summary.clone(),
                             result_text: full_text.clone(),
                             retrans_text: None,
                             timestamp: crate::history::get_current_timestamp(),
                             is_favorite: false,
+                            tags: Vec::new(),
+                            segments: segments.lock().unwrap().clone(),
+                            group_id: None,
+                            study: None,
+                            detected_language: None,
                         };
                         crate::history::add_history_entry(entry);
                     }
+
+                    // SRT export button on the result window
+                    if !segments.lock().unwrap().is_empty() {
+                        let segments = segments.clone();
+                        let base_name = format!("{}_{}", preset_name, entry_id);
+                        set_export_action(primary_hwnd, Arc::new(move || {
+                            match crate::history::export_segments_srt(&base_name, &segments.lock().unwrap()) {
+                                Ok(path) => { let _ = open::that(path.parent().unwrap_or(&path)); }
+                                Err(e) => log::error!("SRT export failed - {}", e),
+                            }
+                        }));
+                    }
                     
                     // Retranslate API
                     if let Some(sec_hwnd) = secondary_hwnd {
+                        let note = note.clone();
+                        let notice = completion.clone().filter(|_| retranslate_auto_copy);
                         std::thread::spawn(move || {
                              let acc_retrans = Arc::new(Mutex::new(String::new()));
                              let acc_retrans_clone = acc_retrans.clone();
@@ -685,9 +2076,9 @@ pub fn process_audio_post_record(
                                 &gemini_api_key,
                                 &openrouter_api_key,
                                 full_text.clone(),
-                                retranslate_to,
-                                tm_name,
-                                tm_provider,
+                                retranslate_to.clone(),
+                                tm_name.clone(),
+                                tm_provider.clone(),
                                 retranslate_streaming_enabled,
                                 false,
                                 |chunk| {
@@ -700,14 +2091,43 @@ pub fn process_audio_post_record(
                             );
                             
                             let final_retrans = acc_retrans_clone.lock().unwrap().clone();
+                            let final_retrans = if strip_preamble { without_preamble(&final_retrans) } else { final_retrans };
                             if !hide_overlay {
                                  update_window_text(sec_hwnd, &final_retrans);
                             }
+                            if let (Some(note), true) = (&note, text_res.is_ok()) {
+                                note.add_retranslation(&final_retrans);
+                            }
                             if retranslate_auto_copy {
-                                 std::thread::spawn(move || {
-                                    std::thread::sleep(std::time::Duration::from_millis(100));
-                                    copy_to_clipboard(&final_retrans, HWND(0));
-                                });
+                                if let (Some(notice), Err(e)) = (&notice, &text_res) {
+                                    notice.failure(e);
+                                }
+                                copy_timing.copy(sec_hwnd, final_retrans, notice.filter(|_| text_res.is_ok()));
+                            }
+
+                            // Translated SRT: translate segment-by-segment so the timings still match
+                            if retranslate_srt && text_res.is_ok() {
+                                let mut segs = segments.lock().unwrap().clone();
+                                for seg in segs.iter_mut() {
+                                    if let Ok(tr) = translate_text_streaming(
+                                        &groq_api_key,
+                                        &gemini_api_key,
+                                        &openrouter_api_key,
+                                        seg.text.clone(),
+                                        retranslate_to.clone(),
+                                        tm_name.clone(),
+                                        tm_provider.clone(),
+                                        false,
+                                        false,
+                                        |_| {}
+                                    ) {
+                                        seg.translation = Some(tr.trim().to_string());
+                                    }
+                                }
+                                if !segs.is_empty() {
+                                    *segments.lock().unwrap() = segs.clone();
+                                    crate::history::set_segments(&entry_id, segs);
+                                }
                             }
                          });
                          
@@ -719,12 +2139,15 @@ pub fn process_audio_post_record(
                                 DispatchMessageW(&msg);
                                 if !IsWindow(sec_hwnd).as_bool() { break; }
                             }
+                            super::result::destroy_thread_windows();
                         }
                     }
                 }
                 Err(e) => {
-                     let error_msg = get_error_message(&e.to_string(), &ui_language);
-                     if !hide_overlay { update_window_text(primary_hwnd, &error_msg); }
+                     if !hide_overlay { update_window_request_error(primary_hwnd, &e, &ui_language); }
+                     if let Some(completion) = &completion {
+                         completion.failure(&e);
+                     }
                 }
             }
         });
@@ -736,19 +2159,46 @@ pub fn process_audio_post_record(
                 DispatchMessageW(&msg);
                 if !IsWindow(primary_hwnd).as_bool() { break; }
             }
+            super::result::destroy_thread_windows();
         }
     });
 }
 
 pub struct LiveSession {
-    pub tx: Sender<Vec<u8>>,
+    tx: LiveSender<(Instant, Vec<u8>)>,
+    /// Set to the session cap (secs) when the recording loop auto-stopped, shown once the session ends
+    pub stop_notice: Arc<Mutex<Option<u32>>>,
+}
+
+impl LiveSession {
+    /// Queue a finished audio chunk (WAV), stamped so its wait in the queue can be measured
+    pub fn send(&self, wav: Vec<u8>) {
+        if self.tx.push((Instant::now(), wav)) {
+            log::debug!("Live Audio: queue full, dropped the oldest chunk");
+        }
+    }
+}
+
+/// Log newly evicted chunks/frames (the queue was full while the API was busy)
+fn note_dropped(stats: &mut crate::metrics::LiveStats, total: usize, session: &str, unit: &str) {
+    let new = total - stats.dropped();
+    if new > 0 {
+        log::info!("{}: dropped {} {} while the API was busy ({} total)", session, new, unit, total);
+        stats.set_dropped(total);
+    }
+}
+
+/// Refresh the latency line at the bottom of a live result window
+fn refresh_stats_hud(hwnd: HWND, stats: &crate::metrics::LiveStats) {
+    set_stats_line(hwnd, Some(stats.hud_line()));
+    unsafe { windows::Win32::Graphics::Gdi::InvalidateRect(hwnd, None, false); }
 }
 
 pub fn start_live_translation_session(
     preset: crate::config::Preset,
     overlay_hwnd: HWND,
 ) -> LiveSession {
-    let (tx, rx) = channel::<Vec<u8>>();
+    let (tx, rx) = live_queue::<(Instant, Vec<u8>)>(LIVE_QUEUE_BOUND);
 
     let screen_w = unsafe { GetSystemMetrics(SM_CXSCREEN) };
     let screen_h = unsafe { GetSystemMetrics(SM_CYSCREEN) };
@@ -783,24 +2233,44 @@ pub fn start_live_translation_session(
         (app.config.api_key.clone(), app.config.gemini_api_key.clone(), app.config.openrouter_api_key.clone(), app.config.ui_language.clone())
     };
 
-    let mut final_prompt = preset.prompt.clone();
-    for (key, value) in &preset.language_vars {
-        let pattern = format!("{{{}}}", key);
-        final_prompt = final_prompt.replace(&pattern, value);
-    }
-    final_prompt = final_prompt.replace("{language}", &preset.selected_language);
+    let final_prompt = preset.render_prompt();
 
     let streaming_enabled = preset.streaming_enabled;
     let hide_overlay = preset.hide_overlay;
     let retranslate = preset.retranslate && retranslate_rect.is_some();
     let retranslate_streaming_enabled = preset.retranslate_streaming_enabled;
-    let retranslate_to = preset.retranslate_to.clone();
+    let retranslate_to = preset.retranslate_target(&ui_language);
     let skip_frames = preset.skip_frames; // Frame skipping (queue drain) setting
     let retranslate_model_id = preset.retranslate_model.clone();
+    let whisper_language = preset.whisper_language.as_deref().and_then(crate::config::whisper_language_code).map(str::to_string);
+    let whisper_translate = preset.whisper_translate;
+    let preset_name = preset.name.clone();
+    let show_stats = preset.show_live_stats && !hide_overlay;
+    let completion = CompletionNotice::for_preset(&preset, &ui_language);
+    let chunks_overlap = preset.live_chunk_overlap_ms > 0;
+    let font_family = preset.font_family.clone();
+    let stop_notice: Arc<Mutex<Option<u32>>> = Arc::new(Mutex::new(None));
+    let stop_notice_session = stop_notice.clone();
 
     // Spawn Window Thread
     std::thread::spawn(move || {
         let primary_hwnd = create_result_window(rect, WindowType::Primary);
+        set_font_family(primary_hwnd, font_family.clone());
+
+        // Complete session log (the on-screen buffers below are trimmed); exportable while running
+        let session_start = std::time::Instant::now();
+        let segments: Arc<Mutex<Vec<crate::history::TranscriptSegment>>> = Arc::new(Mutex::new(Vec::new()));
+        {
+            let segments = segments.clone();
+            let preset_name = preset_name.clone();
+            set_export_action(primary_hwnd, Arc::new(move || {
+                let snapshot = segments.lock().unwrap().clone();
+                match crate::history::export_session(&preset_name, &snapshot) {
+                    Ok(path) => { let _ = open::that(path.parent().unwrap_or(&path)); }
+                    Err(e) => log::error!("Live Audio: Session export failed - {}", e),
+                }
+            }));
+        }
         
         // In Live Mode, we DO NOT close the recording overlay, because it contains the Stop button!
         // The recording overlay will close itself when the recording loop finishes.
@@ -813,6 +2283,7 @@ pub fn start_live_translation_session(
         let secondary_hwnd = if retranslate {
             let rect_sec = retranslate_rect.unwrap();
             let sec_hwnd = create_result_window(rect_sec, WindowType::SecondaryExplicit);
+            set_font_family(sec_hwnd, font_family.clone());
             link_windows(primary_hwnd, sec_hwnd);
             if !hide_overlay {
                 unsafe { ShowWindow(sec_hwnd, SW_SHOW); }
@@ -825,34 +2296,43 @@ pub fn start_live_translation_session(
 
         // Spawn Processor Thread
         std::thread::spawn(move || {
+            let _worker = crate::shutdown::worker(); // Writes the session's history entry at the end
             let full_transcript = Arc::new(Mutex::new(String::new()));
             let full_translation = Arc::new(Mutex::new(String::new()));
+            let mut stats = crate::metrics::LiveStats::default();
+            let mut last_chunk_text = String::new(); // Raw text of the previous chunk, for overlap trimming
             
             // Loop for chunks
-            while let Ok(mut wav_data) = rx.recv() {
+            while let Some((mut captured_at, mut wav_data)) = rx.recv() {
                 // LATENCY OPTIMIZATION: Drain queue to get the LATEST audio chunk (if skip_frames is enabled)
                 // Skip old audio chunks to stay in sync with real-time
                 if skip_frames {
                     let mut skipped_count = 0;
-                    while let Ok(next_chunk) = rx.try_recv() {
+                    while let Some((next_at, next_chunk)) = rx.try_recv() {
+                        captured_at = next_at;
                         wav_data = next_chunk;
                         skipped_count += 1;
                     }
                     if skipped_count > 0 {
                         log::info!("Live Audio: Skipped {} old chunk(s) to stay in sync", skipped_count);
+                        stats.add_skipped(skipped_count);
                     }
                 }
+                stats.record_capture(captured_at.elapsed(), rx.len());
+                note_dropped(&mut stats, rx.dropped(), "Live Audio", "chunk(s)");
 
                 // 1. Transcribe
                 log::info!("Live Audio: Processing chunk ({} bytes)", wav_data.len());
+                let clip = AudioClip::wav(wav_data);
+                let api_start = Instant::now();
                 let res: anyhow::Result<String> = if provider == "google" {
-                    if gemini_api_key.trim().is_empty() { Err(anyhow::anyhow!("NO_API_KEY")) }
+                    if gemini_api_key.trim().is_empty() { Err(ApiError::NoApiKey.into()) }
                     else {
                         transcribe_audio_gemini(
                             &gemini_api_key,
                             final_prompt.clone(),
                             model_name.clone(),
-                            wav_data,
+                            &clip,
                             |_chunk| { 
                                 // Intermediate stream update? 
                                 // Hard with accumulation. Maybe just wait for final per chunk?
@@ -861,19 +2341,39 @@ pub fn start_live_translation_session(
                         )
                     }
                 } else {
-                    if groq_api_key.trim().is_empty() { Err(anyhow::anyhow!("NO_API_KEY")) }
+                    if groq_api_key.trim().is_empty() { Err(ApiError::NoApiKey.into()) }
                     else {
-                        upload_audio_to_whisper(&groq_api_key, &model_name, wav_data)
+                        upload_audio_to_whisper(&groq_api_key, &model_name, &clip, whisper_language.as_deref(), whisper_translate).map(|t| t.text)
                     }
                 };
 
+                stats.record_api(api_start.elapsed());
+                if show_stats { refresh_stats_hud(primary_hwnd, &stats); }
+
                 match &res {
                     Ok(text) => log::info!("Live Audio: Transcription SUCCESS ({} chars)", text.len()),
-                    Err(e) => log::error!("Live Audio: Transcription FAILED - {}", e),
+                    Err(e) => {
+                        log::error!("Live Audio: Transcription FAILED - {}", e);
+                        if let Some(completion) = &completion { completion.failure(e); }
+                    }
                 }
 
+                // Overlapping chunks repeat the boundary words; keep them once
+                let res = res.map(|text| {
+                    if !chunks_overlap { return text; }
+                    let trimmed = strip_chunk_overlap(&last_chunk_text, &text).to_string();
+                    last_chunk_text = text;
+                    trimmed
+                });
+
                 if let Ok(text) = res {
                     if !text.trim().is_empty() {
+                        segments.lock().unwrap().push(crate::history::TranscriptSegment {
+                            at_ms: session_start.elapsed().as_millis() as u64,
+                            text: text.clone(),
+                            translation: None,
+                        });
+
                         let mut full = full_transcript.lock().unwrap();
                         
                         // LIMIT TEXT BUFFER to ~1000 chars (approx. 10-15 sentences)
@@ -896,6 +2396,9 @@ pub fn start_live_translation_session(
                         if !hide_overlay {
                             update_window_text(primary_hwnd, &current_full);
                         }
+                        if let Some(completion) = &completion {
+                            completion.success(&text);
+                        }
 
                         // 2. Retranslate (Chunk-based)
                         if let Some(sec_hwnd) = secondary_hwnd {
@@ -909,7 +2412,8 @@ pub fn start_live_translation_session(
                             
                             // Streaming retranslation for this chunk
                             // We need to append to the existing translation
-                            let _ = translate_text_streaming(
+                            let rt_start = Instant::now();
+                            let rt_res = translate_text_streaming(
                                 &groq_api_key,
                                 &gemini_api_key,
                                 &openrouter_api_key,
@@ -924,6 +2428,10 @@ pub fn start_live_translation_session(
                                     // Just collect full translation
                                 }
                             ).map(|trans_text| {
+                                if let Some(seg) = segments.lock().unwrap().last_mut() {
+                                    seg.translation = Some(trans_text.clone());
+                                }
+
                                 let mut full_trans = full_translation.lock().unwrap();
                                 
                                 // Limit Translation Buffer as well
@@ -942,10 +2450,45 @@ pub fn start_live_translation_session(
                                     update_window_text(sec_hwnd, &full_trans);
                                 }
                             });
+                            if rt_res.is_ok() {
+                                stats.record_retranslate(rt_start.elapsed());
+                                if show_stats { refresh_stats_hud(primary_hwnd, &stats); }
+                            }
                         }
                     }
                 }
             }
+
+            // Session ended (recording stopped): keep the whole conversation in history
+            log::info!("Live Audio: Session stats - {}", stats.summary());
+            let segments = segments.lock().unwrap();
+            if !segments.is_empty() {
+                let (transcript, translation) = crate::history::session_full_text(&segments);
+                let secs = session_start.elapsed().as_secs();
+                crate::history::add_history_entry(crate::history::HistoryEntry {
+                    id: crate::history::generate_entry_id(),
+                    preset_name: preset_name.clone(),
+                    preset_type: "audio".to_string(),
+                    input_summary: format!("Live session {:02}:{:02}", secs / 60, secs % 60),
+                    result_text: transcript,
+                    retrans_text: translation,
+                    timestamp: crate::history::get_current_timestamp(),
+                    is_favorite: false,
+                    tags: Vec::new(),
+                    segments: crate::history::session_to_subtitles(&segments),
+                    group_id: None,
+                    study: None,
+                    detected_language: None,
+                });
+            }
+
+            // Auto-stopped by the session cap: tell the user why it went quiet
+            if let Some(max_secs) = stop_notice_session.lock().unwrap().take() {
+                if !hide_overlay && unsafe { IsWindow(primary_hwnd).as_bool() } {
+                    set_warning_badge(primary_hwnd, Some(session_cap_message(&ui_language, max_secs)));
+                    unsafe { windows::Win32::Graphics::Gdi::InvalidateRect(primary_hwnd, None, false); }
+                }
+            }
         });
 
         // Message Loop
@@ -956,21 +2499,42 @@ pub fn start_live_translation_session(
                 DispatchMessageW(&msg);
                 if !IsWindow(primary_hwnd).as_bool() { break; }
             }
+            super::result::destroy_thread_windows();
         }
     });
 
-    LiveSession { tx }
+    LiveSession { tx, stop_notice }
+}
+
+fn session_cap_message(lang: &str, max_secs: u32) -> String {
+    let mins = max_secs / 60;
+    let secs = max_secs % 60;
+    match lang {
+        "vi" => format!("⏹ Đã tự dừng: đạt giới hạn phiên {}:{:02}", mins, secs),
+        "ko" => format!("⏹ 자동 중지됨: 세션 제한 {}:{:02} 도달", mins, secs),
+        _ => format!("⏹ Stopped automatically: session limit of {}:{:02} reached", mins, secs),
+    }
 }
 
 pub struct LiveVisionSession {
-    pub tx: Sender<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>,
+    tx: LiveSender<(Instant, image::ImageBuffer<image::Rgba<u8>, Vec<u8>>)>,
+}
+
+impl LiveVisionSession {
+    /// Queue a changed frame; `captured_at` is when the screen was grabbed
+    pub fn send(&self, captured_at: Instant, img: image::ImageBuffer<image::Rgba<u8>, Vec<u8>>) {
+        if self.tx.push((captured_at, img)) {
+            log::debug!("Live Vision: queue full, dropped the oldest frame");
+        }
+    }
 }
 
 pub fn start_live_vision_session(
     preset: crate::config::Preset,
+    preset_idx: usize,
     overlay_hwnd: HWND,
 ) -> LiveVisionSession {
-    let (tx, rx) = channel::<image::ImageBuffer<image::Rgba<u8>, Vec<u8>>>();
+    let (tx, rx) = live_queue::<(Instant, image::ImageBuffer<image::Rgba<u8>, Vec<u8>>)>(LIVE_QUEUE_BOUND);
 
     let screen_w = unsafe { GetSystemMetrics(SM_CXSCREEN) };
     let screen_h = unsafe { GetSystemMetrics(SM_CYSCREEN) };
@@ -1005,26 +2569,30 @@ pub fn start_live_vision_session(
         (app.config.api_key.clone(), app.config.gemini_api_key.clone(), app.config.openrouter_api_key.clone(), app.config.ui_language.clone())
     };
 
-    let mut final_prompt = preset.prompt.clone();
-    for (key, value) in &preset.language_vars {
-        let pattern = format!("{{{}}}", key);
-        final_prompt = final_prompt.replace(&pattern, value);
-    }
-    final_prompt = final_prompt.replace("{language}", &preset.selected_language);
+    let mut final_prompt = vision_prompt(&preset);
+    super::window_context::push_context(&mut final_prompt, &preset);
     // STRICT INSTRUCTION for Live Mode
-    final_prompt.push_str("\n\nIf the image does not contain any text, output EXACTLY '[NO_TEXT]' and nothing else.");
+    final_prompt.push_str(NO_TEXT_INSTRUCTION);
 
     let streaming_enabled = preset.streaming_enabled;
+    let sampling = Sampling::from_preset(&preset);
     let hide_overlay = preset.hide_overlay;
     let _retranslate = preset.retranslate && retranslate_rect.is_some(); // retranslate flag
+    let preserve_layout = preset.preserve_layout;
     let retranslate_streaming_enabled = preset.retranslate_streaming_enabled;
-    let retranslate_to = preset.retranslate_to.clone();
+    let retranslate_to = preset.retranslate_target(&ui_language);
     let skip_frames = preset.skip_frames; // Frame skipping (queue drain) setting
+    let dedup_history = preset.dedup_history;
+    let font_family = preset.font_family.clone();
     let retranslate_model_id = preset.retranslate_model.clone();
+    let show_stats = preset.show_live_stats && !hide_overlay;
+    let completion = CompletionNotice::for_preset(&preset, &ui_language);
 
     // Spawn Window Thread
     std::thread::spawn(move || {
         let primary_hwnd = create_result_window(rect, WindowType::Primary);
+        set_font_family(primary_hwnd, font_family.clone());
+        if preserve_layout { set_preserve_layout(primary_hwnd, true); }
         
         // In Live Mode (Vision), we keep the overlay (if it's the selection overlay, strictly speaking it closes after selection?)
         // Actually, for Vision, the overlay provided is likely the SELECTION overlay which closes after selection.
@@ -1040,6 +2608,7 @@ pub fn start_live_vision_session(
         let secondary_hwnd = if preset.retranslate && retranslate_rect.is_some() {
             let rect_sec = retranslate_rect.unwrap();
             let sec_hwnd = create_result_window(rect_sec, WindowType::SecondaryExplicit);
+            set_font_family(sec_hwnd, font_family.clone());
             link_windows(primary_hwnd, sec_hwnd);
             if !hide_overlay {
                 unsafe { ShowWindow(sec_hwnd, SW_SHOW); }
@@ -1050,39 +2619,61 @@ pub fn start_live_vision_session(
             None
         };
 
+        // Pause/resume + change region; lives on this thread's message loop
+        let controls_hwnd = super::live_controls::create_live_controls(rect, primary_hwnd, preset_idx);
+
         // Spawn Processor Thread
         std::thread::spawn(move || {
             let full_transcript = Arc::new(Mutex::new(String::new()));
             let full_translation = Arc::new(Mutex::new(String::new()));
             
-            let mut last_processed_text = String::new();
+            let mut dedup = DedupHistory::new(dedup_history);
+            let mut stats = crate::metrics::LiveStats::default();
 
             // Loop for images
-            while let Ok(mut img) = rx.recv() {
+            while let Some((mut captured_at, mut img)) = rx.recv() {
                 // LATENCY OPTIMIZATION: Drain the channel to get the LATEST image (if skip_frames is enabled).
                 // If processing took 1s, and capture is 0.2s, we have 4-5 images queued.
                 // We should skip them and only process the newest one.
                 if skip_frames {
-                    while let Ok(next_img) = rx.try_recv() {
+                    while let Some((next_at, next_img)) = rx.try_recv() {
+                        captured_at = next_at;
                         img = next_img;
+                        stats.add_skipped(1);
                     }
                 }
-
-                // 1. Vision Translation
-                let res: anyhow::Result<String> = translate_image_streaming(
-                    &groq_api_key,
-                    &gemini_api_key,
-                    &openrouter_api_key,
-                    final_prompt.clone(),
-                    model_name.clone(),
-                    provider.clone(),
-                    img,
-                    streaming_enabled, 
-                    false, // json format? assume no for general
-                    |chunk| { 
-                        // Intermediate logging?
-                    }
-                );
+                stats.record_capture(captured_at.elapsed(), rx.len());
+                note_dropped(&mut stats, rx.dropped(), "Live Vision", "frame(s)");
+
+                // 1. Vision Translation (the same frame after a scene cut comes from the cache)
+                let cache_key = crate::translation_cache::image_key(&format!("{}/{}", provider, model_name), &final_prompt, &img);
+                let api_start = Instant::now();
+                let res: anyhow::Result<String> = match crate::translation_cache::lookup(cache_key) {
+                    Some(cached) => Ok(cached),
+                    None => translate_image_streaming(
+                        &groq_api_key,
+                        &gemini_api_key,
+                        &openrouter_api_key,
+                        final_prompt.clone(),
+                        model_name.clone(),
+                        provider.clone(),
+                        &img,
+                        streaming_enabled, 
+                        false, // json format? assume no for general
+                        sampling,
+                        |chunk| { 
+                            // Intermediate logging?
+                        }
+                    ).map(|text| {
+                        crate::translation_cache::store(cache_key, &text);
+                        text
+                    }),
+                };
+                stats.record_api(api_start.elapsed());
+                if show_stats { refresh_stats_hud(primary_hwnd, &stats); }
+                if let (Err(e), Some(completion)) = (&res, &completion) {
+                    completion.failure(e);
+                }
 
                 if let Ok(text) = res {
                     let text_clean = text.trim();
@@ -1098,36 +2689,8 @@ pub fn start_live_vision_session(
                             continue;
                         }
 
-                        // FILTER: Deduplicate (Historical Check)
-                        // Normalize: Lowercase + Alphanumeric only
-                        let normalize = |s: &str| -> String {
-                            s.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase()
-                        };
-                        
-                        let norm_current = normalize(text_clean);
-                        let mut is_dup = false;
-
-                        // Check against last raw processed
-                        if normalize(&last_processed_text) == norm_current {
-                            is_dup = true;
-                        }
-
-                        // Check against current display buffer (max 2 lines)
-                        if !is_dup {
-                             let history_lock = full_transcript.lock().unwrap();
-                             let lines: Vec<&str> = history_lock.split('\n').collect();
-                             for line in lines {
-                                 if normalize(line) == norm_current {
-                                     is_dup = true;
-                                     break;
-                                 }
-                             }
-                        }
-
-                        if is_dup { continue; }
-                        
-                        // Update last processed
-                        last_processed_text = text_clean.to_string();
+                        // FILTER: Deduplicate against the last `dedup_history` lines
+                        if dedup.check_and_remember(text_clean) { continue; }
 
                         // --- UPDATE TRANSCRIPT HISTORY (Max 2 lines) ---
                         let mut full_history_str = full_transcript.lock().unwrap();
@@ -1148,6 +2711,9 @@ pub fn start_live_vision_session(
                         if !hide_overlay {
                             update_window_text(primary_hwnd, &new_full_str);
                         }
+                        if let Some(completion) = &completion {
+                            completion.success(text_clean);
+                        }
 
                         // 2. Retranslate (Chunk-based)
                         if let Some(sec_hwnd) = secondary_hwnd {
@@ -1159,7 +2725,8 @@ pub fn start_live_vision_session(
                                 None => ("openai/gpt-oss-20b".to_string(), "groq".to_string())
                             };
                             
-                            let _ = translate_text_streaming(
+                            let rt_start = Instant::now();
+                            let rt_res = translate_text_streaming(
                                 &groq_api_key,
                                 &gemini_api_key,
                                 &openrouter_api_key,
@@ -1197,10 +2764,16 @@ pub fn start_live_vision_session(
                                     update_window_text(sec_hwnd, &new_trans_str);
                                 }
                             });
+                            if rt_res.is_ok() {
+                                stats.record_retranslate(rt_start.elapsed());
+                                if show_stats { refresh_stats_hud(primary_hwnd, &stats); }
+                            }
                         }
                     }
                 }
             }
+
+            log::info!("Live Vision: Session stats - {}", stats.summary());
         });
 
         // Message Loop
@@ -1211,8 +2784,211 @@ pub fn start_live_vision_session(
                 DispatchMessageW(&msg);
                 if !IsWindow(primary_hwnd).as_bool() { break; }
             }
+            super::result::destroy_thread_windows();
+            if IsWindow(controls_hwnd).as_bool() { DestroyWindow(controls_hwnd); }
         }
     });
 
     LiveVisionSession { tx }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_assess_ocr_quality() {
+        // Normal text
+        assert_eq!(assess_ocr_quality("Hello world, this is a normal sentence.", 400, 60), QualityHint::Ok);
+        assert_eq!(assess_ocr_quality("", 400, 60), QualityHint::Ok);
+        assert_eq!(assess_ocr_quality("こんにちは、世界", 300, 50), QualityHint::Ok);
+
+        // Replacement characters
+        assert_eq!(assess_ocr_quality("\u{FFFD}\u{FFFD}ab\u{FFFD}\u{FFFD}", 400, 60), QualityHint::Garbled);
+
+        // Stuck in a loop
+        let looped = "the ".repeat(30);
+        assert_eq!(assess_ocr_quality(&looped, 800, 600), QualityHint::Repetitive);
+
+        // ~2000 chars out of a 100x20 crop
+        let long = (0..300).map(|i| format!("word{}", i)).collect::<Vec<_>>().join(" ");
+        assert_eq!(assess_ocr_quality(&long, 100, 20), QualityHint::Disproportionate);
+        assert_eq!(assess_ocr_quality(&long, 1920, 1080), QualityHint::Ok);
+    }
+
+    #[test]
+    fn test_merge_frame_texts() {
+        let frames = vec![
+            "Breaking news: markets rally".to_string(),
+            "breaking news -- markets rally!\nOil prices fall".to_string(),
+            "[NO_TEXT]".to_string(),
+            "Oil prices fall\nRain expected tomorrow".to_string(),
+        ];
+        assert_eq!(
+            merge_frame_texts(&frames),
+            "Breaking news: markets rally\nOil prices fall\nRain expected tomorrow"
+        );
+        assert_eq!(merge_frame_texts(&[]), "");
+    }
+
+    #[test]
+    fn test_strip_chunk_overlap() {
+        assert_eq!(strip_chunk_overlap("so we went to the", "to the market, and then"), "market, and then");
+        assert_eq!(strip_chunk_overlap("We went to the market.", "Market. Then home"), "Then home");
+        assert_eq!(strip_chunk_overlap("hello there", "general kenobi"), "general kenobi");
+        assert_eq!(strip_chunk_overlap("", "first chunk"), "first chunk");
+        assert_eq!(strip_chunk_overlap("it was the end", "the end"), "");
+    }
+
+    #[test]
+    fn test_dedup_history() {
+        assert_eq!(normalize_for_dedup("  Hello, World! 42 "), "helloworld42");
+        assert_eq!(normalize_for_dedup("..."), "");
+
+        let mut dedup = DedupHistory::new(3);
+        for line in ["One", "Two", "Three"] {
+            assert!(!dedup.check_and_remember(line));
+        }
+        // Punctuation/case jitter still counts as a repeat
+        assert!(dedup.check_and_remember("one."));
+        assert!(!dedup.check_and_remember("Four"));
+        // "One" fell out of the 3-line window
+        assert!(!dedup.check_and_remember("One"));
+        assert_eq!(dedup.lines.len(), 3);
+
+        assert_eq!(DedupHistory::new(0).capacity, 1);
+        assert_eq!(DedupHistory::new(10_000).capacity, MAX_DEDUP_HISTORY);
+    }
+
+    #[test]
+    fn test_model_badge() {
+        assert_eq!(model_badge("gemini-2.0-flash", std::time::Duration::from_millis(1800)), "gemini-2.0-flash · 1.8s");
+        assert_eq!(model_badge("llama", std::time::Duration::from_millis(40)), "llama · 0.0s");
+    }
+
+    #[test]
+    fn test_auto_copy_timing() {
+        let preset = crate::config::Preset { auto_copy_delay_ms: 250, copy_on_close: true, ..Default::default() };
+        let timing = AutoCopy::from_preset(&preset);
+        assert_eq!(timing.delay, std::time::Duration::from_millis(250));
+        assert!(timing.on_close);
+
+        // Nobody closes a hidden window, so it copies after the delay instead
+        let hidden = crate::config::Preset { hide_overlay: true, ..preset.clone() };
+        assert!(!AutoCopy::from_preset(&hidden).on_close);
+
+        let slow = crate::config::Preset { auto_copy_delay_ms: 60_000, ..preset.clone() };
+        assert_eq!(AutoCopy::from_preset(&slow).delay, std::time::Duration::from_secs(5));
+        assert!(timing.type_out.is_none());
+
+        // Typed out as soon as it's done, never on close
+        let typed = crate::config::Preset { output_mode: OutputMode::TypeOut, type_out_delay_ms: 20, ..preset };
+        let timing = AutoCopy::from_preset(&typed);
+        assert!(!timing.on_close);
+        assert_eq!(timing.type_out, Some(std::time::Duration::from_millis(20)));
+    }
+
+    #[test]
+    fn test_region_too_small() {
+        let rect = |w, h| RECT { left: 100, top: 100, right: 100 + w, bottom: 100 + h };
+        assert!(region_too_small(rect(0, 0), 12));
+        assert!(region_too_small(rect(300, 8), 12));
+        assert!(!region_too_small(rect(12, 12), 12));
+        // Lowered for deliberately small selections
+        assert!(!region_too_small(rect(6, 6), 4));
+    }
+
+    #[test]
+    fn test_without_preamble() {
+        assert_eq!(without_preamble("Here is the translation:\n\nXin chào\nbạn"), "Xin chào\nbạn");
+        assert_eq!(without_preamble("Sure! Here's the extracted text from the image:\n  indented"), "  indented");
+        assert_eq!(without_preamble("Đây là bản dịch:\nHello"), "Hello");
+        assert_eq!(without_preamble("다음은 번역입니다:\n안녕"), "안녕");
+        assert_eq!(without_preamble("Here is the translated text.\n\nHi there."), "Hi there.");
+        assert_eq!(without_preamble("Translation:\nHello"), "Hello");
+        assert_eq!(without_preamble("Bản dịch：\nXin chào"), "Xin chào");
+
+        // Left alone: the text itself, or nothing after the line
+        for text in [
+            "Translation: Hello world",
+            "Translation services:\n- Documents\n- Websites",
+            "Văn bản hành chính:\nĐiều 1",
+            "번역 서비스 안내:\n문서 번역",
+            "Here is the text of the sign.\nOpen daily",
+            "Sure!\n\nHello",
+            "Hello world:\nnext",
+            "Here is the translation:",
+        ] {
+            assert_eq!(without_preamble(text), text);
+        }
+    }
+
+    #[test]
+    fn test_reading_order_prompt() {
+        let preset = crate::config::Preset { prompt: "Translate.".to_string(), preset_type: "image".to_string(), ..Default::default() };
+        assert_eq!(preview_prompt(&preset, ""), "Translate.");
+
+        let manga = crate::config::Preset { reading_order: ReadingOrder::RightToLeft, ..preset.clone() };
+        assert_eq!(preview_prompt(&manga, ""), format!("Translate.{}", RIGHT_TO_LEFT_INSTRUCTION));
+        let live = crate::config::Preset { live_mode: true, ..manga.clone() };
+        assert!(preview_prompt(&live, "").contains(RIGHT_TO_LEFT_INSTRUCTION));
+
+        // Audio presets have no panels to order
+        let audio = crate::config::Preset { preset_type: "audio".to_string(), ..manga };
+        assert_eq!(preview_prompt(&audio, ""), "Translate.");
+    }
+
+    #[test]
+    fn test_expand_on_empty() {
+        // Grown on every side, clamped to the screenshot
+        assert_eq!(expand_crop((100, 100, 50, 20), 24, (1920, 1080)), (76, 76, 98, 68));
+        assert_eq!(expand_crop((10, 1070, 50, 10), 24, (1920, 1080)), (0, 1046, 84, 34));
+        assert_eq!(expand_crop((0, 0, 1920, 1080), 24, (1920, 1080)), (0, 0, 1920, 1080));
+
+        let answer = |text: &str| Ok(VisionAnswer { text: text.to_string(), detected_language: None });
+        assert!(found_no_text(&answer(" [NO_TEXT]\n")));
+        assert!(found_no_text(&answer("  ")));
+        assert!(found_no_text(&Err(ApiError::EmptyResponse.into())));
+        assert!(!found_no_text(&answer("No text? No problem.")));
+        assert!(!found_no_text(&Err(ApiError::NoApiKey.into())));
+
+        // The sentinel is only asked for when the preset retries, and never by other requests
+        let preset = crate::config::Preset { prompt: "Translate.".to_string(), preset_type: "image".to_string(), ..Default::default() };
+        let retrying = crate::config::Preset { expand_on_empty: Some(24), ..preset.clone() };
+        assert_eq!(preview_prompt(&retrying, ""), format!("Translate.{}", NO_TEXT_INSTRUCTION));
+        assert_eq!(vision_prompt(&retrying), "Translate.");
+        let chat = crate::config::Preset { enable_chat_mode: true, ..retrying.clone() };
+        assert!(!ExpandOnEmpty::applies_to(&chat));
+
+        // A capture filling the screenshot has nowhere to grow
+        let img = image::ImageBuffer::new(200, 100);
+        assert!(ExpandOnEmpty::for_capture(&retrying, &img, (0, 0, 200, 100)).is_none());
+        let expand = ExpandOnEmpty::for_capture(&retrying, &img, (50, 40, 20, 20)).unwrap();
+        assert_eq!(expand.image.dimensions(), (68, 68));
+        assert!(ExpandOnEmpty::for_capture(&preset, &img, (50, 40, 20, 20)).is_none());
+    }
+
+    #[test]
+    fn test_detected_language() {
+        let translate = crate::config::Preset { id: "preset_translate".to_string(), prompt: "Translate.".to_string(), preset_type: "image".to_string(), ..Default::default() };
+        assert_eq!(preview_prompt(&translate, ""), format!("Translate.{}", DETECT_LANGUAGE_INSTRUCTION));
+        // Not while streaming (the JSON would be on screen), nor for other presets
+        let streaming = crate::config::Preset { streaming_enabled: true, ..translate.clone() };
+        assert_eq!(preview_prompt(&streaming, ""), "Translate.");
+        let other = crate::config::Preset { id: "preset_ocr".to_string(), ..translate };
+        assert!(!asks_detected_language(&other));
+
+        assert!(same_language("Japanese", "japanese"));
+        assert!(same_language("Chinese", "Chinese (Simplified)"));
+        assert!(!same_language("Chinese", "Japanese"));
+        assert!(!same_language("", "English"));
+
+        let detected = crate::config::SAME_AS_DETECTED;
+        assert_eq!(resolve_target("Korean", Some("Korean"), Some("Korean")).as_deref(), Some("Korean"));
+        assert_eq!(resolve_target(detected, Some("Japanese"), Some("Vietnamese")).as_deref(), Some("Japanese"));
+        // Nothing to translate into, or the text in the image already was the answer's language
+        assert_eq!(resolve_target(detected, None, Some("Vietnamese")), None);
+        assert_eq!(resolve_target(detected, Some(" "), None), None);
+        assert_eq!(resolve_target(detected, Some("Vietnamese"), Some("Vietnamese")), None);
+    }
+}
@@ -0,0 +1,124 @@
+//! Recent Copies
+//!
+//! Every text `copy_to_clipboard` puts on the clipboard is also kept in a small ring in
+//! `AppState`, newest first. `Config.recent_copies_hotkey` lists them next to the cursor
+//! (like the preset picker) and the tray has a "Recent Copies" submenu; picking one copies
+//! it again. Very long texts are not kept.
+
+use std::collections::VecDeque;
+
+use crate::APP;
+
+/// Picker rows are numbered 1-9
+pub const MAX_ENTRIES: usize = super::preset_picker::MAX_ITEMS;
+/// Longer texts (whole documents, crash reports) are copied but not remembered
+pub const MAX_CHARS: usize = 20_000;
+/// Characters of a text shown in the picker and the tray submenu
+const LABEL_CHARS: usize = 60;
+
+#[derive(Default)]
+pub struct RecentCopies {
+    entries: VecDeque<String>,
+    generation: u64, // Bumped on every change, so the tray submenu knows when to rebuild
+}
+
+impl RecentCopies {
+    /// Put `text` in front, dropping an older copy of it and the oldest entry past the bound
+    pub fn push(&mut self, text: &str) {
+        if text.trim().is_empty() || text.chars().count() > MAX_CHARS {
+            return;
+        }
+        if self.entries.front().is_some_and(|e| e == text) {
+            return;
+        }
+        self.entries.retain(|e| e != text);
+        self.entries.push_front(text.to_string());
+        self.entries.truncate(MAX_ENTRIES);
+        self.generation += 1;
+    }
+
+    pub fn get(&self, index: usize) -> Option<&str> {
+        self.entries.get(index).map(String::as_str)
+    }
+
+    pub fn labels(&self) -> Vec<String> {
+        self.entries.iter().map(|e| label(e)).collect()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+/// One-line preview: whitespace collapsed, cut at `LABEL_CHARS`
+pub fn label(text: &str) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= LABEL_CHARS {
+        return line;
+    }
+    let cut: String = line.chars().take(LABEL_CHARS - 1).collect();
+    format!("{}…", cut.trim_end())
+}
+
+/// Called after a successful copy. `try_lock`, because the crash dialog copies from a
+/// panic hook, possibly while `APP` is held.
+pub fn remember(text: &str) {
+    if let Ok(mut app) = APP.try_lock() {
+        app.recent_copies.push(text);
+    }
+}
+
+/// Copy entry `index` back to the clipboard (it moves to the front)
+pub fn recopy(index: usize) -> bool {
+    let Some(text) = APP.lock().ok().and_then(|app| app.recent_copies.get(index).map(str::to_string)) else {
+        return false;
+    };
+    super::utils::copy_to_clipboard(&text, windows::Win32::Foundation::HWND(0))
+}
+
+/// The quick-pick list (hotkey and tray "Show list…")
+pub fn pick_and_copy() {
+    let labels = match APP.lock() {
+        Ok(app) => app.recent_copies.labels(),
+        Err(_) => return,
+    };
+    if labels.is_empty() {
+        log::info!("Recent copies: nothing copied yet");
+        return;
+    }
+    if let Some(row) = super::preset_picker::pick_row(labels) {
+        recopy(row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recent_copies() {
+        let mut ring = RecentCopies::default();
+        ring.push("one");
+        ring.push("two");
+        ring.push("one");
+        assert_eq!(ring.labels(), vec!["one", "two"]);
+        assert_eq!(ring.generation(), 3);
+        ring.push("one"); // Already in front: unchanged
+        assert_eq!(ring.generation(), 3);
+
+        ring.push("   ");
+        ring.push(&"x".repeat(MAX_CHARS + 1));
+        assert_eq!(ring.labels().len(), 2);
+
+        for i in 0..20 {
+            ring.push(&i.to_string());
+        }
+        assert_eq!(ring.labels().len(), MAX_ENTRIES);
+        assert_eq!(ring.get(0), Some("19"));
+
+        assert_eq!(label("Hello\n  world"), "Hello world");
+        let long = label(&"word ".repeat(40));
+        assert_eq!(long.chars().count(), LABEL_CHARS);
+        assert!(long.ends_with("word…"));
+    }
+}
@@ -3,7 +3,7 @@ use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::System::LibraryLoader::*;
 use windows::core::*;
-use std::sync::{Arc, atomic::{AtomicBool, Ordering}, Once};
+use std::sync::{Arc, atomic::{AtomicBool, AtomicU32, Ordering}, Once};
 use crate::APP;
 
 static mut RECORDING_HWND: HWND = HWND(0);
@@ -27,6 +27,14 @@ lazy_static::lazy_static! {
     pub static ref AUDIO_ABORT_SIGNAL: Arc<AtomicBool> = Arc::new(AtomicBool::new(false));
 }
 
+/// Seconds left before the recording auto-stops (u32::MAX = no limit); updated by the audio thread
+pub static RECORDING_SECS_LEFT: AtomicU32 = AtomicU32::new(u32::MAX);
+/// Countdown is shown on the overlay once this close to the limit
+const COUNTDOWN_SECS: u32 = 60;
+
+/// Input level per source (f32 bits, 0.0..=1.0): [mic or single device, system audio when recording both]
+pub static INPUT_LEVELS: [AtomicU32; 2] = [AtomicU32::new(0), AtomicU32::new(0)];
+
 // OPTIMIZATION: Thread-safe one-time window class registration
 static REGISTER_RECORDING_CLASS: Once = Once::new();
 
@@ -48,7 +56,8 @@ pub fn show_recording_overlay(preset_idx: usize) {
     unsafe {
         if IS_RECORDING { return; }
         
-        let preset = APP.lock().unwrap().config.presets[preset_idx].clone();
+        let mut preset = APP.lock().unwrap().config.presets[preset_idx].clone();
+        super::app_language::apply(&mut preset, preset_idx);
         
         IS_RECORDING = true;
         IS_PAUSED = false;
@@ -58,6 +67,8 @@ pub fn show_recording_overlay(preset_idx: usize) {
         AUDIO_STOP_SIGNAL.store(false, Ordering::SeqCst);
         AUDIO_PAUSE_SIGNAL.store(false, Ordering::SeqCst);
         AUDIO_ABORT_SIGNAL.store(false, Ordering::SeqCst); // Reset abort signal
+        RECORDING_SECS_LEFT.store(u32::MAX, Ordering::SeqCst);
+        for level in &INPUT_LEVELS { level.store(0, Ordering::SeqCst); }
 
         let instance = GetModuleHandleW(None).unwrap();
         let class_name = w!("RecordingOverlay");
@@ -88,6 +99,7 @@ pub fn show_recording_overlay(preset_idx: usize) {
         );
 
         RECORDING_HWND = hwnd;
+        crate::capture::exclude_from_capture(hwnd);
         
         SetTimer(hwnd, 1, 16, None); 
 
@@ -98,8 +110,25 @@ pub fn show_recording_overlay(preset_idx: usize) {
         }
 
         std::thread::spawn(move || {
+            // Owns the mic stream until the session returns
+            let _worker = crate::shutdown::worker();
             // FIX: Pass AUDIO_ABORT_SIGNAL to the worker thread
-            if preset.live_mode {
+            let live_only = crate::model_config::is_live_only(&preset.model);
+            let use_gemini_live = (preset.live_mode && preset.use_gemini_live) || live_only;
+            if use_gemini_live && crate::api::run_gemini_live_preset(
+                preset.clone(),
+                AUDIO_STOP_SIGNAL.clone(),
+                AUDIO_ABORT_SIGNAL.clone(),
+                hwnd
+            ) {
+                // Session ran (or was stopped) over the websocket
+            } else if live_only {
+                // The chunked path would only fail with a confusing API error
+                log::warn!("Gemini Live unavailable for live-only model {}, not falling back", preset.model);
+                let lang = APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+                crate::overlay::app_blocklist::show_toast(format!("⚠ {}", crate::gui::LocaleText::get(&lang).gemini_live_only_failed));
+                PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+            } else if preset.live_mode {
                 crate::api::record_audio_continuous(
                     preset, 
                     AUDIO_STOP_SIGNAL.clone(), 
@@ -107,13 +136,6 @@ pub fn show_recording_overlay(preset_idx: usize) {
                     AUDIO_ABORT_SIGNAL.clone(),
                     hwnd
                 );
-            } else if preset.model == "gemini-2.0-flash-live" {
-                crate::api::run_gemini_live_preset(
-                    preset,
-                    AUDIO_STOP_SIGNAL.clone(),
-                    AUDIO_ABORT_SIGNAL.clone(),
-                    hwnd
-                );
             } else {
                 crate::api::record_audio_and_transcribe(
                     preset, 
@@ -272,6 +294,31 @@ unsafe fn paint_layered_window(hwnd: HWND, width: i32, height: i32, alpha: u8) {
             }
         }
 
+        // -- LEVEL METER (bottom): one bar per source, mic left / system right when recording both --
+        let both_sources = APP.lock().unwrap().config.presets.get(CURRENT_PRESET_IDX).map_or(false, |p| p.audio_source == "both");
+        let meter_left = BTN_OFFSET + HIT_RADIUS;
+        let meter_right = width - BTN_OFFSET - HIT_RADIUS;
+        let meter_y = height - 9;
+        let bars: Vec<(i32, i32, u32)> = if both_sources {
+            let mid = (meter_left + meter_right) / 2;
+            vec![(meter_left, mid - 4, 0xFF66BB6A), (mid + 4, meter_right, 0xFF42A5F5)]
+        } else {
+            vec![(meter_left, meter_right, 0xFF66BB6A)]
+        };
+        for (i, (x0, x1, color)) in bars.into_iter().enumerate() {
+            let level = if IS_PAUSED { 0.0 } else { f32::from_bits(INPUT_LEVELS[i].load(Ordering::Relaxed)) };
+            // Decay per frame, so a source that goes quiet (loopback sends nothing) falls back
+            INPUT_LEVELS[i].store((level * 0.9).to_bits(), Ordering::Relaxed);
+            let filled = x0 + ((x1 - x0) as f32 * level) as i32;
+            for y in meter_y..(meter_y + 3) {
+                for x in x0..x1 {
+                    if x >= 0 && x < width && y >= 0 && y < height {
+                        pixels[(y * width + x) as usize] = if x < filled { color } else { 0x60303030 };
+                    }
+                }
+            }
+        }
+
         // -- CLOSE BUTTON (X) (Right) --
          let c_cx = width - BTN_OFFSET;
          let c_cy = height / 2;
@@ -301,15 +348,18 @@ unsafe fn paint_layered_window(hwnd: HWND, width: i32, height: i32, alpha: u8) {
     let hfont_main = CreateFontW(19, 0, 0, 0, FW_BOLD.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
     let old_font = SelectObject(mem_dc, hfont_main);
 
+    let lang = APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+    let text = crate::gui::LocaleText::get(&lang);
     let src_text = if is_waiting {
-        "Đang xử lý..."
+        text.recording_processing
     } else {
         if CURRENT_PRESET_IDX < APP.lock().unwrap().config.presets.len() {
              let p = &APP.lock().unwrap().config.presets[CURRENT_PRESET_IDX];
-             if IS_PAUSED { "Tạm dừng" } 
-             else if p.audio_source == "device" { "Ghi âm máy..." } 
-             else { "Ghi âm mic..." }
-        } else { "Recording..." }
+             if IS_PAUSED { text.recording_paused } 
+             else if p.audio_source == "device" { text.recording_device } 
+             else if p.audio_source == "both" { text.recording_both } 
+             else { text.recording_mic }
+        } else { text.recording_mic }
     };
 
     let mut text_w = crate::overlay::utils::to_wstring(src_text);
@@ -325,8 +375,14 @@ unsafe fn paint_layered_window(hwnd: HWND, width: i32, height: i32, alpha: u8) {
         SelectObject(mem_dc, hfont_sub);
         SetTextColor(mem_dc, COLORREF(0x00DDDDDD)); 
 
-        let sub_text = "Bấm hotkey lần nữa để xử lý âm thanh";
-        let mut sub_text_w = crate::overlay::utils::to_wstring(sub_text);
+        let secs_left = RECORDING_SECS_LEFT.load(Ordering::SeqCst);
+        let sub_text = if secs_left <= COUNTDOWN_SECS {
+            SetTextColor(mem_dc, COLORREF(0x0060C0FF)); // Orange (BGR)
+            text.recording_countdown.replace("{}", &format!("{}:{:02}", secs_left / 60, secs_left % 60))
+        } else {
+            text.recording_hint.to_string()
+        };
+        let mut sub_text_w = crate::overlay::utils::to_wstring(&sub_text);
         let mut tr_sub = RECT { left: 0, top: 47, right: width, bottom: height };
         DrawTextW(mem_dc, &mut sub_text_w, &mut tr_sub, DT_CENTER | DT_TOP | DT_SINGLELINE);
 
@@ -0,0 +1,132 @@
+//! Paragraph Reflow
+//!
+//! Vision models break lines where the image does, which pastes into a document as a
+//! column of short lines. `Preset.reflow_paragraphs` joins the lines of each paragraph
+//! (blank lines still separate paragraphs, list items keep their own line) and
+//! collapses repeated spaces. Chinese, Japanese and Thai lines are joined without a
+//! space. The text as the model returned it stays on the result window's Shift+right
+//! click menu.
+
+#[derive(Debug, Clone, Copy)]
+pub struct Reflow {
+    dehyphenate: bool, // "transla-\ntion" -> "translation"
+}
+
+impl Reflow {
+    /// None when the preset keeps the model's line breaks. Layout-preserving, chat and
+    /// study layout presets always do.
+    pub fn for_preset(preset: &crate::config::Preset) -> Option<Self> {
+        let chat = preset.preset_type == "chat" || preset.enable_chat_mode;
+        (preset.reflow_paragraphs && !preset.preserve_layout && !chat && !preset.study_layout)
+            .then_some(Reflow { dehyphenate: preset.reflow_dehyphenate })
+    }
+
+    pub fn apply(&self, text: &str) -> String {
+        reflow_paragraphs(text, self.dehyphenate)
+    }
+}
+
+/// Scripts written without spaces between words
+fn is_unspaced(c: char) -> bool {
+    matches!(c as u32,
+        0x3000..=0x30FF // CJK punctuation, kana
+        | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF // Han
+        | 0xFF00..=0xFFEF // Fullwidth forms
+        | 0x0E00..=0x0E7F // Thai
+        | 0x20000..=0x2FA1F)
+}
+
+/// A line that starts a list item ("- ", "• ", "1. ", "2) ")
+fn is_list_item(line: &str) -> bool {
+    if let Some(rest) = line.strip_prefix(['-', '*', '•', '·', '–']) {
+        return rest.starts_with(' ');
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && digits <= 3 && {
+        let rest = &line[digits..];
+        rest.starts_with(". ") || rest.starts_with(") ")
+    }
+}
+
+fn collapse_spaces(line: &str) -> String {
+    let mut out = String::with_capacity(line.len());
+    for word in line.split([' ', '\t']).filter(|w| !w.is_empty()) {
+        if !out.is_empty() {
+            out.push(' ');
+        }
+        out.push_str(word);
+    }
+    out
+}
+
+/// Append `next` to the paragraph line `joined`, with a space unless the scripts at the
+/// join don't use them or the line ends in a hyphenated word
+fn join_line(joined: &mut String, next: &str, dehyphenate: bool) {
+    let (Some(last), Some(first)) = (joined.chars().last(), next.chars().next()) else {
+        joined.push_str(next);
+        return;
+    };
+    let hyphenated = last == '-' && joined.chars().rev().nth(1).map_or(false, char::is_alphabetic);
+    if hyphenated {
+        // A word split over the lines, or a compound like "Jean-Paul" that keeps its hyphen
+        if dehyphenate && first.is_lowercase() {
+            joined.pop();
+        }
+    } else if !(is_unspaced(last) || is_unspaced(first)) {
+        joined.push(' ');
+    }
+    joined.push_str(next);
+}
+
+/// Join the lines of each paragraph; blank lines separate paragraphs
+pub fn reflow_paragraphs(text: &str, dehyphenate: bool) -> String {
+    let mut paragraphs: Vec<Vec<String>> = Vec::new();
+    let mut current: Vec<String> = Vec::new();
+    for line in text.lines() {
+        let line = collapse_spaces(line);
+        if line.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        match current.last_mut() {
+            Some(joined) if !is_list_item(&line) => join_line(joined, &line, dehyphenate),
+            _ => current.push(line),
+        }
+    }
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs.iter().map(|p| p.join("\n")).collect::<Vec<_>>().join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reflow_latin() {
+        let text = "The quick brown  fox\njumps over the\r\nlazy dog.\n\n\nSecond   paragraph\nhere.";
+        assert_eq!(reflow_paragraphs(text, false), "The quick brown fox jumps over the lazy dog.\n\nSecond paragraph here.");
+        // Line-end hyphens only come off when asked, and only inside a word
+        assert_eq!(reflow_paragraphs("a transla-\ntion", true), "a translation");
+        assert_eq!(reflow_paragraphs("a transla-\ntion", false), "a transla-tion");
+        assert_eq!(reflow_paragraphs("Jean-\nPaul and 10 -\nfive", true), "Jean-Paul and 10 - five");
+        // List items keep their lines
+        assert_eq!(reflow_paragraphs("Steps:\n- open\nthe file\n- save", false), "Steps:\n- open the file\n- save");
+        assert_eq!(reflow_paragraphs("1. one\n2. two", false), "1. one\n2. two");
+    }
+
+    #[test]
+    fn test_reflow_cjk() {
+        // No spaces are added between Chinese or Japanese lines
+        assert_eq!(reflow_paragraphs("这是一个很长的\n句子。", false), "这是一个很长的句子。");
+        assert_eq!(reflow_paragraphs("日本語の文章が\n改行されている。\n\n次の段落", false), "日本語の文章が改行されている。\n\n次の段落");
+        assert_eq!(reflow_paragraphs("「こんにちは」\nと言った", false), "「こんにちは」と言った");
+        // Mixed: no space at a CJK side of the join
+        assert_eq!(reflow_paragraphs("使用\nWindows 11", false), "使用Windows 11");
+        // Korean puts spaces between words
+        assert_eq!(reflow_paragraphs("안녕하세요\n반갑습니다", false), "안녕하세요 반갑습니다");
+    }
+}
@@ -0,0 +1,175 @@
+//! Replace Selection Module
+//!
+//! `capture_mode == "replace_selection"`: copies the text selected in the focused
+//! app (simulated Ctrl+C), translates it with the preset's text model and pastes the
+//! translation over the selection (Ctrl+V). The user's clipboard is saved first and
+//! put back afterwards. Only runs once the user confirmed the mode in settings.
+//! Nothing selected, a missing key or a failed request is reported with a toast.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::System::DataExchange::GetClipboardSequenceNumber;
+use windows::Win32::UI::Input::KeyboardAndMouse::*;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+
+use crate::APP;
+use crate::api_error::ApiError;
+use super::utils::{copy_to_clipboard, get_clipboard_text, get_error_message, restore_clipboard, snapshot_clipboard};
+
+pub const CAPTURE_MODE: &str = "replace_selection";
+
+/// How long the focused app gets to answer Ctrl+C
+const COPY_TIMEOUT: Duration = Duration::from_millis(600);
+/// Apps read the clipboard asynchronously on Ctrl+V; restoring earlier would paste the old content
+const PASTE_SETTLE: Duration = Duration::from_millis(300);
+/// Give up waiting for the hotkey's modifiers to be released after this
+const MODIFIER_RELEASE_TIMEOUT: Duration = Duration::from_secs(2);
+
+static BUSY: AtomicBool = AtomicBool::new(false);
+
+pub fn run_replace_selection(preset_idx: usize) {
+    let (mut preset, groq_api_key, gemini_api_key, openrouter_api_key, confirmed, ui_language) = {
+        let app = APP.lock().unwrap();
+        match app.config.presets.get(preset_idx) {
+            Some(p) => (
+                p.clone(),
+                app.config.api_key.clone(),
+                app.config.gemini_api_key.clone(),
+                app.config.openrouter_api_key.clone(),
+                app.config.replace_selection_confirmed,
+                app.config.ui_language.clone(),
+            ),
+            None => return,
+        }
+    };
+    super::app_language::apply(&mut preset, preset_idx);
+    if !confirmed {
+        log::warn!("Replace selection: mode not confirmed in settings, ignoring hotkey");
+        return;
+    }
+    // Holding the hotkey auto-repeats; one operation at a time
+    if BUSY.swap(true, Ordering::SeqCst) {
+        return;
+    }
+
+    // Ctrl+C with the hotkey's Alt/Shift still down would be a different shortcut
+    wait_for_modifiers_release();
+
+    let saved = snapshot_clipboard();
+    let result = (|| -> anyhow::Result<()> {
+        let Some(original) = copy_selection() else {
+            return Err(anyhow::anyhow!(no_selection_message(&ui_language)));
+        };
+
+        let (model, provider) = match crate::model_config::get_model_by_id(&preset.retranslate_model) {
+            Some(m) => (m.full_name, m.provider),
+            None => ("openai/gpt-oss-20b".to_string(), "groq".to_string()),
+        };
+        let translated = crate::api::translate_text_streaming(
+            &groq_api_key,
+            &gemini_api_key,
+            &openrouter_api_key,
+            original.clone(),
+            preset.retranslate_target(&ui_language),
+            model,
+            provider,
+            false,
+            false,
+            |_| {},
+        )?;
+        if translated.trim().is_empty() {
+            return Err(ApiError::EmptyResponse.into());
+        }
+
+        copy_to_clipboard(&keep_surrounding_whitespace(&original, &translated), HWND(0));
+        send_shortcut(VK_V);
+        std::thread::sleep(PASTE_SETTLE);
+        Ok(())
+    })();
+    restore_clipboard(&saved);
+
+    if let Err(e) = result {
+        log::error!("Replace selection failed: {}", e);
+        // Provider errors get the usual localized text; the rest already is
+        let message = if ApiError::of(&e).is_some() { get_error_message(&e, &ui_language) } else { e.to_string() };
+        super::app_blocklist::show_toast(format!("⚠ {}", message));
+    }
+    BUSY.store(false, Ordering::SeqCst);
+}
+
+fn no_selection_message(ui_language: &str) -> &'static str {
+    match ui_language {
+        "vi" => "Chưa chọn văn bản nào để thay thế",
+        "ko" => "바꿀 텍스트가 선택되지 않았습니다",
+        _ => "No text selected to replace",
+    }
+}
+
+/// Copy the focused app's selection and return it (clipboard is cleared first so a
+/// stale value can't be mistaken for the selection). None if nothing came back.
+fn copy_selection() -> Option<String> {
+    restore_clipboard(&[]);
+    let seq = unsafe { GetClipboardSequenceNumber() };
+    send_shortcut(VK_C);
+
+    let start = Instant::now();
+    while start.elapsed() < COPY_TIMEOUT {
+        std::thread::sleep(Duration::from_millis(20));
+        if unsafe { GetClipboardSequenceNumber() } != seq {
+            // Some apps set the clipboard in several steps; let them finish
+            std::thread::sleep(Duration::from_millis(30));
+            if let Some(text) = get_clipboard_text().filter(|t| !t.trim().is_empty()) {
+                return Some(text);
+            }
+        }
+    }
+    None
+}
+
+fn wait_for_modifiers_release() {
+    let start = Instant::now();
+    let modifiers = [VK_CONTROL, VK_SHIFT, VK_MENU, VK_LWIN, VK_RWIN];
+    while start.elapsed() < MODIFIER_RELEASE_TIMEOUT {
+        let held = modifiers.iter().any(|vk| unsafe { GetAsyncKeyState(vk.0 as i32) } as u16 & 0x8000 != 0);
+        if !held {
+            return;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+}
+
+/// Press and release Ctrl+`key` in the foreground window
+fn send_shortcut(key: VIRTUAL_KEY) {
+    let input = |vk: VIRTUAL_KEY, flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT { wVk: vk, wScan: 0, dwFlags: flags, time: 0, dwExtraInfo: 0 },
+        },
+    };
+    let inputs = [
+        input(VK_CONTROL, KEYBD_EVENT_FLAGS(0)),
+        input(key, KEYBD_EVENT_FLAGS(0)),
+        input(key, KEYEVENTF_KEYUP),
+        input(VK_CONTROL, KEYEVENTF_KEYUP),
+    ];
+    unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32); }
+}
+
+/// Models trim their output; keep the selection's leading/trailing whitespace so
+/// replacing "word " or a whole line doesn't glue it to the next one
+fn keep_surrounding_whitespace(original: &str, translated: &str) -> String {
+    let leading = &original[..original.len() - original.trim_start().len()];
+    let trailing = &original[original.trim_end().len()..];
+    format!("{}{}{}", leading, translated.trim(), trailing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keep_surrounding_whitespace() {
+        assert_eq!(keep_surrounding_whitespace("  hello world\r\n", "xin chào\n"), "  xin chào\r\n");
+        assert_eq!(keep_surrounding_whitespace("hello", " xin chào "), "xin chào");
+    }
+}
@@ -0,0 +1,41 @@
+//! Result Text Direction
+//!
+//! Arabic and Hebrew results are drawn with `DT_RTLREADING` and right-aligned, so GDI
+//! runs the Unicode bidi algorithm from an RTL base direction (LTR words and numbers
+//! inside them stay readable). The base direction is that of the first strong
+//! character, as in the bidi algorithm's paragraph rule. The buttons move to the
+//! bottom-left in RTL windows, clear of where the lines start.
+
+/// Direction of a strong character; None for neutrals (digits, spaces, punctuation)
+fn strong_direction(c: char) -> Option<bool> {
+    match c as u32 {
+        0x0590..=0x08FF // Hebrew, Arabic, Syriac, Thaana, NKo, Samaritan, Arabic Extended
+        | 0xFB1D..=0xFDFF // Hebrew and Arabic presentation forms
+        | 0xFE70..=0xFEFF
+        | 0x10800..=0x10FFF | 0x1E800..=0x1EFFF => Some(true),
+        _ if c.is_alphabetic() => Some(false),
+        _ => None,
+    }
+}
+
+/// Whether the text's base direction is right-to-left (its first strong character is RTL)
+pub fn is_rtl(text: &str) -> bool {
+    text.chars().find_map(strong_direction).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_rtl() {
+        assert!(!is_rtl("Hello, world"));
+        assert!(is_rtl("مرحبا بالعالم"));
+        assert!(is_rtl("שלום עולם"));
+        // Neutrals are skipped, the first strong character decides
+        assert!(is_rtl("2024: «مرحبا» Windows 11"));
+        assert!(!is_rtl("Windows 11: مرحبا"));
+        assert!(!is_rtl("12345 ..."));
+        assert!(!is_rtl(""));
+    }
+}
@@ -0,0 +1,147 @@
+//! Result Window Editing
+//!
+//! F2 (or the pencil button) lets the user fix a misread character before copying.
+//! The window text is edited in place as UTF-16, the unit GetWindowTextW/SetWindowTextW
+//! work in; `caret` is an offset into it. `paint.rs` draws the caret with the same line
+//! layout the search uses.
+
+#[derive(Default, Clone)]
+pub struct EditState {
+    pub caret: usize,
+    pub reveal_caret: bool, // Scroll the caret into view on the next paint
+}
+
+const LF: u16 = b'\n' as u16;
+const CR: u16 = b'\r' as u16;
+
+fn is_high_surrogate(unit: u16) -> bool {
+    (0xD800..0xDC00).contains(&unit)
+}
+
+/// `units` up to their NUL terminator, less a high surrogate whose low half was cut off
+/// (GetWindowTextW filling a buffer sized before the text grew)
+pub fn whole_characters(units: &[u16]) -> &[u16] {
+    let end = units.iter().position(|&c| c == 0).unwrap_or(units.len());
+    let units = &units[..end];
+    match units.last() {
+        Some(&last) if is_high_surrogate(last) => &units[..end - 1],
+        _ => units,
+    }
+}
+
+/// Offset of the character before `caret` (a surrogate pair or CRLF moves as one)
+pub fn prev_boundary(text: &[u16], caret: usize) -> usize {
+    let caret = caret.min(text.len());
+    if caret == 0 {
+        return 0;
+    }
+    let prev = caret - 1;
+    let low_surrogate = (0xDC00..0xE000).contains(&text[prev]);
+    if prev > 0 && ((text[prev] == LF && text[prev - 1] == CR) || (low_surrogate && is_high_surrogate(text[prev - 1]))) {
+        prev - 1
+    } else {
+        prev
+    }
+}
+
+/// Offset of the character after `caret`
+pub fn next_boundary(text: &[u16], caret: usize) -> usize {
+    if caret >= text.len() {
+        return text.len();
+    }
+    let pair = caret + 1 < text.len() && ((text[caret] == CR && text[caret + 1] == LF) || is_high_surrogate(text[caret]));
+    if pair { caret + 2 } else { caret + 1 }
+}
+
+/// Start of the line the caret is on
+pub fn line_start(text: &[u16], caret: usize) -> usize {
+    text[..caret.min(text.len())].iter().rposition(|&c| c == LF).map_or(0, |p| p + 1)
+}
+
+/// End of the line the caret is on (before its line break)
+pub fn line_end(text: &[u16], caret: usize) -> usize {
+    let end = text[caret.min(text.len())..].iter().position(|&c| c == LF).map_or(text.len(), |p| caret + p);
+    if end > caret && text[end - 1] == CR { end - 1 } else { end }
+}
+
+impl EditState {
+    pub fn insert(&mut self, text: &mut Vec<u16>, units: &[u16]) {
+        let caret = self.caret.min(text.len());
+        text.splice(caret..caret, units.iter().copied());
+        self.caret = caret + units.len();
+        self.reveal_caret = true;
+    }
+
+    pub fn backspace(&mut self, text: &mut Vec<u16>) {
+        let start = prev_boundary(text, self.caret);
+        text.drain(start..self.caret.min(text.len()));
+        self.caret = start;
+        self.reveal_caret = true;
+    }
+
+    pub fn delete(&mut self, text: &mut Vec<u16>) {
+        let caret = self.caret.min(text.len());
+        let end = next_boundary(text, caret);
+        text.drain(caret..end);
+        self.reveal_caret = true;
+    }
+
+    /// Move to `caret` (from the boundary helpers above)
+    pub fn move_to(&mut self, caret: usize) {
+        self.caret = caret;
+        self.reveal_caret = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+
+    #[test]
+    fn test_edit_text() {
+        // OCR read "rn" as "m": fix it
+        let mut text = wide("Hello wm\r\nline 👋");
+        let mut edit = EditState { caret: 7, ..Default::default() };
+        edit.delete(&mut text);
+        edit.insert(&mut text, &wide("or"));
+        assert_eq!(String::from_utf16_lossy(&text), "Hello wor\r\nline 👋");
+        assert_eq!(edit.caret, 9);
+
+        // CRLF and surrogate pairs are single steps
+        assert_eq!(next_boundary(&text, 9), 11);
+        assert_eq!(prev_boundary(&text, 11), 9);
+        let end = text.len();
+        assert_eq!(prev_boundary(&text, end), end - 2);
+        edit.move_to(end);
+        edit.backspace(&mut text);
+        assert_eq!(String::from_utf16_lossy(&text), "Hello wor\r\nline ");
+
+        // Home / End stay within the caret's line
+        assert_eq!(line_start(&text, 13), 11);
+        assert_eq!(line_end(&text, 3), 9);
+        assert_eq!(line_end(&text, 12), text.len());
+
+        edit.move_to(0);
+        edit.backspace(&mut text);
+        assert_eq!(edit.caret, 0);
+        edit.move_to(text.len());
+        edit.delete(&mut text);
+        assert_eq!(String::from_utf16_lossy(&text), "Hello wor\r\nline ");
+    }
+
+    #[test]
+    fn test_whole_characters() {
+        let text = wide("ok 😀");
+        assert_eq!(whole_characters(&text), &text[..]);
+        // Cut between the surrogates: the emoji's high half is dropped, not shown as U+FFFD
+        assert_eq!(String::from_utf16(whole_characters(&text[..text.len() - 1])).unwrap(), "ok ");
+        let mut terminated = text.clone();
+        terminated.extend([0, 0]);
+        assert_eq!(whole_characters(&terminated), &text[..]);
+        assert!(whole_characters(&[]).is_empty());
+    }
+}
@@ -0,0 +1,97 @@
+//! Escape To Close
+//!
+//! Result windows are layered popups that rarely have keyboard focus, so their own
+//! WM_KEYDOWN almost never sees Escape. While at least one result window exists, a
+//! low-level keyboard hook catches Escape and closes the newest visible result window
+//! together with its linked windows. The hook goes away with the last window, so Escape
+//! is left alone the rest of the time. While a result is being typed out, Escape
+//! stops the typing instead.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::UI::Input::KeyboardAndMouse::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use super::hook::HookThread;
+use super::state::WINDOW_STATES;
+
+static HOOK: HookThread = HookThread::new("Escape", WH_KEYBOARD_LL, Some(hook_proc));
+
+/// Install or remove the hook to match whether any result window exists.
+/// Called after a window state is added or removed (never with WINDOW_STATES locked).
+pub fn sync() {
+    let any_window = !WINDOW_STATES.lock().unwrap().is_empty();
+    HOOK.set_installed(any_window);
+}
+
+unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 && wparam.0 as u32 == WM_KEYDOWN {
+        let info = &*(lparam.0 as *const KBDLLHOOKSTRUCT);
+        if info.vkCode == VK_ESCAPE.0 as u32 && !modifier_down() {
+            if crate::overlay::type_out::request_abort() {
+                return LRESULT(1); // Stops the typing and keeps Escape out of the target window
+            }
+            if let Some(hwnd) = escape_target() {
+                PostMessageW(hwnd, super::logic::WM_DISMISS, WPARAM(0), LPARAM(0));
+                return LRESULT(1); // Meant for the overlay: don't pass it on
+            }
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+/// Shift+Esc, Ctrl+Shift+Esc (Task Manager) and friends belong to other apps
+unsafe fn modifier_down() -> bool {
+    [VK_SHIFT, VK_CONTROL, VK_MENU, VK_LWIN, VK_RWIN].iter().any(|vk| GetAsyncKeyState(vk.0 as i32) < 0)
+}
+
+/// A result window that could take Escape right now
+struct Candidate {
+    hwnd: isize,
+    created_seq: u64,
+    visible: bool,
+    editing: bool,
+}
+
+/// Newest visible window not being edited. None when a result window is in the
+/// foreground: it gets Escape itself (leaving search / edit mode first).
+fn pick_target(candidates: &[Candidate], foreground: isize) -> Option<isize> {
+    if candidates.iter().any(|c| c.hwnd == foreground) {
+        return None;
+    }
+    candidates.iter()
+        .filter(|c| c.visible && !c.editing)
+        .max_by_key(|c| c.created_seq)
+        .map(|c| c.hwnd)
+}
+
+unsafe fn escape_target() -> Option<HWND> {
+    let foreground = GetForegroundWindow();
+    let candidates: Vec<Candidate> = WINDOW_STATES.lock().unwrap().iter()
+        .map(|(&hwnd, state)| Candidate {
+            hwnd,
+            created_seq: state.created_seq,
+            visible: IsWindowVisible(HWND(hwnd)).as_bool() && !state.hidden_by_toggle,
+            editing: state.edit.is_some(),
+        })
+        .collect();
+    pick_target(&candidates, foreground.0).map(HWND)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_target() {
+        let window = |hwnd, created_seq, visible, editing| Candidate { hwnd, created_seq, visible, editing };
+        let windows = [window(10, 1, true, false), window(20, 3, true, false), window(30, 2, true, false)];
+        assert_eq!(pick_target(&windows, 0), Some(20));
+        // A focused result window handles Escape itself
+        assert_eq!(pick_target(&windows, 30), None);
+
+        // Hidden and edited windows are skipped
+        let windows = [window(10, 1, true, false), window(20, 3, false, false), window(30, 2, true, true)];
+        assert_eq!(pick_target(&windows, 0), Some(10));
+        assert_eq!(pick_target(&[window(10, 1, false, false)], 0), None);
+        assert_eq!(pick_target(&[], 0), None);
+    }
+}
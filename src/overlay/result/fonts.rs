@@ -0,0 +1,69 @@
+//! Result Window Fonts
+//!
+//! Segoe UI has no Hangul, kana, Han, Thai or Indic glyphs, and on systems without
+//! font linking set up for it those results render as boxes. The text's main script
+//! picks a Windows font that covers it (and Latin, so mixed lines still work);
+//! `Preset.font_family` overrides the choice.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+    Hangul,
+    Japanese, // Kana, with the Han characters around it
+    Han,
+    Thai,
+    Devanagari,
+}
+
+fn script_of(c: char) -> Option<Script> {
+    match c as u32 {
+        0x1100..=0x11FF | 0x3130..=0x318F | 0xAC00..=0xD7AF => Some(Script::Hangul),
+        0x3040..=0x30FF | 0x31F0..=0x31FF | 0xFF66..=0xFF9F => Some(Script::Japanese),
+        0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x2FA1F => Some(Script::Han),
+        0x0E00..=0x0E7F => Some(Script::Thai),
+        0x0900..=0x097F => Some(Script::Devanagari),
+        _ => None, // Latin, Cyrillic, Greek, Arabic, Hebrew: Segoe UI has them
+    }
+}
+
+/// The script most of the text is in, when Segoe UI can't show it. Han characters
+/// count as Japanese once there is any kana.
+pub fn main_script(text: &str) -> Option<Script> {
+    let mut counts = [0usize; 5];
+    for script in text.chars().filter_map(script_of) {
+        counts[script as usize] += 1;
+    }
+    if counts[Script::Japanese as usize] > 0 {
+        counts[Script::Japanese as usize] += std::mem::take(&mut counts[Script::Han as usize]);
+    }
+    let scripts = [Script::Hangul, Script::Japanese, Script::Han, Script::Thai, Script::Devanagari];
+    scripts.into_iter().zip(counts).filter(|&(_, n)| n > 0).max_by_key(|&(_, n)| n).map(|(s, _)| s)
+}
+
+/// Face name for `text`: a font covering its script, else Segoe UI (Consolas when monospace)
+pub fn face_for(text: &str, monospace: bool) -> &'static str {
+    match main_script(text) {
+        Some(Script::Hangul) => "Malgun Gothic",
+        Some(Script::Japanese) => if monospace { "MS Gothic" } else { "Yu Gothic UI" },
+        Some(Script::Han) => if monospace { "SimSun" } else { "Microsoft YaHei UI" },
+        Some(Script::Thai) => "Leelawadee UI",
+        Some(Script::Devanagari) => "Nirmala UI",
+        None => if monospace { "Consolas" } else { "Segoe UI" },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_face_for() {
+        assert_eq!(face_for("Hello, world", false), "Segoe UI");
+        assert_eq!(face_for("Привет, Xin chào", true), "Consolas");
+        assert_eq!(face_for("안녕하세요 (Hello)", false), "Malgun Gothic");
+        assert_eq!(face_for("日本語のテキスト", false), "Yu Gothic UI");
+        assert_eq!(face_for("中文文本", false), "Microsoft YaHei UI");
+        assert_eq!(face_for("สวัสดี", false), "Leelawadee UI");
+        // Mostly Korean with a few Han characters
+        assert_eq!(main_script("大韓民國 대한민국 헌법"), Some(Script::Hangul));
+    }
+}
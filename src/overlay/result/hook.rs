@@ -0,0 +1,63 @@
+//! Low-Level Hooks
+//!
+//! Escape-to-close and click-outside-to-dismiss each use a WH_*_LL hook. Such a hook is
+//! called through the message loop of the thread that installed it, so each one runs on a
+//! small thread of its own that exists only while the hook is needed.
+
+use std::sync::Mutex;
+use windows::Win32::Foundation::*;
+use windows::Win32::System::LibraryLoader::GetModuleHandleW;
+use windows::Win32::System::Threading::GetCurrentThreadId;
+use windows::Win32::UI::WindowsAndMessaging::*;
+
+pub struct HookThread {
+    name: &'static str,
+    kind: WINDOWS_HOOK_ID,
+    hook_proc: HOOKPROC,
+    thread: Mutex<Option<u32>>, // Id of the thread running the hook, while it's installed
+}
+
+impl HookThread {
+    pub const fn new(name: &'static str, kind: WINDOWS_HOOK_ID, hook_proc: HOOKPROC) -> Self {
+        Self { name, kind, hook_proc, thread: Mutex::new(None) }
+    }
+
+    /// Install or remove the hook. Never call with WINDOW_STATES locked: the hook procedure locks it.
+    pub fn set_installed(&self, installed: bool) {
+        let mut thread = self.thread.lock().unwrap();
+        match (installed, *thread) {
+            (true, None) => {
+                let (tx, rx) = std::sync::mpsc::channel();
+                let (name, kind, hook_proc) = (self.name, self.kind, self.hook_proc);
+                std::thread::spawn(move || run_hook(name, kind, hook_proc, tx));
+                *thread = rx.recv().ok().flatten();
+            }
+            (false, Some(id)) => {
+                unsafe { PostThreadMessageW(id, WM_QUIT, WPARAM(0), LPARAM(0)); }
+                *thread = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn run_hook(name: &str, kind: WINDOWS_HOOK_ID, hook_proc: HOOKPROC, ready: std::sync::mpsc::Sender<Option<u32>>) {
+    unsafe {
+        let mut msg = MSG::default();
+        // Creates the message queue the WM_QUIT from `set_installed` is posted to
+        PeekMessageW(&mut msg, None, 0, 0, PM_NOREMOVE);
+        let instance = GetModuleHandleW(None).unwrap_or_default();
+        let hook = match SetWindowsHookExW(kind, hook_proc, instance, 0) {
+            Ok(hook) => hook,
+            Err(e) => {
+                log::warn!("{} hook unavailable: {}", name, e);
+                let _ = ready.send(None);
+                return;
+            }
+        };
+        let _ = ready.send(Some(GetCurrentThreadId()));
+
+        while GetMessageW(&mut msg, None, 0, 0).into() {}
+        UnhookWindowsHookEx(hook);
+    }
+}
@@ -0,0 +1,92 @@
+//! Result Text Line Breaking
+//!
+//! DT_WORDBREAK only breaks lines at spaces, so a Chinese or Japanese result (no spaces)
+//! overflowed as one long line or was cut mid-character. `wrap_lines` asks
+//! `can_break_before` for break opportunities instead: at spaces as before, and between
+//! CJK characters, except before closing punctuation and small kana or after opening
+//! brackets (the basic kinsoku rules). Text with CJK in it is wrapped that way and
+//! drawn with its line breaks as-is; other text keeps DrawTextW's wrapping.
+
+const SPACE: u16 = b' ' as u16;
+
+/// Han, kana, Hangul, CJK punctuation and fullwidth forms
+fn is_cjk(c: u16) -> bool {
+    matches!(c,
+        0x1100..=0x11FF // Hangul Jamo
+        | 0x2E80..=0x2FDF // CJK radicals
+        | 0x3000..=0x30FF // CJK punctuation, kana
+        | 0x3130..=0x318F // Hangul compatibility Jamo
+        | 0x31F0..=0x31FF // Katakana extensions
+        | 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF // Han
+        | 0xAC00..=0xD7AF // Hangul syllables
+        | 0xFF00..=0xFFEF) // Fullwidth / halfwidth forms
+}
+
+/// Never starts a line: closing punctuation, iteration marks, small kana, the prolonged sound mark
+fn no_break_before(c: u16) -> bool {
+    matches!(c,
+        0x3001 | 0x3002 | 0x3005 | 0x3009 | 0x300B | 0x300D | 0x300F | 0x3011 | 0x3015 | 0x3017 | 0x3019 | 0x301B | 0x301E | 0x301F // 、。々 〉》」』】〕〗〙〛 〞〟
+        | 0x3041 | 0x3043 | 0x3045 | 0x3047 | 0x3049 | 0x3063 | 0x3083 | 0x3085 | 0x3087 | 0x308E | 0x3095 | 0x3096 // Small hiragana
+        | 0x309D | 0x309E | 0x30FB | 0x30FC | 0x30FD | 0x30FE // ゝゞ・ーヽヾ
+        | 0x30A1 | 0x30A3 | 0x30A5 | 0x30A7 | 0x30A9 | 0x30C3 | 0x30E3 | 0x30E5 | 0x30E7 | 0x30EE | 0x30F5 | 0x30F6 // Small katakana
+        | 0xFF01 | 0xFF09 | 0xFF0C | 0xFF0E | 0xFF1A | 0xFF1B | 0xFF1F | 0xFF3D | 0xFF5D | 0xFF61 | 0xFF63 | 0xFF64 // ！），．：；？］｝ ｡｣､
+        | 0x2019 | 0x201D | 0x2026 | 0x2025 // ’ ” … ‥
+        | 0x0021 | 0x0029 | 0x002C | 0x002E | 0x003A | 0x003B | 0x003F | 0x005D | 0x007D // ! ) , . : ; ? ] }
+        | 0x00B7)
+}
+
+/// Never ends a line: opening brackets and quotes
+fn no_break_after(c: u16) -> bool {
+    matches!(c,
+        0x3008 | 0x300A | 0x300C | 0x300E | 0x3010 | 0x3014 | 0x3016 | 0x3018 | 0x301A | 0x301D // 〈《「『【〔〖〘〚〝
+        | 0xFF08 | 0xFF3B | 0xFF5B | 0xFF62 // （［｛｢
+        | 0x2018 | 0x201C // ‘ “
+        | 0x0028 | 0x005B | 0x007B) // ( [ {
+}
+
+/// Whether a line may end just before `text[i]` (0 < i < text.len()). Spaces break as
+/// before (the line ends at the space); CJK characters break on either side of them.
+pub fn can_break_before(text: &[u16], i: usize) -> bool {
+    let (prev, next) = (text[i - 1], text[i]);
+    if (0xDC00..0xE000).contains(&next) {
+        return false; // Inside a surrogate pair
+    }
+    if next == SPACE {
+        return prev != SPACE;
+    }
+    if prev == SPACE {
+        return false; // The break is at the space itself
+    }
+    (is_cjk(prev) || is_cjk(next)) && !no_break_before(next) && !no_break_after(prev)
+}
+
+/// Whether the text needs `can_break_before` wrapping (DT_WORDBREAK would overflow it)
+pub fn has_cjk(text: &[u16]) -> bool {
+    text.iter().any(|&c| is_cjk(c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaks(s: &str) -> Vec<usize> {
+        let text: Vec<u16> = s.encode_utf16().collect();
+        (1..text.len()).filter(|&i| can_break_before(&text, i)).collect()
+    }
+
+    #[test]
+    fn test_break_opportunities() {
+        // Latin: only at spaces, as DT_WORDBREAK did
+        assert_eq!(breaks("the quick  fox"), vec![3, 9]);
+        // Between ideographs, but not before 。or after 「
+        assert_eq!(breaks("这是中文。"), vec![1, 2, 3]);
+        assert_eq!(breaks("彼は「はい」と言った"), vec![1, 2, 4, 6, 7, 9]);
+        // Small kana and ー stay with the character before them
+        assert_eq!(breaks("ちょっとコーヒー"), vec![3, 4, 6]);
+        // Mixed: a Latin word next to CJK breaks at its edges, never inside
+        assert_eq!(breaks("使用Windows系统"), vec![1, 2, 9, 10]);
+        assert_eq!(breaks("한국어 text와 중국어"), vec![1, 2, 3, 8, 9, 11, 12]);
+        assert!(has_cjk(&"abc 中".encode_utf16().collect::<Vec<_>>()));
+        assert!(!has_cjk(&"Tiếng Việt".encode_utf16().collect::<Vec<_>>()));
+    }
+}
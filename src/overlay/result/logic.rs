@@ -3,6 +3,48 @@ use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::Win32::Graphics::Gdi::*;
 use super::state::{WINDOW_STATES, AnimationMode, DustParticle};
 
+// Timer 3: 60 FPS physics, or just over the 66ms text throttle without animations.
+// It only runs while something changes, so static windows cost no wakeups.
+const ANIMATION_TIMER_MS: u32 = 16;
+const STATIC_TIMER_MS: u32 = 70;
+
+/// Posted to restart timer 3 from other threads (SetTimer only works on the window's own thread)
+pub const WM_WAKE_TIMER: u32 = WM_APP + 1;
+
+/// Posted by the Escape and outside-click hooks: the user dismissed the window. Handled
+/// on the window's thread, so the copy on close doesn't hold up the hook.
+pub const WM_DISMISS: u32 = WM_APP + 2;
+
+/// Timer 3 period a window needs (0 = stop): physics still moving, or text waiting to be shown
+fn timer_interval(animations: bool, settled: bool, pending_text: bool) -> u32 {
+    if animations && (!settled || pending_text) {
+        ANIMATION_TIMER_MS
+    } else if pending_text {
+        STATIC_TIMER_MS
+    } else {
+        0
+    }
+}
+
+/// Start, retune or stop timer 3 to match the window's state (call on the window's thread)
+pub fn update_timer(hwnd: HWND) {
+    let mut states = WINDOW_STATES.lock().unwrap();
+    let Some(state) = states.get_mut(&(hwnd.0 as isize)) else { return };
+    // Text held back while editing doesn't need the timer until edit mode ends
+    let pending_text = state.pending_text.is_some() && state.edit.is_none();
+    let interval = timer_interval(state.animations, state.physics.is_settled(), pending_text);
+    if interval != state.timer_interval {
+        state.timer_interval = interval;
+        unsafe {
+            if interval == 0 {
+                KillTimer(hwnd, 3);
+            } else {
+                SetTimer(hwnd, 3, interval, None);
+            }
+        }
+    }
+}
+
 fn rand_float(min: f32, max: f32) -> f32 {
     static mut SEED: u32 = 12345;
     unsafe {
@@ -12,6 +54,20 @@ fn rand_float(min: f32, max: f32) -> f32 {
     }
 }
 
+/// Close the window and the windows linked to it
+pub fn close_with_linked(hwnd: HWND) {
+    let linked_hwnds = {
+        let states = WINDOW_STATES.lock().unwrap();
+        if let Some(state) = states.get(&(hwnd.0 as isize)) { state.linked_windows.clone() } else { Vec::new() }
+    };
+    unsafe {
+        for linked in linked_hwnds {
+            if IsWindow(linked).as_bool() { PostMessageW(linked, WM_CLOSE, WPARAM(0), LPARAM(0)); }
+        }
+        PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+    }
+}
+
 pub fn handle_timer(hwnd: HWND, wparam: WPARAM) {
     unsafe {
         if wparam.0 == 3 { // 60 FPS Physics Loop
@@ -19,7 +75,8 @@ pub fn handle_timer(hwnd: HWND, wparam: WPARAM) {
             
             {
                 let mut states = WINDOW_STATES.lock().unwrap();
-                if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+                // Without animations the timer only paces pending text (handled by the caller)
+                if let Some(state) = states.get_mut(&(hwnd.0 as isize)).filter(|s| s.animations) {
                     let p = &mut state.physics;
 
                     // --- 1. MOUSE PHYSICS (Spring System) ---
@@ -113,14 +170,7 @@ pub fn handle_timer(hwnd: HWND, wparam: WPARAM) {
             }
 
             if should_close {
-                 let linked_hwnd = {
-                    let states = WINDOW_STATES.lock().unwrap();
-                    if let Some(state) = states.get(&(hwnd.0 as isize)) { state.linked_window } else { None }
-                };
-                if let Some(linked) = linked_hwnd {
-                    if IsWindow(linked).as_bool() { PostMessageW(linked, WM_CLOSE, WPARAM(0), LPARAM(0)); }
-                }
-                PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+                close_with_linked(hwnd);
             }
         } 
         else if wparam.0 == 1 {
@@ -129,6 +179,11 @@ pub fn handle_timer(hwnd: HWND, wparam: WPARAM) {
             let mut states = WINDOW_STATES.lock().unwrap();
             if let Some(state) = states.get_mut(&(hwnd.0 as isize)) { 
                 state.copy_success = false; 
+                if !state.animations {
+                    drop(states);
+                    InvalidateRect(hwnd, None, false);
+                    return;
+                }
                 
                 // Spawn sparkles for success
                  let cx = state.physics.x;
@@ -149,3 +204,20 @@ pub fn handle_timer(hwnd: HWND, wparam: WPARAM) {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timer_interval() {
+        // Static window: no timer at all
+        assert_eq!(timer_interval(true, true, false), 0);
+        assert_eq!(timer_interval(false, true, false), 0);
+        // Broom or smash still moving
+        assert_eq!(timer_interval(true, false, false), ANIMATION_TIMER_MS);
+        // Streaming text keeps within the 66ms update budget either way
+        assert_eq!(timer_interval(true, true, true), ANIMATION_TIMER_MS);
+        assert_eq!(timer_interval(false, true, true), STATIC_TIMER_MS);
+    }
+}
@@ -7,22 +7,36 @@ use windows::Win32::UI::Input::KeyboardAndMouse::*;
 use windows::core::*;
 use std::mem::size_of;
 use std::sync::Once;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::{SystemTime, UNIX_EPOCH};
 
+use crate::config::CopyFormat;
 use crate::overlay::utils::to_wstring;
 
 mod state;
 mod paint;
 mod logic;
+mod search;
+mod edit;
+mod escape;
+mod hook;
+mod outside_click;
+mod quick_translate;
+mod fonts;
+mod line_break;
+mod bidi;
 
-use state::{WINDOW_STATES, WindowState, CursorPhysics, AnimationMode, InteractionMode, ResizeEdge};
-pub use state::{WindowType, link_windows};
+use state::{WINDOW_STATES, WindowState, OwnedBitmap, CursorPhysics, AnimationMode, InteractionMode, ResizeEdge};
+pub use state::{WindowType, link_windows, set_export_action, set_follow_up_action, set_preserve_layout, set_dismiss_on_outside_click, set_warning_badge, set_stats_line, set_model_badge, set_retry_action, set_copy_on_close, set_copy_format, format_for_copy, set_source_image, set_font_family, set_text_model, set_unreflowed_text, set_study_card};
 
 static mut CURRENT_BG_COLOR: u32 = 0x00222222;
 
 // OPTIMIZATION: Thread-safe one-time window class registration
 static REGISTER_RESULT_CLASS: Once = Once::new();
 
+/// Source of `WindowState::created_seq`
+static NEXT_WINDOW_SEQ: AtomicU64 = AtomicU64::new(0);
+
 pub fn create_result_window(target_rect: RECT, win_type: WindowType) -> HWND {
     unsafe {
         let instance = GetModuleHandleW(None).unwrap();
@@ -55,65 +69,16 @@ pub fn create_result_window(target_rect: RECT, win_type: WindowType) -> HWND {
                 (target_rect.left, target_rect.top, 0x002d4a22)
             },
             WindowType::Secondary => {
-                let padding = 10;
-                
-                // --- INTELLIGENT MONITOR-AWARE POSITIONING ---
-                // 1. Get the monitor that contains the selection
-                let hmonitor = MonitorFromRect(&target_rect, MONITOR_DEFAULTTONEAREST);
-                
-                // 2. Get that monitor's WORK AREA (excludes taskbars)
-                let mut mi = MONITORINFO::default();
-                mi.cbSize = size_of::<MONITORINFO>() as u32;
-                GetMonitorInfoW(hmonitor, &mut mi);
-                let work_rect = mi.rcWork;
-
-                // Potential coordinates
-                let pos_right_x = target_rect.right + padding;
-                let pos_left_x  = target_rect.left - width - padding;
-                let pos_bottom_y = target_rect.bottom + padding;
-                let pos_top_y    = target_rect.top - height - padding;
-
-                // Calculate available space on each side relative to the WORK AREA
-                let space_right  = work_rect.right - pos_right_x;
-                let space_left   = (target_rect.left - padding) - work_rect.left;
-                let space_bottom = work_rect.bottom - pos_bottom_y;
-                let space_top    = (target_rect.top - padding) - work_rect.top;
-
-                // 3. Logic: Find best side
                 // Priority: Right -> Bottom -> Left -> Top
-                let (mut best_x, mut best_y) = if space_right >= width {
-                    (pos_right_x, target_rect.top)
-                } else if space_bottom >= height {
-                    (target_rect.left, pos_bottom_y)
-                } else if space_left >= width {
-                    (pos_left_x, target_rect.top)
-                } else if space_top >= height {
-                    (target_rect.left, pos_top_y)
-                } else {
-                    // 4. Fallback: Pick the side with the MOST available space (minimizes overlap)
-                    let max_space = space_right.max(space_left).max(space_bottom).max(space_top);
-                    
-                    if max_space == space_right {
-                        (pos_right_x, target_rect.top)
-                    } else if max_space == space_left {
-                        (pos_left_x, target_rect.top)
-                    } else if max_space == space_bottom {
-                        (target_rect.left, pos_bottom_y)
-                    } else {
-                        (target_rect.left, pos_top_y)
-                    }
-                };
-                
-                // 5. FINAL SAFEGUARD: Hard Clamp to Monitor Work Area
-                // This ensures the window is fully visible even if it has to overlap the selection.
-                let safe_w = width.min(work_rect.right - work_rect.left);
-                let safe_h = height.min(work_rect.bottom - work_rect.top);
-                
-                best_x = best_x.clamp(work_rect.left, work_rect.right - safe_w);
-                best_y = best_y.clamp(work_rect.top, work_rect.bottom - safe_h);
-
+                let (best_x, best_y) = place_beside_selection(&target_rect, width, height, [Side::Right, Side::Bottom, Side::Left, Side::Top]);
                 CURRENT_BG_COLOR = 0x002d4a22; 
                 (best_x, best_y, 0x002d4a22)
+            },
+            WindowType::Tertiary => {
+                // Priority: Bottom -> Left -> Top -> Right, so it lands below while the secondary takes the right
+                let (best_x, best_y) = place_beside_selection(&target_rect, width, height, [Side::Bottom, Side::Left, Side::Top, Side::Right]);
+                CURRENT_BG_COLOR = 0x004a2d22; 
+                (best_x, best_y, 0x004a2d22)
             }
         };
 
@@ -126,8 +91,11 @@ pub fn create_result_window(target_rect: RECT, win_type: WindowType) -> HWND {
             None, None, instance, None
         );
 
+        state::drop_destroyed_windows();
+
         let mut physics = CursorPhysics::default();
         physics.initialized = true;
+        let animations = overlay_animations_enabled();
 
         {
             let mut states = WINDOW_STATES.lock().unwrap();
@@ -136,9 +104,25 @@ pub fn create_result_window(target_rect: RECT, win_type: WindowType) -> HWND {
                 is_hovered: false,
                 on_copy_btn: false,
                 copy_success: false,
+                on_export_btn: false,
+                on_edit_btn: false,
+                export_action: None,
+                follow_up_action: None,
+                raw_text: None,
+                unreflowed_text: None,
+                study: None,
+                preserve_layout: false,
+                dismiss_on_outside_click: false,
+                warning_badge: None,
+                stats_line: None,
+                model_badge: None,
+                source_image: None,
                 bg_color: color,
-                linked_window: None,
+                linked_windows: Vec::new(),
                 physics,
+                animations,
+                timer_interval: 0, // Started by the first text update or mouse move
+                created_seq: NEXT_WINDOW_SEQ.fetch_add(1, Ordering::Relaxed),
                 interaction_mode: InteractionMode::None,
                 current_resize_edge: ResizeEdge::None, // Initial state
                 drag_start_mouse: POINT { x: 0, y: 0 },
@@ -146,19 +130,39 @@ pub fn create_result_window(target_rect: RECT, win_type: WindowType) -> HWND {
                 has_moved_significantly: false,
                 font_cache_dirty: true,
                 cached_font_size: 72,
-                content_bitmap: HBITMAP(0),
+                content_bitmap: OwnedBitmap::none(),
                 last_w: 0,
                 last_h: 0,
                 pending_text: None,
                 last_text_update_time: 0,
-                bg_bitmap: HBITMAP(0),
+                bg_bitmap: OwnedBitmap::none(),
                 bg_bits: std::ptr::null_mut(),
                 bg_w: 0,
                 bg_h: 0,
+                hidden_by_toggle: false,
+                search: None,
+                scroll_y: 0,
+                edit: None,
+                edited: false,
+                quick_translation: None,
+                text_model: Default::default(),
+                on_translate_btn: false,
+                retry_action: None,
+                on_retry_btn: false,
+                copy_on_close: false,
+                copy_format: CopyFormat::Plain,
+                copy_source: String::new(),
+                font_family: None,
+                rtl: false,
+                is_error: false,
+                key_error: false,
             });
         }
 
+        escape::sync();
+
         SetLayeredWindowAttributes(hwnd, COLORREF(0), 220, LWA_ALPHA);
+        crate::capture::exclude_from_capture(hwnd);
         
         let corner_preference = 2u32; 
         let _ = DwmSetWindowAttribute(
@@ -168,8 +172,6 @@ pub fn create_result_window(target_rect: RECT, win_type: WindowType) -> HWND {
             size_of::<u32>() as u32
         );
         
-        SetTimer(hwnd, 3, 16, None);
-        
         InvalidateRect(hwnd, None, false);
         UpdateWindow(hwnd);
         
@@ -177,13 +179,273 @@ pub fn create_result_window(target_rect: RECT, win_type: WindowType) -> HWND {
     }
 }
 
+/// Result windows with live state (open, hidden or not yet swept)
+pub fn live_window_count() -> usize {
+    WINDOW_STATES.lock().unwrap().len()
+}
+
+/// Destroys the result windows still open on the calling thread. Session threads call this
+/// when their message loop returns: windows left to die with the thread never get a
+/// WM_DESTROY, so their state would stay behind.
+pub fn destroy_thread_windows() {
+    use windows::Win32::System::Threading::GetCurrentThreadId;
+    let thread = unsafe { GetCurrentThreadId() };
+    let hwnds: Vec<HWND> = WINDOW_STATES.lock().unwrap().keys()
+        .map(|&h| HWND(h))
+        .filter(|&h| unsafe { GetWindowThreadProcessId(h, None) } == thread)
+        .collect();
+    // Not under the lock: WM_DESTROY takes it
+    for hwnd in hwnds {
+        unsafe { DestroyWindow(hwnd); }
+    }
+}
+
+/// How often the janitor looks for states of windows that are already gone
+const JANITOR_INTERVAL_SECS: u64 = 30;
+
+/// Drops the state of result windows that died without a WM_DESTROY (their thread panicked
+/// or exited first), freeing their bitmaps
+pub fn start_state_janitor() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(std::time::Duration::from_secs(JANITOR_INTERVAL_SECS));
+        let dropped = state::drop_destroyed_windows();
+        if dropped > 0 {
+            log::warn!("Dropped state of {} result window(s) destroyed without WM_DESTROY", dropped);
+            escape::sync();
+            outside_click::sync();
+        }
+    });
+}
+
+/// GDI objects held by the whole process (Task Manager's "GDI objects" column)
+pub fn gdi_object_count() -> u32 {
+    use windows::Win32::System::Threading::{GetCurrentProcess, GetGuiResources, GR_GDIOBJECTS};
+    unsafe { GetGuiResources(GetCurrentProcess(), GR_GDIOBJECTS) }
+}
+
+/// Windows stops handing out GDI objects at 10,000 per process; warn well before that
+#[cfg(debug_assertions)]
+const GDI_WARN_THRESHOLD: u32 = 5000;
+
+/// Debug builds: log the GDI object count once a minute, so a leak shows up as a steady
+/// climb long before result windows stop painting
+#[cfg(debug_assertions)]
+pub fn start_gdi_monitor() {
+    std::thread::spawn(|| loop {
+        std::thread::sleep(std::time::Duration::from_secs(60));
+        let count = gdi_object_count();
+        let windows = WINDOW_STATES.lock().unwrap().len();
+        if count > GDI_WARN_THRESHOLD {
+            log::warn!("GDI objects: {} ({} result window state(s)) - likely a leak", count, windows);
+        } else {
+            log::debug!("GDI objects: {} ({} result window state(s))", count, windows);
+        }
+    });
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Side { Right, Bottom, Left, Top }
+
+/// Position a window of the given size next to the selection, trying sides in `priority` order.
+unsafe fn place_beside_selection(target_rect: &RECT, width: i32, height: i32, priority: [Side; 4]) -> (i32, i32) {
+    let padding = 10;
+    
+    // --- INTELLIGENT MONITOR-AWARE POSITIONING ---
+    // 1. Get the monitor that contains the selection
+    let hmonitor = MonitorFromRect(target_rect, MONITOR_DEFAULTTONEAREST);
+    
+    // 2. Get that monitor's WORK AREA (excludes taskbars)
+    let mut mi = MONITORINFO::default();
+    mi.cbSize = size_of::<MONITORINFO>() as u32;
+    GetMonitorInfoW(hmonitor, &mut mi);
+    let work_rect = mi.rcWork;
+
+    // Potential coordinates
+    let pos_right_x = target_rect.right + padding;
+    let pos_left_x  = target_rect.left - width - padding;
+    let pos_bottom_y = target_rect.bottom + padding;
+    let pos_top_y    = target_rect.top - height - padding;
+
+    // Calculate available space on each side relative to the WORK AREA
+    let space_right  = work_rect.right - pos_right_x;
+    let space_left   = (target_rect.left - padding) - work_rect.left;
+    let space_bottom = work_rect.bottom - pos_bottom_y;
+    let space_top    = (target_rect.top - padding) - work_rect.top;
+
+    let fits = |side: Side| match side {
+        Side::Right => space_right >= width,
+        Side::Bottom => space_bottom >= height,
+        Side::Left => space_left >= width,
+        Side::Top => space_top >= height,
+    };
+    let space = |side: Side| match side {
+        Side::Right => space_right,
+        Side::Bottom => space_bottom,
+        Side::Left => space_left,
+        Side::Top => space_top,
+    };
+    let pos = |side: Side| match side {
+        Side::Right => (pos_right_x, target_rect.top),
+        Side::Bottom => (target_rect.left, pos_bottom_y),
+        Side::Left => (pos_left_x, target_rect.top),
+        Side::Top => (target_rect.left, pos_top_y),
+    };
+
+    // 3. Logic: Find best side in priority order
+    // 4. Fallback: Pick the side with the MOST available space (minimizes overlap)
+    let side = priority.iter().copied().find(|s| fits(*s)).unwrap_or_else(|| {
+        let mut best = priority[0];
+        for s in priority {
+            if space(s) > space(best) { best = s; }
+        }
+        best
+    });
+    let (mut best_x, mut best_y) = pos(side);
+    
+    // 5. FINAL SAFEGUARD: Hard Clamp to Monitor Work Area
+    // This ensures the window is fully visible even if it has to overlap the selection.
+    let safe_w = width.min(work_rect.right - work_rect.left);
+    let safe_h = height.min(work_rect.bottom - work_rect.top);
+    
+    best_x = best_x.clamp(work_rect.left, work_rect.right - safe_w);
+    best_y = best_y.clamp(work_rect.top, work_rect.bottom - safe_h);
+
+    (best_x, best_y)
+}
+
+/// Rect the size of the selection next to it, for a `SecondaryExplicit` window (compare
+/// mode). `prefer_left` leaves the right side to a retranslation window.
+pub fn rect_beside_selection(target_rect: RECT, prefer_left: bool) -> RECT {
+    let width = (target_rect.right - target_rect.left).abs();
+    let height = (target_rect.bottom - target_rect.top).abs();
+    let priority = if prefer_left {
+        [Side::Left, Side::Top, Side::Right, Side::Bottom]
+    } else {
+        [Side::Right, Side::Left, Side::Bottom, Side::Top]
+    };
+    let (x, y) = unsafe { place_beside_selection(&target_rect, width, height, priority) };
+    RECT { left: x, top: y, right: x + width, bottom: y + height }
+}
+
+/// Whether the last toggle hid the result windows
+static RESULTS_HIDDEN: AtomicBool = AtomicBool::new(false);
+
+/// Hide every visible result window, or bring back exactly the ones hidden that way.
+/// Windows the user had hidden themselves stay hidden.
+pub fn toggle_all_result_windows() {
+    let hide = !RESULTS_HIDDEN.load(Ordering::SeqCst);
+    let mut hidden_any = false;
+    let mut states = WINDOW_STATES.lock().unwrap();
+    for (&key, state) in states.iter_mut() {
+        let hwnd = HWND(key);
+        unsafe {
+            if !IsWindow(hwnd).as_bool() { continue; }
+            // Async: the windows live on other threads that may be waiting on WINDOW_STATES
+            if hide {
+                if IsWindowVisible(hwnd).as_bool() {
+                    ShowWindowAsync(hwnd, SW_HIDE);
+                    state.hidden_by_toggle = true;
+                    hidden_any = true;
+                }
+            } else if state.hidden_by_toggle {
+                ShowWindowAsync(hwnd, SW_SHOWNOACTIVATE);
+                state.hidden_by_toggle = false;
+            }
+        }
+    }
+    // Nothing to hide: stay in "shown" so the next press hides again
+    RESULTS_HIDDEN.store(hide && hidden_any, Ordering::SeqCst);
+}
+
+/// Hide the visible result windows so a capture doesn't include them (fallback
+/// when capture exclusion isn't available); returns the windows to restore
+pub fn hide_for_capture() -> Vec<HWND> {
+    let visible: Vec<HWND> = WINDOW_STATES.lock().unwrap().keys()
+        .map(|&key| HWND(key))
+        .filter(|&hwnd| unsafe { IsWindowVisible(hwnd).as_bool() })
+        .collect();
+    if !visible.is_empty() {
+        unsafe {
+            for &hwnd in &visible {
+                ShowWindowAsync(hwnd, SW_HIDE);
+            }
+        }
+        // Let DWM compose a frame without them
+        std::thread::sleep(std::time::Duration::from_millis(60));
+    }
+    visible
+}
+
+pub fn restore_after_capture(hidden: &[HWND]) {
+    for &hwnd in hidden {
+        unsafe {
+            if IsWindow(hwnd).as_bool() {
+                ShowWindowAsync(hwnd, SW_SHOWNOACTIVATE);
+            }
+        }
+    }
+}
+
 pub fn update_window_text(hwnd: HWND, text: &str) {
     if !unsafe { IsWindow(hwnd).as_bool() } { return; }
     
-    let mut states = WINDOW_STATES.lock().unwrap();
-    if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
-        state.pending_text = Some(text.to_string());
+    {
+        let mut states = WINDOW_STATES.lock().unwrap();
+        if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+            if state.edited { return; } // Keep the user's correction
+            if quick_translate::redirect_update(state, text, None) { return; }
+            state.pending_text = Some(text.to_string());
+            state.raw_text = None;
+            state.unreflowed_text = None;
+            state.study = None;
+            state::set_error_style(state, false);
+        }
+    }
+    // The timer that applies it may be stopped (and only the window's thread can start it)
+    unsafe { PostMessageW(hwnd, logic::WM_WAKE_TIMER, WPARAM(0), LPARAM(0)); }
+}
+
+/// Show an error message styled so it can't pass for a result
+pub fn update_window_error(hwnd: HWND, message: &str) {
+    show_error(hwnd, message, false);
+}
+
+/// Show a failed request (`get_error_message`). Key problems point to the API key
+/// settings, which a click on the window opens.
+pub fn update_window_request_error(hwnd: HWND, error: &anyhow::Error, lang: &str) {
+    let key_error = crate::api_error::ApiError::of(error).map_or(false, |e| e.is_key_error());
+    show_error(hwnd, &crate::overlay::utils::get_error_message(error, lang), key_error);
+}
+
+fn show_error(hwnd: HWND, message: &str, key_error: bool) {
+    update_window_text(hwnd, message);
+    if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+        // Not over the user's correction or a quick translation (the error wasn't shown)
+        if !state.edited && state.quick_translation.is_none() {
+            state::set_error_style(state, true);
+            state.key_error = key_error;
+        }
+    }
+}
+
+/// Show `markdown` cleaned for display but keep the source for "copy Markdown"
+pub fn update_window_markdown(hwnd: HWND, markdown: &str) {
+    if !unsafe { IsWindow(hwnd).as_bool() } { return; }
+
+    let display = crate::overlay::utils::clean_markdown_for_display(markdown);
+    {
+        let mut states = WINDOW_STATES.lock().unwrap();
+        if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+            if state.edited { return; }
+            if quick_translate::redirect_update(state, &display, Some(markdown)) { return; }
+            state.pending_text = Some(display);
+            state.raw_text = Some(markdown.to_string());
+            state.unreflowed_text = None;
+            state.study = None;
+            state::set_error_style(state, false);
+        }
     }
+    unsafe { PostMessageW(hwnd, logic::WM_WAKE_TIMER, WPARAM(0), LPARAM(0)); }
 }
 
 fn get_copy_btn_rect(window_w: i32, window_h: i32) -> RECT {
@@ -204,6 +466,237 @@ fn get_copy_btn_rect(window_w: i32, window_h: i32) -> RECT {
     }
 }
 
+/// A button's rect in a right-to-left window: the whole row is mirrored to the bottom-left
+fn mirror_btn_rect(btn: RECT, window_w: i32, rtl: bool) -> RECT {
+    if !rtl { return btn; }
+    RECT { left: window_w - btn.right, top: btn.top, right: window_w - btn.left, bottom: btn.bottom }
+}
+
+fn is_rtl_window(hwnd: HWND) -> bool {
+    WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).map_or(false, |s| s.rtl)
+}
+
+/// Export button sits just left of the copy button
+fn get_export_btn_rect(window_w: i32, window_h: i32) -> RECT {
+    let copy = get_copy_btn_rect(window_w, window_h);
+    let gap = 8;
+    let btn_size = copy.right - copy.left;
+    RECT { left: copy.left - gap - btn_size, top: copy.top, right: copy.left - gap, bottom: copy.bottom }
+}
+
+fn has_export_action(hwnd: HWND) -> bool {
+    WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).map_or(false, |s| s.export_action.is_some())
+}
+
+/// The edit button takes the export button's place; live sessions (which have an
+/// export action) keep rewriting their text and export timed segments, so they can't be edited
+fn get_edit_btn_rect(window_w: i32, window_h: i32) -> RECT {
+    get_export_btn_rect(window_w, window_h)
+}
+
+/// Globe button (quick translate), left of the pencil; same windows as editing
+fn get_translate_btn_rect(window_w: i32, window_h: i32) -> RECT {
+    let edit = get_edit_btn_rect(window_w, window_h);
+    let gap = 8;
+    let btn_size = edit.right - edit.left;
+    RECT { left: edit.left - gap - btn_size, top: edit.top, right: edit.left - gap, bottom: edit.bottom }
+}
+
+/// Retry button of a failed request, bottom-left
+fn get_retry_btn_rect(window_w: i32, window_h: i32) -> RECT {
+    let copy = get_copy_btn_rect(window_w, window_h);
+    RECT { left: 12, top: copy.top, right: 12 + 84, bottom: copy.bottom }
+}
+
+fn has_retry_action(hwnd: HWND) -> bool {
+    WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).map_or(false, |s| s.retry_action.is_some())
+}
+
+fn is_key_error_window(hwnd: HWND) -> bool {
+    WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).map_or(false, |s| s.is_error && s.key_error)
+}
+
+fn is_editable(hwnd: HWND) -> bool {
+    WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).map_or(false, |s| s.export_action.is_none())
+}
+
+fn is_editing(hwnd: HWND) -> bool {
+    WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).map_or(false, |s| s.edit.is_some())
+}
+
+/// The window text as UTF-16, cut to whole characters. The buffer is sized before the text is
+/// read, so a streamed update applied in between (a read from another thread) can end it in
+/// the middle of a surrogate pair, which would show and copy as U+FFFD.
+unsafe fn window_text_units(hwnd: HWND) -> Vec<u16> {
+    let text_len = GetWindowTextLengthW(hwnd).max(0) + 1;
+    let mut buf = vec![0u16; text_len as usize];
+    let copied = GetWindowTextW(hwnd, &mut buf).max(0) as usize;
+    buf.truncate(copied);
+    let whole = edit::whole_characters(&buf).len();
+    buf.truncate(whole);
+    buf
+}
+
+/// Enter edit mode (caret at the end) or leave it
+unsafe fn toggle_edit_mode(hwnd: HWND) {
+    if !is_editable(hwnd) { return; }
+    let text_len = GetWindowTextLengthW(hwnd).max(0) as usize;
+    let entering = {
+        let mut states = WINDOW_STATES.lock().unwrap();
+        let Some(state) = states.get_mut(&(hwnd.0 as isize)) else { return };
+        state.edit = match state.edit {
+            Some(_) => None,
+            None => Some(edit::EditState { caret: text_len, reveal_caret: true }),
+        };
+        state.search = None;
+        state.scroll_y = 0;
+        state.font_cache_dirty = true;
+        state.edit.is_some()
+    };
+    if entering {
+        SetForegroundWindow(hwnd);
+        SetFocus(hwnd);
+    } else {
+        // Apply text that arrived while editing
+        logic::update_timer(hwnd);
+    }
+    InvalidateRect(hwnd, None, false);
+}
+
+/// Run an edit-mode keystroke against the window text; a change marks the window as
+/// edited, so copy uses the corrected text and later result updates don't replace it
+unsafe fn edit_window_text(hwnd: HWND, apply: impl FnOnce(&mut edit::EditState, &mut Vec<u16>)) {
+    let mut buf = window_text_units(hwnd);
+    let before = buf.clone();
+
+    let changed = {
+        let mut states = WINDOW_STATES.lock().unwrap();
+        let Some(state) = states.get_mut(&(hwnd.0 as isize)) else { return };
+        let Some(edit) = state.edit.as_mut() else { return };
+        apply(edit, &mut buf);
+        let changed = buf != before;
+        if changed {
+            state.edited = true;
+            state.raw_text = None;
+            state.pending_text = None;
+        }
+        state.font_cache_dirty = true;
+        changed
+    };
+    if changed {
+        buf.push(0);
+        SetWindowTextW(hwnd, PCWSTR(buf.as_ptr()));
+    }
+    InvalidateRect(hwnd, None, false);
+}
+
+/// The user dismissed the window (click, Escape, outside click): do the copy on close,
+/// then close it with its linked windows
+unsafe fn dismiss_with_linked(hwnd: HWND) {
+    copy_on_dismiss(hwnd);
+    logic::close_with_linked(hwnd);
+}
+
+/// Copy the text of the window in `hwnd`'s group that has copy on close. The flag is
+/// taken, so dismissing the rest of the group doesn't copy again. Windows closed any
+/// other way (shutdown, a new capture replacing them) don't copy.
+unsafe fn copy_on_dismiss(hwnd: HWND) {
+    let target = {
+        let mut states = WINDOW_STATES.lock().unwrap();
+        let linked = states.get(&(hwnd.0 as isize)).map(|s| s.linked_windows.clone()).unwrap_or_default();
+        std::iter::once(hwnd).chain(linked).find(|h| {
+            states.get_mut(&(h.0 as isize)).map_or(false, |s| std::mem::take(&mut s.copy_on_close))
+        })
+    };
+    if let Some(target) = target {
+        copy_window_text(target, false, copy_button_markdown(), None);
+    }
+}
+
+/// Copy the window's text and flash the copy icon. `rich` also places CF_HTML and,
+/// for image-grounded results, the capture thumbnail. `markdown` copies a chat
+/// result's Markdown source instead of the cleaned text shown. Plain copies are
+/// wrapped in the preset's copy format unless `format` picks another. False if the
+/// clipboard couldn't be set (another app holding it open).
+unsafe fn copy_window_text(hwnd: HWND, rich: bool, markdown: bool, format: Option<CopyFormat>) -> bool {
+    let raw = if markdown && !rich {
+        WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).and_then(|s| s.raw_text.clone())
+    } else {
+        None
+    };
+    let text = raw.unwrap_or_else(|| String::from_utf16_lossy(&window_text_units(hwnd)));
+
+    let copied = if rich {
+        let image = state::get_source_image(hwnd);
+        crate::overlay::utils::copy_rich_to_clipboard(&text, image.as_ref(), hwnd)
+    } else {
+        crate::overlay::utils::copy_to_clipboard(&format_for_copy(hwnd, &text, format), hwnd)
+    };
+    show_copied(hwnd);
+    copied
+}
+
+/// Flash the copy icon green
+unsafe fn show_copied(hwnd: HWND) {
+    {
+        let mut states = WINDOW_STATES.lock().unwrap();
+        if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+            state.copy_success = true;
+        }
+    }
+    InvalidateRect(hwnd, None, false);
+    SetTimer(hwnd, 1, 1500, None);
+}
+
+/// Shift+right click: a menu of the copy formats; the chosen one is used for this copy only.
+/// A reflowed result also offers the text with the model's line breaks.
+unsafe fn show_copy_format_menu(hwnd: HWND) {
+    let Ok(menu) = CreatePopupMenu() else { return };
+    let lang = crate::APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+    let text = crate::gui::LocaleText::get(&lang);
+    let (current, unreflowed) = WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize))
+        .map(|s| (s.copy_format, s.unreflowed_text.clone().filter(|_| s.quick_translation.is_none())))
+        .unwrap_or_default();
+    for (i, format) in CopyFormat::ALL.into_iter().enumerate() {
+        let flags = if format == current { MF_STRING | MF_CHECKED } else { MF_STRING };
+        let label = to_wstring(crate::gui::copy_format_name(&text, format));
+        AppendMenuW(menu, flags, i + 1, PCWSTR(label.as_ptr()));
+    }
+    let unreflowed_item = CopyFormat::ALL.len() + 1;
+    if unreflowed.is_some() {
+        AppendMenuW(menu, MF_SEPARATOR, 0, PCWSTR::null());
+        let label = to_wstring(text.copy_unreflowed_label);
+        AppendMenuW(menu, MF_STRING, unreflowed_item, PCWSTR(label.as_ptr()));
+    }
+    let mut pt = POINT::default();
+    GetCursorPos(&mut pt);
+    // Without this the menu stays open when the user clicks elsewhere
+    SetForegroundWindow(hwnd);
+    let choice = TrackPopupMenu(menu, TPM_RETURNCMD | TPM_RIGHTBUTTON | TPM_NONOTIFY, pt.x, pt.y, 0, hwnd, None).0 as usize;
+    DestroyMenu(menu);
+    if let Some(&format) = choice.checked_sub(1).and_then(|i| CopyFormat::ALL.get(i)) {
+        copy_window_text(hwnd, false, !copy_button_markdown(), Some(format));
+    } else if let (true, Some(text)) = (choice == unreflowed_item, unreflowed) {
+        crate::overlay::utils::copy_to_clipboard(&format_for_copy(hwnd, &text, None), hwnd);
+        show_copied(hwnd);
+    }
+}
+
+fn copy_button_markdown() -> bool {
+    crate::APP.lock().map(|app| app.config.copy_button_markdown).unwrap_or(true)
+}
+
+/// The "overlay animations" setting, unless Windows has animations turned off
+/// (Settings > Accessibility > Visual effects > Animation effects)
+fn overlay_animations_enabled() -> bool {
+    let setting = crate::APP.lock().map(|app| app.config.overlay_animations).unwrap_or(true);
+    let mut system = BOOL(1);
+    unsafe {
+        SystemParametersInfoW(SPI_GETCLIENTAREAANIMATION, 0, Some(&mut system as *mut BOOL as *mut _), SYSTEM_PARAMETERS_INFO_UPDATE_FLAGS(0));
+    }
+    setting && system.as_bool()
+}
+
 fn get_resize_edge(width: i32, height: i32, x: i32, y: i32) -> ResizeEdge {
     let margin = 8;
     let left = x < margin;
@@ -246,18 +739,43 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
                 ResizeEdge::TopRight | ResizeEdge::BottomLeft => cursor_id = IDC_SIZENESW,
                 ResizeEdge::None => {
                     // Check button
-                     let btn_rect = get_copy_btn_rect(rect.right, rect.bottom);
+                     let rtl = is_rtl_window(hwnd);
+                     let btn_rect = mirror_btn_rect(get_copy_btn_rect(rect.right, rect.bottom), rect.right, rtl);
                      let on_btn = pt.x >= btn_rect.left && pt.x <= btn_rect.right && 
                                   pt.y >= btn_rect.top && pt.y <= btn_rect.bottom;
-                    if on_btn {
+                     let export_rect = mirror_btn_rect(get_export_btn_rect(rect.right, rect.bottom), rect.right, rtl);
+                     let on_export = has_export_action(hwnd) &&
+                                  pt.x >= export_rect.left && pt.x <= export_rect.right &&
+                                  pt.y >= export_rect.top && pt.y <= export_rect.bottom;
+                     let on_edit = !on_export && is_editable(hwnd) && {
+                         let edit_rect = mirror_btn_rect(get_edit_btn_rect(rect.right, rect.bottom), rect.right, rtl);
+                         pt.x >= edit_rect.left && pt.x <= edit_rect.right && pt.y >= edit_rect.top && pt.y <= edit_rect.bottom
+                     };
+                     let on_translate = is_editable(hwnd) && !is_editing(hwnd) && {
+                         let translate_rect = mirror_btn_rect(get_translate_btn_rect(rect.right, rect.bottom), rect.right, rtl);
+                         pt.x >= translate_rect.left && pt.x <= translate_rect.right && pt.y >= translate_rect.top && pt.y <= translate_rect.bottom
+                     };
+                     let on_retry = has_retry_action(hwnd) && {
+                         let retry_rect = mirror_btn_rect(get_retry_btn_rect(rect.right, rect.bottom), rect.right, rtl);
+                         pt.x >= retry_rect.left && pt.x <= retry_rect.right && pt.y >= retry_rect.top && pt.y <= retry_rect.bottom
+                     };
+                    if on_btn || on_export || on_edit || on_translate || on_retry {
                         cursor_id = IDC_HAND;
+                    } else if is_editing(hwnd) {
+                        cursor_id = IDC_IBEAM;
+                    } else if is_key_error_window(hwnd) {
+                        cursor_id = IDC_HAND; // Opens the API key settings
                     }
                 }
             }
             
+            let animations = WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).map_or(true, |s| s.animations);
             if !cursor_id.0.is_null() {
                  SetCursor(LoadCursorW(None, cursor_id).unwrap());
                  LRESULT(1)
+            } else if !animations {
+                 SetCursor(LoadCursorW(None, IDC_ARROW).unwrap());
+                 LRESULT(1)
             } else {
                  // Hide standard cursor inside to show broom
                  SetCursor(HCURSOR(0));
@@ -333,13 +851,38 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
                 // Hover state
                 let mut rect = RECT::default();
                 GetClientRect(hwnd, &mut rect);
-                let btn_rect = get_copy_btn_rect(rect.right, rect.bottom);
+                let rtl = state.rtl;
+                let btn_rect = mirror_btn_rect(get_copy_btn_rect(rect.right, rect.bottom), rect.right, rtl);
                 let padding = 4;
                 state.on_copy_btn = 
                     x as i32 >= btn_rect.left - padding && 
                     x as i32 <= btn_rect.right + padding && 
                     y as i32 >= btn_rect.top - padding && 
                     y as i32 <= btn_rect.bottom + padding;
+                let export_rect = mirror_btn_rect(get_export_btn_rect(rect.right, rect.bottom), rect.right, rtl);
+                state.on_export_btn = state.export_action.is_some() &&
+                    x as i32 >= export_rect.left - padding &&
+                    x as i32 <= export_rect.right + padding &&
+                    y as i32 >= export_rect.top - padding &&
+                    y as i32 <= export_rect.bottom + padding;
+                let edit_rect = mirror_btn_rect(get_edit_btn_rect(rect.right, rect.bottom), rect.right, rtl);
+                state.on_edit_btn = state.export_action.is_none() &&
+                    x as i32 >= edit_rect.left - padding &&
+                    x as i32 <= edit_rect.right + padding &&
+                    y as i32 >= edit_rect.top - padding &&
+                    y as i32 <= edit_rect.bottom + padding;
+                let translate_rect = mirror_btn_rect(get_translate_btn_rect(rect.right, rect.bottom), rect.right, rtl);
+                state.on_translate_btn = state.export_action.is_none() && state.edit.is_none() &&
+                    x as i32 >= translate_rect.left - padding &&
+                    x as i32 <= translate_rect.right + padding &&
+                    y as i32 >= translate_rect.top - padding &&
+                    y as i32 <= translate_rect.bottom + padding;
+                let retry_rect = mirror_btn_rect(get_retry_btn_rect(rect.right, rect.bottom), rect.right, rtl);
+                state.on_retry_btn = state.retry_action.is_some() &&
+                    x as i32 >= retry_rect.left - padding &&
+                    x as i32 <= retry_rect.right + padding &&
+                    y as i32 >= retry_rect.top - padding &&
+                    y as i32 <= retry_rect.bottom + padding;
 
                 if !state.is_hovered {
                     state.is_hovered = true;
@@ -413,6 +956,9 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
                 
                 InvalidateRect(hwnd, None, false);
             }
+            drop(states);
+            // The broom moved: run the physics until it settles again
+            logic::update_timer(hwnd);
             LRESULT(0)
         }
 
@@ -421,6 +967,10 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
             if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
                 state.is_hovered = false;
                 state.on_copy_btn = false;
+                state.on_export_btn = false;
+                state.on_edit_btn = false;
+                state.on_translate_btn = false;
+                state.on_retry_btn = false;
                 state.current_resize_edge = ResizeEdge::None; // Reset edge on leave
                 InvalidateRect(hwnd, None, false);
             }
@@ -431,6 +981,12 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
             ReleaseCapture();
             let mut perform_click = false;
             let mut is_copy_click = false;
+            let mut export_click: Option<state::ExportAction> = None;
+            let mut is_edit_click = false;
+            let mut is_translate_click = false;
+            let mut retry_click: Option<state::ExportAction> = None;
+            let mut editing = false;
+            let mut key_error = false;
             
             // Check interaction end
             {
@@ -441,27 +997,43 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
                     if !state.has_moved_significantly {
                         perform_click = true;
                         is_copy_click = state.on_copy_btn;
+                        if state.on_export_btn { export_click = state.export_action.clone(); }
+                        is_edit_click = state.on_edit_btn;
+                        is_translate_click = state.on_translate_btn;
+                        if state.on_retry_btn {
+                            // The error goes away with the button; a new failure brings both back
+                            retry_click = state.retry_action.take();
+                            state.on_retry_btn = false;
+                        }
                     }
+                    editing = state.edit.is_some();
+                    key_error = state.is_error && state.key_error;
                 }
             }
             
             if perform_click {
-                 if is_copy_click {
-                    let text_len = GetWindowTextLengthW(hwnd) + 1;
-                    let mut buf = vec![0u16; text_len as usize];
-                    GetWindowTextW(hwnd, &mut buf);
-                    let text = String::from_utf16_lossy(&buf[..text_len as usize - 1]).to_string();
-                    crate::overlay::utils::copy_to_clipboard(&text, hwnd);
-                    
-                    {
-                        let mut states = WINDOW_STATES.lock().unwrap();
-                        if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
-                            state.copy_success = true;
-                        }
-                    }
-                    SetTimer(hwnd, 1, 1500, None);
+                 if let Some(action) = export_click {
+                    // Run outside the state lock; the action may touch other windows
+                    action();
+                 } else if let Some(action) = retry_click {
+                    action();
+                    InvalidateRect(hwnd, None, false);
+                 } else if is_copy_click {
+                    // Ctrl+click on the copy button = rich copy
+                    copy_window_text(hwnd, GetKeyState(VK_CONTROL.0 as i32) < 0, copy_button_markdown(), None);
+                 } else if is_edit_click {
+                    toggle_edit_mode(hwnd);
+                 } else if is_translate_click {
+                    quick_translate::toggle(hwnd);
+                 } else if editing {
+                    // Clicking the text while editing must not sweep the window away
+                 } else if key_error {
+                    crate::gui::show_api_keys();
+                 } else if !WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).map_or(true, |s| s.animations) {
+                     dismiss_with_linked(hwnd);
                  } else {
-                     // Smash Animation
+                     // Smash Animation; the text is copied now, while it's still there
+                     copy_on_dismiss(hwnd);
                      {
                         let mut states = WINDOW_STATES.lock().unwrap();
                         if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
@@ -469,20 +1041,26 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
                             state.physics.state_timer = 0.0;
                         }
                     }
+                    logic::update_timer(hwnd);
                     
-                    let (linked_hwnd, main_alpha) = {
+                    let (linked_hwnds, main_alpha) = {
                         let states = WINDOW_STATES.lock().unwrap();
-                        let linked = if let Some(state) = states.get(&(hwnd.0 as isize)) { state.linked_window } else { None };
+                        let linked = if let Some(state) = states.get(&(hwnd.0 as isize)) { state.linked_windows.clone() } else { Vec::new() };
                         let alpha = if let Some(state) = states.get(&(hwnd.0 as isize)) { state.alpha } else { 220 };
                         (linked, alpha)
                     };
-                    if let Some(linked) = linked_hwnd {
+                    for linked in linked_hwnds {
                         if IsWindow(linked).as_bool() {
                             let mut states = WINDOW_STATES.lock().unwrap();
                             if let Some(state) = states.get_mut(&(linked.0 as isize)) {
+                                if !state.animations {
+                                    PostMessageW(linked, WM_CLOSE, WPARAM(0), LPARAM(0));
+                                    continue;
+                                }
                                 state.physics.mode = AnimationMode::DragOut;
                                 state.physics.state_timer = 0.0;
                                 state.alpha = main_alpha;
+                                PostMessageW(linked, logic::WM_WAKE_TIMER, WPARAM(0), LPARAM(0));
                             }
                         }
                     }
@@ -492,20 +1070,15 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
         }
         
         WM_RBUTTONUP => {
-            // Right click always copies
-            let text_len = GetWindowTextLengthW(hwnd) + 1;
-            let mut buf = vec![0u16; text_len as usize];
-            GetWindowTextW(hwnd, &mut buf);
-            let text = String::from_utf16_lossy(&buf[..text_len as usize - 1]).to_string();
-            crate::overlay::utils::copy_to_clipboard(&text, hwnd);
-            
-            {
-                let mut states = WINDOW_STATES.lock().unwrap();
-                if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
-                    state.copy_success = true;
-                }
+            // Right click always copies (the other of Markdown / plain text than the copy
+            // button); Ctrl+Right-click copies text + HTML (+ source image), and
+            // Shift+Right-click asks which copy format to use
+            if (wparam.0 & 0x0004) != 0 { // MK_SHIFT
+                show_copy_format_menu(hwnd);
+            } else {
+                let rich = (wparam.0 & 0x0008) != 0; // MK_CONTROL
+                copy_window_text(hwnd, rich, !copy_button_markdown(), None);
             }
-            SetTimer(hwnd, 1, 1500, None);
             LRESULT(0)
         }
 
@@ -520,7 +1093,7 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
             {
                 let mut states = WINDOW_STATES.lock().unwrap();
                 if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
-                     if state.pending_text.is_some() && 
+                     if state.pending_text.is_some() && state.edit.is_none() &&
                         (state.last_text_update_time == 0 || now.wrapping_sub(state.last_text_update_time) > 66) {
                          
                          pending_update = state.pending_text.take();
@@ -544,19 +1117,26 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
             if need_repaint {
                 InvalidateRect(hwnd, None, false);
             }
+            // Stops the timer once nothing moves and no text is waiting
+            logic::update_timer(hwnd);
+            LRESULT(0)
+        }
+
+        logic::WM_WAKE_TIMER => {
+            logic::update_timer(hwnd);
+            LRESULT(0)
+        }
+
+        logic::WM_DISMISS => {
+            dismiss_with_linked(hwnd);
             LRESULT(0)
         }
 
         WM_DESTROY => {
-            let mut states = WINDOW_STATES.lock().unwrap();
-            if let Some(state) = states.remove(&(hwnd.0 as isize)) {
-                if state.content_bitmap.0 != 0 {
-                    DeleteObject(state.content_bitmap);
-                }
-                if state.bg_bitmap.0 != 0 {
-                    DeleteObject(state.bg_bitmap);
-                }
-            }
+            // Dropping the state deletes its cached bitmaps
+            WINDOW_STATES.lock().unwrap().remove(&(hwnd.0 as isize));
+            escape::sync(); // Last window gone: Escape is left alone again
+            outside_click::sync();
             LRESULT(0)
         }
 
@@ -565,11 +1145,194 @@ unsafe extern "system" fn result_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM,
             LRESULT(0)
         }
         WM_KEYDOWN => {
-            if wparam.0 == VK_ESCAPE.0 as usize { 
-                 PostMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+            let ctrl = GetKeyState(VK_CONTROL.0 as i32) < 0;
+            let shift = GetKeyState(VK_SHIFT.0 as i32) < 0;
+            let key = wparam.0 as u16;
+            if key == VK_F2.0 {
+                toggle_edit_mode(hwnd);
+                return LRESULT(0);
+            }
+            if is_editing(hwnd) && !(ctrl && key == b'F' as u16) {
+                if key == VK_ESCAPE.0 {
+                    toggle_edit_mode(hwnd);
+                } else if key == VK_BACK.0 {
+                    edit_window_text(hwnd, |e, text| e.backspace(text));
+                } else if key == VK_DELETE.0 {
+                    edit_window_text(hwnd, |e, text| e.delete(text));
+                } else if key == VK_LEFT.0 {
+                    edit_window_text(hwnd, |e, text| e.move_to(edit::prev_boundary(text, e.caret)));
+                } else if key == VK_RIGHT.0 {
+                    edit_window_text(hwnd, |e, text| e.move_to(edit::next_boundary(text, e.caret)));
+                } else if key == VK_HOME.0 {
+                    edit_window_text(hwnd, |e, text| e.move_to(if ctrl { 0 } else { edit::line_start(text, e.caret) }));
+                } else if key == VK_END.0 {
+                    edit_window_text(hwnd, |e, text| e.move_to(if ctrl { text.len() } else { edit::line_end(text, e.caret) }));
+                }
+                // Typed text (including Enter) arrives as WM_CHAR
+                return LRESULT(0);
+            }
+            let handled_by_search = {
+                let mut states = WINDOW_STATES.lock().unwrap();
+                match states.get_mut(&(hwnd.0 as isize)) {
+                    Some(state) if ctrl && key == b'F' as u16 => {
+                        state.search.get_or_insert_with(Default::default);
+                        state.edit = None;
+                        state.font_cache_dirty = true;
+                        true
+                    }
+                    Some(state) if state.search.is_some() => {
+                        let search = state.search.as_mut().unwrap();
+                        if key == VK_ESCAPE.0 {
+                            // Close the search, not the window
+                            state.search = None;
+                            state.scroll_y = 0;
+                        } else if key == VK_RETURN.0 {
+                            search.step(!shift);
+                        } else if key == VK_BACK.0 {
+                            let mut query = search.query.clone();
+                            query.pop();
+                            search.set_query(query);
+                        }
+                        state.font_cache_dirty = true;
+                        true
+                    }
+                    _ => false,
+                }
+            };
+
+            if handled_by_search {
+                InvalidateRect(hwnd, None, false);
+            } else if key == VK_ESCAPE.0 {
+                 // Same as the Escape hook when the window isn't focused
+                 dismiss_with_linked(hwnd);
+            } else if key == VK_RETURN.0 && ctrl {
+                // Copy and close; the clipboard is set before WM_CLOSE is posted, and a
+                // failed copy keeps the window so the text isn't lost
+                if copy_window_text(hwnd, false, copy_button_markdown(), None) {
+                    logic::close_with_linked(hwnd);
+                } else {
+                    log::warn!("Copy and close: clipboard busy, keeping the window");
+                }
+            } else if key == VK_RETURN.0 {
+                let action = WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).and_then(|s| s.follow_up_action.clone());
+                if let Some(action) = action { action(); }
+            }
+            LRESULT(0)
+        }
+        WM_CHAR => {
+            if is_editing(hwnd) {
+                // UTF-16 units as typed (surrogate pairs arrive as two messages); Enter = new line
+                let unit = wparam.0 as u16;
+                if unit == b'\r' as u16 {
+                    edit_window_text(hwnd, |e, text| e.insert(text, &[b'\n' as u16]));
+                } else if (unit >= 0x20 && unit != 0x7F) || unit == b'\t' as u16 {
+                    edit_window_text(hwnd, |e, text| e.insert(text, &[unit]));
+                }
+                return LRESULT(0);
+            }
+            // Typed characters go to the search query while it's open
+            let typed = char::from_u32(wparam.0 as u32).filter(|c| !c.is_control());
+            if let Some(c) = typed {
+                let mut states = WINDOW_STATES.lock().unwrap();
+                if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+                    if let Some(search) = state.search.as_mut() {
+                        let mut query = search.query.clone();
+                        query.push(c);
+                        search.set_query(query);
+                        state.font_cache_dirty = true;
+                        drop(states);
+                        InvalidateRect(hwnd, None, false);
+                    }
+                }
+            }
+            LRESULT(0)
+        }
+        WM_MOUSEWHEEL => {
+            // Scrolling only exists while searching (otherwise the text is shrunk to fit)
+            let delta = (wparam.0 >> 16) as u16 as i16 as i32;
+            let mut states = WINDOW_STATES.lock().unwrap();
+            if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+                if state.search.is_some() {
+                    // paint.rs clamps to the text height
+                    state.scroll_y = (state.scroll_y - delta / 2).max(0);
+                    state.font_cache_dirty = true;
+                    drop(states);
+                    InvalidateRect(hwnd, None, false);
+                }
             }
             LRESULT(0)
         }
         _ => DefWindowProcW(hwnd, msg, wparam, lparam),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates, paints, resizes and destroys a result window on the current thread
+    unsafe fn paint_cycle(text: &str) -> HWND {
+        let hwnd = create_result_window(RECT { left: 100, top: 100, right: 420, bottom: 260 }, WindowType::Primary);
+        SetWindowTextW(hwnd, &HSTRING::from(text));
+        ShowWindow(hwnd, SW_SHOWNOACTIVATE);
+        UpdateWindow(hwnd);
+        // New size: the gradient and text bitmaps are both rebuilt
+        SetWindowPos(hwnd, None, 0, 0, 360, 200, SWP_NOMOVE | SWP_NOZORDER | SWP_NOACTIVATE);
+        if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+            state.font_cache_dirty = true;
+        }
+        UpdateWindow(hwnd);
+        hwnd
+    }
+
+    #[test]
+    fn test_mirror_btn_rect() {
+        let copy = get_copy_btn_rect(300, 200);
+        assert_eq!(mirror_btn_rect(copy, 300, false), copy);
+        let mirrored = mirror_btn_rect(copy, 300, true);
+        assert_eq!((mirrored.left, mirrored.right, mirrored.top), (12, 40, copy.top));
+        // The retry button swaps to the other side too
+        let retry = mirror_btn_rect(get_retry_btn_rect(300, 200), 300, true);
+        assert_eq!((retry.left, retry.right), (204, 288));
+    }
+
+    unsafe fn drain_messages() {
+        let mut msg = MSG::default();
+        while PeekMessageW(&mut msg, None, 0, 0, PM_REMOVE).as_bool() {
+            DispatchMessageW(&msg);
+        }
+    }
+
+    /// 500 result windows come and go; GDI objects and window states must return to where
+    /// they started. Every tenth window is left open when its thread exits, like a live
+    /// session's secondary window. Needs a desktop session: `cargo test gdi_soak -- --ignored`
+    #[test]
+    #[ignore]
+    fn gdi_soak() {
+        unsafe {
+            // Warm-up: class registration, stock objects and font caches aren't per window
+            for _ in 0..5 {
+                DestroyWindow(paint_cycle("warm-up"));
+            }
+            drain_messages();
+            let baseline = gdi_object_count();
+            let states_before = WINDOW_STATES.lock().unwrap().len();
+
+            for i in 0..500 {
+                let text = format!("Soak {} - the quick brown fox jumps over the lazy dog", i);
+                if i % 10 == 0 {
+                    std::thread::spawn(move || { paint_cycle(&text); }).join().unwrap();
+                } else {
+                    DestroyWindow(paint_cycle(&text));
+                }
+                drain_messages();
+            }
+            state::drop_destroyed_windows();
+
+            assert_eq!(WINDOW_STATES.lock().unwrap().len(), states_before);
+            let after = gdi_object_count();
+            // A leak of even one object per window would be hundreds over
+            assert!(after <= baseline + 10, "GDI objects {} -> {}", baseline, after);
+        }
+    }
+}
@@ -0,0 +1,92 @@
+//! Click Outside To Dismiss
+//!
+//! With a preset's `dismiss_on_outside_click`, its result windows close like a tooltip
+//! when you click anywhere else. A low-level mouse hook watches button presses while such
+//! a window exists; the click itself is always passed on, so the app under the cursor
+//! still gets it. Clicking any result window (a linked retranslation included) keeps
+//! everything open, and a window whose text is being edited is left alone.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use super::hook::HookThread;
+use super::state::WINDOW_STATES;
+
+static HOOK: HookThread = HookThread::new("Outside click", WH_MOUSE_LL, Some(hook_proc));
+
+/// Install the hook while any window wants to be dismissed, remove it otherwise.
+/// Never called with WINDOW_STATES locked.
+pub fn sync() {
+    let wanted = WINDOW_STATES.lock().unwrap().values().any(|s| s.dismiss_on_outside_click);
+    HOOK.set_installed(wanted);
+}
+
+unsafe extern "system" fn hook_proc(code: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if code == HC_ACTION as i32 && matches!(wparam.0 as u32, WM_LBUTTONDOWN | WM_RBUTTONDOWN | WM_MBUTTONDOWN) {
+        let info = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        for hwnd in dismissed_by_click(info.pt) {
+            PostMessageW(hwnd, super::logic::WM_DISMISS, WPARAM(0), LPARAM(0));
+        }
+    }
+    CallNextHookEx(None, code, wparam, lparam)
+}
+
+struct Candidate {
+    hwnd: isize,
+    rect: RECT,
+    visible: bool,
+    dismissable: bool, // Has the option and isn't being edited
+}
+
+fn contains(rect: &RECT, pt: POINT) -> bool {
+    pt.x >= rect.left && pt.x < rect.right && pt.y >= rect.top && pt.y < rect.bottom
+}
+
+/// Windows to close for a click at `pt`: none if it landed on a visible result window
+fn pick_dismissed(candidates: &[Candidate], pt: POINT) -> Vec<isize> {
+    if candidates.iter().any(|c| c.visible && contains(&c.rect, pt)) {
+        return Vec::new();
+    }
+    candidates.iter().filter(|c| c.visible && c.dismissable).map(|c| c.hwnd).collect()
+}
+
+unsafe fn dismissed_by_click(pt: POINT) -> Vec<HWND> {
+    let candidates: Vec<Candidate> = WINDOW_STATES.lock().unwrap().iter()
+        .map(|(&hwnd, state)| {
+            let mut rect = RECT::default();
+            GetWindowRect(HWND(hwnd), &mut rect);
+            Candidate {
+                hwnd,
+                rect,
+                visible: IsWindowVisible(HWND(hwnd)).as_bool() && !state.hidden_by_toggle,
+                dismissable: state.dismiss_on_outside_click && state.edit.is_none(),
+            }
+        })
+        .collect();
+    pick_dismissed(&candidates, pt).into_iter().map(HWND).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pick_dismissed() {
+        let rect = |left, top| RECT { left, top, right: left + 100, bottom: top + 50 };
+        let window = |hwnd, rect, visible, dismissable| Candidate { hwnd, rect, visible, dismissable };
+        let pt = |x, y| POINT { x, y };
+        // Primary with the option, its linked retranslation, and an unrelated window without it
+        let windows = [window(1, rect(0, 0), true, true), window(2, rect(0, 60), true, false), window(3, rect(300, 0), true, false)];
+
+        assert_eq!(pick_dismissed(&windows, pt(500, 500)), vec![1]);
+        // A click on any result window, the linked one included, dismisses nothing
+        assert!(pick_dismissed(&windows, pt(10, 10)).is_empty());
+        assert!(pick_dismissed(&windows, pt(10, 70)).is_empty());
+        assert!(pick_dismissed(&windows, pt(350, 10)).is_empty());
+        // Right/bottom edges are outside
+        assert_eq!(pick_dismissed(&windows, pt(100, 10)), vec![1]);
+
+        // Hidden windows neither catch the click nor get dismissed
+        let windows = [window(1, rect(0, 0), false, true), window(2, rect(300, 0), true, true)];
+        assert_eq!(pick_dismissed(&windows, pt(10, 10)), vec![2]);
+    }
+}
@@ -1,10 +1,13 @@
 use windows::Win32::Foundation::*;
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
-use windows::core::w;
+use windows::core::{w, PCWSTR};
 use std::mem::size_of;
 use crate::overlay::broom_assets::{render_procedural_broom, BroomRenderParams, BROOM_W, BROOM_H};
-use super::state::{WINDOW_STATES, AnimationMode, ResizeEdge};
+use super::state::{WINDOW_STATES, OwnedBitmap, AnimationMode, ResizeEdge};
+use super::search::{SearchState, find_matches, wrap_lines, scroll_to_reveal};
+use super::edit::EditState;
+use crate::history::StudyFields;
 
 // RAII Wrapper for GDI Objects to ensure cleanup
 struct GdiObj(HGDIOBJ);
@@ -18,18 +21,76 @@ impl Drop for GdiObj {
     }
 }
 
+// Memory DC with one bitmap selected; on drop the bitmap is deselected (so it can be
+// deleted) and the DC is freed
+struct MemDc {
+    dc: CreatedHDC,
+    old: HGDIOBJ,
+}
+impl MemDc {
+    unsafe fn select(hdc: HDC, bitmap: HBITMAP) -> Self {
+        let dc = CreateCompatibleDC(hdc);
+        let old = SelectObject(dc, bitmap);
+        MemDc { dc, old }
+    }
+}
+impl Drop for MemDc {
+    fn drop(&mut self) {
+        unsafe {
+            SelectObject(self.dc, self.old);
+            DeleteDC(self.dc);
+        }
+    }
+}
+
+/// Result text font. `face` is a nul-terminated face name (see `fonts::face_for`); the
+/// pitch and family hints keep a missing face close to Segoe UI, or Consolas when the
+/// layout must be preserved.
+unsafe fn create_text_font(font_size: i32, monospace: bool, face: &[u16]) -> HFONT {
+    let pitch_family = if monospace { FIXED_PITCH.0 | FF_MODERN.0 } else { VARIABLE_PITCH.0 | FF_SWISS.0 };
+    CreateFontW(font_size, 0, 0, 0, FW_MEDIUM.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, pitch_family as u32, PCWSTR(face.as_ptr()))
+}
+
+/// DrawText flags: word-wrapped prose, or verbatim lines (tabs expanded, no wrapping) when preserving layout.
+/// Right-to-left text is laid out from an RTL base direction (see `bidi`).
+fn text_format_flags(preserve_layout: bool, rtl: bool) -> DRAW_TEXT_FORMAT {
+    let flags = if preserve_layout { DT_EXPANDTABS | DT_NOPREFIX } else { DT_WORDBREAK | DT_EDITCONTROL };
+    if rtl { flags | DT_RTLREADING } else { flags }
+}
+
+/// DT_WORDBREAK only breaks at spaces: text with CJK in it is wrapped to `max_width` here
+/// at `line_break` opportunities, then drawn without DT_WORDBREAK (as a preserved layout)
+unsafe fn prewrap_lines(hdc: CreatedHDC, text: &[u16], max_width: i32) -> Vec<u16> {
+    let measure = |s: &[u16]| {
+        let mut size = SIZE::default();
+        GetTextExtentPoint32W(hdc, s, &mut size);
+        size.cx
+    };
+    let end = text.iter().position(|&c| c == 0).unwrap_or(text.len());
+    let mut out = Vec::with_capacity(end + 16);
+    for (i, line) in wrap_lines(&text[..end], max_width, true, measure).into_iter().enumerate() {
+        if i > 0 {
+            out.push(b'\n' as u16);
+        }
+        out.extend_from_slice(&text[line]);
+    }
+    out
+}
+
 // Helper: Measure text dimensions (Height AND Width)
-unsafe fn measure_text_bounds(hdc: windows::Win32::Graphics::Gdi::CreatedHDC, text: &mut [u16], font_size: i32, max_width: i32) -> (i32, i32) {
-    let hfont = CreateFontW(font_size, 0, 0, 0, FW_MEDIUM.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+unsafe fn measure_text_bounds(hdc: windows::Win32::Graphics::Gdi::CreatedHDC, text: &mut [u16], font_size: i32, max_width: i32, preserve_layout: bool, prewrap: bool, rtl: bool, face: &[u16]) -> (i32, i32) {
+    let hfont = create_text_font(font_size, preserve_layout, face);
     let old_font = SelectObject(hdc, hfont);
+    let mut wrapped = if prewrap { prewrap_lines(hdc, text, max_width) } else { Vec::new() };
+    let text = if prewrap { &mut wrapped[..] } else { text };
     
     // We start with the max width constraint.
     // DT_CALCRECT will expand the 'right' value if a single word is wider than max_width (unless we handle it),
-    // or wrap lines which increases 'bottom'.
+    // or wrap lines which increases 'bottom'. Without wrapping (preserve_layout) the widest line sets 'right'.
     let mut calc_rect = RECT { left: 0, top: 0, right: max_width, bottom: 0 };
     
     // DT_EDITCONTROL helps simulate multiline text box behavior
-    DrawTextW(hdc, text, &mut calc_rect, DT_CALCRECT | DT_WORDBREAK | DT_EDITCONTROL);
+    DrawTextW(hdc, text, &mut calc_rect, DT_CALCRECT | text_format_flags(preserve_layout || prewrap, rtl));
     
     SelectObject(hdc, old_font);
     DeleteObject(hfont);
@@ -38,6 +99,186 @@ unsafe fn measure_text_bounds(hdc: windows::Win32::Graphics::Gdi::CreatedHDC, te
     (calc_rect.bottom, calc_rect.right)
 }
 
+// --- STUDY LAYOUT ---
+const STUDY_READING_COLOR: u32 = 0x00F0C890; // Light blue (BGR)
+const STUDY_TRANSLATION_COLOR: u32 = 0x00D0D0D0; // Light grey
+
+/// Lay out a study card's original, reading and translation at `font_size`, each in its
+/// own style with a gap between, and draw them at `origin` if given. Returns
+/// (Height, Width) like `measure_text_bounds`. Each section picks its own font and
+/// direction: the reading is usually Latin even when the original isn't.
+unsafe fn layout_study_card(hdc: CreatedHDC, card: &StudyFields, font_size: i32, max_width: i32, family: Option<&str>, origin: Option<(i32, i32)>) -> (i32, i32) {
+    let sections = [
+        (&card.original, 1.0, FW_SEMIBOLD.0, false, 0x00FFFFFF),
+        (&card.reading, 0.7, FW_NORMAL.0, true, STUDY_READING_COLOR),
+        (&card.translation, 0.9, FW_NORMAL.0, false, STUDY_TRANSLATION_COLOR),
+    ];
+    let (mut y, mut width) = (0, 0);
+    for (text, scale, weight, italic, color) in sections {
+        if text.is_empty() { continue; }
+        if y > 0 { y += font_size / 2; }
+        let size = ((font_size as f32 * scale) as i32).max(8);
+        let face = crate::overlay::utils::to_wstring(family.unwrap_or_else(|| super::fonts::face_for(text, false)));
+        let hfont = CreateFontW(size, 0, 0, 0, weight as i32, italic as u32, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, PCWSTR(face.as_ptr()));
+        let old_font = SelectObject(hdc, hfont);
+
+        let mut buf: Vec<u16> = text.encode_utf16().collect();
+        let prewrap = super::line_break::has_cjk(&buf);
+        if prewrap { buf = prewrap_lines(hdc, &buf, max_width); }
+        let rtl = super::bidi::is_rtl(text);
+        let flags = text_format_flags(prewrap, rtl);
+        let mut calc_rect = RECT { left: 0, top: 0, right: max_width, bottom: 0 };
+        DrawTextW(hdc, &mut buf, &mut calc_rect, DT_CALCRECT | flags);
+        if let Some((x, top)) = origin {
+            let mut draw_rect = RECT { left: x, top: top + y, right: x + max_width, bottom: top + y + calc_rect.bottom };
+            SetTextColor(hdc, COLORREF(color));
+            DrawTextW(hdc, &mut buf, &mut draw_rect, if rtl { DT_RIGHT } else { DT_LEFT } | flags);
+        }
+        y += calc_rect.bottom;
+        width = width.max(calc_rect.right);
+
+        SelectObject(hdc, old_font);
+        DeleteObject(hfont);
+    }
+    (y, width)
+}
+
+// --- SEARCH (Ctrl+F) & EDIT (F2) ---
+const SEARCH_BAR_H: i32 = 30; // Space kept above the text for the search box / edit hint
+const SEARCH_MIN_FONT: i32 = 14; // Long text scrolls rather than shrinking below this
+const MATCH_COLOR: u32 = 0x00205A80; // Muted amber (BGR)
+const CURRENT_MATCH_COLOR: u32 = 0x0000A5FF; // Orange (BGR)
+const EDIT_ACCENT_COLOR: u32 = 0x00E0A030; // Blue (BGR): border and hint while editing
+
+/// Button and hint labels in the UI language
+fn ui_text() -> crate::gui::LocaleText {
+    let lang = crate::APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+    crate::gui::LocaleText::get(&lang)
+}
+
+/// Dark red (BGR) behind an error message
+const ERROR_BG_COLOR: u32 = 0x0022223A;
+
+fn background_color(bg_color: u32, is_error: bool) -> u32 {
+    if is_error { ERROR_BG_COLOR } else { bg_color }
+}
+
+/// Line-by-line text layout used while searching or editing: highlights matches, draws
+/// the caret, and scrolls so the current match / caret is visible.
+/// Returns (scroll_y, match_count, current match).
+unsafe fn draw_line_layout(dc: CreatedHDC, text: &[u16], search: Option<&SearchState>, edit: Option<&EditState>, scroll_y: i32, h_padding: i32, width: i32, height: i32, preserve_layout: bool) -> (i32, usize, usize) {
+    let measure = |s: &[u16]| if s.is_empty() { 0 } else { (GetTabbedTextExtentW(dc, s, None) & 0xFFFF) as i32 };
+    let mut tm = TEXTMETRICW::default();
+    GetTextMetricsW(dc, &mut tm);
+    let line_h = tm.tmHeight.max(1);
+
+    let lines = wrap_lines(text, (width - h_padding * 2).max(1), !preserve_layout, measure);
+    let matches = search.map(|s| find_matches(text, &s.query)).unwrap_or_default();
+    let current = search.map_or(0, |s| s.current.min(matches.len().saturating_sub(1)));
+
+    let view_h = (height - SEARCH_BAR_H).max(line_h);
+    let total_h = lines.len() as i32 * line_h;
+    let mut scroll = scroll_y.clamp(0, (total_h - view_h).max(0));
+    let line_of = |pos: usize, inclusive: bool| lines.iter().position(|l| pos < l.end || (inclusive && pos == l.end)).unwrap_or(lines.len().saturating_sub(1));
+    let reveal = match (search, edit) {
+        (Some(s), _) if s.reveal_current => matches.get(current).map(|m| line_of(m.start, false)),
+        (_, Some(e)) if e.reveal_caret => Some(line_of(e.caret, true)),
+        _ => None,
+    };
+    if let Some(line) = reveal {
+        let line = line as i32;
+        scroll = scroll_to_reveal(line * line_h, (line + 1) * line_h, view_h, scroll).min((total_h - view_h).max(0));
+    }
+    let top = if total_h < view_h { SEARCH_BAR_H + (view_h - total_h) / 2 } else { SEARCH_BAR_H - scroll };
+
+    let match_brush = GdiObj::from_hbrush(CreateSolidBrush(COLORREF(MATCH_COLOR)));
+    let current_brush = GdiObj::from_hbrush(CreateSolidBrush(COLORREF(CURRENT_MATCH_COLOR)));
+    for (i, line) in lines.iter().enumerate() {
+        let y = top + i as i32 * line_h;
+        if y + line_h < 0 || y > height {
+            continue;
+        }
+        for (j, m) in matches.iter().enumerate().filter(|(_, m)| m.start < line.end && m.end > line.start) {
+            let start = m.start.max(line.start);
+            let end = m.end.min(line.end);
+            let highlight = RECT {
+                left: h_padding + measure(&text[line.start..start]),
+                top: y,
+                right: h_padding + measure(&text[line.start..end]),
+                bottom: y + line_h,
+            };
+            let brush = if j == current { &current_brush } else { &match_brush };
+            FillRect(dc, &highlight, HBRUSH(brush.0.0));
+        }
+        TabbedTextOutW(dc, h_padding, y, &text[line.clone()], None, h_padding);
+    }
+
+    if let Some(edit) = edit {
+        let caret = edit.caret.min(text.len());
+        let i = line_of(caret, true);
+        if let Some(line) = lines.get(i) {
+            let x = h_padding + measure(&text[line.start..caret.clamp(line.start, line.end)]);
+            let y = top + i as i32 * line_h;
+            let caret_rect = RECT { left: x, top: y, right: x + 2, bottom: y + line_h };
+            FillRect(dc, &caret_rect, HBRUSH(GetStockObject(WHITE_BRUSH).0));
+        }
+    }
+    (scroll, matches.len(), current)
+}
+
+/// "Editing" hint top-left and an accent border, so edit mode can't be mistaken for the normal view
+unsafe fn draw_edit_frame(dc: CreatedHDC, width: i32, height: i32) {
+    let accent = GdiObj::from_hbrush(CreateSolidBrush(COLORREF(EDIT_ACCENT_COLOR)));
+    for inset in 0..2 {
+        let frame = RECT { left: inset, top: inset, right: width - inset, bottom: height - inset };
+        FrameRect(dc, &frame, HBRUSH(accent.0.0));
+    }
+
+    let ui_language = crate::APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+    let hint = match ui_language.as_str() {
+        "vi" => "✎ Đang sửa · Esc để xong",
+        "ko" => "✎ 편집 중 · Esc로 완료",
+        _ => "✎ Editing · Esc to finish",
+    };
+    let hfont = CreateFontW(13, 0, 0, 0, FW_SEMIBOLD.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+    let old_font = SelectObject(dc, hfont);
+    SetBkMode(dc, TRANSPARENT);
+    SetTextColor(dc, COLORREF(EDIT_ACCENT_COLOR));
+    let mut hint_w: Vec<u16> = hint.encode_utf16().collect();
+    let mut hint_rect = RECT { left: 8, top: 4, right: width - 8, bottom: SEARCH_BAR_H - 4 };
+    DrawTextW(dc, &mut hint_w, &mut hint_rect, DT_LEFT | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS);
+    SelectObject(dc, old_font);
+    DeleteObject(hfont);
+}
+
+/// Search box in the top-right corner: query, caret and "i/n" match count
+unsafe fn draw_search_box(dc: CreatedHDC, search: &SearchState, width: i32) {
+    let hfont = CreateFontW(14, 0, 0, 0, FW_NORMAL.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+    let old_font = SelectObject(dc, hfont);
+
+    let box_w = 220.min(width - 8).max(1);
+    let box_rect = RECT { left: width - 4 - box_w, top: 4, right: width - 4, bottom: SEARCH_BAR_H - 4 };
+    let bg = GdiObj::from_hbrush(CreateSolidBrush(COLORREF(0x00303030)));
+    FillRect(dc, &box_rect, HBRUSH(bg.0.0));
+    let border = GdiObj::from_hbrush(CreateSolidBrush(COLORREF(0x00707070)));
+    FrameRect(dc, &box_rect, HBRUSH(border.0.0));
+
+    SetBkMode(dc, TRANSPARENT);
+    let mut count: Vec<u16> = search.count_label().encode_utf16().collect();
+    let mut count_rect = RECT { left: box_rect.left + 6, top: box_rect.top, right: box_rect.right - 6, bottom: box_rect.bottom };
+    SetTextColor(dc, COLORREF(if search.match_count == 0 && !search.query.is_empty() { 0x006060FF } else { 0x00A0A0A0 }));
+    DrawTextW(dc, &mut count, &mut count_rect, DT_RIGHT | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX);
+
+    // Query with a caret; the start is cut off rather than the end so the caret stays visible
+    let mut query: Vec<u16> = format!("{}|", search.query).encode_utf16().collect();
+    let mut query_rect = RECT { left: box_rect.left + 6, top: box_rect.top, right: box_rect.right - 50, bottom: box_rect.bottom };
+    SetTextColor(dc, COLORREF(0x00FFFFFF));
+    DrawTextW(dc, &mut query, &mut query_rect, DT_LEFT | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX | DT_PATH_ELLIPSIS);
+
+    SelectObject(dc, old_font);
+    DeleteObject(hfont);
+}
+
 pub fn create_bitmap_from_pixels(pixels: &[u32], w: i32, h: i32) -> HBITMAP {
     unsafe {
         let hdc = GetDC(None);
@@ -48,7 +289,7 @@ pub fn create_bitmap_from_pixels(pixels: &[u32], w: i32, h: i32) -> HBITMAP {
             }, ..Default::default()
         };
         let mut bits: *mut core::ffi::c_void = std::ptr::null_mut();
-        let hbm = CreateDIBSection(hdc, &bmi, DIB_RGB_COLORS, &mut bits, None, 0).unwrap();
+        let hbm = CreateDIBSection(hdc, &bmi, DIB_RGB_COLORS, &mut bits, None, 0).unwrap_or(HBITMAP(0));
         if !bits.is_null() {
             std::ptr::copy_nonoverlapping(pixels.as_ptr() as *const u8, bits as *mut u8, pixels.len() * 4);
         }
@@ -76,6 +317,83 @@ fn sd_box(px: f32, py: f32, cx: f32, cy: f32, w: f32, h: f32) -> f32 {
     (dx.max(0.0).powi(2) + dy.max(0.0).powi(2)).sqrt() + dx.max(dy).min(0.0)
 }
 
+/// Composite a round, bordered button with a white SDF icon (`icon_alpha(x, y)`) into the frame
+fn blend_round_button(raw_pixels: &mut [u32], width: i32, height: i32, cx: f32, cy: f32, color: (f32, f32, f32), icon_alpha: impl Fn(f32, f32) -> f32) {
+    let radius = 13.0;
+    let (tr, tg, tb) = color;
+
+    let b_start_x = (cx - radius - 4.0) as i32;
+    let b_end_x = (cx + radius + 4.0) as i32;
+    let b_start_y = (cy - radius - 4.0) as i32;
+    let b_end_y = (cy + radius + 4.0) as i32;
+
+    for y in b_start_y.max(0)..b_end_y.min(height) {
+        for x in b_start_x.max(0)..b_end_x.min(width) {
+            let fx = x as f32;
+            let fy = y as f32;
+            let dx = (fx - cx).abs();
+            let dy = (fy - cy).abs();
+            let dist = (dx*dx + dy*dy).sqrt();
+            
+            // 1. AA for Button Body
+            let aa_body = (radius + 0.5 - dist).clamp(0.0, 1.0);
+
+            // 2. Smooth Ring Border (Thickness 1.5px)
+            let border_inner_radius = radius - 1.5;
+            let border_outer = (radius + 0.5 - dist).clamp(0.0, 1.0);
+            let border_inner = (dist - (border_inner_radius - 0.5)).clamp(0.0, 1.0);
+            let border_alpha = border_outer * border_inner * 0.6; // 60% opacity white border
+
+            // 3. Icon Anti-Aliasing (SDF)
+            let icon_alpha = icon_alpha(fx, fy);
+
+            if aa_body > 0.0 || border_alpha > 0.0 || icon_alpha > 0.0 {
+                let idx = (y * width + x) as usize;
+                let bg = raw_pixels[idx];
+                let bg_b = (bg & 0xFF) as f32;
+                let bg_g = ((bg >> 8) & 0xFF) as f32;
+                let bg_r = ((bg >> 16) & 0xFF) as f32;
+                
+                let mut final_r = bg_r;
+                let mut final_g = bg_g;
+                let mut final_b = bg_b;
+
+                // Composite: Body -> Border -> Icon
+                
+                // A. Body
+                if aa_body > 0.0 {
+                    let alpha = 0.9 * aa_body;
+                    let inv = 1.0 - alpha;
+                    final_r = tr * alpha + final_r * inv;
+                    final_g = tg * alpha + final_g * inv;
+                    final_b = tb * alpha + final_b * inv;
+                }
+
+                // B. Border (Additive)
+                if border_alpha > 0.0 {
+                    final_r += 255.0 * border_alpha;
+                    final_g += 255.0 * border_alpha;
+                    final_b += 255.0 * border_alpha;
+                }
+
+                // C. Icon (White, Normal Blend on top of result)
+                if icon_alpha > 0.0 {
+                    let inv_icon = 1.0 - icon_alpha;
+                    final_r = 255.0 * icon_alpha + final_r * inv_icon;
+                    final_g = 255.0 * icon_alpha + final_g * inv_icon;
+                    final_b = 255.0 * icon_alpha + final_b * inv_icon;
+                }
+
+                final_r = final_r.min(255.0);
+                final_g = final_g.min(255.0);
+                final_b = final_b.min(255.0);
+                
+                raw_pixels[idx] = (255 << 24) | ((final_r as u32) << 16) | ((final_g as u32) << 8) | (final_b as u32);
+            }
+        }
+    }
+}
+
 pub fn paint_window(hwnd: HWND) {
     unsafe {
         let mut ps = PAINTSTRUCT::default();
@@ -88,17 +406,16 @@ pub fn paint_window(hwnd: HWND) {
         // --- PHASE 1: STATE SNAPSHOT & CACHE MANAGEMENT ---
          // We lock the mutex ONCE to read state and update caches if dirty.
          let (
-             bg_color_u32, is_hovered, on_copy_btn, copy_success, broom_data, particles,
+             bg_color_u32, is_hovered, on_copy_btn, copy_success, on_export_btn, has_export, preserve_layout, warning_badge, stats_line, model_badge, broom_data, particles,
              mut cached_text_bm, _cached_font_size, cache_dirty,
-             cached_bg_bm // The background gradient cache
+             cached_bg_bm, // The background gradient cache
+             search, scroll_y, edit, on_edit_btn, on_translate_btn, translation_done, retry, key_error, font_family, mut rtl, study
          ) = {
             let mut states = WINDOW_STATES.lock().unwrap();
             if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
                 
                 // 1.1 Update Background Cache if needed (Resize or First Run)
-                if state.bg_bitmap.0 == 0 || state.bg_w != width || state.bg_h != height {
-                    if state.bg_bitmap.0 != 0 { DeleteObject(state.bg_bitmap); }
-
+                if state.bg_bitmap.is_none() || state.bg_w != width || state.bg_h != height {
                     let bmi = BITMAPINFO {
                         bmiHeader: BITMAPINFOHEADER {
                             biSize: size_of::<BITMAPINFOHEADER>() as u32,
@@ -107,14 +424,15 @@ pub fn paint_window(hwnd: HWND) {
                     };
                     
                     let mut p_bg_bits: *mut core::ffi::c_void = std::ptr::null_mut();
-                    let hbm_bg = CreateDIBSection(hdc, &bmi, DIB_RGB_COLORS, &mut p_bg_bits, None, 0).unwrap();
+                    let hbm_bg = CreateDIBSection(hdc, &bmi, DIB_RGB_COLORS, &mut p_bg_bits, None, 0).unwrap_or(HBITMAP(0));
                     
                     // Draw Gradient into Cache
                     if !p_bg_bits.is_null() {
                         let pixels = std::slice::from_raw_parts_mut(p_bg_bits as *mut u32, (width * height) as usize);
-                        let top_r = (state.bg_color >> 16) & 0xFF;
-                        let top_g = (state.bg_color >> 8) & 0xFF;
-                        let top_b = state.bg_color & 0xFF;
+                        let bg_color = background_color(state.bg_color, state.is_error);
+                        let top_r = (bg_color >> 16) & 0xFF;
+                        let top_g = (bg_color >> 8) & 0xFF;
+                        let top_b = bg_color & 0xFF;
                         let bot_r = (top_r as f32 * 0.6) as u32;
                         let bot_g = (top_g as f32 * 0.6) as u32;
                         let bot_b = (top_b as f32 * 0.6) as u32;
@@ -132,7 +450,7 @@ pub fn paint_window(hwnd: HWND) {
                         }
                     }
                     
-                    state.bg_bitmap = hbm_bg;
+                    state.bg_bitmap = OwnedBitmap::new(hbm_bg); // Deletes the old size's gradient
                     state.bg_w = width;
                     state.bg_h = height;
                 }
@@ -148,10 +466,11 @@ pub fn paint_window(hwnd: HWND) {
                     .map(|p| (p.x, p.y, p.life, p.size, p.color)).collect();
 
                 // HIDE BROOM IF HOVERING RESIZE EDGE
-                let show_broom = state.is_hovered 
+                let show_broom = state.animations && (state.is_hovered 
                     && !state.on_copy_btn 
+                    && !state.on_export_btn
                     && state.current_resize_edge == ResizeEdge::None 
-                    || state.physics.mode == AnimationMode::Smashing;
+                    || state.physics.mode == AnimationMode::Smashing);
                 let broom_info = if show_broom {
                      Some((state.physics.x, state.physics.y, BroomRenderParams {
                             tilt_angle: state.physics.current_tilt,
@@ -162,12 +481,18 @@ pub fn paint_window(hwnd: HWND) {
                 } else { None };
 
                 (
-                    state.bg_color, state.is_hovered, state.on_copy_btn, state.copy_success, broom_info, particles_vec,
-                    state.content_bitmap, state.cached_font_size as i32, state.font_cache_dirty,
-                    state.bg_bitmap
+                    background_color(state.bg_color, state.is_error), state.is_hovered, state.on_copy_btn, state.copy_success,
+                    state.on_export_btn, state.export_action.is_some(), state.preserve_layout, state.warning_badge.clone(), state.stats_line.clone(), state.model_badge.clone(), broom_info, particles_vec,
+                    state.content_bitmap.handle(), state.cached_font_size as i32, state.font_cache_dirty,
+                    state.bg_bitmap.handle(),
+                    state.search.clone(), state.scroll_y, state.edit.clone(), state.on_edit_btn,
+                    state.on_translate_btn, state.quick_translation.as_ref().map(|qt| qt.done),
+                    state.retry_action.as_ref().map(|_| state.on_retry_btn), state.is_error && state.key_error, state.font_family.clone(), state.rtl,
+                    // A quick translation is shown as plain text
+                    state.study.clone().filter(|_| state.quick_translation.is_none())
                 )
             } else {
-                (0, false, false, false, None, Vec::new(), HBITMAP(0), 72, true, HBITMAP(0))
+                (0, false, false, false, false, false, false, None, None, None, None, Vec::new(), HBITMAP(0), 72, true, HBITMAP(0), None, 0, None, false, false, None, None, false, None, false, None)
             }
         };
 
@@ -177,8 +502,6 @@ pub fn paint_window(hwnd: HWND) {
         // 2. Manipulate pixels directly for particles (Fast)
         // 3. BitBlt the text on top
         // 4. AlphaBlend the broom
-        let mem_dc = CreateCompatibleDC(hdc);
-        
         let bmi_scratch = BITMAPINFO {
             bmiHeader: BITMAPINFOHEADER {
                 biSize: size_of::<BITMAPINFOHEADER>() as u32,
@@ -186,38 +509,49 @@ pub fn paint_window(hwnd: HWND) {
             }, ..Default::default()
         };
         let mut scratch_bits: *mut core::ffi::c_void = std::ptr::null_mut();
-        let scratch_bitmap = CreateDIBSection(hdc, &bmi_scratch, DIB_RGB_COLORS, &mut scratch_bits, None, 0).unwrap();
-        let old_scratch = SelectObject(mem_dc, scratch_bitmap);
+        let scratch_bitmap = OwnedBitmap::new(CreateDIBSection(hdc, &bmi_scratch, DIB_RGB_COLORS, &mut scratch_bits, None, 0).unwrap_or(HBITMAP(0)));
+        if scratch_bitmap.is_none() {
+            // Out of GDI resources: skip this frame rather than draw into nothing
+            EndPaint(hwnd, &ps);
+            return;
+        }
+        // Declared after the bitmap so it's dropped (deselecting it) first
+        let scratch = MemDc::select(hdc, scratch_bitmap.handle());
+        let mem_dc = scratch.dc;
 
         // 2.1 Copy Background from Cache -> Scratch
         if cached_bg_bm.0 != 0 {
-            let cache_dc = CreateCompatibleDC(hdc);
-            let old_cbm = SelectObject(cache_dc, cached_bg_bm);
-            let _ = BitBlt(mem_dc, 0, 0, width, height, cache_dc, 0, 0, SRCCOPY).ok();
-            SelectObject(cache_dc, old_cbm);
-            DeleteDC(cache_dc);
+            let cache = MemDc::select(hdc, cached_bg_bm);
+            let _ = BitBlt(mem_dc, 0, 0, width, height, cache.dc, 0, 0, SRCCOPY).ok();
         }
 
         // --- PHASE 3: TEXT CACHE UPDATE (If needed) ---
         if cache_dirty || cached_text_bm.0 == 0 {
-            if cached_text_bm.0 != 0 { DeleteObject(cached_text_bm); }
-
-            cached_text_bm = CreateCompatibleBitmap(hdc, width, height);
-            let cache_dc = CreateCompatibleDC(hdc);
-            let old_cache_bm = SelectObject(cache_dc, cached_text_bm);
+            // The state takes ownership below, deleting the previous text bitmap
+            let text_bitmap = OwnedBitmap::new(CreateCompatibleBitmap(hdc, width, height));
+            cached_text_bm = text_bitmap.handle();
+            let cache = MemDc::select(hdc, cached_text_bm);
+            let cache_dc = cache.dc;
 
             // Clear with background color
-            let dark_brush = CreateSolidBrush(COLORREF(bg_color_u32));
+            let dark_brush = GdiObj::from_hbrush(CreateSolidBrush(COLORREF(bg_color_u32)));
             let fill_rect = RECT { left: 0, top: 0, right: width, bottom: height };
-            FillRect(cache_dc, &fill_rect, dark_brush);
-            DeleteObject(dark_brush);
+            FillRect(cache_dc, &fill_rect, HBRUSH(dark_brush.0.0));
 
             SetBkMode(cache_dc, TRANSPARENT);
             SetTextColor(cache_dc, COLORREF(0x00FFFFFF));
 
-            let text_len = GetWindowTextLengthW(hwnd) + 1;
-            let mut buf = vec![0u16; text_len as usize];
-            GetWindowTextW(hwnd, &mut buf);
+            let mut buf = super::window_text_units(hwnd);
+
+            let text = String::from_utf16_lossy(&buf);
+            rtl = super::bidi::is_rtl(&text);
+            let prewrap = !preserve_layout && super::line_break::has_cjk(&buf);
+            let family = font_family.filter(|f| !f.trim().is_empty()).map(|f| f.trim().to_string());
+            let face = match &family {
+                Some(family) => family.clone(),
+                None => super::fonts::face_for(&text, preserve_layout).to_string(),
+            };
+            let face = crate::overlay::utils::to_wstring(&face);
 
             // Font sizing logic
             // FIX: Reduced padding to 6 to accommodate smaller windows
@@ -236,7 +570,10 @@ pub fn paint_window(hwnd: HWND) {
             } else {
                 while low <= high {
                     let mid = (low + high) / 2;
-                    let (h, w) = measure_text_bounds(cache_dc, &mut buf, mid, available_w);
+                    let (h, w) = match &study {
+                        Some(card) => layout_study_card(cache_dc, card, mid, available_w, family.as_deref(), None),
+                        None => measure_text_bounds(cache_dc, &mut buf, mid, available_w, preserve_layout, prewrap, rtl, &face),
+                    };
                     
                     if h <= available_h && w <= available_w {
                         best_fit = mid;
@@ -246,48 +583,74 @@ pub fn paint_window(hwnd: HWND) {
                     }
                 }
             }
-            let font_size_val = best_fit;
+            let line_layout = search.is_some() || edit.is_some();
+            let font_size_val = if line_layout { best_fit.max(SEARCH_MIN_FONT) } else { best_fit };
 
-            let hfont = CreateFontW(font_size_val, 0, 0, 0, FW_MEDIUM.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+            let hfont = create_text_font(font_size_val, preserve_layout, &face);
             let old_font = SelectObject(cache_dc, hfont);
 
-            // Re-measure with selected font for vertical alignment
-            let mut measure_rect = RECT { left: 0, top: 0, right: available_w, bottom: 0 };
-            DrawTextW(cache_dc, &mut buf, &mut measure_rect, DT_CALCRECT | DT_WORDBREAK | DT_EDITCONTROL);
-            let text_h = measure_rect.bottom;
-            
-            let offset_y = ((height - text_h) / 2).max(0);
-            let mut draw_rect = RECT {
-                left: h_padding,
-                top: offset_y,
-                right: width - h_padding,
-                bottom: height
-            };
-            
-            // Draw actual text
-            DrawTextW(cache_dc, &mut buf, &mut draw_rect as *mut _, DT_LEFT | DT_WORDBREAK | DT_EDITCONTROL);
+            let mut search_layout = None;
+            if line_layout {
+                let text_end = buf.iter().position(|&c| c == 0).unwrap_or(buf.len());
+                search_layout = Some(draw_line_layout(cache_dc, &buf[..text_end], search.as_ref(), edit.as_ref(), scroll_y, h_padding, width, height, preserve_layout));
+            } else if let Some(card) = &study {
+                let (text_h, _) = layout_study_card(cache_dc, card, font_size_val, available_w, family.as_deref(), None);
+                let offset_y = ((height - text_h) / 2).max(0);
+                layout_study_card(cache_dc, card, font_size_val, available_w, family.as_deref(), Some((h_padding, offset_y)));
+            } else {
+                let mut wrapped = if prewrap { prewrap_lines(cache_dc, &buf, available_w) } else { Vec::new() };
+                let shown = if prewrap { &mut wrapped[..] } else { &mut buf[..] };
+                let flags = text_format_flags(preserve_layout || prewrap, rtl);
+
+                // Re-measure with selected font for vertical alignment
+                let mut measure_rect = RECT { left: 0, top: 0, right: available_w, bottom: 0 };
+                DrawTextW(cache_dc, shown, &mut measure_rect, DT_CALCRECT | flags);
+                let text_h = measure_rect.bottom;
+
+                let offset_y = ((height - text_h) / 2).max(0);
+                let mut draw_rect = RECT {
+                    left: h_padding,
+                    top: offset_y,
+                    right: width - h_padding,
+                    bottom: height
+                };
+
+                // Draw actual text (right-aligned when it reads right-to-left)
+                let align = if rtl { DT_RIGHT } else { DT_LEFT };
+                DrawTextW(cache_dc, shown, &mut draw_rect as *mut _, align | flags);
+            }
 
             SelectObject(cache_dc, old_font);
             DeleteObject(hfont);
-            SelectObject(cache_dc, old_cache_bm);
-            DeleteDC(cache_dc);
+            drop(cache);
 
             // Update State with new text cache
              let mut states = WINDOW_STATES.lock().unwrap();
              if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
-                 state.content_bitmap = cached_text_bm;
+                 state.content_bitmap = text_bitmap;
                  state.cached_font_size = font_size_val;
                  state.font_cache_dirty = false;
+                 state.rtl = rtl;
+                 if let Some((scroll, match_count, current)) = search_layout {
+                     state.scroll_y = scroll;
+                     if let Some(live) = state.search.as_mut() {
+                         live.match_count = match_count;
+                         live.current = current;
+                         live.reveal_current = false;
+                     }
+                     if let Some(live) = state.edit.as_mut() {
+                         live.reveal_caret = false;
+                     }
+                 }
+             } else {
+                 cached_text_bm = HBITMAP(0); // Window already gone: text_bitmap is deleted here
              }
         }
 
         // 3.1 Blit Text Cache -> Scratch
         if cached_text_bm.0 != 0 {
-            let cache_dc = CreateCompatibleDC(hdc);
-            let old_cbm = SelectObject(cache_dc, cached_text_bm);
-            let _ = BitBlt(mem_dc, 0, 0, width, height, cache_dc, 0, 0, SRCCOPY).ok();
-            SelectObject(cache_dc, old_cbm);
-            DeleteDC(cache_dc);
+            let cache = MemDc::select(hdc, cached_text_bm);
+            let _ = BitBlt(mem_dc, 0, 0, width, height, cache.dc, 0, 0, SRCCOPY).ok();
         }
 
         // --- PHASE 4: PIXEL MANIPULATION (Particles & Button) ---
@@ -355,9 +718,10 @@ pub fn paint_window(hwnd: HWND) {
                     (height - margin - btn_size / 2) as f32
                 };
                 let cx = (width - margin - btn_size / 2) as f32;
-                let radius = 13.0;
+                // RTL windows mirror the row: copy button bottom-left, the others to its right
+                let (cx, step) = if rtl { (width as f32 - cx, -(btn_size + 8) as f32) } else { (cx, (btn_size + 8) as f32) };
 
-                let (tr, tg, tb) = if copy_success {
+                let copy_color = if copy_success {
                     (30.0, 180.0, 30.0) // Success Green
                 } else if on_copy_btn {
                     (128.0, 128.0, 128.0) // Hover Bright
@@ -365,139 +729,228 @@ pub fn paint_window(hwnd: HWND) {
                     (80.0, 80.0, 80.0)    // Standard Visible Grey
                 };
 
-                let b_start_x = (cx - radius - 4.0) as i32;
-                let b_end_x = (cx + radius + 4.0) as i32;
-                let b_start_y = (cy - radius - 4.0) as i32;
-                let b_end_y = (cy + radius + 4.0) as i32;
-
-                for y in b_start_y.max(0)..b_end_y.min(height) {
-                    for x in b_start_x.max(0)..b_end_x.min(width) {
-                        let fx = x as f32;
-                        let fy = y as f32;
-                        let dx = (fx - cx).abs();
-                        let dy = (fy - cy).abs();
-                        let dist = (dx*dx + dy*dy).sqrt();
+                blend_round_button(raw_pixels, width, height, cx, cy, copy_color, |fx, fy| {
+                    if copy_success {
+                        // Checkmark (Tick) - THICKER
+                        // Points: Left(-4,0) -> Mid(-1,3) -> Right(4,-4)
+                        let d1 = dist_segment(fx, fy, cx - 4.0, cy, cx - 1.0, cy + 3.0);
+                        let d2 = dist_segment(fx, fy, cx - 1.0, cy + 3.0, cx + 4.0, cy - 4.0);
+                        let d = d1.min(d2);
+                        // Increased thickness threshold from 1.2 to 1.8
+                        (1.8 - d).clamp(0.0, 1.0)
+                    } else {
+                        // Copy Icon (Two rounded rects) - THICKER
                         
-                        // 1. AA for Button Body
-                        let aa_body = (radius + 0.5 - dist).clamp(0.0, 1.0);
-
-                        // 2. Smooth Ring Border (Thickness 1.5px)
-                        let border_inner_radius = radius - 1.5;
-                        let border_outer = (radius + 0.5 - dist).clamp(0.0, 1.0);
-                        let border_inner = (dist - (border_inner_radius - 0.5)).clamp(0.0, 1.0);
-                        let border_alpha = border_outer * border_inner * 0.6; // 60% opacity white border
-
-                        // 3. Icon Anti-Aliasing (SDF) with Increased Thickness
-                        // FIX: Initialize with expression to avoid warning
-                        let icon_alpha = if copy_success {
-                            // Checkmark (Tick) - THICKER
-                            // Points: Left(-4,0) -> Mid(-1,3) -> Right(4,-4)
-                            let d1 = dist_segment(fx, fy, cx - 4.0, cy, cx - 1.0, cy + 3.0);
-                            let d2 = dist_segment(fx, fy, cx - 1.0, cy + 3.0, cx + 4.0, cy - 4.0);
-                            let d = d1.min(d2);
-                            // Increased thickness threshold from 1.2 to 1.8
-                            (1.8 - d).clamp(0.0, 1.0)
-                        } else {
-                            // Copy Icon (Two rounded rects) - THICKER
-                            
-                            // Back Rect: Centered at (-2, -2) relatively, size 6x8 outline
-                            let back_d = sd_box(fx, fy, cx - 2.0, cy - 2.0, 3.0, 4.0);
-                            // Increased stroke width from 0.75 to 1.25
-                            let back_outline = (1.25 - back_d.abs()).clamp(0.0, 1.0);
-                            
-                            // Front Rect: Centered at (+2, +2) relatively, size 6x8 filled
-                            let front_d = sd_box(fx, fy, cx + 2.0, cy + 2.0, 3.0, 4.0);
-                            // Standard AA edge (0.8 allows a slight softness)
-                            let front_fill = (0.8 - front_d).clamp(0.0, 1.0);
-                            
-                            // Masking: Don't draw back rect where front rect (plus margin) is
-                            let mask_d = sd_box(fx, fy, cx + 2.0, cy + 2.0, 4.5, 5.5);
-                            let mask = (mask_d).clamp(0.0, 1.0); 
-                            
-                            // Combine
-                            (front_fill + back_outline * mask).clamp(0.0, 1.0)
-                        };
+                        // Back Rect: Centered at (-2, -2) relatively, size 6x8 outline
+                        let back_d = sd_box(fx, fy, cx - 2.0, cy - 2.0, 3.0, 4.0);
+                        // Increased stroke width from 0.75 to 1.25
+                        let back_outline = (1.25 - back_d.abs()).clamp(0.0, 1.0);
+                        
+                        // Front Rect: Centered at (+2, +2) relatively, size 6x8 filled
+                        let front_d = sd_box(fx, fy, cx + 2.0, cy + 2.0, 3.0, 4.0);
+                        // Standard AA edge (0.8 allows a slight softness)
+                        let front_fill = (0.8 - front_d).clamp(0.0, 1.0);
+                        
+                        // Masking: Don't draw back rect where front rect (plus margin) is
+                        let mask_d = sd_box(fx, fy, cx + 2.0, cy + 2.0, 4.5, 5.5);
+                        let mask = (mask_d).clamp(0.0, 1.0); 
+                        
+                        // Combine
+                        (front_fill + back_outline * mask).clamp(0.0, 1.0)
+                    }
+                });
+
+                // Edit Button (pencil) in the export button's slot; blue while editing
+                if !has_export {
+                    let ex = cx - step;
+                    let edit_color = if edit.is_some() { (48.0, 160.0, 224.0) } else if on_edit_btn { (128.0, 128.0, 128.0) } else { (80.0, 80.0, 80.0) };
+                    blend_round_button(raw_pixels, width, height, ex, cy, edit_color, |fx, fy| {
+                        // Pencil: body along the diagonal, tip at the bottom-left
+                        let body = dist_segment(fx, fy, ex - 2.0, cy + 2.0, ex + 4.0, cy - 4.0);
+                        let tip = dist_segment(fx, fy, ex - 5.0, cy + 5.0, ex - 2.5, cy + 2.5);
+                        (2.2 - body).clamp(0.0, 1.0).max((1.2 - tip).clamp(0.0, 1.0))
+                    });
+                }
 
-                        if aa_body > 0.0 || border_alpha > 0.0 || icon_alpha > 0.0 {
-                            let idx = (y * width + x) as usize;
-                            let bg = raw_pixels[idx];
-                            let bg_b = (bg & 0xFF) as f32;
-                            let bg_g = ((bg >> 8) & 0xFF) as f32;
-                            let bg_r = ((bg >> 16) & 0xFF) as f32;
-                            
-                            let mut final_r = bg_r;
-                            let mut final_g = bg_g;
-                            let mut final_b = bg_b;
+                // Quick translate (globe) left of the pencil; amber while translating, blue while shown
+                if !has_export && edit.is_none() {
+                    let gx = cx - 2.0 * step;
+                    let globe_color = match translation_done {
+                        Some(false) => (200.0, 150.0, 40.0),
+                        Some(true) => (48.0, 160.0, 224.0),
+                        None if on_translate_btn => (128.0, 128.0, 128.0),
+                        None => (80.0, 80.0, 80.0),
+                    };
+                    blend_round_button(raw_pixels, width, height, gx, cy, globe_color, |fx, fy| {
+                        // Outline, meridian and equator
+                        let (dx, dy) = (fx - gx, fy - cy);
+                        let outline = ((dx * dx + dy * dy).sqrt() - 6.0).abs();
+                        let meridian = (((dx / 2.5).powi(2) + (dy / 6.0).powi(2)).sqrt() - 1.0).abs() * 2.5;
+                        let equator = dist_segment(fx, fy, gx - 6.0, cy, gx + 6.0, cy);
+                        let d = outline.min(meridian).min(equator);
+                        (1.3 - d).clamp(0.0, 1.0)
+                    });
+                }
 
-                            // Composite: Body -> Border -> Icon
-                            
-                            // A. Body
-                            if aa_body > 0.0 {
-                                let alpha = 0.9 * aa_body;
-                                let inv = 1.0 - alpha;
-                                final_r = tr * alpha + final_r * inv;
-                                final_g = tg * alpha + final_g * inv;
-                                final_b = tb * alpha + final_b * inv;
-                            }
-
-                            // B. Border (Additive)
-                            if border_alpha > 0.0 {
-                                final_r += 255.0 * border_alpha;
-                                final_g += 255.0 * border_alpha;
-                                final_b += 255.0 * border_alpha;
-                            }
-
-                            // C. Icon (White, Normal Blend on top of result)
-                            if icon_alpha > 0.0 {
-                                let inv_icon = 1.0 - icon_alpha;
-                                final_r = 255.0 * icon_alpha + final_r * inv_icon;
-                                final_g = 255.0 * icon_alpha + final_g * inv_icon;
-                                final_b = 255.0 * icon_alpha + final_b * inv_icon;
-                            }
-
-                            final_r = final_r.min(255.0);
-                            final_g = final_g.min(255.0);
-                            final_b = final_b.min(255.0);
-                            
-                            raw_pixels[idx] = (255 << 24) | ((final_r as u32) << 16) | ((final_g as u32) << 8) | (final_b as u32);
-                        }
-                    }
+                // 4.3 Export Button (live sessions), left of the copy button
+                if has_export {
+                    let ex = cx - step;
+                    let export_color = if on_export_btn { (128.0, 128.0, 128.0) } else { (80.0, 80.0, 80.0) };
+                    blend_round_button(raw_pixels, width, height, ex, cy, export_color, |fx, fy| {
+                        // Download arrow over a tray line
+                        let shaft = dist_segment(fx, fy, ex, cy - 6.0, ex, cy + 2.0);
+                        let left = dist_segment(fx, fy, ex - 3.5, cy - 1.5, ex, cy + 2.0);
+                        let right = dist_segment(fx, fy, ex + 3.5, cy - 1.5, ex, cy + 2.0);
+                        let tray = dist_segment(fx, fy, ex - 5.0, cy + 5.5, ex + 5.0, cy + 5.5);
+                        let d = shaft.min(left).min(right).min(tray);
+                        (1.6 - d).clamp(0.0, 1.0)
+                    });
                 }
             }
         }
 
+        // 4.4 Warning Badge (e.g. low OCR confidence), top-left
+        if let Some(badge) = warning_badge {
+            let mut badge_w: Vec<u16> = badge.encode_utf16().collect();
+            let hfont = CreateFontW(13, 0, 0, 0, FW_SEMIBOLD.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+            let old_font = SelectObject(mem_dc, hfont);
+
+            let mut text_rect = RECT { left: 0, top: 0, right: (width - 16).max(1), bottom: 0 };
+            DrawTextW(mem_dc, &mut badge_w, &mut text_rect, DT_CALCRECT | DT_SINGLELINE | DT_NOPREFIX);
+            let pad = 4;
+            let badge_rect = RECT { left: 4, top: 4, right: (4 + text_rect.right + pad * 2).min(width - 4), bottom: 4 + text_rect.bottom + pad };
+
+            let brush = GdiObj::from_hbrush(CreateSolidBrush(COLORREF(0x00204060))); // Dark amber (BGR)
+            FillRect(mem_dc, &badge_rect, HBRUSH(brush.0.0));
+            SetBkMode(mem_dc, TRANSPARENT);
+            SetTextColor(mem_dc, COLORREF(0x0040C8FF)); // Amber (BGR)
+            let mut draw_rect = RECT { left: badge_rect.left + pad, top: badge_rect.top + pad / 2, right: badge_rect.right - pad, bottom: badge_rect.bottom };
+            DrawTextW(mem_dc, &mut badge_w, &mut draw_rect, DT_LEFT | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS);
+
+            SelectObject(mem_dc, old_font);
+            DeleteObject(hfont);
+        }
+
+        if let Some(line) = stats_line {
+            let mut line_w: Vec<u16> = line.encode_utf16().collect();
+            let hfont = CreateFontW(12, 0, 0, 0, FW_NORMAL.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (FIXED_PITCH.0 | FF_MODERN.0) as u32, w!("Consolas"));
+            let old_font = SelectObject(mem_dc, hfont);
+
+            SetBkMode(mem_dc, TRANSPARENT);
+            SetTextColor(mem_dc, COLORREF(0x00B0B0B0)); // Muted grey
+            let mut draw_rect = RECT { left: 6, top: height - 18, right: width - 6, bottom: height - 3 };
+            DrawTextW(mem_dc, &mut line_w, &mut draw_rect, DT_LEFT | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS | DT_BOTTOM);
+
+            SelectObject(mem_dc, old_font);
+            DeleteObject(hfont);
+        }
+
+        // Key error: warning icon and where to fix it, top-left
+        if key_error && search.is_none() {
+            let lang = crate::APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+            let mut hint_w: Vec<u16> = format!("⚠  {}", crate::overlay::utils::get_error_hint(&lang)).encode_utf16().collect();
+            let hfont = CreateFontW(13, 0, 0, 0, FW_SEMIBOLD.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+            let old_font = SelectObject(mem_dc, hfont);
+
+            SetBkMode(mem_dc, TRANSPARENT);
+            SetTextColor(mem_dc, COLORREF(0x008080FF)); // Light red in BGR
+            let mut draw_rect = RECT { left: 6, top: 3, right: width - 6, bottom: 19 };
+            DrawTextW(mem_dc, &mut hint_w, &mut draw_rect, DT_LEFT | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS);
+
+            SelectObject(mem_dc, old_font);
+            DeleteObject(hfont);
+        }
+
+        // Model and elapsed time, top-right (where the search box goes while it is open)
+        if let Some(badge) = model_badge.filter(|_| search.is_none()) {
+            let mut badge_w: Vec<u16> = badge.encode_utf16().collect();
+            let hfont = CreateFontW(12, 0, 0, 0, FW_NORMAL.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+            let old_font = SelectObject(mem_dc, hfont);
+
+            SetBkMode(mem_dc, TRANSPARENT);
+            SetTextColor(mem_dc, COLORREF(0x00909090)); // Muted grey
+            let mut draw_rect = RECT { left: width / 2, top: 3, right: width - 6, bottom: 18 };
+            DrawTextW(mem_dc, &mut badge_w, &mut draw_rect, DT_RIGHT | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS);
+
+            SelectObject(mem_dc, old_font);
+            DeleteObject(hfont);
+        }
+
+        // Hovering the copy button shows the copy-and-close shortcut just above it
+        if is_hovered && on_copy_btn && !copy_success {
+            let mut hint_w: Vec<u16> = ui_text().result_copy_hint.encode_utf16().collect();
+            let hfont = CreateFontW(12, 0, 0, 0, FW_NORMAL.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+            let old_font = SelectObject(mem_dc, hfont);
+
+            SetBkMode(mem_dc, TRANSPARENT);
+            SetTextColor(mem_dc, COLORREF(0x00C0C0C0));
+            let bottom = (height - 12 - 28 - 2).max(15); // Above the copy button (margin 12, size 28)
+            let mut draw_rect = if rtl { RECT { left: 10, top: bottom - 15, right: width - 6, bottom } } else { RECT { left: 6, top: bottom - 15, right: width - 10, bottom } };
+            let align = if rtl { DT_LEFT } else { DT_RIGHT };
+            DrawTextW(mem_dc, &mut hint_w, &mut draw_rect, align | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS);
+
+            SelectObject(mem_dc, old_font);
+            DeleteObject(hfont);
+        }
+
+        // Retry button of a failed request, bottom-left
+        if let Some(on_retry_btn) = retry {
+            let btn = super::mirror_btn_rect(super::get_retry_btn_rect(width, height), width, rtl);
+            let brush = CreateSolidBrush(COLORREF(if on_retry_btn { 0x00B07830 } else { 0x00805828 })); // Blue in BGR
+            let old_brush = SelectObject(mem_dc, brush);
+            let old_pen = SelectObject(mem_dc, GetStockObject(NULL_PEN));
+            RoundRect(mem_dc, btn.left, btn.top, btn.right, btn.bottom, 14, 14);
+            SelectObject(mem_dc, old_pen);
+            SelectObject(mem_dc, old_brush);
+            DeleteObject(brush);
+
+            let mut label_w: Vec<u16> = format!("↻ {}", ui_text().result_retry_label).encode_utf16().collect();
+            let hfont = CreateFontW(14, 0, 0, 0, FW_SEMIBOLD.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+            let old_font = SelectObject(mem_dc, hfont);
+            SetBkMode(mem_dc, TRANSPARENT);
+            SetTextColor(mem_dc, COLORREF(0x00FFFFFF));
+            let mut draw_rect = btn;
+            DrawTextW(mem_dc, &mut label_w, &mut draw_rect, DT_CENTER | DT_VCENTER | DT_SINGLELINE | DT_NOPREFIX | DT_END_ELLIPSIS);
+            SelectObject(mem_dc, old_font);
+            DeleteObject(hfont);
+        }
+
+        if edit.is_some() {
+            draw_edit_frame(mem_dc, width, height);
+        }
+
+        // 4.5 Search Box (Ctrl+F), top-right; reads the counts stored by the text pass above
+        if search.is_some() {
+            let live = WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).and_then(|s| s.search.clone());
+            if let Some(live) = live {
+                draw_search_box(mem_dc, &live, width);
+            }
+        }
+
         // --- PHASE 5: DYNAMIC BROOM ---
         let broom_bitmap_data = if let Some((bx, by, params)) = broom_data {
             let pixels = render_procedural_broom(params);
-            let hbm = create_bitmap_from_pixels(&pixels, BROOM_W, BROOM_H);
+            let hbm = OwnedBitmap::new(create_bitmap_from_pixels(&pixels, BROOM_W, BROOM_H));
             Some((bx, by, hbm))
         } else { None };
 
         if let Some((px, py, hbm)) = broom_bitmap_data {
-             if hbm.0 != 0 {
-                let broom_dc = CreateCompatibleDC(hdc);
-                let old_hbm_broom = SelectObject(broom_dc, hbm);
+             if !hbm.is_none() {
+                let broom = MemDc::select(hdc, hbm.handle());
                 let mut bf = BLENDFUNCTION::default();
                 bf.BlendOp = AC_SRC_OVER as u8;
                 bf.SourceConstantAlpha = 255;
                 bf.AlphaFormat = AC_SRC_ALPHA as u8;
                 let draw_x = px as i32 - (BROOM_W / 2); 
                 let draw_y = py as i32 - (BROOM_H as f32 * 0.65) as i32; 
-                GdiAlphaBlend(mem_dc, draw_x, draw_y, BROOM_W, BROOM_H, broom_dc, 0, 0, BROOM_W, BROOM_H, bf);
-                SelectObject(broom_dc, old_hbm_broom);
-                DeleteDC(broom_dc);
-                DeleteObject(hbm);
+                GdiAlphaBlend(mem_dc, draw_x, draw_y, BROOM_W, BROOM_H, broom.dc, 0, 0, BROOM_W, BROOM_H, bf);
             }
         }
 
         // --- PHASE 6: FINAL BLIT TO SCREEN ---
         let _ = BitBlt(hdc, 0, 0, width, height, mem_dc, 0, 0, SRCCOPY).ok();
 
-        // Cleanup Scratch Resources
-        SelectObject(mem_dc, old_scratch);
-        DeleteObject(scratch_bitmap);
-        DeleteDC(mem_dc);
-        
-        EndPaint(hwnd, &mut ps);
+        // Scratch DC and bitmap are freed as they go out of scope
+        EndPaint(hwnd, &ps);
     }
 }
@@ -0,0 +1,185 @@
+//! Quick Translate
+//!
+//! The globe button next to the pencil translates whatever a result window shows into
+//! `Config.quick_translate_to`, in place; clicking it again brings the original back.
+//! Unlike a preset's retranslation this is on demand, for any result window, but it uses
+//! the window's preset's retranslation model and streaming setting (`set_text_model`).
+//! Text still streaming into the window meanwhile updates the kept original, not the translation.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::Graphics::Gdi::InvalidateRect;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use super::state::WINDOW_STATES;
+
+/// Same text model as a new preset's retranslation
+const QUICK_TRANSLATE_MODEL: &str = "fast_text";
+
+/// Text model the globe button translates with
+#[derive(Clone)]
+pub struct TextModel {
+    pub model_id: String,
+    pub streaming: bool,
+}
+
+impl Default for TextModel {
+    /// Windows not opened for a preset's capture (live sessions): a new preset's retranslation settings
+    fn default() -> Self {
+        TextModel { model_id: QUICK_TRANSLATE_MODEL.to_string(), streaming: true }
+    }
+}
+
+/// Window text replaced by a quick translation
+pub struct QuickTranslation {
+    pub original: String,
+    pub original_raw: Option<String>, // Markdown source of a chat answer
+    pub done: bool, // False while the translation streams in (the button is ignored)
+}
+
+/// What `toggle` does for a window's current quick translation
+#[derive(Debug, PartialEq)]
+enum Toggle {
+    Start,
+    Undo,
+    Busy,
+}
+
+fn next_toggle(current: Option<&QuickTranslation>) -> Toggle {
+    match current {
+        None => Toggle::Start,
+        Some(qt) if qt.done => Toggle::Undo,
+        Some(_) => Toggle::Busy,
+    }
+}
+
+/// Button click: translate the shown text, or restore the original
+pub unsafe fn toggle(hwnd: HWND) {
+    let shown = String::from_utf16_lossy(&super::window_text_units(hwnd));
+
+    let original = {
+        let mut states = WINDOW_STATES.lock().unwrap();
+        let Some(state) = states.get_mut(&(hwnd.0 as isize)) else { return };
+        match next_toggle(state.quick_translation.as_ref()) {
+            Toggle::Busy => return,
+            Toggle::Undo => {
+                let qt = state.quick_translation.take().unwrap();
+                state.pending_text = Some(qt.original);
+                state.raw_text = qt.original_raw;
+                None
+            }
+            Toggle::Start => {
+                // Text not applied yet is newer than what's on screen
+                let original = state.pending_text.clone().unwrap_or(shown);
+                if original.trim().is_empty() { return; }
+                state.quick_translation = Some(QuickTranslation {
+                    original: original.clone(),
+                    original_raw: state.raw_text.clone(),
+                    done: false,
+                });
+                Some(original)
+            }
+        }
+    };
+
+    match original {
+        Some(text) => { std::thread::spawn(move || translate(hwnd, text)); }
+        None => { PostMessageW(hwnd, super::logic::WM_WAKE_TIMER, WPARAM(0), LPARAM(0)); }
+    }
+    InvalidateRect(hwnd, None, false);
+}
+
+/// Where a result update goes while a quick translation is shown: into the kept
+/// original. False if there is none (the update is shown as usual).
+pub fn redirect_update(state: &mut super::state::WindowState, text: &str, raw: Option<&str>) -> bool {
+    match state.quick_translation.as_mut() {
+        Some(qt) => {
+            qt.original = text.to_string();
+            qt.original_raw = raw.map(str::to_string);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Put translated text on screen (bypasses `redirect_update`). Text corrected by hand
+/// meanwhile is kept, as with result updates.
+fn show(hwnd: HWND, text: &str, done: bool) {
+    {
+        let mut states = WINDOW_STATES.lock().unwrap();
+        let Some(state) = states.get_mut(&(hwnd.0 as isize)) else { return };
+        let Some(qt) = state.quick_translation.as_mut() else { return };
+        qt.done = done;
+        if state.edited { return; }
+        state.pending_text = Some(text.to_string());
+        state.raw_text = None;
+    }
+    unsafe { PostMessageW(hwnd, super::logic::WM_WAKE_TIMER, WPARAM(0), LPARAM(0)); }
+}
+
+/// Worker: stream the translation into the window, or put the original back on failure
+fn translate(hwnd: HWND, text: String) {
+    let text_model = WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize)).map(|s| s.text_model.clone()).unwrap_or_default();
+    let (groq_key, gemini_key, openrouter_key, target_lang, ui_language) = match crate::APP.lock() {
+        Ok(app) => (
+            app.config.api_key.clone(),
+            app.config.gemini_api_key.clone(),
+            app.config.openrouter_api_key.clone(),
+            app.config.quick_translate_to.clone(),
+            app.config.ui_language.clone(),
+        ),
+        Err(_) => return,
+    };
+    // A model removed since the preset was saved: the default text model
+    let result = match crate::model_config::get_model_by_id(&text_model.model_id)
+        .or_else(|| crate::model_config::get_model_by_id(QUICK_TRANSLATE_MODEL)) {
+        Some(model) => {
+            log::info!("Quick translate to {} with {} ({} chars)", target_lang, model.id, text.chars().count());
+            let mut acc = String::new();
+            crate::api::translate_text_streaming(
+                &groq_key, &gemini_key, &openrouter_key,
+                text, target_lang, model.full_name, model.provider,
+                text_model.streaming, false,
+                |chunk| {
+                    acc.push_str(chunk);
+                    show(hwnd, &acc, false);
+                },
+            )
+        }
+        None => Err(anyhow::anyhow!("Text model {} not found", text_model.model_id)),
+    };
+
+    match result {
+        Ok(translated) => show(hwnd, &translated, true),
+        Err(e) => {
+            log::warn!("Quick translate failed: {}", e);
+            {
+                let mut states = WINDOW_STATES.lock().unwrap();
+                if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+                    if let Some(qt) = state.quick_translation.take().filter(|_| !state.edited) {
+                        state.pending_text = Some(qt.original);
+                        state.raw_text = qt.original_raw;
+                    }
+                    let message = crate::overlay::utils::get_error_message(&e, &ui_language);
+                    state.warning_badge = Some(format!("⚠ {}", message.lines().next().unwrap_or_default()));
+                }
+            }
+            unsafe {
+                PostMessageW(hwnd, super::logic::WM_WAKE_TIMER, WPARAM(0), LPARAM(0));
+                InvalidateRect(hwnd, None, false);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_toggle() {
+        let translation = |done| QuickTranslation { original: "Xin chào".to_string(), original_raw: None, done };
+        assert_eq!(next_toggle(None), Toggle::Start);
+        assert_eq!(next_toggle(Some(&translation(true))), Toggle::Undo);
+        // A second click while it streams doesn't start another request
+        assert_eq!(next_toggle(Some(&translation(false))), Toggle::Busy);
+    }
+}
@@ -0,0 +1,186 @@
+//! Result Window Search
+//!
+//! Ctrl+F find-in-text for result windows. While a search is open `paint.rs` lays the
+//! text out line by line with `wrap_lines` (instead of a single DrawTextW call), so
+//! matches can be highlighted at exact positions and scrolled into view.
+
+use std::ops::Range;
+
+#[derive(Default, Clone)]
+pub struct SearchState {
+    pub query: String,
+    pub current: usize,       // Match Enter/Shift+Enter moved to
+    pub match_count: usize,   // From the last paint
+    pub reveal_current: bool, // Scroll the current match into view on the next paint
+}
+
+impl SearchState {
+    pub fn set_query(&mut self, query: String) {
+        self.query = query;
+        self.current = 0;
+        self.reveal_current = true;
+    }
+
+    /// Next (or previous) match, wrapping around
+    pub fn step(&mut self, forward: bool) {
+        if self.match_count == 0 {
+            return;
+        }
+        self.current = if forward {
+            (self.current + 1) % self.match_count
+        } else {
+            (self.current + self.match_count - 1) % self.match_count
+        };
+        self.reveal_current = true;
+    }
+
+    /// "2/5", or "0/0" when nothing matches
+    pub fn count_label(&self) -> String {
+        if self.match_count == 0 {
+            "0/0".to_string()
+        } else {
+            format!("{}/{}", self.current + 1, self.match_count)
+        }
+    }
+}
+
+/// Lowercase a UTF-16 unit when that keeps it a single unit (surrogates stay as-is)
+fn fold(unit: u16) -> u16 {
+    match char::from_u32(unit as u32) {
+        Some(c) => {
+            let mut lower = c.to_lowercase();
+            match (lower.next(), lower.next()) {
+                (Some(l), None) if (l as u32) <= 0xFFFF => l as u32 as u16,
+                _ => unit,
+            }
+        }
+        None => unit,
+    }
+}
+
+/// Non-overlapping, case-insensitive matches of `query` in `text` (UTF-16 offsets)
+pub fn find_matches(text: &[u16], query: &str) -> Vec<Range<usize>> {
+    let needle: Vec<u16> = query.encode_utf16().map(fold).collect();
+    let mut matches = Vec::new();
+    if needle.is_empty() || needle.len() > text.len() {
+        return matches;
+    }
+    let mut i = 0;
+    while i + needle.len() <= text.len() {
+        if text[i..i + needle.len()].iter().zip(&needle).all(|(&t, &n)| fold(t) == n) {
+            matches.push(i..i + needle.len());
+            i += needle.len();
+        } else {
+            i += 1;
+        }
+    }
+    matches
+}
+
+/// Split `text` into display lines: at newlines, and (with `wrap`) at the last space
+/// that keeps a line within `max_width` as measured by `measure`. A word wider than
+/// the line is broken between characters. Ranges exclude the newline and the space
+/// a line was wrapped at.
+pub fn wrap_lines(text: &[u16], max_width: i32, wrap: bool, measure: impl Fn(&[u16]) -> i32) -> Vec<Range<usize>> {
+    const SPACE: u16 = b' ' as u16;
+    let mut lines = Vec::new();
+    let mut para_start = 0;
+    while para_start <= text.len() {
+        let para_end = text[para_start..].iter().position(|&c| c == b'\n' as u16).map_or(text.len(), |p| para_start + p);
+        let content_end = if para_end > para_start && text[para_end - 1] == b'\r' as u16 { para_end - 1 } else { para_end };
+
+        let mut start = para_start;
+        loop {
+            if !wrap || measure(&text[start..content_end]) <= max_width {
+                lines.push(start..content_end);
+                break;
+            }
+            // Longest prefix that fits (at least one character)
+            let (mut lo, mut hi) = (start + 1, content_end);
+            while lo < hi {
+                let mid = (lo + hi + 1) / 2;
+                if measure(&text[start..mid]) <= max_width { lo = mid } else { hi = mid - 1 }
+            }
+            let mut end = lo;
+            if (0xD800..0xDC00).contains(&text[end - 1]) && end < content_end {
+                end += 1; // Keep surrogate pairs together
+            }
+            // Last break opportunity (a space, or between CJK characters); else split the word
+            let line_end = if end < content_end {
+                (start + 1..=end).rev().find(|&i| super::line_break::can_break_before(text, i)).unwrap_or(end)
+            } else {
+                end
+            };
+            lines.push(start..line_end);
+            start = line_end;
+            while start < content_end && text[start] == SPACE {
+                start += 1;
+            }
+            if start >= content_end {
+                break;
+            }
+        }
+        para_start = para_end + 1;
+    }
+    lines
+}
+
+/// New scroll offset showing the `top..bottom` band (text coordinates) in a view of
+/// `view_h`; unchanged if it's already visible
+pub fn scroll_to_reveal(top: i32, bottom: i32, view_h: i32, scroll: i32) -> i32 {
+    if top >= scroll && bottom <= scroll + view_h {
+        scroll
+    } else {
+        (top - view_h / 3).max(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wide(s: &str) -> Vec<u16> {
+        s.encode_utf16().collect()
+    }
+
+    #[test]
+    fn test_find_matches() {
+        let text = wide("Error: file not found. ERROR again, error!");
+        assert_eq!(find_matches(&text, "error"), vec![0..5, 23..28, 36..41]);
+        assert_eq!(find_matches(&wide("Tiếng VIỆT tiếng việt"), "việt"), vec![6..10, 17..21]);
+        assert_eq!(find_matches(&wide("aaaa"), "aa"), vec![0..2, 2..4]);
+        assert!(find_matches(&text, "").is_empty());
+        assert!(find_matches(&wide("ab"), "abc").is_empty());
+    }
+
+    #[test]
+    fn test_wrap_lines() {
+        // 10px per character
+        let measure = |s: &[u16]| s.len() as i32 * 10;
+        let text = wide("the quick brown fox\r\n\njumps");
+        let lines = wrap_lines(&text, 100, true, measure);
+        let shown: Vec<String> = lines.iter().map(|r| String::from_utf16_lossy(&text[r.clone()])).collect();
+        assert_eq!(shown, vec!["the quick", "brown fox", "", "jumps"]);
+
+        // A word longer than the line is split; without wrapping only newlines break
+        let text = wide("abcdefghijkl mn");
+        let shown: Vec<String> = wrap_lines(&text, 50, true, measure).iter().map(|r| String::from_utf16_lossy(&text[r.clone()])).collect();
+        assert_eq!(shown, vec!["abcde", "fghij", "kl mn"]);
+        assert_eq!(wrap_lines(&text, 50, false, measure), vec![0..15]);
+
+        // CJK wraps between characters, keeping 。 off the start of a line
+        let text = wide("这是一个句子。Hello world");
+        let shown: Vec<String> = wrap_lines(&text, 60, true, measure).iter().map(|r| String::from_utf16_lossy(&text[r.clone()])).collect();
+        assert_eq!(shown, vec!["这是一个句", "子。", "Hello", "world"]);
+
+        let mut search = SearchState::default();
+        search.set_query("x".to_string());
+        search.match_count = 3;
+        search.step(false);
+        assert_eq!(search.count_label(), "3/3");
+        search.step(true);
+        assert_eq!(search.count_label(), "1/3");
+        assert_eq!(scroll_to_reveal(500, 520, 200, 0), 434);
+        assert_eq!(scroll_to_reveal(50, 70, 200, 0), 0);
+    }
+}
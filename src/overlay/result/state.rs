@@ -1,7 +1,29 @@
 use windows::Win32::Foundation::*;
 use std::collections::HashMap;
-use std::sync::Mutex;
-use windows::Win32::Graphics::Gdi::HBITMAP;
+use std::sync::{Arc, Mutex};
+use windows::Win32::Graphics::Gdi::{HBITMAP, HGDIOBJ, DeleteObject, InvalidateRect};
+use windows::Win32::UI::WindowsAndMessaging::IsWindow;
+use image::{ImageBuffer, Rgba};
+use crate::config::CopyFormat;
+
+// --- GDI OWNERSHIP ---
+/// A bitmap cached in a window's state. Deleted when it's replaced (resize, text change)
+/// or when the state is dropped, so no path can forget it. Must not be selected into a
+/// DC at that point; `paint.rs` only selects it for the length of a blit.
+pub struct OwnedBitmap(HBITMAP);
+
+impl OwnedBitmap {
+    pub fn new(hbm: HBITMAP) -> Self { OwnedBitmap(hbm) }
+    pub fn none() -> Self { OwnedBitmap(HBITMAP(0)) }
+    pub fn handle(&self) -> HBITMAP { HBITMAP(self.0.0) }
+    pub fn is_none(&self) -> bool { self.0.0 == 0 }
+}
+
+impl Drop for OwnedBitmap {
+    fn drop(&mut self) {
+        if self.0.0 != 0 { unsafe { DeleteObject(HGDIOBJ(self.0.0)); } }
+    }
+}
 
 // --- DYNAMIC PARTICLES ---
 pub struct DustParticle {
@@ -62,6 +84,18 @@ pub struct CursorPhysics {
     pub initialized: bool,
 }
 
+impl CursorPhysics {
+    /// Nothing left to animate: springs at rest, no smash in progress, no particles
+    pub fn is_settled(&self) -> bool {
+        self.mode == AnimationMode::Idle
+            && self.particles.is_empty()
+            && self.current_tilt.abs() < 0.01
+            && self.tilt_velocity.abs() < 0.01
+            && (self.squish_factor - 1.0).abs() < 0.01
+            && self.bristle_bend.abs() < 0.01
+    }
+}
+
 impl Default for CursorPhysics {
     fn default() -> Self {
         Self {
@@ -78,14 +112,34 @@ impl Default for CursorPhysics {
     }
 }
 
+/// Callback run by the export button; must not block the window thread for long
+pub type ExportAction = Arc<dyn Fn() + Send + Sync>;
+
+/// Callback run on Enter (a chat answer's follow-up question); runs on the window thread,
+/// so it hands the request off to a worker
+pub type FollowUpAction = Arc<dyn Fn() + Send + Sync>;
+
 pub struct WindowState {
     pub alpha: u8,
     pub is_hovered: bool,
     pub on_copy_btn: bool,
     pub copy_success: bool,
+    pub on_export_btn: bool,
+    pub on_edit_btn: bool,
+    pub export_action: Option<ExportAction>, // Shows the export button when set (live sessions)
+    pub follow_up_action: Option<FollowUpAction>, // Run on Enter (chat mode: ask another question)
+    pub preserve_layout: bool, // Monospace, no word-wrap (code / tables)
+    pub dismiss_on_outside_click: bool, // Closed (with linked windows) by a click outside every result window
+    pub warning_badge: Option<String>, // Small non-blocking hint in the top-left corner
+    pub stats_line: Option<String>, // Live session timings along the bottom edge
+    pub model_badge: Option<String>, // "model · 1.8s" in the top-right corner; never copied
+    pub source_image: Option<ImageBuffer<Rgba<u8>, Vec<u8>>>, // Thumbnail of the capture, for rich copy
     pub bg_color: u32,
-    pub linked_window: Option<HWND>,
+    pub linked_windows: Vec<HWND>,
     pub physics: CursorPhysics,
+    pub animations: bool, // Broom cursor, smash/fade and particles (false = plain cursor, instant close)
+    pub timer_interval: u32, // Current timer 3 period in ms (0 = stopped while nothing changes)
+    pub created_seq: u64, // Creation order; Escape closes the newest window first
     
     // --- INTERACTION STATE ---
     pub interaction_mode: InteractionMode,
@@ -97,22 +151,56 @@ pub struct WindowState {
     // --- CACHING & THROTTLING ---
     pub font_cache_dirty: bool,
     pub cached_font_size: i32,
-    pub content_bitmap: HBITMAP, 
+    pub content_bitmap: OwnedBitmap, // Rendered text; rebuilt when `font_cache_dirty`
     pub last_w: i32,
     pub last_h: i32,
     
     // New: Handle pending updates to avoid flooding Paint
     pub pending_text: Option<String>,
+    pub raw_text: Option<String>, // Markdown source of a chat result (the window shows it cleaned)
+    pub unreflowed_text: Option<String>, // The result before Preset.reflow_paragraphs joined its lines
+    pub study: Option<crate::history::StudyFields>, // Drawn as styled sections (Preset.study_layout)
     
     // Timestamp for throttling text updates (in milliseconds)
     pub last_text_update_time: u32,
     
     // BACKGROUND CACHING
-     pub bg_bitmap: HBITMAP,
+     pub bg_bitmap: OwnedBitmap, // Gradient; rebuilt on resize
      #[allow(dead_code)]
      pub bg_bits: *mut core::ffi::c_void, 
      pub bg_w: i32,
      pub bg_h: i32,
+
+    // Hidden by the "show/hide all" hotkey (restored by the next toggle)
+    pub hidden_by_toggle: bool,
+
+    // Ctrl+F search (text is scrolled instead of shrunk to fit while it's open)
+    pub search: Option<super::search::SearchState>,
+    pub scroll_y: i32,
+
+    // F2 / pencil button: the text is being corrected by hand (exclusive with search)
+    pub edit: Option<super::edit::EditState>,
+    pub edited: bool, // Text was changed by hand; later result updates no longer replace it
+
+    // Globe button: the text shown is a quick translation of this
+    pub quick_translation: Option<super::quick_translate::QuickTranslation>,
+    pub text_model: super::quick_translate::TextModel,
+    pub on_translate_btn: bool,
+
+    // Retry button of a failed request: sends it again into this window
+    pub retry_action: Option<ExportAction>,
+    pub on_retry_btn: bool,
+
+    pub copy_on_close: bool, // Auto copy: put the text on the clipboard when the user dismisses the window
+    pub copy_format: CopyFormat, // How the copy button, right click and auto copy wrap the text
+    pub copy_source: String, // Header line for CopyFormat::SourceHeader
+    pub font_family: Option<String>, // Preset.font_family; None picks a font for the text's script
+    pub rtl: bool, // The text reads right-to-left (set when it's laid out); the buttons sit bottom-left
+
+    // The text is an error message, red-tinted
+    pub is_error: bool,
+    // ...one the API key settings fix (missing or invalid key, quota): a click opens them
+    pub key_error: bool,
 }
 
 // SAFETY: Raw pointers are not Send/Sync, but we only use them within the main thread
@@ -128,14 +216,183 @@ pub enum WindowType {
     Primary,
     Secondary,
     SecondaryExplicit, // New type: Trust the coordinates, use Secondary color
+    Tertiary, // Second retranslation target, prefers the spot below the selection
+}
+
+pub fn set_export_action(hwnd: HWND, action: ExportAction) {
+    let mut states = WINDOW_STATES.lock().unwrap();
+    if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+        state.export_action = Some(action);
+    }
+}
+
+/// Drops the state (and bitmaps) of windows destroyed without their WM_DESTROY reaching
+/// `result_wnd_proc`: a session thread that returns from its message loop while another of
+/// its windows is still open takes that window down with it.
+/// Returns how many were dropped.
+pub fn drop_destroyed_windows() -> usize {
+    let mut states = WINDOW_STATES.lock().unwrap();
+    let before = states.len();
+    states.retain(|&h, _| unsafe { IsWindow(HWND(h)).as_bool() });
+    before - states.len()
+}
+
+pub fn set_follow_up_action(hwnd: HWND, action: FollowUpAction) {
+    let mut states = WINDOW_STATES.lock().unwrap();
+    if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+        state.follow_up_action = Some(action);
+    }
+}
+
+pub fn set_retry_action(hwnd: HWND, action: ExportAction) {
+    if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+        state.retry_action = Some(action);
+    }
+    unsafe { InvalidateRect(hwnd, None, false); }
+}
+
+pub fn set_copy_on_close(hwnd: HWND, enabled: bool) {
+    if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+        state.copy_on_close = enabled;
+    }
+}
+
+/// The text shown before `Preset.reflow_paragraphs` joined its lines, offered on the
+/// copy menu. Set after the reflowed text; the next text update clears it.
+pub fn set_unreflowed_text(hwnd: HWND, text: Option<String>) {
+    if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+        state.unreflowed_text = text;
+    }
+}
+
+/// Draw the result as the sections of a study card. Set after the card's text; the next
+/// text update clears it.
+pub fn set_study_card(hwnd: HWND, card: Option<crate::history::StudyFields>) {
+    if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+        state.study = card;
+        state.font_cache_dirty = true;
+    }
+    unsafe { InvalidateRect(hwnd, None, false); }
+}
+
+/// The preset's retranslation model and streaming setting, for the globe button
+pub fn set_text_model(hwnd: HWND, model_id: String, streaming: bool) {
+    if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+        state.text_model = super::quick_translate::TextModel { model_id, streaming };
+    }
+}
+
+/// The preset's `font_family` override for the result text
+pub fn set_font_family(hwnd: HWND, family: Option<String>) {
+    if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+        state.font_family = family;
+        state.font_cache_dirty = true;
+    }
+}
+
+/// The preset's `copy_format`, with the header `CopyFormat::SourceHeader` puts above the text
+pub fn set_copy_format(hwnd: HWND, format: CopyFormat, source: String) {
+    if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+        state.copy_format = format;
+        state.copy_source = source;
+    }
+}
+
+/// `text` wrapped in the window's copy format (`format` overrides it)
+pub fn format_for_copy(hwnd: HWND, text: &str, format: Option<CopyFormat>) -> String {
+    let (default_format, source) = WINDOW_STATES.lock().unwrap().get(&(hwnd.0 as isize))
+        .map(|s| (s.copy_format, s.copy_source.clone()))
+        .unwrap_or_default();
+    crate::overlay::utils::format_copy(text, format.unwrap_or(default_format), &source)
+}
+
+/// Switch the error styling, repainting the background when it changes
+pub fn set_error_style(state: &mut WindowState, is_error: bool) {
+    if !is_error {
+        state.key_error = false;
+    }
+    if state.is_error != is_error {
+        state.is_error = is_error;
+        state.bg_bitmap = OwnedBitmap::none();
+        state.font_cache_dirty = true;
+    }
+}
+
+pub fn set_preserve_layout(hwnd: HWND, enabled: bool) {
+    let mut states = WINDOW_STATES.lock().unwrap();
+    if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+        state.preserve_layout = enabled;
+        state.font_cache_dirty = true;
+    }
+    // The window's timer may be stopped, so nothing else would repaint it
+    unsafe { InvalidateRect(hwnd, None, false); }
+}
+
+pub fn set_dismiss_on_outside_click(hwnd: HWND, enabled: bool) {
+    if let Some(state) = WINDOW_STATES.lock().unwrap().get_mut(&(hwnd.0 as isize)) {
+        state.dismiss_on_outside_click = enabled;
+    }
+    super::outside_click::sync();
+}
+
+pub fn set_warning_badge(hwnd: HWND, badge: Option<String>) {
+    let mut states = WINDOW_STATES.lock().unwrap();
+    if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+        state.warning_badge = badge;
+    }
+    unsafe { InvalidateRect(hwnd, None, false); }
+}
+
+pub fn set_model_badge(hwnd: HWND, badge: Option<String>) {
+    let mut states = WINDOW_STATES.lock().unwrap();
+    if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+        state.model_badge = badge;
+    }
+    unsafe { InvalidateRect(hwnd, None, false); }
+}
+
+pub fn set_stats_line(hwnd: HWND, line: Option<String>) {
+    let mut states = WINDOW_STATES.lock().unwrap();
+    if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+        state.stats_line = line;
+    }
+    unsafe { InvalidateRect(hwnd, None, false); }
+}
+
+/// Longest side of the thumbnail kept for rich copy
+const SOURCE_THUMB_MAX: u32 = 640;
+
+pub fn set_source_image(hwnd: HWND, image: &ImageBuffer<Rgba<u8>, Vec<u8>>) {
+    let (w, h) = image.dimensions();
+    let thumb = if w.max(h) > SOURCE_THUMB_MAX {
+        let scale = SOURCE_THUMB_MAX as f32 / w.max(h) as f32;
+        image::imageops::thumbnail(image, ((w as f32 * scale) as u32).max(1), ((h as f32 * scale) as u32).max(1))
+    } else {
+        image.clone()
+    };
+
+    let mut states = WINDOW_STATES.lock().unwrap();
+    if let Some(state) = states.get_mut(&(hwnd.0 as isize)) {
+        state.source_image = Some(thumb);
+    }
+}
+
+/// The capture behind this window, or behind a linked window (retranslations share the primary's)
+pub fn get_source_image(hwnd: HWND) -> Option<ImageBuffer<Rgba<u8>, Vec<u8>>> {
+    let states = WINDOW_STATES.lock().unwrap();
+    let state = states.get(&(hwnd.0 as isize))?;
+    state.source_image.clone().or_else(|| {
+        state.linked_windows.iter()
+            .find_map(|h| states.get(&(h.0 as isize)).and_then(|s| s.source_image.clone()))
+    })
 }
 
 pub fn link_windows(hwnd1: HWND, hwnd2: HWND) {
     let mut states = WINDOW_STATES.lock().unwrap();
     if let Some(s1) = states.get_mut(&(hwnd1.0 as isize)) {
-        s1.linked_window = Some(hwnd2);
+        if !s1.linked_windows.contains(&hwnd2) { s1.linked_windows.push(hwnd2); }
     }
     if let Some(s2) = states.get_mut(&(hwnd2.0 as isize)) {
-        s2.linked_window = Some(hwnd1);
+        if !s2.linked_windows.contains(&hwnd1) { s2.linked_windows.push(hwnd1); }
     }
 }
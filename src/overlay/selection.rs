@@ -2,7 +2,7 @@ use windows::Win32::Foundation::*;
 use windows::Win32::UI::WindowsAndMessaging::*;
 use windows::Win32::Graphics::Gdi::*;
 use windows::Win32::System::LibraryLoader::*;
-use windows::Win32::UI::Input::KeyboardAndMouse::{SetCapture, ReleaseCapture, VK_ESCAPE};
+use windows::Win32::UI::Input::KeyboardAndMouse::{SetCapture, ReleaseCapture, GetKeyState, VK_ESCAPE, VK_RETURN, VK_SHIFT, VK_SPACE, VK_LEFT, VK_RIGHT, VK_UP, VK_DOWN};
 use windows::core::*;
 use image::GenericImageView;
 
@@ -12,8 +12,9 @@ use crate::{APP};
 // --- CONFIGURATION ---
 const FADE_TIMER_ID: usize = 2;
 const ANIM_TIMER_ID: usize = 1;
-const TARGET_OPACITY: u8 = 120; 
-const FADE_STEP: u8 = 40; // Increased for much faster fade (approx 3 frames / 50ms)
+const FADE_STEP: u8 = 85; // Fades in or out in 3 frames (~50ms)
+const NUDGE_STEP: i32 = 1;
+const NUDGE_STEP_FAST: i32 = 10; // With Shift held
 
 // --- STATE ---
 static mut START_POS: POINT = POINT { x: 0, y: 0 };
@@ -26,6 +27,22 @@ static mut SELECTION_OVERLAY_ACTIVE: bool = false;
 static mut SELECTION_OVERLAY_HWND: HWND = HWND(0);
 static mut CURRENT_PRESET_IDX: usize = 0;
 static mut ANIMATION_OFFSET: f32 = 0.0;
+// Precise mode: entered when Shift/Space/arrows are used during a drag. The selection
+// then stays on screen after release until Enter confirms or Escape cancels.
+static mut IS_ADJUSTING: bool = false;
+static mut PRECISE_MODE: bool = false;
+static mut ASPECT_LOCK: Option<f32> = None;
+// Picking a new region for a running live vision session instead of starting a capture
+static mut REGION_PICK_MODE: bool = false;
+// Screen area the overlay covers (the preset's target monitor, or all monitors)
+static mut OVERLAY_BOUNDS: RECT = RECT { left: 0, top: 0, right: 0, bottom: 0 };
+// Appearance (Config.overlay_appearance), read each time the overlay opens
+static mut DIM_ALPHA: u8 = 120;
+static mut BORDER_COLOR: u32 = 0x00AAAAAA;
+static mut BORDER_WIDTH: f32 = 2.0;
+static mut SHOW_GRID: bool = false;
+static mut SHOW_CROSSHAIR: bool = false;
+static mut CURSOR_POS: POINT = POINT { x: 0, y: 0 }; // For the crosshair, dragging or not
 
 
 pub fn is_selection_overlay_active_and_dismiss() -> bool {
@@ -39,15 +56,62 @@ pub fn is_selection_overlay_active_and_dismiss() -> bool {
     }
 }
 
-pub fn show_selection_overlay(preset_idx: usize) {
+/// Select a new region for the running live vision session (its control bar's "change
+/// region"). `preset_idx` is the session's preset, not whichever overlay opened last.
+pub fn pick_live_vision_region(preset_idx: usize) {
+    unsafe {
+        if SELECTION_OVERLAY_ACTIVE { return; }
+        REGION_PICK_MODE = true;
+        let target_monitor = APP.lock().unwrap().config.presets.get(preset_idx).and_then(|p| p.target_monitor.clone());
+        show_selection_overlay(preset_idx, selection_bounds(target_monitor.as_deref()));
+        REGION_PICK_MODE = false;
+    }
+}
+
+fn monitor_missing_message(ui_language: &str, device_name: &str) -> String {
+    match ui_language {
+        "vi" => format!("Không tìm thấy màn hình {}, dùng màn hình hiện tại", device_name),
+        "ko" => format!("모니터 {}을(를) 찾을 수 없어 현재 모니터를 사용합니다", device_name),
+        _ => format!("Monitor {} not found, using the current one", device_name),
+    }
+}
+
+/// Area the selection overlay covers for a preset: its `target_monitor`, or every monitor.
+/// A target monitor that's no longer attached falls back to the cursor's, with a toast.
+pub fn selection_bounds(target_monitor: Option<&str>) -> RECT {
+    let Some(device_name) = target_monitor else {
+        return crate::capture::virtual_screen_rect();
+    };
+    crate::capture::find_monitor(device_name).unwrap_or_else(|| {
+        log::warn!("Target monitor {} not found, using the cursor's monitor", device_name);
+        let ui_language = APP.lock().map(|a| a.config.ui_language.clone()).unwrap_or_default();
+        super::app_blocklist::show_toast(monitor_missing_message(&ui_language, device_name));
+        crate::capture::cursor_monitor_rect()
+    })
+}
+
+/// `bounds`: screen area to cover, see `selection_bounds`
+pub fn show_selection_overlay(preset_idx: usize, bounds: RECT) {
     unsafe {
         CURRENT_PRESET_IDX = preset_idx;
+        OVERLAY_BOUNDS = bounds;
         SELECTION_OVERLAY_ACTIVE = true;
         ANIMATION_OFFSET = 0.0;
         CURRENT_ALPHA = 0;
         IS_FADING_OUT = false;
         IS_DRAGGING = false;
         IS_PROCESSING = false;
+        IS_ADJUSTING = false;
+        PRECISE_MODE = false;
+        ASPECT_LOCK = None;
+
+        let appearance = APP.lock().unwrap().config.overlay_appearance.clone();
+        DIM_ALPHA = appearance.overlay_alpha();
+        BORDER_COLOR = appearance.border_rgb();
+        BORDER_WIDTH = appearance.border_width.clamp(1, crate::config::OverlayAppearance::MAX_BORDER_WIDTH) as f32;
+        SHOW_GRID = appearance.show_grid;
+        SHOW_CROSSHAIR = appearance.crosshair;
+        GetCursorPos(std::ptr::addr_of_mut!(CURSOR_POS));
         
         let instance = GetModuleHandleW(None).unwrap();
         let class_name = w!("SnippingOverlay");
@@ -62,10 +126,8 @@ pub fn show_selection_overlay(preset_idx: usize) {
             RegisterClassW(&wc);
         }
 
-        let x = GetSystemMetrics(SM_XVIRTUALSCREEN);
-        let y = GetSystemMetrics(SM_YVIRTUALSCREEN);
-        let w = GetSystemMetrics(SM_CXVIRTUALSCREEN);
-        let h = GetSystemMetrics(SM_CYVIRTUALSCREEN);
+        let (x, y) = (bounds.left, bounds.top);
+        let (w, h) = (bounds.right - bounds.left, bounds.bottom - bounds.top);
         
         let hwnd = CreateWindowExW(
             WS_EX_LAYERED | WS_EX_TOPMOST | WS_EX_TOOLWINDOW,
@@ -77,8 +139,9 @@ pub fn show_selection_overlay(preset_idx: usize) {
         );
 
         SELECTION_OVERLAY_HWND = hwnd;
+        crate::capture::exclude_from_capture(hwnd);
 
-        SetLayeredWindowAttributes(hwnd, COLORREF(0), 0, LWA_ALPHA);
+        present(hwnd); // Fully faded out until the fade timer runs
         ShowWindow(hwnd, SW_SHOW);
         
         SetTimer(hwnd, FADE_TIMER_ID, 16, None);
@@ -98,25 +161,87 @@ pub fn show_selection_overlay(preset_idx: usize) {
 unsafe extern "system" fn selection_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
     match msg {
         WM_KEYDOWN => {
-            if wparam.0 == VK_ESCAPE.0 as usize {
+            let key = wparam.0 as u16;
+            if key == VK_ESCAPE.0 {
                 SendMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+            } else if key == VK_RETURN.0 && (IS_DRAGGING || IS_ADJUSTING) {
+                if IS_DRAGGING {
+                    IS_DRAGGING = false;
+                    ReleaseCapture();
+                }
+                IS_ADJUSTING = false;
+                confirm_selection(hwnd);
+            } else if let Some((dx, dy)) = nudge_delta(key) {
+                let step = if is_key_down(VK_SHIFT.0) { NUDGE_STEP_FAST } else { NUDGE_STEP };
+                let (dx, dy) = (dx * step, dy * step);
+                if IS_DRAGGING {
+                    // Move the cursor so the dragged corner follows and the mouse stays in sync
+                    PRECISE_MODE = true;
+                    let mut pt = POINT::default();
+                    GetCursorPos(&mut pt);
+                    SetCursorPos(pt.x + dx, pt.y + dy);
+                } else if IS_ADJUSTING {
+                    // Space + arrows moves the whole selection, arrows alone nudge the active edge
+                    if is_key_down(VK_SPACE.0) {
+                        START_POS.x += dx;
+                        START_POS.y += dy;
+                    }
+                    CURR_POS.x += dx;
+                    CURR_POS.y += dy;
+                    present(hwnd);
+                }
             }
             LRESULT(0)
         }
         WM_LBUTTONDOWN => {
             if !IS_PROCESSING && !IS_FADING_OUT {
                 IS_DRAGGING = true;
+                IS_ADJUSTING = false;
+                PRECISE_MODE = false;
+                ASPECT_LOCK = None;
                 GetCursorPos(std::ptr::addr_of_mut!(START_POS));
                 CURR_POS = START_POS;
                 SetCapture(hwnd);
-                InvalidateRect(hwnd, None, false);
+                present(hwnd);
             }
             LRESULT(0)
         }
         WM_MOUSEMOVE => {
+            if SHOW_CROSSHAIR {
+                GetCursorPos(std::ptr::addr_of_mut!(CURSOR_POS));
+                CURSOR_POS = clamp_to(CURSOR_POS, OVERLAY_BOUNDS);
+                if !IS_DRAGGING {
+                    present(hwnd);
+                }
+            }
             if IS_DRAGGING {
-                GetCursorPos(std::ptr::addr_of_mut!(CURR_POS));
-                InvalidateRect(hwnd, None, false);
+                let mut pt = POINT::default();
+                GetCursorPos(&mut pt);
+
+                if is_key_down(VK_SPACE.0) {
+                    // Space: move the whole selection with the cursor
+                    PRECISE_MODE = true;
+                    START_POS.x += pt.x - CURR_POS.x;
+                    START_POS.y += pt.y - CURR_POS.y;
+                    CURR_POS = pt;
+                } else if is_key_down(VK_SHIFT.0) {
+                    // Shift: lock to the aspect ratio the selection had when Shift went down
+                    PRECISE_MODE = true;
+                    let ratio = match ASPECT_LOCK {
+                        Some(ratio) => ratio,
+                        None => aspect_ratio(CURR_POS.x - START_POS.x, CURR_POS.y - START_POS.y),
+                    };
+                    ASPECT_LOCK = Some(ratio);
+                    let (dx, dy) = lock_aspect(pt.x - START_POS.x, pt.y - START_POS.y, ratio);
+                    CURR_POS = POINT { x: START_POS.x + dx, y: START_POS.y + dy };
+                } else {
+                    ASPECT_LOCK = None;
+                    CURR_POS = pt;
+                }
+                // The mouse is captured, so it can leave the overlay's monitor
+                START_POS = clamp_to(START_POS, OVERLAY_BOUNDS);
+                CURR_POS = clamp_to(CURR_POS, OVERLAY_BOUNDS);
+                present(hwnd);
             }
             LRESULT(0)
         }
@@ -125,100 +250,12 @@ unsafe extern "system" fn selection_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARA
                 IS_DRAGGING = false;
                 ReleaseCapture();
 
-                let rect = RECT {
-                    left: START_POS.x.min(CURR_POS.x),
-                    top: START_POS.y.min(CURR_POS.y),
-                    right: START_POS.x.max(CURR_POS.x),
-                    bottom: START_POS.y.max(CURR_POS.y),
-                };
-
-                let width = (rect.right - rect.left).abs();
-                let height = (rect.bottom - rect.top).abs();
-
-                if width > 10 && height > 10 {
-                    // Check if Quick Actions is enabled
-                    let (quick_actions_enabled, preset_show_quick_actions) = {
-                        if let Ok(app) = APP.lock() {
-                            let qa_enabled = app.config.quick_actions.enabled;
-                            let preset_qa = if CURRENT_PRESET_IDX < app.config.presets.len() {
-                                app.config.presets[CURRENT_PRESET_IDX].show_quick_actions
-                            } else {
-                                false
-                            };
-                            (qa_enabled, preset_qa)
-                        } else {
-                            (false, false)
-                        }
-                    };
-
-                    // If Quick Actions is enabled globally or for this preset, show menu
-                    if quick_actions_enabled || preset_show_quick_actions {
-                        // Close selection overlay first
-                        SendMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
-                        
-                        // Show Quick Actions menu in a new thread
-                        let app_clone = APP.clone();
-                        std::thread::spawn(move || {
-                            // Capture the region first
-                            if let Ok(app) = app_clone.lock() {
-                                if let Some(ref screenshot) = app.original_screenshot {
-                                    // Crop the selected region
-                                    let screen_x = GetSystemMetrics(SM_XVIRTUALSCREEN);
-                                    let screen_y = GetSystemMetrics(SM_YVIRTUALSCREEN);
-                                    
-                                    let crop_x = (rect.left - screen_x).max(0) as u32;
-                                    let crop_y = (rect.top - screen_y).max(0) as u32;
-                                    let crop_w = width as u32;
-                                    let crop_h = height as u32;
-                                    
-                                    let cropped = image::imageops::crop_imm(
-                                        screenshot, 
-                                        crop_x, crop_y, 
-                                        crop_w.min(screenshot.width() - crop_x), 
-                                        crop_h.min(screenshot.height() - crop_y)
-                                    ).to_image();
-                                    
-                                    // Encode to PNG for the menu
-                                    let mut png_data = Vec::new();
-                                    let _ = cropped.write_to(
-                                        &mut std::io::Cursor::new(&mut png_data), 
-                                        image::ImageFormat::Png
-                                    );
-                                    
-                                    drop(app); // Release lock before showing menu
-                                    
-                                    // Show quick actions menu - returns selected QuickAction with model
-                                    if let Some(selected_action) = super::quick_actions::show_quick_actions_menu(rect, png_data) {
-                                        // Find the preset and process with selected model
-                                        if let Ok(mut app2) = app_clone.lock() {
-                                            if let Some(preset_idx) = app2.config.presets.iter()
-                                                .position(|p| p.id == selected_action.preset_id) 
-                                            {
-                                                // Override model if QuickAction has a specific model set
-                                                if !selected_action.model.is_empty() {
-                                                    app2.config.presets[preset_idx].model = selected_action.model.clone();
-                                                }
-                                                drop(app2);
-                                                process_and_close(app_clone.clone(), rect, HWND(0), preset_idx);
-                                            }
-                                        }
-                                    }
-                                }
-                            }
-                        });
-                    } else {
-                        // Original flow - process immediately
-                        IS_PROCESSING = true;
-                        SetTimer(hwnd, ANIM_TIMER_ID, 16, None);
-                        
-                        let app_clone = APP.clone();
-                        let p_idx = CURRENT_PRESET_IDX;
-                        std::thread::spawn(move || {
-                            process_and_close(app_clone, rect, hwnd, p_idx);
-                        });
-                    }
+                if PRECISE_MODE {
+                    // Keep the selection up for fine-tuning; Enter confirms
+                    IS_ADJUSTING = true;
+                    present(hwnd);
                 } else {
-                    SendMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+                    confirm_selection(hwnd);
                 }
             }
             LRESULT(0)
@@ -240,8 +277,8 @@ unsafe extern "system" fn selection_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARA
                         return LRESULT(0);
                     }
                 } else {
-                    if CURRENT_ALPHA < TARGET_OPACITY {
-                        CURRENT_ALPHA = (CURRENT_ALPHA as u16 + FADE_STEP as u16).min(TARGET_OPACITY as u16) as u8;
+                    if CURRENT_ALPHA < 255 {
+                        CURRENT_ALPHA = CURRENT_ALPHA.saturating_add(FADE_STEP);
                         changed = true;
                     } else {
                         KillTimer(hwnd, FADE_TIMER_ID);
@@ -249,7 +286,7 @@ unsafe extern "system" fn selection_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARA
                 }
                 
                 if changed {
-                    SetLayeredWindowAttributes(hwnd, COLORREF(0), CURRENT_ALPHA, LWA_ALPHA);
+                    present(hwnd);
                 }
             }
             
@@ -257,68 +294,10 @@ unsafe extern "system" fn selection_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARA
                 // ANIMATION UPDATE
                 ANIMATION_OFFSET += 5.0; 
                 if ANIMATION_OFFSET > 360.0 { ANIMATION_OFFSET -= 360.0; }
-                InvalidateRect(hwnd, None, false);
+                present(hwnd);
             }
             LRESULT(0)
         }
-        WM_PAINT => {
-            let mut ps = PAINTSTRUCT::default();
-            let hdc = BeginPaint(hwnd, &mut ps);
-            
-            let mem_dc = CreateCompatibleDC(hdc);
-            let width = GetSystemMetrics(SM_CXVIRTUALSCREEN);
-            let height = GetSystemMetrics(SM_CYVIRTUALSCREEN);
-            
-            let mem_bitmap = CreateCompatibleBitmap(hdc, width, height);
-            SelectObject(mem_dc, mem_bitmap);
-
-            // Clear Background
-            let brush = CreateSolidBrush(COLORREF(0x00000000));
-            let full_rect = RECT { left: 0, top: 0, right: width, bottom: height };
-            FillRect(mem_dc, &full_rect, brush);
-            DeleteObject(brush);
-
-            if IS_DRAGGING || IS_PROCESSING {
-                let rect_abs = RECT {
-                    left: START_POS.x.min(CURR_POS.x),
-                    top: START_POS.y.min(CURR_POS.y),
-                    right: START_POS.x.max(CURR_POS.x),
-                    bottom: START_POS.y.max(CURR_POS.y),
-                };
-
-                let screen_x = GetSystemMetrics(SM_XVIRTUALSCREEN);
-                let screen_y = GetSystemMetrics(SM_YVIRTUALSCREEN);
-
-                let r = RECT {
-                    left: rect_abs.left - screen_x,
-                    top: rect_abs.top - screen_y,
-                    right: rect_abs.right - screen_x,
-                    bottom: rect_abs.bottom - screen_y,
-                };
-                
-                let w = (r.right - r.left) as i32;
-                let h = (r.bottom - r.top) as i32;
-                if w > 0 && h > 0 {
-                    // FIX: Always use the optimized render_box_sdf.
-                    // Pass IS_PROCESSING as the is_glowing flag for animated rainbow.
-                    // Pass ANIMATION_OFFSET for time-based animation.
-                    super::paint_utils::render_box_sdf(
-                        HDC(mem_dc.0),
-                        r,
-                        w,
-                        h,
-                        IS_PROCESSING, // True = Animated Rainbow, False = Static White
-                        ANIMATION_OFFSET
-                    );
-                }
-            }
-
-            BitBlt(hdc, 0, 0, width, height, mem_dc, 0, 0, SRCCOPY).ok().unwrap();
-            DeleteObject(mem_bitmap);
-            DeleteDC(mem_dc);
-            EndPaint(hwnd, &mut ps);
-            LRESULT(0)
-        }
         WM_CLOSE => {
             if !IS_FADING_OUT {
                 IS_FADING_OUT = true;
@@ -330,3 +309,357 @@ unsafe extern "system" fn selection_wnd_proc(hwnd: HWND, msg: u32, wparam: WPARA
         _ => DefWindowProcW(hwnd, msg, wparam, lparam),
     }
 }
+
+/// Draw the overlay and hand it to DWM with per-pixel alpha: black at the dim level
+/// behind everything, while the border, grid, crosshair and size label stay opaque
+/// however low the dim is set. `CURRENT_ALPHA` fades the whole window in and out.
+unsafe fn present(hwnd: HWND) {
+    let width = OVERLAY_BOUNDS.right - OVERLAY_BOUNDS.left;
+    let height = OVERLAY_BOUNDS.bottom - OVERLAY_BOUNDS.top;
+    if width <= 0 || height <= 0 { return; }
+
+    let screen_dc = GetDC(None);
+    let mem_dc = CreateCompatibleDC(screen_dc);
+    let bmi = BITMAPINFO {
+        bmiHeader: BITMAPINFOHEADER {
+            biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+            biWidth: width,
+            biHeight: -height, // Top-down
+            biPlanes: 1,
+            biBitCount: 32,
+            biCompression: BI_RGB.0 as u32,
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let mut bits: *mut core::ffi::c_void = std::ptr::null_mut();
+    let Ok(bitmap) = CreateDIBSection(screen_dc, &bmi, DIB_RGB_COLORS, &mut bits, None, 0) else {
+        DeleteDC(mem_dc);
+        ReleaseDC(None, screen_dc);
+        return;
+    };
+    let old_bitmap = SelectObject(mem_dc, bitmap);
+    let pixels = std::slice::from_raw_parts_mut(bits as *mut u32, (width * height) as usize);
+    pixels.fill((DIM_ALPHA as u32) << 24); // Premultiplied black, never alpha 0
+
+    if IS_DRAGGING || IS_PROCESSING || IS_ADJUSTING {
+        let r = RECT {
+            left: START_POS.x.min(CURR_POS.x) - OVERLAY_BOUNDS.left,
+            top: START_POS.y.min(CURR_POS.y) - OVERLAY_BOUNDS.top,
+            right: START_POS.x.max(CURR_POS.x) - OVERLAY_BOUNDS.left,
+            bottom: START_POS.y.max(CURR_POS.y) - OVERLAY_BOUNDS.top,
+        };
+        let w = r.right - r.left;
+        let h = r.bottom - r.top;
+        if w > 0 && h > 0 {
+            // Animated rainbow while processing, the border color while selecting
+            super::paint_utils::render_box_sdf(HDC(mem_dc.0), r, w, h, IS_PROCESSING, ANIMATION_OFFSET, BORDER_COLOR, BORDER_WIDTH, DIM_ALPHA);
+            if SHOW_GRID && !IS_PROCESSING {
+                draw_thirds_grid(HDC(mem_dc.0), r);
+            }
+        }
+        if !IS_PROCESSING {
+            draw_dimensions_label(HDC(mem_dc.0), r, width, height);
+        }
+    }
+
+    if SHOW_CROSSHAIR && !IS_PROCESSING && !IS_ADJUSTING {
+        let at = POINT { x: CURSOR_POS.x - OVERLAY_BOUNDS.left, y: CURSOR_POS.y - OVERLAY_BOUNDS.top };
+        draw_crosshair(HDC(mem_dc.0), at, width, height);
+    }
+
+    // GDI zeroes the alpha of what it draws (lines, label): make those pixels opaque
+    GdiFlush();
+    for pixel in pixels.iter_mut().filter(|p| **p >> 24 == 0) {
+        *pixel |= 0xFF000000;
+    }
+
+    let size = SIZE { cx: width, cy: height };
+    let pt_src = POINT { x: 0, y: 0 };
+    let blend = BLENDFUNCTION {
+        BlendOp: AC_SRC_OVER as u8,
+        BlendFlags: 0,
+        SourceConstantAlpha: CURRENT_ALPHA,
+        AlphaFormat: AC_SRC_ALPHA as u8,
+    };
+    UpdateLayeredWindow(hwnd, HDC(0), None, Some(&size), mem_dc, Some(&pt_src), COLORREF(0), Some(&blend), ULW_ALPHA);
+
+    SelectObject(mem_dc, old_bitmap);
+    DeleteObject(bitmap);
+    DeleteDC(mem_dc);
+    ReleaseDC(None, screen_dc);
+}
+
+/// Hand the current selection off to processing (or Quick Actions), or close if it is too small
+unsafe fn confirm_selection(hwnd: HWND) {
+    let rect = RECT {
+        left: START_POS.x.min(CURR_POS.x),
+        top: START_POS.y.min(CURR_POS.y),
+        right: START_POS.x.max(CURR_POS.x),
+        bottom: START_POS.y.max(CURR_POS.y),
+    };
+
+    let width = (rect.right - rect.left).abs();
+    let height = (rect.bottom - rect.top).abs();
+    let (min_size, ui_language) = APP.lock().map(|app| (app.config.min_region_size, app.config.ui_language.clone())).unwrap_or_default();
+    let big_enough = !super::process::region_too_small(rect, min_size);
+
+    if big_enough && !REGION_PICK_MODE && !capture_if_pending(hwnd, rect) {
+        SendMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+        return;
+    }
+
+    if big_enough && REGION_PICK_MODE {
+        crate::api::set_live_vision_region(rect);
+        SendMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+    } else if big_enough {
+        // Check if Quick Actions is enabled
+        let (quick_actions_enabled, preset_show_quick_actions) = {
+            if let Ok(app) = APP.lock() {
+                let qa_enabled = app.config.quick_actions.enabled;
+                let preset_qa = if CURRENT_PRESET_IDX < app.config.presets.len() {
+                    app.config.presets[CURRENT_PRESET_IDX].show_quick_actions
+                } else {
+                    false
+                };
+                (qa_enabled, preset_qa)
+            } else {
+                (false, false)
+            }
+        };
+
+        // If Quick Actions is enabled globally or for this preset, show menu
+        if quick_actions_enabled || preset_show_quick_actions {
+            // Close selection overlay first
+            SendMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+            
+            // Show Quick Actions menu in a new thread
+            let app_clone = APP.clone();
+            std::thread::spawn(move || {
+                // Capture the region first
+                if let Ok(app) = app_clone.lock() {
+                    if let Some(ref screenshot) = app.original_screenshot {
+                        // Crop the selected region
+                        let (screen_x, screen_y) = app.screenshot_origin;
+                        
+                        let crop_x = (rect.left - screen_x).max(0) as u32;
+                        let crop_y = (rect.top - screen_y).max(0) as u32;
+                        let crop_w = width as u32;
+                        let crop_h = height as u32;
+                        
+                        let cropped = image::imageops::crop_imm(
+                            screenshot, 
+                            crop_x, crop_y, 
+                            crop_w.min(screenshot.width() - crop_x), 
+                            crop_h.min(screenshot.height() - crop_y)
+                        ).to_image();
+                        
+                        // Encode to PNG for the menu
+                        let mut png_data = Vec::new();
+                        let _ = cropped.write_to(
+                            &mut std::io::Cursor::new(&mut png_data), 
+                            image::ImageFormat::Png
+                        );
+                        
+                        drop(app); // Release lock before showing menu
+                        
+                        // Show quick actions menu - returns selected QuickAction with model
+                        if let Some(selected_action) = super::quick_actions::show_quick_actions_menu(rect, png_data) {
+                            // Find the preset and process with selected model
+                            if let Ok(mut app2) = app_clone.lock() {
+                                if let Some(preset_idx) = app2.config.presets.iter()
+                                    .position(|p| p.id == selected_action.preset_id) 
+                                {
+                                    // Override model if QuickAction has a specific model set
+                                    if !selected_action.model.is_empty() {
+                                        app2.config.presets[preset_idx].model = selected_action.model.clone();
+                                    }
+                                    drop(app2);
+                                    process_and_close(app_clone.clone(), rect, HWND(0), preset_idx);
+                                }
+                            }
+                        }
+                    }
+                }
+            });
+        } else {
+            // Original flow - process immediately
+            IS_PROCESSING = true;
+            SetTimer(hwnd, ANIM_TIMER_ID, 16, None);
+            
+            let app_clone = APP.clone();
+            let p_idx = CURRENT_PRESET_IDX;
+            std::thread::spawn(move || {
+                process_and_close(app_clone, rect, hwnd, p_idx);
+            });
+        }
+    } else {
+        // A plain click just cancels; a small drag says why nothing happens
+        if width > 0 || height > 0 {
+            super::app_blocklist::show_toast(super::process::too_small_message(&ui_language, min_size));
+        }
+        SendMessageW(hwnd, WM_CLOSE, WPARAM(0), LPARAM(0));
+    }
+}
+
+/// `Preset.capture_on_release`: nothing was captured when the hotkey was pressed, so the
+/// selection is captured now, at mouse-up. False if that failed.
+unsafe fn capture_if_pending(hwnd: HWND, rect: RECT) -> bool {
+    if APP.lock().map_or(true, |app| app.original_screenshot.is_some()) {
+        return true;
+    }
+    // Without capture exclusion the dimmed overlay would be in the picture
+    let hide = !crate::capture::exclusion_supported();
+    let alpha = CURRENT_ALPHA;
+    if hide {
+        CURRENT_ALPHA = 0;
+        present(hwnd);
+        std::thread::sleep(std::time::Duration::from_millis(60)); // Let DWM compose a frame without it
+    }
+    let result = crate::capture::capture_rect(rect);
+    if hide {
+        CURRENT_ALPHA = alpha;
+        present(hwnd);
+    }
+    match result {
+        Ok(img) => {
+            if let Ok(mut app) = APP.lock() {
+                app.original_screenshot = Some(img);
+                app.screenshot_origin = (rect.left, rect.top);
+            }
+            true
+        }
+        Err(e) => {
+            log::error!("Capture Error: {}", e);
+            false
+        }
+    }
+}
+
+/// 1 px pen in the border color (COLORREF is BGR)
+unsafe fn border_pen() -> HPEN {
+    let rgb = BORDER_COLOR;
+    let bgr = ((rgb & 0xFF) << 16) | (rgb & 0xFF00) | ((rgb >> 16) & 0xFF);
+    CreatePen(PS_SOLID, 1, COLORREF(bgr))
+}
+
+/// Guide lines across the whole overlay through `at`, in the border color
+unsafe fn draw_crosshair(hdc: HDC, at: POINT, width: i32, height: i32) {
+    let pen = border_pen();
+    let old_pen = SelectObject(hdc, pen);
+    MoveToEx(hdc, at.x, 0, None);
+    LineTo(hdc, at.x, height);
+    MoveToEx(hdc, 0, at.y, None);
+    LineTo(hdc, width, at.y);
+    SelectObject(hdc, old_pen);
+    DeleteObject(pen);
+}
+
+/// Rule-of-thirds lines inside the selection, in the border color
+unsafe fn draw_thirds_grid(hdc: HDC, r: RECT) {
+    let pen = border_pen();
+    let old_pen = SelectObject(hdc, pen);
+    let (w, h) = (r.right - r.left, r.bottom - r.top);
+    for i in 1..3 {
+        let x = r.left + w * i / 3;
+        let y = r.top + h * i / 3;
+        MoveToEx(hdc, x, r.top, None);
+        LineTo(hdc, x, r.bottom);
+        MoveToEx(hdc, r.left, y, None);
+        LineTo(hdc, r.right, y);
+    }
+    SelectObject(hdc, old_pen);
+    DeleteObject(pen);
+}
+
+/// Live "W × H" readout under the selection (above it when near the screen bottom)
+unsafe fn draw_dimensions_label(hdc: HDC, r: RECT, screen_w: i32, screen_h: i32) {
+    let mut label = format!("{} × {}", r.right - r.left, r.bottom - r.top);
+    if IS_ADJUSTING {
+        label.push_str("  ·  Enter ✓  Esc ✕");
+    }
+    let mut text_w: Vec<u16> = label.encode_utf16().collect();
+
+    let hfont = CreateFontW(15, 0, 0, 0, FW_SEMIBOLD.0 as i32, 0, 0, 0, DEFAULT_CHARSET.0 as u32, OUT_DEFAULT_PRECIS.0 as u32, CLIP_DEFAULT_PRECIS.0 as u32, CLEARTYPE_QUALITY.0 as u32, (VARIABLE_PITCH.0 | FF_SWISS.0) as u32, w!("Segoe UI"));
+    let old_font = SelectObject(hdc, hfont);
+
+    let mut measure = RECT::default();
+    DrawTextW(hdc, &mut text_w, &mut measure, DT_CALCRECT | DT_SINGLELINE);
+    let box_w = (measure.right - measure.left) + 12;
+    let box_h = (measure.bottom - measure.top) + 6;
+
+    let x = r.left.clamp(0, (screen_w - box_w).max(0));
+    let y = if r.bottom + 6 + box_h <= screen_h { r.bottom + 6 } else { (r.top - 6 - box_h).max(0) };
+    let mut box_rect = RECT { left: x, top: y, right: x + box_w, bottom: y + box_h };
+
+    let bg = CreateSolidBrush(COLORREF(0x00202020));
+    FillRect(hdc, &box_rect, bg);
+    DeleteObject(bg);
+
+    SetBkMode(hdc, TRANSPARENT);
+    SetTextColor(hdc, COLORREF(0x00FFFFFF));
+    DrawTextW(hdc, &mut text_w, &mut box_rect, DT_CENTER | DT_VCENTER | DT_SINGLELINE);
+
+    SelectObject(hdc, old_font);
+    DeleteObject(hfont);
+}
+
+fn is_key_down(vk: u16) -> bool {
+    unsafe { GetKeyState(vk as i32) < 0 }
+}
+
+fn nudge_delta(key: u16) -> Option<(i32, i32)> {
+    match key {
+        k if k == VK_LEFT.0 => Some((-1, 0)),
+        k if k == VK_RIGHT.0 => Some((1, 0)),
+        k if k == VK_UP.0 => Some((0, -1)),
+        k if k == VK_DOWN.0 => Some((0, 1)),
+        _ => None,
+    }
+}
+
+/// `pt` moved inside `bounds` (right/bottom edges exclusive)
+fn clamp_to(pt: POINT, bounds: RECT) -> POINT {
+    POINT {
+        x: pt.x.clamp(bounds.left, (bounds.right - 1).max(bounds.left)),
+        y: pt.y.clamp(bounds.top, (bounds.bottom - 1).max(bounds.top)),
+    }
+}
+
+/// Width/height ratio of a drag vector; square when the selection is still degenerate
+fn aspect_ratio(dx: i32, dy: i32) -> f32 {
+    if dx.abs() < 2 || dy.abs() < 2 { 1.0 } else { dx.abs() as f32 / dy.abs() as f32 }
+}
+
+/// Constrain a drag vector to `ratio` (w/h), keeping its direction and the larger extent
+fn lock_aspect(dx: i32, dy: i32, ratio: f32) -> (i32, i32) {
+    let (w, h) = (dx.abs() as f32, dy.abs() as f32);
+    let (w, h) = if w / ratio > h { (w, w / ratio) } else { (h * ratio, h) };
+    let sign = |v: i32| if v < 0 { -1 } else { 1 };
+    (w.round() as i32 * sign(dx), h.round() as i32 * sign(dy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lock_aspect() {
+        // 16:9 lock, wide drag keeps the width
+        assert_eq!(lock_aspect(160, 20, 16.0 / 9.0), (160, 90));
+        // Tall drag keeps the height, direction preserved
+        assert_eq!(lock_aspect(-10, -90, 16.0 / 9.0), (-160, -90));
+        // Degenerate start locks to square
+        assert_eq!(aspect_ratio(0, 0), 1.0);
+        assert_eq!(lock_aspect(50, 30, aspect_ratio(1, 1)), (50, 50));
+    }
+
+    #[test]
+    fn test_clamp_to() {
+        // Second monitor left of the primary
+        let bounds = RECT { left: -1920, top: 0, right: 0, bottom: 1080 };
+        assert_eq!(clamp_to(POINT { x: -100, y: 500 }, bounds), POINT { x: -100, y: 500 });
+        assert_eq!(clamp_to(POINT { x: 300, y: -20 }, bounds), POINT { x: -1, y: 0 });
+        assert_eq!(clamp_to(POINT { x: -5000, y: 2000 }, bounds), POINT { x: -1920, y: 1079 });
+    }
+}
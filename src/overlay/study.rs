@@ -0,0 +1,73 @@
+//! Study Layout
+//!
+//! For language study: `Preset.study_layout` asks the model for JSON with the text as
+//! written, its reading (furigana, pinyin or romanization) and a translation. The result
+//! window shows the three as sections in their own styles, and history keeps them apart
+//! for the Anki export. An answer that doesn't parse is shown as it came. Study answers
+//! are never streamed, so the window doesn't show the JSON building up.
+
+use crate::history::StudyFields;
+
+const STUDY_INSTRUCTION: &str = "\n\nAnswer only with a JSON object with three string fields: \"original\" (the text exactly as written in the image), \"reading\" (how it is read: furigana in hiragana for Japanese, pinyin with tone marks for Chinese, romanization for other non-Latin scripts, empty for Latin scripts) and \"translation\" (the translation). Do not wrap it in code fences or add anything else.";
+
+/// Whether `preset` uses the study layout. Chat answers and live captions never do.
+pub fn enabled_for(preset: &crate::config::Preset) -> bool {
+    let chat = preset.preset_type == "chat" || preset.enable_chat_mode;
+    preset.study_layout && preset.preset_type == "image" && !chat && !preset.live_mode
+}
+
+pub fn push_instruction(prompt: &mut String) {
+    prompt.push_str(STUDY_INSTRUCTION);
+}
+
+/// The fields of a study answer: a JSON object, also inside code fences or after a lead-in line
+pub fn parse_study_card(text: &str) -> Option<StudyFields> {
+    let (start, end) = (text.find('{')?, text.rfind('}')?);
+    if end < start {
+        return None;
+    }
+    let card: StudyFields = serde_json::from_str(&text[start..=end]).ok()?;
+    let card = StudyFields {
+        original: card.original.trim().to_string(),
+        reading: card.reading.trim().to_string(),
+        translation: card.translation.trim().to_string(),
+    };
+    (!card.original.is_empty() || !card.translation.is_empty()).then_some(card)
+}
+
+/// The text to show for a study answer, with its fields when it parsed
+pub fn apply(text: String) -> (String, Option<StudyFields>) {
+    match parse_study_card(&text) {
+        Some(card) => (display_text(&card), Some(card)),
+        None => (text, None),
+    }
+}
+
+/// The sections as plain text (what the window copies and history searches)
+pub fn display_text(card: &StudyFields) -> String {
+    [&card.original, &card.reading, &card.translation]
+        .into_iter()
+        .filter(|s| !s.is_empty())
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_study_card() {
+        let card = parse_study_card("{\"original\": \"猫が好き\", \"reading\": \"ねこがすき\", \"translation\": \"I like cats\"}").unwrap();
+        assert_eq!(card.reading, "ねこがすき");
+        assert_eq!(display_text(&card), "猫が好き\n\nねこがすき\n\nI like cats");
+        // Fenced, after a lead-in, with a field missing
+        let card = parse_study_card("Here it is:\n```json\n{\"original\": \"Hola\", \"translation\": \"Hello\"}\n```").unwrap();
+        assert_eq!(display_text(&card), "Hola\n\nHello");
+        // Not JSON, or JSON without the fields: shown as it came
+        assert_eq!(parse_study_card("猫が好き"), None);
+        assert_eq!(parse_study_card("{\"text\": \"Hola\"}"), None);
+        assert_eq!(parse_study_card("{\"original\": [1, 2]}"), None);
+    }
+}
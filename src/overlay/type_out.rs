@@ -0,0 +1,180 @@
+//! Type Out Module
+//!
+//! `OutputMode::TypeOut`: instead of the clipboard, a finished result is typed into the
+//! window that was focused when the hotkey was pressed, one character at a time as
+//! Unicode keystrokes. For games and remote sessions that block paste. Pressing Escape
+//! or moving the mouse stops it; long results ask first.
+
+use windows::Win32::Foundation::*;
+use windows::Win32::UI::Input::KeyboardAndMouse::*;
+use windows::Win32::UI::WindowsAndMessaging::*;
+use windows::core::PCWSTR;
+use std::sync::atomic::{AtomicBool, AtomicIsize, Ordering};
+use std::time::Duration;
+
+/// Results longer than this (in characters) are confirmed before typing
+pub const CONFIRM_ABOVE_CHARS: usize = 500;
+
+/// Cursor movement (px) that counts as the user taking over
+const MOUSE_ABORT_DISTANCE: i32 = 8;
+
+/// Time for the restored window to take focus before the first keystroke
+const FOCUS_SETTLE: Duration = Duration::from_millis(80);
+
+/// Window focused when the last hotkey was pressed
+static TARGET_WINDOW: AtomicIsize = AtomicIsize::new(0);
+static BUSY: AtomicBool = AtomicBool::new(false);
+/// Set when Escape reaches one of our keyboard hooks instead of the key state
+static ABORT: AtomicBool = AtomicBool::new(false);
+
+/// Remember the foreground window, before an overlay of ours takes focus
+pub fn remember_foreground() {
+    let hwnd = unsafe { GetForegroundWindow() };
+    TARGET_WINDOW.store(hwnd.0, Ordering::SeqCst);
+}
+
+/// Bring back the window `remember_foreground` saw. False if it's gone or Windows
+/// refused to focus it (typing would then go somewhere else).
+fn restore_foreground() -> bool {
+    let hwnd = HWND(TARGET_WINDOW.load(Ordering::SeqCst));
+    unsafe {
+        if hwnd.0 == 0 || !IsWindow(hwnd).as_bool() {
+            return false;
+        }
+        if GetForegroundWindow() != hwnd {
+            SetForegroundWindow(hwnd);
+            std::thread::sleep(FOCUS_SETTLE);
+        }
+        GetForegroundWindow() == hwnd
+    }
+}
+
+/// One key press of the typed text
+#[derive(Debug, PartialEq)]
+enum Keystroke {
+    Enter,
+    Tab,
+    Unicode(Vec<u16>), // Two units for characters outside the BMP
+}
+
+/// Line breaks (any style) become Enter, everything else Unicode input
+fn keystrokes(text: &str) -> Vec<Keystroke> {
+    text.replace("\r\n", "\n")
+        .chars()
+        .filter(|&c| c != '\r')
+        .map(|c| match c {
+            '\n' => Keystroke::Enter,
+            '\t' => Keystroke::Tab,
+            c => Keystroke::Unicode(c.encode_utf16(&mut [0; 2]).to_vec()),
+        })
+        .collect()
+}
+
+fn send(keystroke: &Keystroke) {
+    let input = |vk: VIRTUAL_KEY, scan: u16, flags: KEYBD_EVENT_FLAGS| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT { wVk: vk, wScan: scan, dwFlags: flags, time: 0, dwExtraInfo: 0 },
+        },
+    };
+    let inputs: Vec<INPUT> = match keystroke {
+        Keystroke::Enter => vec![input(VK_RETURN, 0, KEYBD_EVENT_FLAGS(0)), input(VK_RETURN, 0, KEYEVENTF_KEYUP)],
+        Keystroke::Tab => vec![input(VK_TAB, 0, KEYBD_EVENT_FLAGS(0)), input(VK_TAB, 0, KEYEVENTF_KEYUP)],
+        Keystroke::Unicode(units) => units.iter()
+            .flat_map(|&u| [input(VIRTUAL_KEY(0), u, KEYEVENTF_UNICODE), input(VIRTUAL_KEY(0), u, KEYEVENTF_UNICODE | KEYEVENTF_KEYUP)])
+            .collect(),
+    };
+    unsafe { SendInput(&inputs, std::mem::size_of::<INPUT>() as i32); }
+}
+
+fn cursor_pos() -> POINT {
+    let mut pt = POINT::default();
+    unsafe { GetCursorPos(&mut pt); }
+    pt
+}
+
+fn moved_away(from: POINT, to: POINT) -> bool {
+    (from.x - to.x).abs() > MOUSE_ABORT_DISTANCE || (from.y - to.y).abs() > MOUSE_ABORT_DISTANCE
+}
+
+/// Stop the typing in progress. Called from the Escape hook of the result windows,
+/// which swallows the key so `escape_pressed` never sees it. False when not typing.
+pub fn request_abort() -> bool {
+    if !BUSY.load(Ordering::SeqCst) {
+        return false;
+    }
+    ABORT.store(true, Ordering::SeqCst);
+    true
+}
+
+fn escape_pressed() -> bool {
+    unsafe { GetAsyncKeyState(VK_ESCAPE.0 as i32) as u16 & 0x8000 != 0 }
+}
+
+fn confirm_message(lang: &str, chars: usize) -> String {
+    match lang {
+        "vi" => format!("Gõ {} ký tự vào cửa sổ đang dùng?\n\nNhấn Esc hoặc di chuột để dừng.", chars),
+        "ko" => format!("활성 창에 {}자를 입력할까요?\n\nEsc를 누르거나 마우스를 움직이면 중지됩니다.", chars),
+        _ => format!("Type {} characters into the focused window?\n\nPress Escape or move the mouse to stop.", chars),
+    }
+}
+
+fn confirm(lang: &str, chars: usize) -> bool {
+    let message: Vec<u16> = confirm_message(lang, chars).encode_utf16().chain(std::iter::once(0)).collect();
+    let title: Vec<u16> = "XT Screen Translator".encode_utf16().chain(std::iter::once(0)).collect();
+    let choice = unsafe {
+        MessageBoxW(None, PCWSTR(message.as_ptr()), PCWSTR(title.as_ptr()), MB_ICONQUESTION | MB_YESNO | MB_TOPMOST | MB_SETFOREGROUND)
+    };
+    choice == IDYES
+}
+
+/// Type `text` into the remembered window, waiting `delay` between characters.
+/// Blocks until done or aborted, so call it off the UI thread.
+pub fn type_text(text: &str, delay: Duration) {
+    let keys = keystrokes(text.trim_end());
+    if keys.is_empty() || BUSY.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    ABORT.store(false, Ordering::SeqCst);
+    let lang = crate::APP.lock().map(|app| app.config.ui_language.clone()).unwrap_or_default();
+
+    if keys.len() <= CONFIRM_ABOVE_CHARS || confirm(&lang, keys.len()) {
+        if restore_foreground() {
+            log::info!("Type out: {} keystrokes", keys.len());
+            let start = cursor_pos();
+            for (typed, key) in keys.iter().enumerate() {
+                if ABORT.load(Ordering::SeqCst) || escape_pressed() || moved_away(start, cursor_pos()) {
+                    log::info!("Type out: stopped after {} of {} keystrokes", typed, keys.len());
+                    break;
+                }
+                send(key);
+                std::thread::sleep(delay);
+            }
+        } else {
+            log::warn!("Type out: the window focused at the hotkey is gone or can't be focused");
+        }
+    }
+    BUSY.store(false, Ordering::SeqCst);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystrokes() {
+        assert_eq!(keystrokes("a\r\nb\tc"), vec![
+            Keystroke::Unicode(vec![0x61]),
+            Keystroke::Enter,
+            Keystroke::Unicode(vec![0x62]),
+            Keystroke::Tab,
+            Keystroke::Unicode(vec![0x63]),
+        ]);
+        // Outside the BMP: one keystroke, a surrogate pair
+        assert_eq!(keystrokes("😀"), vec![Keystroke::Unicode(vec![0xD83D, 0xDE00])]);
+        assert_eq!(keystrokes("ệ").len(), 1);
+
+        assert!(!moved_away(POINT { x: 10, y: 10 }, POINT { x: 14, y: 6 }));
+        assert!(moved_away(POINT { x: 10, y: 10 }, POINT { x: 10, y: 30 }));
+    }
+}
@@ -2,14 +2,17 @@ use windows::Win32::Foundation::*;
 use windows::Win32::System::DataExchange::*;
 use windows::Win32::System::Memory::*;
 use windows::Win32::Graphics::Gdi::*;
+use windows::core::w;
 use image::{ImageBuffer, Rgba};
+use crate::config::CopyFormat;
 
 pub fn to_wstring(s: &str) -> Vec<u16> {
     s.encode_utf16().chain(std::iter::once(0)).collect()
 }
 
 // --- CLIPBOARD SUPPORT ---
-pub fn copy_to_clipboard(text: &str, hwnd: HWND) {
+pub fn copy_to_clipboard(text: &str, hwnd: HWND) -> bool {
+    let mut copied = false;
     unsafe {
         if OpenClipboard(hwnd).as_bool() {
             EmptyClipboard();
@@ -26,16 +29,164 @@ pub fn copy_to_clipboard(text: &str, hwnd: HWND) {
                 
                 // Set clipboard data (CF_UNICODETEXT = 13)
                 let h_mem_handle = HANDLE(h_mem.0);
-                let _ = SetClipboardData(13u32, h_mem_handle);
+                copied = SetClipboardData(13u32, h_mem_handle).is_ok();
             }
             
             CloseClipboard();
         }
     }
+    if copied {
+        super::recent_copies::remember(text);
+    }
+    copied
 }
 
 /// Copies an RGBA image to the Windows Clipboard using CF_DIB format.
 pub fn copy_image_to_clipboard(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> bool {
+    unsafe {
+        if !OpenClipboard(HWND(0)).as_bool() {
+            log::error!("Failed to open clipboard for image copy");
+            return false;
+        }
+        EmptyClipboard();
+        
+        // CF_DIB = 8
+        let ok = set_clipboard_bytes(8u32, &build_dib(image));
+        CloseClipboard();
+        if ok {
+            log::info!("Image copied to clipboard ({}x{})", image.width(), image.height());
+        }
+        ok
+    }
+}
+
+/// Rich copy: plain text plus an HTML fragment (CF_HTML) so Word/Docs keep paragraphs,
+/// bullets and bold; the source thumbnail goes along as CF_DIB when there is one.
+pub fn copy_rich_to_clipboard(text: &str, image: Option<&ImageBuffer<Rgba<u8>, Vec<u8>>>, hwnd: HWND) -> bool {
+    unsafe {
+        if !OpenClipboard(hwnd).as_bool() {
+            log::error!("Failed to open clipboard for rich copy");
+            return false;
+        }
+        EmptyClipboard();
+
+        // CF_UNICODETEXT = 13
+        let wide: Vec<u8> = text.encode_utf16().chain(std::iter::once(0)).flat_map(|c| c.to_le_bytes()).collect();
+        let mut ok = set_clipboard_bytes(13u32, &wide);
+
+        let cf_html = RegisterClipboardFormatW(w!("HTML Format"));
+        if cf_html != 0 {
+            let mut html = build_cf_html(&text_to_html(text)).into_bytes();
+            html.push(0);
+            ok &= set_clipboard_bytes(cf_html, &html);
+        }
+
+        if let Some(img) = image {
+            ok &= set_clipboard_bytes(8u32, &build_dib(img));
+        }
+
+        CloseClipboard();
+        ok
+    }
+}
+
+// GDI-handle formats can't be copied as raw bytes: CF_BITMAP, CF_METAFILEPICT, CF_PALETTE, CF_ENHMETAFILE
+const NON_MEMORY_FORMATS: [u32; 4] = [2, 3, 9, 14];
+
+/// Raw bytes of every memory-backed format on the clipboard, for `restore_clipboard`
+pub fn snapshot_clipboard() -> Vec<(u32, Vec<u8>)> {
+    let mut formats = Vec::new();
+    unsafe {
+        if !OpenClipboard(HWND(0)).as_bool() {
+            log::error!("Failed to open clipboard for snapshot");
+            return formats;
+        }
+        let mut format = EnumClipboardFormats(0);
+        while format != 0 {
+            if !NON_MEMORY_FORMATS.contains(&format) && !(0x80..=0x8E).contains(&format) {
+                if let Ok(handle) = GetClipboardData(format) {
+                    let h_mem = HGLOBAL(handle.0);
+                    let size = GlobalSize(h_mem);
+                    let ptr = GlobalLock(h_mem) as *const u8;
+                    if !ptr.is_null() {
+                        formats.push((format, std::slice::from_raw_parts(ptr, size).to_vec()));
+                        GlobalUnlock(h_mem);
+                    }
+                }
+            }
+            format = EnumClipboardFormats(format);
+        }
+        CloseClipboard();
+    }
+    formats
+}
+
+/// Put a `snapshot_clipboard` result back (an empty snapshot leaves the clipboard empty)
+pub fn restore_clipboard(formats: &[(u32, Vec<u8>)]) {
+    unsafe {
+        if !OpenClipboard(HWND(0)).as_bool() {
+            log::error!("Failed to open clipboard for restore");
+            return;
+        }
+        EmptyClipboard();
+        for (format, data) in formats {
+            set_clipboard_bytes(*format, data);
+        }
+        CloseClipboard();
+    }
+}
+
+/// Current clipboard text (CF_UNICODETEXT), if any
+pub fn get_clipboard_text() -> Option<String> {
+    unsafe {
+        if !OpenClipboard(HWND(0)).as_bool() {
+            return None;
+        }
+        let mut text = None;
+        if let Ok(handle) = GetClipboardData(13u32) {
+            let h_mem = HGLOBAL(handle.0);
+            let ptr = GlobalLock(h_mem) as *const u16;
+            if !ptr.is_null() {
+                let max = GlobalSize(h_mem) / 2;
+                let len = (0..max).take_while(|&i| *ptr.add(i) != 0).count();
+                text = Some(String::from_utf16_lossy(std::slice::from_raw_parts(ptr, len)));
+                GlobalUnlock(h_mem);
+            }
+        }
+        CloseClipboard();
+        text
+    }
+}
+
+/// Hand a byte buffer to the (already open) clipboard. The clipboard owns the memory on success.
+unsafe fn set_clipboard_bytes(format: u32, data: &[u8]) -> bool {
+    let h_mem = match GlobalAlloc(GMEM_MOVEABLE, data.len()) {
+        Ok(h) => h,
+        Err(e) => {
+            log::error!("GlobalAlloc failed: {:?}", e);
+            return false;
+        }
+    };
+
+    let ptr = GlobalLock(h_mem) as *mut u8;
+    if ptr.is_null() {
+        log::error!("GlobalLock returned null");
+        let _ = GlobalFree(h_mem);
+        return false;
+    }
+    std::ptr::copy_nonoverlapping(data.as_ptr(), ptr, data.len());
+    GlobalUnlock(h_mem);
+
+    if SetClipboardData(format, HANDLE(h_mem.0)).is_err() {
+        log::error!("SetClipboardData failed (format {})", format);
+        let _ = GlobalFree(h_mem);
+        return false;
+    }
+    true
+}
+
+/// BITMAPINFOHEADER + bottom-up BGRA rows, as CF_DIB expects
+fn build_dib(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> Vec<u8> {
     let width = image.width() as i32;
     let height = image.height() as i32;
     
@@ -46,94 +197,180 @@ pub fn copy_image_to_clipboard(image: &ImageBuffer<Rgba<u8>, Vec<u8>>) -> bool {
     // BITMAPINFOHEADER is 40 bytes
     let header_size = std::mem::size_of::<BITMAPINFOHEADER>();
     let pixel_data_size = padded_row_size * height as usize;
-    let total_size = header_size + pixel_data_size;
     
+    let header = BITMAPINFOHEADER {
+        biSize: header_size as u32,
+        biWidth: width,
+        biHeight: height, // Positive = bottom-up DIB
+        biPlanes: 1,
+        biBitCount: 32,
+        biCompression: BI_RGB.0 as u32,
+        biSizeImage: pixel_data_size as u32,
+        biXPelsPerMeter: 0,
+        biYPelsPerMeter: 0,
+        biClrUsed: 0,
+        biClrImportant: 0,
+    };
+    let mut dib = vec![0u8; header_size + pixel_data_size];
     unsafe {
-        if !OpenClipboard(HWND(0)).as_bool() {
-            log::error!("Failed to open clipboard for image copy");
-            return false;
+        std::ptr::copy_nonoverlapping(&header as *const _ as *const u8, dib.as_mut_ptr(), header_size);
+    }
+    
+    // Write pixel data (BGRA, bottom-up)
+    let pixels = &mut dib[header_size..];
+    for y in 0..height {
+        let src_y = (height - 1 - y) as u32; // Flip vertically for bottom-up
+        for x in 0..width {
+            let pixel = image.get_pixel(x as u32, src_y);
+            let offset = (y as usize * padded_row_size) + (x as usize * 4);
+            // RGBA -> BGRA
+            pixels[offset] = pixel[2];     // B
+            pixels[offset + 1] = pixel[1]; // G
+            pixels[offset + 2] = pixel[0]; // R
+            pixels[offset + 3] = pixel[3]; // A
         }
-        EmptyClipboard();
-        
-        let h_mem = match GlobalAlloc(GMEM_MOVEABLE, total_size) {
-            Ok(h) => h,
-            Err(e) => {
-                log::error!("GlobalAlloc failed: {:?}", e);
-                CloseClipboard();
-                return false;
-            }
-        };
-        
-        let ptr = GlobalLock(h_mem) as *mut u8;
-        if ptr.is_null() {
-            log::error!("GlobalLock returned null");
-            let _ = GlobalFree(h_mem);
-            CloseClipboard();
-            return false;
+    }
+    dib
+}
+
+/// Wrap an HTML fragment in the CF_HTML envelope. The offsets are byte offsets into the
+/// UTF-8 payload, zero-padded to a fixed width so the header length doesn't depend on them.
+pub fn build_cf_html(fragment: &str) -> String {
+    const HEADER_TEMPLATE: &str = "Version:0.9\r\nStartHTML:0000000000\r\nEndHTML:0000000000\r\nStartFragment:0000000000\r\nEndFragment:0000000000\r\n";
+    const PREFIX: &str = "<html><body>\r\n<!--StartFragment-->";
+    const SUFFIX: &str = "<!--EndFragment-->\r\n</body></html>";
+
+    let start_html = HEADER_TEMPLATE.len();
+    let start_fragment = start_html + PREFIX.len();
+    let end_fragment = start_fragment + fragment.len();
+    let end_html = end_fragment + SUFFIX.len();
+
+    format!(
+        "Version:0.9\r\nStartHTML:{:010}\r\nEndHTML:{:010}\r\nStartFragment:{:010}\r\nEndFragment:{:010}\r\n{}{}{}",
+        start_html, end_html, start_fragment, end_fragment, PREFIX, fragment, SUFFIX
+    )
+}
+
+/// `text` wrapped the way `format` copies it. `source` is the header line of
+/// `CopyFormat::SourceHeader` (see `copy_source`).
+pub fn format_copy(text: &str, format: CopyFormat, source: &str) -> String {
+    let trimmed = text.trim_end();
+    match format {
+        CopyFormat::Plain => text.to_string(),
+        CopyFormat::CodeBlock => {
+            // The fence must be longer than any backtick run inside, or that run would close it
+            let longest_run = trimmed.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+            let fence = "`".repeat(longest_run.max(2) + 1);
+            format!("{}\n{}\n{}", fence, trimmed, fence)
         }
-        
-        // Write BITMAPINFOHEADER
-        let header = BITMAPINFOHEADER {
-            biSize: header_size as u32,
-            biWidth: width,
-            biHeight: height, // Positive = bottom-up DIB
-            biPlanes: 1,
-            biBitCount: 32,
-            biCompression: BI_RGB.0 as u32,
-            biSizeImage: pixel_data_size as u32,
-            biXPelsPerMeter: 0,
-            biYPelsPerMeter: 0,
-            biClrUsed: 0,
-            biClrImportant: 0,
-        };
-        std::ptr::copy_nonoverlapping(&header as *const _ as *const u8, ptr, header_size);
-        
-        // Write pixel data (BGRA, bottom-up)
-        let pixel_ptr = ptr.add(header_size);
-        for y in 0..height {
-            let src_y = (height - 1 - y) as u32; // Flip vertically for bottom-up
-            for x in 0..width {
-                let pixel = image.get_pixel(x as u32, src_y);
-                let offset = (y as usize * padded_row_size) + (x as usize * 4);
-                // RGBA -> BGRA
-                *pixel_ptr.add(offset) = pixel[2];     // B
-                *pixel_ptr.add(offset + 1) = pixel[1]; // G
-                *pixel_ptr.add(offset + 2) = pixel[0]; // R
-                *pixel_ptr.add(offset + 3) = pixel[3]; // A
-            }
+        // Discord and Slack need the space after '>' on blank lines too
+        CopyFormat::Quote => trimmed.lines().map(|line| format!("> {}", line)).collect::<Vec<_>>().join("\n"),
+        CopyFormat::SourceHeader => format!("{}\n\n{}", source, trimmed),
+    }
+}
+
+/// Header line for a result of `preset_name` finished now, like the history's
+pub fn copy_source(preset_name: &str) -> String {
+    format!("[{}] {}", preset_name, chrono::Local::now().format("%d/%m/%Y %H:%M"))
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Basic Markdown-ish formatting for pasting into documents: paragraphs, line breaks,
+/// `-`/`*` bullets, `#` headings and **bold**
+pub fn text_to_html(text: &str) -> String {
+    let mut html = String::new();
+    let mut in_list = false;
+    let mut paragraph: Vec<String> = Vec::new();
+
+    let flush_paragraph = |html: &mut String, paragraph: &mut Vec<String>| {
+        if !paragraph.is_empty() {
+            html.push_str(&format!("<p>{}</p>", paragraph.join("<br>")));
+            paragraph.clear();
         }
-        
-        GlobalUnlock(h_mem);
-        
-        // CF_DIB = 8
-        let h_mem_handle = HANDLE(h_mem.0);
-        if SetClipboardData(8u32, h_mem_handle).is_err() {
-            log::error!("SetClipboardData failed");
-            let _ = GlobalFree(h_mem);
-            CloseClipboard();
-            return false;
+    };
+
+    for line in text.lines() {
+        let trimmed = line.trim();
+        let bullet = trimmed.strip_prefix("- ").or_else(|| trimmed.strip_prefix("* "));
+
+        if bullet.is_none() && in_list {
+            html.push_str("</ul>");
+            in_list = false;
         }
-        
-        CloseClipboard();
-        log::info!("Image copied to clipboard ({}x{})", width, height);
-        true
+
+        if trimmed.is_empty() {
+            flush_paragraph(&mut html, &mut paragraph);
+        } else if let Some(item) = bullet {
+            flush_paragraph(&mut html, &mut paragraph);
+            if !in_list {
+                html.push_str("<ul>");
+                in_list = true;
+            }
+            html.push_str(&format!("<li>{}</li>", inline_html(item)));
+        } else if let Some(heading) = trimmed.strip_prefix("# ").or_else(|| trimmed.strip_prefix("## ")).or_else(|| trimmed.strip_prefix("### ")) {
+            flush_paragraph(&mut html, &mut paragraph);
+            html.push_str(&format!("<h3>{}</h3>", inline_html(heading)));
+        } else {
+            paragraph.push(inline_html(line.trim_end()));
+        }
+    }
+    if in_list { html.push_str("</ul>"); }
+    flush_paragraph(&mut html, &mut paragraph);
+    html
+}
+
+/// Escape a line and turn balanced **pairs** into <b>
+fn inline_html(line: &str) -> String {
+    let escaped = escape_html(line);
+    let parts: Vec<&str> = escaped.split("**").collect();
+    if parts.len() < 3 || parts.len() % 2 == 0 {
+        return escaped; // No pairs, or an unbalanced marker: leave as-is
     }
+    parts.iter().enumerate()
+        .map(|(i, p)| if i % 2 == 1 { format!("<b>{}</b>", p) } else { p.to_string() })
+        .collect()
 }
 
-pub fn get_error_message(error: &str, lang: &str) -> String {
-    match error {
-        "NO_API_KEY" => {
+/// Localized text for a failed request; provider errors are told apart by `ApiError`
+pub fn get_error_message(error: &anyhow::Error, lang: &str) -> String {
+    use crate::api_error::ApiError;
+    match ApiError::of(error) {
+        Some(ApiError::NoApiKey) => {
             match lang {
                 "vi" => "Bạn chưa nhập API key!".to_string(),
                 _ => "You haven't entered an API key!".to_string(),
             }
         }
-        "INVALID_API_KEY" => {
+        Some(ApiError::InvalidKey) => {
             match lang {
                 "vi" => "API key không hợp lệ!".to_string(),
                 _ => "Invalid API key!".to_string(),
             }
         }
+        Some(ApiError::RateLimited { retry_after }) => {
+            let wait = retry_after.map(|d| d.as_secs().max(1));
+            match (lang, wait) {
+                ("vi", Some(secs)) => format!("Quá giới hạn yêu cầu, thử lại sau {} giây.", secs),
+                ("vi", None) => "Quá giới hạn yêu cầu, hãy thử lại sau.".to_string(),
+                (_, Some(secs)) => format!("Rate limited, try again in {} s.", secs),
+                (_, None) => "Rate limited, try again in a moment.".to_string(),
+            }
+        }
+        Some(ApiError::QuotaExceeded) => {
+            match lang {
+                "vi" => "Đã hết hạn mức hoặc hết credit của API key.".to_string(),
+                _ => "The API key's quota or credits are used up.".to_string(),
+            }
+        }
+        Some(ApiError::EmptyResponse) => {
+            match lang {
+                "vi" => "Mô hình không trả về nội dung.".to_string(),
+                _ => "The model returned no text.".to_string(),
+            }
+        }
         _ => {
             match lang {
                 "vi" => format!("Lỗi: {}", error),
@@ -143,6 +380,15 @@ pub fn get_error_message(error: &str, lang: &str) -> String {
     }
 }
 
+/// Shown on an error result window, which opens the API key settings when clicked
+pub fn get_error_hint(lang: &str) -> &'static str {
+    match lang {
+        "vi" => "Nhấn để mở cài đặt API key",
+        "ko" => "클릭하여 API 키 설정 열기",
+        _ => "Click to open API key settings",
+    }
+}
+
 /// Convert markdown to cleaner plain text for display
 /// Removes formatting symbols but preserves structure
 pub fn clean_markdown_for_display(text: &str) -> String {
@@ -203,3 +449,39 @@ pub fn clean_markdown_for_display(text: &str) -> String {
     
     final_result.trim().to_string()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_cf_html_offsets() {
+        let fragment = "<p>Xin chào <b>thế giới</b></p>"; // Multi-byte chars: offsets must count bytes
+        let cf = build_cf_html(fragment);
+        let field = |name: &str| -> usize {
+            let start = cf.find(name).unwrap() + name.len();
+            cf[start..start + 10].parse().unwrap()
+        };
+        let bytes = cf.as_bytes();
+        assert!(cf[field("StartHTML:")..].starts_with("<html>"));
+        assert_eq!(field("EndHTML:"), bytes.len());
+        assert_eq!(&cf[field("StartFragment:")..field("EndFragment:")], fragment);
+        assert!(cf[..field("StartFragment:")].ends_with("<!--StartFragment-->"));
+    }
+
+    #[test]
+    fn test_format_copy() {
+        assert_eq!(format_copy("a\nb\n", CopyFormat::Plain, "[P] t"), "a\nb\n");
+        assert_eq!(format_copy("let x = 1;", CopyFormat::CodeBlock, ""), "```\nlet x = 1;\n```");
+        assert_eq!(format_copy("has ``` inside", CopyFormat::CodeBlock, ""), "````\nhas ``` inside\n````");
+        assert_eq!(format_copy("one\n\ntwo", CopyFormat::Quote, ""), "> one\n> \n> two");
+        assert_eq!(format_copy("text", CopyFormat::SourceHeader, "[OCR] 15/10/2026 09:30"), "[OCR] 15/10/2026 09:30\n\ntext");
+    }
+
+    #[test]
+    fn test_text_to_html() {
+        assert_eq!(text_to_html("a < b\nnext\n\nsecond"), "<p>a &lt; b<br>next</p><p>second</p>");
+        assert_eq!(text_to_html("Items:\n- **one**\n- two"), "<p>Items:</p><ul><li><b>one</b></li><li>two</li></ul>");
+        assert_eq!(text_to_html("# Title\nbody **unclosed"), "<h3>Title</h3><p>body **unclosed</p>");
+    }
+}
@@ -0,0 +1,55 @@
+//! Window Context
+//!
+//! Presets with `include_context` tell the model which app the captured text is in:
+//! the title of the foreground window, taken when the hotkey fires (before any overlay
+//! of ours is in front), is added to the vision prompt. It helps with names in games,
+//! manga readers and the like. Opt-in, since it costs tokens on every capture.
+
+use std::sync::Mutex;
+
+/// Longer titles (a whole document path, a browser tab list) are cut
+const MAX_TITLE_CHARS: usize = 120;
+
+lazy_static::lazy_static! {
+    static ref HOTKEY_WINDOW_TITLE: Mutex<Option<String>> = Mutex::new(None);
+}
+
+/// A capture hotkey fired: keep the foreground window's title for its prompt
+pub fn remember() {
+    let title = super::app_blocklist::foreground_window().map(super::follow_window::window_title);
+    *HOTKEY_WINDOW_TITLE.lock().unwrap() = title;
+}
+
+/// The prompt sentence for a window titled `title`; None for an untitled window
+pub fn context_instruction(title: &str) -> Option<String> {
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+    if title.is_empty() {
+        return None;
+    }
+    let title: String = title.chars().take(MAX_TITLE_CHARS).collect();
+    Some(format!("\n\nThis text appears in the app: \"{}\". Use it only to get names and terms right; do not translate or mention it.", title))
+}
+
+/// Add the remembered window's title to `prompt` when `preset` asks for it
+pub fn push_context(prompt: &mut String, preset: &crate::config::Preset) {
+    if !preset.include_context {
+        return;
+    }
+    if let Some(instruction) = HOTKEY_WINDOW_TITLE.lock().unwrap().as_deref().and_then(context_instruction) {
+        prompt.push_str(&instruction);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_context_instruction() {
+        let instruction = context_instruction("  Honkai:\tStar Rail ").unwrap();
+        assert!(instruction.contains("the app: \"Honkai: Star Rail\"."));
+        assert_eq!(context_instruction(" \n"), None);
+        let long = context_instruction(&"a".repeat(500)).unwrap();
+        assert!(long.contains(&format!("\"{}\"", "a".repeat(MAX_TITLE_CHARS))));
+    }
+}
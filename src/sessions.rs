@@ -0,0 +1,98 @@
+//! Sessions Module
+//!
+//! Recordings and live sessions are each stopped by re-pressing the hotkey that started
+//! them, which is easy to get wrong with several presets. Every running session is
+//! listed in `AppState::active_sessions` while its guard lives, so `Config.stop_all_hotkey`
+//! can stop all of them at once, whichever preset started them.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static NEXT_SESSION_ID: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SessionKind {
+    Recording,  // One-shot audio recording: stopping submits what was recorded
+    LiveAudio,
+    LiveVision,
+}
+
+#[derive(Debug, Clone)]
+pub struct ActiveSession {
+    id: u64,
+    pub kind: SessionKind,
+    pub preset_name: String,
+}
+
+/// Lists the session in `AppState::active_sessions` until dropped
+pub struct SessionGuard(u64);
+
+impl Drop for SessionGuard {
+    fn drop(&mut self) {
+        if let Ok(mut app) = crate::APP.lock() {
+            app.active_sessions.retain(|s| s.id != self.0);
+        }
+    }
+}
+
+/// Register a session started by `preset_name` for as long as the guard is held
+pub fn register(kind: SessionKind, preset_name: &str) -> SessionGuard {
+    let id = NEXT_SESSION_ID.fetch_add(1, Ordering::SeqCst);
+    if let Ok(mut app) = crate::APP.lock() {
+        app.active_sessions.push(ActiveSession { id, kind, preset_name: preset_name.to_string() });
+    }
+    SessionGuard(id)
+}
+
+/// The "Stop everything" hotkey: signal every running recording and live session (and
+/// Live Captions) to stop, then say how many were stopped
+pub fn stop_all() {
+    let (sessions, ui_language) = match crate::APP.lock() {
+        Ok(app) => (app.active_sessions.clone(), app.config.ui_language.clone()),
+        Err(_) => return,
+    };
+
+    let mut stopped = sessions.len();
+    if sessions.iter().any(|s| s.kind == SessionKind::LiveVision) {
+        crate::api::VISION_STOP_SIGNAL.store(true, Ordering::SeqCst);
+    }
+    // Also covers a recording overlay whose worker hasn't registered yet
+    if crate::overlay::is_recording_overlay_active() {
+        if !sessions.iter().any(|s| s.kind != SessionKind::LiveVision) {
+            stopped += 1;
+        }
+        crate::overlay::stop_recording_and_submit();
+    }
+    if crate::overlay::is_live_captions_active() {
+        crate::overlay::stop_live_captions_overlay();
+        stopped += 1;
+    }
+
+    for session in &sessions {
+        log::info!("Stop all: stopping {:?} of preset \"{}\"", session.kind, session.preset_name);
+    }
+    crate::overlay::app_blocklist::show_toast(stopped_message(&ui_language, stopped));
+}
+
+fn stopped_message(lang: &str, count: usize) -> String {
+    match (lang, count) {
+        ("vi", 0) => "Không có phiên nào đang chạy".to_string(),
+        ("vi", n) => format!("Đã dừng {} phiên", n),
+        ("ko", 0) => "실행 중인 세션이 없습니다".to_string(),
+        ("ko", n) => format!("세션 {}개를 중지했습니다", n),
+        (_, 0) => "Nothing is running".to_string(),
+        (_, 1) => "Stopped 1 session".to_string(),
+        (_, n) => format!("Stopped {} sessions", n),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stopped_message() {
+        assert_eq!(stopped_message("en", 0), "Nothing is running");
+        assert_eq!(stopped_message("en", 1), "Stopped 1 session");
+        assert_eq!(stopped_message("vi", 2), "Đã dừng 2 phiên");
+    }
+}
@@ -0,0 +1,112 @@
+//! Shutdown Module
+//!
+//! Quitting used to be a bare `process::exit` from the tray menu thread, which could cut
+//! a live session off mid-write and leave the microphone open for a moment. `shutdown()`
+//! signals every live session to stop, gives the threads that own the capture streams
+//! and write the session's history entry a short grace period, then exits.
+//!
+//! History and config are written as they change and the translation cache within a few
+//! seconds, so once the workers are done only the cache's pending save and the log are
+//! left to flush. `model_usage_stats` holds
+//! rate-limit headers that are only meaningful while the app runs, so it isn't saved.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// How long quitting waits for sessions to wind down before exiting anyway
+const GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+static ACTIVE_WORKERS: AtomicUsize = AtomicUsize::new(0);
+static SHUTTING_DOWN: AtomicBool = AtomicBool::new(false);
+
+/// Held by a thread that owns a capture stream or writes history when its session ends
+pub struct WorkerGuard(&'static AtomicUsize);
+
+impl WorkerGuard {
+    fn new(counter: &'static AtomicUsize) -> Self {
+        counter.fetch_add(1, Ordering::SeqCst);
+        Self(counter)
+    }
+}
+
+impl Drop for WorkerGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// Register the current thread as a session worker until the guard is dropped
+pub fn worker() -> WorkerGuard {
+    WorkerGuard::new(&ACTIVE_WORKERS)
+}
+
+/// Wait until no guard on `counter` is alive; false if `timeout` ran out first
+fn wait_for_workers(counter: &AtomicUsize, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while counter.load(Ordering::SeqCst) > 0 {
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(Duration::from_millis(20));
+    }
+    true
+}
+
+/// Stop live sessions and wait for their workers. Safe to call more than once;
+/// only the first call does anything.
+pub fn stop_and_flush() {
+    if SHUTTING_DOWN.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    log::info!("Shutting down");
+
+    // Recording: abort skips the transcription request, stop ends the capture loop
+    crate::overlay::recording::AUDIO_ABORT_SIGNAL.store(true, Ordering::SeqCst);
+    crate::overlay::recording::AUDIO_STOP_SIGNAL.store(true, Ordering::SeqCst);
+    crate::api::VISION_STOP_SIGNAL.store(true, Ordering::SeqCst);
+    if crate::live_captions::LIVE_CAPTIONS_ACTIVE.load(Ordering::SeqCst) {
+        crate::live_captions::stop_live_captions();
+    }
+
+    if !wait_for_workers(&ACTIVE_WORKERS, GRACE_PERIOD) {
+        log::warn!("Shutdown: {} session worker(s) still running, exiting anyway", ACTIVE_WORKERS.load(Ordering::SeqCst));
+    }
+
+    if let Ok(app) = crate::APP.lock() {
+        crate::config::save_config(&app.config);
+    }
+    crate::translation_cache::flush();
+    log::info!("Shutdown complete");
+    log::logger().flush();
+}
+
+/// Quit the app: stop everything, then exit the process
+pub fn shutdown() -> ! {
+    stop_and_flush();
+    std::process::exit(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static TEST_WORKERS: AtomicUsize = AtomicUsize::new(0);
+
+    #[test]
+    fn test_wait_for_workers() {
+        assert!(wait_for_workers(&TEST_WORKERS, Duration::ZERO));
+
+        let guard = WorkerGuard::new(&TEST_WORKERS);
+        assert_eq!(TEST_WORKERS.load(Ordering::SeqCst), 1);
+        assert!(!wait_for_workers(&TEST_WORKERS, Duration::from_millis(50)));
+
+        // A worker finishing within the grace period is waited for
+        let handle = std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            drop(guard);
+        });
+        assert!(wait_for_workers(&TEST_WORKERS, Duration::from_secs(5)));
+        assert_eq!(TEST_WORKERS.load(Ordering::SeqCst), 0);
+        handle.join().unwrap();
+    }
+}
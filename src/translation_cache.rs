@@ -0,0 +1,228 @@
+//! Translation Memory Module
+//!
+//! LRU cache of API outputs keyed by model + prompt + normalized input, so a
+//! subtitle that reappears (or a repeated retranslation) isn't billed twice.
+//! Capacity and disk persistence come from `Config.translation_cache_size` /
+//! `Config.translation_cache_persist`.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+pub const DEFAULT_CACHE_SIZE: usize = 500;
+/// With persistence on, new entries are written to disk at most this often
+const SAVE_DEBOUNCE: Duration = Duration::from_secs(3);
+
+static PERSIST: AtomicBool = AtomicBool::new(false);
+static SAVE_PENDING: AtomicBool = AtomicBool::new(false);
+static HITS: AtomicU64 = AtomicU64::new(0);
+
+lazy_static::lazy_static! {
+    static ref CACHE: Mutex<LruCache> = Mutex::new(LruCache::new(DEFAULT_CACHE_SIZE));
+}
+
+/// Minimal LRU: `order` holds keys from least to most recently used
+pub struct LruCache {
+    capacity: usize,
+    map: HashMap<u64, String>,
+    order: VecDeque<u64>,
+}
+
+impl LruCache {
+    pub fn new(capacity: usize) -> Self {
+        Self { capacity, map: HashMap::new(), order: VecDeque::new() }
+    }
+
+    pub fn get(&mut self, key: u64) -> Option<String> {
+        let value = self.map.get(&key)?.clone();
+        self.touch(key);
+        Some(value)
+    }
+
+    pub fn put(&mut self, key: u64, value: String) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.map.insert(key, value).is_some() {
+            self.touch(key);
+        } else {
+            self.order.push_back(key);
+        }
+        self.shrink();
+    }
+
+    pub fn set_capacity(&mut self, capacity: usize) {
+        self.capacity = capacity;
+        self.shrink();
+    }
+
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.order.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    fn touch(&mut self, key: u64) {
+        if let Some(pos) = self.order.iter().position(|k| *k == key) {
+            self.order.remove(pos);
+        }
+        self.order.push_back(key);
+    }
+
+    fn shrink(&mut self) {
+        while self.map.len() > self.capacity {
+            match self.order.pop_front() {
+                Some(oldest) => { self.map.remove(&oldest); }
+                None => break,
+            }
+        }
+    }
+}
+
+// DefaultHasher::new() is deterministic, so keys stay valid across runs for the
+// disk cache (a toolchain change can only cause misses, never wrong hits).
+
+/// Key for a text request. Whitespace differences in the input don't matter.
+pub fn text_key(model: &str, prompt: &str, input: &str) -> u64 {
+    let normalized = input.split_whitespace().collect::<Vec<_>>().join(" ");
+    let mut hasher = DefaultHasher::new();
+    (model, prompt, normalized).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Key for an image request (exact pixels)
+pub fn image_key(model: &str, prompt: &str, image: &image::ImageBuffer<image::Rgba<u8>, Vec<u8>>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (model, prompt, image.width(), image.height()).hash(&mut hasher);
+    image.as_raw().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Cached output for `key`, counting the hit
+pub fn lookup(key: u64) -> Option<String> {
+    let hit = CACHE.lock().ok()?.get(key);
+    if hit.is_some() {
+        HITS.fetch_add(1, Ordering::Relaxed);
+    }
+    hit
+}
+
+pub fn store(key: u64, output: &str) {
+    if output.trim().is_empty() {
+        return;
+    }
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.put(key, output.to_string());
+    }
+    if PERSIST.load(Ordering::SeqCst) && !SAVE_PENDING.swap(true, Ordering::SeqCst) {
+        // One write for every entry stored in the meantime
+        std::thread::spawn(|| {
+            std::thread::sleep(SAVE_DEBOUNCE);
+            flush();
+        });
+    }
+}
+
+/// Write a pending save now (debounced saves, and on quit)
+pub fn flush() {
+    if !SAVE_PENDING.swap(false, Ordering::SeqCst) {
+        return;
+    }
+    if let Ok(cache) = CACHE.lock() {
+        if PERSIST.load(Ordering::SeqCst) {
+            save_cache(&cache);
+        }
+    }
+}
+
+/// Apply the config; loads the disk cache the first time persistence is on
+pub fn configure(capacity: usize, persist: bool) {
+    let was_persisting = PERSIST.swap(persist, Ordering::SeqCst);
+    if let Ok(mut cache) = CACHE.lock() {
+        if persist && !was_persisting {
+            for (key, value) in load_entries() {
+                if !cache.map.contains_key(&key) {
+                    cache.put(key, value);
+                }
+            }
+        }
+        cache.set_capacity(capacity);
+    }
+}
+
+pub fn hit_count() -> u64 {
+    HITS.load(Ordering::Relaxed)
+}
+
+pub fn entry_count() -> usize {
+    CACHE.lock().map(|c| c.len()).unwrap_or(0)
+}
+
+/// Drop every cached translation (memory and disk) and reset the hit counter
+pub fn clear() {
+    if let Ok(mut cache) = CACHE.lock() {
+        cache.clear();
+    }
+    HITS.store(0, Ordering::Relaxed);
+    let _ = std::fs::remove_file(get_cache_path());
+}
+
+fn get_cache_path() -> PathBuf {
+    let config_dir = dirs::config_dir()
+        .unwrap_or_default()
+        .join("xt-screen-translator");
+    let _ = std::fs::create_dir_all(&config_dir);
+    config_dir.join("translation_cache.json")
+}
+
+/// Entries oldest first, so re-inserting them keeps the LRU order
+fn load_entries() -> Vec<(u64, String)> {
+    std::fs::read_to_string(get_cache_path())
+        .ok()
+        .and_then(|data| serde_json::from_str(&data).ok())
+        .unwrap_or_default()
+}
+
+fn save_cache(cache: &LruCache) {
+    let entries: Vec<(u64, &String)> = cache.order.iter()
+        .filter_map(|k| cache.map.get(k).map(|v| (*k, v)))
+        .collect();
+    if let Ok(data) = serde_json::to_string(&entries) {
+        if let Err(e) = std::fs::write(get_cache_path(), data) {
+            log::error!("Failed to write translation cache: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lru_evicts_least_recently_used() {
+        let mut cache = LruCache::new(2);
+        cache.put(1, "a".to_string());
+        cache.put(2, "b".to_string());
+        assert_eq!(cache.get(1), Some("a".to_string())); // 2 is now the oldest
+        cache.put(3, "c".to_string());
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.len(), 2);
+
+        cache.set_capacity(1);
+        assert_eq!(cache.get(3), Some("c".to_string()));
+        assert_eq!(cache.get(1), None);
+    }
+
+    #[test]
+    fn test_text_key_ignores_whitespace() {
+        assert_eq!(text_key("m", "Vietnamese", " Hello   world\n"), text_key("m", "Vietnamese", "Hello world"));
+        assert_ne!(text_key("m", "Vietnamese", "Hello"), text_key("m", "Korean", "Hello"));
+    }
+}
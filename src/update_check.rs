@@ -0,0 +1,133 @@
+//! Update Check Module
+//!
+//! Once a day at most (and only with `Config.check_updates`), asks GitHub for the latest
+//! release in the background. A newer version shows a banner in the GUI footer that opens
+//! the release page; nothing is downloaded. Network or parse errors are only logged.
+//! The release found is kept in `Config.latest_release`, so the banner is back on the
+//! following startups (which skip the check) until the app is updated.
+
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/nganlinh4/screen-grounded-translator/releases/latest";
+const CHECK_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct UpdateInfo {
+    pub version: String,
+    pub url: String, // Release page
+}
+
+lazy_static::lazy_static! {
+    static ref AVAILABLE: Mutex<Option<UpdateInfo>> = Mutex::new(None);
+}
+
+/// "v1.2.3" / "1.2" -> [1, 2, 3] / [1, 2]; anything after a '-' (pre-release) is ignored
+fn parse_version(version: &str) -> Option<Vec<u32>> {
+    let core = version.trim().trim_start_matches(['v', 'V']).split('-').next()?;
+    core.split('.').map(|part| part.parse().ok()).collect()
+}
+
+/// Missing components count as 0, so "1.2" == "1.2.0"
+fn is_newer(latest: &str, current: &str) -> bool {
+    let (Some(latest), Some(current)) = (parse_version(latest), parse_version(current)) else {
+        return false;
+    };
+    let len = latest.len().max(current.len());
+    let pad = |v: &[u32]| (0..len).map(|i| v.get(i).copied().unwrap_or(0)).collect::<Vec<_>>();
+    pad(&latest) > pad(&current)
+}
+
+fn check_due(last_check: u64, now: u64) -> bool {
+    now.saturating_sub(last_check) >= CHECK_INTERVAL_SECS
+}
+
+/// The saved release, if this build (`current`) is older
+fn pending_update(saved: Option<&UpdateInfo>, current: &str) -> Option<UpdateInfo> {
+    saved.filter(|release| is_newer(&release.version, current)).cloned()
+}
+
+/// Called once from `main()`. The check time is saved before the GUI copies the config,
+/// so the GUI's later saves keep it.
+pub fn start() {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    {
+        let mut app = crate::APP.lock().unwrap();
+        if !app.config.check_updates {
+            return;
+        }
+        *AVAILABLE.lock().unwrap() = pending_update(app.config.latest_release.as_ref(), env!("CARGO_PKG_VERSION"));
+        if !check_due(app.config.last_update_check, now) {
+            return;
+        }
+        app.config.last_update_check = now;
+        crate::config::save_config(&app.config);
+    }
+
+    std::thread::spawn(|| match fetch_latest() {
+        Ok(latest) => remember(latest),
+        Err(e) => log::debug!("Update check failed: {}", e),
+    });
+}
+
+/// Show the release found if it's newer, and save it for the startups that skip the check.
+/// The GUI keeps this field from APP when it saves (see `save_and_sync`).
+fn remember(latest: UpdateInfo) {
+    let update = pending_update(Some(&latest), env!("CARGO_PKG_VERSION"));
+    match &update {
+        Some(update) => log::info!("Update available: {}", update.version),
+        None => log::debug!("Update check: up to date"),
+    }
+    *AVAILABLE.lock().unwrap() = update;
+    if let Ok(mut app) = crate::APP.lock() {
+        if app.config.latest_release.as_ref() != Some(&latest) {
+            app.config.latest_release = Some(latest);
+            crate::config::save_config(&app.config);
+        }
+    }
+}
+
+fn fetch_latest() -> anyhow::Result<UpdateInfo> {
+    let release: serde_json::Value = crate::api::UREQ_AGENT.get(LATEST_RELEASE_URL)
+        .set("User-Agent", concat!("xt-screen-translator/", env!("CARGO_PKG_VERSION")))
+        .call()?
+        .into_json()?;
+    let version = release["tag_name"].as_str().ok_or_else(|| anyhow::anyhow!("no tag_name"))?;
+    let url = release["html_url"].as_str().ok_or_else(|| anyhow::anyhow!("no html_url"))?;
+    Ok(UpdateInfo { version: version.to_string(), url: url.to_string() })
+}
+
+/// A release newer than this build, found by this or an earlier check
+pub fn available() -> Option<UpdateInfo> {
+    AVAILABLE.lock().unwrap().clone()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_newer() {
+        assert_eq!(parse_version("v1.2.3"), Some(vec![1, 2, 3]));
+        assert_eq!(parse_version("2.0-beta"), Some(vec![2, 0]));
+        assert_eq!(parse_version("nightly"), None);
+
+        assert!(is_newer("v0.6.0", "0.5.0"));
+        assert!(is_newer("0.5.1", "0.5"));
+        assert!(is_newer("v0.10.0", "0.9.9")); // Numeric, not string order
+        assert!(!is_newer("v0.5.0", "0.5.0"));
+        assert!(!is_newer("0.5", "0.5.0"));
+        assert!(!is_newer("v0.4.9", "0.5.0"));
+        assert!(!is_newer("latest", "0.5.0"));
+
+        assert!(check_due(0, CHECK_INTERVAL_SECS));
+        assert!(!check_due(100, 100 + CHECK_INTERVAL_SECS - 1));
+        assert!(!check_due(200, 100)); // Clock went backwards
+
+        let release = UpdateInfo { version: "v0.6.0".to_string(), url: String::new() };
+        assert_eq!(pending_update(Some(&release), "0.5.0"), Some(release.clone()));
+        assert_eq!(pending_update(Some(&release), "0.6.0"), None); // Updated since
+        assert_eq!(pending_update(None, "0.5.0"), None);
+    }
+}
@@ -0,0 +1,156 @@
+//! Streaming UTF-8 Decoding
+//!
+//! Network reads end at arbitrary byte offsets, which can fall inside a multi-byte
+//! character (Korean, Vietnamese diacritics, emoji). The decoder holds such an
+//! incomplete tail until the rest arrives, so partial code points never reach the
+//! result window; bytes that can never become valid UTF-8 turn into U+FFFD instead
+//! of aborting the stream like `BufRead::lines()` does.
+
+use std::io::{self, Read};
+
+#[derive(Default)]
+pub struct Utf8StreamDecoder {
+    pending: Vec<u8>, // Incomplete sequence left over from the last chunk (at most 3 bytes)
+}
+
+impl Utf8StreamDecoder {
+    /// Decode `bytes` after whatever was left over; returns only complete characters
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+        let mut out = String::with_capacity(self.pending.len());
+        let mut rest = &self.pending[..];
+        loop {
+            match std::str::from_utf8(rest) {
+                Ok(valid) => {
+                    out.push_str(valid);
+                    rest = &[];
+                    break;
+                }
+                Err(e) => {
+                    let (valid, after) = rest.split_at(e.valid_up_to());
+                    out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                    match e.error_len() {
+                        // Broken sequence: replace it and keep going
+                        Some(len) => {
+                            out.push(char::REPLACEMENT_CHARACTER);
+                            rest = &after[len..];
+                        }
+                        // Sequence cut off by the end of the chunk: wait for more bytes
+                        None => {
+                            rest = after;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        self.pending = rest.to_vec();
+        out
+    }
+
+    /// End of stream: a still-incomplete sequence becomes U+FFFD
+    pub fn finish(&mut self) -> String {
+        let tail = String::from_utf8_lossy(&self.pending).into_owned();
+        self.pending.clear();
+        tail
+    }
+}
+
+/// Lines of a streaming (SSE) response body, decoded with `Utf8StreamDecoder`
+pub struct StreamLines<R: Read> {
+    reader: R,
+    decoder: Utf8StreamDecoder,
+    buffer: String, // Decoded text not yet returned as a line
+    done: bool,
+}
+
+pub fn stream_lines<R: Read>(reader: R) -> StreamLines<R> {
+    StreamLines { reader, decoder: Utf8StreamDecoder::default(), buffer: String::new(), done: false }
+}
+
+impl<R: Read> Iterator for StreamLines<R> {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = [0u8; 4096];
+        loop {
+            if let Some(pos) = self.buffer.find('\n') {
+                let mut line: String = self.buffer.drain(..=pos).collect();
+                line.pop();
+                if line.ends_with('\r') {
+                    line.pop();
+                }
+                return Some(Ok(line));
+            }
+            if self.done {
+                return (!self.buffer.is_empty()).then(|| Ok(std::mem::take(&mut self.buffer)));
+            }
+            match self.reader.read(&mut chunk) {
+                Ok(0) => {
+                    self.done = true;
+                    let tail = self.decoder.finish();
+                    self.buffer.push_str(&tail);
+                }
+                Ok(n) => {
+                    let text = self.decoder.decode(&chunk[..n]);
+                    self.buffer.push_str(&text);
+                }
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Hands out the source a few bytes at a time, like a slow network
+    struct Trickle<'a> {
+        data: &'a [u8],
+        sizes: std::iter::Cycle<std::slice::Iter<'a, usize>>,
+    }
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let n = (*self.sizes.next().unwrap()).min(self.data.len()).min(buf.len());
+            buf[..n].copy_from_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_split_utf8_chunks() {
+        let text = "안녕하세요 thế giới 👋🏽\r\ndata: Tiếng Việt có dấu\n끝";
+        let bytes = text.as_bytes();
+        // Every split point, including the middle of each multi-byte character
+        for split in 0..=bytes.len() {
+            let mut decoder = Utf8StreamDecoder::default();
+            let mut out = decoder.decode(&bytes[..split]);
+            assert!(!out.contains(char::REPLACEMENT_CHARACTER), "partial code point shown at split {}", split);
+            out += &decoder.decode(&bytes[split..]);
+            out += &decoder.finish();
+            assert_eq!(out, text);
+        }
+
+        let sizes = [1, 2, 3, 5];
+        let lines: Vec<String> = stream_lines(Trickle { data: bytes, sizes: sizes.iter().cycle() })
+            .collect::<io::Result<_>>()
+            .unwrap();
+        assert_eq!(lines, vec!["안녕하세요 thế giới 👋🏽", "data: Tiếng Việt có dấu", "끝"]);
+    }
+
+    #[test]
+    fn test_invalid_bytes_are_replaced() {
+        let mut decoder = Utf8StreamDecoder::default();
+        assert_eq!(decoder.decode(b"ok \xFF \xED\x95"), "ok \u{FFFD} ");
+        // Truncated sequence at end of stream
+        assert_eq!(decoder.finish(), "\u{FFFD}");
+        assert_eq!(decoder.decode("ä".as_bytes()), "ä");
+    }
+}
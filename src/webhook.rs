@@ -0,0 +1,153 @@
+//! Webhook Module
+//!
+//! `Preset.webhook`: after each successful run the result is POSTed as JSON to a URL,
+//! e.g. a note-taking app's REST API. The body is the user's template with the run's
+//! values filled in; custom headers carry auth tokens. Requests run in the background
+//! and a failure is logged and shown as a toast, never blocking the result windows.
+
+use std::time::Duration;
+
+use crate::config::WebhookConfig;
+
+/// Body of a new preset's webhook
+pub const DEFAULT_TEMPLATE: &str = r#"{"preset": "{preset}", "timestamp": "{timestamp}", "result": "{result}", "retranslation": "{retranslation}"}"#;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+lazy_static::lazy_static! {
+    static ref AGENT: ureq::Agent = ureq::AgentBuilder::new().timeout(TIMEOUT).build();
+}
+
+/// One run's values for the template placeholders
+pub struct Payload<'a> {
+    pub preset: &'a str,
+    pub result: &'a str,
+    pub retranslation: &'a str, // "" without one; several are joined by blank lines
+    pub timestamp: String, // RFC 3339, local time
+}
+
+impl<'a> Payload<'a> {
+    pub fn now(preset: &'a str, result: &'a str, retranslation: &'a str) -> Self {
+        Self { preset, result, retranslation, timestamp: chrono::Local::now().to_rfc3339() }
+    }
+}
+
+/// `s` escaped for use inside a JSON string literal (the template supplies the quotes)
+fn json_escape(s: &str) -> String {
+    let quoted = serde_json::to_string(s).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+/// The template with every placeholder replaced by its escaped value
+pub fn render(template: &str, payload: &Payload) -> String {
+    // One pass, so a value containing "{result}" isn't substituted again
+    let placeholders = [
+        ("{result}", payload.result),
+        ("{retranslation}", payload.retranslation),
+        ("{preset}", payload.preset),
+        ("{timestamp}", payload.timestamp.as_str()),
+    ];
+    let mut body = String::with_capacity(template.len() + payload.result.len());
+    let mut rest = template;
+    'outer: while !rest.is_empty() {
+        for (name, value) in placeholders {
+            if let Some(after) = rest.strip_prefix(name) {
+                body.push_str(&json_escape(value));
+                rest = after;
+                continue 'outer;
+            }
+        }
+        let ch = rest.chars().next().unwrap();
+        body.push(ch);
+        rest = &rest[ch.len_utf8()..];
+    }
+    body
+}
+
+/// POST `body` and wait for the answer. Ok with the HTTP status.
+pub fn send(webhook: &WebhookConfig, body: &str) -> anyhow::Result<u16> {
+    let mut request = AGENT.post(webhook.url.trim()).set("Content-Type", "application/json");
+    for header in webhook.headers.iter().filter(|h| !h.name.trim().is_empty()) {
+        request = request.set(header.name.trim(), header.value.trim());
+    }
+    match request.send_string(body) {
+        Ok(response) => Ok(response.status()),
+        Err(ureq::Error::Status(code, response)) => {
+            let detail = response.into_string().unwrap_or_default();
+            Err(anyhow::anyhow!("HTTP {} {}", code, detail.chars().take(200).collect::<String>()))
+        }
+        Err(e) => Err(anyhow::anyhow!("{}", e)),
+    }
+}
+
+fn failed_message(ui_language: &str, error: &str) -> String {
+    match ui_language {
+        "vi" => format!("Gửi webhook thất bại: {}", error),
+        "ko" => format!("웹훅 전송 실패: {}", error),
+        _ => format!("Webhook failed: {}", error),
+    }
+}
+
+fn sent_message(ui_language: &str, status: u16) -> String {
+    match ui_language {
+        "vi" => format!("Đã gửi webhook (HTTP {})", status),
+        "ko" => format!("웹훅 전송됨 (HTTP {})", status),
+        _ => format!("Webhook sent (HTTP {})", status),
+    }
+}
+
+/// Send one run's payload on a background thread. With `confirm` a success is shown
+/// too (the settings' test button); otherwise only failures are.
+pub fn post(webhook: WebhookConfig, payload: &Payload, confirm: bool) {
+    if webhook.url.trim().is_empty() {
+        return;
+    }
+    let body = render(&webhook.template, payload);
+    std::thread::spawn(move || {
+        let result = send(&webhook, &body);
+        let ui_language = crate::APP.lock().map(|a| a.config.ui_language.clone()).unwrap_or_default();
+        match result {
+            Ok(status) => {
+                log::info!("Webhook: POST {} -> {}", webhook.url, status);
+                if confirm {
+                    crate::overlay::app_blocklist::show_toast(format!("✓ {}", sent_message(&ui_language, status)));
+                }
+            }
+            Err(e) => {
+                log::error!("Webhook: POST {} failed - {}", webhook.url, e);
+                crate::overlay::app_blocklist::show_toast(format!("⚠ {}", failed_message(&ui_language, &e.to_string())));
+            }
+        }
+    });
+}
+
+/// The settings' "Send test payload" button: sample values through the preset's webhook
+pub fn send_test(webhook: WebhookConfig, preset_name: &str) {
+    let payload = Payload::now(preset_name, "Test result \"quoted\"\nsecond line", "Test retranslation");
+    post(webhook, &payload, true);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let payload = Payload {
+            preset: "Translate",
+            result: "Line \"one\"\n{preset}",
+            retranslation: "",
+            timestamp: "2026-10-15T09:30:00+07:00".to_string(),
+        };
+        let body = render(DEFAULT_TEMPLATE, &payload);
+        let json: serde_json::Value = serde_json::from_str(&body).unwrap();
+        assert_eq!(json["result"], "Line \"one\"\n{preset}");
+        assert_eq!(json["preset"], "Translate");
+        assert_eq!(json["retranslation"], "");
+        assert_eq!(json["timestamp"], "2026-10-15T09:30:00+07:00");
+
+        // Anything else in the template is kept as written
+        assert_eq!(render(r##"{"content": "# {preset}\n{result}", "tags": ["xt"]}"##, &payload),
+            r##"{"content": "# Translate\nLine \"one\"\n{preset}", "tags": ["xt"]}"##);
+    }
+}